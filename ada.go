@@ -0,0 +1,181 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+var (
+	// adaUnit matches the line of Ada starting a package, subprogram, task,
+	// protected unit, or entry, such as "package body Stacks is" or
+	// "overriding procedure Push", and its kind and name.
+	adaUnit = regexp.MustCompile(`(?i)^[ \t]*(?:(?:not[ \t]+)?overriding[ \t]+)?(package[ \t]+body|package|procedure|function|task[ \t]+body|task[ \t]+type|task|protected[ \t]+body|protected[ \t]+type|protected|entry)[ \t]+("[^"\n]*"|[\w.]+)`)
+	// adaContextClause matches a with, use, or pragma clause of Ada, or the
+	// separate clause of a subunit, which belong to the unit after them.
+	adaContextClause = regexp.MustCompile(`(?i)^(?:(?:limited[ \t]+)?(?:private[ \t]+)?with|use|pragma|separate)\b`)
+	// adaGeneric matches the line of Ada starting the formal part of a
+	// generic unit.
+	adaGeneric = regexp.MustCompile(`(?i)^[ \t]*generic\b`)
+	// adaBlock matches the line of Ada starting a block statement or the
+	// statements of a body, after its label, and the keyword.
+	adaBlock = regexp.MustCompile(`(?i)^[ \t]*(?:\w+[ \t]*:[ \t]*)?(declare|begin)\b`)
+	// adaAccept matches the line of Ada starting an accept statement with a
+	// body.
+	adaAccept = regexp.MustCompile(`(?i)^[ \t]*accept\b.*\bdo\b`)
+	// adaEnd matches an Ada statement starting with "end", and the word after
+	// it, if any.
+	adaEnd = regexp.MustCompile(`(?i)^[ \t]*end\b[ \t]*(\w*)`)
+)
+
+// adaEndKeywords are the words after "end" that end a statement or type of
+// Ada rather than a unit or block.
+var adaEndKeywords = map[string]bool{
+	"if":     true,
+	"loop":   true,
+	"case":   true,
+	"record": true,
+	"select": true,
+	"return": true,
+}
+
+// adaSpans returns the library units of the Ada code, such as its package
+// specs and bodies and its subprograms, each with the comment lines, context
+// clauses, and generic formal part right above it. Packages, tasks, and
+// protected units record their kind and name as MetadataBlock, such as
+// "package body Stacks", and subprograms record MetadataDefinition.
+func adaSpans(code string) []textSpan {
+	return adaUnits(code, 0)
+}
+
+// adaNested returns the units within the Ada unit after the line starting
+// it, such as the subprogram bodies of a package body.
+func adaNested(unit string) []textSpan {
+	for offset := 0; offset < len(unit); {
+		line, next := codeLine(unit, offset)
+		if adaUnit.MatchString(line) {
+			return adaUnits(unit, next)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// adaUnits returns the units of the Ada code with a body or a spec that
+// start on the lines from offset on, leaving out declarations, renamings,
+// and instantiations.
+func adaUnits(code string, offset int) []textSpan {
+	var spans []textSpan
+	generic := -1 // Start of the generic formal part of the next unit
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		if adaGeneric.MatchString(line) {
+			if generic < 0 {
+				generic = offset
+			}
+			offset = next
+			continue
+		}
+		m := adaUnit.FindStringSubmatch(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+		body := adaBodyStart(code, offset)
+		if body < 0 {
+			generic, offset = -1, next
+			continue
+		}
+
+		_, from := codeLine(code, body)
+		end := adaUnitEnd(code, from)
+		if end < 0 {
+			generic, offset = -1, next
+			continue
+		}
+		start := offset
+		if generic >= 0 {
+			start = generic
+		}
+		kind := strings.ToLower(strings.Join(strings.Fields(m[1]), " "))
+		metadata := map[string]string{MetadataDefinition: m[2]}
+		if kind != "procedure" && kind != "function" && kind != "entry" {
+			metadata = map[string]string{MetadataBlock: kind + " " + m[2]}
+		}
+		spans = append(spans, textSpan{
+			start:    contextClauses(code, start, spanEnd(spans), adaContextClause),
+			end:      end,
+			metadata: metadata,
+		})
+		generic, offset = -1, end
+	}
+	return spans
+}
+
+// adaUnitEnd returns the offset after the end statement of the Ada unit whose
+// lines after the one holding its "is" start at offset in code, skipping the
+// units, blocks, and accept statements within it, or -1 if it isn't ended.
+func adaUnitEnd(code string, offset int) int {
+	// Whether each open unit or block awaits the begin of its statements
+	open := []bool{true}
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		text, _, _ := strings.Cut(line, "--")
+		if adaUnit.MatchString(text) {
+			if body := adaBodyStart(code, offset); body >= 0 {
+				open = append(open, true)
+				_, next = codeLine(code, body)
+			}
+		} else if m := adaBlock.FindStringSubmatch(text); m != nil {
+			if n := len(open); strings.EqualFold(m[1], "begin") && open[n-1] {
+				open[n-1] = false
+			} else {
+				open = append(open, strings.EqualFold(m[1], "declare"))
+			}
+		} else if adaAccept.MatchString(text) {
+			open = append(open, false)
+		} else if m := adaEnd.FindStringSubmatch(text); m != nil && !adaEndKeywords[strings.ToLower(m[1])] {
+			if open = open[:len(open)-1]; len(open) == 0 {
+				return next
+			}
+		}
+		offset = next
+	}
+	return -1
+}
+
+// adaBodyStart returns the offset after the "is" of the Ada unit starting at
+// offset in code, or -1 if it has none before the ";" ending its declaration
+// or it is followed by "new", "separate", "abstract", "null", "<>", or an
+// expression, as instantiations, stubs, and expression functions are.
+func adaBodyStart(code string, offset int) int {
+	depth := 0
+	for i := offset; i < len(code); i++ {
+		switch c := code[i]; {
+		case c == '(':
+			depth++
+		case c == ')':
+			depth--
+		case c == ';' && depth == 0:
+			return -1
+		case c == '"':
+			for i++; i < len(code) && code[i] != '"' && code[i] != '\n'; i++ {
+			}
+		case c == '\'' && i+2 < len(code) && code[i+2] == '\'':
+			i += 2 // A character literal, such as '('
+		case strings.HasPrefix(code[i:], "--"):
+			for i+1 < len(code) && code[i+1] != '\n' {
+				i++
+			}
+		case depth == 0 && i > 0 && !isWordByte(code[i-1]) && i+2 <= len(code) && strings.EqualFold(code[i:i+2], "is") &&
+			(i+2 == len(code) || !isWordByte(code[i+2])):
+			rest := strings.TrimLeft(code[i+2:], " \t\r\n")
+			for _, word := range []string{"new", "separate", "abstract", "null", "<>", "("} {
+				if len(rest) >= len(word) && strings.EqualFold(rest[:len(word)], word) && (len(word) < 3 || len(rest) == len(word) || !isWordByte(rest[len(word)])) {
+					return -1
+				}
+			}
+			return i + 2
+		}
+	}
+	return -1
+}
@@ -0,0 +1,47 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Ada(t *testing.T) {
+	code := `with Ada.Text_IO; use Ada.Text_IO;
+
+-- Integer stacks
+package body Stacks is
+
+   procedure Push (S : in out Stack; X : Integer) is
+   begin
+      S.Top := S.Top + 1;
+      S.Items (S.Top) := X;
+   end Push;
+
+   function Pop (S : in out Stack) return Integer is
+      X : Integer;
+   begin
+      X := S.Items (S.Top);
+      if S.Top > 0 then
+         S.Top := S.Top - 1;
+      end if;
+      return X;
+   end Pop;
+
+   procedure Print (S : Stack) is separate;
+
+end Stacks;
+`
+	chunks := testChunkMetadata(t, code, languages.Ada, 35, []struct{ prefix, key, value string }{
+		{"with Ada.Text_IO; use Ada.Text_IO;\n\n-- Integer stacks\npackage body Stacks is\n", MetadataBlock, "package body Stacks"},
+		{"   procedure Push (S", MetadataDefinition, "Push"},
+		{"   function Pop (S", MetadataDefinition, "Pop"},
+		{"   function Pop (S", MetadataBlock, "package body Stacks"},
+	})
+	for _, chunk := range chunks {
+		if strings.HasPrefix(chunk.Content, "   function Pop") && !strings.HasSuffix(chunk.Content, "end Pop;\n") {
+			t.Errorf("expected the function chunked up to its end, got %q", chunk.Content)
+		}
+	}
+}
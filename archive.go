@@ -0,0 +1,193 @@
+package chunkx
+
+import (
+	"archive/tar"
+	"archive/zip"
+	"compress/gzip"
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+)
+
+// IsArchive reports whether path names an archive that RunArchive can read,
+// judging by its extension: .zip, .tar, .tar.gz, or .tgz.
+func IsArchive(path string) bool {
+	name := strings.ToLower(path)
+	for _, ext := range []string{".zip", ".tar", ".tar.gz", ".tgz"} {
+		if strings.HasSuffix(name, ext) {
+			return true
+		}
+	}
+	return false
+}
+
+// RunArchive chunks every regular file inside the archive at archivePath
+// without extracting it to disk. Hidden directories and paths matching
+// WithIgnore patterns are skipped as in RunDir. Files larger than
+// Limits.MaxBytes, or DefaultMaxReadBytes without a limit, fail without being
+// decompressed whole.
+//
+// Results are reported with the path inside the archive joined onto
+// archivePath, and every chunk records the archive and the path inside it in
+// Metadata under MetadataArchive and MetadataArchivePath. The language of each
+// file is detected from its path inside the archive. Errors opening or reading
+// the archive are reported as a result for archivePath.
+func (p *ChunkPipeline) RunArchive(ctx context.Context, archivePath string) <-chan FileResult {
+	sources := make(chan source, p.workers)
+
+	// send queues a file for the workers, stopping the walk on cancellation
+	send := func(src source) error {
+		select {
+		case <-ctx.Done():
+			return ctx.Err()
+		case sources <- src:
+			return nil
+		}
+	}
+
+	go func() {
+		defer close(sources)
+		var n int
+		err := walkArchive(archivePath, p.ignore, p.limits.maxRead(), func(name string, content []byte, err error) error {
+			src := source{
+				path:     filepath.Join(archivePath, filepath.FromSlash(name)),
+				content:  content,
+				inMemory: true,
+				metadata: map[string]string{
+					MetadataArchive:     archivePath,
+					MetadataArchivePath: name,
+				},
+				err: err,
+			}
+			if !p.queued(src.path, &n) {
+				return errSampleFull
+			}
+			return send(src)
+		})
+		if err != nil && ctx.Err() == nil && !errors.Is(err, errSampleFull) && p.queued(archivePath, &n) {
+			_ = send(source{path: archivePath, err: err})
+		}
+	}()
+
+	return p.run(ctx, sources)
+}
+
+// walkArchive calls fn with the name and content of every regular file in the
+// archive that isn't skipped. Files that can't be read, or that are larger
+// than max bytes, are passed to fn with their error. The walk stops when fn
+// returns an error.
+func walkArchive(archivePath string, ignore []string, max int, fn func(name string, content []byte, err error) error) error {
+	name := strings.ToLower(archivePath)
+	if strings.HasSuffix(name, ".zip") {
+		return walkZip(archivePath, ignore, max, fn)
+	}
+
+	f, err := os.Open(archivePath)
+	if err != nil {
+		return err
+	}
+	defer f.Close()
+
+	var r io.Reader = f
+	if strings.HasSuffix(name, ".gz") || strings.HasSuffix(name, ".tgz") {
+		gz, err := gzip.NewReader(f)
+		if err != nil {
+			return fmt.Errorf("failed to read archive: %w", err)
+		}
+		defer gz.Close()
+		r = gz
+	}
+
+	tr := tar.NewReader(r)
+	for {
+		hdr, err := tr.Next()
+		if errors.Is(err, io.EOF) {
+			return nil
+		}
+		if err != nil {
+			return fmt.Errorf("failed to read archive: %w", err)
+		}
+
+		if hdr.Typeflag != tar.TypeReg || skippedPath(ignore, hdr.Name) {
+			continue
+		}
+
+		// Entries too large to read are skipped by the next header, while a
+		// failed read leaves the stream unusable, so it ends the walk
+		if hdr.Size > int64(max) {
+			if err := fn(cleanArchiveName(hdr.Name), nil, &LimitExceeded{Limit: LimitBytes, Max: max}); err != nil {
+				return err
+			}
+			continue
+		}
+		content, err := readAtMost(tr, max)
+		if err != nil {
+			return fmt.Errorf("failed to read archive: %w", err)
+		}
+		if err := fn(cleanArchiveName(hdr.Name), content, nil); err != nil {
+			return err
+		}
+	}
+}
+
+// walkZip walks a zip archive for walkArchive.
+func walkZip(archivePath string, ignore []string, max int, fn func(name string, content []byte, err error) error) error {
+	zr, err := zip.OpenReader(archivePath)
+	if err != nil {
+		return fmt.Errorf("failed to read archive: %w", err)
+	}
+	defer zr.Close()
+
+	for _, f := range zr.File {
+		if !f.Mode().IsRegular() || skippedPath(ignore, f.Name) {
+			continue
+		}
+
+		content, readErr := readZipFile(f, max)
+		if err := fn(cleanArchiveName(f.Name), content, readErr); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// readZipFile reads the content of a file in a zip archive, failing if it is
+// larger than max bytes. The size in the archive is checked before reading,
+// and the read is bounded too, as the size can lie.
+func readZipFile(f *zip.File, max int) ([]byte, error) {
+	if f.UncompressedSize64 > uint64(max) {
+		return nil, &LimitExceeded{Limit: LimitBytes, Max: max}
+	}
+	rc, err := f.Open()
+	if err != nil {
+		return nil, err
+	}
+	defer rc.Close()
+	return readAtMost(rc, max)
+}
+
+// cleanArchiveName normalizes a path inside an archive.
+func cleanArchiveName(name string) string {
+	return strings.TrimPrefix(path.Clean("/"+name), "/")
+}
+
+// skippedPath reports whether a file, given by its slash-separated path
+// inside an archive or repository, is skipped because it is in a hidden
+// directory or it or one of its directories is ignored.
+func skippedPath(ignore []string, name string) bool {
+	segments := strings.Split(cleanArchiveName(name), "/")
+	for i, segment := range segments {
+		if i < len(segments)-1 && strings.HasPrefix(segment, ".") {
+			return true
+		}
+		if len(ignore) > 0 && matchAnyGlob(ignore, strings.Join(segments[:i+1], "/")) {
+			return true
+		}
+	}
+	return false
+}
@@ -0,0 +1,183 @@
+package chunkx
+
+import (
+	"archive/tar"
+	"archive/zip"
+	"compress/gzip"
+	"context"
+	"errors"
+	"io"
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+var archiveFiles = []struct {
+	name, content string
+}{
+	{"repo/main.go", "package main\n\nfunc main() {}\n"},
+	{"repo/README.md", "# Repo\n"},
+	{"repo/.git/config", "[core]\n"},
+	{"repo/vendor/lib.go", "package lib\n"},
+}
+
+// writeZip writes archiveFiles to a zip archive.
+func writeZip(t *testing.T, path string) {
+	t.Helper()
+
+	f, err := os.Create(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer f.Close()
+
+	zw := zip.NewWriter(f)
+	for _, file := range archiveFiles {
+		w, err := zw.Create(file.name)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if _, err := io.WriteString(w, file.content); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if err := zw.Close(); err != nil {
+		t.Fatal(err)
+	}
+}
+
+// writeTarGz writes archiveFiles to a gzipped tar archive.
+func writeTarGz(t *testing.T, path string) {
+	t.Helper()
+
+	f, err := os.Create(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer f.Close()
+
+	gz := gzip.NewWriter(f)
+	tw := tar.NewWriter(gz)
+	for _, file := range archiveFiles {
+		hdr := &tar.Header{Name: file.name, Mode: 0o644, Size: int64(len(file.content)), Typeflag: tar.TypeReg}
+		if err := tw.WriteHeader(hdr); err != nil {
+			t.Fatal(err)
+		}
+		if _, err := io.WriteString(tw, file.content); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	if err := gz.Close(); err != nil {
+		t.Fatal(err)
+	}
+}
+
+func TestChunkPipeline_RunArchive(t *testing.T) {
+	dir := t.TempDir()
+	archives := map[string]func(*testing.T, string){
+		"repo.zip":    writeZip,
+		"repo.tar.gz": writeTarGz,
+	}
+
+	for name, write := range archives {
+		t.Run(name, func(t *testing.T) {
+			archivePath := filepath.Join(dir, name)
+			write(t, archivePath)
+
+			pipeline := NewChunkPipeline(2, WithIgnore("vendor"), WithOrderedOutput())
+			var paths []string
+			for result := range pipeline.RunArchive(context.Background(), archivePath) {
+				if result.Err != nil {
+					t.Fatalf("unexpected error for %s: %v", result.Path, result.Err)
+				}
+				paths = append(paths, result.Path)
+
+				for _, chunk := range result.Chunks {
+					want := map[string]string{
+						MetadataArchive:     archivePath,
+						MetadataArchivePath: chunk.Metadata[MetadataArchivePath],
+					}
+					if !reflect.DeepEqual(chunk.Metadata, want) {
+						t.Errorf("unexpected metadata: %v", chunk.Metadata)
+					}
+					if chunk.Metadata[MetadataArchivePath] == "repo/main.go" && chunk.Language != languages.Go {
+						t.Errorf("expected Go to be detected inside the archive, got %s", chunk.Language)
+					}
+				}
+			}
+
+			want := []string{
+				filepath.Join(archivePath, "repo", "main.go"),
+				filepath.Join(archivePath, "repo", "README.md"),
+			}
+			if !reflect.DeepEqual(paths, want) {
+				t.Errorf("got paths %v, want %v", paths, want)
+			}
+		})
+	}
+}
+
+func TestChunkPipeline_RunArchiveLimit(t *testing.T) {
+	dir := t.TempDir()
+	archives := map[string]func(*testing.T, string){
+		"repo.zip":    writeZip,
+		"repo.tar.gz": writeTarGz,
+	}
+
+	for name, write := range archives {
+		t.Run(name, func(t *testing.T) {
+			archivePath := filepath.Join(dir, name)
+			write(t, archivePath)
+
+			// Entries larger than the limit fail without stopping the walk
+			pipeline := NewChunkPipeline(1, WithIgnore("vendor"), WithOrderedOutput(), WithLimits(Limits{MaxBytes: 20}))
+			var results []FileResult
+			for result := range pipeline.RunArchive(context.Background(), archivePath) {
+				results = append(results, result)
+			}
+			if len(results) != 2 {
+				t.Fatalf("expected two results, got %+v", results)
+			}
+			var fileErr *FileError
+			if !errors.As(results[0].Err, &fileErr) || fileErr.Kind != ErrorLimit || !errors.Is(fileErr, ErrLimitExceeded) {
+				t.Errorf("expected a limit error for main.go, got %v", results[0].Err)
+			}
+			if results[1].Err != nil || len(results[1].Chunks) != 1 {
+				t.Errorf("expected README.md to be chunked, got %+v", results[1])
+			}
+		})
+	}
+}
+
+func TestChunkPipeline_RunArchiveInvalid(t *testing.T) {
+	path := filepath.Join(writeTestFiles(t, map[string]string{"broken.zip": "not a zip"}), "broken.zip")
+
+	var results []FileResult
+	for result := range NewChunkPipeline(1).RunArchive(context.Background(), path) {
+		results = append(results, result)
+	}
+	if len(results) != 1 || results[0].Path != path || results[0].Err == nil {
+		t.Errorf("expected a single error for the archive, got %+v", results)
+	}
+}
+
+func TestIsArchive(t *testing.T) {
+	for path, want := range map[string]bool{
+		"repo.zip":    true,
+		"repo.tar.gz": true,
+		"repo.TGZ":    true,
+		"repo.tar":    true,
+		"repo.gz":     false,
+		"main.go":     false,
+	} {
+		if got := IsArchive(path); got != want {
+			t.Errorf("IsArchive(%q) = %v, want %v", path, got, want)
+		}
+	}
+}
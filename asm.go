@@ -0,0 +1,119 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+// Metadata keys set on chunks of assembly and linker scripts.
+const (
+	MetadataLabel   = "label"   // Label the chunk of assembly starts at, such as "main"
+	MetadataSection = "section" // Linker script command, such as "MEMORY", output section, such as ".text", COBOL section, INI section, Pascal unit section, such as "implementation", or title of the MATLAB code section holding the chunk
+)
+
+var (
+	// asmLabel matches a label of assembly that isn't local, such as "main:"
+	// but not ".L3:" or "1:", or a MASM procedure, such as "main PROC".
+	asmLabel = regexp.MustCompile(`^(?:([A-Za-z_$][\w.$@]*):|([A-Za-z_$?@][\w$?@]*)[ \t]+(?i:proc)\b)`)
+	// asmDirective matches a line of assembly holding a directive, such as
+	// ".globl main" or "section .text", or a comment.
+	asmDirective = regexp.MustCompile(`^[ \t]*(?:\.[A-Za-z]|(?i:global|extern|section|segment|align|bits|default)\b|[;#@]|//|/\*|\*)`)
+	// asmBlockEnd matches the directives closing the block of a label, such
+	// as the .size GCC emits after a function.
+	asmBlockEnd = regexp.MustCompile(`^[ \t]*\.(?:size|cfi_endproc|endfunc|end)\b`)
+	// linkerStatement matches a line that may open a block of a linker
+	// script, such as "SECTIONS" or ".text : ALIGN(4) {", and the name of the
+	// block.
+	linkerStatement = regexp.MustCompile(`^[ \t]*([A-Za-z_.][\w.]*)[^;={}]*\{?[ \t]*(?:/\*.*)?$`)
+)
+
+// asmSpans returns the blocks of the assembly code from each label that
+// isn't local to the next, with the directives and comments right above the
+// label, such as ".globl" and ".type", recording MetadataLabel.
+func asmSpans(code string) []textSpan {
+	var spans []textSpan
+	from := 0 // End of the line of the last label
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		m := asmLabel.FindStringSubmatch(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+
+		start := asmDirectives(code, offset, from)
+		if n := len(spans); n > 0 {
+			spans[n-1].end = start
+		}
+		spans = append(spans, textSpan{start: start, end: len(code), metadata: map[string]string{MetadataLabel: m[1] + m[2]}})
+		from, offset = next, next
+	}
+	return spans
+}
+
+// asmDirectives returns the start of the directive and comment lines right
+// above the line at offset of the assembly code, not before from, or offset
+// if there are none. Directives closing the block of an earlier label stay
+// with it.
+func asmDirectives(code string, offset, from int) int {
+	for offset > from {
+		prev := strings.LastIndexByte(code[:offset-1], '\n') + 1
+		line := code[prev:offset]
+		if prev < from || !asmDirective.MatchString(line) || asmBlockEnd.MatchString(line) {
+			break
+		}
+		offset = prev
+	}
+	return offset
+}
+
+// linkerSpans returns the commands of the linker script code opening a block,
+// such as MEMORY and SECTIONS, recording MetadataSection.
+func linkerSpans(code string) []textSpan {
+	return linkerBlocks(code, 0)
+}
+
+// linkerNested returns the blocks within the block of a linker script, such
+// as the output sections of SECTIONS.
+func linkerNested(block string) []textSpan {
+	open := strings.IndexByte(block, '{')
+	if open < 0 {
+		return nil
+	}
+	_, next := codeLine(block, open)
+	return linkerBlocks(block, next)
+}
+
+// linkerBlocks returns the blocks of the linker script code opened on the
+// lines from offset on, or on the lines after them.
+func linkerBlocks(code string, offset int) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := linkerStatement.FindStringSubmatchIndex(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+
+		// The brace opening the block may start the next line
+		open := strings.IndexByte(line, '{')
+		if open >= 0 {
+			open += offset
+		} else if following, _ := codeLine(code, next); strings.HasPrefix(strings.TrimSpace(following), "{") {
+			open = next + strings.IndexByte(following, '{')
+		} else {
+			offset = next
+			continue
+		}
+
+		_, end := codeLine(code, braceClosing(code, open))
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "/*", "*"),
+			end:      end,
+			metadata: map[string]string{MetadataSection: line[m[2]:m[3]]},
+		})
+		offset = end
+	}
+	return spans
+}
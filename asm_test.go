@@ -0,0 +1,99 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Assembly(t *testing.T) {
+	code := "\t.file\t\"main.c\"\n\t.text\n\t.globl\tadd\n\t.type\tadd, @function\nadd:\n.LFB0:\n\tleal\t(%rdi,%rsi), %eax\n\tret\n.LFE0:\n\t.size\tadd, .-add\n" +
+		"\t.globl\tmain\n\t.type\tmain, @function\nmain:\n\tmovl\t$2, %esi\n\tjmp\t.L2\n.L2:\n\tcall\tadd\n\tret\n\t.size\tmain, .-main\n\n" +
+		"\t.section\t.rodata\nmsg:\n\t.asciz\t\"hi\"\n"
+	opts := []Option{WithLanguage(languages.Assembly)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	tests := []struct{ prefix, suffix, label string }{
+		{"\t.file", "\t.size\tadd, .-add\n", "add"},
+		{"\t.globl\tmain\n\t.type\tmain, @function\nmain:", "\t.size\tmain, .-main\n\n", "main"},
+		{"\t.section\t.rodata\nmsg:", "\"hi\"\n", "msg"},
+	}
+	if len(chunks) != len(tests) {
+		t.Fatalf("expected %d chunks, got %+v", len(tests), chunks)
+	}
+	for i, tt := range tests {
+		chunk := chunks[i]
+		if !strings.HasPrefix(chunk.Content, tt.prefix) || !strings.HasSuffix(chunk.Content, tt.suffix) || chunk.Metadata[MetadataLabel] != tt.label {
+			t.Errorf("expected chunk %d of %s from %q to %q, got %q with metadata %v", i, tt.label, tt.prefix, tt.suffix, chunk.Content, chunk.Metadata)
+		}
+	}
+}
+
+func TestChunker_LinkerScript(t *testing.T) {
+	code := `/* Flash and RAM of the board */
+MEMORY
+{
+  FLASH (rx) : ORIGIN = 0x08000000, LENGTH = 256K
+  RAM (rwx) : ORIGIN = 0x20000000, LENGTH = 64K
+}
+
+ENTRY(Reset_Handler)
+
+SECTIONS
+{
+  .text : ALIGN(4)
+  {
+    KEEP(*(.isr_vector))
+    *(.text*)
+  } > FLASH
+
+  .data : { *(.data*) } > RAM AT> FLASH
+
+  /* Zeroed at startup */
+  .bss :
+  {
+    *(.bss*)
+  } > RAM
+}
+`
+	opts := []Option{WithLanguage(languages.Linker), WithMaxSize(30)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	tests := []struct{ prefix, section string }{
+		{"/* Flash and RAM of the board */\nMEMORY\n", "MEMORY"},
+		{"  .text : ALIGN(4)\n", ".text"},
+		{"  /* Zeroed at startup */\n  .bss :\n", ".bss"},
+	}
+	for _, tt := range tests {
+		var found bool
+		for _, chunk := range chunks {
+			if strings.HasPrefix(chunk.Content, tt.prefix) {
+				found = true
+				if got := chunk.Metadata[MetadataSection]; got != tt.section {
+					t.Errorf("expected section %q of %q, got %q", tt.section, chunk.Content, got)
+				}
+			}
+		}
+		if !found {
+			t.Errorf("expected a chunk starting with %q, got %+v", tt.prefix, chunks)
+		}
+	}
+	for _, chunk := range chunks {
+		if strings.Contains(chunk.Content, ".data :") && chunk.Metadata[MetadataSection] != "SECTIONS" {
+			t.Errorf("expected the one-line .data section in SECTIONS, got %v", chunk.Metadata)
+		}
+	}
+}
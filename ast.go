@@ -0,0 +1,648 @@
+//go:build cgo
+
+package chunkx
+
+import (
+	"fmt"
+	"slices"
+	"sort"
+	"strings"
+	"sync"
+	"unsafe"
+
+	"github.com/gomantics/chunkx/languages"
+	sitter "github.com/smacker/go-tree-sitter"
+)
+
+// castChunker implements the CAST algorithm for code chunking. It is safe for
+// concurrent use: each call parses with a parser of its own from the pool,
+// and the interned node types are shared by every call.
+type castChunker struct {
+	parsers   sync.Pool // Idle *Parser values
+	nodeTypes sync.Map  // Interned nodeTypeInfo by nodeTypeKey, see nodeType
+}
+
+// newCastChunker creates a chunker with an empty pool of parsers.
+func newCastChunker() *castChunker {
+	c := &castChunker{}
+	c.parsers.New = func() any {
+		return NewParser()
+	}
+	return c
+}
+
+// chunkSyntax chunks code along its syntax tree, without overlap. It reports
+// parsed as false when the code can't be parsed, so the caller can fall back
+// to line-based chunking.
+func (c *castChunker) chunkSyntax(code string, cfg *config) (chunks []Chunk, parsed bool, err error) {
+	endParse := cfg.startSpan(SpanParse, Attr{Key: "language", Value: cfg.language.String()})
+	parseResult, release, err := c.parse(code, cfg)
+	endParse(err)
+	if err != nil {
+		return nil, false, nil
+	}
+	defer release()
+
+	// Bound the depth of the tree before recursing into it
+	root := parseResult.Tree.RootNode()
+	if cfg.limits.MaxDepth > 0 {
+		if err := walkTree(root, cfg, func(*sitter.Node) bool { return true }); err != nil {
+			return nil, true, err
+		}
+	}
+
+	endSplit := cfg.startSpan(SpanSplit)
+	chunks, err = c.chunkCode(root, parseResult.Source, cfg)
+	endSplit(err)
+	if err != nil {
+		return nil, true, err
+	}
+	if cfg.symbolPaths {
+		addSymbolPaths(chunks, root, parseResult.Source)
+	}
+	return chunks, true, nil
+}
+
+// addSymbolPaths records under MetadataSymbolPath the names of the nodes of
+// the tree at root that enclose each chunk, outermost first.
+func addSymbolPaths(chunks []Chunk, root *sitter.Node, source []byte) {
+	for i := range chunks {
+		var names []string
+		for node := root; node != nil; {
+			if name := node.ChildByFieldName("name"); name != nil && node != root {
+				names = append(names, name.Content(source))
+			}
+			var inner *sitter.Node
+			for j := 0; j < int(node.NamedChildCount()); j++ {
+				child := node.NamedChild(j)
+				if int(child.StartByte()) <= chunks[i].StartByte && int(child.EndByte()) >= chunks[i].EndByte {
+					inner = child
+					break
+				}
+			}
+			node = inner
+		}
+		if len(names) == 0 {
+			continue
+		}
+
+		if chunks[i].Metadata == nil {
+			chunks[i].Metadata = make(map[string]string, 1)
+		}
+		chunks[i].Metadata[MetadataSymbolPath] = strings.Join(names, ".")
+	}
+}
+
+// syntaxComments returns the byte ranges of the comments in code, which are
+// the nodes of its syntax tree with "comment" in their type. It reports parsed
+// as false when the code can't be parsed.
+func (c *castChunker) syntaxComments(code string, cfg *config) (comments [][2]int, parsed bool, err error) {
+	parseResult, release, err := c.parse(code, cfg)
+	if err != nil {
+		return nil, false, nil
+	}
+	defer release()
+
+	err = walkTree(parseResult.Tree.RootNode(), cfg, func(node *sitter.Node) bool {
+		if strings.Contains(node.Type(), "comment") {
+			comments = append(comments, [2]int{int(node.StartByte()), int(node.EndByte())})
+			return false
+		}
+		return true
+	})
+	return comments, true, err
+}
+
+// walkTree visits the nodes of the tree below root in order, descending into
+// the children of the nodes visit returns true for. It walks the tree with a
+// cursor rather than recursion, spending a step of cfg per node, and fails
+// below the depth limit of cfg.
+func walkTree(root *sitter.Node, cfg *config, visit func(node *sitter.Node) bool) error {
+	cursor := sitter.NewTreeCursor(root)
+	defer cursor.Close()
+
+	for depth := 0; ; {
+		if err := cfg.step(); err != nil {
+			return err
+		}
+		if visit(cursor.CurrentNode()) && cursor.GoToFirstChild() {
+			if depth++; cfg.limits.MaxDepth > 0 && depth > cfg.limits.MaxDepth {
+				return &LimitExceeded{Limit: LimitDepth, Max: cfg.limits.MaxDepth}
+			}
+			continue
+		}
+		for !cursor.GoToNextSibling() {
+			if depth == 0 || !cursor.GoToParent() {
+				return nil
+			}
+			depth--
+		}
+	}
+}
+
+// parse parses code in the configured language, reusing a cached tree when a
+// parse cache is configured. The returned release function must be called
+// once the tree is no longer in use.
+func (c *castChunker) parse(code string, cfg *config) (*ParseResult, func(), error) {
+	// Trees outlive the parser that built them, so it goes back to the pool
+	// as soon as parsing is done
+	parser := c.parsers.Get().(*Parser)
+	defer c.parsers.Put(parser)
+
+	// The code of a Document is reparsed from its edited tree, and kept for
+	// its next edits
+	if run := cfg.incremental; run != nil && code == run.code {
+		var old syntaxTree
+		if prev := run.prev; prev != nil && prev.language == cfg.language {
+			old = prev.tree
+		}
+		parseResult, err := parser.parse(cfg.ctx, run.code, cfg.language, old)
+		if err != nil {
+			return nil, nil, err
+		}
+		run.tree, run.language = parseResult.Tree, cfg.language
+		return parseResult, func() {}, nil
+	}
+
+	if cfg.parseCache != nil {
+		return cfg.parseCache.parse(cfg.ctx, parser, code, cfg.language)
+	}
+
+	parseResult, err := parser.ParseCtx(cfg.ctx, code, cfg.language)
+	if err != nil {
+		return nil, nil, err
+	}
+	return parseResult, func() {}, nil
+}
+
+// chunkCode implements the main CAST algorithm
+func (c *castChunker) chunkCode(node *sitter.Node, source []byte, cfg *config) ([]Chunk, error) {
+	if err := cfg.ctx.Err(); err != nil {
+		return nil, err
+	}
+	if err := cfg.step(); err != nil {
+		return nil, err
+	}
+
+	size, err := cfg.nodeSize(node, source)
+	if err != nil {
+		return nil, fmt.Errorf("%w: %w", ErrNodeSize, err)
+	}
+
+	// If node fits within max size, or the soft limit, return it as a single chunk
+	if size <= cfg.softMaxSize() {
+		return []Chunk{overBudget(c.nodeToChunk(node, source, cfg.language), size, cfg)}, nil
+	}
+
+	// Syntax errors don't follow the grammar, so their children are no
+	// better boundaries than lines
+	if node.IsError() {
+		return c.splitError(node, source, cfg)
+	}
+
+	// Fenced code is better split by the grammar of its own language
+	if cfg.fencedCode && cfg.language == languages.Markdown && node.Type() == "fenced_code_block" {
+		if chunks, ok, err := c.chunkFence(node, source, cfg); ok || err != nil {
+			return chunks, err
+		}
+	}
+
+	// Tables are split by rows, and their header recorded with each group
+	if cfg.tableHeaders {
+		if children, header := tableChildren(node, source, cfg.language); header > 0 {
+			return c.chunkTable(children, header, source, cfg)
+		}
+	}
+
+	// Otherwise, chunk the node's children
+	childCount := int(node.ChildCount())
+	if childCount == 0 {
+		// Leaf node that's too large - return as is (can't split further)
+		return []Chunk{c.nodeToChunk(node, source, cfg.language)}, nil
+	}
+
+	children := make([]*sitter.Node, 0, childCount)
+	for i := 0; i < childCount; i++ {
+		if child := node.Child(i); child != nil {
+			children = append(children, child)
+		}
+	}
+
+	return c.chunkNodes(children, source, cfg)
+}
+
+// chunkNodes implements the node grouping logic.
+func (c *castChunker) chunkNodes(nodes []*sitter.Node, source []byte, cfg *config) ([]Chunk, error) {
+	var chunks []Chunk
+	var currentNodes []*sitter.Node
+	currentSize := 0
+
+	for _, node := range nodes {
+		if err := cfg.step(); err != nil {
+			return nil, err
+		}
+
+		nodeSize, err := cfg.nodeSize(node, source)
+		if err != nil {
+			return nil, fmt.Errorf("%w: %w", ErrNodeSize, err)
+		}
+
+		// If adding this node would exceed max size
+		if len(currentNodes) > 0 && currentSize+nodeSize > cfg.maxSize {
+			// Save current chunk
+			chunks = append(chunks, c.mergeNodesToChunk(currentNodes, source, cfg.language))
+			currentNodes = nil
+			currentSize = 0
+		}
+
+		// If single node exceeds max size, keep it whole within the soft
+		// limit, or recursively chunk it
+		if nodeSize > cfg.maxSize {
+			if len(currentNodes) > 0 {
+				chunks = append(chunks, c.mergeNodesToChunk(currentNodes, source, cfg.language))
+				currentNodes = nil
+				currentSize = 0
+			}
+
+			if nodeSize <= cfg.softMaxSize() {
+				chunks = append(chunks, overBudget(c.nodeToChunk(node, source, cfg.language), nodeSize, cfg))
+				continue
+			}
+			subChunks, err := c.chunkCode(node, source, cfg)
+			if err != nil {
+				return nil, err
+			}
+			chunks = append(chunks, subChunks...)
+		} else {
+			currentNodes = append(currentNodes, node)
+			currentSize += nodeSize
+		}
+	}
+
+	// Don't forget the last chunk
+	if len(currentNodes) > 0 {
+		chunks = append(chunks, c.mergeNodesToChunk(currentNodes, source, cfg.language))
+	}
+
+	return chunks, nil
+}
+
+// chunkFence splits the content of a fenced code block of Markdown in the
+// language of its fence, extending the first and last chunk over the fences.
+// It reports false if the fence names no supported language.
+func (c *castChunker) chunkFence(node *sitter.Node, source []byte, cfg *config) ([]Chunk, bool, error) {
+	var lang languages.LanguageName
+	var content *sitter.Node
+	for i := 0; i < int(node.ChildCount()); i++ {
+		switch child := node.Child(i); child.Type() {
+		case "info_string":
+			lang = fenceLanguage(child.Content(source))
+		case "code_fence_content":
+			content = child
+		}
+	}
+	if lang == "" || content == nil {
+		return nil, false, nil
+	}
+
+	inner := *cfg
+	inner.language = lang
+	chunks, err := c.split(sliceSource(source, content.StartByte(), content.EndByte()), &inner)
+	if err != nil {
+		return nil, true, err
+	}
+	if len(chunks) == 0 {
+		return nil, false, nil
+	}
+	shiftChunks(chunks, int(content.StartByte()), int(content.StartPoint().Row))
+
+	first, last := &chunks[0], &chunks[len(chunks)-1]
+	first.StartByte, last.EndByte = int(node.StartByte()), int(node.EndByte())
+	first.StartLine, _ = GetLineNumbers(node)
+	_, last.EndLine = GetLineNumbers(node)
+	for i := range chunks {
+		chunks[i].Content = sliceSource(source, uint32(chunks[i].StartByte), uint32(chunks[i].EndByte))
+		if chunks[i].Metadata == nil {
+			chunks[i].Metadata = make(map[string]string, 1)
+		}
+		chunks[i].Metadata[MetadataFenceLanguage] = string(lang)
+	}
+	return chunks, true, nil
+}
+
+// tableChildren returns the children of a table node of Markdown or HTML, with
+// those of an HTML table body in place of the body, and the number of
+// children up to the end of its header. It returns a header of 0 if node isn't
+// a table or has no header row.
+func tableChildren(node *sitter.Node, source []byte, lang languages.LanguageName) ([]*sitter.Node, int) {
+	var isHeader func(child *sitter.Node) bool
+	switch {
+	case lang == languages.Markdown && node.Type() == "pipe_table":
+		isHeader = func(child *sitter.Node) bool { return child.Type() == "pipe_table_delimiter_row" }
+	case lang == languages.HTML && htmlTag(node, source) == "table":
+		isHeader = func(child *sitter.Node) bool {
+			switch htmlTag(child, source) {
+			case "thead":
+				return true
+			case "tr":
+				cell := child.NamedChild(1) // After the start tag, the first cell
+				return cell != nil && htmlTag(cell, source) == "th"
+			}
+			return false
+		}
+	default:
+		return nil, 0
+	}
+
+	var children []*sitter.Node
+	for i := 0; i < int(node.ChildCount()); i++ {
+		child := node.Child(i)
+		if htmlTag(child, source) == "tbody" {
+			for j := 0; j < int(child.ChildCount()); j++ {
+				children = append(children, child.Child(j))
+			}
+			continue
+		}
+		children = append(children, child)
+	}
+	for i, child := range children {
+		if isHeader(child) {
+			return children, i + 1
+		}
+		if htmlTag(child, source) == "tr" || child.Type() == "pipe_table_row" {
+			break
+		}
+	}
+	return nil, 0
+}
+
+// htmlTag returns the lowercase tag name of an HTML element node, or "" if
+// node isn't an element.
+func htmlTag(node *sitter.Node, source []byte) string {
+	if node.Type() != "element" || node.NamedChildCount() == 0 {
+		return ""
+	}
+	start := node.NamedChild(0)
+	if start.Type() != "start_tag" || start.NamedChildCount() == 0 {
+		return ""
+	}
+	return strings.ToLower(start.NamedChild(0).Content(source))
+}
+
+// chunkTable groups the children of a table into chunks of whole rows, the
+// first header of which make up its header. Every group is sized to fit with
+// the header, and the groups after the first record it.
+func (c *castChunker) chunkTable(children []*sitter.Node, header int, source []byte, cfg *config) ([]Chunk, error) {
+	headerText := sliceSource(source, children[0].StartByte(), children[header-1].EndByte())
+	headerSize, err := cfg.tokenCounter.CountTokens(headerText)
+	if err != nil {
+		return nil, fmt.Errorf("%w: %w", ErrNodeSize, err)
+	}
+
+	var chunks []Chunk
+	add := func(chunk ...Chunk) {
+		for _, chunk := range chunk {
+			if len(chunks) > 0 {
+				if chunk.Metadata == nil {
+					chunk.Metadata = make(map[string]string, 1)
+				}
+				chunk.Metadata[MetadataTableHeader] = headerText
+			}
+			chunks = append(chunks, chunk)
+		}
+	}
+
+	group := slices.Clone(children[:header])
+	size := headerSize
+	for _, node := range children[header:] {
+		if err := cfg.step(); err != nil {
+			return nil, err
+		}
+		nodeSize, err := cfg.nodeSize(node, source)
+		if err != nil {
+			return nil, fmt.Errorf("%w: %w", ErrNodeSize, err)
+		}
+
+		if len(group) > 0 && size+nodeSize > cfg.maxSize {
+			add(c.mergeNodesToChunk(group, source, cfg.language))
+			group, size = nil, headerSize
+		}
+		if headerSize+nodeSize <= cfg.maxSize {
+			group = append(group, node)
+			size += nodeSize
+			continue
+		}
+
+		// A row too large to fit with the header is a group of its own
+		if nodeSize <= cfg.softMaxSize() {
+			add(overBudget(c.nodeToChunk(node, source, cfg.language), nodeSize, cfg))
+			continue
+		}
+		subChunks, err := c.chunkCode(node, source, cfg)
+		if err != nil {
+			return nil, err
+		}
+		add(subChunks...)
+	}
+	if len(group) > 0 {
+		add(c.mergeNodesToChunk(group, source, cfg.language))
+	}
+	return chunks, nil
+}
+
+// splitError splits a syntax error that is too large for one chunk by line.
+func (c *castChunker) splitError(node *sitter.Node, source []byte, cfg *config) ([]Chunk, error) {
+	chunks, err := c.splitLines(sliceSource(source, node.StartByte(), node.EndByte()), cfg)
+	if err != nil {
+		return nil, err
+	}
+
+	start, row := int(node.StartByte()), int(node.StartPoint().Row)
+	for i := range chunks {
+		chunks[i].StartByte += start
+		chunks[i].EndByte += start
+		chunks[i].StartLine += row
+		chunks[i].EndLine += row
+		chunks[i].Metadata = map[string]string{MetadataParseDegraded: "true"}
+	}
+	return chunks, nil
+}
+
+// degraded returns the metadata of a chunk of nodes, marking chunks that
+// span syntax errors.
+func degraded(nodes ...*sitter.Node) map[string]string {
+	for _, node := range nodes {
+		if node.HasError() {
+			return map[string]string{MetadataParseDegraded: "true"}
+		}
+	}
+	return nil
+}
+
+// nodeToChunk converts a single node to a Chunk.
+func (c *castChunker) nodeToChunk(node *sitter.Node, source []byte, language languages.LanguageName) Chunk {
+	startLine, endLine := GetLineNumbers(node)
+
+	// Collect unique node types
+	nodeTypeSet := make(map[string]bool)
+	c.collectNodeTypes(node, language, nodeTypeSet)
+
+	nodeTypes := make([]string, 0, len(nodeTypeSet))
+	for nodeType := range nodeTypeSet {
+		nodeTypes = append(nodeTypes, nodeType)
+	}
+	sort.Strings(nodeTypes)
+
+	return Chunk{
+		Content:   sliceSource(source, node.StartByte(), node.EndByte()),
+		StartLine: startLine,
+		EndLine:   endLine,
+		StartByte: int(node.StartByte()),
+		EndByte:   int(node.EndByte()),
+		NodeTypes: nodeTypes,
+		Language:  language,
+		Metadata:  degraded(node),
+	}
+}
+
+// mergeNodesToChunk merges multiple nodes into a single chunk.
+func (c *castChunker) mergeNodesToChunk(nodes []*sitter.Node, source []byte, language languages.LanguageName) Chunk {
+	if len(nodes) == 0 {
+		return Chunk{Language: language}
+	}
+
+	// Find the span of all nodes
+	firstNode := nodes[0]
+	lastNode := nodes[len(nodes)-1]
+
+	startByte := firstNode.StartByte()
+	endByte := lastNode.EndByte()
+
+	// Collect unique node types using a map
+	nodeTypeSet := make(map[string]bool)
+	for _, node := range nodes {
+		c.collectNodeTypes(node, language, nodeTypeSet)
+	}
+
+	// Convert map to sorted slice for consistent output
+	nodeTypes := make([]string, 0, len(nodeTypeSet))
+	for nodeType := range nodeTypeSet {
+		nodeTypes = append(nodeTypes, nodeType)
+	}
+	sort.Strings(nodeTypes)
+
+	startLine, _ := GetLineNumbers(firstNode)
+	_, endLine := GetLineNumbers(lastNode)
+
+	return Chunk{
+		Content:   sliceSource(source, startByte, endByte),
+		StartLine: startLine,
+		EndLine:   endLine,
+		StartByte: int(startByte),
+		EndByte:   int(endByte),
+		NodeTypes: nodeTypes,
+		Language:  language,
+		Metadata:  degraded(nodes...),
+	}
+}
+
+// collectNodeTypes recursively collects all unique node types from a node and its descendants.
+func (c *castChunker) collectNodeTypes(node *sitter.Node, language languages.LanguageName, nodeTypeSet map[string]bool) {
+	if node == nil {
+		return
+	}
+
+	nodeType := c.nodeType(node, language)
+
+	// Skip pure punctuation/whitespace node types
+	if nodeType.include {
+		nodeTypeSet[nodeType.name] = true
+	}
+
+	// Recursively collect from children
+	childCount := int(node.ChildCount())
+	for i := 0; i < childCount; i++ {
+		if child := node.Child(i); child != nil {
+			c.collectNodeTypes(child, language, nodeTypeSet)
+		}
+	}
+}
+
+// syntaxTree is the syntax tree a Document keeps for incremental parsing.
+type syntaxTree = *sitter.Tree
+
+// nodeSize returns the size of node in source. Re-chunking the code of a
+// Document reuses the sizes measured before its edits for the nodes outside
+// the edited regions, as their bytes are the same.
+func (cfg *config) nodeSize(node *sitter.Node, source []byte) (int, error) {
+	run := cfg.incremental
+	if run == nil || !run.parsed(source, cfg.language) {
+		return GetNodeSize(node, source, cfg.tokenCounter)
+	}
+
+	span := nodeSpan{node.StartByte(), node.EndByte()}
+	size, ok := run.prev.reuse(span, cfg.language)
+	if !ok {
+		var err error
+		if size, err = GetNodeSize(node, source, cfg.tokenCounter); err != nil {
+			return 0, err
+		}
+	}
+	run.sizes[span] = size
+	return size, nil
+}
+
+// parsed reports whether source is the code of the run as parsed in
+// language, rather than a part or a rewrite of it, so the byte ranges of its
+// nodes are those of the code.
+func (run *incrementalRun) parsed(source []byte, language languages.LanguageName) bool {
+	return run.language == language && len(source) == len(run.code) && len(source) > 0 &&
+		unsafe.SliceData(source) == unsafe.StringData(run.code)
+}
+
+// reuse returns the size measured for the node at span by the previous run,
+// if it parsed the code in the same language.
+func (run *incrementalRun) reuse(span nodeSpan, language languages.LanguageName) (int, bool) {
+	if run == nil || run.language != language {
+		return 0, false
+	}
+	size, ok := run.sizes[span]
+	return size, ok
+}
+
+// editTree records an edit against code in tree, if there is one, so the next
+// parse can reuse the unchanged parts.
+func editTree(tree syntaxTree, code string, edit Edit) {
+	if tree != nil {
+		tree.Edit(editInput(code, edit))
+	}
+}
+
+// editInput converts an edit against code into a tree-sitter edit.
+func editInput(code string, edit Edit) sitter.EditInput {
+	start := pointAt(code, edit.StartByte)
+	return sitter.EditInput{
+		StartIndex:  uint32(edit.StartByte),
+		OldEndIndex: uint32(edit.OldEndByte),
+		NewEndIndex: uint32(edit.StartByte + len(edit.NewText)),
+		StartPoint:  start,
+		OldEndPoint: pointAt(code, edit.OldEndByte),
+		NewEndPoint: advancePoint(start, edit.NewText),
+	}
+}
+
+// pointAt returns the row and byte column of offset in code.
+func pointAt(code string, offset int) sitter.Point {
+	return advancePoint(sitter.Point{}, code[:offset])
+}
+
+// advancePoint returns the point reached by appending text at p.
+func advancePoint(p sitter.Point, text string) sitter.Point {
+	if i := strings.LastIndexByte(text, '\n'); i >= 0 {
+		return sitter.Point{
+			Row:    p.Row + uint32(strings.Count(text, "\n")),
+			Column: uint32(len(text) - i - 1),
+		}
+	}
+	return sitter.Point{Row: p.Row, Column: p.Column + uint32(len(text))}
+}
@@ -0,0 +1,154 @@
+package chunkx
+
+import (
+	"context"
+	"fmt"
+	"iter"
+	"runtime"
+	"sync"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// manyBatch is the number of texts ChunkMany hands a worker at once, so
+// sending them doesn't dominate the time spent chunking short texts.
+const manyBatch = 256
+
+// TextChunk is a chunk of one of the texts ChunkMany chunks.
+type TextChunk struct {
+	ID string // ID of the text the chunk is from
+	Chunk
+}
+
+// textBatch is a run of texts for a worker of ChunkMany, numbered in the
+// order they were yielded.
+type textBatch struct {
+	seq   int
+	ids   []string
+	texts []string
+}
+
+// batchResult is the outcome of a textBatch.
+type batchResult struct {
+	seq    int
+	chunks []TextChunk
+	err    error
+}
+
+// ChunkMany chunks many short texts, such as tweets or product descriptions,
+// yielded with their IDs, on one worker per CPU, returning the chunks of all
+// of them in one slice tagged with the ID of their text: in the order texts
+// yields them, and each text's by offset. The options are applied once for
+// all texts, and texts are handed to workers in batches, so neither setting
+// up a call nor scheduling it dominates the runtime as it does when calling
+// Chunk for each. The language defaults to languages.Generic.
+//
+// ChunkMany stops at the first error, returning it with the ID of the text
+// it is for, or at cancellation of ctx.
+func ChunkMany(ctx context.Context, texts iter.Seq2[string, string], opts ...Option) ([]TextChunk, error) {
+	cfg := newDefaultConfig()
+	for _, opt := range opts {
+		opt(cfg)
+	}
+	if cfg.language == "" {
+		cfg.language = languages.Generic
+	}
+
+	ctx, cancel := context.WithCancel(ctx)
+	defer cancel()
+
+	workers := runtime.GOMAXPROCS(0)
+	batches := make(chan textBatch, workers)
+	results := make(chan batchResult, workers)
+	go func() {
+		defer close(batches)
+		batch := textBatch{}
+		send := func() bool {
+			select {
+			case batches <- batch:
+				batch = textBatch{seq: batch.seq + 1}
+				return true
+			case <-ctx.Done():
+				return false
+			}
+		}
+		for id, text := range texts {
+			batch.ids, batch.texts = append(batch.ids, id), append(batch.texts, text)
+			if len(batch.texts) == manyBatch && !send() {
+				return
+			}
+		}
+		if len(batch.texts) > 0 {
+			send()
+		}
+	}()
+
+	c := newCastChunker()
+	var wg sync.WaitGroup
+	for range workers {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			for batch := range batches {
+				results <- c.chunkBatch(ctx, batch, cfg)
+			}
+		}()
+	}
+	go func() {
+		wg.Wait()
+		close(results)
+	}()
+
+	var done [][]TextChunk // By the seq of their batch
+	var err error
+	for result := range results {
+		if result.err != nil && err == nil {
+			err = result.err
+			cancel()
+		}
+		for len(done) <= result.seq {
+			done = append(done, nil)
+		}
+		done[result.seq] = result.chunks
+	}
+	if err == nil {
+		err = ctx.Err()
+	}
+	if err != nil {
+		return nil, err
+	}
+
+	var n int
+	for _, chunks := range done {
+		n += len(chunks)
+	}
+	all := make([]TextChunk, 0, n)
+	for _, chunks := range done {
+		all = append(all, chunks...)
+	}
+	return all, nil
+}
+
+// chunkBatch chunks the texts of batch with a copy of cfg each, as
+// ChunkContext would with the options cfg was built from.
+func (c *castChunker) chunkBatch(ctx context.Context, batch textBatch, cfg *config) batchResult {
+	result := batchResult{seq: batch.seq}
+	for i, text := range batch.texts {
+		if err := ctx.Err(); err != nil {
+			result.err = err
+			return result
+		}
+		textCfg := *cfg
+		textCfg.ctx = ctx
+		chunks, err := c.chunk(text, &textCfg)
+		if err != nil {
+			result.err = fmt.Errorf("text %q: %w", batch.ids[i], err)
+			return result
+		}
+		renderContext(chunks, "", &textCfg)
+		for _, chunk := range chunks {
+			result.chunks = append(result.chunks, TextChunk{ID: batch.ids[i], Chunk: chunk})
+		}
+	}
+	return result
+}
@@ -0,0 +1,66 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunkMany_MatchesChunk(t *testing.T) {
+	texts := make([]string, 1000)
+	for i := range texts {
+		texts[i] = strings.Repeat(fmt.Sprintf("word%d ", i), i%20+1) + "\nlast line"
+	}
+	seq := func(yield func(string, string) bool) {
+		for i, text := range texts {
+			if !yield(fmt.Sprint(i), text) {
+				return
+			}
+		}
+	}
+
+	got, err := ChunkMany(context.Background(), seq, WithMaxSize(8))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	var want []TextChunk
+	chunker := NewChunker()
+	for i, text := range texts {
+		chunks, err := chunker.Chunk(text, WithLanguage(languages.Generic), WithMaxSize(8))
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		for _, chunk := range chunks {
+			want = append(want, TextChunk{ID: fmt.Sprint(i), Chunk: chunk})
+		}
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("expected the chunks of every text in order, got %d chunks, want %d", len(got), len(want))
+	}
+}
+
+func TestChunkMany_Error(t *testing.T) {
+	seq := func(yield func(string, string) bool) {
+		for i := range 2000 {
+			text := "short"
+			if i == 1500 {
+				text = strings.Repeat("long ", 100)
+			}
+			if !yield(fmt.Sprintf("doc-%d", i), text) {
+				return
+			}
+		}
+	}
+
+	_, err := ChunkMany(context.Background(), seq, WithLimits(Limits{MaxBytes: 100}))
+	var limit *LimitExceeded
+	if !errors.As(err, &limit) || !strings.Contains(err.Error(), `"doc-1500"`) {
+		t.Errorf("expected the limit exceeded by doc-1500, got %v", err)
+	}
+}
@@ -0,0 +1,111 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+// Metadata keys of the bibliography entry holding the chunk.
+const (
+	MetadataCitationKey = "citation_key" // Citation key of the BibTeX entry, such as "knuth1984", or ID of the RIS record
+	MetadataEntryType   = "entry_type"   // Type of the BibTeX entry in lower case, such as "article", or of the RIS record, such as "JOUR"
+)
+
+var (
+	// bibEntry matches the start of a BibTeX entry, such as "@article{" or
+	// "@Book(", and its type and opening delimiter.
+	bibEntry = regexp.MustCompile(`^[ \t]*@[ \t]*(\w+)[ \t]*([{(])`)
+	// risTag matches a line of a RIS record, such as "TY  - JOUR", and its
+	// tag and value.
+	risTag = regexp.MustCompile(`^([A-Z][A-Z0-9])  -[ \t]?(.*?)[ \t]*\r?$`)
+)
+
+// bibSpans returns the entries of the BibTeX code, each up to the delimiter
+// closing it, recording its citation key as MetadataCitationKey and its type
+// as MetadataEntryType, so every entry is chunked apart. String, preamble,
+// and comment entries have no key and record their type only. The text
+// between entries, which BibTeX ignores, is chunked as is.
+func bibSpans(code string) []textSpan {
+	var spans []textSpan
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		m := bibEntry.FindStringSubmatchIndex(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+		body := offset + m[5]
+		closing := bibEntryEnd(code, body, line[m[4]])
+		if closing < 0 {
+			offset = next
+			continue
+		}
+
+		kind := strings.ToLower(line[m[2]:m[3]])
+		metadata := map[string]string{MetadataEntryType: kind}
+		if kind != "string" && kind != "preamble" && kind != "comment" {
+			if key, _, ok := strings.Cut(code[body:closing], ","); ok {
+				metadata[MetadataCitationKey] = strings.TrimSpace(key)
+			}
+		}
+		_, end := codeLine(code, closing)
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "%"),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// bibEntryEnd returns the offset of the delimiter closing the BibTeX entry
+// whose body starts at offset in code, after the given opening delimiter,
+// skipping the braces nested within its fields, or -1 if it isn't closed.
+func bibEntryEnd(code string, offset int, open byte) int {
+	depth := 0
+	for i := offset; i < len(code); i++ {
+		switch code[i] {
+		case '{':
+			depth++
+		case '}':
+			if depth == 0 && open == '{' {
+				return i
+			}
+			depth--
+		case ')':
+			if depth == 0 && open == '(' {
+				return i
+			}
+		case '\\':
+			i++ // An escaped brace, such as \{
+		}
+	}
+	return -1
+}
+
+// risSpans returns the records of the RIS code, each from its TY line to its
+// ER line, recording its ID as MetadataCitationKey, if it has one, and its
+// reference type as MetadataEntryType, so every record is chunked apart.
+func risSpans(code string) []textSpan {
+	var spans []textSpan
+	open := false // Whether the last record awaits its ER line
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		m := risTag.FindStringSubmatch(line)
+		if m != nil && m[1] == "TY" {
+			spans = append(spans, textSpan{start: offset, metadata: map[string]string{MetadataEntryType: m[2]}})
+			open = true
+		}
+		if open {
+			record := &spans[len(spans)-1]
+			record.end = next
+			if m != nil && m[1] == "ID" && m[2] != "" {
+				record.metadata[MetadataCitationKey] = m[2]
+			}
+			open = m == nil || m[1] != "ER"
+		}
+		offset = next
+	}
+	return spans
+}
@@ -0,0 +1,58 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_BibTeX(t *testing.T) {
+	code := `@string{acm = "Communications of the ACM"}
+
+% Literate programming
+@Article{knuth1984,
+  author  = {Donald E. Knuth},
+  title   = {Literate {P}rogramming},
+  journal = acm,
+  year    = 1984,
+}
+
+@book(lamport1994,
+  author = "Leslie Lamport",
+  title  = {{\LaTeX}: A Document Preparation System},
+)
+`
+	chunks := testChunkMetadata(t, code, languages.BibTeX, 100, []struct{ prefix, key, value string }{
+		{"@string{", MetadataEntryType, "string"},
+		{"% Literate programming\n@Article{knuth1984,", MetadataCitationKey, "knuth1984"},
+		{"% Literate programming\n@Article{knuth1984,", MetadataEntryType, "article"},
+		{"@book(lamport1994,", MetadataCitationKey, "lamport1994"},
+	})
+	for _, chunk := range chunks {
+		if strings.HasPrefix(chunk.Content, "@book(") && !strings.HasSuffix(chunk.Content, "System},\n)\n") {
+			t.Errorf("expected the entry to end at its closing parenthesis, got %q", chunk.Content)
+		}
+	}
+}
+
+func TestChunker_RIS(t *testing.T) {
+	code := `TY  - JOUR
+ID  - knuth1984
+AU  - Knuth, Donald E.
+TI  - Literate Programming
+PY  - 1984
+ER  -
+
+TY  - BOOK
+AU  - Lamport, Leslie
+TI  - LaTeX: A Document Preparation System
+ER  -
+`
+	testChunkMetadata(t, code, languages.RIS, 100, []struct{ prefix, key, value string }{
+		{"TY  - JOUR\nID  - knuth1984\n", MetadataCitationKey, "knuth1984"},
+		{"TY  - JOUR\n", MetadataEntryType, "JOUR"},
+		{"TY  - BOOK\n", MetadataEntryType, "BOOK"},
+		{"TY  - BOOK\n", MetadataCitationKey, ""},
+	})
+}
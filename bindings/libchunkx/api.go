@@ -0,0 +1,172 @@
+package main
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"os"
+
+	"github.com/gomantics/chunkx"
+)
+
+// chunkRecord is the JSON form of a chunk returned to bindings.
+type chunkRecord struct {
+	ID        string            `json:"id"`
+	Language  string            `json:"language"`
+	StartLine int               `json:"start_line"`
+	EndLine   int               `json:"end_line"`
+	StartByte int               `json:"start_byte"`
+	EndByte   int               `json:"end_byte"`
+	NodeTypes []string          `json:"node_types"`
+	Metadata  map[string]string `json:"metadata,omitempty"`
+	Content   string            `json:"content"`
+}
+
+// fileRecord is the JSON form of a file result returned to bindings.
+type fileRecord struct {
+	Path   string        `json:"path"`
+	Chunks []chunkRecord `json:"chunks"`
+	Error  string        `json:"error,omitempty"`
+}
+
+// newChunkRecords converts chunks to their JSON form.
+func newChunkRecords(chunks []chunkx.Chunk) []chunkRecord {
+	records := make([]chunkRecord, len(chunks))
+	for i, chunk := range chunks {
+		records[i] = chunkRecord{
+			ID:        chunk.ID(),
+			Language:  chunk.Language.String(),
+			StartLine: chunk.StartLine,
+			EndLine:   chunk.EndLine,
+			StartByte: chunk.StartByte,
+			EndByte:   chunk.EndByte,
+			NodeTypes: chunk.NodeTypes,
+			Metadata:  chunk.Metadata,
+			Content:   chunk.Content,
+		}
+	}
+	return records
+}
+
+// parseOptions parses a JSON object with the keys of a chunkx.json config
+// file. An empty string selects the defaults.
+func parseOptions(options string) ([]chunkx.Option, error) {
+	if options == "" {
+		return nil, nil
+	}
+	cfg, err := chunkx.ParseConfig([]byte(options), "json")
+	if err != nil {
+		return nil, err
+	}
+	return cfg.Options()
+}
+
+// marshal encodes v as JSON.
+func marshal(v any) (string, error) {
+	data, err := json.Marshal(v)
+	return string(data), err
+}
+
+// chunkText chunks code, line by line unless options set a language.
+func chunkText(code, options string) (string, error) {
+	opts, err := parseOptions(options)
+	if err != nil {
+		return "", err
+	}
+	return (&chunkerHandle{chunker: chunkx.NewChunker(), opts: opts}).chunkText(code)
+}
+
+// chunkFile chunks the file at path, detecting its language from the
+// extension.
+func chunkFile(path, options string) (string, error) {
+	opts, err := parseOptions(options)
+	if err != nil {
+		return "", err
+	}
+	return (&chunkerHandle{chunker: chunkx.NewChunker(), opts: opts}).chunkFile(path)
+}
+
+// chunkDir chunks every file below dir with the given number of workers.
+// Files that fail are reported with an error rather than failing the call.
+func chunkDir(dir, options string, workers int) (string, error) {
+	opts, err := parseOptions(options)
+	if err != nil {
+		return "", err
+	}
+	if _, err := os.Stat(dir); err != nil {
+		return "", err
+	}
+
+	pipeline := chunkx.NewChunkPipeline(workers, append(opts, chunkx.WithOrderedOutput())...)
+
+	files := []fileRecord{}
+	for result := range pipeline.RunDir(context.Background(), dir) {
+		file := fileRecord{Path: result.Path, Chunks: newChunkRecords(result.Chunks)}
+		if result.Err != nil {
+			file.Error = result.Err.Error()
+		}
+		files = append(files, file)
+	}
+	return marshal(files)
+}
+
+// loadConfig reads a config file and returns it as a JSON object with the
+// same keys, so bindings can inspect and adjust it.
+func loadConfig(path string) (string, error) {
+	cfg, err := chunkx.LoadConfig(path)
+	if err != nil {
+		return "", err
+	}
+
+	var buf bytes.Buffer
+	buf.WriteByte('{')
+	field := func(key string, value any) {
+		if buf.Len() > 1 {
+			buf.WriteByte(',')
+		}
+		k, _ := json.Marshal(key)
+		v, _ := json.Marshal(value)
+		buf.Write(k)
+		buf.WriteByte(':')
+		buf.Write(v)
+	}
+
+	if cfg.Strategy != "" {
+		field("strategy", cfg.Strategy)
+	}
+	if cfg.MaxSize != 0 {
+		field("max_size", cfg.MaxSize)
+	}
+	if cfg.Overlap != 0 {
+		field("overlap", cfg.Overlap)
+	}
+	if cfg.Counter != "" {
+		field("counter", cfg.Counter)
+	}
+	if cfg.Language != "" {
+		field("language", cfg.Language)
+	}
+	if len(cfg.Languages) > 0 {
+		// Written by hand to keep the order of the overrides
+		var overrides bytes.Buffer
+		overrides.WriteByte('{')
+		for i, o := range cfg.Languages {
+			if i > 0 {
+				overrides.WriteByte(',')
+			}
+			k, _ := json.Marshal(o.Pattern)
+			v, _ := json.Marshal(o.Language)
+			overrides.Write(k)
+			overrides.WriteByte(':')
+			overrides.Write(v)
+		}
+		overrides.WriteByte('}')
+		field("languages", json.RawMessage(overrides.Bytes()))
+	}
+	if len(cfg.Ignore) > 0 {
+		field("ignore", cfg.Ignore)
+	}
+	buf.WriteByte('}')
+
+	return buf.String(), nil
+}
@@ -0,0 +1,91 @@
+package main
+
+import (
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestChunkText(t *testing.T) {
+	out, err := chunkText("one two\nthree four\n", `{"max_size": 2}`)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	var chunks []chunkRecord
+	if err := json.Unmarshal([]byte(out), &chunks); err != nil {
+		t.Fatalf("invalid JSON %q: %v", out, err)
+	}
+	if len(chunks) != 2 || chunks[1].Content != "three four\n" || chunks[0].Language != "generic" {
+		t.Errorf("unexpected chunks %+v", chunks)
+	}
+}
+
+func TestChunkText_InvalidOptions(t *testing.T) {
+	if _, err := chunkText("x", `{"max_chunks": 2}`); err == nil {
+		t.Error("expected an error for an unknown option")
+	}
+}
+
+func TestChunkDir(t *testing.T) {
+	dir := t.TempDir()
+	for name, content := range map[string]string{
+		"a.go":           "package a\n",
+		"vendor/b.go":    "package b\n",
+		"sub/c.txt":      "hello\n",
+		"sub/deep/d.txt": "world\n",
+	} {
+		path := filepath.Join(dir, name)
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(path, []byte(content), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	out, err := chunkDir(dir, `{"ignore": ["vendor/**"]}`, 2)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	var files []fileRecord
+	if err := json.Unmarshal([]byte(out), &files); err != nil {
+		t.Fatalf("invalid JSON %q: %v", out, err)
+	}
+	var paths []string
+	for _, f := range files {
+		rel, _ := filepath.Rel(dir, f.Path)
+		paths = append(paths, filepath.ToSlash(rel))
+	}
+	if len(paths) != 3 || paths[0] != "a.go" || paths[1] != "sub/c.txt" || paths[2] != "sub/deep/d.txt" {
+		t.Errorf("paths = %v", paths)
+	}
+
+	if _, err := chunkDir(filepath.Join(dir, "missing"), "", 0); err == nil {
+		t.Error("expected an error for a missing directory")
+	}
+}
+
+func TestLoadConfig(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "chunkx.toml")
+	config := "max_size = 300\nignore = [\"vendor/**\"]\n\n[languages]\n\"*.tpl\" = \"html\"\n\"*.x\" = \"go\"\n"
+	if err := os.WriteFile(path, []byte(config), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	out, err := loadConfig(path)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	want := `{"max_size":300,"languages":{"*.tpl":"html","*.x":"go"},"ignore":["vendor/**"]}`
+	if out != want {
+		t.Errorf("loadConfig = %s, want %s", out, want)
+	}
+
+	// The result is accepted back as options
+	if _, err := parseOptions(out); err != nil {
+		t.Errorf("options from loadConfig rejected: %v", err)
+	}
+}
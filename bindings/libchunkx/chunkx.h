@@ -0,0 +1,99 @@
+/*
+ * chunkx.h - C API of the chunkx shared library.
+ *
+ * Build the library with Go and a C compiler:
+ *
+ *     go build -buildmode=c-shared -o libchunkx.so ./bindings/libchunkx
+ *
+ * and include this header rather than the libchunkx.h generated by cgo,
+ * which exposes Go's internal types.
+ *
+ * Strings
+ *   All strings are NUL-terminated UTF-8, except the code passed to
+ *   chunkx_chunk and chunkx_chunker_chunk, which is given with an explicit
+ *   length and may contain NUL bytes. Results are JSON; invalid UTF-8 in the
+ *   input is replaced with U+FFFD in the output. Options are a JSON object
+ *   with the keys of a chunkx.json config file, or NULL for the defaults.
+ *
+ * Ownership
+ *   The library never keeps pointers to its arguments past the call that
+ *   received them. Every string it returns, through a return value, *out, or
+ *   *err, is owned by the caller and must be released with chunkx_free
+ *   exactly once. Chunker handles must be released with chunkx_chunker_free.
+ *
+ * Threads
+ *   Every function may be called from any thread, and calls on the same
+ *   chunker handle run in parallel, so threads can share one handle.
+ *
+ * Errors
+ *   Handle functions return a chunkx_status. On failure, *out is left
+ *   untouched and, if err is not NULL, *err receives an error message.
+ *   Using a freed handle returns CHUNKX_ERROR_INVALID_ARGUMENT.
+ */
+#ifndef CHUNKX_H
+#define CHUNKX_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The version of this API, returned by chunkx_abi_version. */
+#define CHUNKX_ABI_VERSION 1
+
+/* Result codes of the handle functions. */
+typedef enum {
+	CHUNKX_OK = 0,
+	CHUNKX_ERROR_INVALID_ARGUMENT = 1, /* NULL pointer or unknown handle */
+	CHUNKX_ERROR_INVALID_OPTIONS = 2,  /* Malformed or unknown options */
+	CHUNKX_ERROR_IO = 3,               /* A file could not be read */
+	CHUNKX_ERROR_CHUNK = 4,            /* Chunking failed */
+} chunkx_status;
+
+/* An opaque reference to a chunker and its options. Zero is never valid. */
+typedef uintptr_t chunkx_chunker;
+
+#ifndef CHUNKX_NO_PROTOTYPES
+
+/* Returns the API version of the library, to compare with CHUNKX_ABI_VERSION. */
+int chunkx_abi_version(void);
+
+/* Creates a chunker with the given options and stores its handle in *out. */
+int chunkx_chunker_new(const char *options, chunkx_chunker *out, char **err);
+
+/*
+ * Chunks length bytes of code, line by line unless the options set a
+ * language, and stores a JSON array of chunks in *out.
+ */
+int chunkx_chunker_chunk(chunkx_chunker chunker, const char *code, size_t length, char **out, char **err);
+
+/*
+ * Chunks the file at path, detecting its language from the extension, and
+ * stores a JSON array of chunks in *out.
+ */
+int chunkx_chunker_chunk_file(chunkx_chunker chunker, const char *path, char **out, char **err);
+
+/* Releases a chunker. Freeing 0 or an already freed handle does nothing. */
+void chunkx_chunker_free(chunkx_chunker chunker);
+
+/*
+ * One-shot functions used by the Python and Node.js bindings. They return a
+ * JSON string, or NULL with a message in *err on failure.
+ */
+char *chunkx_chunk(const char *code, size_t length, const char *options, char **err);
+char *chunkx_chunk_file(const char *path, const char *options, char **err);
+char *chunkx_chunk_dir(const char *dir, const char *options, int workers, char **err);
+char *chunkx_load_config(const char *path, char **err);
+
+/* Releases a string returned by the library. Freeing NULL does nothing. */
+void chunkx_free(char *s);
+
+#endif /* CHUNKX_NO_PROTOTYPES */
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CHUNKX_H */
@@ -0,0 +1,120 @@
+package main
+
+import (
+	"errors"
+	"fmt"
+	"io/fs"
+	"sync"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+// abiVersion is the version of the C API in chunkx.h. It changes only when a
+// function is removed or its signature or ownership rules change.
+const abiVersion = 1
+
+// status is a result code of the handle API, matching chunkx_status in
+// chunkx.h.
+type status int
+
+const (
+	statusOK status = iota
+	statusInvalidArgument
+	statusInvalidOptions
+	statusIO
+	statusChunk
+)
+
+// errInvalidArgument reports a NULL pointer or a handle that is unknown or
+// already freed.
+var errInvalidArgument = errors.New("invalid argument")
+
+// statusOf returns the status code reporting err.
+func statusOf(err error) status {
+	var pathErr *fs.PathError
+	switch {
+	case err == nil:
+		return statusOK
+	case errors.Is(err, errInvalidArgument):
+		return statusInvalidArgument
+	case errors.Is(err, chunkx.ErrInvalidConfig):
+		return statusInvalidOptions
+	case errors.As(err, &pathErr):
+		return statusIO
+	default:
+		return statusChunk
+	}
+}
+
+// chunkerHandle is a chunker and its options, referenced from C by an opaque
+// handle. Chunkers are safe for concurrent use, so calls on the same handle
+// aren't serialized.
+type chunkerHandle struct {
+	chunker chunkx.Chunker
+	opts    []chunkx.Option
+}
+
+// handles maps the handles given out to C to their chunkers. Handles are
+// never reused, so a stale handle is reported instead of reaching another
+// chunker.
+var handles = struct {
+	sync.Mutex
+	next    uintptr
+	byValue map[uintptr]*chunkerHandle
+}{byValue: make(map[uintptr]*chunkerHandle)}
+
+// newHandle creates a chunker with the given JSON options and returns its
+// handle.
+func newHandle(options string) (uintptr, error) {
+	opts, err := parseOptions(options)
+	if err != nil {
+		return 0, err
+	}
+
+	handles.Lock()
+	defer handles.Unlock()
+	handles.next++
+	handles.byValue[handles.next] = &chunkerHandle{chunker: chunkx.NewChunker(), opts: opts}
+	return handles.next, nil
+}
+
+// lookupHandle returns the chunker referenced by h.
+func lookupHandle(h uintptr) (*chunkerHandle, error) {
+	handles.Lock()
+	defer handles.Unlock()
+	c, ok := handles.byValue[h]
+	if !ok {
+		return nil, fmt.Errorf("%w: unknown chunker handle %d", errInvalidArgument, h)
+	}
+	return c, nil
+}
+
+// freeHandle releases the chunker referenced by h. Unknown handles are
+// ignored.
+func freeHandle(h uintptr) {
+	handles.Lock()
+	defer handles.Unlock()
+	delete(handles.byValue, h)
+}
+
+// chunkText chunks code, line by line unless the options set a language.
+func (c *chunkerHandle) chunkText(code string) (string, error) {
+	// Options are applied in order, so an explicit language still wins
+	opts := append([]chunkx.Option{chunkx.WithLanguage(languages.Generic)}, c.opts...)
+	chunks, err := c.chunker.Chunk(code, opts...)
+	if err != nil {
+		return "", err
+	}
+	return marshal(newChunkRecords(chunks))
+}
+
+// chunkFile chunks the file at path, detecting its language from the
+// extension.
+func (c *chunkerHandle) chunkFile(path string) (string, error) {
+	chunks, err := c.chunker.ChunkFile(path, c.opts...)
+	if err != nil {
+		return "", err
+	}
+	return marshal(newChunkRecords(chunks))
+}
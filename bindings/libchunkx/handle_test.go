@@ -0,0 +1,81 @@
+package main
+
+import (
+	"encoding/json"
+	"errors"
+	"path/filepath"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+func TestHandle(t *testing.T) {
+	h, err := newHandle(`{"max_size": 2}`)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	c, err := lookupHandle(h)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	out, err := c.chunkText("one two\nthree four\n")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	var chunks []chunkRecord
+	if err := json.Unmarshal([]byte(out), &chunks); err != nil {
+		t.Fatalf("invalid JSON %q: %v", out, err)
+	}
+	if len(chunks) != 2 {
+		t.Errorf("expected 2 chunks, got %+v", chunks)
+	}
+
+	freeHandle(h)
+	if _, err := lookupHandle(h); statusOf(err) != statusInvalidArgument {
+		t.Errorf("expected a freed handle to be invalid, got %v", err)
+	}
+}
+
+func TestHandle_NotReused(t *testing.T) {
+	first, err := newHandle("")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	freeHandle(first)
+
+	second, err := newHandle("")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	defer freeHandle(second)
+	if second == first {
+		t.Errorf("handle %d was reused", first)
+	}
+}
+
+func TestStatusOf(t *testing.T) {
+	_, optionsErr := newHandle(`{"max_chunks": 2}`)
+	_, ioErr := (&chunkerHandle{chunker: chunkx.NewChunker()}).chunkFile(filepath.Join(t.TempDir(), "missing.go"))
+
+	tests := []struct {
+		name string
+		err  error
+		want status
+	}{
+		{"nil", nil, statusOK},
+		{"unknown handle", errInvalidArgument, statusInvalidArgument},
+		{"invalid options", optionsErr, statusInvalidOptions},
+		{"missing file", ioErr, statusIO},
+		{"other", errors.New("boom"), statusChunk},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := statusOf(tt.err); got != tt.want {
+				t.Errorf("statusOf(%v) = %d, want %d", tt.err, got, tt.want)
+			}
+		})
+	}
+}
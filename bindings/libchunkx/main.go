@@ -0,0 +1,167 @@
+// Command libchunkx builds chunkx as a C shared library for the Python and
+// other language bindings:
+//
+//	go build -buildmode=c-shared -o libchunkx.so ./bindings/libchunkx
+//
+// Every function takes NUL-terminated UTF-8 strings and returns a JSON
+// string on success. On failure it returns NULL and, if err is not NULL,
+// stores an error message in *err. Options are a JSON object with the keys of
+// a chunkx.json config file, or NULL for the defaults. Returned strings must
+// be released with chunkx_free.
+//
+// Services embedding the library should use the handle API declared in
+// chunkx.h, which keeps a configured chunker alive across calls and reports
+// failures with stable status codes. The header also documents ownership
+// and threading rules; include it instead of the libchunkx.h header
+// generated by cgo.
+package main
+
+/*
+#include <stdlib.h>
+
+// The prototypes in chunkx.h use const pointers and would conflict with the
+// declarations cgo generates for the exported functions
+#define CHUNKX_NO_PROTOTYPES
+#include "chunkx.h"
+*/
+import "C"
+
+import (
+	"fmt"
+	"unsafe"
+)
+
+func main() {}
+
+// export converts a result to a C string, storing the error in errOut.
+func export(out string, err error, errOut **C.char) *C.char {
+	if err != nil {
+		if errOut != nil {
+			*errOut = C.CString(err.Error())
+		}
+		return nil
+	}
+	return C.CString(out)
+}
+
+// chunkx_chunk chunks length bytes of code and returns a JSON array of
+// chunks. The code may contain NUL bytes.
+//
+//export chunkx_chunk
+func chunkx_chunk(code *C.char, length C.size_t, options *C.char, err **C.char) *C.char {
+	out, e := chunkText(C.GoStringN(code, C.int(length)), C.GoString(options))
+	return export(out, e, err)
+}
+
+// chunkx_chunk_file chunks the file at path and returns a JSON array of
+// chunks.
+//
+//export chunkx_chunk_file
+func chunkx_chunk_file(path, options *C.char, err **C.char) *C.char {
+	out, e := chunkFile(C.GoString(path), C.GoString(options))
+	return export(out, e, err)
+}
+
+// chunkx_chunk_dir chunks every file below dir with the given number of
+// workers, or one per CPU if it is not positive, and returns a JSON array of
+// {path, chunks, error} objects in walk order.
+//
+//export chunkx_chunk_dir
+func chunkx_chunk_dir(dir, options *C.char, workers C.int, err **C.char) *C.char {
+	out, e := chunkDir(C.GoString(dir), C.GoString(options), int(workers))
+	return export(out, e, err)
+}
+
+// chunkx_load_config reads a chunkx.toml, chunkx.yaml, or chunkx.json file
+// and returns its settings as a JSON options object.
+//
+//export chunkx_load_config
+func chunkx_load_config(path *C.char, err **C.char) *C.char {
+	out, e := loadConfig(C.GoString(path))
+	return export(out, e, err)
+}
+
+// chunkx_free releases a string returned by the library.
+//
+//export chunkx_free
+func chunkx_free(s *C.char) {
+	C.free(unsafe.Pointer(s))
+}
+
+// result stores the outcome of a handle function in out or errOut and
+// returns its status.
+func result(s string, err error, out, errOut **C.char) C.int {
+	if err == nil && out == nil {
+		err = fmt.Errorf("%w: out is NULL", errInvalidArgument)
+	}
+	if err != nil {
+		if errOut != nil {
+			*errOut = C.CString(err.Error())
+		}
+		return C.int(statusOf(err))
+	}
+	*out = C.CString(s)
+	return C.int(C.CHUNKX_OK)
+}
+
+// chunkx_abi_version returns the version of the C API.
+//
+//export chunkx_abi_version
+func chunkx_abi_version() C.int {
+	return abiVersion
+}
+
+// chunkx_chunker_new creates a chunker with the given options and stores its
+// handle in out.
+//
+//export chunkx_chunker_new
+func chunkx_chunker_new(options *C.char, out *C.chunkx_chunker, err **C.char) C.int {
+	if out == nil {
+		return result("", fmt.Errorf("%w: out is NULL", errInvalidArgument), nil, err)
+	}
+	h, e := newHandle(C.GoString(options))
+	if e != nil {
+		return result("", e, nil, err)
+	}
+	*out = C.chunkx_chunker(h)
+	return C.int(C.CHUNKX_OK)
+}
+
+// chunkx_chunker_chunk chunks length bytes of code with a chunker and stores
+// a JSON array of chunks in out.
+//
+//export chunkx_chunker_chunk
+func chunkx_chunker_chunk(chunker C.chunkx_chunker, code *C.char, length C.size_t, out, err **C.char) C.int {
+	if code == nil && length > 0 {
+		return result("", fmt.Errorf("%w: code is NULL", errInvalidArgument), out, err)
+	}
+	c, e := lookupHandle(uintptr(chunker))
+	if e != nil {
+		return result("", e, out, err)
+	}
+	s, e := c.chunkText(C.GoStringN(code, C.int(length)))
+	return result(s, e, out, err)
+}
+
+// chunkx_chunker_chunk_file chunks the file at path with a chunker and stores
+// a JSON array of chunks in out.
+//
+//export chunkx_chunker_chunk_file
+func chunkx_chunker_chunk_file(chunker C.chunkx_chunker, path *C.char, out, err **C.char) C.int {
+	if path == nil {
+		return result("", fmt.Errorf("%w: path is NULL", errInvalidArgument), out, err)
+	}
+	c, e := lookupHandle(uintptr(chunker))
+	if e != nil {
+		return result("", e, out, err)
+	}
+	s, e := c.chunkFile(C.GoString(path))
+	return result(s, e, out, err)
+}
+
+// chunkx_chunker_free releases a chunker.
+//
+//export chunkx_chunker_free
+func chunkx_chunker_free(chunker C.chunkx_chunker) {
+	freeHandle(uintptr(chunker))
+}
@@ -0,0 +1,59 @@
+/** Settings of a chunkx.toml file. Unset fields keep the library defaults. */
+export interface Options {
+  strategy?: 'ast' | 'lines';
+  maxSize?: number;
+  overlap?: number;
+  counter?: 'words' | 'bytes' | 'lines';
+  /** Language for every input, disabling detection. */
+  language?: string;
+  /** Glob patterns mapped to languages; the first match wins. */
+  languages?: Record<string, string>;
+  /** Glob patterns of paths to skip when walking directories. */
+  ignore?: string[];
+}
+
+export interface DirOptions extends Options {
+  /** Files chunked concurrently; defaults to one per CPU. */
+  workers?: number;
+}
+
+/** A chunk of a file, with 1-based line numbers and byte offsets. */
+export interface Chunk {
+  id: string;
+  content: string;
+  language: string;
+  startLine: number;
+  endLine: number;
+  startByte: number;
+  endByte: number;
+  nodeTypes: string[];
+  metadata: Record<string, string>;
+}
+
+/** The outcome of chunking one file of a directory. */
+export interface FileResult {
+  path: string;
+  chunks: Chunk[];
+  error: string | null;
+}
+
+/** Thrown when chunking fails or options are invalid. */
+export class ChunkxError extends Error {}
+
+/** Chunks text in memory, line by line unless a language is set. */
+export function chunkText(text: string | Buffer, options?: Options): Promise<Chunk[]>;
+export function chunkTextSync(text: string | Buffer, options?: Options): Chunk[];
+
+/** Chunks a file, detecting its language from the extension. */
+export function chunkFile(path: string, options?: Options): Promise<Chunk[]>;
+export function chunkFileSync(path: string, options?: Options): Chunk[];
+
+/** Chunks every file below a directory, in walk order. Failed files carry an error. */
+export function chunkDir(path: string, options?: DirOptions): Promise<FileResult[]>;
+export function chunkDirSync(path: string, options?: DirOptions): FileResult[];
+
+/** Reads a chunkx.toml, chunkx.yaml, or chunkx.json file. */
+export function loadConfig(path: string): Options;
+
+/** Reports whether the shared library can be loaded. */
+export function isAvailable(): boolean;
@@ -0,0 +1,207 @@
+'use strict';
+
+// Node.js bindings for the chunkx shared library, built from the Go sources
+// with:
+//
+//   go build -buildmode=c-shared -o bindings/node/libchunkx.so ./bindings/libchunkx
+//
+// The library is looked up next to this file, or at the path in the
+// CHUNKX_LIBRARY environment variable. Async functions run on the libuv
+// thread pool, so chunking doesn't block the event loop.
+
+const path = require('node:path');
+
+// ChunkxError is thrown when chunking fails or options are invalid.
+class ChunkxError extends Error {
+  constructor(message) {
+    super(message);
+    this.name = 'ChunkxError';
+  }
+}
+
+function libraryPath() {
+  if (process.env.CHUNKX_LIBRARY) {
+    return process.env.CHUNKX_LIBRARY;
+  }
+  const suffix = { darwin: '.dylib', win32: '.dll' }[process.platform] || '.so';
+  return path.join(__dirname, 'libchunkx' + suffix);
+}
+
+let lib;
+
+// load opens the library on first use, so requiring the module never fails.
+function load() {
+  if (lib) {
+    return lib;
+  }
+
+  const koffi = require('koffi');
+  const handle = koffi.load(libraryPath());
+
+  // Strings are returned as raw pointers so they can be released with
+  // chunkx_free after decoding
+  lib = {
+    koffi,
+    chunk: handle.func('void *chunkx_chunk(const void *code, size_t length, const char *options, _Out_ void **err)'),
+    chunkFile: handle.func('void *chunkx_chunk_file(const char *path, const char *options, _Out_ void **err)'),
+    chunkDir: handle.func('void *chunkx_chunk_dir(const char *dir, const char *options, int workers, _Out_ void **err)'),
+    loadConfig: handle.func('void *chunkx_load_config(const char *path, _Out_ void **err)'),
+    free: handle.func('void chunkx_free(void *s)'),
+  };
+  return lib;
+}
+
+// decode turns a result pointer into a value, or the error pointer into a
+// ChunkxError, releasing both.
+function decode(out, err) {
+  const { koffi, free } = load();
+  if (!out) {
+    const message = err[0] ? koffi.decode(err[0], 'char', -1) : 'unknown error';
+    if (err[0]) {
+      free(err[0]);
+    }
+    throw new ChunkxError(message);
+  }
+  try {
+    return JSON.parse(koffi.decode(out, 'char', -1));
+  } finally {
+    free(out);
+  }
+}
+
+// call invokes a library function synchronously.
+function call(name, ...args) {
+  const err = [null];
+  return decode(load()[name](...args, err), err);
+}
+
+// callAsync invokes a library function on the thread pool.
+function callAsync(name, ...args) {
+  return new Promise((resolve, reject) => {
+    let fn;
+    try {
+      fn = load()[name];
+    } catch (e) {
+      reject(e);
+      return;
+    }
+
+    const err = [null];
+    fn.async(...args, err, (e, out) => {
+      if (e) {
+        reject(e);
+        return;
+      }
+      try {
+        resolve(decode(out, err));
+      } catch (e) {
+        reject(e);
+      }
+    });
+  });
+}
+
+// Option keys are camelCase in JavaScript and snake_case in the library.
+const optionKeys = {
+  strategy: 'strategy',
+  maxSize: 'max_size',
+  overlap: 'overlap',
+  counter: 'counter',
+  language: 'language',
+  languages: 'languages',
+  ignore: 'ignore',
+};
+
+function encodeOptions(options = {}) {
+  const encoded = {};
+  for (const [key, value] of Object.entries(options)) {
+    if (key === 'workers' || value === undefined) {
+      continue;
+    }
+    if (!(key in optionKeys)) {
+      throw new ChunkxError(`unknown option ${key}`);
+    }
+    encoded[optionKeys[key]] = value;
+  }
+  return JSON.stringify(encoded);
+}
+
+function toChunk(r) {
+  return {
+    id: r.id,
+    content: r.content,
+    language: r.language,
+    startLine: r.start_line,
+    endLine: r.end_line,
+    startByte: r.start_byte,
+    endByte: r.end_byte,
+    nodeTypes: r.node_types || [],
+    metadata: r.metadata || {},
+  };
+}
+
+function toFileResult(f) {
+  return { path: f.path, chunks: f.chunks.map(toChunk), error: f.error || null };
+}
+
+function textArgs(text, options) {
+  const data = Buffer.isBuffer(text) ? text : Buffer.from(text, 'utf8');
+  return [data, data.length, encodeOptions(options)];
+}
+
+function chunkTextSync(text, options) {
+  return call('chunk', ...textArgs(text, options)).map(toChunk);
+}
+
+async function chunkText(text, options) {
+  return (await callAsync('chunk', ...textArgs(text, options))).map(toChunk);
+}
+
+function chunkFileSync(file, options) {
+  return call('chunkFile', file, encodeOptions(options)).map(toChunk);
+}
+
+async function chunkFile(file, options) {
+  return (await callAsync('chunkFile', file, encodeOptions(options))).map(toChunk);
+}
+
+function chunkDirSync(dir, options = {}) {
+  return call('chunkDir', dir, encodeOptions(options), options.workers || 0).map(toFileResult);
+}
+
+async function chunkDir(dir, options = {}) {
+  return (await callAsync('chunkDir', dir, encodeOptions(options), options.workers || 0)).map(toFileResult);
+}
+
+function loadConfig(file) {
+  const raw = call('loadConfig', file);
+  const config = {};
+  for (const [key, name] of Object.entries(optionKeys)) {
+    if (name in raw) {
+      config[key] = raw[name];
+    }
+  }
+  return config;
+}
+
+// isAvailable reports whether the library can be loaded.
+function isAvailable() {
+  try {
+    load();
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+module.exports = {
+  ChunkxError,
+  chunkText,
+  chunkTextSync,
+  chunkFile,
+  chunkFileSync,
+  chunkDir,
+  chunkDirSync,
+  loadConfig,
+  isAvailable,
+};
@@ -0,0 +1,47 @@
+'use strict';
+
+const assert = require('node:assert/strict');
+const fs = require('node:fs');
+const os = require('node:os');
+const path = require('node:path');
+const { test } = require('node:test');
+
+const chunkx = require('..');
+
+const skip = chunkx.isAvailable() ? false : 'libchunkx is not built; see index.js';
+
+test('chunkTextSync', { skip }, () => {
+  const chunks = chunkx.chunkTextSync('one two\nthree four\n', { maxSize: 2 });
+  assert.deepEqual(chunks.map((c) => c.content), ['one two\n', 'three four\n']);
+  assert.equal(chunks[1].startLine, 2);
+});
+
+test('chunkText', { skip }, async () => {
+  const chunks = await chunkx.chunkText(Buffer.from('package main\n'), { language: 'go' });
+  assert.equal(chunks[0].language, 'go');
+});
+
+test('invalid options', { skip }, async () => {
+  assert.throws(() => chunkx.chunkTextSync('x', { counter: 'syllables' }), chunkx.ChunkxError);
+  await assert.rejects(chunkx.chunkFile('/does/not/exist.go'), chunkx.ChunkxError);
+});
+
+test('unknown option', () => {
+  assert.throws(() => chunkx.chunkTextSync('x', { maxChunks: 2 }), /unknown option maxChunks/);
+});
+
+test('chunkDir', { skip }, async () => {
+  const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'chunkx-'));
+  try {
+    fs.mkdirSync(path.join(dir, 'vendor'));
+    fs.writeFileSync(path.join(dir, 'a.go'), 'package a\n');
+    fs.writeFileSync(path.join(dir, 'vendor', 'b.go'), 'package b\n');
+
+    const results = await chunkx.chunkDir(dir, { ignore: ['vendor/**'], workers: 2 });
+    assert.deepEqual(results.map((r) => path.relative(dir, r.path)), ['a.go']);
+    assert.equal(results[0].error, null);
+    assert.deepEqual(chunkx.chunkDirSync(dir, { ignore: ['vendor/**'] }), results);
+  } finally {
+    fs.rmSync(dir, { recursive: true });
+  }
+});
@@ -0,0 +1,197 @@
+"""Syntax-aware code chunking for RAG pipelines.
+
+This package wraps the chunkx shared library, built from the Go sources with::
+
+    go build -buildmode=c-shared -o bindings/python/chunkx/libchunkx.so ./bindings/libchunkx
+
+The library is looked up next to this file, or at the path in the
+``CHUNKX_LIBRARY`` environment variable. Calls release the GIL while
+chunking, so several threads can chunk in parallel.
+"""
+
+from __future__ import annotations
+
+import ctypes
+import json
+import os
+import sys
+from dataclasses import asdict, dataclass, field
+from pathlib import Path
+from typing import Dict, List, Optional, Union
+
+__all__ = [
+    "Chunk",
+    "Chunker",
+    "ChunkxError",
+    "Config",
+    "FileResult",
+    "chunk",
+    "chunk_dir",
+    "chunk_file",
+    "load_config",
+]
+
+
+class ChunkxError(Exception):
+    """Raised when chunking fails or options are invalid."""
+
+
+@dataclass(frozen=True)
+class Chunk:
+    """A chunk of a file, with 1-based line numbers and byte offsets."""
+
+    id: str
+    content: str
+    language: str
+    start_line: int
+    end_line: int
+    start_byte: int
+    end_byte: int
+    node_types: List[str] = field(default_factory=list)
+    metadata: Dict[str, str] = field(default_factory=dict)
+
+
+@dataclass(frozen=True)
+class FileResult:
+    """The outcome of chunking one file of a directory."""
+
+    path: str
+    chunks: List[Chunk]
+    error: Optional[str] = None
+
+
+@dataclass
+class Config:
+    """Chunking settings, with the keys of a chunkx.toml file.
+
+    Unset fields keep the library defaults.
+    """
+
+    strategy: Optional[str] = None
+    max_size: Optional[int] = None
+    overlap: Optional[float] = None
+    counter: Optional[str] = None
+    language: Optional[str] = None
+    languages: Dict[str, str] = field(default_factory=dict)
+    ignore: List[str] = field(default_factory=list)
+
+    def to_json(self) -> str:
+        """Encode the settings as a JSON options object."""
+        return json.dumps({k: v for k, v in asdict(self).items() if v not in (None, {}, [])})
+
+
+def _library_path() -> str:
+    explicit = os.environ.get("CHUNKX_LIBRARY")
+    if explicit:
+        return explicit
+
+    suffix = {"darwin": ".dylib", "win32": ".dll"}.get(sys.platform, ".so")
+    return str(Path(__file__).with_name("libchunkx" + suffix))
+
+
+def _load_library() -> ctypes.CDLL:
+    # CDLL, unlike PyDLL, releases the GIL for the duration of every call
+    lib = ctypes.CDLL(_library_path())
+
+    err = ctypes.POINTER(ctypes.c_char_p)
+    signatures = {
+        "chunkx_chunk": [ctypes.c_char_p, ctypes.c_size_t, ctypes.c_char_p, err],
+        "chunkx_chunk_file": [ctypes.c_char_p, ctypes.c_char_p, err],
+        "chunkx_chunk_dir": [ctypes.c_char_p, ctypes.c_char_p, ctypes.c_int, err],
+        "chunkx_load_config": [ctypes.c_char_p, err],
+    }
+    for name, argtypes in signatures.items():
+        fn = getattr(lib, name)
+        fn.argtypes = argtypes
+        # A void pointer, so ctypes doesn't copy and lose the original
+        fn.restype = ctypes.c_void_p
+
+    lib.chunkx_free.argtypes = [ctypes.c_void_p]
+    lib.chunkx_free.restype = None
+    return lib
+
+
+_lib: Optional[ctypes.CDLL] = None
+
+
+def _call(name: str, *args) -> object:
+    global _lib
+    if _lib is None:
+        _lib = _load_library()
+
+    err = ctypes.c_char_p()
+    out = getattr(_lib, name)(*args, ctypes.byref(err))
+    if not out:
+        message = err.value.decode("utf-8", "replace") if err.value else "unknown error"
+        if err.value:
+            _lib.chunkx_free(ctypes.cast(err, ctypes.c_void_p))
+        raise ChunkxError(message)
+
+    try:
+        return json.loads(ctypes.string_at(out))
+    finally:
+        _lib.chunkx_free(out)
+
+
+def _chunks(records: list) -> List[Chunk]:
+    return [Chunk(**{**r, "node_types": r.get("node_types") or [], "metadata": r.get("metadata") or {}}) for r in records]
+
+
+def _encode_path(path: Union[str, os.PathLike]) -> bytes:
+    return os.fsencode(path)
+
+
+class Chunker:
+    """Chunks text, files, and directories with fixed settings.
+
+    Settings come from a Config, keyword arguments, or both; keyword
+    arguments win::
+
+        chunker = Chunker(load_config("chunkx.toml"), max_size=300)
+        for chunk in chunker.chunk_file("main.go"):
+            print(chunk.start_line, chunk.end_line)
+    """
+
+    def __init__(self, config: Optional[Config] = None, **options) -> None:
+        settings = asdict(config) if config else {}
+        settings.update(options)
+        self.config = Config(**settings)
+        self._options = self.config.to_json().encode()
+
+    def chunk(self, text: Union[str, bytes]) -> List[Chunk]:
+        """Chunk text in memory, line by line unless a language is set."""
+        data = text.encode("utf-8") if isinstance(text, str) else bytes(text)
+        return _chunks(_call("chunkx_chunk", data, len(data), self._options))
+
+    def chunk_file(self, path: Union[str, os.PathLike]) -> List[Chunk]:
+        """Chunk a file, detecting its language from the extension."""
+        return _chunks(_call("chunkx_chunk_file", _encode_path(path), self._options))
+
+    def chunk_dir(self, path: Union[str, os.PathLike], workers: int = 0) -> List[FileResult]:
+        """Chunk every file below a directory concurrently.
+
+        Files are returned in walk order. Files that fail to chunk are
+        reported with an error instead of raising.
+        """
+        files = _call("chunkx_chunk_dir", _encode_path(path), self._options, workers)
+        return [FileResult(path=f["path"], chunks=_chunks(f["chunks"]), error=f.get("error")) for f in files]
+
+
+def load_config(path: Union[str, os.PathLike]) -> Config:
+    """Read a chunkx.toml, chunkx.yaml, or chunkx.json file."""
+    return Config(**_call("chunkx_load_config", _encode_path(path)))
+
+
+def chunk(text: Union[str, bytes], **options) -> List[Chunk]:
+    """Chunk text with the given settings; see Config for the keys."""
+    return Chunker(**options).chunk(text)
+
+
+def chunk_file(path: Union[str, os.PathLike], **options) -> List[Chunk]:
+    """Chunk a file with the given settings; see Config for the keys."""
+    return Chunker(**options).chunk_file(path)
+
+
+def chunk_dir(path: Union[str, os.PathLike], workers: int = 0, **options) -> List[FileResult]:
+    """Chunk a directory with the given settings; see Config for the keys."""
+    return Chunker(**options).chunk_dir(path, workers)
@@ -0,0 +1,53 @@
+import os
+import tempfile
+import unittest
+from pathlib import Path
+
+import chunkx
+
+try:
+    chunkx._load_library()
+except OSError:
+    available = False
+else:
+    available = True
+
+
+@unittest.skipUnless(available, "libchunkx is not built; see the package docstring")
+class ChunkxTest(unittest.TestCase):
+    def test_chunk(self):
+        chunks = chunkx.chunk("one two\nthree four\n", max_size=2)
+        self.assertEqual([c.content for c in chunks], ["one two\n", "three four\n"])
+        self.assertEqual(chunks[1].start_line, 2)
+        self.assertEqual(chunks[0].language, "generic")
+
+    def test_chunk_bytes_with_language(self):
+        chunks = chunkx.chunk(b"package main\n\nfunc main() {}\n", language="go")
+        self.assertEqual(chunks[0].language, "go")
+
+    def test_invalid_options(self):
+        with self.assertRaises(chunkx.ChunkxError):
+            chunkx.chunk("x", counter="syllables")
+
+    def test_chunk_dir_and_config(self):
+        with tempfile.TemporaryDirectory() as tmp:
+            root = Path(tmp)
+            (root / "vendor").mkdir()
+            (root / "a.go").write_text("package a\n")
+            (root / "vendor" / "b.go").write_text("package b\n")
+            (root / "chunkx.toml").write_text('max_size = 100\nignore = ["vendor/**", "*.toml"]\n')
+
+            config = chunkx.load_config(root / "chunkx.toml")
+            self.assertEqual(config.max_size, 100)
+
+            results = chunkx.Chunker(config).chunk_dir(root)
+            self.assertEqual([os.path.relpath(r.path, tmp) for r in results], ["a.go"])
+            self.assertIsNone(results[0].error)
+
+    def test_missing_file(self):
+        with self.assertRaises(chunkx.ChunkxError):
+            chunkx.chunk_file("/does/not/exist.go")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,115 @@
+//go:build js && wasm
+
+// Command wasm builds chunkx as a WebAssembly module for browsers and edge
+// runtimes, so documents can be chunked without leaving the client:
+//
+//	GOOS=js GOARCH=wasm go build -o chunkx.wasm ./bindings/wasm
+//
+// Tree-sitter needs cgo, which WebAssembly builds don't have, so every
+// language is chunked line by line. Once loaded with wasm_exec.js, the module
+// defines a global chunkx object:
+//
+//	chunkx.chunkText(text, {max_size: 200, counter: "words"}) // [{id, content, ...}]
+//	chunkx.detectLanguage("main.go")                           // "go"
+//
+// Options take the keys of a chunkx.json config file. Errors are thrown as
+// JavaScript exceptions.
+package main
+
+import (
+	"encoding/json"
+	"syscall/js"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+// chunkRecord is the JSON form of a chunk returned to JavaScript.
+type chunkRecord struct {
+	ID        string            `json:"id"`
+	Language  string            `json:"language"`
+	StartLine int               `json:"startLine"`
+	EndLine   int               `json:"endLine"`
+	StartByte int               `json:"startByte"`
+	EndByte   int               `json:"endByte"`
+	Metadata  map[string]string `json:"metadata,omitempty"`
+	Content   string            `json:"content"`
+}
+
+func main() {
+	chunker := chunkx.NewChunker()
+
+	js.Global().Set("chunkx", js.ValueOf(map[string]any{
+		"chunkText": js.FuncOf(func(_ js.Value, args []js.Value) any {
+			if len(args) == 0 {
+				throw("chunkText: missing text")
+			}
+			return chunkText(chunker, args[0].String(), options(args[1:]))
+		}),
+		"detectLanguage": js.FuncOf(func(_ js.Value, args []js.Value) any {
+			if len(args) == 0 {
+				throw("detectLanguage: missing path")
+			}
+			lang, _ := languages.DetectLanguage(args[0].String())
+			return string(lang.Name)
+		}),
+	}))
+
+	// Keep the exported functions alive
+	select {}
+}
+
+// options returns the JSON encoding of the options argument, if any.
+func options(args []js.Value) string {
+	if len(args) == 0 || args[0].IsUndefined() || args[0].IsNull() {
+		return ""
+	}
+	return js.Global().Get("JSON").Call("stringify", args[0]).String()
+}
+
+// chunkText chunks text and returns the chunks as JavaScript objects.
+func chunkText(chunker chunkx.Chunker, text, options string) any {
+	var opts []chunkx.Option
+	if options != "" {
+		cfg, err := chunkx.ParseConfig([]byte(options), "json")
+		if err != nil {
+			throw(err.Error())
+		}
+		if opts, err = cfg.Options(); err != nil {
+			throw(err.Error())
+		}
+	}
+
+	// Options are applied in order, so an explicit language still wins
+	opts = append([]chunkx.Option{chunkx.WithLanguage(languages.Generic)}, opts...)
+	chunks, err := chunker.Chunk(text, opts...)
+	if err != nil {
+		throw(err.Error())
+	}
+
+	records := make([]chunkRecord, len(chunks))
+	for i, chunk := range chunks {
+		records[i] = chunkRecord{
+			ID:        chunk.ID(),
+			Language:  chunk.Language.String(),
+			StartLine: chunk.StartLine,
+			EndLine:   chunk.EndLine,
+			StartByte: chunk.StartByte,
+			EndByte:   chunk.EndByte,
+			Metadata:  chunk.Metadata,
+			Content:   chunk.Content,
+		}
+	}
+
+	data, err := json.Marshal(records)
+	if err != nil {
+		throw(err.Error())
+	}
+	return js.Global().Get("JSON").Call("parse", string(data))
+}
+
+// throw raises a JavaScript Error. Panicking with a js.Value from a function
+// created by js.FuncOf throws the value in the calling JavaScript code.
+func throw(message string) {
+	panic(js.Global().Get("Error").New(message))
+}
@@ -0,0 +1,777 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+	"unicode"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Metadata keys set on chunks of build files and of other code split by its
+// blocks rather than by a syntax tree.
+const (
+	MetadataTarget     = "target"     // Targets of a Make rule, or the CMake or Bazel target a block declares
+	MetadataDefinition = "definition" // Name of the function or macro a block defines
+	MetadataBlock      = "block"      // Block holding the chunk, such as a Gradle block, an F# module, a Verilog module, a section of a Maven POM, or an nginx, Apache, or Caddy block, such as "location /api/", or "load" for Starlark loads
+	MetadataRule       = "rule"       // Rule or macro a Starlark block calls, such as "cc_library"
+	MetadataAttribute  = "attribute"  // Attribute path a Nix binding defines, such as "packages.default"
+)
+
+var (
+	// makeRule matches the targets of a Make rule, leaving out variable
+	// assignments such as "CC := gcc".
+	makeRule = regexp.MustCompile(`^([^\s:=#][^:=#]*?)[ \t]*::?(?:[^=]|$)`)
+	// makeSpecialTarget matches the built-in targets of Make, such as .PHONY,
+	// which declare other targets rather than build one.
+	makeSpecialTarget = regexp.MustCompile(`^\.[A-Z_]+$`)
+	// cmakeCommand matches the name of a CMake command and its opening
+	// parenthesis.
+	cmakeCommand = regexp.MustCompile(`^[ \t]*([A-Za-z_][A-Za-z0-9_]*)[ \t]*\(`)
+	// gradleBlock matches a line opening a block of a Gradle build script,
+	// such as "dependencies {" or `tasks.register("bench") {`, and the head
+	// naming it.
+	gradleBlock = regexp.MustCompile(`^[ \t]*([A-Za-z_][^{}=\n]*?)[ \t]*\{[ \t]*(?://.*)?$`)
+	// starlarkStatement matches a top-level function definition or call of a
+	// Starlark file, such as "def _impl(ctx):" or "cc_library(", and the name
+	// it defines or calls.
+	starlarkStatement = regexp.MustCompile(`^(?:def[ \t]+([A-Za-z_]\w*)|([A-Za-z_][\w.]*))[ \t]*\(`)
+	// starlarkName matches the name argument of a Starlark call.
+	starlarkName = regexp.MustCompile(`[(,\s]name[ \t]*=[ \t]*["']([^"'\n]+)["']`)
+	// nixBinding matches a line binding a Nix attribute path, such as
+	// "packages.default =", leaving out comparisons.
+	nixBinding = regexp.MustCompile(`^[ \t]*([A-Za-z_][\w'-]*(?:\.(?:[A-Za-z_][\w'-]*|"[^"\n]*"))*)[ \t]*=(?:[^=]|$)`)
+	// nixLambda matches the start of a Nix function, such as "x:" or
+	// "{ lib, ... }:".
+	nixLambda = regexp.MustCompile(`^(?:[A-Za-z_][\w'-]*|\{[^{}]*\}(?:[ \t]*@[ \t]*[A-Za-z_][\w'-]*)?)[ \t]*:(?:\s|$)`)
+)
+
+// cmakeTargetCommands are the CMake commands that add a target, which the
+// commands right after them naming the same target are chunked with.
+var cmakeTargetCommands = map[string]bool{
+	"add_executable":    true,
+	"add_library":       true,
+	"add_custom_target": true,
+}
+
+// blockSplitter finds the blocks of a language without a grammar, which its
+// code is chunked by rather than by line.
+type blockSplitter struct {
+	blocks func(code string) []textSpan
+	nested func(block string) []textSpan // Blocks of a block too large for a chunk, if any
+}
+
+var blockSplitters = map[languages.LanguageName]blockSplitter{
+	languages.Ada:        {blocks: adaSpans, nested: adaNested},
+	languages.Apache:     {blocks: apacheSpans, nested: apacheNested},
+	languages.Apex:       {blocks: apexSpans, nested: apexNested},
+	languages.Assembly:   {blocks: asmSpans},
+	languages.BibTeX:     {blocks: bibSpans},
+	languages.Caddy:      {blocks: confSpans, nested: confNested},
+	languages.Clojure:    {blocks: lispSpans, nested: lispNested},
+	languages.CMake:      {blocks: cmakeSpans},
+	languages.COBOL:      {blocks: cobolSpans, nested: cobolNested},
+	languages.DotEnv:     {blocks: envSpans},
+	languages.EmacsLisp:  {blocks: lispSpans, nested: lispNested},
+	languages.Erlang:     {blocks: erlangSpans},
+	languages.Fortran:    {blocks: fortranSpans, nested: fortranNested},
+	languages.FSharp:     {blocks: fsharpSpans, nested: fsharpNested},
+	languages.Gradle:     {blocks: gradleSpans, nested: gradleNested},
+	languages.HL7:        {blocks: hl7Spans},
+	languages.INI:        {blocks: iniSpans, nested: iniNested},
+	languages.Jenkins:    {blocks: jenkinsSpans, nested: jenkinsNested},
+	languages.Linker:     {blocks: linkerSpans, nested: linkerNested},
+	languages.Make:       {blocks: makeSpans},
+	languages.MATLAB:     {blocks: matlabSpans},
+	languages.Maven:      {blocks: mavenSpans, nested: xmlNested},
+	languages.NDJSON:     {blocks: ndjsonSpans},
+	languages.Nginx:      {blocks: confSpans, nested: confNested},
+	languages.Nix:        {blocks: nixSpans, nested: nixNested},
+	languages.ObjectiveC: {blocks: objcSpans, nested: objcNested},
+	languages.Pascal:     {blocks: pascalSpans, nested: pascalNested},
+	languages.Perl:       {blocks: perlSpans},
+	languages.PowerShell: {blocks: psSpans},
+	languages.Properties: {blocks: propertiesSpans},
+	languages.RIS:        {blocks: risSpans},
+	languages.Robots:     {blocks: robotsSpans},
+	languages.Salesforce: {blocks: salesforceSpans, nested: xmlNested},
+	languages.Scheme:     {blocks: lispSpans, nested: lispNested},
+	languages.Starlark:   {blocks: starlarkSpans},
+	languages.VBNet:      {blocks: vbSpans, nested: vbNested},
+	languages.Verilog:    {blocks: verilogSpans, nested: verilogNested},
+	languages.VHDL:       {blocks: vhdlSpans, nested: vhdlNested},
+}
+
+// makeSpans returns the rules of the Makefile code, each with its recipe and
+// the comment lines right above it, recording MetadataTarget.
+func makeSpans(code string) []textSpan {
+	var spans []textSpan
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		m := makeRule.FindStringSubmatch(line)
+		if m == nil || line[0] == '\t' || makeSpecialTarget.MatchString(strings.Fields(m[1])[0]) {
+			offset = next
+			continue
+		}
+
+		// The rule runs over its continued lines and recipe, and over the
+		// blank and comment lines between recipe lines
+		end := continuedLine(code, offset)
+		for scan := end; scan < len(code); {
+			line, next := codeLine(code, scan)
+			switch {
+			case strings.HasPrefix(line, "\t"):
+				end = continuedLine(code, scan)
+				scan = end
+				continue
+			case strings.TrimSpace(line) == "" || strings.HasPrefix(line, "#"):
+				scan = next
+				continue
+			}
+			break
+		}
+
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans)),
+			end:      end,
+			metadata: map[string]string{MetadataTarget: strings.Join(strings.Fields(m[1]), " ")},
+		})
+		offset = end
+	}
+	return spans
+}
+
+// cmakeCall is a command of a CMake file, from the start of its line to the
+// end of the line it ends on.
+type cmakeCall struct {
+	name       string // Lower-case name of the command
+	arg        string // First argument
+	start, end int
+}
+
+// cmakeSpans returns the function and macro definitions of the CMake file
+// code, recording MetadataDefinition, and the commands adding a target with
+// the commands right after them configuring it, recording MetadataTarget.
+// Blocks take the comment lines right above them.
+func cmakeSpans(code string) []textSpan {
+	calls := cmakeCalls(code)
+	var spans []textSpan
+	for i := 0; i < len(calls); i++ {
+		call := calls[i]
+		var metadata map[string]string
+		last := i
+		switch {
+		case call.name == "function" || call.name == "macro":
+			depth := 0
+			for last = i + 1; last < len(calls); last++ {
+				if calls[last].name == call.name {
+					depth++
+				} else if calls[last].name == "end"+call.name {
+					if depth == 0 {
+						break
+					}
+					depth--
+				}
+			}
+			if last == len(calls) {
+				continue
+			}
+			metadata = map[string]string{MetadataDefinition: call.arg}
+		case cmakeTargetCommands[call.name] && call.arg != "":
+			for last+1 < len(calls) && calls[last+1].arg == call.arg && onlyComments(code[calls[last].end:calls[last+1].start]) {
+				last++
+			}
+			metadata = map[string]string{MetadataTarget: call.arg}
+		default:
+			continue
+		}
+
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, call.start, spanEnd(spans)),
+			end:      calls[last].end,
+			metadata: metadata,
+		})
+		i = last
+	}
+	return spans
+}
+
+// cmakeCalls returns the commands of the CMake file code that start lines.
+func cmakeCalls(code string) []cmakeCall {
+	var calls []cmakeCall
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		m := cmakeCommand.FindStringSubmatchIndex(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+
+		open := offset + m[1] - 1
+		closing := cmakeClosing(code, open)
+		_, end := codeLine(code, closing)
+		args := strings.Fields(code[open+1 : max(closing-1, open+1)])
+		call := cmakeCall{name: strings.ToLower(line[m[2]:m[3]]), start: offset, end: end}
+		if len(args) > 0 {
+			call.arg = strings.Trim(args[0], `"`)
+		}
+		calls = append(calls, call)
+		offset = end
+	}
+	return calls
+}
+
+// cmakeClosing returns the offset after the parenthesis closing the one at
+// open in code, skipping quoted arguments and comments, or len(code) if it
+// isn't closed.
+func cmakeClosing(code string, open int) int {
+	depth := 0
+	for i := open; i < len(code); i++ {
+		switch code[i] {
+		case '(':
+			depth++
+		case ')':
+			if depth--; depth == 0 {
+				return i + 1
+			}
+		case '"':
+			for i++; i < len(code) && code[i] != '"'; i++ {
+				if code[i] == '\\' {
+					i++
+				}
+			}
+		case '#':
+			for i < len(code) && code[i] != '\n' {
+				i++
+			}
+		}
+	}
+	return len(code)
+}
+
+// gradleSpans returns the blocks of the Gradle build script code opened on
+// lines of their own, each with the comment lines right above it, recording
+// MetadataBlock.
+func gradleSpans(code string) []textSpan {
+	return gradleBlocks(code, 0)
+}
+
+// gradleNested returns the blocks within the Gradle block, after the line
+// opening it.
+func gradleNested(block string) []textSpan {
+	for offset := 0; offset < len(block); {
+		line, next := codeLine(block, offset)
+		if gradleBlock.MatchString(line) {
+			return gradleBlocks(block, next)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// gradleBlocks returns the blocks of the Gradle build script code opened on
+// the lines from offset on.
+func gradleBlocks(code string, offset int) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := gradleBlock.FindStringSubmatchIndex(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+
+		closing := braceClosing(code, offset+m[3]+strings.IndexByte(line[m[3]:], '{'))
+		_, end := codeLine(code, closing)
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "//", "/*", "*"),
+			end:      end,
+			metadata: map[string]string{MetadataBlock: strings.Join(strings.Fields(line[m[2]:m[3]]), " ")},
+		})
+		offset = end
+	}
+	return spans
+}
+
+// braceClosing returns the offset after the brace closing the one at open in
+// code, skipping strings and comments, or len(code) if it isn't closed.
+func braceClosing(code string, open int) int {
+	depth := 0
+	for i := open; i < len(code); i++ {
+		switch rest := code[i:]; {
+		case code[i] == '{':
+			depth++
+		case code[i] == '}':
+			if depth--; depth == 0 {
+				return i + 1
+			}
+		case strings.HasPrefix(rest, `"""`):
+			if end := strings.Index(rest[3:], `"""`); end >= 0 {
+				i += end + 5
+			} else {
+				i = len(code)
+			}
+		case code[i] == '"' || code[i] == '\'':
+			for quote := code[i]; i+1 < len(code) && code[i+1] != quote && code[i+1] != '\n'; i++ {
+				if code[i+1] == '\\' {
+					i++
+				}
+			}
+			i++
+		case strings.HasPrefix(rest, "//"):
+			for i < len(code) && code[i] != '\n' {
+				i++
+			}
+		case strings.HasPrefix(rest, "/*"):
+			if end := strings.Index(rest[2:], "*/"); end >= 0 {
+				i += end + 3
+			} else {
+				i = len(code)
+			}
+		}
+	}
+	return len(code)
+}
+
+// xmlElement is an element of an XML document.
+type xmlElement struct {
+	name           string
+	start, end     int // From its start tag to after its end tag
+	content, close int // From after its start tag to its end tag
+}
+
+// mavenSpans returns the sections of the Maven POM code holding other
+// elements, such as dependencies or build, each with the lines it is on,
+// recording MetadataBlock.
+func mavenSpans(code string) []textSpan {
+	for _, project := range xmlElements(code, 0, len(code)) {
+		if project.name == "project" {
+			return xmlSpans(code, project, nil)
+		}
+	}
+	return nil
+}
+
+// xmlNested returns the elements within the section of a Maven POM or of
+// Salesforce metadata that hold other elements, such as each dependency of
+// the dependencies section, recording the section as MetadataBlock so those
+// that fit are chunked together.
+func xmlNested(block string) []textSpan {
+	roots := xmlElements(block, 0, len(block))
+	if len(roots) == 0 {
+		return nil
+	}
+	return xmlSpans(block, roots[0], map[string]string{MetadataBlock: roots[0].name})
+}
+
+// xmlSpans returns the elements within the element parent of code that hold
+// other elements, with metadata, or with MetadataBlock set to their name if
+// metadata is nil.
+func xmlSpans(code string, parent xmlElement, metadata map[string]string) []textSpan {
+	var spans []textSpan
+	for _, child := range xmlElements(code, parent.content, parent.close) {
+		if len(xmlElements(code, child.content, child.close)) == 0 {
+			continue
+		}
+
+		span := textSpan{start: child.start, end: child.end, metadata: metadata}
+		if metadata == nil {
+			span.metadata = map[string]string{MetadataBlock: child.name}
+		}
+		if lineStart := strings.LastIndexByte(code[:span.start], '\n') + 1; strings.TrimSpace(code[lineStart:span.start]) == "" {
+			span.start = leadingComments(code, lineStart, max(spanEnd(spans), parent.content), "<!--")
+		}
+		if line, next := codeLine(code, span.end); strings.TrimSpace(line) == "" {
+			span.end = next
+		}
+		spans = append(spans, span)
+	}
+	return spans
+}
+
+// xmlElements returns the elements of the XML document code from start to
+// end that aren't within another, skipping comments, CDATA sections,
+// processing instructions, and declarations.
+func xmlElements(code string, start, end int) []xmlElement {
+	var elements []xmlElement
+	var open xmlElement
+	depth := 0
+	for i := start; i < end; i++ {
+		if code[i] != '<' {
+			continue
+		}
+		rest := code[i:end]
+		switch {
+		case strings.HasPrefix(rest, "<!--"):
+			i = skipPast(code, i, end, "-->") - 1
+		case strings.HasPrefix(rest, "<![CDATA["):
+			i = skipPast(code, i, end, "]]>") - 1
+		case strings.HasPrefix(rest, "<?"), strings.HasPrefix(rest, "<!"):
+			i = skipPast(code, i, end, ">") - 1
+		case strings.HasPrefix(rest, "</"):
+			tagEnd := skipPast(code, i, end, ">")
+			if depth > 0 {
+				if depth--; depth == 0 {
+					open.close, open.end = i, tagEnd
+					elements = append(elements, open)
+				}
+			}
+			i = tagEnd - 1
+		default:
+			tagEnd := xmlTagEnd(code, i, end)
+			if depth == 0 {
+				name := strings.FieldsFunc(code[i+1:tagEnd], func(r rune) bool {
+					return r == '>' || r == '/' || unicode.IsSpace(r)
+				})
+				open = xmlElement{start: i, end: tagEnd, content: tagEnd, close: tagEnd}
+				if len(name) > 0 {
+					open.name = name[0]
+				}
+			}
+			if strings.HasSuffix(code[i:tagEnd], "/>") {
+				if depth == 0 {
+					elements = append(elements, open)
+				}
+			} else {
+				depth++
+			}
+			i = tagEnd - 1
+		}
+	}
+	return elements
+}
+
+// xmlTagEnd returns the offset after the tag at offset in code, skipping
+// quoted attribute values, or end if it isn't closed before.
+func xmlTagEnd(code string, offset, end int) int {
+	var quote byte
+	for i := offset + 1; i < end; i++ {
+		switch {
+		case quote != 0:
+			if code[i] == quote {
+				quote = 0
+			}
+		case code[i] == '"' || code[i] == '\'':
+			quote = code[i]
+		case code[i] == '>':
+			return i + 1
+		}
+	}
+	return end
+}
+
+// skipPast returns the offset after the first marker in code from offset to
+// end, or end if there is none.
+func skipPast(code string, offset, end int, marker string) int {
+	if i := strings.Index(code[offset:end], marker); i >= 0 {
+		return offset + i + len(marker)
+	}
+	return end
+}
+
+// starlarkSpans returns the top-level function definitions of the Starlark
+// file code, recording MetadataDefinition, its calls, such as rule and macro
+// invocations, recording MetadataRule and the target they name as
+// MetadataTarget, and its load statements, recording MetadataBlock so those
+// that fit are chunked together. Blocks take the comment lines right above
+// them.
+func starlarkSpans(code string) []textSpan {
+	var spans []textSpan
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		m := starlarkStatement.FindStringSubmatchIndex(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+
+		end := starlarkStatementEnd(code, offset)
+		var metadata map[string]string
+		switch name := line[max(m[2], m[4]):max(m[3], m[5])]; {
+		case m[2] >= 0:
+			// The body runs over the indented lines after the definition, and
+			// over the blank lines between them
+			for scan := end; scan < len(code); {
+				line, next := codeLine(code, scan)
+				if strings.TrimSpace(line) == "" {
+					scan = next
+					continue
+				}
+				if line[0] != ' ' && line[0] != '\t' {
+					break
+				}
+				end = starlarkStatementEnd(code, scan)
+				scan = end
+			}
+			metadata = map[string]string{MetadataDefinition: name}
+		case name == "load":
+			metadata = map[string]string{MetadataBlock: "load"}
+		default:
+			metadata = map[string]string{MetadataRule: name}
+			if target := starlarkName.FindStringSubmatch(code[offset:end]); target != nil {
+				metadata[MetadataTarget] = target[1]
+			}
+		}
+
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans)),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// starlarkStatementEnd returns the offset after the line feed ending the
+// Starlark statement at offset in code, which runs on over open brackets,
+// strings, and lines continued with a backslash, or len(code) if it isn't
+// ended.
+func starlarkStatementEnd(code string, offset int) int {
+	depth := 0
+	for i := offset; i < len(code); i++ {
+		switch code[i] {
+		case '(', '[', '{':
+			depth++
+		case ')', ']', '}':
+			depth = max(depth-1, 0)
+		case '\\':
+			i++
+		case '#':
+			for i+1 < len(code) && code[i+1] != '\n' {
+				i++
+			}
+		case '"', '\'':
+			quote := code[i : i+1]
+			if strings.HasPrefix(code[i:], strings.Repeat(quote, 3)) {
+				quote = strings.Repeat(quote, 3)
+			}
+			i += len(quote)
+			for i < len(code) && !strings.HasPrefix(code[i:], quote) && (len(quote) == 3 || code[i] != '\n') {
+				if code[i] == '\\' {
+					i++
+				}
+				i++
+			}
+			// An unterminated string ends at the end of its line
+			if i < len(code) && code[i] == '\n' {
+				i--
+			} else {
+				i += len(quote) - 1
+			}
+		case '\n':
+			if depth == 0 {
+				return i + 1
+			}
+		}
+	}
+	return len(code)
+}
+
+// nixSpans returns the bindings of the Nix expression code that start lines
+// outside the values of other bindings, such as those of its top-level
+// attribute set or let expression, each with the comment lines right above
+// it. They record MetadataAttribute, and MetadataDefinition for those that
+// bind a function.
+func nixSpans(code string) []textSpan {
+	return nixBindings(code, 0)
+}
+
+// nixNested returns the bindings within the value of the Nix binding, after
+// the line it starts on.
+func nixNested(binding string) []textSpan {
+	for offset := 0; offset < len(binding); {
+		line, next := codeLine(binding, offset)
+		if nixBinding.MatchString(line) {
+			return nixBindings(binding, next)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// nixBindings returns the bindings of the Nix expression code that start the
+// lines from offset on.
+func nixBindings(code string, offset int) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := nixBinding.FindStringSubmatchIndex(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+
+		value := offset + m[3] + strings.IndexByte(line[m[3]:], '=') + 1
+		semicolon := nixBindingEnd(code, value)
+		if semicolon < 0 {
+			offset = next
+			continue
+		}
+		_, end := codeLine(code, semicolon)
+
+		name := line[m[2]:m[3]]
+		metadata := map[string]string{MetadataAttribute: name}
+		if nixLambda.MatchString(strings.TrimSpace(code[value:semicolon])) {
+			metadata[MetadataDefinition] = name
+		}
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans)),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// nixBindingEnd returns the offset of the semicolon ending the value of a
+// Nix binding at offset in code, skipping nested expressions, strings, and
+// comments, or -1 if the value isn't ended before the expression holding it.
+// The bindings of let expressions, and the semicolons of with and assert
+// expressions, within the value don't end it.
+func nixBindingEnd(code string, offset int) int {
+	depth := 0
+	lets := 0      // Let expressions whose bindings are open
+	prefixes := 0 // With and assert expressions waiting for their semicolon
+	for i := offset; i < len(code); i++ {
+		switch rest := code[i:]; {
+		case code[i] == '{' || code[i] == '[' || code[i] == '(':
+			depth++
+		case code[i] == '}' || code[i] == ']' || code[i] == ')':
+			if depth--; depth < 0 {
+				return -1
+			}
+		case code[i] == ';':
+			switch {
+			case depth > 0:
+			case prefixes > 0:
+				prefixes--
+			case lets == 0:
+				return i
+			}
+		case code[i] == '"':
+			for i++; i < len(code) && code[i] != '"'; i++ {
+				if code[i] == '\\' {
+					i++
+				}
+			}
+		case strings.HasPrefix(rest, "''"):
+			i = nixIndentedStringEnd(code, i+2) - 1
+		case code[i] == '#':
+			for i+1 < len(code) && code[i+1] != '\n' {
+				i++
+			}
+		case strings.HasPrefix(rest, "/*"):
+			if end := strings.Index(rest[2:], "*/"); end >= 0 {
+				i += end + 3
+			} else {
+				i = len(code)
+			}
+		case nixIdentifier(code[i]) && (i == 0 || !nixIdentifier(code[i-1])):
+			word := i + 1
+			for word < len(code) && nixIdentifier(code[word]) {
+				word++
+			}
+			if depth == 0 {
+				switch code[i:word] {
+				case "let":
+					lets++
+				case "in":
+					lets = max(lets-1, 0)
+				case "with", "assert":
+					prefixes++
+				}
+			}
+			i = word - 1
+		}
+	}
+	return -1
+}
+
+// nixIdentifier reports whether b can be part of a Nix identifier.
+func nixIdentifier(b byte) bool {
+	return b == '_' || b == '\'' || b == '-' || '0' <= b && b <= '9' || 'a' <= b && b <= 'z' || 'A' <= b && b <= 'Z'
+}
+
+// nixIndentedStringEnd returns the offset after the '' closing the indented
+// string of Nix whose content starts at offset in code, or len(code) if it
+// isn't closed. Such strings escape '' as ''' and ${ as ''$.
+func nixIndentedStringEnd(code string, offset int) int {
+	for i := offset; i < len(code); i++ {
+		if !strings.HasPrefix(code[i:], "''") {
+			continue
+		}
+		if i+2 < len(code) && (code[i+2] == '\'' || code[i+2] == '$' || code[i+2] == '\\') {
+			i += 2
+			continue
+		}
+		return i + 2
+	}
+	return len(code)
+}
+
+// codeLine returns the line of code at offset, without its line feed, and
+// the offset of the next line.
+func codeLine(code string, offset int) (string, int) {
+	line, _, _ := strings.Cut(code[offset:], "\n")
+	return line, min(offset+len(line)+1, len(code))
+}
+
+// continuedLine returns the offset after the line of code at offset and the
+// lines it continues onto with a trailing backslash.
+func continuedLine(code string, offset int) int {
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		offset = next
+		if !strings.HasSuffix(strings.TrimRight(line, " \t\r"), "\\") {
+			break
+		}
+	}
+	return offset
+}
+
+// leadingComments returns the start of the comment lines right above the line
+// at offset in code, not before from, or offset if there are none. Comment
+// lines start with one of prefixes, or with "#" if there are none.
+func leadingComments(code string, offset, from int, prefixes ...string) int {
+	if len(prefixes) == 0 {
+		prefixes = []string{"#"}
+	}
+	for offset > from {
+		prev := strings.LastIndexByte(code[:offset-1], '\n') + 1
+		if prev < from || !hasAnyPrefix(strings.TrimSpace(code[prev:offset]), prefixes) {
+			break
+		}
+		offset = prev
+	}
+	return offset
+}
+
+// hasAnyPrefix reports whether s starts with one of prefixes.
+func hasAnyPrefix(s string, prefixes []string) bool {
+	for _, prefix := range prefixes {
+		if strings.HasPrefix(s, prefix) {
+			return true
+		}
+	}
+	return false
+}
+
+// onlyComments reports whether text holds only blank and "#" comment lines.
+func onlyComments(text string) bool {
+	for _, line := range strings.Split(text, "\n") {
+		if line = strings.TrimSpace(line); line != "" && !strings.HasPrefix(line, "#") {
+			return false
+		}
+	}
+	return true
+}
+
+// spanEnd returns the end of the last of spans, or 0 if there are none.
+func spanEnd(spans []textSpan) int {
+	if len(spans) == 0 {
+		return 0
+	}
+	return spans[len(spans)-1].end
+}
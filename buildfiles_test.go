@@ -0,0 +1,446 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Makefile(t *testing.T) {
+	code := `CC := gcc
+
+.PHONY: all clean
+
+# Build everything
+all: app lib
+
+app: main.o util.o
+	$(CC) -o $@ $^
+
+	# Strip the binary
+	strip $@
+
+clean:
+	rm -f app *.o
+`
+	opts := []Option{WithLanguage(languages.Make)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	tests := []struct{ content, target string }{
+		{"# Build everything\nall: app lib\n", "all app lib"},
+		{"app: main.o util.o\n\t$(CC) -o $@ $^\n\n\t# Strip the binary\n\tstrip $@\n", "app"},
+		{"clean:\n\trm -f app *.o\n", "clean"},
+	}
+	targets := make(map[string]string)
+	for _, chunk := range chunks {
+		targets[chunk.Content] = chunk.Metadata[MetadataTarget]
+	}
+	for _, tt := range tests {
+		got, ok := targets[tt.content]
+		if !ok {
+			t.Errorf("expected a chunk of %q, got %+v", tt.content, chunks)
+		} else if got != tt.target {
+			t.Errorf("expected target %q of %q, got %q", tt.target, tt.content, got)
+		}
+		delete(targets, tt.content)
+	}
+	for content, target := range targets {
+		if target != "" {
+			t.Errorf("unexpected target %q of %q", target, content)
+		}
+	}
+}
+
+func TestChunker_CMake(t *testing.T) {
+	code := `cmake_minimum_required(VERSION 3.20)
+project(demo)
+
+# Adds a test binary
+function(add_demo_test name)
+  add_executable(${name} ${name}.cpp)
+  target_link_libraries(${name} PRIVATE demo)
+endfunction()
+
+add_library(demo STATIC
+  src/demo.cpp  # Core sources (and headers
+)
+target_include_directories(demo PUBLIC include)
+# Link the threads
+target_link_libraries(demo PRIVATE Threads::Threads)
+
+add_demo_test(smoke)
+`
+	if lang, _ := languages.DetectLanguage("src/CMakeLists.txt"); lang.Name != languages.CMake {
+		t.Errorf("expected CMakeLists.txt detected as CMake, got %q", lang.Name)
+	}
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.CMake))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	tests := []struct{ prefix, suffix, key, value string }{
+		{"# Adds a test binary\nfunction(", "endfunction()\n", MetadataDefinition, "add_demo_test"},
+		{"add_library(demo", "Threads::Threads)\n", MetadataTarget, "demo"},
+	}
+	for _, tt := range tests {
+		var found bool
+		for _, chunk := range chunks {
+			if !strings.HasPrefix(chunk.Content, tt.prefix) {
+				continue
+			}
+			found = true
+			if !strings.HasSuffix(chunk.Content, tt.suffix) || chunk.Metadata[tt.key] != tt.value {
+				t.Errorf("unexpected chunk %q with metadata %v", chunk.Content, chunk.Metadata)
+			}
+		}
+		if !found {
+			t.Errorf("expected a chunk starting with %q, got %+v", tt.prefix, chunks)
+		}
+	}
+}
+
+func TestChunker_Gradle(t *testing.T) {
+	code := `plugins {
+    id 'java'
+}
+
+group = 'com.example'
+
+// Libraries the app uses
+dependencies {
+    implementation 'com.google.guava:guava:33.0.0-jre' // {pinned}
+    testImplementation "junit:junit:4.13.2"
+}
+
+tasks.register("bench") {
+    doLast { println "}" }
+}
+`
+	opts := []Option{WithLanguage(languages.Gradle)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	tests := []struct{ content, block string }{
+		{"plugins {\n    id 'java'\n}\n", "plugins"},
+		{"// Libraries the app uses\ndependencies {\n    implementation 'com.google.guava:guava:33.0.0-jre' // {pinned}\n    testImplementation \"junit:junit:4.13.2\"\n}\n", "dependencies"},
+		{"tasks.register(\"bench\") {\n    doLast { println \"}\" }\n}\n", `tasks.register("bench")`},
+	}
+	blocks := make(map[string]string)
+	for _, chunk := range chunks {
+		blocks[chunk.Content] = chunk.Metadata[MetadataBlock]
+	}
+	for _, tt := range tests {
+		if got, ok := blocks[tt.content]; !ok {
+			t.Errorf("expected a chunk of %q, got %+v", tt.content, chunks)
+		} else if got != tt.block {
+			t.Errorf("expected block %q of %q, got %q", tt.block, tt.content, got)
+		}
+	}
+}
+
+func TestChunker_Gradle_NestedBlocks(t *testing.T) {
+	code := `android {
+    defaultConfig {
+        minSdk 24
+        targetSdk 34
+    }
+    buildTypes {
+        release {
+            minifyEnabled true
+        }
+    }
+}
+`
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Gradle), WithMaxSize(8))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	tests := []struct{ content, block string }{
+		{"android {\n", "android"},
+		{"    defaultConfig {\n        minSdk 24\n        targetSdk 34\n    }\n", "defaultConfig"},
+		{"    buildTypes {\n        release {\n            minifyEnabled true\n        }\n    }\n", "buildTypes"},
+		{"}\n", "android"},
+	}
+	if len(chunks) != len(tests) {
+		t.Fatalf("expected %d chunks, got %+v", len(tests), chunks)
+	}
+	for i, tt := range tests {
+		if chunks[i].Content != tt.content || chunks[i].Metadata[MetadataBlock] != tt.block {
+			t.Errorf("expected chunk %d %q in block %q, got %q in %q", i, tt.content, tt.block, chunks[i].Content, chunks[i].Metadata[MetadataBlock])
+		}
+	}
+}
+
+func TestChunker_Maven(t *testing.T) {
+	code := `<?xml version="1.0"?>
+<project>
+  <modelVersion>4.0.0</modelVersion>
+  <artifactId>demo</artifactId>
+  <!-- Runtime libraries -->
+  <dependencies>
+    <dependency>
+      <groupId>org.slf4j</groupId>
+      <artifactId>slf4j-api</artifactId>
+    </dependency>
+    <dependency>
+      <groupId>junit</groupId>
+      <artifactId>junit</artifactId>
+    </dependency>
+    <dependency>
+      <groupId>org.mockito</groupId>
+      <artifactId>mockito-core</artifactId>
+    </dependency>
+  </dependencies>
+  <build>
+    <plugins>
+      <plugin><artifactId>maven-compiler-plugin</artifactId></plugin>
+    </plugins>
+  </build>
+</project>
+`
+	opts := []Option{WithLanguage(languages.Maven), WithMaxSize(9)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	tests := []struct{ prefix, block string }{
+		{"  <!-- Runtime libraries -->\n  <dependencies>\n", "dependencies"},
+		{"    <dependency>\n      <groupId>org.slf4j</groupId>", "dependencies"},
+		{"    <dependency>\n      <groupId>org.mockito</groupId>", "dependencies"},
+		{"  <build>\n", "build"},
+	}
+	for _, tt := range tests {
+		var found bool
+		for _, chunk := range chunks {
+			if !strings.HasPrefix(chunk.Content, tt.prefix) {
+				continue
+			}
+			found = true
+			if got := chunk.Metadata[MetadataBlock]; got != tt.block {
+				t.Errorf("expected block %q of %q, got %q", tt.block, chunk.Content, got)
+			}
+		}
+		if !found {
+			t.Errorf("expected a chunk starting with %q, got %+v", tt.prefix, chunks)
+		}
+	}
+	for _, chunk := range chunks {
+		if strings.Contains(chunk.Content, "slf4j") && (!strings.Contains(chunk.Content, "junit") || strings.Contains(chunk.Content, "mockito")) {
+			t.Errorf("expected the first two dependencies chunked together, got %q", chunk.Content)
+		}
+	}
+}
+
+func TestDetectLanguage_BuildFiles(t *testing.T) {
+	for path, want := range map[string]languages.LanguageName{
+		"app/build.gradle":                   languages.Gradle,
+		"app/build.gradle.kts":               languages.Gradle,
+		"settings.gradle.kts":                languages.Gradle,
+		"src/Main.kts":                       languages.Kotlin,
+		"pom.xml":                            languages.Maven,
+		"lib/Makefile":                       languages.Make,
+		"cmake/deps.cmake":                   languages.CMake,
+		"tools/BUILD.bazel":                  languages.Starlark,
+		"WORKSPACE":                          languages.Starlark,
+		"defs.bzl":                           languages.Starlark,
+		"pkgs/default.nix":                   languages.Nix,
+		"boot/start.S":                       languages.Assembly,
+		"stm32.ld":                           languages.Linker,
+		"lib/Counter.pm":                     languages.Perl,
+		"ci/Jenkinsfile":                     languages.Jenkins,
+		"src/gen_srv.erl":                    languages.Erlang,
+		"src/Shapes.fs":                      languages.FSharp,
+		"src/Circle.vb":                      languages.VBNet,
+		"src/PAYROLL.cbl":                    languages.COBOL,
+		"src/solver.f90":                     languages.Fortran,
+		"analysis/fit.m":                     languages.MATLAB,
+		"rtl/counter.sv":                     languages.Verilog,
+		"rtl/counter.vhd":                    languages.VHDL,
+		"classes/Invoices.cls":               languages.Apex,
+		"objects/Invoice__c.object-meta.xml": languages.Salesforce,
+		"src/app/core.clj":                   languages.Clojure,
+		"lisp/demo.el":                       languages.EmacsLisp,
+		"lib/geometry.scm":                   languages.Scheme,
+		"scripts/Deploy.ps1":                 languages.PowerShell,
+		"src/stacks.adb":                     languages.Ada,
+		"src/Shapes.pas":                     languages.Pascal,
+		"src/Widget.mm":                      languages.ObjectiveC,
+		"setup.cfg":                          languages.INI,
+		"config/app.properties":              languages.Properties,
+		"deploy/.env.production":             languages.DotEnv,
+		"paper/references.bib":               languages.BibTeX,
+		"paper/references.ris":               languages.RIS,
+		"export/Patient.ndjson":              languages.NDJSON,
+		"inbound/adt.hl7":                    languages.HL7,
+		"etc/nginx/nginx.conf":               languages.Nginx,
+		"public/.htaccess":                   languages.Apache,
+		"deploy/Caddyfile":                   languages.Caddy,
+		"static/robots.txt":                  languages.Robots,
+	} {
+		if lang, _ := languages.DetectLanguage(path); lang.Name != want {
+			t.Errorf("expected %q detected as %q, got %q", path, want, lang.Name)
+		}
+	}
+}
+
+func TestChunker_Starlark(t *testing.T) {
+	code := `load("@rules_cc//cc:defs.bzl", "cc_binary", "cc_library")
+load("//tools:defs.bzl", "lint_test")
+
+# Core library
+cc_library(
+    name = "core",
+    srcs = ["core.cc"],
+    hdrs = ["core.h"],  # Public API (see docs)
+)
+
+cc_binary(
+    name = "app",
+    deps = [":core"],
+)
+
+def app_test(name, **kwargs):
+    """Declares a test of the app.
+
+Used by every package."""
+    native.cc_test(
+        name = name,
+        **kwargs
+    )
+
+    lint_test(name = name + "_lint")
+`
+	opts := []Option{WithLanguage(languages.Starlark)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	tests := []struct{ prefix, suffix, key, value string }{
+		{"load(\"@rules_cc", "\"lint_test\")\n", MetadataBlock, "load"},
+		{"# Core library\ncc_library(", "(see docs)\n)\n", MetadataTarget, "core"},
+		{"cc_binary(", "\":core\"],\n)\n", MetadataRule, "cc_binary"},
+		{"def app_test(", "name + \"_lint\")\n", MetadataDefinition, "app_test"},
+	}
+	for _, tt := range tests {
+		var found bool
+		for _, chunk := range chunks {
+			if !strings.HasPrefix(chunk.Content, tt.prefix) {
+				continue
+			}
+			found = true
+			if !strings.HasSuffix(chunk.Content, tt.suffix) || chunk.Metadata[tt.key] != tt.value {
+				t.Errorf("unexpected chunk %q with metadata %v", chunk.Content, chunk.Metadata)
+			}
+		}
+		if !found {
+			t.Errorf("expected a chunk starting with %q, got %+v", tt.prefix, chunks)
+		}
+	}
+}
+
+func TestChunker_Nix(t *testing.T) {
+	code := `{ pkgs ? import <nixpkgs> { } }:
+
+let
+  # Adds one
+  inc = x: x + 1;
+  version = "1.0";
+in
+{
+  packages.default = pkgs.stdenv.mkDerivation {
+    pname = "hello";
+    inherit version;
+    buildPhase = ''
+      echo "done; really" ''${out}
+    '';
+  };
+
+  devShells.default = with pkgs; mkShell { buildInputs = [ go ]; };
+}
+`
+	opts := []Option{WithLanguage(languages.Nix)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	tests := []struct{ prefix, suffix, attribute, definition string }{
+		{"  # Adds one\n  inc =", "x + 1;\n", "inc", "inc"},
+		{"  version =", "\"1.0\";\n", "version", ""},
+		{"  packages.default =", "    '';\n  };\n", "packages.default", ""},
+		{"  devShells.default =", "[ go ]; };\n", "devShells.default", ""},
+	}
+	for _, tt := range tests {
+		var found bool
+		for _, chunk := range chunks {
+			if !strings.HasPrefix(chunk.Content, tt.prefix) {
+				continue
+			}
+			found = true
+			m := chunk.Metadata
+			if !strings.HasSuffix(chunk.Content, tt.suffix) || m[MetadataAttribute] != tt.attribute || m[MetadataDefinition] != tt.definition {
+				t.Errorf("unexpected chunk %q with metadata %v", chunk.Content, m)
+			}
+		}
+		if !found {
+			t.Errorf("expected a chunk starting with %q, got %+v", tt.prefix, chunks)
+		}
+	}
+}
+
+func TestChunker_Nix_NestedBindings(t *testing.T) {
+	code := `{
+  packages.default = mkDerivation {
+    pname = "hello";
+    inherit version;
+    buildPhase = ''
+      make all
+    '';
+  };
+}
+`
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Nix), WithMaxSize(6))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	want := map[string]string{"pname": "", "buildPhase": "", "packages.default": ""}
+	for _, chunk := range chunks {
+		attribute := chunk.Metadata[MetadataAttribute]
+		if _, ok := want[attribute]; ok {
+			want[attribute] = chunk.Content
+		}
+		if attribute == "pname" && chunk.Content != "    pname = \"hello\";\n" {
+			t.Errorf("expected the pname binding alone, got %q", chunk.Content)
+		}
+	}
+	for attribute, content := range want {
+		if content == "" {
+			t.Errorf("expected a chunk of %q, got %+v", attribute, chunks)
+		}
+	}
+}
@@ -0,0 +1,113 @@
+package chunkx
+
+import (
+	"bufio"
+	"encoding/binary"
+	"fmt"
+	"math"
+)
+
+// CBOR major types.
+const (
+	cborUint   = 0
+	cborNegInt = 1
+	cborText   = 3
+	cborArray  = 4
+	cborMap    = 5
+	cborSimple = 7
+)
+
+// cborCodec implements the subset of CBOR (RFC 8949) used for chunk records.
+type cborCodec struct{}
+
+// appendCBORHead appends the head of a data item with the given major type
+// and argument.
+func appendCBORHead(b []byte, major byte, n uint64) []byte {
+	major <<= 5
+	switch {
+	case n < 24:
+		return append(b, major|byte(n))
+	case n <= math.MaxUint8:
+		return append(b, major|24, byte(n))
+	case n <= math.MaxUint16:
+		return binary.BigEndian.AppendUint16(append(b, major|25), uint16(n))
+	case n <= math.MaxUint32:
+		return binary.BigEndian.AppendUint32(append(b, major|26), uint32(n))
+	default:
+		return binary.BigEndian.AppendUint64(append(b, major|27), n)
+	}
+}
+
+func (cborCodec) appendMap(b []byte, n int) []byte {
+	return appendCBORHead(b, cborMap, uint64(n))
+}
+
+func (cborCodec) appendArray(b []byte, n int) []byte {
+	return appendCBORHead(b, cborArray, uint64(n))
+}
+
+func (cborCodec) appendString(b []byte, s string) []byte {
+	return append(appendCBORHead(b, cborText, uint64(len(s))), s...)
+}
+
+func (cborCodec) appendInt(b []byte, n int64) []byte {
+	if n < 0 {
+		return appendCBORHead(b, cborNegInt, uint64(-1-n))
+	}
+	return appendCBORHead(b, cborUint, uint64(n))
+}
+
+func (c cborCodec) readValue(r *bufio.Reader, depth int) (any, error) {
+	head, err := r.ReadByte()
+	if err != nil {
+		return nil, err
+	}
+	major, info := head>>5, head&0x1f
+
+	if major == cborSimple {
+		switch info {
+		case 20:
+			return false, nil
+		case 21:
+			return true, nil
+		case 22, 23:
+			return nil, nil // null and undefined
+		default:
+			return nil, fmt.Errorf("unsupported CBOR simple value %d", info)
+		}
+	}
+
+	var n uint64
+	switch {
+	case info < 24:
+		n = uint64(info)
+	case info <= 27:
+		if n, err = readUint(r, 1<<(info-24)); err != nil {
+			return nil, err
+		}
+	default:
+		// Indefinite lengths are never written by the encoder
+		return nil, fmt.Errorf("unsupported CBOR length encoding %d", info)
+	}
+
+	switch major {
+	case cborUint:
+		if n > math.MaxInt64 {
+			return nil, fmt.Errorf("integer %d out of range", n)
+		}
+		return int64(n), nil
+	case cborNegInt:
+		if n > math.MaxInt64 {
+			return nil, fmt.Errorf("integer -%d out of range", n)
+		}
+		return -1 - int64(n), nil
+	case cborText:
+		return readString(r, n)
+	case cborArray:
+		return readArray(c, r, n, depth)
+	case cborMap:
+		return readMap(c, r, n, depth)
+	default:
+		return nil, fmt.Errorf("unsupported CBOR major type %d", major)
+	}
+}
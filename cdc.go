@@ -0,0 +1,148 @@
+package chunkx
+
+import (
+	"math/bits"
+	"unicode/utf8"
+)
+
+// Defaults of the sizes of ContentDefined.
+const (
+	DefaultAvgBytes = 8 << 10 // Size content-defined chunks average
+	minBytesRatio   = 4       // AvgBytes over the default MinBytes
+	maxBytesRatio   = 8       // The default MaxBytes over AvgBytes
+)
+
+// gearSeed seeds gearTable, fixed so boundaries are stable across runs and
+// versions.
+const gearSeed = 0x9e3779b97f4a7c15
+
+// gearTable maps every byte to the random number the gear hash adds for it.
+var gearTable = func() (table [256]uint64) {
+	// splitmix64, which needs no state beyond the seed
+	x := uint64(gearSeed)
+	for i := range table {
+		x += 0x9e3779b97f4a7c15
+		z := x
+		z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9
+		z = (z ^ (z >> 27)) * 0x94d049bb133111eb
+		table[i] = z ^ (z >> 31)
+	}
+	return table
+}()
+
+// ContentDefined configures the content-defined chunking of
+// WithContentDefined. Zero fields take their defaults: AvgBytes of
+// DefaultAvgBytes, a MinBytes of a quarter of it, and a MaxBytes of eight
+// times it.
+type ContentDefined struct {
+	MinBytes int // Smallest chunk, but for the last one
+	AvgBytes int // Size chunks average, rounded down to a power of two
+	MaxBytes int // Largest chunk
+}
+
+// WithContentDefined splits inputs at boundaries picked by a rolling hash of
+// their bytes, as FastCDC does, instead of by their structure. An insertion or
+// deletion only moves the boundaries next to it, so versions of a file share
+// most of their chunks and their Chunk.ID, which suits deduplication and
+// binary inputs where structure doesn't apply.
+//
+// Chunks are sized in bytes rather than tokens, and their boundaries may fall
+// inside a line, though they back off to the start of a UTF-8 sequence.
+// Nothing but WithRedaction and WithLimits applies to them, and they have no
+// node types.
+func WithContentDefined(cd ContentDefined) Option {
+	return func(c *config) {
+		c.contentDefined = &cd
+	}
+}
+
+// sizes returns the sizes of cd with their defaults, with the minimum at
+// most the average and the average at most the maximum, and the bits the
+// average spans.
+func (cd ContentDefined) sizes() (minBytes, avgBytes, maxBytes, avgBits int) {
+	avgBytes = cd.AvgBytes
+	if avgBytes <= 0 {
+		avgBytes = DefaultAvgBytes
+	}
+	avgBits = bits.Len(uint(avgBytes)) - 1
+	avgBytes = 1 << avgBits
+
+	minBytes = cd.MinBytes
+	if minBytes <= 0 {
+		minBytes = avgBytes / minBytesRatio
+	}
+	maxBytes = cd.MaxBytes
+	if maxBytes <= 0 {
+		maxBytes = avgBytes * maxBytesRatio
+	}
+	minBytes = min(minBytes, avgBytes)
+	maxBytes = max(maxBytes, avgBytes, 1)
+	return minBytes, avgBytes, maxBytes, avgBits
+}
+
+// gearMask returns a mask of the n top bits of the gear hash, which are
+// the ones mixed from the most bytes.
+func gearMask(n int) uint64 {
+	n = min(max(n, 0), 64)
+	return ^uint64(0) << (64 - n)
+}
+
+// cdcBoundary returns the length of the chunk at the start of data. Before
+// the average size, it looks for a hash with two more zero bits than the
+// average calls for, and after it for two fewer, which keeps sizes close to
+// the average: FastCDC's normalized chunking.
+func cdcBoundary(data string, minBytes, avgBytes, maxBytes int, strict, loose uint64) int {
+	if len(data) <= minBytes {
+		return len(data)
+	}
+	end := min(len(data), maxBytes)
+	normal := min(end, avgBytes)
+
+	var h uint64
+	i := minBytes
+	for ; i < normal; i++ {
+		h = h<<1 + gearTable[data[i]]
+		if h&strict == 0 {
+			return i + 1
+		}
+	}
+	for ; i < end; i++ {
+		h = h<<1 + gearTable[data[i]]
+		if h&loose == 0 {
+			return i + 1
+		}
+	}
+	return end
+}
+
+// chunkContentDefined splits code at the boundaries of cfg.contentDefined.
+func chunkContentDefined(code string, cfg *config) ([]Chunk, error) {
+	minBytes, avgBytes, maxBytes, avgBits := cfg.contentDefined.sizes()
+	strict, loose := gearMask(avgBits+2), gearMask(avgBits-2)
+
+	var chunks []Chunk
+	line := 1
+	for start := 0; start < len(code); {
+		if err := cfg.ctx.Err(); err != nil {
+			return nil, err
+		}
+		end := start + cdcBoundary(code[start:], minBytes, avgBytes, maxBytes, strict, loose)
+		for back := end; back > start+1 && back > end-utf8.UTFMax && back < len(code); back-- {
+			if utf8.RuneStart(code[back]) {
+				end = back
+				break
+			}
+		}
+		endLine := lineOf(code, start, line, end)
+		chunks = append(chunks, Chunk{
+			Content:   code[start:end],
+			StartLine: line,
+			EndLine:   endLine,
+			StartByte: start,
+			EndByte:   end,
+			Language:  cfg.language,
+		})
+		start, line = end, endLine
+	}
+	return chunks, nil
+}
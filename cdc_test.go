@@ -0,0 +1,82 @@
+package chunkx
+
+import (
+	"math/rand/v2"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// randomText returns n bytes of random lowercase words and newlines.
+func randomText(seed uint64, n int) string {
+	r := rand.New(rand.NewPCG(seed, seed))
+	var b strings.Builder
+	for b.Len() < n {
+		switch r.IntN(8) {
+		case 0:
+			b.WriteByte('\n')
+		case 1:
+			b.WriteByte(' ')
+		default:
+			b.WriteByte(byte('a' + r.IntN(26)))
+		}
+	}
+	return b.String()[:n]
+}
+
+func TestWithContentDefined(t *testing.T) {
+	cd := ContentDefined{MinBytes: 256, AvgBytes: 1024, MaxBytes: 4096}
+	code := randomText(1, 64<<10)
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithContentDefined(cd))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) < 32 || len(chunks) > 128 {
+		t.Errorf("expected about 64 chunks of 1 KiB, got %d", len(chunks))
+	}
+	for i, chunk := range chunks {
+		if n := len(chunk.Content); n > cd.MaxBytes || n < cd.MinBytes && i < len(chunks)-1 {
+			t.Errorf("chunk %d: expected between %d and %d bytes, got %d", i, cd.MinBytes, cd.MaxBytes, n)
+		}
+	}
+	if err := ValidateChunks(chunks, code, WithContentDefined(cd)); err != nil {
+		t.Fatalf("invalid chunks: %v", err)
+	}
+
+	// An insertion only changes the chunks around it
+	edited := code[:32<<10] + "an insertion" + code[32<<10:]
+	editedChunks, err := NewChunker().Chunk(edited, WithLanguage(languages.Generic), WithContentDefined(cd))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	ids := make(map[string]bool, len(chunks))
+	for _, chunk := range chunks {
+		ids[chunk.ID()] = true
+	}
+	var changed int
+	for _, chunk := range editedChunks {
+		if !ids[chunk.ID()] {
+			changed++
+		}
+	}
+	if changed > 3 {
+		t.Errorf("expected at most 3 changed chunks, got %d of %d", changed, len(editedChunks))
+	}
+}
+
+func TestContentDefined_Sizes(t *testing.T) {
+	for _, tt := range []struct {
+		cd               ContentDefined
+		minB, avgB, maxB int
+	}{
+		{ContentDefined{}, 2 << 10, 8 << 10, 64 << 10},
+		{ContentDefined{AvgBytes: 1000}, 128, 512, 4 << 10},
+		{ContentDefined{MinBytes: 4096, AvgBytes: 1024, MaxBytes: 100}, 1024, 1024, 1024},
+	} {
+		minB, avgB, maxB, _ := tt.cd.sizes()
+		if minB != tt.minB || avgB != tt.avgB || maxB != tt.maxB {
+			t.Errorf("%+v: got sizes %d, %d, %d, want %d, %d, %d", tt.cd, minB, avgB, maxB, tt.minB, tt.avgB, tt.maxB)
+		}
+	}
+}
@@ -0,0 +1,272 @@
+package chunkx
+
+import (
+	"bytes"
+	"maps"
+	"slices"
+	"strconv"
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Metadata keys set on chunks of chat transcripts chunked with
+// WithChatTranscripts.
+const (
+	MetadataRole    = "role"    // Roles of the messages of the chunk, in order and without duplicates, joined with ","
+	MetadataTurn    = "turn"    // Index of the turn the chunk starts in
+	MetadataMessage = "message" // Index of the message the chunk starts in
+)
+
+// ChatML tags around the messages of a transcript.
+const (
+	chatMLStart = "<|im_start|>"
+	chatMLEnd   = "<|im_end|>"
+)
+
+// WithChatTranscripts chunks chat transcripts by their messages, for indexing
+// support tickets and chat logs. Transcripts are JSON arrays of messages with
+// a role and a content, as in the OpenAI and Anthropic APIs, objects holding
+// such an array under "messages", or ChatML text, with each message between
+// "<|im_start|>role" and "<|im_end|>".
+//
+// Consecutive messages are chunked together as long as they fit, and a message
+// is only split when it doesn't fit in a chunk on its own, as Markdown. A turn
+// is a user message, with the user messages right after it, and the replies
+// to them; messages before the first user message are in turn 0. Chunks
+// record MetadataRole, MetadataTurn, and MetadataMessage.
+//
+// Chunks span the contents of their messages in the transcript, so their
+// offsets and lines point into it, while their content is the decoded text of
+// the messages, one line feed apart. Content that isn't a transcript is
+// chunked as usual.
+func WithChatTranscripts() Option {
+	return func(c *config) {
+		c.transcripts = true
+	}
+}
+
+// chatMessage is a message of a transcript, by the offsets of its content in
+// the text of the transcript.
+type chatMessage struct {
+	role       string
+	start, end int
+}
+
+// looksLikeTranscript reports whether head starts a JSON document with roles,
+// or holds ChatML tags.
+func looksLikeTranscript(head []byte) bool {
+	if bytes.Contains(head, []byte(chatMLStart)) {
+		return true
+	}
+	head = bytes.TrimLeft(head, " \t\r\n")
+	return (bytes.HasPrefix(head, []byte("[")) || bytes.HasPrefix(head, []byte("{"))) && bytes.Contains(head, []byte(`"role"`))
+}
+
+// chunkTranscript chunks the messages of the transcript code, returning false
+// if code isn't a transcript.
+func (c *castChunker) chunkTranscript(code string, cfg *config) ([]Chunk, bool, error) {
+	var tb textBuilder
+	messages, ok := jsonMessages(code, &tb)
+	if !ok {
+		tb = textBuilder{}
+		messages, ok = chatMLMessages(code, &tb)
+	}
+	if !ok {
+		return nil, false, nil
+	}
+	text := tb.b.String()
+	turns := chatTurns(messages)
+
+	// The call's timeout already runs over the whole transcript
+	inner := *cfg
+	inner.transcripts = false
+	inner.timeout = 0
+	inner.language = languages.Markdown
+
+	var chunks []Chunk
+	for first := 0; first < len(messages); {
+		start := messages[first].start
+		if start == messages[first].end {
+			first++
+			continue
+		}
+		metadata := map[string]string{
+			MetadataTurn:    strconv.Itoa(turns[first]),
+			MetadataMessage: strconv.Itoa(first),
+		}
+
+		size, err := cfg.tokenCounter.CountTokens(text[start:messages[first].end])
+		if err != nil {
+			return nil, true, err
+		}
+		if size > cfg.maxSize {
+			pieces, err := c.chunk(text[start:messages[first].end], &inner)
+			if err != nil {
+				return nil, true, err
+			}
+			shiftChunks(pieces, start, 0)
+			for i := range pieces {
+				if pieces[i].Metadata == nil {
+					pieces[i].Metadata = make(map[string]string, len(metadata)+1)
+				}
+				maps.Copy(pieces[i].Metadata, metadata)
+				pieces[i].Metadata[MetadataRole] = messages[first].role
+			}
+			chunks = append(chunks, pieces...)
+			first++
+			continue
+		}
+
+		// Add the messages after the first while they fit
+		last := first + 1
+		for ; last < len(messages); last++ {
+			size, err := cfg.tokenCounter.CountTokens(text[start:messages[last].end])
+			if err != nil {
+				return nil, true, err
+			}
+			if size > cfg.maxSize {
+				break
+			}
+		}
+
+		var roles []string
+		for _, message := range messages[first:last] {
+			if message.start < message.end && !slices.Contains(roles, message.role) {
+				roles = append(roles, message.role)
+			}
+		}
+		metadata[MetadataRole] = strings.Join(roles, ",")
+		end := messages[last-1].end
+		chunks = append(chunks, Chunk{
+			Content:   text[start:end],
+			StartByte: start,
+			EndByte:   end,
+			Language:  languages.Markdown,
+			Metadata:  metadata,
+		})
+		first = last
+	}
+
+	chunks = mapChunks(chunks, code, &tb.offsets, "")
+	if err := cfg.limits.checkChunks(chunks); err != nil {
+		return nil, true, err
+	}
+	return chunks, true, nil
+}
+
+// chatTurns returns the index of the turn of each message.
+func chatTurns(messages []chatMessage) []int {
+	turns := make([]int, len(messages))
+	turn := 0
+	user := false // Whether a user message was seen
+	for i, message := range messages {
+		if message.role == "user" {
+			if user && messages[i-1].role != "user" {
+				turn++
+			}
+			user = true
+		}
+		turns[i] = turn
+	}
+	return turns
+}
+
+// jsonMessages appends the contents of the messages of the JSON transcript
+// code to tb, one line feed apart, and returns them, or false if code isn't a
+// JSON transcript. Contents are strings or arrays of parts, whose text parts
+// are kept.
+func jsonMessages(code string, tb *textBuilder) ([]chatMessage, bool) {
+	root, ok := parseJSON(code)
+	if !ok {
+		return nil, false
+	}
+	list := root
+	if code[root.start] == '{' {
+		if list, ok = root.get("messages"); !ok {
+			return nil, false
+		}
+	}
+	if code[list.start] != '[' || len(list.values) == 0 {
+		return nil, false
+	}
+
+	messages := make([]chatMessage, 0, len(list.values))
+	for _, value := range list.values {
+		role, ok := value.get("role")
+		if !ok || code[role.start] != '"' {
+			return nil, false
+		}
+
+		var parts []jsonValue
+		if content, ok := value.get("content"); ok {
+			switch code[content.start] {
+			case '"':
+				parts = append(parts, content)
+			case '[':
+				for _, part := range content.values {
+					if text, ok := part.get("text"); ok && code[text.start] == '"' {
+						parts = append(parts, text)
+					} else if code[part.start] == '"' {
+						parts = append(parts, part)
+					}
+				}
+			}
+		}
+
+		message := chatMessage{role: jsonString(code, role)}
+		for i, part := range parts {
+			// A line feed between parts stands for the JSON between them
+			if tb.b.Len() > 0 {
+				tb.writeRune('\n', part.start-tb.offsets.origEnd)
+			}
+			if i == 0 {
+				message.start = tb.b.Len()
+			}
+			writeJSONString(tb, code, part)
+		}
+		if len(parts) == 0 {
+			message.start = tb.b.Len()
+		}
+		message.end = tb.b.Len()
+		messages = append(messages, message)
+	}
+	return messages, true
+}
+
+// chatMLMessages appends the contents of the messages of the ChatML
+// transcript code to tb, one line feed apart, and returns them, or false if
+// code has no messages.
+func chatMLMessages(code string, tb *textBuilder) ([]chatMessage, bool) {
+	var messages []chatMessage
+	for offset := 0; ; {
+		i := strings.Index(code[offset:], chatMLStart)
+		if i < 0 {
+			break
+		}
+		header := offset + i + len(chatMLStart)
+		line, _, _ := strings.Cut(code[header:], "\n")
+		start := min(header+len(line)+1, len(code))
+		end := len(code)
+		if j := strings.Index(code[start:], chatMLEnd); j >= 0 {
+			end = start + j
+		}
+		if j := strings.Index(code[start:end], chatMLStart); j >= 0 {
+			end = start + j
+		}
+
+		// A line feed between messages stands for the tags between them
+		if tb.b.Len() > 0 {
+			tb.writeRune('\n', start-tb.offsets.origEnd)
+		} else {
+			tb.offsets.skip(start - tb.offsets.origEnd)
+		}
+		role, _, _ := strings.Cut(strings.TrimSpace(line), " ")
+		message := chatMessage{role: role, start: tb.b.Len()}
+		tb.writeString(code[start:end])
+		message.end = tb.b.Len()
+		messages = append(messages, message)
+		offset = end
+	}
+	return messages, len(messages) > 0
+}
@@ -1,6 +1,14 @@
 package chunkx
 
-import "github.com/gomantics/chunkx/languages"
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"maps"
+	"slices"
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
 
 // Chunk represents a semantically coherent unit of code extracted via AST-based chunking.
 type Chunk struct {
@@ -11,4 +19,44 @@ type Chunk struct {
 	EndByte   int                    // Ending byte offset
 	NodeTypes []string               // AST node types included in this chunk
 	Language  languages.LanguageName // Programming language of the chunk
+
+	// Metadata holds optional key/value annotations, such as the archive a
+	// chunk was read from. It is nil for most chunks.
+	Metadata map[string]string `json:",omitempty"`
+}
+
+// Metadata keys set by chunkx.
+const (
+	MetadataArchive       = "archive"        // Path of the archive a chunk was read from
+	MetadataArchivePath   = "archive_path"   // Path of the file inside the archive
+	MetadataParseDegraded = "parse_degraded" // "true" for chunks spanning syntax errors
+)
+
+// Clone returns a deep copy of the chunk that owns all of its memory.
+//
+// Chunks share their Content with the input they were produced from. Clone a
+// chunk to keep it after closing the MappedFile it was borrowed from.
+func (c Chunk) Clone() Chunk {
+	c.Content = strings.Clone(c.Content)
+	c.NodeTypes = slices.Clone(c.NodeTypes)
+	c.Metadata = maps.Clone(c.Metadata)
+	return c
+}
+
+// ID returns a stable, content-addressed identifier for the chunk.
+//
+// IDs depend only on the language and content of a chunk, so a chunk keeps
+// its ID when edits elsewhere in the file shift its position.
+func (c Chunk) ID() string {
+	id := c.binaryID()
+	return hex.EncodeToString(id[:])
+}
+
+// binaryID returns the ID of the chunk as the bytes it is the hex of.
+func (c Chunk) binaryID() [16]byte {
+	h := sha256.New()
+	h.Write([]byte(c.Language))
+	h.Write([]byte{0})
+	h.Write([]byte(c.Content))
+	return [16]byte(h.Sum(nil)[:16])
 }
@@ -10,22 +10,30 @@
 //	chunker := chunkx.NewChunker()
 //	chunks, err := chunker.Chunk(code, chunkx.WithLanguage(languages.Go))
 //
-// Supports 30+ languages including Bash, C, C++, C#, CSS, Cue, Dockerfile, Elixir,
-// Elm, Go, Groovy, HCL, HTML, Java, JavaScript, Kotlin, Lua, Markdown, OCaml, PHP,
-// Protobuf, Python, Ruby, Rust, Scala, SQL, Svelte, Swift, TOML, TypeScript, and YAML.
+// Supports 30+ languages including Ada, Apache configs, Apex, assembly, Bash,
+// BibTeX, C, C++, Caddyfiles, C#, Clojure, CMake, COBOL, CSS, Cue, Dockerfile,
+// .env files, Elixir, Elm, Emacs Lisp, Erlang, F#, Fortran, Go, Gradle, Groovy,
+// HCL, HL7, HTML, INI, Java, JavaScript, Jenkinsfiles, Kotlin, linker scripts,
+// Lua, Make, Markdown, MATLAB, Maven, NDJSON, nginx configs, Nix, Objective-C,
+// OCaml, Pascal, Perl, PHP, PowerShell, Java properties, Protobuf, Python, RIS,
+// robots.txt, Ruby, Rust, Salesforce metadata, Scala, Scheme, SQL, Starlark,
+// Svelte, Swift, TOML, TypeScript, VB.NET, Verilog, VHDL, and YAML.
 //
 // For unsupported file types, the chunker automatically falls back to a generic
-// line-based chunking algorithm.
+// line-based chunking algorithm. Tree-sitter grammars need cgo, so builds
+// without it, such as WebAssembly builds, chunk every language line by line.
 package chunkx
 
 import (
+	"context"
 	"fmt"
+	"maps"
 	"os"
-	"sort"
+	"strconv"
 	"strings"
+	"time"
 
 	"github.com/gomantics/chunkx/languages"
-	sitter "github.com/smacker/go-tree-sitter"
 )
 
 // Default configuration values.
@@ -41,29 +49,94 @@ const (
 )
 
 // Chunker provides AST-based code chunking capabilities.
+//
+// Chunkers are safe for concurrent use and cheap to share, so a service can
+// chunk every request with a single chunker. Parsers are pooled and node type
+// names interned across calls, which makes a long-lived chunker faster than a
+// new chunker per call.
 type Chunker interface {
 	Chunk(code string, opts ...Option) ([]Chunk, error)
 	ChunkFile(path string, opts ...Option) ([]Chunk, error)
 }
 
-// castChunker implements the CAST algorithm for code chunking.
-type castChunker struct {
-	parser *Parser
+// ContextChunker is a Chunker that stops early with the error of a cancelled
+// context. The chunker of NewChunker implements it, so callers holding a
+// Chunker type-assert to it:
+//
+//	if cc, ok := chunker.(chunkx.ContextChunker); ok {
+//		chunks, err = cc.ChunkContext(ctx, code)
+//	}
+type ContextChunker interface {
+	Chunker
+	ChunkContext(ctx context.Context, code string, opts ...Option) ([]Chunk, error)
+	ChunkFileContext(ctx context.Context, path string, opts ...Option) ([]Chunk, error)
+}
+
+// MappedChunker is a Chunker that chunks memory-mapped files in place. The
+// chunker of NewChunker implements it:
+//
+//	chunks, err := chunkx.NewChunker().(chunkx.MappedChunker).ChunkMapped(file)
+type MappedChunker interface {
+	Chunker
+	ChunkMapped(file *MappedFile, opts ...Option) ([]Chunk, error)
 }
 
 // NewChunker creates a new CAST chunker instance.
 func NewChunker() Chunker {
-	return &castChunker{
-		parser: NewParser(),
-	}
+	return newCastChunker()
 }
 
 // config holds the configuration for chunking operations.
 type config struct {
-	maxSize      int
-	overlap      float64
-	language     languages.LanguageName
-	tokenCounter TokenCounter
+	maxSize           int
+	softLimit         float64
+	overlap           float64
+	language          languages.LanguageName
+	languageOverrides []LanguageOverride
+	ignore            []string
+	tokenCounter      TokenCounter
+	parseCache        *ParseCache
+	timeout           time.Duration
+	progress          ProgressHooks
+	ordered           bool
+	pollInterval      time.Duration
+	tracer            Tracer
+	meter             Meter
+	index             FileIndex
+	manifest          *Manifest
+	events            ChunkEventListener
+	transcode         TranscodeMode
+	normalizeNewlines bool
+	binary            BinaryPolicy
+	generated         GeneratedPolicy
+	detectors         []Detector
+	license           LicensePolicy
+	boilerplate       banners
+	comments          map[languages.LanguageName]CommentMode // By language, with "" for every other language
+	limits            Limits
+	sampling          Sampling
+	router            *DocumentRouter
+	frontMatter       bool
+	fencedCode        bool
+	tableHeaders      bool
+	links             bool
+	frontMatterKeys   []string
+	notebooks         bool
+	notebookOutputs   OutputPolicy
+	transcripts       bool
+	speakers          bool
+	openAPI           bool
+	jsonSubtrees      bool
+	contextTemplate   string
+	headingPaths      bool
+	symbolPaths       bool
+	stableBoundaries  bool
+	passthrough       *Passthrough                           // Fast path for small inputs, if set
+	contentDefined    *ContentDefined                        // Sizes of content-defined chunks, if set
+	partitions        *Partitions                            // Parallel chunking of huge inputs, if set
+	steps             *stepBudget                            // Steps left to the current call, if limited
+	incremental       *incrementalRun                        // State of the Document being re-chunked, if any
+	ctx               context.Context                        // Context of the current call
 }
 
 // Option configures the chunker.
@@ -76,7 +149,9 @@ func WithMaxSize(tokens int) Option {
 	}
 }
 
-// WithOverlap sets the overlap percentage (0-MaxOverlap).
+// WithOverlap sets the overlap percentage (0-MaxOverlap). Each chunk then
+// also spans the bytes between it and its neighbors, and records the bytes it
+// shares with them, so Reconstruct can join the chunks back into the text.
 func WithOverlap(percent float64) Option {
 	return func(c *config) {
 		if percent < 0 {
@@ -96,247 +171,440 @@ func WithLanguage(lang languages.LanguageName) Option {
 	}
 }
 
-// WithTokenCounter sets a custom token counter.
+// WithTokenCounter sets a custom token counter. A chunker shared between
+// goroutines calls it concurrently, so it must be safe for concurrent use.
 func WithTokenCounter(counter TokenCounter) Option {
 	return func(c *config) {
 		c.tokenCounter = counter
 	}
 }
 
+// WithParseCache sets a cache of parsed syntax trees shared across calls, so
+// chunking unchanged content again skips parsing.
+func WithParseCache(cache *ParseCache) Option {
+	return func(c *config) {
+		c.parseCache = cache
+	}
+}
+
+// WithTimeout bounds the time spent chunking a single input. Chunking stops
+// with context.DeadlineExceeded once the timeout elapses, so a pathological
+// file can't hang a batch run.
+func WithTimeout(timeout time.Duration) Option {
+	return func(c *config) {
+		c.timeout = timeout
+	}
+}
+
 // newDefaultConfig creates a new config with default values.
 func newDefaultConfig() *config {
 	return &config{
 		maxSize:      DefaultMaxSize,
 		overlap:      DefaultOverlap,
 		tokenCounter: &SimpleTokenCounter{},
+		pollInterval: DefaultPollInterval,
+		ctx:          context.Background(),
 	}
 }
 
 // Chunk splits the code into semantically coherent chunks.
 func (c *castChunker) Chunk(code string, opts ...Option) ([]Chunk, error) {
+	return c.ChunkContext(context.Background(), code, opts...)
+}
+
+// ChunkContext splits the code into chunks, stopping early with the
+// context's error if it is cancelled.
+func (c *castChunker) ChunkContext(ctx context.Context, code string, opts ...Option) ([]Chunk, error) {
 	cfg := newDefaultConfig()
+	cfg.ctx = ctx
 
 	for _, opt := range opts {
 		opt(cfg)
 	}
 
-	if cfg.language == "" {
-		return nil, ErrLanguageNotSpecified
-	}
-
-	// Use generic chunking for the generic language
-	if cfg.language == languages.Generic {
-		return c.chunkGeneric(code, cfg)
-	}
-
-	parseResult, err := c.parser.Parse(code, cfg.language)
-	if err != nil {
-		// Fallback to generic chunking if parsing fails
-		return c.chunkGeneric(code, cfg)
-	}
-
-	root := parseResult.Tree.RootNode()
-	chunks, err := c.chunkCode(root, parseResult.Source, cfg)
+	chunks, err := c.chunk(code, cfg)
 	if err != nil {
 		return nil, err
 	}
-
-	// Apply overlap if configured
-	if cfg.overlap > 0 {
-		chunks = c.applyOverlap(chunks, cfg.overlap)
-	}
-
+	renderContext(chunks, "", cfg)
 	return chunks, nil
 }
 
 // ChunkFile chunks code from a file.
 func (c *castChunker) ChunkFile(path string, opts ...Option) ([]Chunk, error) {
+	return c.ChunkFileContext(context.Background(), path, opts...)
+}
+
+// ChunkFileContext chunks code from a file, stopping early with the
+// context's error if it is cancelled.
+func (c *castChunker) ChunkFileContext(ctx context.Context, path string, opts ...Option) ([]Chunk, error) {
+	content, chunks, err := c.chunkFile(ctx, path, opts)
+	manifestConfig(opts).recordFile(FileResult{Path: path, Chunks: chunks, Err: err}, path, content)
+	return chunks, err
+}
+
+// chunkFile reads and chunks the file at path, returning its content unless
+// it couldn't be read.
+func (c *castChunker) chunkFile(ctx context.Context, path string, opts []Option) ([]byte, []Chunk, error) {
+	if err := checkFileSize(path, opts); err != nil {
+		return nil, nil, err
+	}
+
 	content, err := os.ReadFile(path)
 	if err != nil {
-		return nil, fmt.Errorf("failed to read file: %w", err)
+		return nil, nil, &FileError{Path: path, Kind: ErrorIO, Err: fmt.Errorf("failed to read file: %w", err)}
+	}
+
+	chunks, err := c.chunkFileContent(ctx, path, string(content), opts)
+	return content, chunks, err
+}
+
+// ChunkMapped chunks a memory-mapped file without copying its contents.
+// The returned chunks borrow from the mapping and are only valid until the
+// file is closed.
+func (c *castChunker) ChunkMapped(file *MappedFile, opts ...Option) ([]Chunk, error) {
+	chunks, err := c.chunkFileContent(context.Background(), file.Path(), file.String(), opts)
+	// A manifest hashes the mapping in place
+	if cfg := manifestConfig(opts); cfg != nil {
+		cfg.recordFile(FileResult{Path: file.Path(), Chunks: chunks, Err: err}, file.Path(), stringBytes(file.String()))
 	}
+	return chunks, err
+}
 
+// chunkFileContent chunks the content of the file at path, auto-detecting
+// the language from the path if none was specified. Errors are FileErrors.
+func (c *castChunker) chunkFileContent(ctx context.Context, path string, content string, opts []Option) ([]Chunk, error) {
 	cfg := newDefaultConfig()
+	cfg.ctx = ctx
 
 	for _, opt := range opts {
 		opt(cfg)
 	}
 
-	// Auto-detect language if not specified
-	if cfg.language == "" {
-		detectedLang, _ := languages.DetectLanguage(path)
-		cfg.language = detectedLang.Name
-
-		// Use generic chunking if the language doesn't support AST parsing
-		if detectedLang.GetParser == nil {
-			return c.chunkGeneric(string(content), cfg)
+	// Route the file by its document type before its language is settled,
+	// so the options of its type can set the language
+	var docType DocumentType
+	if cfg.router != nil {
+		docType = detectDocumentType(cfg.languageFor(path, content), path, []byte(content[:min(len(content), documentSample)]))
+		if cfg.router.skip[docType] {
+			return nil, nil
 		}
-
-		parseResult, err := c.parser.ParseFile(path, string(content))
-		if err != nil {
-			// Fallback to generic chunking if parsing fails
-			return c.chunkGeneric(string(content), cfg)
+		for _, opt := range cfg.router.routes[docType] {
+			opt(cfg)
 		}
+	}
 
-		root := parseResult.Tree.RootNode()
-		chunks, err := c.chunkCode(root, parseResult.Source, cfg)
+	cfg.language = cfg.languageFor(path, content)
+	if err := cfg.limits.checkBytes(len(content)); err != nil {
+		return nil, newFileError(path, cfg.language, content, err)
+	}
+
+	// Binary files are extracted or rejected before anything else, and other
+	// files transcoded, mapping offsets in the text back to the file
+	text := content
+	var offsets *offsetMap
+	var metadata map[string]string
+	switch {
+	case cfg.binary != BinaryChunk && IsBinary([]byte(content[:min(len(content), binarySample)])):
+		switch cfg.binary {
+		case BinarySkip:
+			return nil, nil
+		case BinaryError:
+			return nil, &FileError{Path: path, Kind: ErrorBinary, Language: cfg.language, Err: ErrBinaryFile}
+		case BinaryStrings:
+			text, offsets = extractStrings(content)
+			metadata = map[string]string{MetadataBinary: "strings"}
+		case BinaryHex:
+			text, offsets = hexDump(content)
+			metadata = map[string]string{MetadataBinary: "hex"}
+		}
+		cfg.language = languages.Generic
+	case cfg.transcode != TranscodeOff:
+		var encoding string
+		var err error
+		text, encoding, offsets, err = transcode([]byte(content), cfg.transcode)
 		if err != nil {
-			return nil, err
+			return nil, newFileError(path, cfg.language, content, err)
 		}
-
-		if cfg.overlap > 0 {
-			chunks = c.applyOverlap(chunks, cfg.overlap)
+		if encoding != EncodingUTF8 {
+			metadata = map[string]string{MetadataEncoding: encoding}
 		}
-
-		return chunks, nil
 	}
 
-	return c.Chunk(string(content), opts...)
-}
+	var generated string
+	if cfg.generated != GeneratedChunk && metadata[MetadataBinary] == "" {
+		generated = detectGenerated(text)
+		if generated != "" && cfg.generated == GeneratedSkip {
+			return nil, nil
+		}
+	}
 
-// chunkCode implements the main CAST algorithm
-func (c *castChunker) chunkCode(node *sitter.Node, source []byte, cfg *config) ([]Chunk, error) {
-	size, err := GetNodeSize(node, source, cfg.tokenCounter)
+	chunks, err := c.chunk(text, cfg)
 	if err != nil {
-		return nil, fmt.Errorf("%w: %w", ErrNodeSize, err)
+		return nil, newFileError(path, cfg.language, text, err)
+	}
+	if generated != "" {
+		chunks = sampleChunks(chunks, GeneratedSampleSize)
+		if metadata == nil {
+			metadata = make(map[string]string, 1)
+		}
+		metadata[MetadataGenerated] = generated
+	}
+	if docType != "" {
+		if metadata == nil {
+			metadata = make(map[string]string, 1)
+		}
+		metadata[MetadataDocumentType] = string(docType)
 	}
 
-	// If node fits within max size, return it as a single chunk
-	if size <= cfg.maxSize {
-		return []Chunk{c.nodeToChunk(node, source, cfg.language)}, nil
+	if offsets != nil {
+		for i := range chunks {
+			chunks[i].StartByte = offsets.original(chunks[i].StartByte)
+			chunks[i].EndByte = offsets.original(chunks[i].EndByte)
+		}
+	}
+	if metadata != nil {
+		for i := range chunks {
+			if chunks[i].Metadata == nil {
+				chunks[i].Metadata = make(map[string]string, len(metadata))
+			}
+			maps.Copy(chunks[i].Metadata, metadata)
+		}
 	}
+	renderContext(chunks, path, cfg)
+	return chunks, nil
+}
 
-	// Otherwise, chunk the node's children
-	childCount := int(node.ChildCount())
-	if childCount == 0 {
-		// Leaf node that's too large - return as is (can't split further)
-		return []Chunk{c.nodeToChunk(node, source, cfg.language)}, nil
+// languageFor returns the language to chunk the file at path with: the
+// explicit language if set, then the first matching override, and finally the
+// language detected from the extension, or from the start of content for
+// extensions several languages share, such as the .m of Objective-C and
+// MATLAB or the .conf of nginx and Apache.
+func (cfg *config) languageFor(path, content string) languages.LanguageName {
+	if cfg.language != "" {
+		return cfg.language
 	}
 
-	children := make([]*sitter.Node, 0, childCount)
-	for i := 0; i < childCount; i++ {
-		if child := node.Child(i); child != nil {
-			children = append(children, child)
+	for _, override := range cfg.languageOverrides {
+		if matchGlob(override.Pattern, path) {
+			return override.Language
 		}
 	}
 
-	return c.chunkNodes(children, source, cfg)
+	if isObjectiveC(path, content) {
+		return languages.ObjectiveC
+	}
+	if lang := confLanguage(path, content); lang != "" {
+		return lang
+	}
+	detectedLang, _ := languages.DetectLanguage(path)
+	return detectedLang.Name
 }
 
-// chunkNodes implements the node grouping logic.
-func (c *castChunker) chunkNodes(nodes []*sitter.Node, source []byte, cfg *config) ([]Chunk, error) {
-	var chunks []Chunk
-	var currentNodes []*sitter.Node
-	currentSize := 0
+// chunk splits the code into chunks using a fully built config.
+func (c *castChunker) chunk(code string, cfg *config) ([]Chunk, error) {
+	if cfg.language == "" {
+		return nil, ErrLanguageNotSpecified
+	}
+
+	if cfg.timeout > 0 {
+		ctx, cancel := context.WithTimeout(cfg.ctx, cfg.timeout)
+		defer cancel()
 
-	for _, node := range nodes {
-		nodeSize, err := GetNodeSize(node, source, cfg.tokenCounter)
+		callCfg := *cfg
+		callCfg.ctx = ctx
+		cfg = &callCfg
+	}
+
+	if err := cfg.limits.checkBytes(len(code)); err != nil {
+		return nil, err
+	}
+	if cfg.limits.MaxSteps > 0 && cfg.steps == nil {
+		callCfg := *cfg
+		callCfg.steps = &stepBudget{left: cfg.limits.MaxSteps, max: cfg.limits.MaxSteps}
+		cfg = &callCfg
+	}
+
+	// Mask sensitive data byte for byte, so offsets into the code still hold,
+	// before any way of chunking it takes content from it. The parts of the
+	// code chunked again on their own have nothing left to mask.
+	if len(cfg.detectors) > 0 {
+		var redacted []Match
+		code, redacted = redact(code, cfg.detectors)
+		callCfg := *cfg
+		callCfg.detectors = nil
+		chunks, err := c.chunkMasked(code, &callCfg)
 		if err != nil {
-			return nil, fmt.Errorf("%w: %w", ErrNodeSize, err)
+			return nil, err
 		}
+		flagRedacted(chunks, redacted)
+		return chunks, nil
+	}
+	return c.chunkMasked(code, cfg)
+}
 
-		// If adding this node would exceed max size
-		if len(currentNodes) > 0 && currentSize+nodeSize > cfg.maxSize {
-			// Save current chunk
-			chunks = append(chunks, c.mergeNodesToChunk(currentNodes, source, cfg.language))
-			currentNodes = nil
-			currentSize = 0
+// chunkMasked splits code whose sensitive data is already masked.
+func (c *castChunker) chunkMasked(code string, cfg *config) ([]Chunk, error) {
+	// Content-defined chunks depend on nothing but the bytes
+	if cfg.contentDefined != nil {
+		chunks, err := chunkContentDefined(code, cfg)
+		if err != nil {
+			return nil, err
 		}
+		if err := cfg.limits.checkChunks(chunks); err != nil {
+			return nil, err
+		}
+		return chunks, nil
+	}
 
-		// If single node exceeds max size, recursively chunk it
-		if nodeSize > cfg.maxSize {
-			if len(currentNodes) > 0 {
-				chunks = append(chunks, c.mergeNodesToChunk(currentNodes, source, cfg.language))
-				currentNodes = nil
-				currentSize = 0
-			}
-
-			subChunks, err := c.chunkCode(node, source, cfg)
-			if err != nil {
-				return nil, err
-			}
-			chunks = append(chunks, subChunks...)
-		} else {
-			currentNodes = append(currentNodes, node)
-			currentSize += nodeSize
+	// Small inputs are taken as is before their content is even looked at,
+	// unless their comments or license header must be split from them
+	if cfg.passes(code) && !cfg.passthrough.Metadata &&
+		cfg.commentMode() == CommentsKeep && cfg.license == LicenseKeep {
+		bom := 0
+		if strings.HasPrefix(code, utf8BOM) {
+			bom = len(utf8BOM)
+		}
+		chunk := passthroughChunk(code, bom, cfg)
+		if cfg.normalizeNewlines {
+			normalizeChunk(&chunk)
 		}
+		return []Chunk{chunk}, nil
 	}
 
-	// Don't forget the last chunk
-	if len(currentNodes) > 0 {
-		chunks = append(chunks, c.mergeNodesToChunk(currentNodes, source, cfg.language))
+	if cfg.notebooks && looksLikeNotebook([]byte(code[:min(len(code), documentSample)])) {
+		if chunks, ok, err := c.chunkNotebook(code, cfg); ok || err != nil {
+			return chunks, err
+		}
+	}
+	if cfg.transcripts && looksLikeTranscript([]byte(code[:min(len(code), documentSample)])) {
+		if chunks, ok, err := c.chunkTranscript(code, cfg); ok || err != nil {
+			return chunks, err
+		}
+	}
+	if cfg.speakers && looksLikeSpeech([]byte(code[:min(len(code), documentSample)])) {
+		if chunks, ok, err := c.chunkSpeech(code, cfg); ok || err != nil {
+			return chunks, err
+		}
+	}
+	if cfg.openAPI && looksLikeOpenAPI([]byte(code[:min(len(code), documentSample)])) {
+		if chunks, ok, err := c.chunkOpenAPI(code, cfg); ok || err != nil {
+			return chunks, err
+		}
+	}
+	if cfg.jsonSubtrees && looksLikeJSON([]byte(code[:min(len(code), documentSample)])) {
+		if chunks, ok, err := c.chunkJSON(code, cfg); ok || err != nil {
+			return chunks, err
+		}
+	}
+	if mode := cfg.commentMode(); mode != CommentsKeep {
+		return c.chunkComments(code, cfg, mode)
 	}
 
-	return chunks, nil
-}
+	// Split the text without a byte order mark or lone carriage returns, and
+	// map the chunks back onto the original code before adding overlap, which
+	// copies content between chunks
+	text, bom, replaced := prepareText(code)
 
-// nodeToChunk converts a single node to a Chunk.
-func (c *castChunker) nodeToChunk(node *sitter.Node, source []byte, language languages.LanguageName) Chunk {
-	startLine, endLine := GetLineNumbers(node)
+	// Leave front matter, or else a license header, out of the text to split
+	header := 0
+	var front map[string]string
+	if cfg.frontMatter {
+		header, front = frontMatter(text, cfg.language)
+	}
+	if cfg.license != LicenseKeep && front == nil {
+		header = licenseHeader(text, cfg.language, cfg.boilerplate)
+	}
+	var chunks []Chunk
+	if rest := text[header:]; cfg.passes(rest) {
+		chunks = []Chunk{passthroughChunk(rest, 0, cfg)}
+	} else {
+		var err error
+		if chunks, err = c.splitPartitioned(rest, cfg); err != nil {
+			return nil, err
+		}
+	}
+	if header > 0 {
+		shiftChunks(chunks, header, strings.Count(text[:header], "\n"))
+	}
+	restoreText(chunks, code, bom, replaced)
+	if front != nil {
+		addFrontMatter(chunks, front, cfg.frontMatterKeys)
+	} else if header > 0 && len(chunks) > 0 {
+		if cfg.license == LicenseExclude {
+			chunks[0].StartByte, chunks[0].StartLine = bom, 1
+			chunks[0].Content = code[bom:chunks[0].EndByte]
+		}
+		if chunks[0].Metadata == nil {
+			chunks[0].Metadata = make(map[string]string, 1)
+		}
+		chunks[0].Metadata[MetadataLicenseHeader] = strconv.Itoa(header)
+	}
 
-	// Collect unique node types
-	nodeTypeSet := make(map[string]bool)
-	collectNodeTypes(node, nodeTypeSet)
+	// Apply overlap if configured
+	if cfg.overlap > 0 {
+		chunks = c.applyOverlap(chunks, code, cfg.overlap)
+	}
 
-	nodeTypes := make([]string, 0, len(nodeTypeSet))
-	for nodeType := range nodeTypeSet {
-		nodeTypes = append(nodeTypes, nodeType)
+	if cfg.normalizeNewlines {
+		for i := range chunks {
+			normalizeChunk(&chunks[i])
+		}
+	}
+	if cfg.links && (cfg.language == languages.Markdown || cfg.language == languages.HTML) {
+		addLinks(chunks, code, bom+header, cfg.language)
+	}
+	if cfg.headingPaths && cfg.language == languages.Markdown {
+		addHeadingPaths(chunks, code, bom+header)
 	}
-	sort.Strings(nodeTypes)
 
-	return Chunk{
-		Content:   GetNodeText(node, source),
-		StartLine: startLine,
-		EndLine:   endLine,
-		StartByte: int(node.StartByte()),
-		EndByte:   int(node.EndByte()),
-		NodeTypes: nodeTypes,
-		Language:  language,
+	if err := cfg.limits.checkChunks(chunks); err != nil {
+		return nil, err
 	}
+	return chunks, nil
 }
 
-// mergeNodesToChunk merges multiple nodes into a single chunk.
-func (c *castChunker) mergeNodesToChunk(nodes []*sitter.Node, source []byte, language languages.LanguageName) Chunk {
-	if len(nodes) == 0 {
-		return Chunk{Language: language}
+// split splits the code into chunks without overlap.
+func (c *castChunker) split(code string, cfg *config) ([]Chunk, error) {
+	// Split languages without a grammar, such as build files, by their blocks
+	if splitter, ok := blockSplitters[cfg.language]; ok {
+		return chunkSpans(code, cfg, splitter.blocks(code), splitter.nested, func(code string) ([]Chunk, error) {
+			return c.splitLines(code, cfg)
+		})
 	}
 
-	// Find the span of all nodes
-	firstNode := nodes[0]
-	lastNode := nodes[len(nodes)-1]
-
-	startByte := firstNode.StartByte()
-	endByte := lastNode.EndByte()
-
-	// Collect unique node types using a map
-	nodeTypeSet := make(map[string]bool)
-	for _, node := range nodes {
-		collectNodeTypes(node, nodeTypeSet)
+	// Split prose at stable anchors if asked to
+	if cfg.stableBoundaries && prose(cfg.language) {
+		return c.splitStable(code, cfg)
 	}
 
-	// Convert map to sorted slice for consistent output
-	nodeTypes := make([]string, 0, len(nodeTypeSet))
-	for nodeType := range nodeTypeSet {
-		nodeTypes = append(nodeTypes, nodeType)
+	// Use generic chunking for the generic language
+	if cfg.language == languages.Generic {
+		return c.splitLines(code, cfg)
 	}
-	sort.Strings(nodeTypes)
 
-	startLine, _ := GetLineNumbers(firstNode)
-	_, endLine := GetLineNumbers(lastNode)
+	chunks, parsed, err := c.chunkSyntax(code, cfg)
+	if err != nil {
+		return nil, err
+	}
+	if !parsed {
+		// A cancelled parse must not fall back to generic chunking
+		if ctxErr := cfg.ctx.Err(); ctxErr != nil {
+			return nil, ctxErr
+		}
 
-	return Chunk{
-		Content:   string(source[startByte:endByte]),
-		StartLine: startLine,
-		EndLine:   endLine,
-		StartByte: int(startByte),
-		EndByte:   int(endByte),
-		NodeTypes: nodeTypes,
-		Language:  language,
+		// Fallback to generic chunking if parsing fails
+		return c.splitLines(code, cfg)
 	}
+	return chunks, nil
 }
 
-// applyOverlap adds overlap between consecutive chunks.
-func (c *castChunker) applyOverlap(chunks []Chunk, overlapPercent float64) []Chunk {
+// applyOverlap adds overlap between consecutive chunks of code. Each chunk
+// is extended over the bytes between it and its neighbors and a share of
+// their content, so its content is still the bytes of code it spans. The
+// bytes it shares with its neighbors are recorded in its metadata under
+// MetadataOverlapBefore and MetadataOverlapAfter.
+func (c *castChunker) applyOverlap(chunks []Chunk, code string, overlapPercent float64) []Chunk {
 	if len(chunks) <= 1 || overlapPercent <= 0 {
 		return chunks
 	}
@@ -349,74 +617,56 @@ func (c *castChunker) applyOverlap(chunks []Chunk, overlapPercent float64) []Chu
 		// Calculate overlap size
 		overlapSize := int(float64(len(chunk.Content)) * (overlapPercent / 100.0))
 
-		// Add content from previous chunk if available
-		if i > 0 && overlapSize > 0 {
+		// Start within the previous chunk, always covering the bytes between
+		// the two so that consecutive chunks leave no gap
+		if i > 0 {
 			prevChunk := chunks[i-1]
-			prevContent := prevChunk.Content
-
-			// Get the last N characters from previous chunk
-			startIdx := max(len(prevContent)-overlapSize, 0)
-
-			chunk.Content = prevContent[startIdx:] + "\n" + chunk.Content
-			// Adjust start position to reflect the overlap
-			chunk.StartByte -= (len(prevContent) - startIdx)
-			chunk.StartLine = prevChunk.StartLine + countLines(prevContent[:startIdx])
+			startIdx := max(len(prevChunk.Content)-overlapSize, 0)
+			chunk.StartByte = prevChunk.StartByte + startIdx
+			chunk.StartLine = lineOf(code, prevChunk.StartByte, prevChunk.StartLine, chunk.StartByte)
 		}
 
-		// Add content from next chunk if available
+		// End within the next chunk
 		if i < len(chunks)-1 && overlapSize > 0 {
 			nextChunk := chunks[i+1]
-			nextContent := nextChunk.Content
-
-			// Get the first N characters from next chunk
-			endIdx := min(overlapSize, len(nextContent))
-
-			chunk.Content = chunk.Content + "\n" + nextContent[:endIdx]
-			// Adjust end position to reflect the overlap
-			chunk.EndByte += endIdx
-			chunk.EndLine = nextChunk.StartLine + countLines(nextContent[:endIdx]) - 1
+			endIdx := min(overlapSize, len(nextChunk.Content))
+			chunk.EndByte = nextChunk.StartByte + endIdx
+			chunk.EndLine = lineOf(code, nextChunk.StartByte, nextChunk.StartLine, chunk.EndByte)
 		}
 
+		chunk.Content = code[chunk.StartByte:chunk.EndByte]
 		overlappedChunks = append(overlappedChunks, chunk)
 	}
 
-	return overlappedChunks
-}
-
-// countLines counts the number of lines in a string.
-func countLines(s string) int {
-	if s == "" {
-		return 0
-	}
-	count := 1
-	for _, r := range s {
-		if r == '\n' {
-			count++
+	// Record the bytes shared by consecutive chunks
+	for i := 1; i < len(overlappedChunks); i++ {
+		if shared := overlappedChunks[i-1].EndByte - overlappedChunks[i].StartByte; shared > 0 {
+			setOverlap(&overlappedChunks[i-1], MetadataOverlapAfter, shared)
+			setOverlap(&overlappedChunks[i], MetadataOverlapBefore, shared)
 		}
 	}
-	return count
-}
-
-// collectNodeTypes recursively collects all unique node types from a node and its descendants.
-func collectNodeTypes(node *sitter.Node, nodeTypeSet map[string]bool) {
-	if node == nil {
-		return
-	}
 
-	nodeType := node.Type()
+	return overlappedChunks
+}
 
-	// Skip pure punctuation/whitespace node types
-	if shouldIncludeNodeType(nodeType) {
-		nodeTypeSet[nodeType] = true
+// lineOf returns the line of the byte at offset in code, given that the byte
+// at from, which is not after it, is on line fromLine. An offset between "\r"
+// and "\n" is still on the line they end.
+func lineOf(code string, from, fromLine, offset int) int {
+	s := code[from:offset]
+	if strings.HasSuffix(s, "\r") && strings.HasPrefix(code[offset:], "\n") {
+		s = s[:len(s)-1]
 	}
+	return fromLine + max(countLines(s)-1, 0)
+}
 
-	// Recursively collect from children
-	childCount := int(node.ChildCount())
-	for i := 0; i < childCount; i++ {
-		if child := node.Child(i); child != nil {
-			collectNodeTypes(child, nodeTypeSet)
-		}
+// countLines counts the number of lines in a string, ending lines at "\n",
+// "\r\n", or a lone "\r".
+func countLines(s string) int {
+	if s == "" {
+		return 0
 	}
+	return strings.Count(s, "\n") + strings.Count(s, "\r") - strings.Count(s, "\r\n") + 1
 }
 
 // shouldIncludeNodeType determines if a node type should be included in the NodeTypes array.
@@ -445,61 +695,78 @@ func isPunctuationOrOperator(r rune) bool {
 	return strings.ContainsRune("{}()[]<>;:,.=*&|!~^%+-/?#@$\\\"'`", r)
 }
 
-// chunkGeneric implements a simple line-based chunking algorithm for unsupported languages.
-// This is used as a fallback when tree-sitter parsing is not available.
-func (c *castChunker) chunkGeneric(code string, cfg *config) ([]Chunk, error) {
-	lines := strings.Split(code, "\n")
-	var chunks []Chunk
-	var currentLines []string
+// splitLines splits code into chunks of whole lines, without overlap.
+func (c *castChunker) splitLines(code string, cfg *config) (chunks []Chunk, err error) {
+	endSpan := cfg.startSpan(SpanSplit)
+	defer func() { endSpan(err) }()
+
+	currentLines := 0
 	currentSize := 0
 	currentStartLine := 1
+	currentStartByte := 0
+	currentEndByte := 0
+
+	// Scan line by line with IndexByte, which is vectorized, instead of
+	// splitting the whole input up front
+	lineNum := 1
+	for offset := 0; offset <= len(code); lineNum++ {
+		// Checking the context is comparatively costly, so only do it periodically
+		if lineNum%1024 == 0 {
+			if err := cfg.ctx.Err(); err != nil {
+				return nil, err
+			}
+		}
+		if err := cfg.step(); err != nil {
+			return nil, err
+		}
+
+		end := strings.IndexByte(code[offset:], '\n')
+		if end < 0 {
+			end = len(code)
+		} else {
+			end += offset
+		}
 
-	for i, line := range lines {
-		lineSize, err := cfg.tokenCounter.CountTokens(line)
+		lineSize, err := cfg.tokenCounter.CountTokens(code[offset:end])
 		if err != nil {
 			return nil, fmt.Errorf("%w: %w", ErrNodeSize, err)
 		}
 
 		// If adding this line would exceed max size and we have content
-		if len(currentLines) > 0 && currentSize+lineSize > cfg.maxSize {
+		if currentLines > 0 && currentSize+lineSize > cfg.maxSize {
 			// Save current chunk
-			chunk := Chunk{
-				Content:   strings.Join(currentLines, "\n"),
-				StartLine: currentStartLine,
-				EndLine:   currentStartLine + len(currentLines) - 1,
-				StartByte: 0, // Generic chunks don't track byte positions accurately
-				EndByte:   0,
-				NodeTypes: []string{"generic"},
-				Language:  cfg.language,
-			}
-			chunks = append(chunks, chunk)
-			currentLines = nil
+			chunks = append(chunks, genericChunk(code, currentStartByte, currentEndByte,
+				currentStartLine, currentStartLine+currentLines-1, cfg.language))
+			currentLines = 0
 			currentSize = 0
-			currentStartLine = i + 1
+			currentStartLine = lineNum
+			currentStartByte = offset
 		}
 
-		currentLines = append(currentLines, line)
+		currentLines++
 		currentSize += lineSize
+		currentEndByte = end
+		offset = end + 1
 	}
 
 	// Don't forget the last chunk
-	if len(currentLines) > 0 {
-		chunk := Chunk{
-			Content:   strings.Join(currentLines, "\n"),
-			StartLine: currentStartLine,
-			EndLine:   currentStartLine + len(currentLines) - 1,
-			StartByte: 0,
-			EndByte:   0,
-			NodeTypes: []string{"generic"},
-			Language:  cfg.language,
-		}
-		chunks = append(chunks, chunk)
-	}
-
-	// Apply overlap if configured
-	if cfg.overlap > 0 {
-		chunks = c.applyOverlap(chunks, cfg.overlap)
+	if currentLines > 0 {
+		chunks = append(chunks, genericChunk(code, currentStartByte, currentEndByte,
+			currentStartLine, currentStartLine+currentLines-1, cfg.language))
 	}
 
 	return chunks, nil
 }
+
+// genericChunk creates a chunk from the lines of code spanning [startByte, endByte).
+func genericChunk(code string, startByte, endByte, startLine, endLine int, language languages.LanguageName) Chunk {
+	return Chunk{
+		Content:   code[startByte:endByte],
+		StartLine: startLine,
+		EndLine:   endLine,
+		StartByte: startByte,
+		EndByte:   endByte,
+		NodeTypes: []string{"generic"},
+		Language:  language,
+	}
+}
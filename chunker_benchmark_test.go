@@ -1,7 +1,12 @@
+//go:build cgo
+
 package chunkx
 
 import (
+	"context"
 	"fmt"
+	"os"
+	"path/filepath"
 	"strings"
 	"testing"
 
@@ -232,3 +237,160 @@ func BenchmarkOverlapChunking(b *testing.B) {
 		})
 	}
 }
+
+// countLinesNaive is the rune-by-rune line counter replaced by countLines
+func countLinesNaive(s string) int {
+	if s == "" {
+		return 0
+	}
+	count := 1
+	for _, r := range s {
+		if r == '\n' {
+			count++
+		}
+	}
+	return count
+}
+
+// BenchmarkCountLines compares vectorized and rune-by-rune newline scanning
+func BenchmarkCountLines(b *testing.B) {
+	largeCode := strings.Repeat(testCode+"\n\n", 100)
+
+	b.Run("Naive", func(b *testing.B) {
+		for b.Loop() {
+			_ = countLinesNaive(largeCode)
+		}
+	})
+	b.Run("Vectorized", func(b *testing.B) {
+		for b.Loop() {
+			_ = countLines(largeCode)
+		}
+	})
+}
+
+// BenchmarkCountFields compares allocation-free field counting to strings.Fields
+func BenchmarkCountFields(b *testing.B) {
+	largeCode := strings.Repeat(testCode+"\n\n", 100)
+
+	b.Run("StringsFields", func(b *testing.B) {
+		for b.Loop() {
+			_ = len(strings.Fields(largeCode))
+		}
+	})
+	b.Run("CountFields", func(b *testing.B) {
+		for b.Loop() {
+			_ = countFields(largeCode)
+		}
+	})
+}
+
+// BenchmarkGenericChunking measures line-based fallback chunking on a large input
+func BenchmarkGenericChunking(b *testing.B) {
+	largeCode := strings.Repeat(testCode+"\n\n", 100)
+	chunker := NewChunker()
+
+	for b.Loop() {
+		_, err := chunker.Chunk(largeCode, WithLanguage(languages.Generic), WithMaxSize(50))
+		if err != nil {
+			b.Fatal(err)
+		}
+	}
+}
+
+// BenchmarkSmallFiles measures chunking of each example source file
+func BenchmarkSmallFiles(b *testing.B) {
+	entries, err := os.ReadDir("testdata/sources")
+	if err != nil {
+		b.Fatal(err)
+	}
+
+	chunker := NewChunker()
+
+	for _, entry := range entries {
+		path := filepath.Join("testdata/sources", entry.Name())
+		b.Run(entry.Name(), func(b *testing.B) {
+			for b.Loop() {
+				_, err := chunker.ChunkFile(path, WithMaxSize(50))
+				if err != nil {
+					b.Fatal(err)
+				}
+			}
+		})
+	}
+}
+
+// BenchmarkHugeFile measures chunking of a single multi-megabyte file
+func BenchmarkHugeFile(b *testing.B) {
+	hugeCode := strings.Repeat(testCode+"\n\n", 2000)
+	chunker := NewChunker()
+
+	b.SetBytes(int64(len(hugeCode)))
+	for b.Loop() {
+		_, err := chunker.Chunk(hugeCode, WithLanguage(languages.Go), WithMaxSize(500))
+		if err != nil {
+			b.Fatal(err)
+		}
+	}
+}
+
+// BenchmarkDeeplyNested measures chunking of deeply nested ASTs, which
+// exercise the recursive descent into oversized nodes
+func BenchmarkDeeplyNested(b *testing.B) {
+	for _, depth := range []int{10, 100, 500} {
+		var sb strings.Builder
+		for i := range depth {
+			fmt.Fprintf(&sb, "if (x > %d) {\n", i)
+		}
+		sb.WriteString("console.log(x);\n")
+		sb.WriteString(strings.Repeat("}\n", depth))
+		code := sb.String()
+
+		chunker := NewChunker()
+
+		b.Run(fmt.Sprintf("Depth%d", depth), func(b *testing.B) {
+			for b.Loop() {
+				_, err := chunker.Chunk(code, WithLanguage(languages.JavaScript), WithMaxSize(20))
+				if err != nil {
+					b.Fatal(err)
+				}
+			}
+		})
+	}
+}
+
+// BenchmarkDirectory measures directory-scale runs through the pipeline
+// for each token counting strategy
+func BenchmarkDirectory(b *testing.B) {
+	dir := b.TempDir()
+	for i := range 200 {
+		path := filepath.Join(dir, fmt.Sprintf("pkg%d", i%10), fmt.Sprintf("file%d.go", i))
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			b.Fatal(err)
+		}
+		if err := os.WriteFile(path, []byte(testCode), 0o644); err != nil {
+			b.Fatal(err)
+		}
+	}
+
+	counters := []struct {
+		name    string
+		counter TokenCounter
+	}{
+		{"SimpleTokenCounter", &SimpleTokenCounter{}},
+		{"ByteCounter", &ByteCounter{}},
+		{"LineCounter", &LineCounter{}},
+	}
+
+	for _, tc := range counters {
+		b.Run(tc.name, func(b *testing.B) {
+			pipeline := NewChunkPipeline(0, WithMaxSize(100), WithTokenCounter(tc.counter))
+			for b.Loop() {
+				for result := range pipeline.RunDir(context.Background(), dir) {
+					if result.Err != nil {
+						b.Fatal(result.Err)
+					}
+				}
+			}
+		})
+	}
+}
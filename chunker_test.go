@@ -1,6 +1,10 @@
+//go:build cgo
+
 package chunkx
 
 import (
+	"errors"
+	"fmt"
 	"os"
 	"path/filepath"
 	"strings"
@@ -345,6 +349,26 @@ Line 5`,
 	}
 }
 
+func TestChunker_GenericByteOffsets(t *testing.T) {
+	code := "alpha beta\ngamma delta\n\nepsilon zeta\n"
+
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithMaxSize(2))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	for i, chunk := range chunks {
+		if got := code[chunk.StartByte:chunk.EndByte]; got != chunk.Content {
+			t.Errorf("chunk %d offsets [%d, %d) select %q, want %q",
+				i, chunk.StartByte, chunk.EndByte, got, chunk.Content)
+		}
+	}
+
+	if last := chunks[len(chunks)-1]; last.EndLine != 5 {
+		t.Errorf("last chunk ends at line %d, want 5", last.EndLine)
+	}
+}
+
 // Helper token counter for testing
 type semicolonCounter struct{}
 
@@ -361,6 +385,246 @@ type ChunkingResult struct {
 
 // TestChunkingExamples tests chunking of real code examples and creates
 // human-readable approval snapshots in JSON format
+func TestChunker_ParseErrors(t *testing.T) {
+	var broken strings.Builder
+	broken.WriteString("package main\n\nfunc valid() int {\n\tx := 1\n\ty := 2\n\treturn x + y\n}\n\n")
+	for i := range 20 {
+		fmt.Fprintf(&broken, "%d := ) ( , %d\n", i, i)
+	}
+
+	code := broken.String()
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Go), WithMaxSize(20), WithOverlap(0))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	var valid, degraded int
+	for _, chunk := range chunks {
+		if got := code[chunk.StartByte:chunk.EndByte]; got != chunk.Content {
+			t.Errorf("content %q doesn't match its bytes %q", chunk.Content, got)
+		}
+		if want := countLines(code[:chunk.StartByte] + "x"); chunk.StartLine != want {
+			t.Errorf("chunk %q starts on line %d, want %d", chunk.Content, chunk.StartLine, want)
+		}
+		switch {
+		case chunk.Metadata[MetadataParseDegraded] == "true":
+			degraded++
+		case strings.Contains(chunk.Content, "func valid"):
+			valid++
+		}
+	}
+
+	// The valid function keeps its syntax-aware chunk, and the broken lines
+	// are split into several chunks rather than one oversized one
+	if valid != 1 {
+		t.Errorf("expected the valid function in an undegraded chunk, got %+v", chunks)
+	}
+	if degraded < 2 {
+		t.Errorf("expected the syntax error split into degraded chunks, got %+v", chunks)
+	}
+}
+
+func TestChunker_DepthLimit(t *testing.T) {
+	code := "x = " + strings.Repeat("[", 2000) + strings.Repeat("]", 2000) + "\n"
+
+	_, err := NewChunker().Chunk(code, WithLanguage(languages.Python), WithLimits(Limits{MaxDepth: 100}))
+	var limitErr *LimitExceeded
+	if !errors.As(err, &limitErr) || limitErr.Limit != LimitDepth {
+		t.Fatalf("expected the depth limit exceeded, got %v", err)
+	}
+
+	if _, err := NewChunker().Chunk(code, WithLanguage(languages.Python), WithLimits(Limits{MaxDepth: 10000})); err != nil {
+		t.Errorf("unexpected error under the depth limit: %v", err)
+	}
+}
+
+func TestChunker_WithSoftLimit(t *testing.T) {
+	code := "package main\n\nfunc add(a, b int) int {\n\treturn a + b\n}\n"
+	fn := code[strings.Index(code, "func"):strings.LastIndex(code, "}")+1]
+
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Go), WithMaxSize(10))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	for _, chunk := range chunks {
+		if chunk.Content == fn || chunk.Metadata[MetadataOverBudget] != "" {
+			t.Fatalf("expected the function split without a soft limit, got %+v", chunks)
+		}
+	}
+
+	chunks, err = NewChunker().Chunk(code, WithLanguage(languages.Go), WithMaxSize(10), WithSoftLimit(20))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	kept := false
+	for _, chunk := range chunks {
+		if overBudget := chunk.Metadata[MetadataOverBudget] == "true"; overBudget != (chunk.Content == fn) {
+			t.Errorf("expected only the function over budget, got %+v", chunk)
+		}
+		kept = kept || chunk.Content == fn
+	}
+	if !kept {
+		t.Errorf("expected the function kept whole, got %+v", chunks)
+	}
+	if err := ValidateChunks(chunks, code, WithMaxSize(10), WithSoftLimit(20)); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+}
+
+func TestChunker_WithFencedCode(t *testing.T) {
+	code := "# Title\n\nIntro.\n\n```python\ndef a():\n    return 1\n\n\ndef b():\n    return 2\n```\n"
+	opts := []Option{WithLanguage(languages.Markdown), WithMaxSize(6), WithFencedCode()}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	var fenced []Chunk
+	for _, chunk := range chunks {
+		if chunk.Metadata[MetadataFenceLanguage] == string(languages.Python) {
+			fenced = append(fenced, chunk)
+		}
+	}
+	if len(fenced) != 2 {
+		t.Fatalf("expected the fenced block split into its two functions, got %+v", chunks)
+	}
+	if !strings.HasPrefix(fenced[0].Content, "```python\ndef a():") || strings.Contains(fenced[0].Content, "def b") {
+		t.Errorf("expected the first function with the opening fence, got %q", fenced[0].Content)
+	}
+	if !strings.HasPrefix(fenced[1].Content, "def b():") || !strings.HasSuffix(strings.TrimSpace(fenced[1].Content), "```") {
+		t.Errorf("expected the second function with the closing fence, got %q", fenced[1].Content)
+	}
+	for _, chunk := range fenced {
+		if chunk.Language != languages.Python {
+			t.Errorf("expected the fenced chunks in Python, got %s", chunk.Language)
+		}
+	}
+}
+
+func TestChunker_WithTableHeaders(t *testing.T) {
+	tests := []struct {
+		name    string
+		lang    languages.LanguageName
+		code    string
+		maxSize int
+		header  string
+		first   string // Content of the first row
+	}{
+		{
+			"markdown", languages.Markdown,
+			"| Name | Value |\n| --- | --- |\n| a | 1 |\n| b | 2 |\n| c | 3 |\n| d | 4 |\n",
+			20, "Name", "| a |",
+		},
+		{
+			"html", languages.HTML,
+			"<table>\n<tr><th>Name Value</th></tr>\n<tr><td>a 1 a 1</td></tr>\n<tr><td>b 2 b 2</td></tr>\n<tr><td>c 3 c 3</td></tr>\n</table>\n",
+			11, "Name", "a 1",
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			opts := []Option{WithLanguage(tt.lang), WithMaxSize(tt.maxSize), WithTableHeaders()}
+			chunks, err := NewChunker().Chunk(tt.code, opts...)
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if err := ValidateChunks(chunks, tt.code, opts...); err != nil {
+				t.Errorf("unexpected validation error: %v", err)
+			}
+
+			counter := &SimpleTokenCounter{}
+			groups := 0
+			for _, chunk := range chunks {
+				header, ok := chunk.Metadata[MetadataTableHeader]
+				if !ok {
+					continue
+				}
+				groups++
+				if !strings.Contains(header, tt.header) || strings.Contains(chunk.Content, tt.header) {
+					t.Errorf("expected the header recorded instead of chunked, got %q with header %q", chunk.Content, header)
+				}
+				if size, _ := counter.CountTokens(header + "\n" + chunk.Content); size > tt.maxSize {
+					t.Errorf("expected %q to fit with its header, got %d tokens", chunk.Content, size)
+				}
+				if strings.Contains(chunk.Content, tt.first) {
+					t.Errorf("expected the first rows with the header, got %q", chunk.Content)
+				}
+			}
+			if groups == 0 {
+				t.Errorf("expected the table split into row groups, got %+v", chunks)
+			}
+		})
+	}
+}
+
+func TestChunker_WithContextTemplate_SymbolPath(t *testing.T) {
+	code := "class Server:\n    def handle(self):\n        return 1\n\n    def close(self):\n        return 2\n"
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Python), WithMaxSize(4), WithContextTemplate("{symbol_path} in server.py"))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	for _, tt := range []struct{ text, path string }{
+		{"return 1", "Server.handle"},
+		{"return 2", "Server.close"},
+	} {
+		for _, chunk := range chunks {
+			if !strings.Contains(chunk.Content, tt.text) {
+				continue
+			}
+			if got := chunk.Metadata[MetadataSymbolPath]; got != tt.path {
+				t.Errorf("expected the chunk %q under %q, got %q", chunk.Content, tt.path, got)
+			}
+			if got, want := chunk.Metadata[MetadataContext], tt.path+" in server.py"; got != want {
+				t.Errorf("got context %q, want %q", got, want)
+			}
+		}
+	}
+}
+
+func TestChunker_Concurrent(t *testing.T) {
+	inputs := []struct {
+		code string
+		lang languages.LanguageName
+	}{
+		{"package main\n\nfunc a() int {\n\treturn 1\n}\n\nfunc b() int {\n\treturn 2\n}\n", languages.Go},
+		{"def a():\n    return 1\n\n\ndef b():\n    return 2\n", languages.Python},
+		{"fn a() -> i32 {\n    1\n}\n\nfn b() -> i32 {\n    2\n}\n", languages.Rust},
+	}
+
+	chunker := NewChunker()
+	want := make([]string, len(inputs))
+	for i, in := range inputs {
+		chunks, err := chunker.Chunk(in.code, WithLanguage(in.lang), WithMaxSize(5))
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		want[i] = fmt.Sprintf("%+v", chunks)
+	}
+
+	errs := make(chan error, 8*len(inputs))
+	for range 8 {
+		for i, in := range inputs {
+			go func() {
+				chunks, err := chunker.Chunk(in.code, WithLanguage(in.lang), WithMaxSize(5))
+				if err == nil && fmt.Sprintf("%+v", chunks) != want[i] {
+					err = fmt.Errorf("%s: chunks differ from a serial call: %+v", in.lang, chunks)
+				}
+				errs <- err
+			}()
+		}
+	}
+	for range cap(errs) {
+		if err := <-errs; err != nil {
+			t.Error(err)
+		}
+	}
+}
+
 func TestChunkingExamples(t *testing.T) {
 	sourcesDir := "testdata/sources"
 
@@ -377,7 +641,12 @@ func TestChunkingExamples(t *testing.T) {
 			continue
 		}
 
+		// The rest of the corpus is covered by the boundary snapshots of
+		// the chunkxtest package
 		filename := entry.Name()
+		if _, err := os.Stat(filepath.Join("testdata", "chunker_test.TestChunkingExamples."+filename+".approved.json")); err != nil {
+			continue
+		}
 		filepath := filepath.Join(sourcesDir, filename)
 
 		t.Run(filename, func(t *testing.T) {
@@ -0,0 +1,200 @@
+package chunkx
+
+import (
+	"encoding/hex"
+	"iter"
+	"maps"
+	"unsafe"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// ChunkSet holds chunks column by column rather than as a slice of Chunk:
+// the contents of all chunks back to back in one buffer, the positions in
+// integer vectors, and paths, languages, and node types as indexes into
+// dictionaries of their distinct values. Millions of chunks then take a
+// handful of allocations instead of several each, and the columns can be
+// handed to Apache Arrow or another columnar format without converting them,
+// see Columns.
+//
+// The zero value is an empty set ready to use. A ChunkSet is not safe for
+// concurrent use while chunks are added.
+type ChunkSet struct {
+	cols      ChunkColumns
+	paths     dictionary
+	languages dictionary
+	nodeTypes dictionary
+	metadata  map[int]map[string]string // Metadata of the few chunks that have any
+}
+
+// ChunkColumns are the columns of a ChunkSet, each with a value for every
+// chunk, except the dictionaries, which hold the distinct values the index
+// columns refer to. Offsets are int64, as Arrow's large types use. The
+// columns are shared with the set and must not be modified.
+type ChunkColumns struct {
+	Content        []byte  // Contents of the chunks, back to back
+	ContentOffsets []int64 // Chunk i spans Content[ContentOffsets[i]:ContentOffsets[i+1]]
+	IDs            []byte  // Chunk.ID of each chunk as 16 binary bytes
+	StartLines     []int64
+	EndLines       []int64
+	StartBytes     []int64
+	EndBytes       []int64
+
+	Paths         []string // Dictionary of the paths of the files of chunks
+	PathIndex     []int32
+	Languages     []languages.LanguageName // Dictionary of the languages of chunks
+	LanguageIndex []int32
+
+	NodeTypes       []string // Dictionary of node types
+	NodeTypeIndex   []int32  // Node types of all chunks, back to back
+	NodeTypeOffsets []int64  // Chunk i's are NodeTypeIndex[NodeTypeOffsets[i]:NodeTypeOffsets[i+1]]
+}
+
+// dictionary numbers the distinct strings of a column.
+type dictionary struct {
+	values []string
+	index  map[string]int32
+}
+
+// add returns the number of s, adding it if it is new.
+func (d *dictionary) add(s string) int32 {
+	if i, ok := d.index[s]; ok {
+		return i
+	}
+	if d.index == nil {
+		d.index = make(map[string]int32)
+	}
+	i := int32(len(d.values))
+	d.values = append(d.values, s)
+	d.index[s] = i
+	return i
+}
+
+// Add appends the chunks of the file at path. Their contents are copied into
+// the set.
+func (s *ChunkSet) Add(path string, chunks ...Chunk) {
+	c := &s.cols
+	if c.ContentOffsets == nil {
+		c.ContentOffsets = []int64{0}
+		c.NodeTypeOffsets = []int64{0}
+	}
+
+	pathIndex := s.paths.add(path)
+	for _, chunk := range chunks {
+		if len(chunk.Metadata) > 0 {
+			if s.metadata == nil {
+				s.metadata = make(map[int]map[string]string)
+			}
+			s.metadata[s.Len()] = maps.Clone(chunk.Metadata)
+		}
+
+		c.Content = append(c.Content, chunk.Content...)
+		c.ContentOffsets = append(c.ContentOffsets, int64(len(c.Content)))
+		id := chunk.binaryID()
+		c.IDs = append(c.IDs, id[:]...)
+		c.StartLines = append(c.StartLines, int64(chunk.StartLine))
+		c.EndLines = append(c.EndLines, int64(chunk.EndLine))
+		c.StartBytes = append(c.StartBytes, int64(chunk.StartByte))
+		c.EndBytes = append(c.EndBytes, int64(chunk.EndByte))
+
+		c.PathIndex = append(c.PathIndex, pathIndex)
+		c.LanguageIndex = append(c.LanguageIndex, s.languages.add(string(chunk.Language)))
+		for _, nodeType := range chunk.NodeTypes {
+			c.NodeTypeIndex = append(c.NodeTypeIndex, s.nodeTypes.add(nodeType))
+		}
+		c.NodeTypeOffsets = append(c.NodeTypeOffsets, int64(len(c.NodeTypeIndex)))
+	}
+}
+
+// Len returns the number of chunks in the set.
+func (s *ChunkSet) Len() int {
+	return len(s.cols.PathIndex)
+}
+
+// Content returns the content of chunk i. The string shares the memory of
+// the set rather than copying it.
+func (s *ChunkSet) Content(i int) string {
+	start, end := s.cols.ContentOffsets[i], s.cols.ContentOffsets[i+1]
+	if start == end {
+		return ""
+	}
+	// Content is only ever appended to, so the bytes never change
+	return unsafe.String(&s.cols.Content[start], int(end-start))
+}
+
+// Path returns the path of the file of chunk i.
+func (s *ChunkSet) Path(i int) string {
+	return s.paths.values[s.cols.PathIndex[i]]
+}
+
+// Language returns the language of chunk i.
+func (s *ChunkSet) Language(i int) languages.LanguageName {
+	return languages.LanguageName(s.languages.values[s.cols.LanguageIndex[i]])
+}
+
+// ID returns the Chunk.ID of chunk i, without hashing its content again.
+func (s *ChunkSet) ID(i int) string {
+	return hex.EncodeToString(s.cols.IDs[16*i : 16*i+16])
+}
+
+// Chunk returns chunk i. Its content shares the memory of the set, while its
+// node types and metadata are copies.
+func (s *ChunkSet) Chunk(i int) Chunk {
+	c := &s.cols
+	chunk := Chunk{
+		Content:   s.Content(i),
+		StartLine: int(c.StartLines[i]),
+		EndLine:   int(c.EndLines[i]),
+		StartByte: int(c.StartBytes[i]),
+		EndByte:   int(c.EndBytes[i]),
+		NodeTypes: make([]string, 0, c.NodeTypeOffsets[i+1]-c.NodeTypeOffsets[i]),
+		Language:  s.Language(i),
+		Metadata:  maps.Clone(s.metadata[i]),
+	}
+	for _, nodeType := range c.NodeTypeIndex[c.NodeTypeOffsets[i]:c.NodeTypeOffsets[i+1]] {
+		chunk.NodeTypes = append(chunk.NodeTypes, s.nodeTypes.values[nodeType])
+	}
+	return chunk
+}
+
+// All returns an iterator over the paths and chunks of the set, in the order
+// they were added.
+func (s *ChunkSet) All() iter.Seq2[string, Chunk] {
+	return func(yield func(string, Chunk) bool) {
+		for i := range s.Len() {
+			if !yield(s.Path(i), s.Chunk(i)) {
+				return
+			}
+		}
+	}
+}
+
+// Filter returns a new set of the chunks i for which keep returns true, in
+// their order. The predicate is given indexes rather than chunks, so it can
+// test columns such as Path or Language without materializing every chunk.
+func (s *ChunkSet) Filter(keep func(i int) bool) *ChunkSet {
+	filtered := &ChunkSet{}
+	for i := range s.Len() {
+		if keep(i) {
+			filtered.Add(s.Path(i), s.Chunk(i))
+		}
+	}
+	return filtered
+}
+
+// Columns returns the columns of the set. Chunk metadata, which few chunks
+// have, is not part of them, and is read with Chunk.
+func (s *ChunkSet) Columns() ChunkColumns {
+	cols := s.cols
+	if cols.ContentOffsets == nil {
+		cols.ContentOffsets = []int64{0}
+		cols.NodeTypeOffsets = []int64{0}
+	}
+	cols.Paths = s.paths.values
+	cols.Languages = make([]languages.LanguageName, len(s.languages.values))
+	for i, language := range s.languages.values {
+		cols.Languages[i] = languages.LanguageName(language)
+	}
+	cols.NodeTypes = s.nodeTypes.values
+	return cols
+}
@@ -0,0 +1,79 @@
+package chunkx
+
+import (
+	"maps"
+	"reflect"
+	"slices"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunkSet(t *testing.T) {
+	files := map[string][]Chunk{
+		"a.md": {
+			{Content: "# Title\n", StartLine: 1, EndLine: 2, EndByte: 8, NodeTypes: []string{"atx_heading"}, Language: languages.Markdown},
+			{Content: "Body text.\n", StartLine: 2, EndLine: 3, StartByte: 8, EndByte: 19, NodeTypes: []string{"paragraph"}, Language: languages.Markdown},
+		},
+		"b.go": {
+			{Content: "", StartLine: 1, EndLine: 1, Language: languages.Go, Metadata: map[string]string{MetadataArchive: "src.zip"}},
+			{Content: "package b\n", StartLine: 1, EndLine: 2, EndByte: 10, NodeTypes: []string{"package_clause", "atx_heading"}, Language: languages.Go},
+		},
+	}
+
+	var set ChunkSet
+	var want []Chunk
+	for _, path := range slices.Sorted(maps.Keys(files)) {
+		set.Add(path, files[path]...)
+		want = append(want, files[path]...)
+	}
+	if set.Len() != 4 {
+		t.Fatalf("Len() = %d, want 4", set.Len())
+	}
+
+	var i int
+	for path, got := range set.All() {
+		// Chunks without node types come back with an empty slice
+		if len(want[i].NodeTypes) == 0 {
+			got.NodeTypes = want[i].NodeTypes
+		}
+		if wantPath := []string{"a.md", "a.md", "b.go", "b.go"}[i]; path != wantPath || !reflect.DeepEqual(got, want[i]) {
+			t.Errorf("chunk %d = %q %+v, want %q %+v", i, path, got, wantPath, want[i])
+		}
+		if set.ID(i) != want[i].ID() {
+			t.Errorf("ID(%d) = %s, want %s", i, set.ID(i), want[i].ID())
+		}
+		i++
+	}
+
+	cols := set.Columns()
+	if string(cols.Content) != "# Title\nBody text.\npackage b\n" || !slices.Equal(cols.ContentOffsets, []int64{0, 8, 19, 19, 29}) {
+		t.Errorf("unexpected content column %q with offsets %v", cols.Content, cols.ContentOffsets)
+	}
+	if !slices.Equal(cols.Paths, []string{"a.md", "b.go"}) || !slices.Equal(cols.PathIndex, []int32{0, 0, 1, 1}) {
+		t.Errorf("unexpected path dictionary %q with index %v", cols.Paths, cols.PathIndex)
+	}
+	if !slices.Equal(cols.NodeTypes, []string{"atx_heading", "paragraph", "package_clause"}) ||
+		!slices.Equal(cols.NodeTypeIndex, []int32{0, 1, 2, 0}) || !slices.Equal(cols.NodeTypeOffsets, []int64{0, 1, 2, 2, 4}) {
+		t.Errorf("unexpected node types %q with index %v and offsets %v", cols.NodeTypes, cols.NodeTypeIndex, cols.NodeTypeOffsets)
+	}
+
+	goChunks := set.Filter(func(i int) bool { return set.Language(i) == languages.Go })
+	if goChunks.Len() != 2 || goChunks.Path(0) != "b.go" || goChunks.Content(1) != "package b\n" {
+		t.Errorf("expected the two Go chunks, got %d", goChunks.Len())
+	}
+	if got := goChunks.Chunk(0).Metadata; got[MetadataArchive] != "src.zip" {
+		t.Errorf("expected the metadata of the filtered chunk, got %v", got)
+	}
+}
+
+func TestChunkSet_Empty(t *testing.T) {
+	var set ChunkSet
+	cols := set.Columns()
+	if set.Len() != 0 || !slices.Equal(cols.ContentOffsets, []int64{0}) {
+		t.Errorf("expected an empty set with one offset, got %d chunks and %v", set.Len(), cols.ContentOffsets)
+	}
+	for range set.All() {
+		t.Error("expected no chunks")
+	}
+}
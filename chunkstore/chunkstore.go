@@ -0,0 +1,179 @@
+// Package chunkstore keeps chunks and the files they were read from in a
+// SQLite database. Used as the index of a ChunkPipeline, it makes corpus runs
+// resumable: files chunked before an interruption, and files unchanged since
+// an earlier run, are skipped.
+//
+//	db, err := sql.Open("sqlite", "chunks.db")
+//	...
+//	store := &chunkstore.Store{DB: db}
+//	if err := store.Init(ctx); err != nil {
+//		return err
+//	}
+//	pipeline := chunkx.NewChunkPipeline(0, chunkx.WithIndex(store))
+//
+// The caller opens the database with a SQLite driver of their choice, such
+// as modernc.org/sqlite or github.com/mattn/go-sqlite3.
+package chunkstore
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"sync"
+	"time"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+// schema creates the tables of a Store.
+var schema = []string{
+	`CREATE TABLE IF NOT EXISTS files (
+		path       TEXT PRIMARY KEY,
+		hash       TEXT NOT NULL,
+		chunks     INTEGER NOT NULL,
+		indexed_at INTEGER NOT NULL
+	)`,
+	`CREATE TABLE IF NOT EXISTS chunks (
+		path       TEXT NOT NULL REFERENCES files (path) ON DELETE CASCADE,
+		seq        INTEGER NOT NULL,
+		id         TEXT NOT NULL,
+		language   TEXT NOT NULL,
+		start_line INTEGER NOT NULL,
+		end_line   INTEGER NOT NULL,
+		start_byte INTEGER NOT NULL,
+		end_byte   INTEGER NOT NULL,
+		node_types TEXT NOT NULL,
+		metadata   TEXT,
+		content    TEXT NOT NULL,
+		PRIMARY KEY (path, seq)
+	)`,
+	`CREATE INDEX IF NOT EXISTS chunks_id ON chunks (id)`,
+}
+
+// Store records files and their chunks in a SQLite database. It implements
+// chunkx.FileIndex. The files table holds the path, content hash, chunk
+// count, and indexing time of every file; the chunks table holds the chunks
+// of each file in order, with node types and metadata as JSON.
+//
+// A Store is safe for concurrent use. Writes are serialized, since SQLite
+// allows a single writer at a time.
+type Store struct {
+	DB *sql.DB
+
+	mu sync.Mutex // Serializes writes
+}
+
+// Init creates the tables of the store if they don't exist yet.
+func (s *Store) Init(ctx context.Context) error {
+	for _, stmt := range schema {
+		if _, err := s.DB.ExecContext(ctx, stmt); err != nil {
+			return fmt.Errorf("chunkstore: %w", err)
+		}
+	}
+	return nil
+}
+
+// Hash implements chunkx.FileIndex.
+func (s *Store) Hash(ctx context.Context, path string) (string, error) {
+	var hash string
+	err := s.DB.QueryRowContext(ctx, `SELECT hash FROM files WHERE path = ?`, path).Scan(&hash)
+	if errors.Is(err, sql.ErrNoRows) {
+		return "", nil
+	}
+	if err != nil {
+		return "", fmt.Errorf("chunkstore: %w", err)
+	}
+	return hash, nil
+}
+
+// Record implements chunkx.FileIndex. The file and its chunks are written in
+// a single transaction, so an interrupted run never leaves a file recorded
+// with only some of its chunks.
+func (s *Store) Record(ctx context.Context, result chunkx.FileResult, hash string) error {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	tx, err := s.DB.BeginTx(ctx, nil)
+	if err != nil {
+		return fmt.Errorf("chunkstore: %w", err)
+	}
+	defer tx.Rollback()
+
+	if _, err := tx.ExecContext(ctx, `DELETE FROM chunks WHERE path = ?`, result.Path); err != nil {
+		return fmt.Errorf("chunkstore: %w", err)
+	}
+	if _, err := tx.ExecContext(ctx,
+		`INSERT INTO files (path, hash, chunks, indexed_at) VALUES (?, ?, ?, ?)
+		ON CONFLICT (path) DO UPDATE SET hash = excluded.hash, chunks = excluded.chunks, indexed_at = excluded.indexed_at`,
+		result.Path, hash, len(result.Chunks), time.Now().Unix()); err != nil {
+		return fmt.Errorf("chunkstore: %w", err)
+	}
+
+	for i, chunk := range result.Chunks {
+		nodeTypes, err := json.Marshal(chunk.NodeTypes)
+		if err != nil {
+			return fmt.Errorf("chunkstore: %w", err)
+		}
+		var metadata any
+		if len(chunk.Metadata) > 0 {
+			data, err := json.Marshal(chunk.Metadata)
+			if err != nil {
+				return fmt.Errorf("chunkstore: %w", err)
+			}
+			metadata = string(data)
+		}
+
+		if _, err := tx.ExecContext(ctx,
+			`INSERT INTO chunks (path, seq, id, language, start_line, end_line, start_byte, end_byte, node_types, metadata, content)
+			VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)`,
+			result.Path, i, chunk.ID(), chunk.Language.String(), chunk.StartLine, chunk.EndLine,
+			chunk.StartByte, chunk.EndByte, string(nodeTypes), metadata, chunk.Content); err != nil {
+			return fmt.Errorf("chunkstore: %w", err)
+		}
+	}
+
+	if err := tx.Commit(); err != nil {
+		return fmt.Errorf("chunkstore: %w", err)
+	}
+	return nil
+}
+
+// Chunks returns the chunks recorded for the file at path, in order.
+func (s *Store) Chunks(ctx context.Context, path string) ([]chunkx.Chunk, error) {
+	rows, err := s.DB.QueryContext(ctx,
+		`SELECT language, start_line, end_line, start_byte, end_byte, node_types, metadata, content
+		FROM chunks WHERE path = ? ORDER BY seq`, path)
+	if err != nil {
+		return nil, fmt.Errorf("chunkstore: %w", err)
+	}
+	defer rows.Close()
+
+	var chunks []chunkx.Chunk
+	for rows.Next() {
+		var chunk chunkx.Chunk
+		var language, nodeTypes string
+		var metadata sql.NullString
+		if err := rows.Scan(&language, &chunk.StartLine, &chunk.EndLine, &chunk.StartByte, &chunk.EndByte,
+			&nodeTypes, &metadata, &chunk.Content); err != nil {
+			return nil, fmt.Errorf("chunkstore: %w", err)
+		}
+
+		chunk.Language = languages.LanguageName(language)
+		if err := json.Unmarshal([]byte(nodeTypes), &chunk.NodeTypes); err != nil {
+			return nil, fmt.Errorf("chunkstore: invalid node types of %s: %w", path, err)
+		}
+		if metadata.Valid {
+			if err := json.Unmarshal([]byte(metadata.String), &chunk.Metadata); err != nil {
+				return nil, fmt.Errorf("chunkstore: invalid metadata of %s: %w", path, err)
+			}
+		}
+		chunks = append(chunks, chunk)
+	}
+	if err := rows.Err(); err != nil {
+		return nil, fmt.Errorf("chunkstore: %w", err)
+	}
+	return chunks, nil
+}
@@ -0,0 +1,167 @@
+package chunkstore
+
+import (
+	"context"
+	"database/sql"
+	"database/sql/driver"
+	"io"
+	"reflect"
+	"strings"
+	"sync"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+// fakeDB is a database/sql driver that understands just the statements of a
+// Store, keeping its tables in memory. Transactions apply writes immediately.
+type fakeDB struct {
+	mu     sync.Mutex
+	files  map[string]string           // Hash by path
+	chunks map[string][][]driver.Value // Inserted arguments by path
+}
+
+func (d *fakeDB) Open(string) (driver.Conn, error) {
+	return d, nil
+}
+
+func (d *fakeDB) Prepare(query string) (driver.Stmt, error) {
+	return &fakeStmt{d: d, query: strings.Join(strings.Fields(query), " ")}, nil
+}
+
+func (d *fakeDB) Close() error {
+	return nil
+}
+
+func (d *fakeDB) Begin() (driver.Tx, error) {
+	return d, nil
+}
+
+func (d *fakeDB) Commit() error {
+	return nil
+}
+
+func (d *fakeDB) Rollback() error {
+	return nil
+}
+
+// fakeStmt is a statement of fakeDB.
+type fakeStmt struct {
+	d     *fakeDB
+	query string
+}
+
+func (s *fakeStmt) Close() error {
+	return nil
+}
+
+func (s *fakeStmt) NumInput() int {
+	return -1
+}
+
+func (s *fakeStmt) Exec(args []driver.Value) (driver.Result, error) {
+	s.d.mu.Lock()
+	defer s.d.mu.Unlock()
+
+	switch {
+	case strings.HasPrefix(s.query, "DELETE FROM chunks"):
+		delete(s.d.chunks, args[0].(string))
+	case strings.HasPrefix(s.query, "INSERT INTO files"):
+		s.d.files[args[0].(string)] = args[1].(string)
+	case strings.HasPrefix(s.query, "INSERT INTO chunks"):
+		path := args[0].(string)
+		s.d.chunks[path] = append(s.d.chunks[path], args)
+	}
+	return driver.RowsAffected(1), nil
+}
+
+func (s *fakeStmt) Query(args []driver.Value) (driver.Rows, error) {
+	s.d.mu.Lock()
+	defer s.d.mu.Unlock()
+
+	path := args[0].(string)
+	if strings.HasPrefix(s.query, "SELECT hash") {
+		if hash, ok := s.d.files[path]; ok {
+			return &fakeRows{rows: [][]driver.Value{{hash}}}, nil
+		}
+		return &fakeRows{}, nil
+	}
+
+	// Select the columns after path, seq, and id from the inserted chunks
+	rows := &fakeRows{}
+	for _, args := range s.d.chunks[path] {
+		rows.rows = append(rows.rows, args[3:])
+	}
+	return rows, nil
+}
+
+// fakeRows are the rows of a fakeStmt query.
+type fakeRows struct {
+	rows [][]driver.Value
+}
+
+func (r *fakeRows) Columns() []string {
+	if len(r.rows) == 0 {
+		return []string{"hash"}
+	}
+	return make([]string, len(r.rows[0]))
+}
+
+func (r *fakeRows) Close() error {
+	return nil
+}
+
+func (r *fakeRows) Next(dest []driver.Value) error {
+	if len(r.rows) == 0 {
+		return io.EOF
+	}
+	copy(dest, r.rows[0])
+	r.rows = r.rows[1:]
+	return nil
+}
+
+func TestStore(t *testing.T) {
+	sql.Register("chunkstore-fake", &fakeDB{files: map[string]string{}, chunks: map[string][][]driver.Value{}})
+	db, err := sql.Open("chunkstore-fake", "")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	defer db.Close()
+
+	ctx := context.Background()
+	store := &Store{DB: db}
+	if err := store.Init(ctx); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if hash, err := store.Hash(ctx, "a.go"); err != nil || hash != "" {
+		t.Fatalf("Hash of an unrecorded file = %q, %v", hash, err)
+	}
+
+	chunks := []chunkx.Chunk{
+		{Content: "package a", StartLine: 1, EndLine: 1, EndByte: 9, NodeTypes: []string{"package_clause"}, Language: "go"},
+		{Content: "func A() {}", StartLine: 3, EndLine: 3, StartByte: 11, EndByte: 22, NodeTypes: []string{"function_declaration"}, Language: "go", Metadata: map[string]string{"k": "v"}},
+	}
+	if err := store.Record(ctx, chunkx.FileResult{Path: "a.go", Chunks: chunks}, "hash1"); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if hash, err := store.Hash(ctx, "a.go"); err != nil || hash != "hash1" {
+		t.Errorf("Hash = %q, %v, want hash1", hash, err)
+	}
+	got, err := store.Chunks(ctx, "a.go")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if !reflect.DeepEqual(got, chunks) {
+		t.Errorf("Chunks = %+v, want %+v", got, chunks)
+	}
+
+	// Recording a file again replaces its chunks
+	if err := store.Record(ctx, chunkx.FileResult{Path: "a.go", Chunks: chunks[:1]}, "hash2"); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if got, err := store.Chunks(ctx, "a.go"); err != nil || len(got) != 1 {
+		t.Errorf("Chunks = %+v, %v, want one chunk", got, err)
+	}
+}
@@ -0,0 +1,184 @@
+// Package chunkxtest runs snapshot tests of chunk boundaries over a corpus of
+// source files, so that boundary changes from a grammar upgrade or a new
+// splitter show up as golden file diffs:
+//
+//	func TestCorpus(t *testing.T) {
+//		corpus := &chunkxtest.Corpus{Dir: "testdata/sources"}
+//		corpus.Run(t)
+//	}
+//
+// Every file is chunked with every strategy of the corpus, and the chunks are
+// rendered with Render and compared with the golden file of the file and
+// strategy. Golden files that don't exist yet are written on the first run.
+// Set the CHUNKX_UPDATE_SNAPSHOTS environment variable to rewrite golden
+// files after an intended change, and review the diff before committing it.
+//
+// Syntax-aware chunking needs cgo, so the golden files of the "ast" strategy
+// only match in builds with cgo.
+package chunkxtest
+
+import (
+	"errors"
+	"fmt"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+// UpdateEnv is the environment variable that makes Run rewrite golden files.
+const UpdateEnv = "CHUNKX_UPDATE_SNAPSHOTS"
+
+// DefaultStrategies are the chunker options of a Corpus without strategies:
+// syntax-aware and line-based chunking of small chunks, which makes for many
+// boundaries in little code.
+var DefaultStrategies = map[string][]chunkx.Option{
+	chunkx.StrategyAST:   {chunkx.WithMaxSize(50)},
+	chunkx.StrategyLines: {chunkx.WithMaxSize(50), chunkx.WithLanguage(languages.Generic)},
+}
+
+// Corpus is a directory of source files with golden files of their chunks.
+type Corpus struct {
+	Dir        string                     // Directory of the source files
+	Golden     string                     // Directory of the golden files; defaults to "golden" next to Dir
+	Strategies map[string][]chunkx.Option // Chunker options by strategy name; defaults to DefaultStrategies
+	Chunker    chunkx.Chunker             // Defaults to chunkx.NewChunker()
+}
+
+// Run chunks every file of the corpus with every strategy in a subtest, and
+// fails the subtests whose chunks don't match their golden file.
+func (c *Corpus) Run(t *testing.T) {
+	t.Helper()
+
+	entries, err := os.ReadDir(c.Dir)
+	if err != nil {
+		t.Fatalf("reading corpus: %v", err)
+	}
+
+	golden := c.Golden
+	if golden == "" {
+		golden = filepath.Join(filepath.Dir(c.Dir), "golden")
+	}
+	strategies := c.Strategies
+	if strategies == nil {
+		strategies = DefaultStrategies
+	}
+	chunker := c.Chunker
+	if chunker == nil {
+		chunker = chunkx.NewChunker()
+	}
+	update := os.Getenv(UpdateEnv) != ""
+
+	names := make([]string, 0, len(strategies))
+	for name := range strategies {
+		names = append(names, name)
+	}
+	slices.Sort(names)
+
+	for _, entry := range entries {
+		if entry.IsDir() {
+			continue
+		}
+		file := entry.Name()
+
+		t.Run(file, func(t *testing.T) {
+			for _, name := range names {
+				t.Run(name, func(t *testing.T) {
+					chunks, err := chunker.ChunkFile(filepath.Join(c.Dir, file), strategies[name]...)
+					if err != nil {
+						t.Fatalf("chunking %s: %v", file, err)
+					}
+					checkGolden(t, filepath.Join(golden, file+"."+name+".golden"), Render(chunks), update)
+				})
+			}
+		})
+	}
+}
+
+// checkGolden compares got with the golden file at path, writing the file
+// instead if it doesn't exist or update is set.
+func checkGolden(t testing.TB, path, got string, update bool) {
+	t.Helper()
+
+	data, err := os.ReadFile(path)
+	switch {
+	case errors.Is(err, fs.ErrNotExist) || update:
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			t.Fatalf("creating golden directory: %v", err)
+		}
+		if err := os.WriteFile(path, []byte(got), 0o644); err != nil {
+			t.Fatalf("writing golden file: %v", err)
+		}
+		if !update {
+			t.Logf("wrote new golden file %s", path)
+		}
+		return
+	case err != nil:
+		t.Fatalf("reading golden file: %v", err)
+	}
+
+	want := strings.ReplaceAll(string(data), "\r\n", "\n")
+	if got == want {
+		return
+	}
+
+	gotLines, wantLines := strings.Split(got, "\n"), strings.Split(want, "\n")
+	line := 0
+	for line < min(len(gotLines), len(wantLines)) && gotLines[line] == wantLines[line] {
+		line++
+	}
+	t.Errorf("chunks don't match %s from line %d:\n  want: %s\n  got:  %s\nrun with %s=1 to update it",
+		path, line+1, lineOrEOF(wantLines, line), lineOrEOF(gotLines, line), UpdateEnv)
+}
+
+// lineOrEOF returns lines[i], or a marker for the end of the file.
+func lineOrEOF(lines []string, i int) string {
+	if i < len(lines) {
+		return lines[i]
+	}
+	return "<end of file>"
+}
+
+// maxPreview is the number of characters of chunk content Render shows.
+const maxPreview = 60
+
+// Render renders the boundaries of chunks: the lines, byte offsets, and node
+// types of every chunk, and the start of the first non-blank line of its
+// content.
+func Render(chunks []chunkx.Chunk) string {
+	var b strings.Builder
+	language := languages.Generic
+	if len(chunks) > 0 {
+		language = chunks[0].Language
+	}
+	fmt.Fprintf(&b, "language: %s\nchunks: %d\n", language, len(chunks))
+
+	for i, chunk := range chunks {
+		fmt.Fprintf(&b, "\n%d. lines %d-%d, bytes %d-%d", i+1, chunk.StartLine, chunk.EndLine, chunk.StartByte, chunk.EndByte)
+		if len(chunk.NodeTypes) > 0 {
+			fmt.Fprintf(&b, " (%s)", strings.Join(chunk.NodeTypes, " "))
+		}
+		fmt.Fprintf(&b, "\n   | %s\n", preview(chunk.Content))
+	}
+	return b.String()
+}
+
+// preview returns the start of the first non-blank line of content.
+func preview(content string) string {
+	for line := range strings.Lines(content) {
+		line = strings.TrimSpace(line)
+		if line == "" {
+			continue
+		}
+		if runes := []rune(line); len(runes) > maxPreview {
+			return string(runes[:maxPreview]) + "…"
+		}
+		return line
+	}
+	return ""
+}
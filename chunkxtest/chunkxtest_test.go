@@ -0,0 +1,84 @@
+package chunkxtest
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestRender(t *testing.T) {
+	chunks := []chunkx.Chunk{
+		{Content: "\n\tfunc a() {}\n", StartLine: 1, EndLine: 2, StartByte: 0, EndByte: 14, NodeTypes: []string{"func", "identifier"}, Language: languages.Go},
+		{Content: strings.Repeat("é", 70), StartLine: 3, EndLine: 3, StartByte: 15, EndByte: 155, Language: languages.Go},
+	}
+
+	want := "language: go\nchunks: 2\n" +
+		"\n1. lines 1-2, bytes 0-14 (func identifier)\n   | func a() {}\n" +
+		"\n2. lines 3-3, bytes 15-155\n   | " + strings.Repeat("é", 60) + "…\n"
+	if got := Render(chunks); got != want {
+		t.Errorf("Render() = %q, want %q", got, want)
+	}
+
+	if got := Render(nil); got != "language: generic\nchunks: 0\n" {
+		t.Errorf("Render(nil) = %q", got)
+	}
+}
+
+func TestCorpus_Run(t *testing.T) {
+	dir := t.TempDir()
+	sources := filepath.Join(dir, "sources")
+	if err := os.Mkdir(sources, 0o755); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(filepath.Join(sources, "notes.txt"), []byte("one two\nthree four\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	corpus := &Corpus{
+		Dir:        sources,
+		Strategies: map[string][]chunkx.Option{"lines": {chunkx.WithMaxSize(2), chunkx.WithLanguage(languages.Generic)}},
+	}
+
+	// The first run writes the missing golden file, and later runs compare
+	// against it
+	corpus.Run(t)
+	golden := filepath.Join(dir, "golden", "notes.txt.lines.golden")
+	data, err := os.ReadFile(golden)
+	if err != nil {
+		t.Fatalf("expected a golden file: %v", err)
+	}
+	if !strings.Contains(string(data), "1. lines 1-1, bytes 0-7 (generic)\n   | one two\n") {
+		t.Errorf("unexpected golden file:\n%s", data)
+	}
+	corpus.Run(t)
+
+	// Changed boundaries fail the run
+	if err := os.WriteFile(golden, []byte(strings.Replace(string(data), "bytes 0-7", "bytes 0-8", 1)), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	mismatch := &failureRecorder{TB: t}
+	checkGolden(mismatch, golden, string(data), false)
+	if !mismatch.failed {
+		t.Error("expected a mismatch to fail")
+	}
+
+	// Updating rewrites the golden file
+	checkGolden(t, golden, string(data), true)
+	if updated, _ := os.ReadFile(golden); string(updated) != string(data) {
+		t.Errorf("expected the golden file to be rewritten, got:\n%s", updated)
+	}
+}
+
+// failureRecorder records failures instead of failing the test.
+type failureRecorder struct {
+	testing.TB
+	failed bool
+}
+
+func (r *failureRecorder) Errorf(string, ...any) {
+	r.failed = true
+}
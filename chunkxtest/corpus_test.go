@@ -0,0 +1,13 @@
+//go:build cgo
+
+package chunkxtest
+
+import (
+	"path/filepath"
+	"testing"
+)
+
+func TestCorpus(t *testing.T) {
+	corpus := &Corpus{Dir: filepath.Join("..", "testdata", "sources")}
+	corpus.Run(t)
+}
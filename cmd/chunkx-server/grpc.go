@@ -0,0 +1,150 @@
+package main
+
+import (
+	"context"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"strconv"
+	"strings"
+
+	"github.com/gomantics/chunkx"
+)
+
+// gRPC status codes.
+const (
+	grpcOK                = 0
+	grpcInvalidArgument   = 3
+	grpcDeadlineExceeded  = 4
+	grpcResourceExhausted = 8
+	grpcUnimplemented     = 12
+	grpcInternal          = 13
+	grpcUnavailable       = 14
+)
+
+// errCompressed reports a compressed gRPC message, which isn't supported.
+var errCompressed = errors.New("compressed messages are not supported")
+
+// handleGRPC serves the server-streaming chunkx.v1.ChunkService/Chunk method.
+// The request message is read whole, and each chunk is flushed as its own
+// message so clients can process them as they arrive.
+func (s *server) handleGRPC(w http.ResponseWriter, r *http.Request) {
+	if r.ProtoMajor != 2 || !strings.HasPrefix(r.Header.Get("Content-Type"), "application/grpc") {
+		http.Error(w, "gRPC requests must use HTTP/2 and application/grpc", http.StatusUnsupportedMediaType)
+		return
+	}
+
+	w.Header().Set("Content-Type", "application/grpc")
+	w.Header().Set("Trailer", "Grpc-Status, Grpc-Message")
+	w.WriteHeader(http.StatusOK)
+
+	err := s.streamChunks(w, r)
+	w.Header().Set("Grpc-Status", strconv.Itoa(grpcCode(err)))
+	if err != nil {
+		w.Header().Set("Grpc-Message", grpcMessage(err.Error()))
+	}
+}
+
+// streamChunks chunks the request message and writes one message per chunk.
+func (s *server) streamChunks(w http.ResponseWriter, r *http.Request) error {
+	if err := s.acquire(); err != nil {
+		return err
+	}
+	defer s.release()
+
+	msg, err := readMessage(r.Body, s.maxBytes)
+	if err != nil {
+		return err
+	}
+	req, err := unmarshalRequest(msg)
+	if err != nil {
+		return fmt.Errorf("%w: %w", errInvalidRequest, err)
+	}
+
+	chunks, err := s.chunk(r.Context(), req)
+	if err != nil {
+		return err
+	}
+
+	rc := http.NewResponseController(w)
+	for _, chunk := range chunks {
+		if err := writeMessage(w, marshalChunk(req.Path, chunk)); err != nil {
+			return err
+		}
+		if err := rc.Flush(); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// readMessage reads a length-prefixed gRPC message of at most maxBytes.
+func readMessage(r io.Reader, maxBytes int64) ([]byte, error) {
+	var header [5]byte
+	if _, err := io.ReadFull(r, header[:]); err != nil {
+		return nil, fmt.Errorf("%w: reading message header: %w", errInvalidRequest, err)
+	}
+	if header[0] != 0 {
+		return nil, errCompressed
+	}
+
+	size := binary.BigEndian.Uint32(header[1:])
+	if int64(size) > maxBytes {
+		return nil, fmt.Errorf("%w: message exceeds %d bytes", errTooLarge, maxBytes)
+	}
+
+	msg := make([]byte, size)
+	if _, err := io.ReadFull(r, msg); err != nil {
+		return nil, fmt.Errorf("%w: reading message: %w", errInvalidRequest, err)
+	}
+	return msg, nil
+}
+
+// writeMessage writes an uncompressed length-prefixed gRPC message.
+func writeMessage(w io.Writer, msg []byte) error {
+	header := [5]byte{}
+	binary.BigEndian.PutUint32(header[1:], uint32(len(msg)))
+	if _, err := w.Write(header[:]); err != nil {
+		return err
+	}
+	_, err := w.Write(msg)
+	return err
+}
+
+// grpcCode returns the gRPC status code reporting err.
+func grpcCode(err error) int {
+	switch {
+	case err == nil:
+		return grpcOK
+	case errors.Is(err, errInvalidRequest):
+		return grpcInvalidArgument
+	case errors.Is(err, errTooLarge):
+		return grpcResourceExhausted
+	case errors.Is(err, errCompressed):
+		return grpcUnimplemented
+	case errors.Is(err, errBusy):
+		return grpcUnavailable
+	case errors.Is(err, chunkx.ErrLimitExceeded):
+		return grpcResourceExhausted
+	case errors.Is(err, context.DeadlineExceeded):
+		return grpcDeadlineExceeded
+	default:
+		return grpcInternal
+	}
+}
+
+// grpcMessage percent-encodes a status message as the gRPC protocol
+// requires for the Grpc-Message trailer.
+func grpcMessage(s string) string {
+	var b strings.Builder
+	for i := 0; i < len(s); i++ {
+		if c := s[i]; c >= ' ' && c <= '~' && c != '%' {
+			b.WriteByte(c)
+		} else {
+			fmt.Fprintf(&b, "%%%02X", c)
+		}
+	}
+	return b.String()
+}
@@ -0,0 +1,90 @@
+package main
+
+import (
+	"bytes"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+)
+
+// callChunk calls the gRPC Chunk method on the server over HTTP/2 and returns
+// the response messages and status.
+func callChunk(t *testing.T, s *server, req []byte) (msgs [][]byte, status string) {
+	t.Helper()
+
+	ts := httptest.NewUnstartedServer(s.handler())
+	ts.EnableHTTP2 = true
+	ts.StartTLS()
+	defer ts.Close()
+
+	var body bytes.Buffer
+	if err := writeMessage(&body, req); err != nil {
+		t.Fatal(err)
+	}
+	httpReq, err := http.NewRequest(http.MethodPost, ts.URL+"/chunkx.v1.ChunkService/Chunk", &body)
+	if err != nil {
+		t.Fatal(err)
+	}
+	httpReq.Header.Set("Content-Type", "application/grpc")
+
+	resp, err := ts.Client().Do(httpReq)
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer resp.Body.Close()
+
+	for {
+		msg, err := readMessage(resp.Body, defaultMaxRequestBytes)
+		if err != nil {
+			break
+		}
+		msgs = append(msgs, msg)
+	}
+	io.Copy(io.Discard, resp.Body)
+	return msgs, resp.Trailer.Get("Grpc-Status")
+}
+
+func TestHandleGRPC(t *testing.T) {
+	req := appendString(nil, 1, "one two\nthree four\n")
+	req = appendString(req, 2, "notes.txt")
+	req = appendString(req, 4, `{"max_size": 2}`)
+
+	msgs, status := callChunk(t, newServer(nil, defaultMaxRequestBytes, 1), req)
+	if status != "0" {
+		t.Fatalf("grpc-status = %q", status)
+	}
+	if len(msgs) != 2 {
+		t.Fatalf("expected 2 chunks, got %d", len(msgs))
+	}
+
+	content := ""
+	for b := msgs[1]; len(b) > 0; {
+		f, rest, err := readField(b)
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		if f.number == 10 {
+			content = string(f.data)
+		}
+		b = rest
+	}
+	if content != "three four\n" {
+		t.Errorf("content = %q", content)
+	}
+}
+
+func TestHandleGRPC_InvalidOptions(t *testing.T) {
+	req := appendString(nil, 4, `{"max_tokens": 2}`)
+
+	msgs, status := callChunk(t, newServer(nil, defaultMaxRequestBytes, 1), req)
+	if len(msgs) != 0 || status != "3" {
+		t.Errorf("got %d messages and grpc-status %q, want INVALID_ARGUMENT", len(msgs), status)
+	}
+}
+
+func TestGRPCMessage(t *testing.T) {
+	if got := grpcMessage("bad: 100% \"ü\"\n"); got != "bad: 100%25 \"%C3%BC\"%0A" {
+		t.Errorf("grpcMessage = %q", got)
+	}
+}
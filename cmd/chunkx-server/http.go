@@ -0,0 +1,81 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net/http"
+
+	"github.com/gomantics/chunkx"
+)
+
+// chunkResponse is the JSON response to a chunk request.
+type chunkResponse struct {
+	Chunks []chunkRecord `json:"chunks"`
+}
+
+// errorResponse is the JSON response to a failed request.
+type errorResponse struct {
+	Error string `json:"error"`
+}
+
+// handleChunk serves POST /v1/chunk.
+func (s *server) handleChunk(w http.ResponseWriter, r *http.Request) {
+	if err := s.acquire(); err != nil {
+		w.Header().Set("Retry-After", "1")
+		writeJSON(w, httpStatus(err), errorResponse{Error: err.Error()})
+		return
+	}
+	defer s.release()
+
+	var req chunkRequest
+	body := http.MaxBytesReader(w, r.Body, s.maxBytes)
+	if err := json.NewDecoder(body).Decode(&req); err != nil {
+		var maxErr *http.MaxBytesError
+		if errors.As(err, &maxErr) {
+			err = fmt.Errorf("%w: body exceeds %d bytes", errTooLarge, s.maxBytes)
+		} else {
+			err = fmt.Errorf("%w: %w", errInvalidRequest, err)
+		}
+		writeJSON(w, httpStatus(err), errorResponse{Error: err.Error()})
+		return
+	}
+
+	chunks, err := s.chunk(r.Context(), req)
+	if err != nil {
+		writeJSON(w, httpStatus(err), errorResponse{Error: err.Error()})
+		return
+	}
+
+	resp := chunkResponse{Chunks: make([]chunkRecord, len(chunks))}
+	for i, chunk := range chunks {
+		resp.Chunks[i] = newChunkRecord(req.Path, chunk)
+	}
+	writeJSON(w, http.StatusOK, resp)
+}
+
+// httpStatus returns the HTTP status code reporting err.
+func httpStatus(err error) int {
+	switch {
+	case errors.Is(err, errInvalidRequest):
+		return http.StatusBadRequest
+	case errors.Is(err, errTooLarge):
+		return http.StatusRequestEntityTooLarge
+	case errors.Is(err, errBusy):
+		return http.StatusServiceUnavailable
+	case errors.Is(err, chunkx.ErrLimitExceeded):
+		return http.StatusUnprocessableEntity
+	case errors.Is(err, context.DeadlineExceeded):
+		return http.StatusGatewayTimeout
+	default:
+		return http.StatusInternalServerError
+	}
+}
+
+// writeJSON writes v as a JSON response with the given status code.
+func writeJSON(w http.ResponseWriter, status int, v any) {
+	w.Header().Set("Content-Type", "application/json")
+	w.WriteHeader(status)
+	json.NewEncoder(w).Encode(v)
+}
@@ -0,0 +1,105 @@
+package main
+
+import (
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/gomantics/chunkx"
+)
+
+// post sends a JSON chunk request to the server and returns the response.
+func post(t *testing.T, s *server, body string) *httptest.ResponseRecorder {
+	t.Helper()
+	rec := httptest.NewRecorder()
+	s.handler().ServeHTTP(rec, httptest.NewRequest(http.MethodPost, "/v1/chunk", strings.NewReader(body)))
+	return rec
+}
+
+func TestHandleChunk(t *testing.T) {
+	s := newServer(nil, defaultMaxRequestBytes, 1)
+	rec := post(t, s, `{"content": "one two\nthree four\n", "path": "notes.txt", "options": {"max_size": 2}}`)
+	if rec.Code != http.StatusOK {
+		t.Fatalf("status = %d, body = %s", rec.Code, rec.Body)
+	}
+
+	var resp chunkResponse
+	if err := json.Unmarshal(rec.Body.Bytes(), &resp); err != nil {
+		t.Fatalf("invalid response: %v", err)
+	}
+	if len(resp.Chunks) != 2 || resp.Chunks[1].Content != "three four\n" || resp.Chunks[1].Path != "notes.txt" {
+		t.Errorf("unexpected chunks %+v", resp.Chunks)
+	}
+}
+
+func TestHandleChunk_Errors(t *testing.T) {
+	tests := []struct {
+		name string
+		body string
+		want int
+	}{
+		{"malformed JSON", `{"content": `, http.StatusBadRequest},
+		{"unknown option", `{"content": "x", "options": {"max_tokens": 2}}`, http.StatusBadRequest},
+		{"unknown language", `{"content": "x", "language": "klingon"}`, http.StatusBadRequest},
+		{"too large", `{"content": "` + strings.Repeat("x", 100) + `"}`, http.StatusRequestEntityTooLarge},
+	}
+
+	s := newServer(nil, 64, 1)
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if rec := post(t, s, tt.body); rec.Code != tt.want {
+				t.Errorf("status = %d, want %d; body = %s", rec.Code, tt.want, rec.Body)
+			}
+		})
+	}
+}
+
+func TestHandleChunk_ServerLimits(t *testing.T) {
+	s := newServer(nil, defaultMaxRequestBytes, 1)
+	s.limits = chunkx.Limits{MaxBytes: 10}
+
+	// Requests can tighten the limits of the server, but not loosen them
+	tests := []struct {
+		name string
+		body string
+		want int
+	}{
+		{"within", `{"content": "one two"}`, http.StatusOK},
+		{"over", `{"content": "one two three"}`, http.StatusUnprocessableEntity},
+		{"loosened", `{"content": "one two three", "options": {"max_bytes": 1000}}`, http.StatusUnprocessableEntity},
+		{"tightened", `{"content": "one two", "options": {"max_bytes": 3}}`, http.StatusUnprocessableEntity},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if rec := post(t, s, tt.body); rec.Code != tt.want {
+				t.Errorf("status = %d, want %d; body = %s", rec.Code, tt.want, rec.Body)
+			}
+		})
+	}
+}
+
+func TestHandleChunk_Timeout(t *testing.T) {
+	s := newServer(nil, defaultMaxRequestBytes, 1)
+	s.timeout = time.Nanosecond
+
+	rec := post(t, s, `{"content": "`+strings.Repeat("word ", 100000)+`"}`)
+	if rec.Code != http.StatusGatewayTimeout {
+		t.Errorf("status = %d, want %d; body = %s", rec.Code, http.StatusGatewayTimeout, rec.Body)
+	}
+}
+
+func TestHandleChunk_Busy(t *testing.T) {
+	s := newServer(nil, defaultMaxRequestBytes, 1)
+	if err := s.acquire(); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	defer s.release()
+
+	rec := post(t, s, `{"content": "x"}`)
+	if rec.Code != http.StatusServiceUnavailable || rec.Header().Get("Retry-After") == "" {
+		t.Errorf("status = %d, headers = %v", rec.Code, rec.Header())
+	}
+}
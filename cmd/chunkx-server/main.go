@@ -0,0 +1,145 @@
+// Command chunkx-server serves chunking over HTTP, so services in any
+// language can run chunkx as a sidecar instead of embedding the library.
+//
+// Usage:
+//
+//	chunkx-server [flags]
+//
+// The server answers JSON requests at POST /v1/chunk:
+//
+//	{"content": "package main...", "path": "main.go", "options": {"max_size": 500}}
+//
+// with {"chunks": [...]}, using the record fields of the chunkx command's JSON
+// output. The language is taken from "language", then the extension of
+// "path", and defaults to line-based chunking. Options take the keys of a
+// chunkx.json config file and are applied over the -config file, except that
+// requests can only tighten its limits, such as max_bytes.
+//
+// The same port serves the gRPC method chunkx.v1.ChunkService/Chunk from
+// chunkx.proto over cleartext HTTP/2, streaming each chunk back as its own
+// message. GET /healthz reports whether the server is up.
+//
+// At most -max-concurrent requests are chunked at once; others are turned
+// away with 503 Service Unavailable, or UNAVAILABLE over gRPC, so callers can
+// retry against another replica. Request bodies larger than
+// -max-request-bytes are rejected, and requests chunked for longer than
+// -timeout fail with 504 Gateway Timeout, or DEADLINE_EXCEEDED over gRPC.
+package main
+
+import (
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"net"
+	"net/http"
+	"os"
+	"os/signal"
+	"runtime"
+	"time"
+
+	"github.com/gomantics/chunkx"
+)
+
+// defaultMaxRequestBytes is the default limit on the size of a request body.
+const defaultMaxRequestBytes = 10 << 20
+
+func main() {
+	os.Exit(run(os.Args[1:], os.Stderr))
+}
+
+// serverContext returns the context the server runs under until
+// interrupted. Tests replace it to bound the run.
+var serverContext = func() (context.Context, context.CancelFunc) {
+	return signal.NotifyContext(context.Background(), os.Interrupt)
+}
+
+// run starts the server and returns its exit code once it stops.
+func run(args []string, stderr io.Writer) int {
+	fs := flag.NewFlagSet("chunkx-server", flag.ContinueOnError)
+	fs.SetOutput(stderr)
+	fs.Usage = func() {
+		fmt.Fprintln(stderr, "Usage: chunkx-server [flags]")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Serves chunking over HTTP and gRPC.")
+		fmt.Fprintln(stderr)
+		fs.PrintDefaults()
+	}
+
+	addr := fs.String("addr", ":8080", "address to listen on")
+	config := fs.String("config", "", "config file with the default chunking options")
+	maxBytes := fs.Int64("max-request-bytes", defaultMaxRequestBytes, "maximum size of a request body")
+	maxConcurrent := fs.Int("max-concurrent", runtime.NumCPU(), "maximum number of requests chunked at once")
+	timeout := fs.Duration("timeout", defaultTimeout, "maximum time spent chunking a request; 0 for no limit")
+	if err := fs.Parse(args); err != nil {
+		if errors.Is(err, flag.ErrHelp) {
+			return 0
+		}
+		return 2
+	}
+	if fs.NArg() > 0 || *maxBytes <= 0 || *maxConcurrent <= 0 || *timeout < 0 {
+		fs.Usage()
+		return 2
+	}
+
+	var opts []chunkx.Option
+	var limits chunkx.Limits
+	if *config != "" {
+		cfg, err := chunkx.LoadConfig(*config)
+		if err == nil {
+			opts, err = cfg.Options()
+			limits = cfg.Limits
+		}
+		if err != nil {
+			fmt.Fprintf(stderr, "chunkx-server: %v\n", err)
+			return 2
+		}
+	}
+
+	ln, err := net.Listen("tcp", *addr)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx-server: %v\n", err)
+		return 1
+	}
+
+	ctx, cancel := serverContext()
+	defer cancel()
+
+	s := newServer(opts, *maxBytes, *maxConcurrent)
+	s.limits, s.timeout = limits, *timeout
+	if err := serve(ctx, ln, s); err != nil {
+		fmt.Fprintf(stderr, "chunkx-server: %v\n", err)
+		return 1
+	}
+	return 0
+}
+
+// serve serves HTTP/1.1 and cleartext HTTP/2 on ln until ctx is done, then
+// waits for requests in flight to finish.
+func serve(ctx context.Context, ln net.Listener, s *server) error {
+	var protocols http.Protocols
+	protocols.SetHTTP1(true)
+	protocols.SetUnencryptedHTTP2(true)
+
+	srv := &http.Server{
+		Handler:           s.handler(),
+		Protocols:         &protocols,
+		ReadHeaderTimeout: 10 * time.Second,
+	}
+
+	errc := make(chan error, 1)
+	go func() {
+		errc <- srv.Serve(ln)
+	}()
+
+	select {
+	case err := <-errc:
+		return err
+	case <-ctx.Done():
+	}
+
+	shutdownCtx, cancel := context.WithTimeout(context.Background(), 30*time.Second)
+	defer cancel()
+	return srv.Shutdown(shutdownCtx)
+}
@@ -0,0 +1,146 @@
+package main
+
+import (
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"slices"
+
+	"github.com/gomantics/chunkx"
+)
+
+// Protobuf wire types.
+const (
+	wireVarint  = 0
+	wireFixed64 = 1
+	wireBytes   = 2
+	wireFixed32 = 5
+)
+
+// errMalformed reports a protobuf message that can't be decoded.
+var errMalformed = errors.New("malformed protobuf message")
+
+// protoField is a decoded protobuf field. Varint and fixed-size values are
+// stored in num, and length-delimited ones in data.
+type protoField struct {
+	number   int
+	wireType int
+	num      uint64
+	data     []byte
+}
+
+// readField decodes the first field of b and returns it with the rest of b.
+func readField(b []byte) (protoField, []byte, error) {
+	key, n := binary.Uvarint(b)
+	if n <= 0 {
+		return protoField{}, nil, errMalformed
+	}
+	b = b[n:]
+
+	f := protoField{number: int(key >> 3), wireType: int(key & 7)}
+	switch f.wireType {
+	case wireVarint:
+		if f.num, n = binary.Uvarint(b); n <= 0 {
+			return protoField{}, nil, errMalformed
+		}
+		b = b[n:]
+	case wireFixed64:
+		if len(b) < 8 {
+			return protoField{}, nil, errMalformed
+		}
+		f.num, b = binary.LittleEndian.Uint64(b), b[8:]
+	case wireFixed32:
+		if len(b) < 4 {
+			return protoField{}, nil, errMalformed
+		}
+		f.num, b = uint64(binary.LittleEndian.Uint32(b)), b[4:]
+	case wireBytes:
+		size, n := binary.Uvarint(b)
+		if n <= 0 || size > uint64(len(b)-n) {
+			return protoField{}, nil, errMalformed
+		}
+		f.data, b = b[n:n+int(size)], b[n+int(size):]
+	default:
+		return protoField{}, nil, fmt.Errorf("%w: wire type %d", errMalformed, f.wireType)
+	}
+	return f, b, nil
+}
+
+// appendString appends a string field, omitting it when empty as proto3 does.
+func appendString(b []byte, number int, s string) []byte {
+	if s == "" {
+		return b
+	}
+	b = binary.AppendUvarint(b, uint64(number)<<3|wireBytes)
+	b = binary.AppendUvarint(b, uint64(len(s)))
+	return append(b, s...)
+}
+
+// appendInt appends an int64 field, omitting it when zero as proto3 does.
+func appendInt(b []byte, number int, v int) []byte {
+	if v == 0 {
+		return b
+	}
+	b = binary.AppendUvarint(b, uint64(number)<<3|wireVarint)
+	return binary.AppendUvarint(b, uint64(int64(v)))
+}
+
+// unmarshalRequest decodes a chunkx.v1.ChunkRequest message. Unknown fields
+// are skipped so older servers accept newer clients.
+func unmarshalRequest(b []byte) (chunkRequest, error) {
+	var req chunkRequest
+	for len(b) > 0 {
+		f, rest, err := readField(b)
+		if err != nil {
+			return chunkRequest{}, err
+		}
+		b = rest
+
+		if f.wireType != wireBytes {
+			continue
+		}
+		switch f.number {
+		case 1:
+			req.Content = string(f.data)
+		case 2:
+			req.Path = string(f.data)
+		case 3:
+			req.Language = string(f.data)
+		case 4:
+			req.Options = f.data
+		}
+	}
+	return req, nil
+}
+
+// marshalChunk encodes a chunk of the document at path as a chunkx.v1.Chunk
+// message. Metadata entries are sorted by key so the encoding is stable.
+func marshalChunk(path string, chunk chunkx.Chunk) []byte {
+	b := appendString(nil, 1, chunk.ID())
+	b = appendString(b, 2, path)
+	b = appendString(b, 3, chunk.Language.String())
+	b = appendInt(b, 4, chunk.StartLine)
+	b = appendInt(b, 5, chunk.EndLine)
+	b = appendInt(b, 6, chunk.StartByte)
+	b = appendInt(b, 7, chunk.EndByte)
+	for _, nodeType := range chunk.NodeTypes {
+		b = binary.AppendUvarint(b, 8<<3|wireBytes)
+		b = binary.AppendUvarint(b, uint64(len(nodeType)))
+		b = append(b, nodeType...)
+	}
+
+	keys := make([]string, 0, len(chunk.Metadata))
+	for key := range chunk.Metadata {
+		keys = append(keys, key)
+	}
+	slices.Sort(keys)
+	for _, key := range keys {
+		entry := appendString(nil, 1, key)
+		entry = appendString(entry, 2, chunk.Metadata[key])
+		b = binary.AppendUvarint(b, 9<<3|wireBytes)
+		b = binary.AppendUvarint(b, uint64(len(entry)))
+		b = append(b, entry...)
+	}
+
+	return appendString(b, 10, chunk.Content)
+}
@@ -0,0 +1,65 @@
+package main
+
+import (
+	"errors"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestUnmarshalRequest(t *testing.T) {
+	b := appendString(nil, 1, "code")
+	b = appendInt(b, 15, 7) // Unknown fields are skipped
+	b = appendString(b, 2, "main.go")
+	b = appendString(b, 3, "go")
+	b = appendString(b, 4, `{"max_size": 5}`)
+
+	req, err := unmarshalRequest(b)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if req.Content != "code" || req.Path != "main.go" || req.Language != "go" || string(req.Options) != `{"max_size": 5}` {
+		t.Errorf("unexpected request %+v", req)
+	}
+}
+
+func TestUnmarshalRequest_Malformed(t *testing.T) {
+	b := appendString(nil, 1, "code")
+	if _, err := unmarshalRequest(b[:len(b)-1]); !errors.Is(err, errMalformed) {
+		t.Errorf("expected errMalformed, got %v", err)
+	}
+}
+
+func TestMarshalChunk(t *testing.T) {
+	chunk := chunkx.Chunk{
+		Content:   "x",
+		StartLine: 3,
+		EndLine:   4,
+		NodeTypes: []string{"a", "b"},
+		Language:  languages.Go,
+		Metadata:  map[string]string{"k": "v"},
+	}
+
+	fields := map[int][][]byte{}
+	nums := map[int]uint64{}
+	for b := marshalChunk("main.go", chunk); len(b) > 0; {
+		f, rest, err := readField(b)
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		fields[f.number] = append(fields[f.number], f.data)
+		nums[f.number] = f.num
+		b = rest
+	}
+
+	if string(fields[2][0]) != "main.go" || string(fields[3][0]) != "go" || string(fields[10][0]) != "x" {
+		t.Errorf("unexpected string fields %q", fields)
+	}
+	if nums[4] != 3 || nums[5] != 4 || len(fields[6]) != 0 {
+		t.Errorf("unexpected position fields %v", nums)
+	}
+	if len(fields[8]) != 2 || len(fields[9]) != 1 {
+		t.Errorf("unexpected repeated fields %q", fields)
+	}
+}
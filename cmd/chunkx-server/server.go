@@ -0,0 +1,174 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net/http"
+	"time"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+var (
+	// errInvalidRequest reports a malformed request or invalid options.
+	errInvalidRequest = errors.New("invalid request")
+
+	// errTooLarge reports a request body over the size limit.
+	errTooLarge = errors.New("request too large")
+
+	// errBusy reports that every chunking slot is in use.
+	errBusy = errors.New("server busy")
+)
+
+// chunkRequest is a request to chunk a document, decoded from JSON or
+// protobuf.
+type chunkRequest struct {
+	Content  string          `json:"content"`
+	Path     string          `json:"path"`
+	Language string          `json:"language"`
+	Options  json.RawMessage `json:"options"`
+}
+
+// chunkRecord is the serialized form of a chunk in responses.
+type chunkRecord struct {
+	ID        string            `json:"id"`
+	Path      string            `json:"path"`
+	Language  string            `json:"language"`
+	StartLine int               `json:"start_line"`
+	EndLine   int               `json:"end_line"`
+	StartByte int               `json:"start_byte"`
+	EndByte   int               `json:"end_byte"`
+	NodeTypes []string          `json:"node_types"`
+	Metadata  map[string]string `json:"metadata,omitempty"`
+	Content   string            `json:"content"`
+}
+
+// newChunkRecord creates the response record for a chunk of the document at
+// path.
+func newChunkRecord(path string, chunk chunkx.Chunk) chunkRecord {
+	return chunkRecord{
+		ID:        chunk.ID(),
+		Path:      path,
+		Language:  chunk.Language.String(),
+		StartLine: chunk.StartLine,
+		EndLine:   chunk.EndLine,
+		StartByte: chunk.StartByte,
+		EndByte:   chunk.EndByte,
+		NodeTypes: chunk.NodeTypes,
+		Metadata:  chunk.Metadata,
+		Content:   chunk.Content,
+	}
+}
+
+// defaultTimeout is the default limit on the time spent chunking a request.
+const defaultTimeout = 30 * time.Second
+
+// server chunks documents for HTTP and gRPC requests.
+type server struct {
+	chunker  chunkx.ContextChunker // Shared by every request
+	opts     []chunkx.Option       // Default options from the config file
+	limits   chunkx.Limits         // Limits of the config file, which requests can only tighten
+	timeout  time.Duration         // Longest a request is chunked for; zero is unlimited
+	maxBytes int64
+	slots    chan struct{} // Holds a value per request being chunked
+}
+
+// newServer creates a server chunking with opts, accepting bodies of at most
+// maxBytes and chunking at most maxConcurrent requests at once, each for at
+// most defaultTimeout.
+func newServer(opts []chunkx.Option, maxBytes int64, maxConcurrent int) *server {
+	return &server{
+		chunker:  chunkx.NewChunker().(chunkx.ContextChunker),
+		opts:     opts,
+		timeout:  defaultTimeout,
+		maxBytes: maxBytes,
+		slots:    make(chan struct{}, maxConcurrent),
+	}
+}
+
+// handler returns the HTTP handler serving every endpoint.
+func (s *server) handler() http.Handler {
+	mux := http.NewServeMux()
+	mux.HandleFunc("GET /healthz", func(w http.ResponseWriter, r *http.Request) {
+		w.WriteHeader(http.StatusOK)
+	})
+	mux.HandleFunc("POST /v1/chunk", s.handleChunk)
+	mux.HandleFunc("POST /chunkx.v1.ChunkService/Chunk", s.handleGRPC)
+	return mux
+}
+
+// acquire reserves a chunking slot, failing with errBusy when all are in
+// use. The slot must be released with release.
+func (s *server) acquire() error {
+	select {
+	case s.slots <- struct{}{}:
+		return nil
+	default:
+		return errBusy
+	}
+}
+
+// release frees a slot reserved with acquire.
+func (s *server) release() {
+	<-s.slots
+}
+
+// chunk chunks the content of req. The language is the request's language,
+// then the one detected from its path, and defaults to line-based chunking.
+func (s *server) chunk(ctx context.Context, req chunkRequest) ([]chunkx.Chunk, error) {
+	language := languages.Generic
+	if detected, ok := languages.DetectLanguage(req.Path); req.Path != "" && ok {
+		language = detected.Name
+	}
+
+	// Options are applied in order, so request options win over the config
+	// file and an explicit language wins over both. The limits and timeout of
+	// the server are its protection against its callers, so requests can
+	// only tighten them.
+	opts := append([]chunkx.Option{chunkx.WithLanguage(language)}, s.opts...)
+	limits := s.limits
+	if len(req.Options) > 0 && string(req.Options) != "null" {
+		cfg, err := chunkx.ParseConfig(req.Options, "json")
+		if err != nil {
+			return nil, fmt.Errorf("%w: %w", errInvalidRequest, err)
+		}
+		limits = tighterLimits(cfg.Limits, s.limits)
+		requestOpts, err := cfg.Options()
+		if err != nil {
+			return nil, fmt.Errorf("%w: %w", errInvalidRequest, err)
+		}
+		opts = append(opts, requestOpts...)
+	}
+	if req.Language != "" {
+		if _, ok := languages.GetLanguageConfig(languages.LanguageName(req.Language)); !ok {
+			return nil, fmt.Errorf("%w: unknown language %q", errInvalidRequest, req.Language)
+		}
+		opts = append(opts, chunkx.WithLanguage(languages.LanguageName(req.Language)))
+	}
+	opts = append(opts, chunkx.WithLimits(limits))
+	if s.timeout > 0 {
+		opts = append(opts, chunkx.WithTimeout(s.timeout))
+	}
+
+	return s.chunker.ChunkContext(ctx, req.Content, opts...)
+}
+
+// tighterLimits returns the stricter of two limits for every resource, zero
+// being unlimited.
+func tighterLimits(a, b chunkx.Limits) chunkx.Limits {
+	tighter := func(x, y int) int {
+		if x <= 0 || (y > 0 && y < x) {
+			return y
+		}
+		return x
+	}
+	return chunkx.Limits{
+		MaxBytes:  tighter(a.MaxBytes, b.MaxBytes),
+		MaxChunks: tighter(a.MaxChunks, b.MaxChunks),
+		MaxDepth:  tighter(a.MaxDepth, b.MaxDepth),
+		MaxSteps:  tighter(a.MaxSteps, b.MaxSteps),
+	}
+}
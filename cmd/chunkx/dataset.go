@@ -0,0 +1,103 @@
+package main
+
+import (
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+
+	"github.com/gomantics/chunkx"
+)
+
+// runDataset chunks the inputs into a Hugging Face dataset directory.
+func runDataset(args []string, stdout, stderr io.Writer) int {
+	fs := flag.NewFlagSet("chunkx dataset", flag.ContinueOnError)
+	fs.SetOutput(stderr)
+	fs.Usage = func() {
+		fmt.Fprintln(stderr, "Usage: chunkx dataset -out dir [flags] path ...")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Chunks the inputs into a Hugging Face dataset directory of Parquet shards and")
+		fmt.Fprintln(stderr, "dataset_infos.json, ready to push to the Hub.")
+		fmt.Fprintln(stderr)
+		fs.PrintDefaults()
+	}
+
+	var flags chunkFlags
+	flags.register(fs)
+	out := fs.String("out", "", "directory to write the dataset to (required)")
+	if err := fs.Parse(args); err != nil {
+		if errors.Is(err, flag.ErrHelp) {
+			return 0
+		}
+		return 2
+	}
+	if *out == "" || fs.NArg() == 0 {
+		fs.Usage()
+		return 2
+	}
+
+	cfg, err := flags.load(fs)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	opts, err := cfg.Options()
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+
+	dw, err := chunkx.NewDatasetWriter(*out)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+
+	// Results arrive in walk order, so shards are identical across runs
+	ctx := context.Background()
+	pipeline := chunkx.NewChunkPipeline(flags.workers, append(opts, chunkx.WithOrderedOutput())...)
+
+	var files, chunks, failed int
+	for _, path := range fs.Args() {
+		for result := range pipelinePath(ctx, pipeline, path) {
+			if result.Err != nil {
+				fmt.Fprintf(stderr, "chunkx: %s: %v\n", result.Path, result.Err)
+				failed++
+				continue
+			}
+			if err := dw.Write(result.Path, result.Chunks...); err != nil {
+				fmt.Fprintf(stderr, "chunkx: %v\n", err)
+				return 1
+			}
+			files++
+			chunks += len(result.Chunks)
+		}
+	}
+	if err := dw.Close(); err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+
+	fmt.Fprintf(stdout, "Wrote %d chunks from %d files to %s, %d failed\n", chunks, files, *out, failed)
+	if failed > 0 {
+		return 1
+	}
+	return 0
+}
+
+// pipelinePath chunks a directory, archive, or file with the pipeline.
+func pipelinePath(ctx context.Context, pipeline *chunkx.ChunkPipeline, path string) <-chan chunkx.FileResult {
+	if info, err := os.Stat(path); err == nil && info.IsDir() {
+		return pipeline.RunDir(ctx, path)
+	}
+	if chunkx.IsArchive(path) {
+		return pipeline.RunArchive(ctx, path)
+	}
+
+	paths := make(chan string, 1)
+	paths <- path
+	close(paths)
+	return pipeline.Run(ctx, paths)
+}
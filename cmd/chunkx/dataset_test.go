@@ -0,0 +1,35 @@
+package main
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestRunDataset(t *testing.T) {
+	dir := t.TempDir()
+	writeFile(t, dir, "a.txt", "one\ntwo\n")
+	writeFile(t, dir, "b.txt", "three\n")
+	out := filepath.Join(t.TempDir(), "dataset")
+
+	code, stdout, stderr := runCommand(t, "", "dataset", "-out", out, "-max-size", "1", dir)
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+	if !strings.Contains(stdout, "Wrote 3 chunks from 2 files") {
+		t.Errorf("unexpected summary: %q", stdout)
+	}
+
+	for _, name := range []string{"dataset_infos.json", "data/train-00000-of-00001.parquet"} {
+		if _, err := os.Stat(filepath.Join(out, name)); err != nil {
+			t.Errorf("expected %s: %v", name, err)
+		}
+	}
+}
+
+func TestRunDataset_RequiresOut(t *testing.T) {
+	if code, _, _ := runCommand(t, "", "dataset", t.TempDir()); code != 2 {
+		t.Errorf("exit code = %d, want 2", code)
+	}
+}
@@ -0,0 +1,266 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"text/tabwriter"
+
+	"github.com/gomantics/chunkx"
+)
+
+// fileDiff holds the chunks of a file that differ between two runs.
+type fileDiff struct {
+	path      string
+	added     []record
+	removed   []record
+	unchanged int
+}
+
+// runDiff compares two chunking runs and reports the chunks that would need
+// to be re-embedded.
+func runDiff(args []string, stdout, stderr io.Writer) int {
+	fs := flag.NewFlagSet("chunkx diff", flag.ContinueOnError)
+	fs.SetOutput(stderr)
+	fs.Usage = func() {
+		fmt.Fprintln(stderr, "Usage: chunkx diff [flags] old new")
+		fmt.Fprintln(stderr, "       chunkx diff [flags] -old-config file -new-config file path")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Compares two chunking runs by chunk ID. Each side is either JSON Lines")
+		fmt.Fprintln(stderr, "output from chunkx or a file or directory to chunk.")
+		fmt.Fprintln(stderr)
+		fs.PrintDefaults()
+	}
+
+	var flags chunkFlags
+	flags.register(fs)
+	oldConfig := fs.String("old-config", "", "config file for chunking the old side (default: -config)")
+	newConfig := fs.String("new-config", "", "config file for chunking the new side (default: -config)")
+	price := fs.Float64("price", defaultPricePer1K, "embedding price in dollars per 1K tokens")
+	verbose := fs.Bool("v", false, "list every added and removed chunk")
+	if err := fs.Parse(args); err != nil {
+		if errors.Is(err, flag.ErrHelp) {
+			return 0
+		}
+		return 2
+	}
+
+	inputs := fs.Args()
+	if len(inputs) == 1 && (*oldConfig != "" || *newConfig != "") {
+		inputs = append(inputs, inputs[0])
+	}
+	if len(inputs) != 2 {
+		fs.Usage()
+		return 2
+	}
+
+	ctx := context.Background()
+	sides := make([]map[string][]record, 2)
+	var counter chunkx.TokenCounter
+	for i, config := range []string{*oldConfig, *newConfig} {
+		side := flags
+		if config != "" {
+			side.config = config
+		}
+
+		cfg, err := side.load(fs)
+		if err != nil {
+			fmt.Fprintf(stderr, "chunkx: %v\n", err)
+			return 2
+		}
+		opts, err := cfg.Options()
+		if err != nil {
+			fmt.Fprintf(stderr, "chunkx: %v\n", err)
+			return 2
+		}
+		// Re-embedding cost is measured with the new side's counter
+		counter, _ = cfg.TokenCounter()
+
+		sides[i], err = loadRecords(ctx, inputs[i], flags.workers, opts)
+		if err != nil {
+			fmt.Fprintf(stderr, "chunkx: %v\n", err)
+			return 1
+		}
+	}
+
+	out := bufio.NewWriter(stdout)
+	defer out.Flush()
+
+	if err := printDiff(out, diffRecords(sides[0], sides[1]), counter, *price, *verbose); err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+	return 0
+}
+
+// loadRecords returns the chunk records of one side of a diff, grouped by
+// path. A .jsonl, .msgpack, or .cbor file is read as chunkx output; anything
+// else is chunked.
+// Paths inside a directory or archive are made relative to it so that two
+// directories or archives can be compared.
+func loadRecords(ctx context.Context, input string, workers int, opts []chunkx.Option) (map[string][]record, error) {
+	records := make(map[string][]record)
+
+	if strings.HasSuffix(input, ".jsonl") {
+		f, err := os.Open(input)
+		if err != nil {
+			return nil, err
+		}
+		defer f.Close()
+
+		dec := json.NewDecoder(f)
+		for dec.More() {
+			var r record
+			if err := dec.Decode(&r); err != nil {
+				return nil, fmt.Errorf("%s: %w", input, err)
+			}
+			records[r.Path] = append(records[r.Path], r)
+		}
+		return records, nil
+	}
+
+	if ext := filepath.Ext(input); ext == ".msgpack" || ext == ".cbor" {
+		f, err := os.Open(input)
+		if err != nil {
+			return nil, err
+		}
+		defer f.Close()
+
+		dec := chunkx.NewMessagePackDecoder(f)
+		if ext == ".cbor" {
+			dec = chunkx.NewCBORDecoder(f)
+		}
+		for {
+			path, chunk, err := dec.Decode()
+			if errors.Is(err, io.EOF) {
+				return records, nil
+			}
+			if err != nil {
+				return nil, fmt.Errorf("%s: %w", input, err)
+			}
+			records[path] = append(records[path], newRecord(path, chunk))
+		}
+	}
+
+	info, err := os.Stat(input)
+	if err != nil {
+		return nil, err
+	}
+
+	for result := range chunkPaths(ctx, []string{input}, nil, false, workers, opts) {
+		if result.Err != nil {
+			return nil, fmt.Errorf("%s: %w", result.Path, result.Err)
+		}
+
+		path := filepath.Base(result.Path)
+		if info.IsDir() || chunkx.IsArchive(input) {
+			if rel, err := filepath.Rel(input, result.Path); err == nil {
+				path = filepath.ToSlash(rel)
+			}
+		}
+		for _, chunk := range result.Chunks {
+			records[path] = append(records[path], newRecord(path, chunk))
+		}
+	}
+	return records, nil
+}
+
+// diffRecords compares the chunks of each path by ID, treating them as
+// multisets so duplicate chunks are matched one-to-one. Only paths with
+// changes are returned, sorted by path.
+func diffRecords(prev, next map[string][]record) []fileDiff {
+	var paths []string
+	for path := range prev {
+		paths = append(paths, path)
+	}
+	for path := range next {
+		if _, ok := prev[path]; !ok {
+			paths = append(paths, path)
+		}
+	}
+	slices.Sort(paths)
+
+	var diffs []fileDiff
+	for _, path := range paths {
+		remaining := make(map[string]int)
+		for _, r := range prev[path] {
+			remaining[r.ID]++
+		}
+
+		d := fileDiff{path: path}
+		for _, r := range next[path] {
+			if remaining[r.ID] > 0 {
+				remaining[r.ID]--
+				d.unchanged++
+			} else {
+				d.added = append(d.added, r)
+			}
+		}
+		for _, r := range prev[path] {
+			if remaining[r.ID] > 0 {
+				remaining[r.ID]--
+				d.removed = append(d.removed, r)
+			}
+		}
+
+		if len(d.added) > 0 || len(d.removed) > 0 {
+			diffs = append(diffs, d)
+		}
+	}
+	return diffs
+}
+
+// printDiff writes the diff report to w, estimating the cost of embedding the
+// added chunks at pricePer1K dollars per 1K tokens.
+func printDiff(w io.Writer, diffs []fileDiff, counter chunkx.TokenCounter, pricePer1K float64, verbose bool) error {
+	if len(diffs) == 0 {
+		_, err := fmt.Fprintln(w, "No chunks changed.")
+		return err
+	}
+
+	var added, removed, unchanged, tokens int
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', tabwriter.AlignRight)
+	fmt.Fprintln(tw, "ADDED\tREMOVED\tUNCHANGED\t FILE")
+	for _, d := range diffs {
+		fmt.Fprintf(tw, "+%d\t-%d\t%d\t %s\n", len(d.added), len(d.removed), d.unchanged, d.path)
+		added += len(d.added)
+		removed += len(d.removed)
+		unchanged += d.unchanged
+
+		for _, r := range d.added {
+			size, err := counter.CountTokens(r.Content)
+			if err != nil {
+				return err
+			}
+			tokens += size
+		}
+	}
+	if err := tw.Flush(); err != nil {
+		return err
+	}
+
+	if verbose {
+		fmt.Fprintln(w)
+		for _, d := range diffs {
+			for _, r := range d.removed {
+				fmt.Fprintf(w, "- %s %s:%d-%d\n", r.ID, d.path, r.StartLine, r.EndLine)
+			}
+			for _, r := range d.added {
+				fmt.Fprintf(w, "+ %s %s:%d-%d\n", r.ID, d.path, r.StartLine, r.EndLine)
+			}
+		}
+	}
+
+	fmt.Fprintf(w, "\nTotal: %d added, %d removed, %d unchanged chunks in changed files\n", added, removed, unchanged)
+	_, err := fmt.Fprintf(w, "Re-embedding: %d chunks, %d tokens, estimated $%.4f at $%g per 1K tokens\n",
+		added, tokens, float64(tokens)/1000*pricePer1K, pricePer1K)
+	return err
+}
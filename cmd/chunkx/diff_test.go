@@ -0,0 +1,100 @@
+package main
+
+import (
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestRunDiff_Directories(t *testing.T) {
+	dir := t.TempDir()
+	writeFile(t, dir, "old/a.go", "package a\n\nfunc A() {}\n")
+	writeFile(t, dir, "old/b.go", "package b\n\nfunc B() {}\n")
+	writeFile(t, dir, "new/a.go", "package a\n\nfunc A() {}\n\nfunc C() { println(\"c\") }\n")
+	writeFile(t, dir, "new/b.go", "package b\n\nfunc B() {}\n")
+
+	code, stdout, stderr := runCommand(t, "", "diff", "-max-size", "5", "-v",
+		filepath.Join(dir, "old"), filepath.Join(dir, "new"))
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	if !strings.Contains(stdout, " a.go\n") || strings.Contains(stdout, " b.go\n") {
+		t.Errorf("expected only a.go to be reported:\n%s", stdout)
+	}
+	if !strings.Contains(stdout, "Total: 1 added, 0 removed, 1 unchanged") {
+		t.Errorf("unexpected totals:\n%s", stdout)
+	}
+	if !strings.Contains(stdout, "+ ") || !strings.Contains(stdout, "a.go:5-5") {
+		t.Errorf("expected the added chunk to be listed:\n%s", stdout)
+	}
+}
+
+func TestRunDiff_JSONL(t *testing.T) {
+	dir := t.TempDir()
+	path := writeFile(t, dir, "main.go", "package main\n\nfunc main() {}\n")
+
+	_, stdout, _ := runCommand(t, "", path)
+	old := writeFile(t, dir, "old.jsonl", stdout)
+
+	// The same file chunked again produces identical IDs
+	code, stdout, stderr := runCommand(t, "", "diff", old, old)
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+	if !strings.Contains(stdout, "No chunks changed.") {
+		t.Errorf("expected no changes:\n%s", stdout)
+	}
+}
+
+func TestRunDiff_Configs(t *testing.T) {
+	dir := t.TempDir()
+	writeFile(t, dir, "src/main.go", "package main\n\nfunc main() {}\n\nfunc other() {}\n")
+	oldConfig := writeFile(t, dir, "old.toml", "max_size = 100\n")
+	newConfig := writeFile(t, dir, "new.toml", "max_size = 3\n")
+
+	code, stdout, stderr := runCommand(t, "", "diff", "-old-config", oldConfig, "-new-config", newConfig, filepath.Join(dir, "src"))
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+	if !strings.Contains(stdout, " main.go\n") || !strings.Contains(stdout, "Re-embedding:") {
+		t.Errorf("expected main.go to change between configs:\n%s", stdout)
+	}
+}
+
+func TestRunDiff_InvalidArgs(t *testing.T) {
+	missing := filepath.Join(t.TempDir(), "missing.jsonl")
+
+	if code, _, _ := runCommand(t, "", "diff", "only-one"); code != 2 {
+		t.Errorf("diff with one input exit code = %d, want 2", code)
+	}
+	if code, _, _ := runCommand(t, "", "diff", missing, missing); code != 1 {
+		t.Errorf("diff with missing inputs exit code = %d, want 1", code)
+	}
+}
+
+func TestDiffRecords(t *testing.T) {
+	prev := map[string][]record{
+		"a.go": {{ID: "1"}, {ID: "2"}, {ID: "2"}},
+		"b.go": {{ID: "3"}},
+	}
+	next := map[string][]record{
+		"a.go": {{ID: "1"}, {ID: "2"}, {ID: "4"}},
+		"b.go": {{ID: "3"}},
+		"c.go": {{ID: "5"}},
+	}
+
+	diffs := diffRecords(prev, next)
+	if len(diffs) != 2 {
+		t.Fatalf("expected 2 changed files, got %d", len(diffs))
+	}
+
+	a := diffs[0]
+	if a.path != "a.go" || len(a.added) != 1 || a.added[0].ID != "4" ||
+		len(a.removed) != 1 || a.removed[0].ID != "2" || a.unchanged != 2 {
+		t.Errorf("unexpected diff for a.go: %+v", a)
+	}
+	if c := diffs[1]; c.path != "c.go" || len(c.added) != 1 || c.unchanged != 0 {
+		t.Errorf("unexpected diff for c.go: %+v", c)
+	}
+}
@@ -0,0 +1,525 @@
+// Command chunkx chunks files, directories, or stdin and prints the chunks on
+// stdout.
+//
+// Usage:
+//
+//	chunkx [flags] [path ...]
+//	chunkx stats [flags] [path ...]
+//	chunkx tune [flags] [path ...]
+//	chunkx diff [flags] old new
+//	chunkx repo [flags] url [ref]
+//	chunkx show [flags] file
+//	chunkx watch [flags] dir
+//	chunkx dataset -out dir [flags] path ...
+//
+// Directories and .zip, .tar, and .tar.gz archives are walked recursively;
+// archives are read in memory without extracting them, as are the objects
+// below s3:// and gs:// URLs. With no paths, or a path of "-", the input is
+// read from stdin; -stream chunks it as it arrives, and -stdin0 or
+// -stdin-paths instead read the paths to chunk from stdin. The -format flag
+// selects JSON Lines (the default), a JSON array, human-readable pretty
+// output, TSV, a Parquet file, or a stream of MessagePack or CBOR records.
+// With -schema document, JSON records take the {page_content, metadata}
+// shape of LangChain and LlamaIndex documents. With -manifest, a JSON
+// manifest of every input file, its hash, language, chunk count, and error
+// is written once the run is done.
+//
+// The stats subcommand chunks without printing content and reports per-file
+// and aggregate statistics instead, to tune settings before a large run.
+//
+// The tune subcommand chunks a sample corpus with a grid of strategies, max
+// sizes, and soft limits, reports proxies of the quality of the chunks of each,
+// such as their size uniformity and whether they start at definitions, and
+// recommends a configuration.
+//
+// The diff subcommand compares two runs, given as JSON Lines output or as
+// inputs to chunk, and reports the chunks added and removed by stable ID.
+//
+// The repo subcommand shallow-clones a git repository at a branch, tag, or
+// commit and chunks its tracked files.
+//
+// The show subcommand renders a file with colored chunk boundaries and
+// overlap regions, and can also write the view as an HTML page.
+//
+// The watch subcommand keeps running and prints a JSON line whenever the
+// chunks of a file in the directory are added, updated, or removed.
+//
+// The dataset subcommand writes the chunks as a Hugging Face dataset
+// directory of Parquet shards and dataset_infos.json, to version and share
+// chunk corpora on the Hub.
+//
+// With -post-url, the watch subcommand also posts every created, updated, or
+// deleted file to a URL as a JSON event, to keep a downstream index in sync
+// without polling its output.
+package main
+
+import (
+	"bufio"
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"strings"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+	"github.com/gomantics/chunkx/objectstore"
+)
+
+func main() {
+	os.Exit(run(os.Args[1:], os.Stdin, os.Stdout, os.Stderr))
+}
+
+// configFiles are the config file names looked up in the working directory
+// when -config is not given.
+var configFiles = []string{"chunkx.toml", "chunkx.yaml", "chunkx.yml"}
+
+// chunkFlags holds the flags controlling how inputs are chunked.
+type chunkFlags struct {
+	config          string
+	maxSize         int
+	overlap         float64
+	softLimit       float64
+	language        string
+	counter         string
+	strategy        string
+	transcode       string
+	newlines        string
+	binary          string
+	generated       string
+	license         string
+	comments        string
+	documents       string
+	frontMatter     string
+	frontMatterKeys string
+	fences          string
+	tables          string
+	links           string
+	outputs         string
+	transcripts     string
+	speakers        string
+	openAPI         string
+	json            string
+	context         string
+	redact          string
+	limits          chunkx.Limits
+	sampling        chunkx.Sampling
+	workers         int
+	format          string
+	schema          string
+}
+
+// register adds the chunking flags to fs.
+func (f *chunkFlags) register(fs *flag.FlagSet) {
+	fs.StringVar(&f.config, "config", "", "config file (default: chunkx.toml or chunkx.yaml in the working directory)")
+	fs.IntVar(&f.maxSize, "max-size", chunkx.DefaultMaxSize, "maximum chunk size, in units of the counter")
+	fs.Float64Var(&f.overlap, "overlap", chunkx.DefaultOverlap, "overlap percentage between chunks (0-50)")
+	fs.Float64Var(&f.softLimit, "soft-limit", 0, "percentage of the maximum size a syntax node may exceed it by and still be kept whole")
+	fs.StringVar(&f.language, "lang", "", "language override (default: detect from file extension)")
+	fs.StringVar(&f.counter, "counter", chunkx.CounterWords, "size counter: words, bytes, or lines")
+	fs.StringVar(&f.strategy, "strategy", chunkx.StrategyAST, "chunking strategy: ast or lines")
+	fs.StringVar(&f.transcode, "transcode", "off", "transcode files that aren't UTF-8: off, lossy, or strict")
+	fs.StringVar(&f.newlines, "newlines", "keep", "line endings in chunk content: keep, or lf to normalize them")
+	fs.StringVar(&f.binary, "binary", "chunk", "binary files: chunk, skip, error, strings, or hex")
+	fs.StringVar(&f.generated, "generated", "chunk", "generated and minified files: chunk, skip, or sample")
+	fs.StringVar(&f.license, "license", "keep", "license headers: keep, strip, or exclude to keep them out of chunk sizes")
+	fs.StringVar(&f.comments, "comments", "keep", "comments: keep, strip, only, or separate to chunk code and comments as linked streams")
+	fs.StringVar(&f.documents, "documents", "off", "document routing: off, or auto to chunk code, Markdown, HTML, notebooks, and logs each their own way")
+	fs.StringVar(&f.frontMatter, "front-matter", "keep", "front matter of Markdown and HTML pages: keep, or extract to move it into chunk metadata")
+	fs.StringVar(&f.frontMatterKeys, "front-matter-keys", "", "comma-separated front matter keys to copy onto every chunk of a page, such as title,tags")
+	fs.StringVar(&f.fences, "fences", "keep", "fenced code blocks of Markdown too large for a chunk: keep, or split to split them in the language of their fence")
+	fs.StringVar(&f.tables, "tables", "keep", "Markdown and HTML tables too large for a chunk: keep, or rows to split them by rows, recording their header")
+	fs.StringVar(&f.links, "links", "off", "links of Markdown and HTML chunks: off, or record to list them and the anchor of the chunk's section in its metadata")
+	fs.StringVar(&f.outputs, "outputs", "drop", "outputs of notebook cells: drop, text to keep them truncated, or placeholders to also replace images and HTML with placeholders")
+	fs.StringVar(&f.transcripts, "transcripts", "off", "chat transcripts in JSON messages or ChatML: off, or chat to chunk them on message and turn boundaries")
+	fs.StringVar(&f.speakers, "speakers", "off", "diarized WebVTT, SRT, and JSON transcripts: off, or turns to chunk them by speaker turns, recording speakers and times")
+	fs.StringVar(&f.openAPI, "openapi", "off", "OpenAPI and Swagger documents: off, or operations to chunk them by operation and component, recording routes and operation IDs")
+	fs.StringVar(&f.json, "json", "off", "JSON documents: off, or subtrees to split them into subtrees that fit, recording their JSON pointers")
+	fs.StringVar(&f.context, "context", "", "template of a context line recorded in chunk metadata, such as \"{file} §{heading_path}\" or \"{symbol_path} in {file}\"")
+	fs.StringVar(&f.redact, "redact", "", "comma-separated kinds of data to mask: email, credit_card, api_key, high_entropy, or all")
+	fs.IntVar(&f.limits.MaxBytes, "max-bytes", 0, "skip files larger than this many bytes with an error (default: no limit)")
+	fs.IntVar(&f.limits.MaxChunks, "max-chunks", 0, "fail files with more chunks than this (default: no limit)")
+	fs.IntVar(&f.limits.MaxDepth, "max-depth", 0, "fail files whose syntax tree nests deeper than this (default: no limit)")
+	fs.IntVar(&f.limits.MaxSteps, "max-steps", 0, "fail files needing more syntax tree nodes and lines visited than this (default: no limit)")
+	fs.Float64Var(&f.sampling.Ratio, "sample-ratio", 0, "share of the files of directories and archives to chunk, between 0 and 1 (default: all)")
+	fs.IntVar(&f.sampling.MaxFiles, "max-files", 0, "most files of directories and archives to chunk (default: no limit)")
+	fs.IntVar(&f.sampling.MaxChunksPerFile, "max-chunks-per-file", 0, "most chunks to keep of each file, spread evenly over it (default: no limit)")
+	fs.IntVar(&f.workers, "workers", 0, "number of files chunked concurrently (default: one per CPU)")
+	fs.StringVar(&f.format, "format", formatJSONL, "output format: jsonl, json, pretty, tsv, parquet, msgpack, or cbor")
+	fs.StringVar(&f.schema, "schema", schemaChunkx, "JSON record schema: chunkx, or document for LangChain and LlamaIndex {page_content, metadata} documents")
+}
+
+// load reads the config file, if any, and overlays the flags that were set
+// explicitly, so flags always win over the file.
+func (f *chunkFlags) load(fs *flag.FlagSet) (*chunkx.Config, error) {
+	path := f.config
+	if path == "" {
+		for _, name := range configFiles {
+			if _, err := os.Stat(name); err == nil {
+				path = name
+				break
+			}
+		}
+	}
+
+	cfg := &chunkx.Config{}
+	if path != "" {
+		loaded, err := chunkx.LoadConfig(path)
+		if err != nil {
+			return nil, err
+		}
+		cfg = loaded
+	}
+
+	fs.Visit(func(fl *flag.Flag) {
+		switch fl.Name {
+		case "max-size":
+			cfg.MaxSize = f.maxSize
+		case "overlap":
+			cfg.Overlap = f.overlap
+		case "soft-limit":
+			cfg.SoftLimit = f.softLimit
+		case "lang":
+			cfg.Language = languages.LanguageName(f.language)
+		case "counter":
+			cfg.Counter = f.counter
+		case "strategy":
+			cfg.Strategy = f.strategy
+		case "transcode":
+			cfg.Transcode = f.transcode
+		case "newlines":
+			cfg.Newlines = f.newlines
+		case "binary":
+			cfg.Binary = f.binary
+		case "generated":
+			cfg.Generated = f.generated
+		case "license":
+			cfg.License = f.license
+		case "comments":
+			cfg.Comments = f.comments
+		case "documents":
+			cfg.Documents = f.documents
+		case "front-matter":
+			cfg.FrontMatter = f.frontMatter
+		case "tables":
+			cfg.Tables = f.tables
+		case "links":
+			cfg.Links = f.links
+		case "fences":
+			cfg.Fences = f.fences
+		case "outputs":
+			cfg.Outputs = f.outputs
+		case "transcripts":
+			cfg.Transcripts = f.transcripts
+		case "speakers":
+			cfg.Speakers = f.speakers
+		case "openapi":
+			cfg.OpenAPI = f.openAPI
+		case "json":
+			cfg.JSON = f.json
+		case "context":
+			cfg.Context = f.context
+		case "front-matter-keys":
+			cfg.FrontMatterKeys = splitList(f.frontMatterKeys)
+		case "max-bytes":
+			cfg.Limits.MaxBytes = f.limits.MaxBytes
+		case "max-chunks":
+			cfg.Limits.MaxChunks = f.limits.MaxChunks
+		case "max-depth":
+			cfg.Limits.MaxDepth = f.limits.MaxDepth
+		case "max-steps":
+			cfg.Limits.MaxSteps = f.limits.MaxSteps
+		case "sample-ratio":
+			cfg.Sampling.Ratio = f.sampling.Ratio
+		case "max-files":
+			cfg.Sampling.MaxFiles = f.sampling.MaxFiles
+		case "max-chunks-per-file":
+			cfg.Sampling.MaxChunksPerFile = f.sampling.MaxChunksPerFile
+		case "redact":
+			cfg.Redact = splitList(f.redact)
+		}
+	})
+
+	if cfg.MaxSize == 0 {
+		cfg.MaxSize = chunkx.DefaultMaxSize
+	}
+	return cfg, nil
+}
+
+// splitList splits a comma-separated flag value, dropping empty items.
+func splitList(s string) []string {
+	var items []string
+	for item := range strings.SplitSeq(s, ",") {
+		if item = strings.TrimSpace(item); item != "" {
+			items = append(items, item)
+		}
+	}
+	return items
+}
+
+// inputFlags holds the flags controlling how stdin is read.
+type inputFlags struct {
+	pathsNUL bool // Read NUL-delimited paths from stdin
+	paths    bool // Read newline-delimited paths from stdin
+	stream   bool // Chunk stdin incrementally as it arrives
+}
+
+// register adds the input flags to fs.
+func (f *inputFlags) register(fs *flag.FlagSet) {
+	fs.BoolVar(&f.pathsNUL, "stdin0", false, "read NUL-delimited paths to chunk from stdin, as printed by find -print0")
+	fs.BoolVar(&f.paths, "stdin-paths", false, "read newline-delimited paths to chunk from stdin")
+	fs.BoolVar(&f.stream, "stream", false, "chunk stdin line by line as it arrives, for unbounded streams")
+}
+
+// inputs returns the paths to chunk: the arguments, the paths listed on stdin,
+// or "-" to read stdin itself.
+func (f *inputFlags) inputs(args []string, stdin io.Reader) ([]string, error) {
+	if f.pathsNUL && f.paths {
+		return nil, errors.New("-stdin0 and -stdin-paths are mutually exclusive")
+	}
+	if !f.pathsNUL && !f.paths {
+		if len(args) == 0 {
+			return []string{"-"}, nil
+		}
+		return args, nil
+	}
+
+	if len(args) > 0 || f.stream {
+		return nil, errors.New("paths read from stdin can't be combined with arguments or -stream")
+	}
+
+	delim := byte('\n')
+	if f.pathsNUL {
+		delim = 0
+	}
+
+	var paths []string
+	br := bufio.NewReader(stdin)
+	for {
+		path, err := br.ReadString(delim)
+		if path = strings.TrimSuffix(path, string(delim)); path != "" {
+			paths = append(paths, path)
+		}
+		if errors.Is(err, io.EOF) {
+			return paths, nil
+		}
+		if err != nil {
+			return nil, err
+		}
+	}
+}
+
+// run executes the command and returns its exit code.
+func run(args []string, stdin io.Reader, stdout, stderr io.Writer) int {
+	if len(args) > 0 {
+		switch args[0] {
+		case "stats":
+			return runStats(args[1:], stdin, stdout, stderr)
+		case "tune":
+			return runTune(args[1:], stdin, stdout, stderr)
+		case "diff":
+			return runDiff(args[1:], stdout, stderr)
+		case "repo":
+			return runRepo(args[1:], stdout, stderr)
+		case "show":
+			return runShow(args[1:], stdout, stderr)
+		case "watch":
+			return runWatch(args[1:], stdout, stderr)
+		case "dataset":
+			return runDataset(args[1:], stdout, stderr)
+		}
+	}
+
+	return runChunk(args, stdin, stdout, stderr)
+}
+
+// runChunk chunks the inputs and prints the chunks.
+func runChunk(args []string, stdin io.Reader, stdout, stderr io.Writer) int {
+	fs := flag.NewFlagSet("chunkx", flag.ContinueOnError)
+	fs.SetOutput(stderr)
+	fs.Usage = func() {
+		fmt.Fprintln(stderr, "Usage: chunkx [flags] [path ...]")
+		fmt.Fprintln(stderr, "       chunkx stats [flags] [path ...]")
+		fmt.Fprintln(stderr, "       chunkx tune [flags] [path ...]")
+		fmt.Fprintln(stderr, "       chunkx diff [flags] old new")
+		fmt.Fprintln(stderr, "       chunkx repo [flags] url [ref]")
+		fmt.Fprintln(stderr, "       chunkx show [flags] file")
+		fmt.Fprintln(stderr, "       chunkx watch [flags] dir")
+		fmt.Fprintln(stderr, "       chunkx dataset -out dir [flags] path ...")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Chunks files, directories, or stdin and prints the chunks.")
+		fmt.Fprintln(stderr)
+		fs.PrintDefaults()
+	}
+
+	var flags chunkFlags
+	var input inputFlags
+	flags.register(fs)
+	input.register(fs)
+	manifestPath := fs.String("manifest", "", "write a JSON manifest of every input file, its hash, language, chunk count, and error to this file")
+	if err := fs.Parse(args); err != nil {
+		if errors.Is(err, flag.ErrHelp) {
+			return 0
+		}
+		return 2
+	}
+
+	paths, err := input.inputs(fs.Args(), stdin)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	cfg, err := flags.load(fs)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	opts, err := cfg.Options()
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	var manifest *chunkx.Manifest
+	if *manifestPath != "" {
+		manifest = &chunkx.Manifest{}
+		opts = append(opts, chunkx.WithManifest(manifest))
+	}
+
+	counter, _ := cfg.TokenCounter()
+	out := bufio.NewWriter(stdout)
+	defer out.Flush()
+
+	w, err := newWriter(flags.format, flags.schema, out, counter)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+
+	code := writeResults(w, chunkPaths(context.Background(), paths, stdin, input.stream, flags.workers, opts), stderr)
+	if manifest != nil {
+		if err := writeManifest(*manifestPath, manifest); err != nil {
+			fmt.Fprintf(stderr, "chunkx: %v\n", err)
+			return 1
+		}
+	}
+	return code
+}
+
+// writeManifest writes manifest to path as JSON.
+func writeManifest(path string, manifest *chunkx.Manifest) error {
+	f, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+	if err := manifest.WriteJSON(f); err != nil {
+		f.Close()
+		return err
+	}
+	return f.Close()
+}
+
+// writeResults writes the chunks of every result, reporting failed files on
+// stderr, and returns the exit code.
+func writeResults(w writer, results <-chan chunkx.FileResult, stderr io.Writer) int {
+	failed := false
+	for result := range results {
+		if result.Err != nil {
+			fmt.Fprintf(stderr, "chunkx: %s: %v\n", result.Path, result.Err)
+			failed = true
+			continue
+		}
+		if err := w.write(result); err != nil {
+			fmt.Fprintf(stderr, "chunkx: %v\n", err)
+			return 1
+		}
+	}
+
+	if err := w.close(); err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+
+	if failed {
+		return 1
+	}
+	return 0
+}
+
+// chunkPaths chunks every input path in order. Directories and archives are
+// walked with a pipeline, and "-" is read from stdin. With stream set, stdin is
+// chunked as it arrives and each chunk is delivered as its own result.
+func chunkPaths(ctx context.Context, paths []string, stdin io.Reader, stream bool, workers int, opts []chunkx.Option) <-chan chunkx.FileResult {
+	results := make(chan chunkx.FileResult)
+
+	go func() {
+		defer close(results)
+
+		chunker := chunkx.NewChunker().(chunkx.ContextChunker)
+		pipeline := chunkx.NewChunkPipeline(workers, append(opts, chunkx.WithOrderedOutput())...)
+
+		for _, path := range paths {
+			if path == "-" && stream {
+				for chunk, err := range chunkx.ChunkReader(ctx, stdin, opts...) {
+					if err != nil {
+						results <- chunkx.FileResult{Path: "-", Err: err}
+						break
+					}
+					results <- chunkx.FileResult{Path: "-", Chunks: []chunkx.Chunk{chunk}}
+				}
+				continue
+			}
+			if path == "-" {
+				results <- chunkStdin(ctx, chunker, stdin, opts)
+				continue
+			}
+			if objectstore.IsURL(path) {
+				store, prefix, err := objectstore.Open(path)
+				if err != nil {
+					results <- chunkx.FileResult{Path: path, Err: err}
+					continue
+				}
+				for result := range pipeline.RunObjects(ctx, store, prefix) {
+					results <- result
+				}
+				continue
+			}
+
+			// Paths that can't be stat'ed fail as files, like any unreadable file
+			info, err := os.Stat(path)
+			if err == nil && info.IsDir() {
+				for result := range pipeline.RunDir(ctx, path) {
+					results <- result
+				}
+				continue
+			}
+
+			if chunkx.IsArchive(path) {
+				for result := range pipeline.RunArchive(ctx, path) {
+					results <- result
+				}
+				continue
+			}
+
+			chunks, err := chunker.ChunkFileContext(ctx, path, opts...)
+			results <- chunkx.FileResult{Path: path, Chunks: chunks, Err: err}
+		}
+	}()
+
+	return results
+}
+
+// chunkStdin chunks all of stdin. Without a language override the input is
+// chunked line by line.
+func chunkStdin(ctx context.Context, chunker chunkx.ContextChunker, stdin io.Reader, opts []chunkx.Option) chunkx.FileResult {
+	content, err := io.ReadAll(stdin)
+	if err != nil {
+		return chunkx.FileResult{Path: "-", Err: err}
+	}
+
+	// Options are applied in order, so an explicit language still wins
+	opts = append([]chunkx.Option{chunkx.WithLanguage(languages.Generic)}, opts...)
+	chunks, err := chunker.ChunkContext(ctx, string(content), opts...)
+	return chunkx.FileResult{Path: "-", Chunks: chunks, Err: err}
+}
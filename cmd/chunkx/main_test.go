@@ -0,0 +1,297 @@
+package main
+
+import (
+	"archive/zip"
+	"bytes"
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+// runCommand runs the command and returns its exit code, stdout, and stderr.
+func runCommand(t *testing.T, stdin string, args ...string) (int, string, string) {
+	t.Helper()
+
+	var stdout, stderr bytes.Buffer
+	code := run(args, strings.NewReader(stdin), &stdout, &stderr)
+	return code, stdout.String(), stderr.String()
+}
+
+// decodeRecords parses JSON Lines output.
+func decodeRecords(t *testing.T, output string) []record {
+	t.Helper()
+
+	var records []record
+	dec := json.NewDecoder(strings.NewReader(output))
+	for dec.More() {
+		var r record
+		if err := dec.Decode(&r); err != nil {
+			t.Fatalf("failed to decode output: %v\n%s", err, output)
+		}
+		records = append(records, r)
+	}
+	return records
+}
+
+func writeFile(t *testing.T, dir, name, content string) string {
+	t.Helper()
+
+	path := filepath.Join(dir, name)
+	if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+		t.Fatalf("failed to create directory: %v", err)
+	}
+	if err := os.WriteFile(path, []byte(content), 0o644); err != nil {
+		t.Fatalf("failed to write file: %v", err)
+	}
+	return path
+}
+
+func TestRun_File(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "", "-max-size", "50", filepath.Join("..", "..", "testdata", "sources", "example.go"))
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	records := decodeRecords(t, stdout)
+	if len(records) < 2 {
+		t.Fatalf("expected multiple chunks, got %d", len(records))
+	}
+	for i, r := range records {
+		if r.Language != "go" || r.ID == "" || r.Content == "" {
+			t.Errorf("record %d is incomplete: %+v", i, r)
+		}
+	}
+}
+
+func TestRun_DirectoryIsOrdered(t *testing.T) {
+	dir := t.TempDir()
+	writeFile(t, dir, "b.py", "def b():\n    pass\n")
+	writeFile(t, dir, "a/a.go", "package a\n")
+	writeFile(t, dir, "c.txt", "text\n")
+
+	code, stdout, stderr := runCommand(t, "", dir)
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	var paths []string
+	for _, r := range decodeRecords(t, stdout) {
+		rel, _ := filepath.Rel(dir, r.Path)
+		paths = append(paths, filepath.ToSlash(rel))
+	}
+	if got := strings.Join(paths, ","); got != "a/a.go,b.py,c.txt" {
+		t.Errorf("paths = %s", got)
+	}
+}
+
+func TestRun_Stdin(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "one two\nthree four\n", "-max-size", "2")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	records := decodeRecords(t, stdout)
+	if len(records) != 2 || records[0].Path != "-" || records[0].Language != "generic" {
+		t.Errorf("unexpected records %+v", records)
+	}
+}
+
+func TestRun_StdinWithLanguage(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "package main\n", "-lang", "go", "-")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	records := decodeRecords(t, stdout)
+	if len(records) != 1 || records[0].Language != "go" {
+		t.Errorf("unexpected records %+v", records)
+	}
+}
+
+func TestRun_InvalidFlags(t *testing.T) {
+	tests := [][]string{
+		{"-counter", "chars"},
+		{"-strategy", "semantic"},
+		{"-lang", "klingon"},
+		{"-no-such-flag"},
+	}
+
+	for _, args := range tests {
+		if code, _, _ := runCommand(t, "", args...); code != 2 {
+			t.Errorf("run(%v) exit code = %d, want 2", args, code)
+		}
+	}
+}
+
+func TestRun_MissingFile(t *testing.T) {
+	code, _, stderr := runCommand(t, "", filepath.Join(t.TempDir(), "missing.go"))
+	if code != 1 || !strings.Contains(stderr, "missing.go") {
+		t.Errorf("exit code = %d, stderr = %s", code, stderr)
+	}
+}
+
+func TestRun_Manifest(t *testing.T) {
+	dir := t.TempDir()
+	writeFile(t, dir, "src/a.txt", "one two\n")
+	single := writeFile(t, dir, "b.txt", "three\n")
+	manifestPath := filepath.Join(dir, "manifest.json")
+
+	code, _, stderr := runCommand(t, "", "-manifest", manifestPath, filepath.Join(dir, "src"), single, filepath.Join(dir, "missing.txt"))
+	if code != 1 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	data, err := os.ReadFile(manifestPath)
+	if err != nil {
+		t.Fatal(err)
+	}
+	var manifest struct {
+		Files []struct {
+			Path      string `json:"path"`
+			Hash      string `json:"hash"`
+			Chunks    int    `json:"chunks"`
+			ErrorKind string `json:"error_kind"`
+		} `json:"files"`
+	}
+	if err := json.Unmarshal(data, &manifest); err != nil {
+		t.Fatalf("invalid manifest: %v\n%s", err, data)
+	}
+	if len(manifest.Files) != 3 {
+		t.Fatalf("expected three files, got %s", data)
+	}
+	b, missing, a := manifest.Files[0], manifest.Files[1], manifest.Files[2]
+	if a.Path != filepath.Join(dir, "src", "a.txt") || a.Chunks != 1 || a.Hash == "" {
+		t.Errorf("unexpected entry of the walked file %+v", a)
+	}
+	if b.Path != single || b.Chunks != 1 || b.Hash == "" {
+		t.Errorf("unexpected entry of the single file %+v", b)
+	}
+	if missing.ErrorKind != "io" || missing.Hash != "" {
+		t.Errorf("expected an io error for the missing file, got %+v", missing)
+	}
+}
+
+func TestRun_ConfigFile(t *testing.T) {
+	dir := t.TempDir()
+	writeFile(t, dir, "chunkx.yaml", "strategy: lines\ncounter: lines\nmax_size: 1\n")
+	writeFile(t, dir, "notes.txt", "one\ntwo\nthree\n")
+	t.Chdir(dir)
+
+	code, stdout, stderr := runCommand(t, "", "notes.txt")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+	if records := decodeRecords(t, stdout); len(records) != 3 {
+		t.Errorf("expected 3 single-line chunks from chunkx.yaml, got %d", len(records))
+	}
+
+	// Flags set explicitly take precedence over the file
+	code, stdout, stderr = runCommand(t, "", "-max-size", "3", "notes.txt")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+	if records := decodeRecords(t, stdout); len(records) != 1 {
+		t.Errorf("expected -max-size to override the config file, got %d chunks", len(records))
+	}
+
+	writeFile(t, dir, "broken.toml", "max_tokens = 3\n")
+	if code, _, _ := runCommand(t, "", "-config", "broken.toml", "notes.txt"); code != 2 {
+		t.Errorf("invalid config exit code = %d, want 2", code)
+	}
+}
+
+func TestRun_Archive(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "repo.zip")
+	f, err := os.Create(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	zw := zip.NewWriter(f)
+	w, err := zw.Create("src/main.go")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, err := w.Write([]byte("package main\n\nfunc main() {}\n")); err != nil {
+		t.Fatal(err)
+	}
+	if err := zw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	if err := f.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	code, stdout, stderr := runCommand(t, "", path)
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	records := decodeRecords(t, stdout)
+	if len(records) == 0 {
+		t.Fatal("expected chunks from the archive")
+	}
+	for _, r := range records {
+		if r.Language != "go" || r.Metadata["archive"] != path || r.Metadata["archive_path"] != "src/main.go" {
+			t.Errorf("unexpected record: %+v", r)
+		}
+	}
+}
+
+func TestRun_PathsFromStdin(t *testing.T) {
+	dir := t.TempDir()
+	a := writeFile(t, dir, "a.go", "package a\n")
+	b := writeFile(t, dir, "b b.go", "package b\n")
+
+	tests := []struct {
+		flag  string
+		stdin string
+	}{
+		{"-stdin0", a + "\x00" + b + "\x00"},
+		{"-stdin-paths", a + "\n" + b + "\n"},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.flag, func(t *testing.T) {
+			code, stdout, stderr := runCommand(t, tt.stdin, tt.flag)
+			if code != 0 {
+				t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+			}
+
+			records := decodeRecords(t, stdout)
+			if len(records) != 2 || records[0].Path != a || records[1].Path != b {
+				t.Errorf("expected one chunk per listed file, got %+v", records)
+			}
+		})
+	}
+}
+
+func TestRun_Stream(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "one two\nthree four\nfive six\n", "-stream", "-max-size", "2")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	records := decodeRecords(t, stdout)
+	if len(records) != 3 {
+		t.Fatalf("expected a chunk per line, got %d", len(records))
+	}
+	if records[1].Content != "three four" || records[1].StartLine != 2 || records[1].StartByte != 8 {
+		t.Errorf("unexpected second chunk: %+v", records[1])
+	}
+}
+
+func TestRun_InvalidStdinFlags(t *testing.T) {
+	tests := [][]string{
+		{"-stdin0", "-stdin-paths"},
+		{"-stdin0", "main.go"},
+		{"-stdin-paths", "-stream"},
+	}
+
+	for _, args := range tests {
+		if code, _, _ := runCommand(t, "", args...); code != 2 {
+			t.Errorf("run(%v) exit code = %d, want 2", args, code)
+		}
+	}
+}
@@ -0,0 +1,283 @@
+package main
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"strings"
+
+	"github.com/gomantics/chunkx"
+)
+
+// Output formats supported by the -format flag.
+const (
+	formatJSONL   = "jsonl"
+	formatJSON    = "json"
+	formatPretty  = "pretty"
+	formatTSV     = "tsv"
+	formatParquet = "parquet"
+	formatMsgpack = "msgpack"
+	formatCBOR    = "cbor"
+)
+
+// Output schemas supported by the -schema flag.
+const (
+	schemaChunkx   = "chunkx"
+	schemaDocument = "document"
+)
+
+// record is the serialized form of a chunk in command output.
+type record struct {
+	ID        string            `json:"id"`
+	Path      string            `json:"path"`
+	Language  string            `json:"language"`
+	StartLine int               `json:"start_line"`
+	EndLine   int               `json:"end_line"`
+	StartByte int               `json:"start_byte"`
+	EndByte   int               `json:"end_byte"`
+	NodeTypes []string          `json:"node_types"`
+	Metadata  map[string]string `json:"metadata,omitempty"`
+	Content   string            `json:"content"`
+}
+
+// newRecord creates the output record for a chunk of the file at path.
+func newRecord(path string, chunk chunkx.Chunk) record {
+	return record{
+		ID:        chunk.ID(),
+		Path:      path,
+		Language:  chunk.Language.String(),
+		StartLine: chunk.StartLine,
+		EndLine:   chunk.EndLine,
+		StartByte: chunk.StartByte,
+		EndByte:   chunk.EndByte,
+		NodeTypes: chunk.NodeTypes,
+		Metadata:  chunk.Metadata,
+		Content:   chunk.Content,
+	}
+}
+
+// document is the Document shape used by LangChain and LlamaIndex loaders.
+// Chunk fields are flattened into the metadata next to the chunk's own
+// metadata, and the path is stored as the source.
+type document struct {
+	PageContent string         `json:"page_content"`
+	Metadata    map[string]any `json:"metadata"`
+}
+
+// newDocument creates the output document for a chunk of the file at path.
+func newDocument(path string, chunk chunkx.Chunk) document {
+	metadata := make(map[string]any, len(chunk.Metadata)+8)
+	for key, value := range chunk.Metadata {
+		metadata[key] = value
+	}
+	metadata["id"] = chunk.ID()
+	metadata["source"] = path
+	metadata["language"] = chunk.Language.String()
+	metadata["start_line"] = chunk.StartLine
+	metadata["end_line"] = chunk.EndLine
+	metadata["start_byte"] = chunk.StartByte
+	metadata["end_byte"] = chunk.EndByte
+	metadata["node_types"] = chunk.NodeTypes
+
+	return document{PageContent: chunk.Content, Metadata: metadata}
+}
+
+// writer renders the chunks of each file in an output format.
+type writer interface {
+	write(result chunkx.FileResult) error
+	close() error
+}
+
+// newWriter creates a writer for the named format. JSON formats serialize
+// chunks with the named schema. Sizes in pretty output are measured with
+// counter.
+func newWriter(format, schema string, w io.Writer, counter chunkx.TokenCounter) (writer, error) {
+	var encode func(path string, chunk chunkx.Chunk) any
+	switch schema {
+	case schemaChunkx:
+		encode = func(path string, chunk chunkx.Chunk) any { return newRecord(path, chunk) }
+	case schemaDocument:
+		if format != formatJSONL && format != formatJSON {
+			return nil, fmt.Errorf("schema %q requires the jsonl or json format", schema)
+		}
+		encode = func(path string, chunk chunkx.Chunk) any { return newDocument(path, chunk) }
+	default:
+		return nil, fmt.Errorf("unknown schema %q", schema)
+	}
+
+	switch format {
+	case formatJSONL:
+		return &jsonlWriter{enc: json.NewEncoder(w), encode: encode}, nil
+	case formatJSON:
+		return &jsonWriter{w: w, encode: encode}, nil
+	case formatPretty:
+		return &prettyWriter{w: w, counter: counter}, nil
+	case formatTSV:
+		// The header is written even if no file has chunks
+		if _, err := io.WriteString(w, tsvHeader); err != nil {
+			return nil, err
+		}
+		return &tsvWriter{w: w}, nil
+	case formatParquet:
+		return &parquetWriter{pw: chunkx.NewParquetWriter(w)}, nil
+	case formatMsgpack:
+		return &binaryWriter{enc: chunkx.NewMessagePackEncoder(w)}, nil
+	case formatCBOR:
+		return &binaryWriter{enc: chunkx.NewCBOREncoder(w)}, nil
+	default:
+		return nil, fmt.Errorf("unknown format %q", format)
+	}
+}
+
+// jsonlWriter writes one JSON record per chunk per line, for streaming into
+// jq or bulk loaders.
+type jsonlWriter struct {
+	enc    *json.Encoder
+	encode func(path string, chunk chunkx.Chunk) any
+}
+
+func (w *jsonlWriter) write(result chunkx.FileResult) error {
+	for _, chunk := range result.Chunks {
+		if err := w.enc.Encode(w.encode(result.Path, chunk)); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+func (w *jsonlWriter) close() error {
+	return nil
+}
+
+// jsonWriter writes all records as a single JSON array. Records are streamed
+// as they arrive rather than buffered.
+type jsonWriter struct {
+	w      io.Writer
+	encode func(path string, chunk chunkx.Chunk) any
+	count  int
+}
+
+func (w *jsonWriter) write(result chunkx.FileResult) error {
+	for _, chunk := range result.Chunks {
+		data, err := json.MarshalIndent(w.encode(result.Path, chunk), "  ", "  ")
+		if err != nil {
+			return err
+		}
+
+		sep := ",\n  "
+		if w.count == 0 {
+			sep = "[\n  "
+		}
+		if _, err := fmt.Fprintf(w.w, "%s%s", sep, data); err != nil {
+			return err
+		}
+		w.count++
+	}
+	return nil
+}
+
+func (w *jsonWriter) close() error {
+	if w.count == 0 {
+		_, err := io.WriteString(w.w, "[]\n")
+		return err
+	}
+	_, err := io.WriteString(w.w, "\n]\n")
+	return err
+}
+
+// prettyWriter renders chunk boundaries with line numbers and sizes for
+// eyeballing in a terminal.
+type prettyWriter struct {
+	w       io.Writer
+	counter chunkx.TokenCounter
+}
+
+func (w *prettyWriter) write(result chunkx.FileResult) error {
+	for i, chunk := range result.Chunks {
+		size, err := w.counter.CountTokens(chunk.Content)
+		if err != nil {
+			return err
+		}
+
+		header := fmt.Sprintf("── %s [%d/%d] lines %d-%d · %d tokens · %d bytes ",
+			result.Path, i+1, len(result.Chunks), chunk.StartLine, chunk.EndLine, size, len(chunk.Content))
+		if _, err := fmt.Fprintf(w.w, "%s%s\n", header, strings.Repeat("─", max(0, 72-len([]rune(header))))); err != nil {
+			return err
+		}
+
+		for j, line := range strings.Split(chunk.Content, "\n") {
+			if _, err := fmt.Fprintf(w.w, "%6d │ %s\n", chunk.StartLine+j, line); err != nil {
+				return err
+			}
+		}
+		if _, err := fmt.Fprintln(w.w); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+func (w *prettyWriter) close() error {
+	return nil
+}
+
+// tsvWriter writes one tab-separated row per chunk, after the header row
+// newWriter writes. Tabs, newlines, and backslashes in content are escaped.
+type tsvWriter struct {
+	w io.Writer
+}
+
+// tsvHeader is the header row of TSV output.
+const tsvHeader = "id\tpath\tlanguage\tstart_line\tend_line\tstart_byte\tend_byte\tcontent\n"
+
+// tsvEscaper escapes content so it fits on a single TSV row.
+var tsvEscaper = strings.NewReplacer(`\`, `\\`, "\t", `\t`, "\n", `\n`, "\r", `\r`)
+
+func (w *tsvWriter) write(result chunkx.FileResult) error {
+	for _, chunk := range result.Chunks {
+		_, err := fmt.Fprintf(w.w, "%s\t%s\t%s\t%d\t%d\t%d\t%d\t%s\n",
+			chunk.ID(), tsvEscaper.Replace(result.Path), chunk.Language,
+			chunk.StartLine, chunk.EndLine, chunk.StartByte, chunk.EndByte,
+			tsvEscaper.Replace(chunk.Content))
+		if err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+func (w *tsvWriter) close() error {
+	return nil
+}
+
+// parquetWriter writes all chunks as a single Parquet file.
+type parquetWriter struct {
+	pw *chunkx.ParquetWriter
+}
+
+func (w *parquetWriter) write(result chunkx.FileResult) error {
+	return w.pw.Write(result.Path, result.Chunks...)
+}
+
+func (w *parquetWriter) close() error {
+	return w.pw.Close()
+}
+
+// binaryWriter writes one MessagePack or CBOR record per chunk, with the same
+// fields as the JSON output.
+type binaryWriter struct {
+	enc *chunkx.RecordEncoder
+}
+
+func (w *binaryWriter) write(result chunkx.FileResult) error {
+	for _, chunk := range result.Chunks {
+		if err := w.enc.Encode(result.Path, chunk); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+func (w *binaryWriter) close() error {
+	return nil
+}
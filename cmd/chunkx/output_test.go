@@ -0,0 +1,148 @@
+package main
+
+import (
+	"encoding/json"
+	"errors"
+	"io"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+func TestRun_FormatJSON(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "one two\nthree four\n", "-format", "json", "-max-size", "2")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	var records []record
+	if err := json.Unmarshal([]byte(stdout), &records); err != nil {
+		t.Fatalf("output is not a JSON array: %v\n%s", err, stdout)
+	}
+	if len(records) != 2 || records[1].Content != "three four\n" {
+		t.Errorf("unexpected records %+v", records)
+	}
+}
+
+func TestRun_FormatJSONEmpty(t *testing.T) {
+	code, stdout, _ := runCommand(t, "", "-format", "json", t.TempDir())
+	if code != 0 || stdout != "[]\n" {
+		t.Errorf("exit code = %d, stdout = %q", code, stdout)
+	}
+}
+
+func TestRun_FormatPretty(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "one two\nthree four", "-format", "pretty", "-max-size", "2")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	for _, want := range []string{"[1/2] lines 1-1 · 2 tokens · 7 bytes", "     1 │ one two", "     2 │ three four"} {
+		if !strings.Contains(stdout, want) {
+			t.Errorf("pretty output missing %q:\n%s", want, stdout)
+		}
+	}
+}
+
+func TestRun_FormatTSV(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "a\tb\nc", "-format", "tsv", "-max-size", "10")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	rows := strings.Split(strings.TrimSuffix(stdout, "\n"), "\n")
+	if len(rows) != 2 {
+		t.Fatalf("expected header and one row, got %q", stdout)
+	}
+	if !strings.HasPrefix(rows[0], "id\tpath\t") {
+		t.Errorf("unexpected header %q", rows[0])
+	}
+	fields := strings.Split(rows[1], "\t")
+	if len(fields) != 8 || fields[7] != `a\tb\nc` {
+		t.Errorf("unexpected row %q", rows[1])
+	}
+
+	// Output without chunks still has its header
+	code, stdout, stderr = runCommand(t, "", "-format", "tsv", t.TempDir())
+	if code != 0 || stdout != tsvHeader {
+		t.Errorf("expected only the header, got code %d, stdout %q, stderr %s", code, stdout, stderr)
+	}
+}
+
+func TestRun_FormatParquet(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "one two\nthree four\n", "-format", "parquet", "-max-size", "2")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+	if !strings.HasPrefix(stdout, "PAR1") || !strings.HasSuffix(stdout, "PAR1") {
+		t.Errorf("output is not a Parquet file: %q", stdout)
+	}
+}
+
+func TestRun_FormatMsgpack(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "one two\nthree four\n", "-format", "msgpack", "-max-size", "2")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	dec := chunkx.NewMessagePackDecoder(strings.NewReader(stdout))
+	var contents []string
+	for {
+		path, chunk, err := dec.Decode()
+		if errors.Is(err, io.EOF) {
+			break
+		}
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		if path != "-" {
+			t.Errorf("path = %q, want -", path)
+		}
+		contents = append(contents, chunk.Content)
+	}
+	if len(contents) != 2 || contents[1] != "three four\n" {
+		t.Errorf("unexpected chunks %q", contents)
+	}
+}
+
+func TestRun_SchemaDocument(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "one two\nthree four\n", "-schema", "document", "-max-size", "2")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	lines := strings.Split(strings.TrimSpace(stdout), "\n")
+	if len(lines) != 2 {
+		t.Fatalf("expected 2 documents, got %q", stdout)
+	}
+
+	var doc struct {
+		PageContent string         `json:"page_content"`
+		Metadata    map[string]any `json:"metadata"`
+	}
+	if err := json.Unmarshal([]byte(lines[1]), &doc); err != nil {
+		t.Fatalf("invalid document: %v", err)
+	}
+	if doc.PageContent != "three four\n" {
+		t.Errorf("page_content = %q", doc.PageContent)
+	}
+	if doc.Metadata["source"] != "-" || doc.Metadata["start_line"] != float64(2) || doc.Metadata["id"] == "" {
+		t.Errorf("unexpected metadata %v", doc.Metadata)
+	}
+}
+
+func TestRun_SchemaDocumentRequiresJSON(t *testing.T) {
+	if code, _, _ := runCommand(t, "", "-schema", "document", "-format", "tsv"); code != 2 {
+		t.Errorf("exit code = %d, want 2", code)
+	}
+	if code, _, _ := runCommand(t, "", "-schema", "xml"); code != 2 {
+		t.Errorf("exit code = %d, want 2", code)
+	}
+}
+
+func TestRun_UnknownFormat(t *testing.T) {
+	if code, _, _ := runCommand(t, "", "-format", "xml"); code != 2 {
+		t.Errorf("exit code = %d, want 2", code)
+	}
+}
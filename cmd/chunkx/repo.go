@@ -0,0 +1,73 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+
+	"github.com/gomantics/chunkx"
+)
+
+// runRepo clones a git repository and prints the chunks of its tracked files.
+func runRepo(args []string, stdout, stderr io.Writer) int {
+	fs := flag.NewFlagSet("chunkx repo", flag.ContinueOnError)
+	fs.SetOutput(stderr)
+	fs.Usage = func() {
+		fmt.Fprintln(stderr, "Usage: chunkx repo [flags] url [ref]")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Shallow-clones a git repository at a branch, tag, or commit (default: the")
+		fmt.Fprintln(stderr, "default branch) and prints the chunks of its tracked files.")
+		fmt.Fprintln(stderr)
+		fs.PrintDefaults()
+	}
+
+	var flags chunkFlags
+	flags.register(fs)
+	if err := fs.Parse(args); err != nil {
+		if errors.Is(err, flag.ErrHelp) {
+			return 0
+		}
+		return 2
+	}
+
+	if fs.NArg() < 1 || fs.NArg() > 2 {
+		fs.Usage()
+		return 2
+	}
+	url, ref := fs.Arg(0), fs.Arg(1)
+
+	cfg, err := flags.load(fs)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	opts, err := cfg.Options()
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+
+	counter, _ := cfg.TokenCounter()
+	out := bufio.NewWriter(stdout)
+	defer out.Flush()
+
+	w, err := newWriter(flags.format, flags.schema, out, counter)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+
+	ctx := context.Background()
+	repo, err := chunkx.CloneRepo(ctx, url, ref)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+	defer repo.Close()
+
+	pipeline := chunkx.NewChunkPipeline(flags.workers, append(opts, chunkx.WithOrderedOutput())...)
+	return writeResults(w, pipeline.RunRepo(ctx, repo), stderr)
+}
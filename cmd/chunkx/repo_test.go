@@ -0,0 +1,47 @@
+package main
+
+import (
+	"os/exec"
+	"testing"
+)
+
+func TestRunRepo(t *testing.T) {
+	if _, err := exec.LookPath("git"); err != nil {
+		t.Skip("git is not installed")
+	}
+
+	dir := t.TempDir()
+	writeFile(t, dir, "main.go", "package main\n\nfunc main() {}\n")
+	for _, args := range [][]string{
+		{"init", "--quiet"},
+		{"add", "--all"},
+		{"-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "--quiet", "-m", "initial"},
+	} {
+		cmd := exec.Command("git", args...)
+		cmd.Dir = dir
+		if out, err := cmd.CombinedOutput(); err != nil {
+			t.Fatalf("git %v: %v\n%s", args, err, out)
+		}
+	}
+
+	code, stdout, stderr := runCommand(t, "", "repo", dir)
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	records := decodeRecords(t, stdout)
+	if len(records) == 0 {
+		t.Fatal("expected chunks from the repository")
+	}
+	for _, r := range records {
+		if r.Path != "main.go" || r.Metadata["repo"] != dir || len(r.Metadata["commit"]) != 40 {
+			t.Errorf("unexpected record: %+v", r)
+		}
+	}
+}
+
+func TestRunRepo_InvalidArgs(t *testing.T) {
+	if code, _, _ := runCommand(t, "", "repo"); code != 2 {
+		t.Errorf("repo without a URL exit code = %d, want 2", code)
+	}
+}
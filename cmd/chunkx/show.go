@@ -0,0 +1,272 @@
+package main
+
+import (
+	"bufio"
+	"errors"
+	"flag"
+	"fmt"
+	"html"
+	"io"
+	"os"
+	"strings"
+
+	"github.com/gomantics/chunkx"
+)
+
+// chunkColors are the ANSI foreground colors cycled through for chunks.
+var chunkColors = []int{36, 33, 35, 32, 34, 31}
+
+// showView holds a file and its chunks for visualization.
+type showView struct {
+	path     string
+	lines    []string
+	chunks   []chunkx.Chunk
+	sizes    []int
+	coverage [][]int // Indexes of the chunks containing each line
+}
+
+// newShowView prepares the view of a file, measuring chunks with counter.
+func newShowView(path, content string, chunks []chunkx.Chunk, counter chunkx.TokenCounter) (*showView, error) {
+	v := &showView{
+		path:   path,
+		lines:  strings.Split(strings.TrimSuffix(content, "\n"), "\n"),
+		chunks: chunks,
+	}
+	v.coverage = make([][]int, len(v.lines))
+
+	for i, chunk := range chunks {
+		size, err := counter.CountTokens(chunk.Content)
+		if err != nil {
+			return nil, err
+		}
+		v.sizes = append(v.sizes, size)
+
+		for line := max(chunk.StartLine, 1); line <= min(chunk.EndLine, len(v.lines)); line++ {
+			v.coverage[line-1] = append(v.coverage[line-1], i)
+		}
+	}
+	return v, nil
+}
+
+// header describes chunk i.
+func (v *showView) header(i int) string {
+	chunk := v.chunks[i]
+	return fmt.Sprintf("chunk %d/%d · lines %d-%d · %d tokens · %s",
+		i+1, len(v.chunks), chunk.StartLine, chunk.EndLine, v.sizes[i], strings.Join(chunk.NodeTypes, ", "))
+}
+
+// startsAt returns the chunks starting at line, in order.
+func (v *showView) startsAt(line int) []int {
+	var starts []int
+	for i, chunk := range v.chunks {
+		if chunk.StartLine == line {
+			starts = append(starts, i)
+		}
+	}
+	return starts
+}
+
+// renderTerminal writes the file with a gutter marking the chunk of every line.
+// Lines shared by several chunks because of overlap get a double bar. Without
+// color, chunks are told apart by their headers alone.
+func (v *showView) renderTerminal(w io.Writer, color bool) error {
+	paint := func(i int, s string) string {
+		if !color {
+			return s
+		}
+		return fmt.Sprintf("\x1b[%dm%s\x1b[0m", chunkColors[i%len(chunkColors)], s)
+	}
+
+	for n, line := range v.lines {
+		for _, i := range v.startsAt(n + 1) {
+			header := "── " + v.header(i) + " "
+			fmt.Fprintln(w, paint(i, header+strings.Repeat("─", max(0, 72-len([]rune(header))))))
+		}
+
+		var gutter string
+		switch covering := v.coverage[n]; len(covering) {
+		case 0:
+			gutter = "  "
+		case 1:
+			gutter = paint(covering[0], "│") + " "
+		default:
+			// Overlap: show the two outermost chunks sharing the line
+			gutter = paint(covering[0], "║") + paint(covering[len(covering)-1], "║")
+		}
+
+		if _, err := fmt.Fprintf(w, "%6d %s %s\n", n+1, gutter, line); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// renderHTML writes a self-contained HTML page with each chunk shaded in its
+// own color and overlapping lines striped with the colors of both chunks.
+func (v *showView) renderHTML(w io.Writer) error {
+	hue := func(i int) string {
+		return fmt.Sprintf("hsl(%d, 70%%, 90%%)", (i*67)%360)
+	}
+
+	fmt.Fprintf(w, `<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>%s</title>
+<style>
+body { font-family: ui-monospace, monospace; font-size: 13px; margin: 1em; }
+.line { white-space: pre; }
+.line .n { display: inline-block; width: 5em; color: #888; text-align: right; padding-right: 1em; user-select: none; }
+.chunk { margin-top: 0.6em; font-weight: bold; }
+</style>
+</head>
+<body>
+<h1>%s</h1>
+<p>%d chunks</p>
+`, html.EscapeString(v.path), html.EscapeString(v.path), len(v.chunks))
+
+	for n, line := range v.lines {
+		for _, i := range v.startsAt(n + 1) {
+			fmt.Fprintf(w, "<div class=\"chunk\" style=\"background: %s\">%s</div>\n", hue(i), html.EscapeString(v.header(i)))
+		}
+
+		style, title := "", ""
+		switch covering := v.coverage[n]; len(covering) {
+		case 0:
+		case 1:
+			style = "background: " + hue(covering[0])
+			title = fmt.Sprintf("chunk %d", covering[0]+1)
+		default:
+			first, last := covering[0], covering[len(covering)-1]
+			style = fmt.Sprintf("background: repeating-linear-gradient(45deg, %s 0 8px, %s 8px 16px)", hue(first), hue(last))
+			title = fmt.Sprintf("overlap of chunks %d and %d", first+1, last+1)
+		}
+
+		fmt.Fprintf(w, "<div class=\"line\" style=\"%s\" title=\"%s\"><span class=\"n\">%d</span>%s</div>\n",
+			style, title, n+1, html.EscapeString(line))
+	}
+
+	_, err := io.WriteString(w, "</body>\n</html>\n")
+	return err
+}
+
+// useColor reports whether terminal output to w should be colored.
+func useColor(mode string, w io.Writer) (bool, error) {
+	switch mode {
+	case "always":
+		return true, nil
+	case "never":
+		return false, nil
+	case "auto":
+		if _, ok := os.LookupEnv("NO_COLOR"); ok {
+			return false, nil
+		}
+		f, ok := w.(*os.File)
+		if !ok {
+			return false, nil
+		}
+		info, err := f.Stat()
+		return err == nil && info.Mode()&os.ModeCharDevice != 0, nil
+	default:
+		return false, fmt.Errorf("unknown color mode %q", mode)
+	}
+}
+
+// runShow renders a file with its chunk boundaries for visual inspection.
+func runShow(args []string, stdout, stderr io.Writer) int {
+	fs := flag.NewFlagSet("chunkx show", flag.ContinueOnError)
+	fs.SetOutput(stderr)
+	fs.Usage = func() {
+		fmt.Fprintln(stderr, "Usage: chunkx show [flags] file")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Renders a file with colored chunk boundaries and overlap regions.")
+		fmt.Fprintln(stderr)
+		fs.PrintDefaults()
+	}
+
+	var flags chunkFlags
+	flags.register(fs)
+	colorMode := fs.String("color", "auto", "color output: auto, always, or never")
+	htmlPath := fs.String("html", "", "also write an HTML visualization to this file")
+	if err := fs.Parse(args); err != nil {
+		if errors.Is(err, flag.ErrHelp) {
+			return 0
+		}
+		return 2
+	}
+
+	if fs.NArg() != 1 {
+		fs.Usage()
+		return 2
+	}
+
+	color, err := useColor(*colorMode, stdout)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	cfg, err := flags.load(fs)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	opts, err := cfg.Options()
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	counter, _ := cfg.TokenCounter()
+
+	path := fs.Arg(0)
+	content, err := os.ReadFile(path)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+	chunks, err := chunkx.NewChunker().ChunkFile(path, opts...)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %s: %v\n", path, err)
+		return 1
+	}
+
+	view, err := newShowView(path, string(content), chunks, counter)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+
+	if *htmlPath != "" {
+		if err := writeHTML(*htmlPath, view); err != nil {
+			fmt.Fprintf(stderr, "chunkx: %v\n", err)
+			return 1
+		}
+	}
+
+	out := bufio.NewWriter(stdout)
+	defer out.Flush()
+
+	if err := view.renderTerminal(out, color); err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+	return 0
+}
+
+// writeHTML writes the HTML visualization of view to path.
+func writeHTML(path string, view *showView) error {
+	f, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+
+	w := bufio.NewWriter(f)
+	if err := view.renderHTML(w); err != nil {
+		f.Close()
+		return err
+	}
+	if err := w.Flush(); err != nil {
+		f.Close()
+		return err
+	}
+	return f.Close()
+}
@@ -0,0 +1,81 @@
+package main
+
+import (
+	"bytes"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+func TestShowView_Overlap(t *testing.T) {
+	content := "one\ntwo\nthree\nfour\n"
+	chunks := []chunkx.Chunk{
+		{Content: "one\ntwo\nthree", StartLine: 1, EndLine: 3, NodeTypes: []string{"a"}},
+		{Content: "three\nfour", StartLine: 3, EndLine: 4, NodeTypes: []string{"b"}},
+	}
+
+	view, err := newShowView("f.txt", content, chunks, &chunkx.LineCounter{})
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	var buf bytes.Buffer
+	if err := view.renderTerminal(&buf, false); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	lines := strings.Split(strings.TrimSuffix(buf.String(), "\n"), "\n")
+	if len(lines) != 6 {
+		t.Fatalf("expected 2 headers and 4 lines, got %d:\n%s", len(lines), buf.String())
+	}
+	if !strings.HasPrefix(lines[0], "── chunk 1/2 · lines 1-3 · 3 tokens · a ") {
+		t.Errorf("unexpected header: %q", lines[0])
+	}
+	if !strings.HasPrefix(lines[3], "── chunk 2/2 · lines 3-4") {
+		t.Errorf("expected the second chunk to start before line 3, got %q", lines[3])
+	}
+	if lines[4] != "     3 ║║ three" {
+		t.Errorf("expected line 3 to be marked as overlap, got %q", lines[4])
+	}
+	if lines[5] != "     4 │  four" {
+		t.Errorf("unexpected line 4: %q", lines[5])
+	}
+}
+
+func TestRunShow(t *testing.T) {
+	dir := t.TempDir()
+	path := writeFile(t, dir, "main.go", "package main\n\nfunc a() {}\n\nfunc b() {}\n")
+	htmlPath := filepath.Join(dir, "view.html")
+
+	code, stdout, stderr := runCommand(t, "", "show", "-max-size", "3", "-color", "always", "-html", htmlPath, path)
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+	if !strings.Contains(stdout, "\x1b[") || !strings.Contains(stdout, "chunk 1/") {
+		t.Errorf("expected colored chunk headers:\n%s", stdout)
+	}
+
+	page, err := os.ReadFile(htmlPath)
+	if err != nil {
+		t.Fatalf("failed to read HTML: %v", err)
+	}
+	if !strings.Contains(string(page), `<div class="chunk"`) || !strings.Contains(string(page), "func a() {}") {
+		t.Errorf("unexpected HTML:\n%s", page)
+	}
+}
+
+func TestRunShow_InvalidArgs(t *testing.T) {
+	tests := [][]string{
+		{"show"},
+		{"show", "-color", "rainbow", "main.go"},
+	}
+
+	for _, args := range tests {
+		if code, _, _ := runCommand(t, "", args...); code != 2 {
+			t.Errorf("run(%v) exit code = %d, want 2", args, code)
+		}
+	}
+}
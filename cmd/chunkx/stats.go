@@ -0,0 +1,250 @@
+package main
+
+import (
+	"bufio"
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"strings"
+	"text/tabwriter"
+
+	"github.com/gomantics/chunkx"
+)
+
+// defaultPricePer1K is the default embedding price in dollars per 1K tokens.
+const defaultPricePer1K = 0.00002
+
+// histogramBuckets are the upper bounds of the size histogram, as percentages
+// of the maximum chunk size. Chunks above the last bound are oversized.
+var histogramBuckets = []int{25, 50, 75, 100}
+
+// fileStats summarizes the chunks of a single file.
+type fileStats struct {
+	path       string
+	chunks     int
+	tokens     int
+	minSize    int
+	maxSize    int
+	oversized  int
+	overBudget int
+}
+
+// oversizedChunk records a chunk larger than the maximum size.
+type oversizedChunk struct {
+	path      string
+	startLine int
+	endLine   int
+	size      int
+}
+
+// corpusStats aggregates statistics over all files.
+type corpusStats struct {
+	maxSize    int
+	files      []fileStats
+	histogram  []int
+	oversized  []oversizedChunk
+	overBudget []oversizedChunk // Oversized chunks kept whole by the soft limit
+}
+
+// newCorpusStats creates empty statistics for chunks of at most maxSize.
+func newCorpusStats(maxSize int) *corpusStats {
+	return &corpusStats{
+		maxSize:   maxSize,
+		histogram: make([]int, len(histogramBuckets)+1),
+	}
+}
+
+// add records the chunks of a file, measuring them with counter.
+func (s *corpusStats) add(result chunkx.FileResult, counter chunkx.TokenCounter) error {
+	fs := fileStats{path: result.Path, chunks: len(result.Chunks)}
+
+	for i, chunk := range result.Chunks {
+		size, err := counter.CountTokens(chunk.Content)
+		if err != nil {
+			return err
+		}
+
+		fs.tokens += size
+		if i == 0 || size < fs.minSize {
+			fs.minSize = size
+		}
+		fs.maxSize = max(fs.maxSize, size)
+
+		s.histogram[s.bucket(size)]++
+		if size <= s.maxSize {
+			continue
+		}
+		oversized := oversizedChunk{
+			path:      result.Path,
+			startLine: chunk.StartLine,
+			endLine:   chunk.EndLine,
+			size:      size,
+		}
+		if chunk.Metadata[chunkx.MetadataOverBudget] == "true" {
+			fs.overBudget++
+			s.overBudget = append(s.overBudget, oversized)
+		} else {
+			fs.oversized++
+			s.oversized = append(s.oversized, oversized)
+		}
+	}
+
+	s.files = append(s.files, fs)
+	return nil
+}
+
+// bucket returns the histogram bucket for a chunk size.
+func (s *corpusStats) bucket(size int) int {
+	for i, bound := range histogramBuckets {
+		if size*100 <= bound*s.maxSize {
+			return i
+		}
+	}
+	return len(histogramBuckets)
+}
+
+// totals returns the total number of chunks and tokens.
+func (s *corpusStats) totals() (chunks, tokens int) {
+	for _, fs := range s.files {
+		chunks += fs.chunks
+		tokens += fs.tokens
+	}
+	return chunks, tokens
+}
+
+// print writes the statistics report to w, estimating the embedding cost at
+// pricePer1K dollars per 1K tokens.
+func (s *corpusStats) print(w io.Writer, pricePer1K float64) error {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', tabwriter.AlignRight)
+	fmt.Fprintln(tw, "CHUNKS\tTOKENS\tMIN\tAVG\tMAX\tOVERSIZED\tOVER BUDGET\t FILE")
+	for _, fs := range s.files {
+		avg := 0
+		if fs.chunks > 0 {
+			avg = fs.tokens / fs.chunks
+		}
+		fmt.Fprintf(tw, "%d\t%d\t%d\t%d\t%d\t%d\t%d\t %s\n",
+			fs.chunks, fs.tokens, fs.minSize, avg, fs.maxSize, fs.oversized, fs.overBudget, fs.path)
+	}
+	if err := tw.Flush(); err != nil {
+		return err
+	}
+
+	chunks, tokens := s.totals()
+	fmt.Fprintf(w, "\nTotal: %d files, %d chunks, %d tokens\n", len(s.files), chunks, tokens)
+
+	fmt.Fprintf(w, "\nSize distribution (max size %d):\n", s.maxSize)
+	largest := 0
+	for _, count := range s.histogram {
+		largest = max(largest, count)
+	}
+	for i, count := range s.histogram {
+		bar := 0
+		if largest > 0 {
+			bar = count * 40 / largest
+		}
+		fmt.Fprintf(w, "  %9s  %-40s  %d\n", bucketLabel(i), strings.Repeat("█", bar), count)
+	}
+
+	if len(s.oversized) > 0 {
+		fmt.Fprintf(w, "\nWarning: %d chunks exceed the max size:\n", len(s.oversized))
+		for _, c := range s.oversized {
+			fmt.Fprintf(w, "  %s:%d-%d (%d tokens)\n", c.path, c.startLine, c.endLine, c.size)
+		}
+	}
+	if len(s.overBudget) > 0 {
+		fmt.Fprintf(w, "\nNote: %d chunks exceed the max size within the soft limit:\n", len(s.overBudget))
+		for _, c := range s.overBudget {
+			fmt.Fprintf(w, "  %s:%d-%d (%d tokens)\n", c.path, c.startLine, c.endLine, c.size)
+		}
+	}
+
+	_, err := fmt.Fprintf(w, "\nEstimated embedding cost: $%.4f at $%g per 1K tokens\n",
+		float64(tokens)/1000*pricePer1K, pricePer1K)
+	return err
+}
+
+// bucketLabel describes a histogram bucket.
+func bucketLabel(i int) string {
+	if i == len(histogramBuckets) {
+		return fmt.Sprintf(">%d%%", histogramBuckets[i-1])
+	}
+	lower := 0
+	if i > 0 {
+		lower = histogramBuckets[i-1]
+	}
+	return fmt.Sprintf("%d-%d%%", lower, histogramBuckets[i])
+}
+
+// runStats chunks the inputs and prints statistics instead of chunks.
+func runStats(args []string, stdin io.Reader, stdout, stderr io.Writer) int {
+	fs := flag.NewFlagSet("chunkx stats", flag.ContinueOnError)
+	fs.SetOutput(stderr)
+	fs.Usage = func() {
+		fmt.Fprintln(stderr, "Usage: chunkx stats [flags] [path ...]")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Chunks the inputs without printing content and reports statistics.")
+		fmt.Fprintln(stderr)
+		fs.PrintDefaults()
+	}
+
+	var flags chunkFlags
+	var input inputFlags
+	flags.register(fs)
+	input.register(fs)
+	price := fs.Float64("price", defaultPricePer1K, "embedding price in dollars per 1K tokens")
+	if err := fs.Parse(args); err != nil {
+		if errors.Is(err, flag.ErrHelp) {
+			return 0
+		}
+		return 2
+	}
+	if flags.format != formatJSONL || flags.schema != schemaChunkx {
+		fmt.Fprintln(stderr, "chunkx: stats prints a text report and takes no -format or -schema")
+		return 2
+	}
+
+	paths, err := input.inputs(fs.Args(), stdin)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+
+	cfg, err := flags.load(fs)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	opts, err := cfg.Options()
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	counter, _ := cfg.TokenCounter()
+
+	stats := newCorpusStats(cfg.MaxSize)
+	failed := false
+	for result := range chunkPaths(context.Background(), paths, stdin, input.stream, flags.workers, opts) {
+		if result.Err == nil {
+			result.Err = stats.add(result, counter)
+		}
+		if result.Err != nil {
+			fmt.Fprintf(stderr, "chunkx: %s: %v\n", result.Path, result.Err)
+			failed = true
+		}
+	}
+
+	out := bufio.NewWriter(stdout)
+	defer out.Flush()
+
+	if err := stats.print(out, *price); err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+
+	if failed {
+		return 1
+	}
+	return 0
+}
@@ -0,0 +1,116 @@
+package main
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+func TestRunStats(t *testing.T) {
+	dir := t.TempDir()
+	writeFile(t, dir, "a.txt", "one two three\nfour five six\n")
+	writeFile(t, dir, "b.txt", "seven eight\n")
+
+	code, stdout, stderr := runCommand(t, "", "stats", "-max-size", "3", "-price", "1", dir)
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	for _, want := range []string{
+		"Total: 2 files, 3 chunks, 8 tokens",
+		"Size distribution (max size 3):",
+		"Estimated embedding cost: $0.0080 at $1 per 1K tokens",
+	} {
+		if !strings.Contains(stdout, want) {
+			t.Errorf("stats output missing %q:\n%s", want, stdout)
+		}
+	}
+	if strings.Contains(stdout, "one two three") {
+		t.Errorf("stats output must not include chunk content:\n%s", stdout)
+	}
+}
+
+func TestRunStats_OversizedWarning(t *testing.T) {
+	code, stdout, stderr := runCommand(t, "a b c d e\n", "stats", "-max-size", "2")
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	if !strings.Contains(stdout, "Warning: 1 chunks exceed the max size:") || !strings.Contains(stdout, "-:1-1 (5 tokens)") {
+		t.Errorf("missing oversized warning:\n%s", stdout)
+	}
+}
+
+func TestRunStats_Format(t *testing.T) {
+	for _, args := range [][]string{{"stats", "-format", "json"}, {"stats", "-schema", "document"}} {
+		if code, _, _ := runCommand(t, "a b\n", args...); code != 2 {
+			t.Errorf("run(%v) exit code = %d, want 2", args, code)
+		}
+	}
+}
+
+func TestCorpusStats_Histogram(t *testing.T) {
+	stats := newCorpusStats(100)
+	result := chunkx.FileResult{
+		Path: "f",
+		Chunks: []chunkx.Chunk{
+			{Content: strings.Repeat("w ", 10)},
+			{Content: strings.Repeat("w ", 50)},
+			{Content: strings.Repeat("w ", 100)},
+			{Content: strings.Repeat("w ", 101)},
+		},
+	}
+	if err := stats.add(result, &chunkx.SimpleTokenCounter{}); err != nil {
+		t.Fatalf("add() error = %v", err)
+	}
+
+	want := []int{1, 1, 0, 1, 1}
+	for i := range want {
+		if stats.histogram[i] != want[i] {
+			t.Errorf("histogram = %v, want %v", stats.histogram, want)
+			break
+		}
+	}
+
+	fs := stats.files[0]
+	if fs.minSize != 10 || fs.maxSize != 101 || fs.oversized != 1 {
+		t.Errorf("unexpected file stats %+v", fs)
+	}
+}
+
+func TestCorpusStats_OverBudget(t *testing.T) {
+	stats := newCorpusStats(2)
+	result := chunkx.FileResult{
+		Path: "f",
+		Chunks: []chunkx.Chunk{
+			{Content: "a b c", StartLine: 1, EndLine: 1, Metadata: map[string]string{chunkx.MetadataOverBudget: "true"}},
+			{Content: "d e f", StartLine: 2, EndLine: 2},
+		},
+	}
+	if err := stats.add(result, &chunkx.SimpleTokenCounter{}); err != nil {
+		t.Fatalf("add() error = %v", err)
+	}
+	if fs := stats.files[0]; fs.oversized != 1 || fs.overBudget != 1 {
+		t.Errorf("unexpected file stats %+v", fs)
+	}
+
+	var out strings.Builder
+	if err := stats.print(&out, defaultPricePer1K); err != nil {
+		t.Fatalf("print() error = %v", err)
+	}
+	for _, want := range []string{"Warning: 1 chunks exceed the max size:\n  f:2-2", "Note: 1 chunks exceed the max size within the soft limit:\n  f:1-1"} {
+		if !strings.Contains(out.String(), want) {
+			t.Errorf("stats output missing %q:\n%s", want, out.String())
+		}
+	}
+}
+
+func TestBucketLabel(t *testing.T) {
+	labels := []string{"0-25%", "25-50%", "50-75%", "75-100%", ">100%"}
+	for i, want := range labels {
+		if got := bucketLabel(i); got != want {
+			t.Errorf("bucketLabel(%d) = %q, want %q", i, got, want)
+		}
+	}
+}
@@ -0,0 +1,293 @@
+package main
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"math"
+	"slices"
+	"strconv"
+	"strings"
+	"text/tabwriter"
+
+	"github.com/gomantics/chunkx"
+)
+
+// Default grid of the tune subcommand.
+const (
+	defaultTuneSizes      = "256,512,1024,1500,2048"
+	defaultTuneSoftLimits = "0,20"
+	defaultTuneStrategies = chunkx.StrategyAST + "," + chunkx.StrategyLines
+)
+
+// smallChunkPercent is the share of the maximum size, in percent, below which
+// a chunk counts as too small to be worth embedding on its own.
+const smallChunkPercent = 10
+
+// tuneCandidate is a configuration of the grid and the quality of its chunks.
+type tuneCandidate struct {
+	strategy  string
+	maxSize   int
+	softLimit float64
+
+	chunks     int
+	mean       float64 // Mean chunk size
+	variation  float64 // Coefficient of variation of chunk sizes; lower is more uniform
+	aligned    int     // Chunks starting at an unindented line that closes nothing
+	small      int     // Chunks below smallChunkPercent of the maximum size
+	oversized  int     // Chunks above the maximum size, beyond any soft limit
+	overBudget int     // Chunks above the maximum size within the soft limit
+}
+
+// share returns n as a share of the chunks.
+func (c *tuneCandidate) share(n int) float64 {
+	if c.chunks == 0 {
+		return 0
+	}
+	return float64(n) / float64(c.chunks)
+}
+
+// score ranks the candidate: the share of aligned chunks, less penalties for
+// uneven sizes and for small and oversized chunks. Oversized chunks are
+// penalized most, as embedding models truncate them.
+func (c *tuneCandidate) score() float64 {
+	return c.share(c.aligned) - c.variation/2 - c.share(c.small) - 2*c.share(c.oversized) - c.share(c.overBudget)/2
+}
+
+// measure records the sizes and boundaries of chunks.
+func (c *tuneCandidate) measure(chunks []chunkx.Chunk, counter chunkx.TokenCounter, sizes *[]int) error {
+	for _, chunk := range chunks {
+		size, err := counter.CountTokens(chunk.Content)
+		if err != nil {
+			return err
+		}
+		*sizes = append(*sizes, size)
+		c.chunks++
+		if alignedStart(chunk.Content) {
+			c.aligned++
+		}
+		switch {
+		case size*100 < smallChunkPercent*c.maxSize:
+			c.small++
+		case size > c.maxSize && chunk.Metadata[chunkx.MetadataOverBudget] == "true":
+			c.overBudget++
+		case size > c.maxSize:
+			c.oversized++
+		}
+	}
+	return nil
+}
+
+// summarize computes the mean and variation of the sizes.
+func (c *tuneCandidate) summarize(sizes []int) {
+	if len(sizes) == 0 {
+		return
+	}
+	var sum float64
+	for _, size := range sizes {
+		sum += float64(size)
+	}
+	c.mean = sum / float64(len(sizes))
+	if c.mean == 0 {
+		return
+	}
+	var squares float64
+	for _, size := range sizes {
+		d := float64(size) - c.mean
+		squares += d * d
+	}
+	c.variation = math.Sqrt(squares/float64(len(sizes))) / c.mean
+}
+
+// alignedStart reports whether content starts at a boundary a reader would
+// choose: its first non-blank line is unindented and doesn't close a bracket
+// or continue an expression, as the first line of a definition, statement,
+// or heading doesn't.
+func alignedStart(content string) bool {
+	for line := range strings.Lines(content) {
+		if strings.TrimSpace(line) == "" {
+			continue
+		}
+		return line[0] != ' ' && line[0] != '\t' && !strings.ContainsRune(")]}.,", rune(line[0]))
+	}
+	return false
+}
+
+// parseInts parses a comma-separated list of positive integers.
+func parseInts(s string) ([]int, error) {
+	var ints []int
+	for _, field := range strings.Split(s, ",") {
+		n, err := strconv.Atoi(strings.TrimSpace(field))
+		if err != nil || n <= 0 {
+			return nil, fmt.Errorf("invalid size %q", field)
+		}
+		ints = append(ints, n)
+	}
+	return ints, nil
+}
+
+// parseFloats parses a comma-separated list of non-negative numbers.
+func parseFloats(s string) ([]float64, error) {
+	var floats []float64
+	for _, field := range strings.Split(s, ",") {
+		f, err := strconv.ParseFloat(strings.TrimSpace(field), 64)
+		if err != nil || f < 0 {
+			return nil, fmt.Errorf("invalid soft limit %q", field)
+		}
+		floats = append(floats, f)
+	}
+	return floats, nil
+}
+
+// runTune chunks a sample corpus with every configuration of a grid and
+// recommends the one whose chunks score best.
+func runTune(args []string, stdin io.Reader, stdout, stderr io.Writer) int {
+	fs := flag.NewFlagSet("chunkx tune", flag.ContinueOnError)
+	fs.SetOutput(stderr)
+	fs.Usage = func() {
+		fmt.Fprintln(stderr, "Usage: chunkx tune [flags] [path ...]")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Chunks a sample corpus with a grid of strategies, max sizes, and soft limits,")
+		fmt.Fprintln(stderr, "reports the quality of the chunks of each, and recommends one. Other flags")
+		fmt.Fprintln(stderr, "apply to every configuration.")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Configurations are scored by the share of chunks starting at an unindented")
+		fmt.Fprintln(stderr, "line, as definitions and headings do, less penalties for uneven sizes, for")
+		fmt.Fprintf(stderr, "chunks under %d%% of the max size, and most of all for oversized chunks.\n", smallChunkPercent)
+		fmt.Fprintln(stderr)
+		fs.PrintDefaults()
+	}
+
+	var flags chunkFlags
+	var input inputFlags
+	flags.register(fs)
+	input.register(fs)
+	sizeList := fs.String("sizes", defaultTuneSizes, "comma-separated max sizes to try")
+	softLimitList := fs.String("soft-limits", defaultTuneSoftLimits, "comma-separated soft limits to try, in percent")
+	strategyList := fs.String("strategies", defaultTuneStrategies, "comma-separated strategies to try")
+	if err := fs.Parse(args); err != nil {
+		if errors.Is(err, flag.ErrHelp) {
+			return 0
+		}
+		return 2
+	}
+
+	sizes, err := parseInts(*sizeList)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	softLimits, err := parseFloats(*softLimitList)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	strategies := strings.Split(*strategyList, ",")
+
+	paths, err := input.inputs(fs.Args(), stdin)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	cfg, err := flags.load(fs)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	counter, err := cfg.TokenCounter()
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+
+	// Stdin is chunked once per configuration, so it is read up front
+	var data []byte
+	if slices.Contains(paths, "-") {
+		if data, err = io.ReadAll(stdin); err != nil {
+			fmt.Fprintf(stderr, "chunkx: %v\n", err)
+			return 1
+		}
+	}
+
+	var candidates []tuneCandidate
+	failed := false
+	for _, strategy := range strategies {
+		for _, size := range sizes {
+			for _, softLimit := range softLimits {
+				candidate := tuneCandidate{strategy: strings.TrimSpace(strategy), maxSize: size, softLimit: softLimit}
+				gridCfg := *cfg
+				gridCfg.Strategy, gridCfg.MaxSize, gridCfg.SoftLimit = candidate.strategy, size, softLimit
+				opts, err := gridCfg.Options()
+				if err != nil {
+					fmt.Fprintf(stderr, "chunkx: %v\n", err)
+					return 2
+				}
+
+				var chunkSizes []int
+				for result := range chunkPaths(context.Background(), paths, bytes.NewReader(data), false, flags.workers, opts) {
+					if result.Err == nil {
+						result.Err = candidate.measure(result.Chunks, counter, &chunkSizes)
+					}
+					if result.Err == nil {
+						continue
+					}
+					// Files fail the same way under every configuration, so report them once
+					if len(candidates) == 0 {
+						fmt.Fprintf(stderr, "chunkx: %s: %v\n", result.Path, result.Err)
+					}
+					failed = true
+				}
+				candidate.summarize(chunkSizes)
+				candidates = append(candidates, candidate)
+			}
+		}
+	}
+
+	out := bufio.NewWriter(stdout)
+	defer out.Flush()
+	if err := printTune(out, candidates); err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 1
+	}
+	if failed {
+		return 1
+	}
+	return 0
+}
+
+// printTune writes the table of candidates and the recommendation to w.
+func printTune(w io.Writer, candidates []tuneCandidate) error {
+	best := 0
+	for i := range candidates {
+		if candidates[i].score() > candidates[best].score() {
+			best = i
+		}
+	}
+
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', tabwriter.AlignRight)
+	fmt.Fprintln(tw, "STRATEGY\tMAX SIZE\tSOFT LIMIT\tCHUNKS\tMEAN\tVARIATION\tALIGNED\tSMALL\tOVERSIZED\tOVER BUDGET\tSCORE\t")
+	for i, c := range candidates {
+		mark := ""
+		if i == best {
+			mark = " *"
+		}
+		fmt.Fprintf(tw, "%s\t%d\t%g%%\t%d\t%.0f\t%.2f\t%.0f%%\t%d\t%d\t%d\t%.3f\t%s\n",
+			c.strategy, c.maxSize, c.softLimit, c.chunks, c.mean, c.variation,
+			100*c.share(c.aligned), c.small, c.oversized, c.overBudget, c.score(), mark)
+	}
+	if err := tw.Flush(); err != nil {
+		return err
+	}
+	if len(candidates) == 0 {
+		return nil
+	}
+
+	c := candidates[best]
+	_, err := fmt.Fprintf(w, "\nRecommended: -strategy %s -max-size %d -soft-limit %g\n\nor in chunkx.toml:\n\n"+
+		"  strategy = %q\n  max_size = %d\n  soft_limit = %g\n", c.strategy, c.maxSize, c.softLimit, c.strategy, c.maxSize, c.softLimit)
+	return err
+}
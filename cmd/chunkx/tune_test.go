@@ -0,0 +1,55 @@
+package main
+
+import (
+	"strings"
+	"testing"
+)
+
+func TestRunTune(t *testing.T) {
+	dir := t.TempDir()
+	writeFile(t, dir, "a.txt", strings.Repeat("one two three four five\n", 40))
+	writeFile(t, dir, "b.txt", "six seven\n")
+
+	code, stdout, stderr := runCommand(t, "", "tune", "-sizes", "2,50", "-soft-limits", "0", "-strategies", "lines", dir)
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	// Chunks of 2 words split every line, so only the larger size avoids oversized chunks
+	for _, want := range []string{
+		"STRATEGY",
+		"Recommended: -strategy lines -max-size 50 -soft-limit 0",
+		`strategy = "lines"`,
+		"max_size = 50",
+	} {
+		if !strings.Contains(stdout, want) {
+			t.Errorf("tune output missing %q:\n%s", want, stdout)
+		}
+	}
+}
+
+func TestRunTune_InvalidGrid(t *testing.T) {
+	code, _, stderr := runCommand(t, "", "tune", "-sizes", "a,1")
+	if code != 2 || !strings.Contains(stderr, `invalid size "a"`) {
+		t.Errorf("exit code = %d, stderr = %s", code, stderr)
+	}
+}
+
+func TestAlignedStart(t *testing.T) {
+	tests := []struct {
+		content string
+		want    bool
+	}{
+		{"func main() {}\n", true},
+		{"\n\n# Heading\n", true},
+		{"\treturn nil\n}\n", false},
+		{"}\n\nfunc b() {}\n", false},
+		{".Where(x)\n", false},
+		{"  \n", false},
+	}
+	for _, tt := range tests {
+		if got := alignedStart(tt.content); got != tt.want {
+			t.Errorf("alignedStart(%q) = %v, want %v", tt.content, got, tt.want)
+		}
+	}
+}
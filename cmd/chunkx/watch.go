@@ -0,0 +1,129 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"flag"
+	"fmt"
+	"io"
+	"os"
+	"os/signal"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/webhook"
+)
+
+// watchContext returns the context a watch runs under until interrupted.
+// Tests replace it to bound the run.
+var watchContext = func() (context.Context, context.CancelFunc) {
+	return signal.NotifyContext(context.Background(), os.Interrupt)
+}
+
+// watchRecord is the serialized form of a watch event. Only the chunks added
+// by the event are included, so consumers can upsert Chunks and delete Removed.
+type watchRecord struct {
+	Event   string   `json:"event"`
+	Path    string   `json:"path"`
+	Chunks  []record `json:"chunks,omitempty"`
+	Removed []string `json:"removed,omitempty"`
+	Error   string   `json:"error,omitempty"`
+}
+
+// newWatchRecord creates the output record for a watch event.
+func newWatchRecord(event chunkx.WatchEvent) watchRecord {
+	r := watchRecord{Event: event.Kind.String(), Path: event.Path}
+	if event.Err != nil {
+		r.Error = event.Err.Error()
+		if event.Diff != nil {
+			r.Removed = event.Diff.Removed
+		}
+		return r
+	}
+
+	added := make(map[string]int, len(event.Diff.Added))
+	for _, id := range event.Diff.Added {
+		added[id]++
+	}
+	for _, chunk := range event.Chunks {
+		if id := chunk.ID(); added[id] > 0 {
+			added[id]--
+			r.Chunks = append(r.Chunks, newRecord(event.Path, chunk))
+		}
+	}
+	r.Removed = event.Diff.Removed
+	return r
+}
+
+// postOptions returns the options that post chunk events to url, if set.
+func postOptions(url string) []chunkx.Option {
+	if url == "" {
+		return nil
+	}
+	return []chunkx.Option{chunkx.WithEventListener(&webhook.Listener{URL: url})}
+}
+
+// runWatch watches a directory and prints chunk changes as JSON Lines until
+// interrupted.
+func runWatch(args []string, stdout, stderr io.Writer) int {
+	fs := flag.NewFlagSet("chunkx watch", flag.ContinueOnError)
+	fs.SetOutput(stderr)
+	fs.Usage = func() {
+		fmt.Fprintln(stderr, "Usage: chunkx watch [flags] dir")
+		fmt.Fprintln(stderr)
+		fmt.Fprintln(stderr, "Watches a directory and prints added, updated, and removed chunks as JSON Lines.")
+		fmt.Fprintln(stderr)
+		fs.PrintDefaults()
+	}
+
+	var flags chunkFlags
+	flags.register(fs)
+	interval := fs.Duration("interval", chunkx.DefaultPollInterval, "how often to scan for changes")
+	postURL := fs.String("post-url", "", "also POST every change as a JSON event to this URL")
+	if err := fs.Parse(args); err != nil {
+		if errors.Is(err, flag.ErrHelp) {
+			return 0
+		}
+		return 2
+	}
+
+	if fs.NArg() != 1 {
+		fs.Usage()
+		return 2
+	}
+	if flags.format != formatJSONL || flags.schema != schemaChunkx {
+		fmt.Fprintf(stderr, "chunkx: watch only supports the %s format and %s schema\n", formatJSONL, schemaChunkx)
+		return 2
+	}
+
+	cfg, err := flags.load(fs)
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+	opts, err := cfg.Options()
+	if err != nil {
+		fmt.Fprintf(stderr, "chunkx: %v\n", err)
+		return 2
+	}
+
+	dir := fs.Arg(0)
+	if info, err := os.Stat(dir); err != nil || !info.IsDir() {
+		fmt.Fprintf(stderr, "chunkx: %s is not a directory\n", dir)
+		return 1
+	}
+
+	ctx, stop := watchContext()
+	defer stop()
+
+	enc := json.NewEncoder(stdout)
+	opts = append(opts, chunkx.WithPollInterval(*interval))
+	watcher := chunkx.NewWatcher(dir, append(opts, postOptions(*postURL)...)...)
+	for event := range watcher.Run(ctx) {
+		if err := enc.Encode(newWatchRecord(event)); err != nil {
+			fmt.Fprintf(stderr, "chunkx: %v\n", err)
+			return 1
+		}
+	}
+	return 0
+}
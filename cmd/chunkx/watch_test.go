@@ -0,0 +1,89 @@
+package main
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/gomantics/chunkx/webhook"
+)
+
+func TestRunWatch(t *testing.T) {
+	dir := t.TempDir()
+	path := writeFile(t, dir, "main.go", "package main\n\nfunc main() {}\n")
+
+	orig := watchContext
+	watchContext = func() (context.Context, context.CancelFunc) {
+		return context.WithTimeout(context.Background(), 200*time.Millisecond)
+	}
+	t.Cleanup(func() { watchContext = orig })
+
+	code, stdout, stderr := runCommand(t, "", "watch", "-interval", "10ms", dir)
+	if code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	lines := strings.Split(strings.TrimSpace(stdout), "\n")
+	if len(lines) != 1 {
+		t.Fatalf("expected one event for an unchanged directory, got %d:\n%s", len(lines), stdout)
+	}
+
+	var r watchRecord
+	if err := json.Unmarshal([]byte(lines[0]), &r); err != nil {
+		t.Fatalf("failed to decode event: %v", err)
+	}
+	if r.Event != "added" || r.Path != path || len(r.Chunks) == 0 {
+		t.Errorf("unexpected event: %+v", r)
+	}
+}
+
+func TestRunWatch_PostURL(t *testing.T) {
+	dir := t.TempDir()
+	path := writeFile(t, dir, "main.go", "package main\n")
+
+	events := make(chan webhook.Event, 1)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		var event webhook.Event
+		if err := json.NewDecoder(r.Body).Decode(&event); err != nil {
+			t.Errorf("invalid event: %v", err)
+		}
+		events <- event
+	}))
+	defer server.Close()
+
+	orig := watchContext
+	watchContext = func() (context.Context, context.CancelFunc) {
+		return context.WithTimeout(context.Background(), 200*time.Millisecond)
+	}
+	t.Cleanup(func() { watchContext = orig })
+
+	if code, _, stderr := runCommand(t, "", "watch", "-interval", "10ms", "-post-url", server.URL, dir); code != 0 {
+		t.Fatalf("exit code = %d, stderr = %s", code, stderr)
+	}
+
+	select {
+	case event := <-events:
+		if event.Event != "created" || event.Path != path || len(event.Chunks) == 0 {
+			t.Errorf("unexpected event: %+v", event)
+		}
+	default:
+		t.Fatal("expected the added file to be posted")
+	}
+}
+
+func TestRunWatch_InvalidArgs(t *testing.T) {
+	tests := [][]string{
+		{"watch"},
+		{"watch", "-format", "pretty", t.TempDir()},
+	}
+
+	for _, args := range tests {
+		if code, _, _ := runCommand(t, "", args...); code != 2 {
+			t.Errorf("run(%v) exit code = %d, want 2", args, code)
+		}
+	}
+}
@@ -0,0 +1,311 @@
+package chunkx
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"maps"
+	"slices"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// binaryCodec encodes and decodes the values of a self-describing binary
+// format.
+type binaryCodec interface {
+	appendMap(b []byte, n int) []byte
+	appendArray(b []byte, n int) []byte
+	appendString(b []byte, s string) []byte
+	appendInt(b []byte, n int64) []byte
+
+	// readValue decodes the next value: int64, string, []any,
+	// map[string]any, bool, or nil. Depth is the number of arrays and maps
+	// the value is nested in.
+	readValue(r *bufio.Reader, depth int) (any, error)
+}
+
+// maxRecordDepth is the deepest arrays and maps of a record may nest, which
+// bounds the stack a crafted record can take to decode. Records nest two
+// deep, and the rest is room for unknown keys.
+const maxRecordDepth = 32
+
+// RecordEncoder writes chunks as a stream of compact binary records, which
+// are smaller than JSON and need no escaping of content. Each record is a map
+// with the same keys as the command-line JSON output: id, path, language,
+// start_line, end_line, start_byte, end_byte, node_types, metadata (omitted
+// when empty), and content.
+type RecordEncoder struct {
+	w     io.Writer
+	codec binaryCodec
+	buf   []byte
+}
+
+// NewMessagePackEncoder creates an encoder writing MessagePack records to w.
+func NewMessagePackEncoder(w io.Writer) *RecordEncoder {
+	return &RecordEncoder{w: w, codec: msgpackCodec{}}
+}
+
+// NewCBOREncoder creates an encoder writing CBOR (RFC 8949) records to w.
+func NewCBOREncoder(w io.Writer) *RecordEncoder {
+	return &RecordEncoder{w: w, codec: cborCodec{}}
+}
+
+// Encode writes a record for a chunk of the file at path.
+func (e *RecordEncoder) Encode(path string, chunk Chunk) error {
+	c := e.codec
+
+	fields := 9
+	if len(chunk.Metadata) > 0 {
+		fields++
+	}
+
+	b := c.appendMap(e.buf[:0], fields)
+	b = c.appendString(b, "id")
+	b = c.appendString(b, chunk.ID())
+	b = c.appendString(b, "path")
+	b = c.appendString(b, path)
+	b = c.appendString(b, "language")
+	b = c.appendString(b, string(chunk.Language))
+	for _, f := range []struct {
+		key   string
+		value int
+	}{
+		{"start_line", chunk.StartLine},
+		{"end_line", chunk.EndLine},
+		{"start_byte", chunk.StartByte},
+		{"end_byte", chunk.EndByte},
+	} {
+		b = c.appendString(b, f.key)
+		b = c.appendInt(b, int64(f.value))
+	}
+
+	b = c.appendString(b, "node_types")
+	b = c.appendArray(b, len(chunk.NodeTypes))
+	for _, nodeType := range chunk.NodeTypes {
+		b = c.appendString(b, nodeType)
+	}
+
+	if len(chunk.Metadata) > 0 {
+		b = c.appendString(b, "metadata")
+		b = c.appendMap(b, len(chunk.Metadata))
+		for _, key := range slices.Sorted(maps.Keys(chunk.Metadata)) {
+			b = c.appendString(b, key)
+			b = c.appendString(b, chunk.Metadata[key])
+		}
+	}
+
+	b = c.appendString(b, "content")
+	b = c.appendString(b, chunk.Content)
+
+	e.buf = b
+	_, err := e.w.Write(b)
+	return err
+}
+
+// RecordDecoder reads chunks written by a RecordEncoder.
+type RecordDecoder struct {
+	r     *bufio.Reader
+	codec binaryCodec
+}
+
+// NewMessagePackDecoder creates a decoder reading MessagePack records from r.
+func NewMessagePackDecoder(r io.Reader) *RecordDecoder {
+	return &RecordDecoder{r: bufio.NewReader(r), codec: msgpackCodec{}}
+}
+
+// NewCBORDecoder creates a decoder reading CBOR records from r.
+func NewCBORDecoder(r io.Reader) *RecordDecoder {
+	return &RecordDecoder{r: bufio.NewReader(r), codec: cborCodec{}}
+}
+
+// Decode reads the next record and returns the path and chunk it holds. It
+// returns io.EOF at the end of the stream. Unknown keys are ignored, and the
+// id is recomputed from the content rather than trusted.
+func (d *RecordDecoder) Decode() (string, Chunk, error) {
+	if _, err := d.r.Peek(1); err != nil {
+		return "", Chunk{}, err
+	}
+
+	value, err := d.codec.readValue(d.r, 0)
+	if err != nil {
+		return "", Chunk{}, invalidRecord(err)
+	}
+	fields, ok := value.(map[string]any)
+	if !ok {
+		return "", Chunk{}, fmt.Errorf("%w: record is not a map", ErrInvalidRecord)
+	}
+
+	var path string
+	var chunk Chunk
+	for key, value := range fields {
+		var ok bool
+		switch key {
+		case "path":
+			path, ok = value.(string)
+		case "language":
+			var s string
+			s, ok = value.(string)
+			chunk.Language = languages.LanguageName(s)
+		case "content":
+			chunk.Content, ok = value.(string)
+		case "start_line":
+			chunk.StartLine, ok = recordInt(value)
+		case "end_line":
+			chunk.EndLine, ok = recordInt(value)
+		case "start_byte":
+			chunk.StartByte, ok = recordInt(value)
+		case "end_byte":
+			chunk.EndByte, ok = recordInt(value)
+		case "node_types":
+			chunk.NodeTypes, ok = recordStrings(value)
+		case "metadata":
+			chunk.Metadata, ok = recordMetadata(value)
+		default:
+			ok = true
+		}
+		if !ok {
+			return "", Chunk{}, fmt.Errorf("%w: unexpected value for %s", ErrInvalidRecord, key)
+		}
+	}
+
+	return path, chunk, nil
+}
+
+// invalidRecord wraps a decoding error, reporting a truncated stream as such.
+func invalidRecord(err error) error {
+	if errors.Is(err, io.EOF) {
+		err = io.ErrUnexpectedEOF
+	}
+	return fmt.Errorf("%w: %w", ErrInvalidRecord, err)
+}
+
+// recordInt converts a decoded integer.
+func recordInt(v any) (int, bool) {
+	n, ok := v.(int64)
+	return int(n), ok
+}
+
+// recordStrings converts a decoded array of strings.
+func recordStrings(v any) ([]string, bool) {
+	items, ok := v.([]any)
+	if !ok {
+		return nil, false
+	}
+	strs := make([]string, len(items))
+	for i, item := range items {
+		if strs[i], ok = item.(string); !ok {
+			return nil, false
+		}
+	}
+	return strs, true
+}
+
+// recordMetadata converts a decoded map of strings.
+func recordMetadata(v any) (map[string]string, bool) {
+	if v == nil {
+		return nil, true
+	}
+	m, ok := v.(map[string]any)
+	if !ok {
+		return nil, false
+	}
+	metadata := make(map[string]string, len(m))
+	for key, value := range m {
+		if metadata[key], ok = value.(string); !ok {
+			return nil, false
+		}
+	}
+	return metadata, true
+}
+
+// readFull reads exactly n bytes. The size is checked against what a stream
+// can plausibly hold, and past 64 KiB the buffer grows as the bytes arrive,
+// so a length the stream doesn't hold takes no more memory than the stream.
+func readFull(r *bufio.Reader, n uint64) ([]byte, error) {
+	if n > 1<<31 {
+		return nil, fmt.Errorf("length %d too large", n)
+	}
+	if n <= 64<<10 {
+		b := make([]byte, n)
+		_, err := io.ReadFull(r, b)
+		return b, err
+	}
+	var buf bytes.Buffer
+	if _, err := io.CopyN(&buf, r, int64(n)); err != nil {
+		return nil, err
+	}
+	return buf.Bytes(), nil
+}
+
+// readMapKey reads a map key, which must be a string.
+func readMapKey(c binaryCodec, r *bufio.Reader, depth int) (string, error) {
+	key, err := c.readValue(r, depth)
+	if err != nil {
+		return "", err
+	}
+	s, ok := key.(string)
+	if !ok {
+		return "", errors.New("map key is not a string")
+	}
+	return s, nil
+}
+
+// readUint reads a big-endian unsigned integer of size bytes.
+func readUint(r *bufio.Reader, size int) (uint64, error) {
+	var buf [8]byte
+	if _, err := io.ReadFull(r, buf[8-size:]); err != nil {
+		return 0, err
+	}
+	return binary.BigEndian.Uint64(buf[:]), nil
+}
+
+// readString reads a string of n bytes.
+func readString(r *bufio.Reader, n uint64) (string, error) {
+	b, err := readFull(r, n)
+	return string(b), err
+}
+
+// readArray reads n values of an array nested in depth others. Room for the
+// values is made as they are read rather than for n up front.
+func readArray(c binaryCodec, r *bufio.Reader, n uint64, depth int) ([]any, error) {
+	if n > 1<<24 {
+		return nil, fmt.Errorf("array length %d too large", n)
+	}
+	if depth >= maxRecordDepth {
+		return nil, fmt.Errorf("values nested over %d deep", maxRecordDepth)
+	}
+	items := make([]any, 0, min(n, 1024))
+	for range n {
+		item, err := c.readValue(r, depth+1)
+		if err != nil {
+			return nil, err
+		}
+		items = append(items, item)
+	}
+	return items, nil
+}
+
+// readMap reads n key/value pairs with string keys of a map nested in depth
+// others.
+func readMap(c binaryCodec, r *bufio.Reader, n uint64, depth int) (map[string]any, error) {
+	if n > 1<<24 {
+		return nil, fmt.Errorf("map length %d too large", n)
+	}
+	if depth >= maxRecordDepth {
+		return nil, fmt.Errorf("values nested over %d deep", maxRecordDepth)
+	}
+	m := make(map[string]any, min(n, 1024))
+	for range n {
+		key, err := readMapKey(c, r, depth+1)
+		if err != nil {
+			return nil, err
+		}
+		if m[key], err = c.readValue(r, depth+1); err != nil {
+			return nil, err
+		}
+	}
+	return m, nil
+}
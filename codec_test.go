@@ -0,0 +1,156 @@
+package chunkx
+
+import (
+	"bufio"
+	"bytes"
+	"errors"
+	"io"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestRecordEncoder_RoundTrip(t *testing.T) {
+	chunks := []Chunk{
+		{
+			Content:   "package main\n",
+			StartLine: 1,
+			EndLine:   1,
+			StartByte: 0,
+			EndByte:   13,
+			NodeTypes: []string{"package_clause"},
+			Language:  languages.Go,
+		},
+		{
+			Content:   strings.Repeat("long content ", 10000),
+			StartLine: 70000,
+			EndLine:   80000,
+			StartByte: 1 << 33,
+			EndByte:   1<<33 + 130000,
+			NodeTypes: []string{},
+			Language:  languages.Generic,
+			Metadata:  map[string]string{MetadataArchive: "src.zip", MetadataArchivePath: "a/b.txt"},
+		},
+	}
+
+	codecs := []struct {
+		name   string
+		encode func(io.Writer) *RecordEncoder
+		decode func(io.Reader) *RecordDecoder
+	}{
+		{"msgpack", NewMessagePackEncoder, NewMessagePackDecoder},
+		{"cbor", NewCBOREncoder, NewCBORDecoder},
+	}
+
+	for _, codec := range codecs {
+		t.Run(codec.name, func(t *testing.T) {
+			var buf bytes.Buffer
+			enc := codec.encode(&buf)
+			for i, chunk := range chunks {
+				if err := enc.Encode("file"+strings.Repeat("x", i), chunk); err != nil {
+					t.Fatalf("unexpected error: %v", err)
+				}
+			}
+
+			dec := codec.decode(&buf)
+			for i, want := range chunks {
+				path, got, err := dec.Decode()
+				if err != nil {
+					t.Fatalf("unexpected error: %v", err)
+				}
+				if wantPath := "file" + strings.Repeat("x", i); path != wantPath {
+					t.Errorf("path = %q, want %q", path, wantPath)
+				}
+				if !reflect.DeepEqual(got, want) {
+					t.Errorf("chunk %d = %+v, want %+v", i, got, want)
+				}
+			}
+			if _, _, err := dec.Decode(); !errors.Is(err, io.EOF) {
+				t.Errorf("expected io.EOF at end of stream, got %v", err)
+			}
+		})
+	}
+}
+
+func TestRecordDecoder_Truncated(t *testing.T) {
+	var buf bytes.Buffer
+	if err := NewCBOREncoder(&buf).Encode("a.go", Chunk{Content: "x", NodeTypes: []string{}}); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	dec := NewCBORDecoder(bytes.NewReader(buf.Bytes()[:buf.Len()-1]))
+	if _, _, err := dec.Decode(); !errors.Is(err, ErrInvalidRecord) || !errors.Is(err, io.ErrUnexpectedEOF) {
+		t.Errorf("expected truncated record error, got %v", err)
+	}
+}
+
+func TestRecordDecoder_NotAMap(t *testing.T) {
+	dec := NewMessagePackDecoder(bytes.NewReader([]byte{0x01}))
+	if _, _, err := dec.Decode(); !errors.Is(err, ErrInvalidRecord) {
+		t.Errorf("expected ErrInvalidRecord, got %v", err)
+	}
+}
+
+func TestRecordDecoder_Hostile(t *testing.T) {
+	nested := func(err error) bool { return strings.Contains(err.Error(), "nested") }
+	truncated := func(err error) bool { return errors.Is(err, io.ErrUnexpectedEOF) }
+	tests := []struct {
+		name  string
+		dec   *RecordDecoder
+		check func(error) bool
+	}{
+		// Arrays nested far deeper than any record
+		{"msgpack nesting", NewMessagePackDecoder(bytes.NewReader(append(bytes.Repeat([]byte{0x91}, 10000), 0x01))), nested},
+		{"cbor nesting", NewCBORDecoder(bytes.NewReader(append(bytes.Repeat([]byte{0x81}, 10000), 0x01))), nested},
+		// A string declared 2 GiB long in a stream of a few bytes
+		{"cbor length", NewCBORDecoder(bytes.NewReader([]byte{0x7a, 0x7f, 0xff, 0xff, 0xff, 'a', 'b'})), truncated},
+		{"msgpack length", NewMessagePackDecoder(bytes.NewReader([]byte{0xdb, 0x7f, 0xff, 0xff, 0xff, 'a', 'b'})), truncated},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, _, err := tt.dec.Decode()
+			if !errors.Is(err, ErrInvalidRecord) || !tt.check(err) {
+				t.Errorf("unexpected error %v", err)
+			}
+		})
+	}
+}
+
+func TestBinaryCodec_Encoding(t *testing.T) {
+	tests := []struct {
+		name   string
+		codec  binaryCodec
+		append func(binaryCodec, []byte) []byte
+		want   []byte
+	}{
+		{"msgpack fixint", msgpackCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendInt(b, 5) }, []byte{0x05}},
+		{"msgpack negative fixint", msgpackCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendInt(b, -1) }, []byte{0xff}},
+		{"msgpack uint16", msgpackCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendInt(b, 500) }, []byte{0xcd, 0x01, 0xf4}},
+		{"msgpack int16", msgpackCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendInt(b, -500) }, []byte{0xd1, 0xfe, 0x0c}},
+		{"msgpack int64", msgpackCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendInt(b, 1<<40) }, []byte{0xd3, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00}},
+		{"msgpack fixstr", msgpackCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendString(b, "a") }, []byte{0xa1, 'a'}},
+		{"msgpack fixmap", msgpackCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendMap(b, 0) }, []byte{0x80}},
+		{"cbor small int", cborCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendInt(b, 5) }, []byte{0x05}},
+		{"cbor negative int", cborCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendInt(b, -500) }, []byte{0x39, 0x01, 0xf3}},
+		{"cbor uint16", cborCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendInt(b, 500) }, []byte{0x19, 0x01, 0xf4}},
+		{"cbor text", cborCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendString(b, "a") }, []byte{0x61, 'a'}},
+		{"cbor map", cborCodec{}, func(c binaryCodec, b []byte) []byte { return c.appendMap(b, 0) }, []byte{0xa0}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got := tt.append(tt.codec, nil)
+			if !bytes.Equal(got, tt.want) {
+				t.Errorf("got % x, want % x", got, tt.want)
+			}
+
+			// Every encoded value decodes back
+			if _, err := tt.codec.readValue(bufio.NewReader(bytes.NewReader(got)), 0); err != nil {
+				t.Errorf("readValue: %v", err)
+			}
+		})
+	}
+}
@@ -0,0 +1,290 @@
+package chunkx
+
+import (
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Metadata keys set on chunks made with a CommentMode other than CommentsKeep.
+const (
+	MetadataStream = "stream" // StreamCode or StreamComments
+	MetadataLinked = "linked" // Comma-separated IDs of the chunks of the other stream on the same lines
+)
+
+// Values of MetadataStream.
+const (
+	StreamCode     = "code"     // A chunk of code without its comments
+	StreamComments = "comments" // A chunk of the comments of code
+)
+
+// CommentMode selects how comments are chunked.
+type CommentMode int
+
+const (
+	CommentsKeep     CommentMode = iota // Chunk comments along with the code
+	CommentsStrip                       // Chunk the code without its comments
+	CommentsOnly                        // Chunk only the comments, line by line
+	CommentsSeparate                    // Chunk the code without its comments, followed by the comments, linking the two
+)
+
+// WithComments sets how comments are chunked in the given languages, or in
+// every language if none is given, so the code and the natural language
+// around it can be embedded separately. Later calls override earlier ones for
+// the languages they name.
+//
+// Comments are found in the syntax tree, or by their delimiters when the
+// code can't be parsed. The content of code chunks leaves out comments, along
+// with the lines they take up alone, so it is shorter than EndByte-StartByte
+// when the chunk spans a comment. The content of comment chunks is the
+// comments in its range, one after the other on separate lines. Chunks record
+// their stream in their metadata under MetadataStream, and with
+// CommentsSeparate, the ID of every chunk of the other stream they share a
+// line with, or that starts on the line after a comment chunk, under
+// MetadataLinked.
+func WithComments(mode CommentMode, langs ...languages.LanguageName) Option {
+	return func(c *config) {
+		if c.comments == nil {
+			c.comments = make(map[languages.LanguageName]CommentMode, max(len(langs), 1))
+		}
+		if len(langs) == 0 {
+			clear(c.comments)
+			c.comments[""] = mode
+		}
+		for _, lang := range langs {
+			c.comments[lang] = mode
+		}
+	}
+}
+
+// commentMode returns the comment mode of the configured language.
+func (cfg *config) commentMode() CommentMode {
+	if mode, ok := cfg.comments[cfg.language]; ok {
+		return mode
+	}
+	return cfg.comments[""]
+}
+
+// chunkComments chunks code with comments stripped, comments only, or both,
+// as mode selects.
+func (c *castChunker) chunkComments(code string, cfg *config, mode CommentMode) ([]Chunk, error) {
+	comments, parsed, err := c.syntaxComments(code, cfg)
+	if err != nil {
+		return nil, err
+	}
+	if !parsed {
+		comments = scanComments(code, cfg.language)
+	}
+
+	inner := *cfg
+	inner.comments = nil
+	inner.timeout = 0
+
+	var chunks []Chunk
+	if mode != CommentsOnly {
+		text, offsets := stripComments(code, comments)
+		codeChunks, err := c.chunk(text, &inner)
+		if err != nil {
+			return nil, err
+		}
+		chunks = append(chunks, mapChunks(codeChunks, code, offsets, StreamCode)...)
+	}
+
+	if mode != CommentsStrip {
+		text, offsets := joinComments(code, comments)
+		inner.language = languages.Generic
+		commentChunks, err := c.chunk(text, &inner)
+		if err != nil {
+			return nil, err
+		}
+		for i := range commentChunks {
+			commentChunks[i].Language = cfg.language
+		}
+		commentChunks = mapChunks(commentChunks, code, offsets, StreamComments)
+
+		if mode == CommentsSeparate {
+			linkStreams(chunks, commentChunks)
+		}
+		chunks = append(chunks, commentChunks...)
+	}
+	if err := cfg.limits.checkChunks(chunks); err != nil {
+		return nil, err
+	}
+	return chunks, nil
+}
+
+// scanComments returns the byte ranges of the comments in code, found by the
+// comment delimiters of the language. Strings are skipped up to the end of
+// their line, and line comments must start a line or follow white space.
+func scanComments(code string, lang languages.LanguageName) [][2]int {
+	syntax, ok := commentSyntaxes[lang]
+	if !ok {
+		syntax = commentSyntaxes[languages.Generic]
+	}
+
+	var comments [][2]int
+	var quote byte // Quote of the string being read, if any
+scan:
+	for i := 0; i < len(code); i++ {
+		if quote != 0 {
+			switch code[i] {
+			case '\\':
+				i++
+			case quote, '\n':
+				quote = 0
+			}
+			continue
+		}
+
+		for _, delims := range syntax.blocks {
+			if strings.HasPrefix(code[i:], delims[0]) {
+				end := len(code)
+				if j := strings.Index(code[i+len(delims[0]):], delims[1]); j >= 0 {
+					end = i + len(delims[0]) + j + len(delims[1])
+				}
+				comments = append(comments, [2]int{i, end})
+				i = end - 1
+				continue scan
+			}
+		}
+		for _, marker := range syntax.line {
+			if strings.HasPrefix(code[i:], marker) && (i == 0 || isSpace(rune(code[i-1]))) {
+				end := len(code)
+				if j := strings.IndexAny(code[i:], "\r\n"); j >= 0 {
+					end = i + j
+				}
+				comments = append(comments, [2]int{i, end})
+				i = end - 1
+				continue scan
+			}
+		}
+		if strings.IndexByte(syntax.quotes, code[i]) >= 0 {
+			quote = code[i]
+		}
+	}
+	return comments
+}
+
+// stripComments returns code without its comments, with the map from offsets
+// in the text to offsets in code. A comment that is alone on its lines is
+// left out with its lines, and the white space before a comment that ends a
+// line is left out with it.
+func stripComments(code string, comments [][2]int) (string, *offsetMap) {
+	var tb textBuilder
+	kept := 0
+	for _, comment := range comments {
+		start, end := comment[0], comment[1]
+		for start > kept && (code[start-1] == ' ' || code[start-1] == '\t') {
+			start--
+		}
+		if lineEnd := skipLineEnd(code, end); start == 0 || code[start-1] == '\n' || code[start-1] == '\r' {
+			if lineEnd > end || end == len(code) {
+				end = lineEnd
+			}
+		}
+
+		tb.writeString(code[kept:start])
+		tb.offsets.skip(end - start)
+		kept = end
+	}
+	tb.writeString(code[kept:])
+	return tb.b.String(), &tb.offsets
+}
+
+// skipLineEnd returns the offset after the white space and line ending that
+// follow offset, or offset if other text follows it on its line.
+func skipLineEnd(code string, offset int) int {
+	i := offset
+	for i < len(code) && (code[i] == ' ' || code[i] == '\t') {
+		i++
+	}
+	switch {
+	case strings.HasPrefix(code[i:], "\r\n"):
+		return i + 2
+	case i < len(code) && (code[i] == '\n' || code[i] == '\r'):
+		return i + 1
+	case i == len(code):
+		return i
+	}
+	return offset
+}
+
+// joinComments returns the comments of code, one after the other on separate
+// lines, with the map from offsets in the text to offsets in code. The line
+// feed between two comments stands for the code between them.
+func joinComments(code string, comments [][2]int) (string, *offsetMap) {
+	var tb textBuilder
+	for i, comment := range comments {
+		gap := comment[0] - tb.offsets.origEnd
+		if i == 0 {
+			tb.offsets.skip(gap)
+		} else {
+			tb.writeRune('\n', gap)
+		}
+		tb.writeString(code[comment[0]:comment[1]])
+	}
+	return tb.b.String(), &tb.offsets
+}
+
+// mapChunks moves chunks of a text built from code back onto code, recording
+// their stream unless it is "".
+func mapChunks(chunks []Chunk, code string, offsets *offsetMap, stream string) []Chunk {
+	from, fromLine := 0, 1
+	for i := range chunks {
+		chunk := &chunks[i]
+		start := offsets.original(chunk.StartByte)
+		end := start
+		if chunk.EndByte > chunk.StartByte {
+			// The chunk ends right after the bytes of its last character,
+			// rather than after the text left out after it
+			end = offsets.originalEnd(chunk.EndByte)
+		}
+
+		if start < from {
+			from, fromLine = 0, 1
+		}
+		chunk.StartByte, chunk.EndByte = start, end
+		chunk.StartLine = lineOf(code, from, fromLine, start)
+		chunk.EndLine = lineOf(code, start, chunk.StartLine, end)
+		from, fromLine = start, chunk.StartLine
+
+		if stream == "" {
+			continue
+		}
+		if chunk.Metadata == nil {
+			chunk.Metadata = make(map[string]string, 1)
+		}
+		chunk.Metadata[MetadataStream] = stream
+	}
+	return chunks
+}
+
+// linkStreams records in each chunk of code and comments the IDs of the
+// chunks of the other stream it shares a line with, or that starts on the
+// line after a comment chunk.
+func linkStreams(code, comments []Chunk) {
+	codeIDs := make([]string, len(code))
+	for j := range code {
+		codeIDs[j] = code[j].ID()
+	}
+
+	links := make([][]string, len(code))
+	for i := range comments {
+		var linked []string
+		id := comments[i].ID()
+		for j := range code {
+			if code[j].StartLine <= comments[i].EndLine+1 && code[j].EndLine >= comments[i].StartLine {
+				linked = append(linked, codeIDs[j])
+				links[j] = append(links[j], id)
+			}
+		}
+		if len(linked) > 0 {
+			comments[i].Metadata[MetadataLinked] = strings.Join(linked, ",")
+		}
+	}
+	for j, linked := range links {
+		if len(linked) > 0 {
+			code[j].Metadata[MetadataLinked] = strings.Join(linked, ",")
+		}
+	}
+}
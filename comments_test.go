@@ -0,0 +1,111 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestScanComments(t *testing.T) {
+	tests := []struct {
+		name string
+		lang languages.LanguageName
+		code string
+		want []string
+	}{
+		{"line and block", languages.Go, "x := 1 // one\n/* two\n*/ y := 2\n", []string{"// one", "/* two\n*/"}},
+		{"strings", languages.Go, "url := \"http://example.com\" // home\ns := `/* no */`\n", []string{"// home"}},
+		{"escaped quote", languages.Python, "s = \"\\\" # no\" # yes\n", []string{"# yes"}},
+		{"marker in a word", languages.Bash, "echo ${#args} # count\n", []string{"# count"}},
+		{"unterminated block", languages.C, "int x; /* open", []string{"/* open"}},
+		{"html", languages.HTML, "<p>a</p><!-- note -->\n", []string{"<!-- note -->"}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			var got []string
+			for _, comment := range scanComments(tt.code, tt.lang) {
+				got = append(got, tt.code[comment[0]:comment[1]])
+			}
+			if strings.Join(got, "|") != strings.Join(tt.want, "|") {
+				t.Errorf("comments = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestWithComments(t *testing.T) {
+	code := "# header comment\nx = 1  # set x\ny = \"# not a comment\"\n\n# trailing\nz = 3\n"
+
+	codeChunk := Chunk{
+		Content:   "x = 1\ny = \"# not a comment\"\n\nz = 3\n",
+		StartLine: 2, EndLine: 7, StartByte: 17, EndByte: len(code),
+	}
+	commentChunk := Chunk{
+		Content:   "# header comment\n# set x\n# trailing",
+		StartLine: 1, EndLine: 5, StartByte: 0, EndByte: strings.Index(code, "\nz"),
+	}
+
+	tests := []struct {
+		mode CommentMode
+		want []Chunk
+	}{
+		{CommentsStrip, []Chunk{codeChunk}},
+		{CommentsOnly, []Chunk{commentChunk}},
+		{CommentsSeparate, []Chunk{codeChunk, commentChunk}},
+	}
+	for _, tt := range tests {
+		chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithComments(tt.mode))
+		if err != nil {
+			t.Fatalf("mode %d: unexpected error: %v", tt.mode, err)
+		}
+		if len(chunks) != len(tt.want) {
+			t.Fatalf("mode %d: expected %d chunks, got %+v", tt.mode, len(tt.want), chunks)
+		}
+		for i, want := range tt.want {
+			got := chunks[i]
+			if got.Content != want.Content {
+				t.Errorf("mode %d, chunk %d: content = %q, want %q", tt.mode, i, got.Content, want.Content)
+			}
+			if got.StartLine != want.StartLine || got.EndLine != want.EndLine || got.StartByte != want.StartByte || got.EndByte != want.EndByte {
+				t.Errorf("mode %d, chunk %d: lines %d-%d, bytes %d-%d, want lines %d-%d, bytes %d-%d", tt.mode, i,
+					got.StartLine, got.EndLine, got.StartByte, got.EndByte,
+					want.StartLine, want.EndLine, want.StartByte, want.EndByte)
+			}
+		}
+	}
+
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithComments(CommentsSeparate))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	code0, comments0 := chunks[0], chunks[1]
+	if code0.Metadata[MetadataStream] != StreamCode || comments0.Metadata[MetadataStream] != StreamComments {
+		t.Errorf("streams = %q, %q", code0.Metadata[MetadataStream], comments0.Metadata[MetadataStream])
+	}
+	if code0.Metadata[MetadataLinked] != comments0.ID() || comments0.Metadata[MetadataLinked] != code0.ID() {
+		t.Errorf("chunks aren't linked: %v, %v", code0.Metadata, comments0.Metadata)
+	}
+}
+
+func TestWithComments_Languages(t *testing.T) {
+	code := "x = 1 # one\n"
+
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithComments(CommentsStrip, languages.Python))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) != 1 || chunks[0].Content != code {
+		t.Errorf("expected comments kept in other languages, got %+v", chunks)
+	}
+
+	chunks, err = NewChunker().Chunk(code, WithLanguage(languages.Generic),
+		WithComments(CommentsStrip), WithComments(CommentsKeep, languages.Python))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) != 1 || chunks[0].Content != "x = 1\n" {
+		t.Errorf("expected comments stripped, got %+v", chunks)
+	}
+}
@@ -0,0 +1,835 @@
+package chunkx
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strconv"
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Strategy names accepted in Config.Strategy.
+const (
+	StrategyAST   = "ast"   // Syntax-aware chunking, falling back to lines
+	StrategyLines = "lines" // Line-based chunking for every file
+)
+
+// Counter names accepted in Config.Counter.
+const (
+	CounterWords = "words" // SimpleTokenCounter
+	CounterBytes = "bytes" // ByteCounter
+	CounterLines = "lines" // LineCounter
+)
+
+// LanguageOverride forces a language for files matching a glob pattern.
+type LanguageOverride struct {
+	Pattern  string
+	Language languages.LanguageName
+}
+
+// Config holds chunking settings that can be committed to a repository as a
+// chunkx.toml or chunkx.yaml file and shared by a team. Zero values keep the
+// defaults.
+type Config struct {
+	Strategy         string                 // StrategyAST or StrategyLines
+	MaxSize          int                    // Maximum chunk size
+	Overlap          float64                // Overlap percentage between chunks
+	SoftLimit        float64                // Percentage of the maximum size syntax nodes may exceed it by and be kept whole
+	Counter          string                 // CounterWords, CounterBytes, or CounterLines
+	Language         languages.LanguageName // Language for every file, disabling detection
+	Languages        []LanguageOverride     // Per-glob language overrides, first match wins
+	Ignore           []string               // Glob patterns of paths to skip when walking directories
+	Transcode        string                 // "lossy" or "strict" to transcode files that aren't UTF-8; "off" or empty to keep them as is
+	Newlines         string                 // "lf" to normalize line endings in chunk content; "keep" or empty to keep them as is
+	Binary           string                 // "skip", "error", "strings", or "hex" for binary files; "chunk" or empty to chunk them as text
+	Generated        string                 // "skip" or "sample" for generated and minified files; "chunk" or empty to chunk them
+	License          string                 // "strip" or "exclude" for license headers; "keep" or empty to chunk them
+	Comments         string                 // "strip", "only", or "separate" to chunk code and comments apart; "keep" or empty to chunk them together
+	Documents        string                 // "auto" to route files by their document type; "off" or empty to chunk them by language
+	FrontMatter      string                 // "extract" to move the front matter of pages into chunk metadata; "keep" or empty to chunk it
+	FrontMatterKeys  []string               // Front matter keys copied onto every chunk of a page, which implies "extract"
+	Fences           string                 // "split" to split large fenced code blocks of Markdown in their language; "keep" or empty to split them as Markdown
+	Tables           string                 // "rows" to split large Markdown and HTML tables by rows, recording their header; "keep" or empty to split them as other nodes
+	Links            string                 // "record" to record the links and section anchors of Markdown and HTML chunks; "off" or empty to leave them out
+	Outputs          string                 // "text" or "placeholders" to keep the outputs of notebook cells; "drop" or empty to leave them out
+	Transcripts      string                 // "chat" to chunk chat transcripts by their messages; "off" or empty to chunk them as JSON or text
+	Speakers         string                 // "turns" to chunk diarized WebVTT, SRT, and JSON transcripts by speaker turns; "off" or empty to chunk them as text
+	OpenAPI          string                 // "operations" to chunk OpenAPI and Swagger documents by operation and component; "off" or empty to chunk them as YAML or JSON
+	JSON             string                 // "subtrees" to chunk JSON documents by subtrees, recording their JSON pointers; "off" or empty to chunk them as text
+	StableBoundaries bool                   // Whether to split Markdown and plain text at stable anchors between sentences; see WithStableBoundaries
+	Context          string                 // Template of the context line recorded on every chunk, such as "{file} §{heading_path}"; see WithContextTemplate
+	Redact           []string               // Kinds of sensitive data to mask, such as RedactEmail, or "all"
+	Limits           Limits                 // Resources spent on every input, from the max_bytes, max_chunks, max_depth, and max_steps keys
+	Sampling         Sampling               // Sample of the files of pipelines, from the sample_ratio, max_files, and max_chunks_per_file keys
+}
+
+// LoadConfig reads a configuration file. The format is chosen by extension:
+// .toml, .yaml or .yml, and .json.
+//
+// Only the subset of TOML and YAML needed for chunkx settings is supported:
+// top-level scalars, string arrays, and a languages table mapping glob
+// patterns to language names.
+//
+//	strategy = "ast"
+//	max_size = 500
+//	ignore = ["vendor/**", "*.min.js"]
+//
+//	[languages]
+//	"*.tpl" = "html"
+func LoadConfig(path string) (*Config, error) {
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return nil, fmt.Errorf("failed to read config: %w", err)
+	}
+
+	format := strings.TrimPrefix(strings.ToLower(filepath.Ext(path)), ".")
+	if format == "yml" {
+		format = "yaml"
+	}
+
+	cfg, err := ParseConfig(data, format)
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", path, err)
+	}
+	return cfg, nil
+}
+
+// ParseConfig parses configuration data in the given format: "toml", "yaml",
+// or "json". It accepts the same settings as LoadConfig, which lets language
+// bindings and services pass settings as a JSON object.
+func ParseConfig(data []byte, format string) (*Config, error) {
+	var values []configValue
+	var err error
+	switch format {
+	case "toml":
+		values, err = parseTOMLConfig(string(data))
+	case "yaml":
+		values, err = parseYAMLConfig(string(data))
+	case "json":
+		values, err = parseJSONConfig(data)
+	default:
+		err = fmt.Errorf("unsupported config format %q", format)
+	}
+	if err != nil {
+		return nil, fmt.Errorf("%w: %w", ErrInvalidConfig, err)
+	}
+
+	cfg, err := decodeConfig(values)
+	if err != nil {
+		return nil, fmt.Errorf("%w: %w", ErrInvalidConfig, err)
+	}
+	return cfg, nil
+}
+
+// TokenCounter returns the token counter selected by Counter.
+func (c *Config) TokenCounter() (TokenCounter, error) {
+	switch c.Counter {
+	case "", CounterWords:
+		return &SimpleTokenCounter{}, nil
+	case CounterBytes:
+		return &ByteCounter{}, nil
+	case CounterLines:
+		return &LineCounter{}, nil
+	default:
+		return nil, fmt.Errorf("%w: unknown counter %q", ErrInvalidConfig, c.Counter)
+	}
+}
+
+// Options converts the configuration into chunker options.
+func (c *Config) Options() ([]Option, error) {
+	counter, err := c.TokenCounter()
+	if err != nil {
+		return nil, err
+	}
+
+	opts := []Option{WithTokenCounter(counter)}
+	if c.MaxSize > 0 {
+		opts = append(opts, WithMaxSize(c.MaxSize))
+	}
+	if c.Overlap > 0 {
+		opts = append(opts, WithOverlap(c.Overlap))
+	}
+	if c.SoftLimit > 0 {
+		opts = append(opts, WithSoftLimit(c.SoftLimit))
+	}
+
+	language := c.Language
+	switch c.Strategy {
+	case "", StrategyAST:
+	case StrategyLines:
+		language = languages.Generic
+	default:
+		return nil, fmt.Errorf("%w: unknown strategy %q", ErrInvalidConfig, c.Strategy)
+	}
+
+	if language != "" {
+		lang, ok := languages.GetLanguageConfig(language)
+		if !ok {
+			return nil, fmt.Errorf("%w: unknown language %q", ErrInvalidConfig, language)
+		}
+		opts = append(opts, WithLanguage(lang.Name))
+	}
+
+	for _, override := range c.Languages {
+		if _, ok := languages.GetLanguageConfig(override.Language); !ok {
+			return nil, fmt.Errorf("%w: unknown language %q for %q", ErrInvalidConfig, override.Language, override.Pattern)
+		}
+	}
+	if len(c.Languages) > 0 {
+		opts = append(opts, WithLanguageOverrides(c.Languages...))
+	}
+	if len(c.Ignore) > 0 {
+		opts = append(opts, WithIgnore(c.Ignore...))
+	}
+
+	switch c.Transcode {
+	case "", "off":
+	case "lossy":
+		opts = append(opts, WithTranscoding(TranscodeLossy))
+	case "strict":
+		opts = append(opts, WithTranscoding(TranscodeStrict))
+	default:
+		return nil, fmt.Errorf("%w: unknown transcode mode %q", ErrInvalidConfig, c.Transcode)
+	}
+
+	switch c.Newlines {
+	case "", "keep":
+	case "lf":
+		opts = append(opts, WithNormalizedNewlines())
+	default:
+		return nil, fmt.Errorf("%w: unknown newlines mode %q", ErrInvalidConfig, c.Newlines)
+	}
+
+	switch c.Binary {
+	case "", "chunk":
+	case "skip":
+		opts = append(opts, WithBinaryFiles(BinarySkip))
+	case "error":
+		opts = append(opts, WithBinaryFiles(BinaryError))
+	case "strings":
+		opts = append(opts, WithBinaryFiles(BinaryStrings))
+	case "hex":
+		opts = append(opts, WithBinaryFiles(BinaryHex))
+	default:
+		return nil, fmt.Errorf("%w: unknown binary policy %q", ErrInvalidConfig, c.Binary)
+	}
+
+	switch c.Generated {
+	case "", "chunk":
+	case "skip":
+		opts = append(opts, WithGeneratedFiles(GeneratedSkip))
+	case "sample":
+		opts = append(opts, WithGeneratedFiles(GeneratedSample))
+	default:
+		return nil, fmt.Errorf("%w: unknown generated policy %q", ErrInvalidConfig, c.Generated)
+	}
+
+	switch c.License {
+	case "", "keep":
+	case "strip":
+		opts = append(opts, WithLicenseHeaders(LicenseStrip), WithBoilerplate(NewBoilerplate(DefaultBoilerplateFiles)))
+	case "exclude":
+		opts = append(opts, WithLicenseHeaders(LicenseExclude), WithBoilerplate(NewBoilerplate(DefaultBoilerplateFiles)))
+	default:
+		return nil, fmt.Errorf("%w: unknown license policy %q", ErrInvalidConfig, c.License)
+	}
+
+	switch c.Comments {
+	case "", "keep":
+	case "strip":
+		opts = append(opts, WithComments(CommentsStrip))
+	case "only":
+		opts = append(opts, WithComments(CommentsOnly))
+	case "separate":
+		opts = append(opts, WithComments(CommentsSeparate))
+	default:
+		return nil, fmt.Errorf("%w: unknown comments mode %q", ErrInvalidConfig, c.Comments)
+	}
+
+	switch c.Documents {
+	case "", "off":
+	case "auto":
+		opts = append(opts, WithDocumentRouter(NewDocumentRouter()))
+	default:
+		return nil, fmt.Errorf("%w: unknown documents mode %q", ErrInvalidConfig, c.Documents)
+	}
+
+	switch c.FrontMatter {
+	case "", "keep":
+		if len(c.FrontMatterKeys) > 0 {
+			opts = append(opts, WithFrontMatter(c.FrontMatterKeys...))
+		}
+	case "extract":
+		opts = append(opts, WithFrontMatter(c.FrontMatterKeys...))
+	default:
+		return nil, fmt.Errorf("%w: unknown front matter mode %q", ErrInvalidConfig, c.FrontMatter)
+	}
+
+	switch c.Fences {
+	case "", "keep":
+	case "split":
+		opts = append(opts, WithFencedCode())
+	default:
+		return nil, fmt.Errorf("%w: unknown fences mode %q", ErrInvalidConfig, c.Fences)
+	}
+
+	switch c.Tables {
+	case "", "keep":
+	case "rows":
+		opts = append(opts, WithTableHeaders())
+	default:
+		return nil, fmt.Errorf("%w: unknown tables mode %q", ErrInvalidConfig, c.Tables)
+	}
+
+	switch c.Links {
+	case "", "off":
+	case "record":
+		opts = append(opts, WithLinks())
+	default:
+		return nil, fmt.Errorf("%w: unknown links mode %q", ErrInvalidConfig, c.Links)
+	}
+
+	switch c.Outputs {
+	case "", "drop":
+	case "text":
+		opts = append(opts, WithNotebookOutputs(OutputsText))
+	case "placeholders":
+		opts = append(opts, WithNotebookOutputs(OutputsPlaceholders))
+	default:
+		return nil, fmt.Errorf("%w: unknown outputs policy %q", ErrInvalidConfig, c.Outputs)
+	}
+
+	switch c.Transcripts {
+	case "", "off":
+	case "chat":
+		opts = append(opts, WithChatTranscripts())
+	default:
+		return nil, fmt.Errorf("%w: unknown transcripts mode %q", ErrInvalidConfig, c.Transcripts)
+	}
+
+	switch c.Speakers {
+	case "", "off":
+	case "turns":
+		opts = append(opts, WithSpeakerTurns())
+	default:
+		return nil, fmt.Errorf("%w: unknown speakers mode %q", ErrInvalidConfig, c.Speakers)
+	}
+
+	switch c.OpenAPI {
+	case "", "off":
+	case "operations":
+		opts = append(opts, WithOpenAPI())
+	default:
+		return nil, fmt.Errorf("%w: unknown openapi mode %q", ErrInvalidConfig, c.OpenAPI)
+	}
+
+	switch c.JSON {
+	case "", "off":
+	case "subtrees":
+		opts = append(opts, WithJSONSubtrees())
+	default:
+		return nil, fmt.Errorf("%w: unknown json mode %q", ErrInvalidConfig, c.JSON)
+	}
+
+	if c.StableBoundaries {
+		opts = append(opts, WithStableBoundaries())
+	}
+	if c.Context != "" {
+		opts = append(opts, WithContextTemplate(c.Context))
+	}
+
+	if c.Limits != (Limits{}) {
+		if min(c.Limits.MaxBytes, c.Limits.MaxChunks, c.Limits.MaxDepth, c.Limits.MaxSteps) < 0 {
+			return nil, fmt.Errorf("%w: negative limit in %+v", ErrInvalidConfig, c.Limits)
+		}
+		opts = append(opts, WithLimits(c.Limits))
+	}
+
+	if c.Sampling != (Sampling{}) {
+		if c.Sampling.Ratio < 0 || c.Sampling.Ratio > 1 || min(c.Sampling.MaxFiles, c.Sampling.MaxChunksPerFile) < 0 {
+			return nil, fmt.Errorf("%w: invalid sampling %+v", ErrInvalidConfig, c.Sampling)
+		}
+		opts = append(opts, WithSampling(c.Sampling))
+	}
+
+	if len(c.Redact) > 0 {
+		detectors, err := redactDetectors(c.Redact)
+		if err != nil {
+			return nil, err
+		}
+		opts = append(opts, WithRedaction(detectors...))
+	}
+
+	return opts, nil
+}
+
+// WithLanguageOverrides forces the language of files whose path matches a
+// pattern, taking precedence over detection by extension. The first matching
+// override wins. An explicit WithLanguage still applies to every file.
+func WithLanguageOverrides(overrides ...LanguageOverride) Option {
+	return func(c *config) {
+		c.languageOverrides = overrides
+	}
+}
+
+// WithIgnore sets glob patterns of paths that ChunkPipeline.RunDir skips.
+// Patterns without a slash match file and directory names at any depth;
+// patterns with a slash match trailing parts of the path, and a "**" segment
+// matches any number of directories.
+func WithIgnore(patterns ...string) Option {
+	return func(c *config) {
+		c.ignore = patterns
+	}
+}
+
+// configValue is a key and its raw value from a configuration file. Values are
+// a string, a []string, or a []configValue for tables.
+type configValue struct {
+	key   string
+	value any
+}
+
+// decodeConfig converts parsed values into a Config.
+func decodeConfig(values []configValue) (*Config, error) {
+	cfg := &Config{}
+
+	for _, v := range values {
+		var err error
+		switch v.key {
+		case "strategy":
+			cfg.Strategy, err = configString(v)
+		case "counter":
+			cfg.Counter, err = configString(v)
+		case "transcode":
+			cfg.Transcode, err = configString(v)
+		case "newlines":
+			cfg.Newlines, err = configString(v)
+		case "binary":
+			cfg.Binary, err = configString(v)
+		case "generated":
+			cfg.Generated, err = configString(v)
+		case "license":
+			cfg.License, err = configString(v)
+		case "comments":
+			cfg.Comments, err = configString(v)
+		case "documents":
+			cfg.Documents, err = configString(v)
+		case "fences":
+			cfg.Fences, err = configString(v)
+		case "tables":
+			cfg.Tables, err = configString(v)
+		case "links":
+			cfg.Links, err = configString(v)
+		case "outputs":
+			cfg.Outputs, err = configString(v)
+		case "transcripts":
+			cfg.Transcripts, err = configString(v)
+		case "speakers":
+			cfg.Speakers, err = configString(v)
+		case "openapi":
+			cfg.OpenAPI, err = configString(v)
+		case "json":
+			cfg.JSON, err = configString(v)
+		case "context":
+			cfg.Context, err = configString(v)
+		case "stable_boundaries":
+			cfg.StableBoundaries, err = configBool(v)
+		case "front_matter":
+			cfg.FrontMatter, err = configString(v)
+		case "front_matter_keys":
+			keys, ok := v.value.([]string)
+			if !ok {
+				err = fmt.Errorf("%s must be a list of strings", v.key)
+			}
+			cfg.FrontMatterKeys = keys
+		case "language":
+			var name string
+			name, err = configString(v)
+			cfg.Language = languages.LanguageName(name)
+		case "max_size":
+			cfg.MaxSize, err = configInt(v)
+		case "max_bytes":
+			cfg.Limits.MaxBytes, err = configInt(v)
+		case "max_chunks":
+			cfg.Limits.MaxChunks, err = configInt(v)
+		case "max_depth":
+			cfg.Limits.MaxDepth, err = configInt(v)
+		case "max_steps":
+			cfg.Limits.MaxSteps, err = configInt(v)
+		case "max_files":
+			cfg.Sampling.MaxFiles, err = configInt(v)
+		case "max_chunks_per_file":
+			cfg.Sampling.MaxChunksPerFile, err = configInt(v)
+		case "sample_ratio":
+			var s string
+			if s, err = configString(v); err == nil {
+				cfg.Sampling.Ratio, err = strconv.ParseFloat(s, 64)
+			}
+		case "overlap":
+			var s string
+			if s, err = configString(v); err == nil {
+				cfg.Overlap, err = strconv.ParseFloat(s, 64)
+			}
+		case "soft_limit":
+			var s string
+			if s, err = configString(v); err == nil {
+				cfg.SoftLimit, err = strconv.ParseFloat(s, 64)
+			}
+		case "ignore":
+			patterns, ok := v.value.([]string)
+			if !ok {
+				err = fmt.Errorf("%s must be a list of strings", v.key)
+			}
+			cfg.Ignore = patterns
+		case "redact":
+			kinds, ok := v.value.([]string)
+			if !ok {
+				err = fmt.Errorf("%s must be a list of strings", v.key)
+			}
+			cfg.Redact = kinds
+		case "languages":
+			table, ok := v.value.([]configValue)
+			if !ok {
+				err = fmt.Errorf("%s must be a table", v.key)
+			}
+			for _, entry := range table {
+				var name string
+				if name, err = configString(entry); err != nil {
+					break
+				}
+				cfg.Languages = append(cfg.Languages, LanguageOverride{
+					Pattern:  entry.key,
+					Language: languages.LanguageName(name),
+				})
+			}
+		default:
+			err = fmt.Errorf("unknown key %q", v.key)
+		}
+
+		if err != nil {
+			return nil, fmt.Errorf("%s: %w", v.key, err)
+		}
+	}
+
+	return cfg, nil
+}
+
+// configString returns the value of v if it is a scalar.
+func configString(v configValue) (string, error) {
+	s, ok := v.value.(string)
+	if !ok {
+		return "", fmt.Errorf("%s must be a single value", v.key)
+	}
+	return s, nil
+}
+
+// configInt returns the value of v if it is an integer.
+func configInt(v configValue) (int, error) {
+	s, err := configString(v)
+	if err != nil {
+		return 0, err
+	}
+	return strconv.Atoi(s)
+}
+
+// configBool returns the value of v if it is a boolean.
+func configBool(v configValue) (bool, error) {
+	s, err := configString(v)
+	if err != nil {
+		return false, err
+	}
+	return strconv.ParseBool(s)
+}
+
+// parseTOMLConfig parses the TOML subset described in LoadConfig.
+func parseTOMLConfig(data string) ([]configValue, error) {
+	var values []configValue
+	table := -1 // Index of the current [table] in values, if any
+
+	lines := strings.Split(data, "\n")
+	for i := 0; i < len(lines); i++ {
+		line := strings.TrimSpace(stripConfigComment(lines[i]))
+		if line == "" {
+			continue
+		}
+
+		if strings.HasPrefix(line, "[") && strings.HasSuffix(line, "]") {
+			values = append(values, configValue{key: strings.TrimSpace(line[1 : len(line)-1]), value: []configValue{}})
+			table = len(values) - 1
+			continue
+		}
+
+		// Quoted keys such as "a=b" may hold the separator
+		eq := indexUnquoted(line, '=')
+		if eq < 0 {
+			return nil, fmt.Errorf("line %d: expected key = value", i+1)
+		}
+		key, err := parseConfigScalar(strings.TrimSpace(line[:eq]))
+		if err != nil {
+			return nil, fmt.Errorf("line %d: %w", i+1, err)
+		}
+		raw := strings.TrimSpace(line[eq+1:])
+
+		var value any
+		if strings.HasPrefix(raw, "[") {
+			// Arrays may span several lines, and hold globs such as "[abc]*"
+			for indexUnquoted(raw, ']') < 0 && i+1 < len(lines) {
+				i++
+				raw += " " + strings.TrimSpace(stripConfigComment(lines[i]))
+			}
+			value, err = parseConfigArray(raw)
+		} else {
+			value, err = parseConfigScalar(raw)
+		}
+		if err != nil {
+			return nil, fmt.Errorf("line %d: %w", i+1, err)
+		}
+
+		if table >= 0 {
+			entries := values[table].value.([]configValue)
+			values[table].value = append(entries, configValue{key: key, value: value})
+		} else {
+			values = append(values, configValue{key: key, value: value})
+		}
+	}
+
+	return values, nil
+}
+
+// parseYAMLConfig parses the YAML subset described in LoadConfig: top-level
+// "key: value" pairs, whose value may instead be a nested block of "- item"
+// list entries or "key: value" table entries.
+func parseYAMLConfig(data string) ([]configValue, error) {
+	var values []configValue
+
+	for i, rawLine := range strings.Split(data, "\n") {
+		line := strings.TrimRight(stripConfigComment(rawLine), " \t\r")
+		trimmed := strings.TrimSpace(line)
+		if trimmed == "" || trimmed == "---" {
+			continue
+		}
+
+		// Nested lines belong to the last top-level key
+		if line[0] == ' ' || line[0] == '\t' {
+			if len(values) == 0 {
+				return nil, fmt.Errorf("line %d: unexpected indentation", i+1)
+			}
+			last := &values[len(values)-1]
+
+			if item, ok := strings.CutPrefix(trimmed, "- "); ok {
+				list, _ := last.value.([]string)
+				if last.value != nil && list == nil {
+					return nil, fmt.Errorf("line %d: cannot mix list items into %s", i+1, last.key)
+				}
+				s, err := parseConfigScalar(strings.TrimSpace(item))
+				if err != nil {
+					return nil, fmt.Errorf("line %d: %w", i+1, err)
+				}
+				last.value = append(list, s)
+				continue
+			}
+
+			key, raw, ok := cutYAMLKey(trimmed)
+			if !ok {
+				return nil, fmt.Errorf("line %d: expected key: value", i+1)
+			}
+			table, _ := last.value.([]configValue)
+			if last.value != nil && table == nil {
+				return nil, fmt.Errorf("line %d: cannot mix table entries into %s", i+1, last.key)
+			}
+			value, err := parseConfigScalar(raw)
+			if err != nil {
+				return nil, fmt.Errorf("line %d: %w", i+1, err)
+			}
+			last.value = append(table, configValue{key: key, value: value})
+			continue
+		}
+
+		key, raw, ok := cutYAMLKey(trimmed)
+		if !ok {
+			return nil, fmt.Errorf("line %d: expected key: value", i+1)
+		}
+
+		var value any
+		var err error
+		switch {
+		case raw == "":
+			// The value follows as a nested block
+		case strings.HasPrefix(raw, "["):
+			value, err = parseConfigArray(raw)
+		default:
+			value, err = parseConfigScalar(raw)
+		}
+		if err != nil {
+			return nil, fmt.Errorf("line %d: %w", i+1, err)
+		}
+		values = append(values, configValue{key: key, value: value})
+	}
+
+	return values, nil
+}
+
+// cutYAMLKey splits a "key: value" line, allowing quoted keys such as "*.tpl".
+func cutYAMLKey(line string) (key, value string, ok bool) {
+	sep := ": "
+	if strings.HasPrefix(line, `"`) || strings.HasPrefix(line, "'") {
+		end := strings.IndexByte(line[1:], line[0])
+		if end < 0 {
+			return "", "", false
+		}
+		sep = line[:end+2] + ":"
+		if !strings.HasPrefix(line, sep) {
+			return "", "", false
+		}
+		var err error
+		key, err = parseConfigScalar(line[:end+2])
+		return key, strings.TrimSpace(line[len(sep):]), err == nil
+	}
+
+	if strings.HasSuffix(line, ":") {
+		return line[:len(line)-1], "", true
+	}
+	key, value, ok = strings.Cut(line, sep)
+	return strings.TrimSpace(key), strings.TrimSpace(value), ok
+}
+
+// parseJSONConfig parses a JSON object with the same keys as the other
+// formats. Objects are decoded token by token to keep the order of language
+// overrides.
+func parseJSONConfig(data []byte) ([]configValue, error) {
+	dec := json.NewDecoder(bytes.NewReader(data))
+	if tok, err := dec.Token(); err != nil || tok != json.Delim('{') {
+		return nil, fmt.Errorf("expected a JSON object")
+	}
+
+	var values []configValue
+	for dec.More() {
+		tok, err := dec.Token()
+		if err != nil {
+			return nil, err
+		}
+		key, _ := tok.(string)
+
+		var raw json.RawMessage
+		if err := dec.Decode(&raw); err != nil {
+			return nil, err
+		}
+
+		var value any
+		switch raw[0] {
+		case '{':
+			value, err = parseJSONConfig(raw)
+		case '[':
+			var items []any
+			err = json.Unmarshal(raw, &items)
+			list := make([]string, 0, len(items))
+			for _, item := range items {
+				list = append(list, fmt.Sprint(item))
+			}
+			value = list
+		case '"':
+			var str string
+			err = json.Unmarshal(raw, &str)
+			value = str
+		default:
+			value = string(raw)
+		}
+		if err != nil {
+			return nil, fmt.Errorf("%s: %w", key, err)
+		}
+		values = append(values, configValue{key: key, value: value})
+	}
+	return values, nil
+}
+
+// parseConfigScalar parses a quoted or bare scalar value.
+func parseConfigScalar(raw string) (string, error) {
+	switch {
+	case strings.HasPrefix(raw, `"`):
+		return strconv.Unquote(raw)
+	case strings.HasPrefix(raw, "'"):
+		if len(raw) < 2 || !strings.HasSuffix(raw, "'") {
+			return "", fmt.Errorf("unterminated string %s", raw)
+		}
+		return raw[1 : len(raw)-1], nil
+	default:
+		return raw, nil
+	}
+}
+
+// parseConfigArray parses a single-line array of scalars such as ["a", "b"].
+func parseConfigArray(raw string) ([]string, error) {
+	if !strings.HasPrefix(raw, "[") || !strings.HasSuffix(raw, "]") {
+		return nil, fmt.Errorf("malformed array %s", raw)
+	}
+
+	var items []string
+	for _, item := range splitConfigList(raw[1 : len(raw)-1]) {
+		item = strings.TrimSpace(item)
+		if item == "" {
+			continue
+		}
+		s, err := parseConfigScalar(item)
+		if err != nil {
+			return nil, err
+		}
+		items = append(items, s)
+	}
+	return items, nil
+}
+
+// splitConfigList splits s on commas outside of quotes.
+func splitConfigList(s string) []string {
+	var items []string
+	var quote byte
+	start := 0
+	for i := 0; i < len(s); i++ {
+		switch c := s[i]; {
+		case quote != 0:
+			if c == '\\' && quote == '"' {
+				i++
+			} else if c == quote {
+				quote = 0
+			}
+		case c == '"' || c == '\'':
+			quote = c
+		case c == ',':
+			items = append(items, s[start:i])
+			start = i + 1
+		}
+	}
+	return append(items, s[start:])
+}
+
+// stripConfigComment removes a trailing # comment outside of quotes.
+func stripConfigComment(line string) string {
+	if i := indexUnquoted(line, '#'); i >= 0 {
+		return line[:i]
+	}
+	return line
+}
+
+// indexUnquoted returns the index of the first c in s outside of quotes, or
+// -1 if there is none.
+func indexUnquoted(s string, c byte) int {
+	var quote byte
+	for i := 0; i < len(s); i++ {
+		switch b := s[i]; {
+		case quote != 0:
+			if b == '\\' && quote == '"' {
+				i++
+			} else if b == quote {
+				quote = 0
+			}
+		case b == '"' || b == '\'':
+			quote = b
+		case b == c:
+			return i
+		}
+	}
+	return -1
+}
@@ -0,0 +1,256 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"os"
+	"path/filepath"
+	"reflect"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestLoadConfig(t *testing.T) {
+	want := &Config{
+		Strategy: StrategyAST,
+		MaxSize:  500,
+		Overlap:  10,
+		Counter:  CounterBytes,
+		Languages: []LanguageOverride{
+			{Pattern: "*.tpl", Language: languages.HTML},
+			{Pattern: "scripts/*", Language: languages.Bash},
+		},
+		Ignore:           []string{"vendor/**", "*.min.js"},
+		StableBoundaries: true,
+	}
+
+	files := map[string]string{
+		"chunkx.toml": `# Shared chunking settings
+strategy = "ast"
+max_size = 500
+overlap = 10.0
+counter = "bytes" # bytes are stable across tokenizers
+stable_boundaries = true
+ignore = [
+  "vendor/**",
+  "*.min.js",
+]
+
+[languages]
+"*.tpl" = "html"
+"scripts/*" = "bash"
+`,
+		"chunkx.yaml": `# Shared chunking settings
+strategy: ast
+max_size: 500
+overlap: 10
+counter: "bytes"
+stable_boundaries: true
+ignore:
+  - vendor/**
+  - "*.min.js"
+languages:
+  "*.tpl": html
+  "scripts/*": bash
+`,
+		"chunkx.json": `{
+  "strategy": "ast",
+  "max_size": 500,
+  "overlap": 10,
+  "counter": "bytes",
+  "stable_boundaries": true,
+  "ignore": ["vendor/**", "*.min.js"],
+  "languages": {"*.tpl": "html", "scripts/*": "bash"}
+}`,
+	}
+	dir := writeTestFiles(t, files)
+
+	for name := range files {
+		t.Run(name, func(t *testing.T) {
+			cfg, err := LoadConfig(filepath.Join(dir, name))
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if !reflect.DeepEqual(cfg, want) {
+				t.Errorf("got %+v, want %+v", cfg, want)
+			}
+		})
+	}
+}
+
+func TestLoadConfig_Errors(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"unknown.toml":  `max_tokens = 3`,
+		"number.yaml":   `max_size: lots`,
+		"list.toml":     `ignore = "vendor"`,
+		"format.ini":    `max_size = 3`,
+		"malformed.yml": "ignore:\n  - a\n  b: c\n",
+	})
+
+	for _, name := range []string{"unknown.toml", "number.yaml", "list.toml", "format.ini", "malformed.yml"} {
+		t.Run(name, func(t *testing.T) {
+			_, err := LoadConfig(filepath.Join(dir, name))
+			if !errors.Is(err, ErrInvalidConfig) {
+				t.Errorf("expected ErrInvalidConfig, got %v", err)
+			}
+		})
+	}
+
+	if _, err := LoadConfig(filepath.Join(dir, "missing.toml")); !errors.Is(err, os.ErrNotExist) {
+		t.Errorf("expected os.ErrNotExist, got %v", err)
+	}
+}
+
+func TestConfig_Options(t *testing.T) {
+	tests := []struct {
+		name    string
+		cfg     Config
+		wantErr bool
+	}{
+		{"defaults", Config{}, false},
+		{"lines strategy", Config{Strategy: StrategyLines}, false},
+		{"unknown strategy", Config{Strategy: "semantic"}, true},
+		{"unknown counter", Config{Counter: "tiktoken"}, true},
+		{"unknown language", Config{Language: "klingon"}, true},
+		{"unknown override", Config{Languages: []LanguageOverride{{Pattern: "*.x", Language: "klingon"}}}, true},
+		{"strict transcoding", Config{Transcode: "strict"}, false},
+		{"unknown transcode mode", Config{Transcode: "utf-7"}, true},
+		{"lf newlines", Config{Newlines: "lf"}, false},
+		{"unknown newlines mode", Config{Newlines: "crlf"}, true},
+		{"binary strings", Config{Binary: "strings"}, false},
+		{"unknown binary policy", Config{Binary: "base64"}, true},
+		{"sampled generated files", Config{Generated: "sample"}, false},
+		{"unknown generated policy", Config{Generated: "drop"}, true},
+		{"stripped license headers", Config{License: "strip"}, false},
+		{"unknown license policy", Config{License: "remove"}, true},
+		{"separate comments", Config{Comments: "separate"}, false},
+		{"unknown comments mode", Config{Comments: "docs"}, true},
+		{"document routing", Config{Documents: "auto"}, false},
+		{"unknown documents mode", Config{Documents: "pdf"}, true},
+		{"front matter keys", Config{FrontMatterKeys: []string{"title", "tags"}}, false},
+		{"unknown front matter mode", Config{FrontMatter: "strip"}, true},
+		{"split fences", Config{Fences: "split"}, false},
+		{"unknown fences mode", Config{Fences: "python"}, true},
+		{"table rows", Config{Tables: "rows"}, false},
+		{"unknown tables mode", Config{Tables: "columns"}, true},
+		{"record links", Config{Links: "record"}, false},
+		{"unknown links mode", Config{Links: "follow"}, true},
+		{"output placeholders", Config{Outputs: "placeholders"}, false},
+		{"unknown outputs policy", Config{Outputs: "html"}, true},
+		{"chat transcripts", Config{Transcripts: "chat"}, false},
+		{"unknown transcripts mode", Config{Transcripts: "email"}, true},
+		{"speaker turns", Config{Speakers: "turns"}, false},
+		{"unknown speakers mode", Config{Speakers: "words"}, true},
+		{"openapi operations", Config{OpenAPI: "operations"}, false},
+		{"unknown openapi mode", Config{OpenAPI: "paths"}, true},
+		{"json subtrees", Config{JSON: "subtrees"}, false},
+		{"unknown json mode", Config{JSON: "flat"}, true},
+		{"stable boundaries", Config{StableBoundaries: true}, false},
+		{"context template", Config{Context: "{file} §{heading_path}"}, false},
+		{"limits", Config{Limits: Limits{MaxBytes: 1 << 20, MaxDepth: 500}}, false},
+		{"negative limit", Config{Limits: Limits{MaxChunks: -1}}, true},
+		{"soft limit", Config{SoftLimit: 10}, false},
+		{"sampling", Config{Sampling: Sampling{Ratio: 0.1, MaxFiles: 100, MaxChunksPerFile: 5}}, false},
+		{"sample ratio over one", Config{Sampling: Sampling{Ratio: 2}}, true},
+		{"redaction", Config{Redact: []string{RedactEmail, RedactAPIKey}}, false},
+		{"all redaction", Config{Redact: []string{"all"}}, false},
+		{"unknown redaction kind", Config{Redact: []string{"phone"}}, true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, err := tt.cfg.Options()
+			if tt.wantErr && !errors.Is(err, ErrInvalidConfig) {
+				t.Errorf("expected ErrInvalidConfig, got %v", err)
+			}
+			if !tt.wantErr && err != nil {
+				t.Errorf("unexpected error: %v", err)
+			}
+		})
+	}
+}
+
+func TestWithLanguageOverrides(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"page.tpl": "<div>\n  <p>hello</p>\n</div>\n",
+	})
+	path := filepath.Join(dir, "page.tpl")
+
+	chunker := NewChunker()
+	chunks, err := chunker.ChunkFile(path, WithLanguageOverrides(LanguageOverride{Pattern: "*.tpl", Language: languages.HTML}))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	for _, chunk := range chunks {
+		if chunk.Language != languages.HTML {
+			t.Errorf("expected language %s, got %s", languages.HTML, chunk.Language)
+		}
+	}
+
+	// An explicit language still wins over overrides
+	chunks, err = chunker.ChunkFile(path,
+		WithLanguage(languages.Generic),
+		WithLanguageOverrides(LanguageOverride{Pattern: "*.tpl", Language: languages.HTML}))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	for _, chunk := range chunks {
+		if chunk.Language != languages.Generic {
+			t.Errorf("expected language %s, got %s", languages.Generic, chunk.Language)
+		}
+	}
+}
+
+func TestChunkPipeline_RunDirIgnore(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"main.go":             "package main\n",
+		"vendor/lib/lib.go":   "package lib\n",
+		"web/app.min.js":      "var a=1;\n",
+		"web/app.js":          "var a = 1;\n",
+		"docs/guide/intro.md": "# Intro\n",
+	})
+
+	pipeline := NewChunkPipeline(2, WithIgnore("vendor/**", "*.min.js", "docs"))
+
+	got := make(map[string]bool)
+	for result := range pipeline.RunDir(context.Background(), dir) {
+		rel, _ := filepath.Rel(dir, result.Path)
+		got[filepath.ToSlash(rel)] = true
+	}
+
+	want := map[string]bool{"main.go": true, "web/app.js": true}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("got files %v, want %v", got, want)
+	}
+}
+
+func TestParseConfig(t *testing.T) {
+	cfg, err := ParseConfig([]byte(`{"max_size": 200, "languages": {"*.tpl": "html"}}`), "json")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if cfg.MaxSize != 200 || len(cfg.Languages) != 1 || cfg.Languages[0].Language != languages.HTML {
+		t.Errorf("unexpected config %+v", cfg)
+	}
+
+	// Quotes hide the separators and brackets they hold
+	cfg, err = ParseConfig([]byte(`ignore = [
+  "[abc]*",
+  "b#c",
+]
+
+[languages]
+"a=b.txt" = "markdown"
+`), "toml")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if !reflect.DeepEqual(cfg.Ignore, []string{"[abc]*", "b#c"}) || len(cfg.Languages) != 1 || cfg.Languages[0] != (LanguageOverride{Pattern: "a=b.txt", Language: languages.Markdown}) {
+		t.Errorf("unexpected config %+v", cfg)
+	}
+
+	if _, err := ParseConfig([]byte(`max_size = 3`), "ini"); !errors.Is(err, ErrInvalidConfig) {
+		t.Errorf("expected ErrInvalidConfig, got %v", err)
+	}
+}
@@ -0,0 +1,127 @@
+package chunkx
+
+import (
+	"strconv"
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Metadata keys set on chunks made with WithContextTemplate.
+const (
+	MetadataContext     = "context"      // The rendered context line
+	MetadataHeadingPath = "heading_path" // Markdown headings the chunk starts under, joined with " > "
+	MetadataSymbolPath  = "symbol_path"  // Named definitions enclosing the chunk, joined with "."
+)
+
+// WithContextTemplate renders a line of context for every chunk from
+// template and records it under MetadataContext, so it can be put in front of
+// the chunk's content for embedding or in a prompt. Placeholders in braces
+// are replaced:
+//
+//   - {file}: the path of the file, empty when chunking content alone
+//   - {language}: the language of the chunk
+//   - {lines}, {start_line}, {end_line}: the lines of the chunk, such as "3-9"
+//   - {heading_path}: the Markdown headings the chunk starts under
+//   - {symbol_path}: the named definitions, such as classes and functions,
+//     enclosing the chunk, found in its syntax tree when built with cgo
+//   - any other name: the chunk's metadata under that key, such as
+//     {front_matter.title} or {anchor}, or nothing if unset
+//
+// "{{" and "}}" stand for literal braces. Templates such as
+// "{file} §{heading_path}" or "{symbol_path} in {file}" record the heading
+// and symbol paths they use under MetadataHeadingPath and MetadataSymbolPath.
+func WithContextTemplate(template string) Option {
+	return func(c *config) {
+		c.contextTemplate = template
+		c.headingPaths = strings.Contains(template, "{heading_path}")
+		c.symbolPaths = strings.Contains(template, "{symbol_path}")
+	}
+}
+
+// renderContext renders the context template of cfg for chunks of the file
+// at path.
+func renderContext(chunks []Chunk, path string, cfg *config) {
+	if cfg.contextTemplate == "" {
+		return
+	}
+	for i := range chunks {
+		chunk := &chunks[i]
+		context := expandTemplate(cfg.contextTemplate, func(name string) string {
+			switch name {
+			case "file":
+				return path
+			case "language":
+				return string(chunk.Language)
+			case "lines":
+				return strconv.Itoa(chunk.StartLine) + "-" + strconv.Itoa(chunk.EndLine)
+			case "start_line":
+				return strconv.Itoa(chunk.StartLine)
+			case "end_line":
+				return strconv.Itoa(chunk.EndLine)
+			}
+			return chunk.Metadata[name]
+		})
+		if chunk.Metadata == nil {
+			chunk.Metadata = make(map[string]string, 1)
+		}
+		chunk.Metadata[MetadataContext] = context
+	}
+}
+
+// expandTemplate replaces the placeholders of template with their values.
+// An unclosed brace is kept as is.
+func expandTemplate(template string, value func(name string) string) string {
+	var b strings.Builder
+	for i := 0; i < len(template); i++ {
+		c := template[i]
+		switch {
+		case (c == '{' || c == '}') && i+1 < len(template) && template[i+1] == c:
+			b.WriteByte(c)
+			i++
+		case c == '{':
+			end := strings.IndexByte(template[i+1:], '}')
+			if end < 0 {
+				b.WriteString(template[i:])
+				return b.String()
+			}
+			b.WriteString(value(template[i+1 : i+1+end]))
+			i += end + 1
+		default:
+			b.WriteByte(c)
+		}
+	}
+	return b.String()
+}
+
+// addHeadingPaths records under MetadataHeadingPath the Markdown headings of
+// code that each chunk starts under, from the offset from.
+func addHeadingPaths(chunks []Chunk, code string, from int) {
+	anchors, _ := scanLinks(code, from, languages.Markdown)
+	var path []linkRef // Headings enclosing the current offset, outermost first
+	next := 0
+	for i := range chunks {
+		for ; next < len(anchors) && anchors[next].offset <= chunks[i].StartByte; next++ {
+			heading := anchors[next]
+			if heading.level == 0 {
+				continue
+			}
+			for len(path) > 0 && path[len(path)-1].level >= heading.level {
+				path = path[:len(path)-1]
+			}
+			path = append(path, heading)
+		}
+		if len(path) == 0 {
+			continue
+		}
+
+		titles := make([]string, len(path))
+		for j, heading := range path {
+			titles[j] = heading.title
+		}
+		if chunks[i].Metadata == nil {
+			chunks[i].Metadata = make(map[string]string, 2)
+		}
+		chunks[i].Metadata[MetadataHeadingPath] = strings.Join(titles, " > ")
+	}
+}
@@ -0,0 +1,64 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestExpandTemplate(t *testing.T) {
+	values := map[string]string{"file": "docs/guide.md", "heading_path": "Guide > Install"}
+	tests := []struct {
+		template string
+		want     string
+	}{
+		{"{file} §{heading_path}", "docs/guide.md §Guide > Install"},
+		{"{missing}in {file}", "in docs/guide.md"},
+		{"{{file}} {file}", "{file} docs/guide.md"},
+		{"open {file", "open {file"},
+		{"", ""},
+	}
+
+	for _, tt := range tests {
+		got := expandTemplate(tt.template, func(name string) string { return values[name] })
+		if got != tt.want {
+			t.Errorf("expandTemplate(%q) = %q, want %q", tt.template, got, tt.want)
+		}
+	}
+}
+
+func TestAddHeadingPaths(t *testing.T) {
+	code := "# Guide\n\nIntro.\n\n## Install\n\nRun it.\n\n### Linux\n\nApt.\n\nOther\n=====\n\nDone.\n"
+	var chunks []Chunk
+	for _, text := range []string{"Intro.", "Run it.", "Apt.", "Done."} {
+		start := strings.Index(code, text)
+		chunks = append(chunks, Chunk{StartByte: start, EndByte: start + len(text)})
+	}
+	chunks = append([]Chunk{{StartByte: 0, EndByte: 1}}, chunks...)
+
+	addHeadingPaths(chunks, code, 0)
+	want := []string{"Guide", "Guide", "Guide > Install", "Guide > Install > Linux", "Other"}
+	for i, chunk := range chunks {
+		if got := chunk.Metadata[MetadataHeadingPath]; got != want[i] {
+			t.Errorf("chunk %d: got heading path %q, want %q", i, got, want[i])
+		}
+	}
+}
+
+func TestWithContextTemplate(t *testing.T) {
+	code := "# Guide\n\nIntro.\n"
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Markdown), WithContextTemplate("{language}:{start_line} {heading_path}"))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) != 1 {
+		t.Fatalf("expected 1 chunk, got %d", len(chunks))
+	}
+	if got, want := chunks[0].Metadata[MetadataContext], "markdown:1 Guide"; got != want {
+		t.Errorf("got context %q, want %q", got, want)
+	}
+	if chunks[0].Content != code {
+		t.Errorf("expected the content left as is, got %q", chunks[0].Content)
+	}
+}
@@ -0,0 +1,128 @@
+package chunkx
+
+import (
+	"fmt"
+	"iter"
+	"maps"
+	"path"
+	"path/filepath"
+	"slices"
+)
+
+// DefaultPrices are the prices of common embedding models in dollars per 1K
+// tokens, as published by their providers. Prices change, so pass your own
+// to EstimateCost for forecasts that matter.
+var DefaultPrices = map[string]float64{
+	"text-embedding-3-small": 0.00002,
+	"text-embedding-3-large": 0.00013,
+	"text-embedding-ada-002": 0.0001,
+}
+
+// PricingModel is the table of prices EstimateCost forecasts with, and how
+// it counts the tokens every model is billed for.
+type PricingModel struct {
+	Prices   map[string]float64      // Dollars per 1K tokens by model name; defaults to DefaultPrices
+	Counter  TokenCounter            // Counts the tokens of chunks; defaults to SimpleTokenCounter
+	Counters map[string]TokenCounter // Counters of the models whose tokenizer differs from Counter
+}
+
+// ModelCost is the tokens and price of embedding chunks with a model.
+type ModelCost struct {
+	Model  string
+	Tokens int
+	Cost   float64 // Dollars
+}
+
+// DirectoryCost is the cost of embedding the chunks of the files directly in
+// a directory.
+type DirectoryCost struct {
+	Dir    string      // Slash-separated directory of the files, "." for the top
+	Chunks int         // Chunks of the files in the directory
+	Models []ModelCost // By model name
+}
+
+// CostEstimate is the forecast cost of embedding chunks, in total and by
+// directory.
+type CostEstimate struct {
+	Chunks      int
+	Models      []ModelCost     // By model name
+	Directories []DirectoryCost // By directory
+}
+
+// EstimateCost forecasts what embedding chunks costs with every model of
+// pricing, so the spend of a corpus can be known before embedding it. The
+// chunks are paired with the paths of their files, as ChunkSet.All yields
+// them, and the paths group the cost by directory.
+//
+// Estimating the chunks of a sample of the corpus, see WithSampling, and
+// scaling the cost by the share of files sampled forecasts a huge corpus
+// without chunking all of it.
+func EstimateCost(chunks iter.Seq2[string, Chunk], pricing PricingModel) (*CostEstimate, error) {
+	prices := pricing.Prices
+	if prices == nil {
+		prices = DefaultPrices
+	}
+	counter := pricing.Counter
+	if counter == nil {
+		counter = &SimpleTokenCounter{}
+	}
+	models := slices.Sorted(maps.Keys(prices))
+
+	estimate := &CostEstimate{Models: newModelCosts(models)}
+	dirs := make(map[string]*DirectoryCost)
+	for file, chunk := range chunks {
+		dir := path.Dir(filepath.ToSlash(file))
+		d := dirs[dir]
+		if d == nil {
+			d = &DirectoryCost{Dir: dir, Models: newModelCosts(models)}
+			dirs[dir] = d
+		}
+		estimate.Chunks++
+		d.Chunks++
+
+		// Models sharing the counter share its count
+		tokens := -1
+		for i, model := range models {
+			var n int
+			var err error
+			if own := pricing.Counters[model]; own != nil {
+				n, err = own.CountTokens(chunk.Content)
+			} else {
+				if tokens < 0 {
+					tokens, err = counter.CountTokens(chunk.Content)
+				}
+				n = tokens
+			}
+			if err != nil {
+				return nil, fmt.Errorf("counting tokens of %s for %s: %w", file, model, err)
+			}
+			estimate.Models[i].Tokens += n
+			d.Models[i].Tokens += n
+		}
+	}
+
+	for _, dir := range slices.Sorted(maps.Keys(dirs)) {
+		estimate.Directories = append(estimate.Directories, *dirs[dir])
+	}
+	price(estimate.Models, prices)
+	for i := range estimate.Directories {
+		price(estimate.Directories[i].Models, prices)
+	}
+	return estimate, nil
+}
+
+// newModelCosts returns an empty cost for every model.
+func newModelCosts(models []string) []ModelCost {
+	costs := make([]ModelCost, len(models))
+	for i, model := range models {
+		costs[i].Model = model
+	}
+	return costs
+}
+
+// price sets the cost of the tokens of every model.
+func price(costs []ModelCost, prices map[string]float64) {
+	for i := range costs {
+		costs[i].Cost = float64(costs[i].Tokens) / 1000 * prices[costs[i].Model]
+	}
+}
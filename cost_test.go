@@ -0,0 +1,77 @@
+package chunkx
+
+import (
+	"math"
+	"reflect"
+	"testing"
+	"unicode/utf8"
+)
+
+// runeCounter counts a token per rune, as an example of a model's tokenizer.
+type runeCounter struct{}
+
+func (runeCounter) CountTokens(text string) (int, error) {
+	return utf8.RuneCountInString(text), nil
+}
+
+func TestEstimateCost(t *testing.T) {
+	var set ChunkSet
+	set.Add("main.go", Chunk{Content: "package main"}, Chunk{Content: "func main() {}"})
+	set.Add("internal/db/db.go", Chunk{Content: "package db"})
+	set.Add("internal/db/query.go", Chunk{Content: "func Query() error"})
+
+	estimate, err := EstimateCost(set.All(), PricingModel{
+		Prices:   map[string]float64{"words": 0.5, "runes": 0.01},
+		Counters: map[string]TokenCounter{"runes": runeCounter{}},
+	})
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	want := &CostEstimate{
+		Chunks: 4,
+		Models: []ModelCost{{Model: "runes", Tokens: 54, Cost: 0.00054}, {Model: "words", Tokens: 10, Cost: 0.005}},
+		Directories: []DirectoryCost{
+			{Dir: ".", Chunks: 2, Models: []ModelCost{{Model: "runes", Tokens: 26, Cost: 0.00026}, {Model: "words", Tokens: 5, Cost: 0.0025}}},
+			{Dir: "internal/db", Chunks: 2, Models: []ModelCost{{Model: "runes", Tokens: 28, Cost: 0.00028}, {Model: "words", Tokens: 5, Cost: 0.0025}}},
+		},
+	}
+	// Costs are compared apart, as floating point
+	costs := func(e *CostEstimate) (costs []float64) {
+		for _, models := range append([][]ModelCost{e.Models}, e.Directories[0].Models, e.Directories[1].Models) {
+			for i := range models {
+				costs = append(costs, models[i].Cost)
+				models[i].Cost = 0
+			}
+		}
+		return costs
+	}
+	if len(estimate.Directories) != 2 {
+		t.Fatalf("expected two directories, got %+v", estimate.Directories)
+	}
+	got, wantCosts := costs(estimate), costs(want)
+	if !reflect.DeepEqual(estimate, want) {
+		t.Errorf("EstimateCost() = %+v, want %+v", estimate, want)
+	}
+	for i := range got {
+		if math.Abs(got[i]-wantCosts[i]) > 1e-12 {
+			t.Errorf("cost %d = %g, want %g", i, got[i], wantCosts[i])
+		}
+	}
+
+	if _, err := EstimateCost(set.All(), PricingModel{Counter: refusingCounter{}}); err == nil {
+		t.Error("expected the error of the token counter")
+	}
+}
+
+func TestEstimateCost_DefaultPrices(t *testing.T) {
+	var set ChunkSet
+	set.Add("a.txt", Chunk{Content: "one two three"})
+	estimate, err := EstimateCost(set.All(), PricingModel{})
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(estimate.Models) != len(DefaultPrices) || estimate.Models[0].Tokens != 3 {
+		t.Errorf("expected three tokens for every default model, got %+v", estimate.Models)
+	}
+}
@@ -0,0 +1,188 @@
+package chunkx
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+	"path/filepath"
+)
+
+// datasetShardBytes is the amount of chunk text at which a DatasetWriter
+// starts a new shard, keeping shards well below the size the Hugging Face Hub
+// recommends for Parquet files.
+const datasetShardBytes = 256 << 20
+
+// datasetSplit is the split every chunk is written to.
+const datasetSplit = "train"
+
+// DatasetWriter writes chunks as a Hugging Face dataset directory, so chunk
+// corpora can be pushed to the Hub, versioned, and loaded with
+// datasets.load_dataset:
+//
+//	dataset_infos.json
+//	data/train-00000-of-00002.parquet
+//	data/train-00001-of-00002.parquet
+//
+// Shards are written with ParquetWriter and hold about 256 MiB of chunk text
+// each, in a single train split. dataset_infos.json describes the features
+// and the number of chunks in the split.
+type DatasetWriter struct {
+	dir        string
+	shardBytes int
+	file       *os.File
+	pw         *ParquetWriter
+	shards     []string // Paths of the shards written so far
+	written    int      // Chunk text in the current shard
+	rows       int64
+	size       int64 // Bytes in closed shards
+	err        error
+}
+
+// NewDatasetWriter creates a writer that writes a dataset into dir, creating
+// it if needed. Close must be called to finish the last shard and write the
+// dataset metadata.
+func NewDatasetWriter(dir string) (*DatasetWriter, error) {
+	if err := os.MkdirAll(filepath.Join(dir, "data"), 0o755); err != nil {
+		return nil, err
+	}
+	return &DatasetWriter{dir: dir, shardBytes: datasetShardBytes}, nil
+}
+
+// Write adds a row for each chunk of the file at path.
+func (dw *DatasetWriter) Write(path string, chunks ...Chunk) error {
+	if dw.err != nil {
+		return dw.err
+	}
+
+	for _, chunk := range chunks {
+		if dw.pw == nil {
+			if dw.err = dw.openShard(); dw.err != nil {
+				return dw.err
+			}
+		}
+		if dw.err = dw.pw.Write(path, chunk); dw.err != nil {
+			return dw.err
+		}
+		dw.rows++
+
+		dw.written += len(chunk.Content)
+		if dw.written >= dw.shardBytes {
+			if dw.err = dw.closeShard(); dw.err != nil {
+				return dw.err
+			}
+		}
+	}
+	return nil
+}
+
+// Close finishes the last shard, names the shards after their final count,
+// and writes dataset_infos.json. A dataset without chunks gets a single
+// empty shard, so it can still be loaded.
+func (dw *DatasetWriter) Close() error {
+	if dw.err != nil {
+		return dw.err
+	}
+	if dw.pw == nil && len(dw.shards) == 0 {
+		if dw.err = dw.openShard(); dw.err != nil {
+			return dw.err
+		}
+	}
+	if dw.pw != nil {
+		if dw.err = dw.closeShard(); dw.err != nil {
+			return dw.err
+		}
+	}
+
+	for i, shard := range dw.shards {
+		name := fmt.Sprintf("%s-%05d-of-%05d.parquet", datasetSplit, i, len(dw.shards))
+		if dw.err = os.Rename(shard, filepath.Join(dw.dir, "data", name)); dw.err != nil {
+			return dw.err
+		}
+	}
+
+	if dw.err = dw.writeInfos(); dw.err != nil {
+		return dw.err
+	}
+
+	// Reject writes after the metadata
+	dw.err = errWriterClosed
+	return nil
+}
+
+// openShard starts a new shard under a temporary name.
+func (dw *DatasetWriter) openShard() error {
+	path := filepath.Join(dw.dir, "data", fmt.Sprintf(".%s-%05d.parquet.tmp", datasetSplit, len(dw.shards)))
+	file, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+	dw.file = file
+	dw.pw = NewParquetWriter(file)
+	dw.shards = append(dw.shards, path)
+	dw.written = 0
+	return nil
+}
+
+// closeShard writes the footer of the current shard and closes its file.
+func (dw *DatasetWriter) closeShard() error {
+	err := dw.pw.Close()
+	if info, statErr := dw.file.Stat(); err == nil && statErr == nil {
+		dw.size += info.Size()
+	}
+	if closeErr := dw.file.Close(); err == nil {
+		err = closeErr
+	}
+	dw.file, dw.pw = nil, nil
+	return err
+}
+
+// datasetFeature is a feature in dataset_infos.json.
+type datasetFeature map[string]any
+
+// datasetValue returns the feature of a scalar column.
+func datasetValue(dtype string) datasetFeature {
+	return datasetFeature{"dtype": dtype, "_type": "Value"}
+}
+
+// writeInfos writes dataset_infos.json for the default config.
+func (dw *DatasetWriter) writeInfos() error {
+	features := map[string]datasetFeature{
+		"id":         datasetValue("string"),
+		"path":       datasetValue("string"),
+		"language":   datasetValue("string"),
+		"text":       datasetValue("string"),
+		"start_line": datasetValue("int64"),
+		"end_line":   datasetValue("int64"),
+		"start_byte": datasetValue("int64"),
+		"end_byte":   datasetValue("int64"),
+		"metadata": {
+			"feature": map[string]datasetFeature{
+				"key":   datasetValue("string"),
+				"value": datasetValue("string"),
+			},
+			"_type": "Sequence",
+		},
+	}
+
+	infos := map[string]any{
+		"default": map[string]any{
+			"config_name": "default",
+			"features":    features,
+			"splits": map[string]any{
+				datasetSplit: map[string]any{
+					"name":         datasetSplit,
+					"num_bytes":    dw.size,
+					"num_examples": dw.rows,
+				},
+			},
+			"download_size": dw.size,
+			"dataset_size":  dw.size,
+		},
+	}
+
+	data, err := json.MarshalIndent(infos, "", "  ")
+	if err != nil {
+		return err
+	}
+	return os.WriteFile(filepath.Join(dw.dir, "dataset_infos.json"), append(data, '\n'), 0o644)
+}
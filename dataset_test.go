@@ -0,0 +1,102 @@
+package chunkx
+
+import (
+	"bytes"
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+// readDatasetInfos decodes the split info of the dataset in dir.
+func readDatasetInfos(t *testing.T, dir string) (features map[string]any, split map[string]any) {
+	t.Helper()
+
+	data, err := os.ReadFile(filepath.Join(dir, "dataset_infos.json"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	var infos map[string]struct {
+		Features map[string]any            `json:"features"`
+		Splits   map[string]map[string]any `json:"splits"`
+	}
+	if err := json.Unmarshal(data, &infos); err != nil {
+		t.Fatalf("invalid dataset_infos.json: %v", err)
+	}
+	return infos["default"].Features, infos["default"].Splits["train"]
+}
+
+func TestDatasetWriter(t *testing.T) {
+	dir := filepath.Join(t.TempDir(), "dataset")
+	dw, err := NewDatasetWriter(dir)
+	if err != nil {
+		t.Fatal(err)
+	}
+	dw.shardBytes = 10
+
+	// Each file fills a shard
+	if err := dw.Write("a.go", Chunk{Content: "package a\n"}, Chunk{Content: "func A() {}\n"}); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := dw.Write("b.go", Chunk{Content: "b"}); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := dw.Close(); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	entries, err := os.ReadDir(filepath.Join(dir, "data"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	var names []string
+	for _, entry := range entries {
+		names = append(names, entry.Name())
+	}
+	want := []string{"train-00000-of-00003.parquet", "train-00001-of-00003.parquet", "train-00002-of-00003.parquet"}
+	if len(names) != len(want) {
+		t.Fatalf("shards = %v, want %v", names, want)
+	}
+	for i, name := range want {
+		if names[i] != name {
+			t.Errorf("shard %d = %s, want %s", i, names[i], name)
+		}
+		data, err := os.ReadFile(filepath.Join(dir, "data", name))
+		if err != nil {
+			t.Fatal(err)
+		}
+		if !bytes.HasPrefix(data, []byte("PAR1")) || !bytes.HasSuffix(data, []byte("PAR1")) {
+			t.Errorf("%s is not a Parquet file", name)
+		}
+	}
+
+	features, split := readDatasetInfos(t, dir)
+	if split["num_examples"] != float64(3) {
+		t.Errorf("num_examples = %v, want 3", split["num_examples"])
+	}
+	if _, ok := features["text"]; !ok {
+		t.Errorf("features are missing the text column: %v", features)
+	}
+
+	if err := dw.Write("c.go", Chunk{Content: "c"}); err == nil {
+		t.Error("expected an error writing to a closed writer")
+	}
+}
+
+func TestDatasetWriter_Empty(t *testing.T) {
+	dir := t.TempDir()
+	dw, err := NewDatasetWriter(dir)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := dw.Close(); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if _, err := os.Stat(filepath.Join(dir, "data", "train-00000-of-00001.parquet")); err != nil {
+		t.Errorf("expected an empty shard: %v", err)
+	}
+	if _, split := readDatasetInfos(t, dir); split["num_examples"] != float64(0) {
+		t.Errorf("num_examples = %v, want 0", split["num_examples"])
+	}
+}
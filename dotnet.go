@@ -0,0 +1,223 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+var (
+	// fsharpDeclaration matches a line of F# starting a declaration, such as
+	// "let rec eval expr =", "type Shape =", or "member this.Area =", after
+	// its indentation and attributes, and the keyword and the rest of the
+	// line.
+	fsharpDeclaration = regexp.MustCompile(`^([ \t]*)(?:\[<.*>\][ \t]*)?(let|type|module|and|exception|member|override|abstract|default|static|new|val)\b[ \t]*(.*)$`)
+	// fsharpName matches the name an F# declaration binds, such as "eval" or
+	// an operator or active pattern in parentheses.
+	fsharpName = regexp.MustCompile(`^(?:[A-Za-z_][\w']*|\([^)]+\))`)
+	// vbDeclaration matches the line of VB.NET opening a declaration, such
+	// as "Public Sub Main()" or "Partial Class Form1", and its modifiers,
+	// kind, and name.
+	vbDeclaration = regexp.MustCompile(`(?i)^[ \t]*(?:<[^>\n]*>[ \t]*)?((?:(?:public|private|friend|protected|shared|overrides|overridable|mustoverride|notoverridable|overloads|shadows|static|async|iterator|partial|mustinherit|notinheritable|readonly|writeonly|default|widening|narrowing)[ \t]+)*)(sub|function|class|module|structure|interface|enum|property|namespace|operator)[ \t]+([^\s(]+)`)
+	// vbEnd matches a VB.NET line ending a block, such as "End Sub", and the
+	// kind of the block.
+	vbEnd = regexp.MustCompile(`(?i)^[ \t]*end[ \t]+(\w+)`)
+	// vbAccessor matches the line of VB.NET starting the Get or Set accessor
+	// of a property.
+	vbAccessor = regexp.MustCompile(`(?i)^[ \t]*(?:(?:public|private|friend|protected)[ \t]+)*(?:get|set)\b`)
+)
+
+// fsharpModifiers are the words that may come between the keyword of an F#
+// declaration and the name it binds.
+var fsharpModifiers = map[string]bool{
+	"rec": true, "inline": true, "private": true, "internal": true, "public": true, "mutable": true,
+	"member": true, "val": true, "static": true, "abstract": true, "override": true, "default": true,
+}
+
+// vbContainers are the kinds of VB.NET block that hold other declarations.
+var vbContainers = map[string]bool{
+	"namespace": true,
+	"module":    true,
+	"class":     true,
+	"structure": true,
+	"interface": true,
+	"enum":      true,
+}
+
+// fsharpSpans returns the top-level declarations of the F# code, each running
+// over the lines indented past it, with the comment and attribute lines
+// right above it. Modules and types record their name as MetadataBlock, and
+// other declarations, such as let bindings, record MetadataDefinition.
+func fsharpSpans(code string) []textSpan {
+	return fsharpDeclarations(code, 0)
+}
+
+// fsharpNested returns the declarations within the F# declaration, after the
+// line it starts on, such as the members of a type or the bindings of a
+// module.
+func fsharpNested(declaration string) []textSpan {
+	for offset := 0; offset < len(declaration); {
+		line, next := codeLine(declaration, offset)
+		if fsharpDeclaration.MatchString(line) {
+			return fsharpDeclarations(declaration, next)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// fsharpDeclarations returns the declarations of the F# code that start the
+// lines from offset on at the indentation of the first of them.
+func fsharpDeclarations(code string, offset int) []textSpan {
+	var spans []textSpan
+	level := -1
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := fsharpDeclaration.FindStringSubmatch(line)
+		// A module declaration without "=" names the module of the whole file
+		if m == nil || level >= 0 && len(m[1]) != level || m[2] == "module" && !strings.HasSuffix(strings.TrimSpace(m[3]), "=") {
+			offset = next
+			continue
+		}
+
+		level = len(m[1])
+		end := fsharpDeclarationEnd(code, next, level)
+		name := m[2]
+		for _, word := range strings.Fields(m[3]) {
+			if fsharpModifiers[word] {
+				continue
+			}
+			if n := fsharpName.FindString(word[strings.LastIndexByte(word, '.')+1:]); n != "" {
+				name = n
+			}
+			break
+		}
+
+		metadata := map[string]string{MetadataDefinition: name}
+		if m[2] == "module" || m[2] == "type" {
+			metadata = map[string]string{MetadataBlock: name}
+		}
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "//", "(*", "*", "[<"),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// fsharpDeclarationEnd returns the offset after the last line of the F#
+// declaration at the indentation indent whose lines after the first start at
+// offset in code. The declaration runs over the lines indented past it, and
+// over those closing a bracket or listing a union case at its indentation.
+func fsharpDeclarationEnd(code string, offset, indent int) int {
+	end := offset
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		trimmed := strings.TrimLeft(line, " \t")
+		switch {
+		case strings.TrimSpace(line) == "":
+		case len(line)-len(trimmed) > indent, hasAnyPrefix(trimmed, []string{")", "]", "}", "|"}), strings.TrimSpace(trimmed) == "end":
+			end = next
+		default:
+			return end
+		}
+		offset = next
+	}
+	return end
+}
+
+// vbSpans returns the declarations of the VB.NET code, such as its
+// namespaces, classes, and modules, each with the comment and attribute
+// lines right above it. Containers of other declarations record their name
+// as MetadataBlock, and subs, functions, properties, and operators record
+// MetadataDefinition.
+func vbSpans(code string) []textSpan {
+	return vbBlocks(code, 0)
+}
+
+// vbNested returns the declarations within the VB.NET declaration, after the
+// line opening it, such as the members of a class.
+func vbNested(declaration string) []textSpan {
+	for offset := 0; offset < len(declaration); {
+		line, next := codeLine(declaration, offset)
+		if vbDeclaration.MatchString(line) {
+			return vbBlocks(declaration, next)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// vbBlocks returns the declarations of the VB.NET code opened on the lines
+// from offset on that have an End line, leaving out those that don't, such
+// as abstract members, those of interfaces, and auto-implemented
+// properties.
+func vbBlocks(code string, offset int) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := vbDeclaration.FindStringSubmatch(line)
+		if m == nil || strings.Contains(strings.ToLower(m[1]), "mustoverride") {
+			offset = next
+			continue
+		}
+
+		kind := strings.ToLower(m[2])
+		end := vbBlockEnd(code, next, kind)
+		if end < 0 {
+			offset = next
+			continue
+		}
+
+		metadata := map[string]string{MetadataDefinition: m[3]}
+		if vbContainers[kind] {
+			metadata = map[string]string{MetadataBlock: m[3]}
+		}
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "'", "<"),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// vbBlockEnd returns the offset after the End line of the VB.NET block of the
+// kind whose lines after the first start at offset in code, or -1 if it has
+// none before the block holding it ends.
+func vbBlockEnd(code string, offset int, kind string) int {
+	if kind == "property" {
+		// Only properties with accessors have an End line
+		for offset < len(code) {
+			line, next := codeLine(code, offset)
+			if strings.TrimSpace(line) != "" {
+				if !vbAccessor.MatchString(line) {
+					return -1
+				}
+				break
+			}
+			offset = next
+		}
+	}
+
+	depth := 1
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		if m := vbEnd.FindStringSubmatch(line); m != nil {
+			switch closing := strings.ToLower(m[1]); {
+			case closing == kind:
+				if depth--; depth == 0 {
+					return next
+				}
+			case !vbContainers[kind] && vbContainers[closing]:
+				return -1
+			}
+		} else if m := vbDeclaration.FindStringSubmatch(line); m != nil && vbContainers[kind] && strings.ToLower(m[2]) == kind {
+			depth++
+		}
+		offset = next
+	}
+	return -1
+}
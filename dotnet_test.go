@@ -0,0 +1,109 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_FSharp(t *testing.T) {
+	code := `module Shapes
+
+open System
+
+/// A shape to draw
+type Shape =
+    | Circle of float
+    | Square of float
+
+[<Literal>]
+let Pi = 3.14159
+
+let rec area shape =
+    match shape with
+    | Circle r -> Pi * r * r
+    | Square s -> s * s
+
+module Render =
+    let describe shape =
+        sprintf "%A" shape
+
+    let draw shape =
+        printfn "%s" (describe shape)
+`
+	testChunkMetadata(t, code, languages.FSharp, 12, []struct{ prefix, key, value string }{
+		{"/// A shape to draw\ntype Shape =\n", MetadataBlock, "Shape"},
+		{"[<Literal>]\nlet Pi = 3.14159\n", MetadataDefinition, "Pi"},
+		{"let rec area shape =\n", MetadataDefinition, "area"},
+		{"    let describe shape =\n", MetadataDefinition, "describe"},
+		{"    let describe shape =\n", MetadataBlock, "Render"},
+		{"    let draw shape =\n", MetadataDefinition, "draw"},
+	})
+}
+
+func TestChunker_VBNet(t *testing.T) {
+	code := `Imports System
+
+Namespace Shapes
+    ''' <summary>A circle.</summary>
+    Public Class Circle
+        Public Property Radius As Double
+
+        Public ReadOnly Property Area As Double
+            Get
+                Return Math.PI * Radius * Radius
+            End Get
+        End Property
+
+        Public Sub Draw()
+            If Radius > 0 Then
+                Console.WriteLine("circle")
+            End If
+        End Sub
+    End Class
+
+    Public Interface IShape
+        Function Area() As Double
+    End Interface
+End Namespace
+`
+	testChunkMetadata(t, code, languages.VBNet, 20, []struct{ prefix, key, value string }{
+		{"    ''' <summary>A circle.</summary>\n    Public Class Circle\n", MetadataBlock, "Circle"},
+		{"        Public ReadOnly Property Area As Double\n", MetadataDefinition, "Area"},
+		{"        Public ReadOnly Property Area As Double\n", MetadataBlock, "Circle"},
+		{"        Public Sub Draw()\n", MetadataDefinition, "Draw"},
+		{"    Public Interface IShape\n", MetadataBlock, "IShape"},
+	})
+}
+
+// testChunkMetadata chunks code in lang with the maximum size, checks that a
+// chunk starts with each prefix and has the metadata value under key, and
+// returns the chunks.
+func testChunkMetadata(t *testing.T, code string, lang languages.LanguageName, maxSize int, tests []struct{ prefix, key, value string }) []Chunk {
+	t.Helper()
+	opts := []Option{WithLanguage(lang), WithMaxSize(maxSize)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	for _, tt := range tests {
+		var found bool
+		for _, chunk := range chunks {
+			if strings.HasPrefix(chunk.Content, tt.prefix) {
+				found = true
+				if got := chunk.Metadata[tt.key]; got != tt.value {
+					t.Errorf("expected %s %q of %q, got %q", tt.key, tt.value, chunk.Content, got)
+				}
+			}
+		}
+		if !found {
+			t.Errorf("expected a chunk starting with %q, got %+v", tt.prefix, chunks)
+		}
+	}
+	return chunks
+}
@@ -0,0 +1,420 @@
+// Package embed turns chunks into vectors with an embedding model.
+//
+// An Embedder maps texts to vectors. Client wraps any Embedder with
+// batching, rate limiting, and retries, and ChunkAndEmbed goes from text to
+// embedded chunks in one call:
+//
+//	model := &embed.OpenAI{APIKey: key, Model: "text-embedding-3-small"}
+//	embedded, err := embed.ChunkAndEmbed(ctx, chunkx.NewChunker(), code, model,
+//		chunkx.WithLanguage(languages.Go))
+//
+// For local backends bound by the tokens of a batch, WithMaxBatchTokens packs
+// texts by length, and EmbedResults embeds the results of a
+// chunkx.ChunkPipeline while it goes on chunking.
+//
+// OpenAI talks to any server implementing the OpenAI embeddings API, which
+// includes local model servers such as Ollama and llama.cpp.
+package embed
+
+import (
+	"cmp"
+	"context"
+	"fmt"
+	"slices"
+	"sync"
+	"time"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/internal/retry"
+)
+
+// Defaults for batching and retrying.
+const (
+	DefaultBatchSize = 32
+	DefaultRetries   = 3
+	DefaultBackoff   = time.Second
+)
+
+// Embedder computes one vector per text, in order.
+type Embedder interface {
+	Embed(ctx context.Context, texts []string) ([][]float32, error)
+}
+
+// PermanentError marks an embedding error that retrying won't fix, such as a
+// rejected request.
+type PermanentError = retry.PermanentError
+
+// Option configures a Client.
+type Option func(*Client)
+
+// WithBatchSize sets the number of texts sent in a single request.
+// Default: DefaultBatchSize.
+func WithBatchSize(n int) Option {
+	return func(c *Client) {
+		c.batchSize = n
+	}
+}
+
+// WithRetries sets how many times a failed request is retried before giving
+// up. Default: DefaultRetries.
+func WithRetries(n int) Option {
+	return func(c *Client) {
+		c.retries = n
+	}
+}
+
+// WithBackoff sets the delay before the first retry; each further retry
+// waits twice as long. Default: DefaultBackoff.
+func WithBackoff(d time.Duration) Option {
+	return func(c *Client) {
+		c.backoff = d
+	}
+}
+
+// WithMaxBatchTokens limits the tokens of the texts sent in a single request,
+// as local backends such as ONNX runtimes and GPU servers bound the tokens of
+// a batch rather than its texts. Texts are then packed by length: sorted by
+// their tokens and batched up to the limit and the batch size, so texts of a
+// batch pad to similar lengths. A text over the limit is sent on its own.
+// Tokens are counted with the counter of WithTokenCounter. Default:
+// unlimited.
+func WithMaxBatchTokens(n int) Option {
+	return func(c *Client) {
+		c.maxTokens = n
+	}
+}
+
+// WithTokenCounter sets the counter of the tokens of texts, which should
+// match the tokenizer of the model. Default: chunkx.SimpleTokenCounter.
+func WithTokenCounter(counter chunkx.TokenCounter) Option {
+	return func(c *Client) {
+		c.counter = counter
+	}
+}
+
+// WithThroughput calls report after every request with the throughput of
+// the client so far. Calls are serialized.
+func WithThroughput(report func(Throughput)) Option {
+	return func(c *Client) {
+		c.report = report
+	}
+}
+
+// WithConcurrency sets the number of requests in flight at once, over all
+// calls of the client and the counters of LimitTokenCounter. Default: 1.
+func WithConcurrency(n int) Option {
+	return func(c *Client) {
+		c.concurrency = n
+	}
+}
+
+// WithRateLimit limits the number of requests sent per minute, including
+// retries. Default: unlimited.
+func WithRateLimit(requestsPerMinute int) Option {
+	return func(c *Client) {
+		c.interval = 0
+		if requestsPerMinute > 0 {
+			c.interval = time.Minute / time.Duration(requestsPerMinute)
+		}
+	}
+}
+
+// Client embeds texts in batches with an Embedder, spacing requests to stay
+// within a rate limit and a number of requests in flight, and retrying
+// failures that aren't permanent with exponential backoff and jitter,
+// waiting at least as long as a 429 Too Many Requests asks for. It is itself
+// an Embedder and is safe for concurrent use.
+type Client struct {
+	embedder    Embedder
+	batchSize   int
+	maxTokens   int // Most tokens of a request, if limited
+	counter     chunkx.TokenCounter
+	report      func(Throughput)
+	retries     int
+	backoff     time.Duration
+	interval    time.Duration // Minimum time between requests
+	concurrency int
+	slots       chan struct{} // One per request in flight
+
+	mu   sync.Mutex
+	next time.Time // Earliest time the next request may start
+
+	statsMu sync.Mutex
+	stats   Throughput
+	started time.Time // Start of the first call of Embed
+}
+
+// New creates a client embedding with e.
+func New(e Embedder, opts ...Option) *Client {
+	c := &Client{
+		embedder:  e,
+		batchSize: DefaultBatchSize,
+		retries:   DefaultRetries,
+		backoff:   DefaultBackoff,
+		counter:   &chunkx.SimpleTokenCounter{},
+	}
+	for _, opt := range opts {
+		opt(c)
+	}
+	c.batchSize = max(c.batchSize, 1)
+	c.slots = make(chan struct{}, max(c.concurrency, 1))
+	return c
+}
+
+// Embed implements Embedder, splitting texts into batches.
+func (c *Client) Embed(ctx context.Context, texts []string) ([][]float32, error) {
+	batches, tokens, err := c.batches(texts)
+	if err != nil {
+		return nil, err
+	}
+	if c.report != nil {
+		c.statsMu.Lock()
+		if c.started.IsZero() {
+			c.started = time.Now()
+		}
+		c.statsMu.Unlock()
+	}
+
+	// Batches take a slot in order before they are sent, so they are sent in
+	// order without concurrency
+	ctx, cancel := context.WithCancel(ctx)
+	defer cancel()
+	var wg sync.WaitGroup
+	var errMu sync.Mutex
+	var firstErr error
+	fail := func(err error) {
+		errMu.Lock()
+		defer errMu.Unlock()
+		if firstErr == nil {
+			firstErr = err
+			cancel()
+		}
+	}
+
+	vectors := make([][]float32, len(texts))
+	for _, indices := range batches {
+		if err := c.acquire(ctx); err != nil {
+			fail(err)
+			break
+		}
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			defer c.release()
+
+			batch := make([]string, len(indices))
+			batchTokens := 0
+			for j, i := range indices {
+				batch[j] = texts[i]
+				if tokens != nil {
+					batchTokens += tokens[i]
+				}
+			}
+
+			var embedded [][]float32
+			err := c.send(ctx, func() error {
+				var err error
+				embedded, err = c.embedder.Embed(ctx, batch)
+				return err
+			})
+			if err != nil {
+				fail(fmt.Errorf("embed %d texts: %w", len(batch), err))
+				return
+			}
+			if len(embedded) != len(batch) {
+				fail(fmt.Errorf("embed %d texts: got %d vectors", len(batch), len(embedded)))
+				return
+			}
+			for j, i := range indices {
+				vectors[i] = embedded[j]
+			}
+			c.record(len(batch), batchTokens)
+		}()
+	}
+	wg.Wait()
+	if firstErr != nil {
+		return nil, firstErr
+	}
+	return vectors, nil
+}
+
+// acquire takes a slot for a request, waiting for one to be released.
+func (c *Client) acquire(ctx context.Context) error {
+	select {
+	case c.slots <- struct{}{}:
+		return nil
+	case <-ctx.Done():
+		return ctx.Err()
+	}
+}
+
+// release frees the slot of a request.
+func (c *Client) release() {
+	<-c.slots
+}
+
+// send calls fn once the rate limit allows, retrying failures that aren't
+// permanent.
+func (c *Client) send(ctx context.Context, fn func() error) error {
+	return retry.Do(ctx, c.retries, c.backoff, func() error {
+		if err := c.wait(ctx); err != nil {
+			return err
+		}
+		return fn()
+	})
+}
+
+// LimitTokenCounter returns a counter that counts tokens with counter, such
+// as the client of a remote tokenizer, within the rate and concurrency
+// limits of the client and with its retries, so sizing chunks against a
+// remote service doesn't exceed its limits either.
+func (c *Client) LimitTokenCounter(counter chunkx.TokenCounter) chunkx.TokenCounter {
+	return &limitedCounter{client: c, counter: counter}
+}
+
+// limitedCounter is a TokenCounter of LimitTokenCounter.
+type limitedCounter struct {
+	client  *Client
+	counter chunkx.TokenCounter
+}
+
+func (l *limitedCounter) CountTokens(text string) (n int, err error) {
+	ctx := context.Background()
+	if err := l.client.acquire(ctx); err != nil {
+		return 0, err
+	}
+	defer l.client.release()
+
+	err = l.client.send(ctx, func() error {
+		var err error
+		n, err = l.counter.CountTokens(text)
+		return err
+	})
+	return n, err
+}
+
+// batches returns the indices of the texts of every request, and the tokens
+// of every text if they were counted.
+func (c *Client) batches(texts []string) (batches [][]int, tokens []int, err error) {
+	if c.maxTokens > 0 || c.report != nil {
+		tokens = make([]int, len(texts))
+		for i, text := range texts {
+			if tokens[i], err = c.counter.CountTokens(text); err != nil {
+				return nil, nil, fmt.Errorf("count tokens: %w", err)
+			}
+		}
+	}
+
+	order := make([]int, len(texts))
+	for i := range order {
+		order[i] = i
+	}
+	if c.maxTokens <= 0 {
+		for start := 0; start < len(order); start += c.batchSize {
+			batches = append(batches, order[start:min(start+c.batchSize, len(order))])
+		}
+		return batches, tokens, nil
+	}
+
+	slices.SortStableFunc(order, func(a, b int) int { return cmp.Compare(tokens[a], tokens[b]) })
+	var batch []int
+	batchTokens := 0
+	for _, i := range order {
+		if len(batch) > 0 && (len(batch) == c.batchSize || batchTokens+tokens[i] > c.maxTokens) {
+			batches = append(batches, batch)
+			batch, batchTokens = nil, 0
+		}
+		batch = append(batch, i)
+		batchTokens += tokens[i]
+	}
+	if len(batch) > 0 {
+		batches = append(batches, batch)
+	}
+	return batches, tokens, nil
+}
+
+// record adds a request of n texts and the given tokens to the throughput,
+// and reports it.
+func (c *Client) record(n, tokens int) {
+	if c.report == nil {
+		return
+	}
+
+	c.statsMu.Lock()
+	defer c.statsMu.Unlock()
+	c.stats.Texts += n
+	c.stats.Tokens += tokens
+	c.stats.Requests++
+	c.stats.Elapsed = time.Since(c.started)
+	c.report(c.stats)
+}
+
+// wait blocks until the rate limit allows another request.
+func (c *Client) wait(ctx context.Context) error {
+	if c.interval == 0 {
+		return nil
+	}
+
+	c.mu.Lock()
+	start := time.Now()
+	if c.next.After(start) {
+		start = c.next
+	}
+	c.next = start.Add(c.interval)
+	c.mu.Unlock()
+
+	select {
+	case <-ctx.Done():
+		return ctx.Err()
+	case <-time.After(time.Until(start)):
+		return nil
+	}
+}
+
+// Embedding is a chunk with its vector.
+type Embedding struct {
+	Chunk  chunkx.Chunk
+	Vector []float32
+}
+
+// ChunkAndEmbed chunks code and embeds every chunk. Unless e is already a
+// Client, it is wrapped in one with the default settings so requests are
+// batched and retried. Chunking stops early on cancellation if chunker is a
+// chunkx.ContextChunker.
+func ChunkAndEmbed(ctx context.Context, chunker chunkx.Chunker, code string, e Embedder, opts ...chunkx.Option) ([]Embedding, error) {
+	var chunks []chunkx.Chunk
+	var err error
+	if cc, ok := chunker.(chunkx.ContextChunker); ok {
+		chunks, err = cc.ChunkContext(ctx, code, opts...)
+	} else if err = ctx.Err(); err == nil {
+		chunks, err = chunker.Chunk(code, opts...)
+	}
+	if err != nil {
+		return nil, err
+	}
+	return EmbedChunks(ctx, chunks, e)
+}
+
+// EmbedChunks embeds the content of every chunk, batching and retrying like
+// ChunkAndEmbed.
+func EmbedChunks(ctx context.Context, chunks []chunkx.Chunk, e Embedder) ([]Embedding, error) {
+	if _, ok := e.(*Client); !ok {
+		e = New(e)
+	}
+
+	texts := make([]string, len(chunks))
+	for i, chunk := range chunks {
+		texts[i] = chunk.Content
+	}
+
+	vectors, err := e.Embed(ctx, texts)
+	if err != nil {
+		return nil, err
+	}
+
+	embedded := make([]Embedding, len(chunks))
+	for i, chunk := range chunks {
+		embedded[i] = Embedding{Chunk: chunk, Vector: vectors[i]}
+	}
+	return embedded, nil
+}
@@ -0,0 +1,154 @@
+package embed
+
+import (
+	"context"
+	"errors"
+	"strings"
+	"sync"
+	"testing"
+	"time"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+// lengthEmbedder embeds each text as its length, recording batch sizes and
+// failing the first calls.
+type lengthEmbedder struct {
+	mu      sync.Mutex
+	batches []int
+	fail    int
+}
+
+func (e *lengthEmbedder) Embed(_ context.Context, texts []string) ([][]float32, error) {
+	e.mu.Lock()
+	defer e.mu.Unlock()
+
+	if e.fail > 0 {
+		e.fail--
+		return nil, errors.New("unavailable")
+	}
+	e.batches = append(e.batches, len(texts))
+
+	vectors := make([][]float32, len(texts))
+	for i, text := range texts {
+		vectors[i] = []float32{float32(len(text))}
+	}
+	return vectors, nil
+}
+
+func TestClient_Batches(t *testing.T) {
+	e := &lengthEmbedder{}
+	texts := []string{"a", "bb", "ccc", "dddd", "eeeee"}
+
+	vectors, err := New(e, WithBatchSize(2)).Embed(context.Background(), texts)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if len(e.batches) != 3 || e.batches[0] != 2 || e.batches[2] != 1 {
+		t.Errorf("batch sizes = %v, want [2 2 1]", e.batches)
+	}
+	for i, v := range vectors {
+		if v[0] != float32(len(texts[i])) {
+			t.Errorf("vector %d = %v, want [%d]", i, v, len(texts[i]))
+		}
+	}
+}
+
+func TestClient_Retries(t *testing.T) {
+	e := &lengthEmbedder{fail: 2}
+	if _, err := New(e, WithBackoff(time.Millisecond)).Embed(context.Background(), []string{"a"}); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	e = &lengthEmbedder{fail: 2}
+	_, err := New(e, WithRetries(1), WithBackoff(time.Millisecond)).Embed(context.Background(), []string{"a"})
+	if err == nil {
+		t.Error("expected an error after exhausting retries")
+	}
+}
+
+func TestClient_RateLimit(t *testing.T) {
+	e := &lengthEmbedder{}
+	c := New(e, WithBatchSize(1), WithRateLimit(1200)) // One request every 50ms
+
+	start := time.Now()
+	if _, err := c.Embed(context.Background(), []string{"a", "b", "c"}); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if elapsed := time.Since(start); elapsed < 100*time.Millisecond {
+		t.Errorf("three rate-limited requests took %v, want at least 100ms", elapsed)
+	}
+}
+
+// slowEmbedder embeds every text as zero after a delay, recording the most
+// calls in flight at once.
+type slowEmbedder struct {
+	mu       sync.Mutex
+	inFlight int
+	most     int
+}
+
+func (e *slowEmbedder) Embed(_ context.Context, texts []string) ([][]float32, error) {
+	e.mu.Lock()
+	e.inFlight++
+	e.most = max(e.most, e.inFlight)
+	e.mu.Unlock()
+
+	time.Sleep(20 * time.Millisecond)
+
+	e.mu.Lock()
+	e.inFlight--
+	e.mu.Unlock()
+	return make([][]float32, len(texts)), nil
+}
+
+func TestClient_Concurrency(t *testing.T) {
+	e := &slowEmbedder{}
+	texts := make([]string, 9)
+	if _, err := New(e, WithBatchSize(1), WithConcurrency(3)).Embed(context.Background(), texts); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if e.most != 3 {
+		t.Errorf("%d requests in flight at most, want 3", e.most)
+	}
+}
+
+// flakyCounter counts bytes, failing the first calls.
+type flakyCounter struct {
+	fail int
+}
+
+func (f *flakyCounter) CountTokens(text string) (int, error) {
+	if f.fail > 0 {
+		f.fail--
+		return 0, errors.New("unavailable")
+	}
+	return len(text), nil
+}
+
+func TestClient_LimitTokenCounter(t *testing.T) {
+	counter := New(&lengthEmbedder{}, WithBackoff(time.Millisecond)).LimitTokenCounter(&flakyCounter{fail: 2})
+	if n, err := counter.CountTokens("four"); err != nil || n != 4 {
+		t.Errorf("CountTokens = %d, %v; want 4 after retrying", n, err)
+	}
+}
+
+func TestChunkAndEmbed(t *testing.T) {
+	code := strings.Repeat("word word\n", 20)
+	embedded, err := ChunkAndEmbed(context.Background(), chunkx.NewChunker(), code, &lengthEmbedder{},
+		chunkx.WithLanguage(languages.Generic), chunkx.WithMaxSize(5))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if len(embedded) < 2 {
+		t.Fatalf("expected several chunks, got %d", len(embedded))
+	}
+	for _, e := range embedded {
+		if e.Vector[0] != float32(len(e.Chunk.Content)) {
+			t.Errorf("vector %v doesn't match chunk %q", e.Vector, e.Chunk.Content)
+		}
+	}
+}
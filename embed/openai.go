@@ -0,0 +1,91 @@
+package embed
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"strings"
+
+	"github.com/gomantics/chunkx/internal/retry"
+)
+
+// DefaultOpenAIURL is the base URL of the OpenAI API.
+const DefaultOpenAIURL = "https://api.openai.com/v1"
+
+// OpenAI embeds texts with the OpenAI embeddings API or a compatible server.
+// It sends a single request per call; wrap it in a Client to batch, rate
+// limit, and retry.
+type OpenAI struct {
+	URL        string       // Base URL; defaults to DefaultOpenAIURL
+	APIKey     string       // Sent as a bearer token, if set
+	Model      string       // Model name, such as text-embedding-3-small
+	Dimensions int          // Vector size for models that support shortening; 0 keeps the default
+	Client     *http.Client // Defaults to http.DefaultClient
+}
+
+// openAIRequest is the body of an embeddings request.
+type openAIRequest struct {
+	Model      string   `json:"model"`
+	Input      []string `json:"input"`
+	Dimensions int      `json:"dimensions,omitempty"`
+}
+
+// openAIResponse is the body of an embeddings response.
+type openAIResponse struct {
+	Data []struct {
+		Index     int       `json:"index"`
+		Embedding []float32 `json:"embedding"`
+	} `json:"data"`
+}
+
+// Embed implements Embedder.
+func (o *OpenAI) Embed(ctx context.Context, texts []string) ([][]float32, error) {
+	if len(texts) == 0 {
+		return nil, nil
+	}
+
+	body, err := json.Marshal(openAIRequest{Model: o.Model, Input: texts, Dimensions: o.Dimensions})
+	if err != nil {
+		return nil, &PermanentError{Err: err}
+	}
+
+	base := o.URL
+	if base == "" {
+		base = DefaultOpenAIURL
+	}
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, strings.TrimSuffix(base, "/")+"/embeddings", bytes.NewReader(body))
+	if err != nil {
+		return nil, &PermanentError{Err: err}
+	}
+	req.Header.Set("Content-Type", "application/json")
+	if o.APIKey != "" {
+		req.Header.Set("Authorization", "Bearer "+o.APIKey)
+	}
+
+	data, err := retry.Send(o.Client, req, "openai")
+	if err != nil {
+		return nil, err
+	}
+
+	var resp openAIResponse
+	if err := json.Unmarshal(data, &resp); err != nil {
+		return nil, fmt.Errorf("openai: invalid response: %w", err)
+	}
+
+	// Data is usually in input order, but the index is authoritative
+	vectors := make([][]float32, len(texts))
+	for _, d := range resp.Data {
+		if d.Index < 0 || d.Index >= len(texts) {
+			return nil, fmt.Errorf("openai: invalid response: index %d out of range", d.Index)
+		}
+		vectors[d.Index] = d.Embedding
+	}
+	for i, v := range vectors {
+		if v == nil {
+			return nil, fmt.Errorf("openai: invalid response: no embedding for input %d", i)
+		}
+	}
+	return vectors, nil
+}
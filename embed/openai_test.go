@@ -0,0 +1,54 @@
+package embed
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+)
+
+func TestOpenAI_Embed(t *testing.T) {
+	var got openAIRequest
+	var auth string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/v1/embeddings" {
+			t.Errorf("unexpected path %s", r.URL.Path)
+		}
+		auth = r.Header.Get("Authorization")
+		if err := json.NewDecoder(r.Body).Decode(&got); err != nil {
+			t.Errorf("invalid request body: %v", err)
+		}
+		// Respond out of order to check that the index is used
+		_, _ = w.Write([]byte(`{"data":[{"index":1,"embedding":[2,2]},{"index":0,"embedding":[1,1]}]}`))
+	}))
+	defer server.Close()
+
+	o := &OpenAI{URL: server.URL + "/v1", APIKey: "key", Model: "small", Dimensions: 2}
+	vectors, err := o.Embed(context.Background(), []string{"one", "two"})
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if got.Model != "small" || got.Dimensions != 2 || len(got.Input) != 2 {
+		t.Errorf("unexpected request %+v", got)
+	}
+	if auth != "Bearer key" {
+		t.Errorf("Authorization = %q", auth)
+	}
+	if len(vectors) != 2 || vectors[0][0] != 1 || vectors[1][0] != 2 {
+		t.Errorf("vectors = %v", vectors)
+	}
+}
+
+func TestOpenAI_MissingEmbedding(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, _ *http.Request) {
+		_, _ = w.Write([]byte(`{"data":[{"index":0,"embedding":[1]}]}`))
+	}))
+	defer server.Close()
+
+	o := &OpenAI{URL: server.URL}
+	if _, err := o.Embed(context.Background(), []string{"one", "two"}); err == nil {
+		t.Error("expected an error for a missing embedding")
+	}
+}
@@ -0,0 +1,142 @@
+package embed
+
+import (
+	"context"
+	"time"
+
+	"github.com/gomantics/chunkx"
+)
+
+// Throughput is the work a Client has done, reported by WithThroughput.
+type Throughput struct {
+	Texts    int           // Texts embedded
+	Tokens   int           // Tokens of the texts embedded
+	Requests int           // Requests that succeeded
+	Elapsed  time.Duration // Time since the first call of Embed started
+}
+
+// TextsPerSecond returns the texts embedded per second.
+func (t Throughput) TextsPerSecond() float64 {
+	return perSecond(t.Texts, t.Elapsed)
+}
+
+// TokensPerSecond returns the tokens embedded per second.
+func (t Throughput) TokensPerSecond() float64 {
+	return perSecond(t.Tokens, t.Elapsed)
+}
+
+// perSecond returns n per second over elapsed, or 0 before any time passed.
+func perSecond(n int, elapsed time.Duration) float64 {
+	if elapsed <= 0 {
+		return 0
+	}
+	return float64(n) / elapsed.Seconds()
+}
+
+// FileEmbeddings is the outcome of embedding the chunks of a file of a
+// chunkx.ChunkPipeline.
+type FileEmbeddings struct {
+	Path       string
+	Embeddings []Embedding
+	Skipped    bool  // Unchanged since it was recorded by chunkx.WithIndex
+	Err        error // Why the file failed to chunk or embed
+}
+
+// EmbedResults embeds the chunks of every result of a chunkx.ChunkPipeline
+// while the pipeline goes on chunking, delivering each file once its chunks
+// are embedded, in the order of results. Chunks of several files are pooled
+// until they fill a batch of the client, so small files share requests, and
+// packed by WithMaxBatchTokens. Unless e is already a Client, it is wrapped
+// in one with the default settings.
+//
+// A failed request fails the files of its chunks, reported in their Err, and
+// embedding goes on. The returned channel is closed once results is closed
+// and its files are delivered, or ctx is cancelled; cancel the pipeline's
+// context too so it stops.
+func EmbedResults(ctx context.Context, results <-chan chunkx.FileResult, e Embedder) <-chan FileEmbeddings {
+	c, ok := e.(*Client)
+	if !ok {
+		c = New(e)
+	}
+
+	// Pooled files are handed over on a buffered channel, so the next pool
+	// fills while the previous one is embedded
+	pools := make(chan []chunkx.FileResult, 1)
+	go func() {
+		defer close(pools)
+		var pool []chunkx.FileResult
+		var n int // Chunks of the pool
+		send := func() bool {
+			select {
+			case pools <- pool:
+				pool, n = nil, 0
+				return true
+			case <-ctx.Done():
+				return false
+			}
+		}
+		for result := range results {
+			pool = append(pool, result)
+			if result.Err == nil {
+				n += len(result.Chunks)
+			}
+			if n >= c.batchSize && !send() {
+				return
+			}
+		}
+		if len(pool) > 0 {
+			send()
+		}
+	}()
+
+	out := make(chan FileEmbeddings)
+	go func() {
+		defer close(out)
+		for pool := range pools {
+			for _, file := range c.embedPool(ctx, pool) {
+				select {
+				case out <- file:
+				case <-ctx.Done():
+					return
+				}
+			}
+		}
+	}()
+	return out
+}
+
+// embedPool embeds the chunks of the files of pool in one call of Embed.
+func (c *Client) embedPool(ctx context.Context, pool []chunkx.FileResult) []FileEmbeddings {
+	var texts []string
+	for _, result := range pool {
+		if result.Err == nil {
+			for _, chunk := range result.Chunks {
+				texts = append(texts, chunk.Content)
+			}
+		}
+	}
+
+	var vectors [][]float32
+	var err error
+	if len(texts) > 0 {
+		vectors, err = c.Embed(ctx, texts)
+	}
+
+	files := make([]FileEmbeddings, len(pool))
+	for i, result := range pool {
+		files[i] = FileEmbeddings{Path: result.Path, Skipped: result.Skipped, Err: result.Err}
+		if result.Err != nil {
+			continue
+		}
+		if err != nil {
+			files[i].Err = err
+			continue
+		}
+		files[i].Embeddings = make([]Embedding, len(result.Chunks))
+		for j, chunk := range result.Chunks {
+			files[i].Embeddings[j] = Embedding{Chunk: chunk, Vector: vectors[j]}
+		}
+		vectors = vectors[len(result.Chunks):]
+	}
+	return files
+}
@@ -0,0 +1,65 @@
+package embed
+
+import (
+	"context"
+	"errors"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+func TestClient_MaxBatchTokens(t *testing.T) {
+	e := &lengthEmbedder{}
+	texts := []string{"aaaa", "b", "cc", "ddd", "eeeee"}
+	var last Throughput
+	c := New(e, WithMaxBatchTokens(6), WithTokenCounter(&chunkx.ByteCounter{}),
+		WithThroughput(func(t Throughput) { last = t }))
+
+	vectors, err := c.Embed(context.Background(), texts)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(e.batches) != 3 || e.batches[0] != 3 || e.batches[1] != 1 || e.batches[2] != 1 {
+		t.Errorf("batch sizes = %v, want [3 1 1]", e.batches)
+	}
+	for i, v := range vectors {
+		if v[0] != float32(len(texts[i])) {
+			t.Errorf("vector %d = %v, want [%d]", i, v, len(texts[i]))
+		}
+	}
+	if last.Texts != 5 || last.Tokens != 15 || last.Requests != 3 {
+		t.Errorf("throughput = %+v, want 5 texts of 15 tokens in 3 requests", last)
+	}
+}
+
+func TestEmbedResults(t *testing.T) {
+	failed := errors.New("unreadable")
+	results := make(chan chunkx.FileResult, 4)
+	results <- chunkx.FileResult{Path: "a.go", Chunks: []chunkx.Chunk{{Content: "a"}, {Content: "aa"}, {Content: "aaa"}}}
+	results <- chunkx.FileResult{Path: "b.go", Err: failed}
+	results <- chunkx.FileResult{Path: "c.go", Chunks: []chunkx.Chunk{{Content: "cccc"}}}
+	results <- chunkx.FileResult{Path: "d.go", Skipped: true}
+	close(results)
+
+	var files []FileEmbeddings
+	for file := range EmbedResults(context.Background(), results, New(&lengthEmbedder{}, WithBatchSize(2))) {
+		files = append(files, file)
+	}
+
+	if len(files) != 4 || files[0].Path != "a.go" || files[3].Path != "d.go" {
+		t.Fatalf("expected the four files in order, got %+v", files)
+	}
+	if !errors.Is(files[1].Err, failed) || !files[3].Skipped {
+		t.Errorf("expected the failed and skipped files as they were, got %+v and %+v", files[1], files[3])
+	}
+	for _, file := range files {
+		for _, embedded := range file.Embeddings {
+			if embedded.Vector[0] != float32(len(embedded.Chunk.Content)) {
+				t.Errorf("%s: vector %v doesn't match chunk %q", file.Path, embedded.Vector, embedded.Chunk.Content)
+			}
+		}
+	}
+	if len(files[0].Embeddings) != 3 || len(files[2].Embeddings) != 1 {
+		t.Errorf("expected every chunk embedded, got %d and %d", len(files[0].Embeddings), len(files[2].Embeddings))
+	}
+}
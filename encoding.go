@@ -0,0 +1,375 @@
+package chunkx
+
+import (
+	"bytes"
+	"fmt"
+	"sort"
+	"strings"
+	"unicode/utf16"
+	"unicode/utf8"
+)
+
+// MetadataEncoding is the metadata key WithTranscoding records the detected
+// encoding of a file under, when it isn't UTF-8.
+const MetadataEncoding = "encoding"
+
+// Encodings reported by DetectEncoding.
+const (
+	EncodingUTF8        = "utf-8"
+	EncodingUTF16LE     = "utf-16le"
+	EncodingUTF16BE     = "utf-16be"
+	EncodingWindows1252 = "windows-1252" // Also covers ISO-8859-1 (Latin-1)
+	EncodingShiftJIS    = "shift_jis"
+)
+
+// TranscodeMode selects whether and how files are transcoded to UTF-8.
+type TranscodeMode int
+
+const (
+	TranscodeOff    TranscodeMode = iota // Chunk file content as is
+	TranscodeLossy                       // Transcode, replacing undecodable bytes with U+FFFD
+	TranscodeStrict                      // Transcode, failing with ErrInvalidEncoding on undecodable bytes
+)
+
+// detectSample is how much of a file DetectEncoding looks at for UTF-16
+// without a byte order mark.
+const detectSample = 4096
+
+// windows1252 maps the bytes 0x80 to 0x9F of Windows-1252 to runes. The five
+// bytes the code page leaves undefined map to the C1 controls of the same
+// value, as in the WHATWG Encoding Standard.
+var windows1252 = [32]rune{
+	'€', 0x81, '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', 0x8D, 'Ž', 0x8F,
+	0x90, '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', 0x9D, 'ž', 'Ÿ',
+}
+
+// WithTranscoding makes files read by ChunkFile, ChunkMapped, and pipelines
+// be transcoded to UTF-8 before chunking, detecting their encoding with
+// DetectEncoding. Chunks of files that weren't UTF-8 record the encoding in
+// their metadata under MetadataEncoding. Content is the transcoded text,
+// but byte offsets still refer to the original bytes of the file. Code passed
+// to Chunk is already a string and is never transcoded.
+//
+// Shift_JIS is detected but not decoded, since that needs mapping tables
+// chunkx doesn't carry: TranscodeLossy keeps its ASCII text and replaces
+// other characters with U+FFFD, and TranscodeStrict rejects it. Convert such
+// files with golang.org/x/text before chunking to keep their text.
+func WithTranscoding(mode TranscodeMode) Option {
+	return func(c *config) {
+		c.transcode = mode
+	}
+}
+
+// DetectEncoding guesses the encoding of content. A byte order mark decides
+// the encoding; otherwise valid UTF-8 is reported as UTF-8, text with the
+// zero bytes of ASCII encoded as UTF-16 as UTF-16, text made of valid
+// Shift_JIS byte pairs as Shift_JIS, and anything else as Windows-1252,
+// which decodes every byte.
+func DetectEncoding(content []byte) string {
+	switch {
+	case bytes.HasPrefix(content, []byte{0xEF, 0xBB, 0xBF}):
+		return EncodingUTF8
+	case bytes.HasPrefix(content, []byte{0xFF, 0xFE}):
+		return EncodingUTF16LE
+	case bytes.HasPrefix(content, []byte{0xFE, 0xFF}):
+		return EncodingUTF16BE
+	}
+
+	// UTF-16 text made of ASCII has a zero in every other byte, which is
+	// valid UTF-8, so look for it first
+	if encoding, ok := detectUTF16(content[:min(len(content), detectSample)]); ok {
+		return encoding
+	}
+	if utf8.Valid(content) {
+		return EncodingUTF8
+	}
+	if isShiftJIS(content) {
+		return EncodingShiftJIS
+	}
+	return EncodingWindows1252
+}
+
+// detectUTF16 reports UTF-16 when zeros make up most of the bytes at odd
+// offsets, for little endian, or even offsets, for big endian, and hardly
+// any of the others.
+func detectUTF16(sample []byte) (string, bool) {
+	pairs := len(sample) / 2
+	if pairs < 2 {
+		return "", false
+	}
+
+	var even, odd int
+	for i := 0; i+1 < len(sample); i += 2 {
+		if sample[i] == 0 {
+			even++
+		}
+		if sample[i+1] == 0 {
+			odd++
+		}
+	}
+
+	switch {
+	case odd*2 > pairs && even*10 < pairs:
+		return EncodingUTF16LE, true
+	case even*2 > pairs && odd*10 < pairs:
+		return EncodingUTF16BE, true
+	}
+	return "", false
+}
+
+// isShiftJIS reports whether every byte above ASCII in content is halfwidth
+// katakana or part of a valid double-byte character, and at least one
+// character has a trail byte above ASCII, which Latin text followed by a
+// letter rarely does.
+func isShiftJIS(content []byte) bool {
+	highTrail := false
+	for i := 0; i < len(content); i++ {
+		b := content[i]
+		switch {
+		case b < 0x80, b >= 0xA1 && b <= 0xDF:
+		case isShiftJISLead(b) && i+1 < len(content) && isShiftJISTrail(content[i+1]):
+			highTrail = highTrail || content[i+1] >= 0x80
+			i++
+		default:
+			return false
+		}
+	}
+	return highTrail
+}
+
+// isShiftJISLead reports whether b starts a double-byte Shift_JIS character.
+func isShiftJISLead(b byte) bool {
+	return b >= 0x81 && b <= 0x9F || b >= 0xE0 && b <= 0xFC
+}
+
+// isShiftJISTrail reports whether b can end a double-byte Shift_JIS
+// character.
+func isShiftJISTrail(b byte) bool {
+	return b >= 0x40 && b <= 0xFC && b != 0x7F
+}
+
+// Transcode converts content to UTF-8 from the encoding DetectEncoding
+// reports for it, and returns the text and the encoding. A UTF-8 byte order
+// mark is kept, but UTF-16 ones are dropped. With TranscodeStrict, content
+// that can't be decoded without replacing bytes fails with
+// ErrInvalidEncoding; TranscodeOff returns content unchanged.
+func Transcode(content []byte, mode TranscodeMode) (string, string, error) {
+	text, encoding, _, err := transcode(content, mode)
+	return text, encoding, err
+}
+
+// transcode converts content like Transcode and also returns the map from
+// offsets in the text to offsets in content, or nil if they are the same.
+func transcode(content []byte, mode TranscodeMode) (string, string, *offsetMap, error) {
+	if mode == TranscodeOff {
+		return string(content), "", nil, nil
+	}
+
+	encoding := DetectEncoding(content)
+	if encoding == EncodingUTF8 && utf8.Valid(content) {
+		return string(content), encoding, nil, nil
+	}
+
+	strict := mode == TranscodeStrict
+	var tb textBuilder
+	tb.b.Grow(len(content) + len(content)/4)
+
+	var err error
+	switch encoding {
+	case EncodingUTF16LE, EncodingUTF16BE:
+		err = decodeUTF16(&tb, content, encoding, strict)
+	case EncodingWindows1252:
+		err = decodeWindows1252(&tb, content, strict)
+	case EncodingShiftJIS:
+		err = decodeShiftJIS(&tb, content, strict)
+	default:
+		// Only invalid content starting with a UTF-8 byte order mark gets here
+		err = decodeUTF8(&tb, content, strict)
+	}
+	if err != nil {
+		return "", encoding, nil, err
+	}
+	return tb.b.String(), encoding, &tb.offsets, nil
+}
+
+// offsetMap maps byte offsets in transcoded text back to the original
+// content. It run-length encodes the sizes of characters before and after
+// transcoding, so text made mostly of one kind of character, such as ASCII
+// in UTF-16, needs only a few runs.
+type offsetMap struct {
+	runs    []offsetRun
+	textEnd int
+	origEnd int
+	skipped bool // Whether original bytes were skipped since the last run
+}
+
+// offsetRun is a run of characters of the same size.
+type offsetRun struct {
+	text, orig         int // Offsets of the first character
+	textSize, origSize int // Size of every character
+}
+
+// add records the next character.
+func (m *offsetMap) add(textSize, origSize int) {
+	if n := len(m.runs); n == 0 || m.skipped || m.runs[n-1].textSize != textSize || m.runs[n-1].origSize != origSize {
+		m.runs = append(m.runs, offsetRun{text: m.textEnd, orig: m.origEnd, textSize: textSize, origSize: origSize})
+		m.skipped = false
+	}
+	m.textEnd += textSize
+	m.origEnd += origSize
+}
+
+// skip records original bytes that have no text, such as a byte order mark.
+// The offset in the text where they were skipped maps to the byte after them.
+func (m *offsetMap) skip(origSize int) {
+	m.origEnd += origSize
+	m.skipped = true
+}
+
+// original returns the offset in the original content of offset in the text.
+func (m *offsetMap) original(offset int) int {
+	i := sort.Search(len(m.runs), func(i int) bool { return m.runs[i].text > offset }) - 1
+	if i < 0 {
+		return m.origEnd - m.textEnd + offset
+	}
+	run := m.runs[i]
+	return run.orig + (offset-run.text)/run.textSize*run.origSize
+}
+
+// originalEnd returns the offset in the original content right after the
+// character of the text that ends at offset.
+func (m *offsetMap) originalEnd(offset int) int {
+	if offset <= 0 {
+		return m.original(offset)
+	}
+	i := sort.Search(len(m.runs), func(i int) bool { return m.runs[i].text >= offset }) - 1
+	if i < 0 {
+		return m.original(offset)
+	}
+	run := m.runs[i]
+	return run.orig + ((offset-run.text-1)/run.textSize+1)*run.origSize
+}
+
+// textBuilder builds transcoded text, recording where its characters came
+// from.
+type textBuilder struct {
+	b       strings.Builder
+	offsets offsetMap
+}
+
+// writeRune appends r, decoded from origSize bytes of the original.
+func (tb *textBuilder) writeRune(r rune, origSize int) {
+	n, _ := tb.b.WriteRune(r)
+	tb.offsets.add(n, origSize)
+}
+
+// writeString appends s, copied unchanged from the original.
+func (tb *textBuilder) writeString(s string) {
+	tb.b.WriteString(s)
+	for range len(s) {
+		tb.offsets.add(1, 1)
+	}
+}
+
+// invalidEncoding returns the error for content that can't be decoded at
+// offset.
+func invalidEncoding(encoding string, offset int) error {
+	return &positionError{offset: offset, err: fmt.Errorf("%w: %s at byte %d", ErrInvalidEncoding, encoding, offset)}
+}
+
+// decodeUTF8 copies UTF-8 content, replacing each invalid byte with U+FFFD.
+func decodeUTF8(tb *textBuilder, content []byte, strict bool) error {
+	for i := 0; i < len(content); {
+		r, size := utf8.DecodeRune(content[i:])
+		if r == utf8.RuneError && size <= 1 && strict {
+			return invalidEncoding(EncodingUTF8, i)
+		}
+		tb.writeRune(r, size)
+		i += size
+	}
+	return nil
+}
+
+// decodeUTF16 decodes UTF-16 content in the byte order of encoding, dropping
+// a byte order mark.
+func decodeUTF16(tb *textBuilder, content []byte, encoding string, strict bool) error {
+	unit := func(i int) uint16 {
+		if encoding == EncodingUTF16BE {
+			return uint16(content[i])<<8 | uint16(content[i+1])
+		}
+		return uint16(content[i+1])<<8 | uint16(content[i])
+	}
+
+	start := 0
+	if len(content) >= 2 && unit(0) == 0xFEFF {
+		start = 2
+		tb.offsets.skip(2)
+	}
+
+	for i := start; i < len(content); i += 2 {
+		if i+1 >= len(content) {
+			if strict {
+				return invalidEncoding(encoding, i)
+			}
+			tb.writeRune(utf8.RuneError, 1)
+			break
+		}
+
+		r := rune(unit(i))
+		if utf16.IsSurrogate(r) {
+			if i+3 < len(content) {
+				if pair := utf16.DecodeRune(r, rune(unit(i+2))); pair != utf8.RuneError {
+					tb.writeRune(pair, 4)
+					i += 2
+					continue
+				}
+			}
+			if strict {
+				return invalidEncoding(encoding, i)
+			}
+			r = utf8.RuneError
+		}
+		tb.writeRune(r, 2)
+	}
+	return nil
+}
+
+// decodeWindows1252 decodes Windows-1252 content. In strict mode, the bytes
+// the code page leaves undefined are rejected.
+func decodeWindows1252(tb *textBuilder, content []byte, strict bool) error {
+	for i, c := range content {
+		r := rune(c)
+		if c >= 0x80 && c <= 0x9F {
+			r = windows1252[c-0x80]
+			if strict && r == rune(c) {
+				return invalidEncoding(EncodingWindows1252, i)
+			}
+		}
+		tb.writeRune(r, 1)
+	}
+	return nil
+}
+
+// decodeShiftJIS keeps the ASCII and halfwidth katakana of Shift_JIS content
+// and replaces double-byte characters with U+FFFD. In strict mode, content
+// with double-byte characters is rejected.
+func decodeShiftJIS(tb *textBuilder, content []byte, strict bool) error {
+	for i := 0; i < len(content); i++ {
+		c := content[i]
+		switch {
+		case c < 0x80:
+			tb.writeRune(rune(c), 1)
+		case c >= 0xA1 && c <= 0xDF:
+			tb.writeRune(0xFF61+rune(c-0xA1), 1)
+		default:
+			if strict {
+				err := fmt.Errorf("%w: %s is detected but not supported, at byte %d", ErrInvalidEncoding, EncodingShiftJIS, i)
+				return &positionError{offset: i, err: err}
+			}
+			size := min(2, len(content)-i)
+			tb.writeRune(utf8.RuneError, size)
+			i += size - 1
+		}
+	}
+	return nil
+}
@@ -0,0 +1,150 @@
+package chunkx
+
+import (
+	"errors"
+	"os"
+	"path/filepath"
+	"testing"
+	"unicode/utf16"
+)
+
+// utf16Bytes encodes s as UTF-16 in the given byte order.
+func utf16Bytes(s string, bigEndian bool) []byte {
+	var b []byte
+	for _, u := range utf16.Encode([]rune(s)) {
+		if bigEndian {
+			b = append(b, byte(u>>8), byte(u))
+		} else {
+			b = append(b, byte(u), byte(u>>8))
+		}
+	}
+	return b
+}
+
+func TestDetectEncoding(t *testing.T) {
+	tests := []struct {
+		name    string
+		content []byte
+		want    string
+	}{
+		{"ascii", []byte("package main\n"), EncodingUTF8},
+		{"utf-8", []byte("naïve café\n"), EncodingUTF8},
+		{"utf-8 bom", []byte("\xEF\xBB\xBFhello"), EncodingUTF8},
+		{"utf-16le bom", append([]byte{0xFF, 0xFE}, utf16Bytes("hi", false)...), EncodingUTF16LE},
+		{"utf-16be bom", append([]byte{0xFE, 0xFF}, utf16Bytes("hi", true)...), EncodingUTF16BE},
+		{"utf-16le", utf16Bytes("def main():\n    pass\n", false), EncodingUTF16LE},
+		{"utf-16be", utf16Bytes("def main():\n    pass\n", true), EncodingUTF16BE},
+		{"latin-1", []byte("caf\xe9 cr\xe8me br\xfbl\xe9e\n"), EncodingWindows1252},
+		{"windows-1252 quotes", []byte("\x93quoted\x94\n"), EncodingWindows1252},
+		{"shift_jis", []byte("// \x93\xfa\x96\x7b\x8c\xea\n"), EncodingShiftJIS},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := DetectEncoding(tt.content); got != tt.want {
+				t.Errorf("DetectEncoding() = %s, want %s", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestTranscode(t *testing.T) {
+	tests := []struct {
+		name    string
+		content []byte
+		mode    TranscodeMode
+		want    string
+		wantErr bool
+	}{
+		{"off", []byte("caf\xe9"), TranscodeOff, "caf\xe9", false},
+		{"latin-1", []byte("caf\xe9"), TranscodeLossy, "café", false},
+		{"windows-1252", []byte("\x80 \x93x\x94"), TranscodeStrict, "€ “x”", false},
+		{"windows-1252 undefined byte", []byte("a\x81b\xe9"), TranscodeStrict, "", true},
+		{"utf-16 drops bom", append([]byte{0xFF, 0xFE}, utf16Bytes("h€llo 😀", false)...), TranscodeStrict, "h€llo 😀", false},
+		{"utf-16 unpaired surrogate", append([]byte{0xFF, 0xFE}, 0x00, 0xD8, 'a', 0), TranscodeLossy, "\uFFFDa", false},
+		{"utf-16 unpaired surrogate strict", append([]byte{0xFF, 0xFE}, 0x00, 0xD8, 'a', 0), TranscodeStrict, "", true},
+		{"utf-16 odd length", []byte{0xFE, 0xFF, 0, 'a', 0}, TranscodeLossy, "a\uFFFD", false},
+		{"utf-8 bom with invalid bytes", []byte("\xEF\xBB\xBFa\xffb"), TranscodeLossy, "\uFEFFa\uFFFDb", false},
+		{"utf-8 bom with invalid bytes strict", []byte("\xEF\xBB\xBFa\xffb"), TranscodeStrict, "", true},
+		{"shift_jis", []byte("x = 1 // \x93\xfa\x96\x7b \xb1\n"), TranscodeLossy, "x = 1 // \uFFFD\uFFFD \uFF71\n", false},
+		{"shift_jis strict", []byte("x = 1 // \x93\xfa\x96\x7b\n"), TranscodeStrict, "", true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got, _, err := Transcode(tt.content, tt.mode)
+			if tt.wantErr {
+				if !errors.Is(err, ErrInvalidEncoding) {
+					t.Errorf("expected ErrInvalidEncoding, got %v (%q)", err, got)
+				}
+				return
+			}
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if got != tt.want {
+				t.Errorf("Transcode() = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestTranscode_Offsets(t *testing.T) {
+	tests := []struct {
+		name    string
+		content []byte
+		offsets map[int]int // Offset in the text to offset in content
+	}{
+		{"latin-1", []byte("caf\xe9 ok"), map[int]int{0: 0, 3: 3, 5: 4, 8: 7}},
+		{"utf-16 bom", append([]byte{0xFF, 0xFE}, utf16Bytes("añ", false)...), map[int]int{0: 2, 1: 4, 3: 6}},
+		{"utf-16 surrogate", append([]byte{0xFF, 0xFE}, utf16Bytes("a😀b", false)...), map[int]int{1: 4, 5: 8, 6: 10}},
+		{"invalid utf-8", []byte("\xEF\xBB\xBFa\xffb"), map[int]int{3: 3, 4: 4, 7: 5, 8: 6}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, _, offsets, err := transcode(tt.content, TranscodeLossy)
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			for text, want := range tt.offsets {
+				if got := offsets.original(text); got != want {
+					t.Errorf("original(%d) = %d, want %d", text, got, want)
+				}
+			}
+		})
+	}
+}
+
+func TestWithTranscoding(t *testing.T) {
+	dir := t.TempDir()
+	path := filepath.Join(dir, "notes.txt")
+	if err := os.WriteFile(path, utf16Bytes("\uFEFFcrème brûlée\n", false), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	chunks, err := NewChunker().ChunkFile(path, WithTranscoding(TranscodeStrict))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) != 1 || chunks[0].Content != "crème brûlée\n" {
+		t.Fatalf("unexpected chunks: %+v", chunks)
+	}
+	if got := chunks[0].Metadata[MetadataEncoding]; got != EncodingUTF16LE {
+		t.Errorf("encoding metadata = %q, want %s", got, EncodingUTF16LE)
+	}
+	// Offsets refer to the UTF-16 bytes, after the byte order mark
+	if chunks[0].StartByte != 2 || chunks[0].EndByte != 28 {
+		t.Errorf("offsets = [%d, %d), want [2, 28)", chunks[0].StartByte, chunks[0].EndByte)
+	}
+
+	// UTF-8 files aren't annotated
+	utf8Path := filepath.Join(dir, "plain.txt")
+	if err := os.WriteFile(utf8Path, []byte("plain\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	chunks, err = NewChunker().ChunkFile(utf8Path, WithTranscoding(TranscodeLossy))
+	if err != nil || chunks[0].Metadata != nil {
+		t.Errorf("expected UTF-8 chunks without metadata, got %+v, %v", chunks, err)
+	}
+}
@@ -0,0 +1,141 @@
+package chunkx
+
+import (
+	"regexp"
+	"strconv"
+	"strings"
+)
+
+var (
+	// erlangFunction matches the start of a clause of an Erlang function,
+	// such as "handle_call(" or "'quoted name'(", and its name.
+	erlangFunction = regexp.MustCompile(`^([a-z][\w@]*|'(?:[^'\\\n]|\\.)*')[ \t]*\(`)
+	// erlangAttribute matches the start of an Erlang attribute, such as
+	// "-module(" or "-spec", and its name.
+	erlangAttribute = regexp.MustCompile(`^-[ \t]*([a-z][\w@]*)`)
+)
+
+// erlangSpans returns the forms of the Erlang code. Functions, with all
+// their clauses, the -spec and -doc attributes declaring them, and the edoc
+// comment lines right above, record their name and arity as
+// MetadataDefinition, such as "init/1". The attributes before the first
+// function, such as -module and -export, record MetadataBlock "header" so
+// they are chunked together, and later attributes, such as -record or
+// -define, record their name as MetadataAttribute.
+func erlangSpans(code string) []textSpan {
+	var spans []textSpan
+	declared := -1 // Start of the -spec or -doc attribute of the next function
+	header := true
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		function := erlangFunction.FindStringSubmatchIndex(line)
+		attribute := erlangAttribute.FindStringSubmatch(line)
+		if function == nil && attribute == nil {
+			offset = next
+			continue
+		}
+
+		_, end := codeLine(code, erlangFormEnd(code, offset))
+		start := offset
+		var metadata map[string]string
+		switch {
+		case function != nil:
+			header = false
+			name := line[function[2]:function[3]]
+			arity := erlangArity(code, offset+function[1]-1)
+			metadata = map[string]string{MetadataDefinition: name + "/" + strconv.Itoa(arity)}
+			if declared >= 0 {
+				start = declared
+			}
+		case attribute[1] == "spec" || attribute[1] == "doc":
+			if declared < 0 {
+				declared = offset
+			}
+			offset = end
+			continue
+		case header:
+			metadata = map[string]string{MetadataBlock: "header"}
+		default:
+			metadata = map[string]string{MetadataAttribute: attribute[1]}
+		}
+
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, start, spanEnd(spans), "%"),
+			end:      end,
+			metadata: metadata,
+		})
+		declared, offset = -1, end
+	}
+	return spans
+}
+
+// erlangFormEnd returns the offset after the full stop ending the Erlang form
+// at offset in code, skipping strings, quoted atoms, character literals, and
+// comments, or len(code) if it isn't ended.
+func erlangFormEnd(code string, offset int) int {
+	for i := offset; i < len(code); i++ {
+		switch code[i] {
+		case '%':
+			for i+1 < len(code) && code[i+1] != '\n' {
+				i++
+			}
+		case '$':
+			// A character literal, such as $. or $\n
+			if i+1 < len(code) && code[i+1] == '\\' {
+				i++
+			}
+			i++
+		case '"', '\'':
+			for quote := code[i]; i+1 < len(code) && code[i+1] != quote; i++ {
+				if code[i+1] == '\\' {
+					i++
+				}
+			}
+			i++
+		case '.':
+			if i+1 == len(code) || strings.ContainsRune(" \t\r\n%", rune(code[i+1])) {
+				return i + 1
+			}
+		}
+	}
+	return len(code)
+}
+
+// erlangArity returns the number of arguments of the Erlang clause whose
+// argument list opens at open in code.
+func erlangArity(code string, open int) int {
+	commas, depth, empty := 0, 0, true
+	for i := open; i < len(code); i++ {
+		if i > open && depth == 1 && !strings.ContainsRune(" \t\r\n)", rune(code[i])) {
+			empty = false
+		}
+		switch code[i] {
+		case '(', '[', '{':
+			depth++
+		case ')', ']', '}':
+			if depth--; depth == 0 {
+				if empty {
+					return 0
+				}
+				return commas + 1
+			}
+		case ',':
+			if depth == 1 {
+				commas++
+			}
+		case '$':
+			if i+1 < len(code) && code[i+1] == '\\' {
+				i++
+			}
+			i++
+		case '"', '\'':
+			for quote := code[i]; i+1 < len(code) && code[i+1] != quote; i++ {
+				if code[i+1] == '\\' {
+					i++
+				}
+			}
+			i++
+		}
+	}
+	return commas + 1
+}
@@ -0,0 +1,59 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Erlang(t *testing.T) {
+	code := `%%% A counter server
+-module(counter).
+-behaviour(gen_server).
+
+-export([start_link/0, init/1]).
+
+-record(state, {count = 0}).
+
+start_link() ->
+    gen_server:start_link(?MODULE, [], []).
+
+%% @doc Starts with a zero count.
+-spec init([]) -> {ok, #state{}}.
+init([]) ->
+    {ok, #state{}}.
+
+handle_call(inc, _From, #state{count = N} = S) ->
+    {reply, N + 1, S#state{count = N + 1}};
+handle_call({split, $.}, _From, S) ->
+    {reply, S#state.count, S}.
+
+-define(TIMEOUT, 5000).
+`
+	opts := []Option{WithLanguage(languages.Erlang)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	tests := []struct{ prefix, suffix, key, value string }{
+		{"%%% A counter server\n-module(counter).\n", "-record(state, {count = 0}).\n", MetadataBlock, "header"},
+		{"start_link() ->\n", "[], []).\n", MetadataDefinition, "start_link/0"},
+		{"%% @doc Starts with a zero count.\n-spec init([])", "{ok, #state{}}.\n", MetadataDefinition, "init/1"},
+		{"handle_call(inc, _From,", "{reply, S#state.count, S}.\n", MetadataDefinition, "handle_call/3"},
+		{"-define(TIMEOUT, 5000).", "", MetadataAttribute, "define"},
+	}
+	if len(chunks) != len(tests) {
+		t.Fatalf("expected %d chunks, got %+v", len(tests), chunks)
+	}
+	for i, tt := range tests {
+		chunk := chunks[i]
+		if !strings.HasPrefix(chunk.Content, tt.prefix) || !strings.HasSuffix(chunk.Content, tt.suffix) || chunk.Metadata[tt.key] != tt.value {
+			t.Errorf("expected chunk %d with %s %q from %q to %q, got %q with metadata %v", i, tt.key, tt.value, tt.prefix, tt.suffix, chunk.Content, chunk.Metadata)
+		}
+	}
+}
@@ -23,6 +23,30 @@ var (
 
 	// ErrNodeSize is returned when node size calculation fails.
 	ErrNodeSize = errors.New("failed to calculate node size")
+
+	// ErrInvalidEdit is returned when an edit falls outside the document it is applied to.
+	ErrInvalidEdit = errors.New("invalid edit")
+
+	// ErrInvalidConfig is returned when a configuration file or Config is invalid.
+	ErrInvalidConfig = errors.New("invalid config")
+
+	// ErrInvalidRecord is returned when a binary chunk record stream can't be decoded.
+	ErrInvalidRecord = errors.New("invalid chunk record")
+
+	// ErrInvalidEncoding is returned when strict transcoding meets bytes it can't decode.
+	ErrInvalidEncoding = errors.New("invalid text encoding")
+
+	// ErrBinaryFile is returned for binary files when WithBinaryFiles is set to BinaryError.
+	ErrBinaryFile = errors.New("binary file")
+
+	// ErrLimitExceeded is returned, wrapped in a LimitExceeded, when an input exceeds its Limits.
+	ErrLimitExceeded = errors.New("limit exceeded")
+
+	// ErrInvalidChunk is returned, wrapped in a ValidationError, when a chunk fails validation.
+	ErrInvalidChunk = errors.New("invalid chunk")
+
+	// ErrChunkNotFound is returned when a ZstdIndex holds no chunk with the ID looked up.
+	ErrChunkNotFound = errors.New("chunk not found")
 )
 
 // LanguageError wraps language-specific errors with the language name.
@@ -38,3 +62,114 @@ func (e *LanguageError) Error() string {
 func (e *LanguageError) Unwrap() error {
 	return e.Err
 }
+
+// ErrorKind classifies why a file couldn't be chunked.
+type ErrorKind int
+
+const (
+	ErrorOther    ErrorKind = iota // Anything else, such as a cancelled context or a failing event listener
+	ErrorIO                        // The file couldn't be read
+	ErrorEncoding                  // The file couldn't be transcoded, see ErrInvalidEncoding
+	ErrorParse                     // The file couldn't be parsed, see ErrParseFailed
+	ErrorSize                      // The size of a chunk couldn't be measured, see ErrNodeSize
+	ErrorConfig                    // The options can't chunk the file, such as when no language is detected
+	ErrorBinary                    // The file is binary, see ErrBinaryFile
+	ErrorLimit                     // The file exceeds its limits, see LimitExceeded
+)
+
+// String returns the lowercase name of the error kind.
+func (k ErrorKind) String() string {
+	switch k {
+	case ErrorIO:
+		return "io"
+	case ErrorEncoding:
+		return "encoding"
+	case ErrorParse:
+		return "parse"
+	case ErrorSize:
+		return "size"
+	case ErrorConfig:
+		return "config"
+	case ErrorBinary:
+		return "binary"
+	case ErrorLimit:
+		return "limit"
+	default:
+		return "other"
+	}
+}
+
+// FileError is the error of a file that couldn't be chunked. ChunkFile,
+// ChunkMapped, and pipelines return it, so one failing file can be reported
+// with its position and the run carries on with the rest.
+type FileError struct {
+	Path     string
+	Kind     ErrorKind
+	Language languages.LanguageName // Language the file was chunked as, if known
+	Line     int                    // Line of the error (1-based), or 0 if unknown
+	Offset   int                    // Byte offset of the error, if Line is known
+	Err      error
+}
+
+func (e *FileError) Error() string {
+	if e.Line > 0 {
+		return fmt.Sprintf("line %d: %v", e.Line, e.Err)
+	}
+	return e.Err.Error()
+}
+
+func (e *FileError) Unwrap() error {
+	return e.Err
+}
+
+// positionError is an error at a byte offset of the input.
+type positionError struct {
+	offset int
+	err    error
+}
+
+func (e *positionError) Error() string {
+	return e.err.Error()
+}
+
+func (e *positionError) Unwrap() error {
+	return e.err
+}
+
+// newFileError wraps err as the FileError of the file at path with content,
+// classifying it by the sentinel errors it wraps. It returns err as is if it
+// already is a FileError.
+func newFileError(path string, language languages.LanguageName, content string, err error) error {
+	var fileErr *FileError
+	if errors.As(err, &fileErr) {
+		return err
+	}
+
+	fileErr = &FileError{Path: path, Kind: errorKind(err), Language: language, Err: err}
+	var pos *positionError
+	if errors.As(err, &pos) && pos.offset <= len(content) {
+		fileErr.Offset = pos.offset
+		fileErr.Line = max(countLines(content[:pos.offset]), 1)
+	}
+	return fileErr
+}
+
+// errorKind classifies err by the sentinel errors it wraps.
+func errorKind(err error) ErrorKind {
+	switch {
+	case errors.Is(err, ErrInvalidEncoding):
+		return ErrorEncoding
+	case errors.Is(err, ErrParseFailed):
+		return ErrorParse
+	case errors.Is(err, ErrNodeSize):
+		return ErrorSize
+	case errors.Is(err, ErrBinaryFile):
+		return ErrorBinary
+	case errors.Is(err, ErrLimitExceeded):
+		return ErrorLimit
+	case errors.Is(err, ErrLanguageNotSpecified), errors.Is(err, ErrUnsupportedLanguage), errors.Is(err, ErrInvalidConfig):
+		return ErrorConfig
+	default:
+		return ErrorOther
+	}
+}
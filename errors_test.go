@@ -0,0 +1,112 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+)
+
+func TestFileError(t *testing.T) {
+	dir := t.TempDir()
+	legacy := filepath.Join(dir, "legacy.txt")
+	if err := os.WriteFile(legacy, []byte("ok\nbad \x81\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	tests := []struct {
+		name       string
+		path       string
+		opts       []Option
+		wantKind   ErrorKind
+		wantLine   int
+		wantOffset int
+		wantErr    error
+	}{
+		{"missing", filepath.Join(dir, "missing.go"), nil, ErrorIO, 0, 0, os.ErrNotExist},
+		{"undecodable", legacy, []Option{WithTranscoding(TranscodeStrict)}, ErrorEncoding, 2, 7, ErrInvalidEncoding},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			_, err := NewChunker().ChunkFile(tt.path, tt.opts...)
+			var fileErr *FileError
+			if !errors.As(err, &fileErr) {
+				t.Fatalf("expected a FileError, got %v", err)
+			}
+			if fileErr.Path != tt.path || fileErr.Kind != tt.wantKind {
+				t.Errorf("got %s error for %s, want %s for %s", fileErr.Kind, fileErr.Path, tt.wantKind, tt.path)
+			}
+			if fileErr.Line != tt.wantLine || fileErr.Offset != tt.wantOffset {
+				t.Errorf("position = line %d, byte %d, want line %d, byte %d", fileErr.Line, fileErr.Offset, tt.wantLine, tt.wantOffset)
+			}
+			if !errors.Is(err, tt.wantErr) {
+				t.Errorf("expected %v, got %v", tt.wantErr, err)
+			}
+		})
+	}
+}
+
+func TestFileError_Pipeline(t *testing.T) {
+	dir := t.TempDir()
+	files := map[string]string{"good.go": "package main\n", "bad.txt": "caf\x81\n"}
+	for name, content := range files {
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(content), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	pipeline := NewChunkPipeline(2, WithTranscoding(TranscodeStrict))
+	var failed, chunked []string
+	for result := range pipeline.RunDir(context.Background(), dir) {
+		var fileErr *FileError
+		switch {
+		case errors.As(result.Err, &fileErr):
+			if fileErr.Path != result.Path || fileErr.Kind != ErrorEncoding || fileErr.Line != 1 {
+				t.Errorf("unexpected error: %+v", fileErr)
+			}
+			failed = append(failed, filepath.Base(result.Path))
+		case result.Err != nil:
+			t.Errorf("expected a FileError, got %v", result.Err)
+		default:
+			chunked = append(chunked, filepath.Base(result.Path))
+		}
+	}
+
+	// One bad file doesn't fail the others
+	if fmt.Sprint(failed) != "[bad.txt]" || fmt.Sprint(chunked) != "[good.go]" {
+		t.Errorf("failed %v and chunked %v", failed, chunked)
+	}
+}
+
+func TestErrorKind(t *testing.T) {
+	tests := []struct {
+		err  error
+		want ErrorKind
+	}{
+		{fmt.Errorf("%w: tab", ErrInvalidEncoding), ErrorEncoding},
+		{&LanguageError{Language: "go", Err: ErrParseFailed}, ErrorParse},
+		{fmt.Errorf("%w: counter", ErrNodeSize), ErrorSize},
+		{ErrLanguageNotSpecified, ErrorConfig},
+		{&LimitExceeded{Limit: LimitDepth, Max: 10}, ErrorLimit},
+		{context.Canceled, ErrorOther},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.want.String(), func(t *testing.T) {
+			if got := errorKind(tt.err); got != tt.want {
+				t.Errorf("errorKind(%v) = %s, want %s", tt.err, got, tt.want)
+			}
+		})
+	}
+}
+
+func TestFileError_Error(t *testing.T) {
+	err := &FileError{Path: "a.txt", Kind: ErrorEncoding, Line: 3, Err: ErrInvalidEncoding}
+	if got := err.Error(); !strings.HasPrefix(got, "line 3: ") {
+		t.Errorf("Error() = %q, want the line first", got)
+	}
+}
@@ -0,0 +1,230 @@
+// Package eval measures how well chunking strategies keep the code relevant
+// to a query in one chunk, so that a change of strategy or size can be
+// justified with numbers rather than by looking at a few chunks:
+//
+//	queries, err := eval.ReadQueries(file)
+//	...
+//	results, err := (&eval.Evaluation{Dir: "testdata/repo", Queries: queries}).Run(ctx)
+//	...
+//	eval.WriteTable(os.Stdout, results)
+//
+// Every query names the spans of the files that answer it. A retriever
+// returning one chunk per hit can only return a span whole if a single chunk
+// holds it, so every strategy is scored by the share of spans that fall
+// within one chunk, how many chunks the others are split across, and how
+// much of the chunk holding a span is the span rather than unrelated code.
+package eval
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"slices"
+	"text/tabwriter"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+// DefaultStrategies are the chunker options of an Evaluation without
+// strategies: syntax-aware and line-based chunking at the default size.
+var DefaultStrategies = map[string][]chunkx.Option{
+	chunkx.StrategyAST:   nil,
+	chunkx.StrategyLines: {chunkx.WithLanguage(languages.Generic)},
+}
+
+// Span is a range of bytes of a file that is relevant to a query.
+type Span struct {
+	Path      string `json:"path"`       // Path of the file, relative to the directory of the Evaluation
+	StartByte int    `json:"start_byte"` // Offset of the first byte of the span
+	EndByte   int    `json:"end_byte"`   // Offset past the last byte of the span
+}
+
+// Query is a query and the spans that answer it.
+type Query struct {
+	Text     string `json:"query"`
+	Relevant []Span `json:"relevant"`
+}
+
+// Result is how well the chunks of a strategy preserve the relevant spans.
+type Result struct {
+	Strategy string
+	Chunks   int // Chunks of the files with relevant spans
+	Spans    int // Relevant spans of all queries
+
+	Preserved     float64 // Share of spans wholly within one chunk
+	Coverage      float64 // Mean share of a span within the chunk holding most of it
+	Fragmentation float64 // Mean number of chunks a span is split across, beyond the first
+	Focus         float64 // Mean share of the chunk holding most of a span that is the span
+}
+
+// Evaluation is a directory of source files and queries of spans of them.
+type Evaluation struct {
+	Dir        string                     // Directory of the source files
+	Queries    []Query                    // Queries with the spans relevant to them
+	Strategies map[string][]chunkx.Option // Chunker options by strategy name; defaults to DefaultStrategies
+	Chunker    chunkx.Chunker             // Defaults to chunkx.NewChunker()
+}
+
+// Run chunks every file with a relevant span with every strategy, and returns
+// the results of the strategies ordered by name. Spans of no bytes are
+// skipped, and spans beyond the end of their file are an error.
+func (e *Evaluation) Run(ctx context.Context) ([]Result, error) {
+	strategies := e.Strategies
+	if strategies == nil {
+		strategies = DefaultStrategies
+	}
+	chunker := e.Chunker
+	if chunker == nil {
+		chunker = chunkx.NewChunker()
+	}
+
+	var paths []string
+	for _, query := range e.Queries {
+		for _, span := range query.Relevant {
+			paths = append(paths, span.Path)
+		}
+	}
+	slices.Sort(paths)
+	paths = slices.Compact(paths)
+	sizes := make(map[string]int, len(paths))
+	for _, path := range paths {
+		info, err := os.Stat(filepath.Join(e.Dir, path))
+		if err != nil {
+			return nil, err
+		}
+		sizes[path] = int(info.Size())
+	}
+
+	names := make([]string, 0, len(strategies))
+	for name := range strategies {
+		names = append(names, name)
+	}
+	slices.Sort(names)
+
+	results := make([]Result, 0, len(names))
+	for _, name := range names {
+		files := make(map[string][]chunkx.Chunk, len(paths))
+		result := Result{Strategy: name}
+		for _, path := range paths {
+			chunks, err := chunkFile(ctx, chunker, filepath.Join(e.Dir, path), strategies[name])
+			if err != nil {
+				return nil, fmt.Errorf("strategy %s: %w", name, err)
+			}
+			files[path] = chunks
+			result.Chunks += len(chunks)
+		}
+
+		var coverage, fragmentation, focus float64
+		for _, query := range e.Queries {
+			for _, span := range query.Relevant {
+				if span.EndByte <= span.StartByte {
+					continue
+				}
+				m, err := measure(span, files[span.Path], sizes[span.Path])
+				if err != nil {
+					return nil, fmt.Errorf("query %q: %w", query.Text, err)
+				}
+				result.Spans++
+				if m.preserved {
+					result.Preserved++
+				}
+				coverage += m.coverage
+				fragmentation += float64(max(m.chunks-1, 0))
+				focus += m.focus
+			}
+		}
+		if result.Spans > 0 {
+			n := float64(result.Spans)
+			result.Preserved /= n
+			result.Coverage, result.Fragmentation, result.Focus = coverage/n, fragmentation/n, focus/n
+		}
+		results = append(results, result)
+	}
+	return results, nil
+}
+
+// chunkFile chunks the file at path, stopping early on cancellation if
+// chunker is a chunkx.ContextChunker.
+func chunkFile(ctx context.Context, chunker chunkx.Chunker, path string, opts []chunkx.Option) ([]chunkx.Chunk, error) {
+	if cc, ok := chunker.(chunkx.ContextChunker); ok {
+		return cc.ChunkFileContext(ctx, path, opts...)
+	}
+	if err := ctx.Err(); err != nil {
+		return nil, err
+	}
+	return chunker.ChunkFile(path, opts...)
+}
+
+// spanMeasure is how the chunks of a file hold a span.
+type spanMeasure struct {
+	preserved bool    // A chunk holds the whole span
+	chunks    int     // Chunks overlapping the span
+	coverage  float64 // Share of the span within the chunk overlapping it most
+	focus     float64 // Share of that chunk that is the span
+}
+
+// measure measures how chunks hold span, checking it against the size of its
+// file.
+func measure(span Span, chunks []chunkx.Chunk, size int) (spanMeasure, error) {
+	if span.StartByte < 0 || span.EndByte > size {
+		return spanMeasure{}, fmt.Errorf("span %s:%d-%d is beyond the %d bytes of the file", span.Path, span.StartByte, span.EndByte, size)
+	}
+
+	var m spanMeasure
+	best := 0
+	length := span.EndByte - span.StartByte
+	for _, chunk := range chunks {
+		overlap := min(chunk.EndByte, span.EndByte) - max(chunk.StartByte, span.StartByte)
+		if overlap <= 0 {
+			continue
+		}
+		m.chunks++
+		if overlap == length {
+			m.preserved = true
+		}
+		chunkLength := chunk.EndByte - chunk.StartByte
+		focus := float64(overlap) / float64(chunkLength)
+		// Of the chunks overlapping the span most, the smallest is the best answer
+		if overlap > best || overlap == best && focus > m.focus {
+			best, m.focus = overlap, focus
+		}
+	}
+	m.coverage = float64(best) / float64(length)
+	return m, nil
+}
+
+// ReadQueries reads queries from JSON Lines, one query per line:
+//
+//	{"query": "where are retries scheduled?", "relevant": [{"path": "retry.go", "start_byte": 120, "end_byte": 480}]}
+func ReadQueries(r io.Reader) ([]Query, error) {
+	var queries []Query
+	scanner := bufio.NewScanner(r)
+	scanner.Buffer(nil, 16<<20)
+	for line := 1; scanner.Scan(); line++ {
+		if len(scanner.Bytes()) == 0 {
+			continue
+		}
+		var query Query
+		if err := json.Unmarshal(scanner.Bytes(), &query); err != nil {
+			return nil, fmt.Errorf("line %d: %w", line, err)
+		}
+		queries = append(queries, query)
+	}
+	return queries, scanner.Err()
+}
+
+// WriteTable writes results to w as a table, one strategy per row.
+func WriteTable(w io.Writer, results []Result) error {
+	tw := tabwriter.NewWriter(w, 0, 0, 2, ' ', tabwriter.AlignRight)
+	fmt.Fprintln(tw, "STRATEGY\tCHUNKS\tSPANS\tPRESERVED\tCOVERAGE\tFRAGMENTATION\tFOCUS\t")
+	for _, r := range results {
+		fmt.Fprintf(tw, "%s\t%d\t%d\t%.1f%%\t%.1f%%\t%.2f\t%.1f%%\t\n",
+			r.Strategy, r.Chunks, r.Spans, 100*r.Preserved, 100*r.Coverage, r.Fragmentation, 100*r.Focus)
+	}
+	return tw.Flush()
+}
@@ -0,0 +1,95 @@
+package eval
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestEvaluation_Run(t *testing.T) {
+	dir := t.TempDir()
+	content := strings.Repeat("one two three four five six\n", 20)
+	if err := os.WriteFile(filepath.Join(dir, "notes.txt"), []byte(content), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	small := []chunkx.Option{chunkx.WithLanguage(languages.Generic), chunkx.WithMaxSize(30)}
+	chunks, err := chunkx.NewChunker().ChunkFile(filepath.Join(dir, "notes.txt"), small...)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(chunks) < 2 {
+		t.Fatalf("expected several chunks, got %d", len(chunks))
+	}
+
+	// One span is a chunk, the other straddles the first two chunks
+	first, second := chunks[0], chunks[1]
+	e := &Evaluation{
+		Dir: dir,
+		Queries: []Query{
+			{Text: "whole", Relevant: []Span{{Path: "notes.txt", StartByte: first.StartByte, EndByte: first.EndByte}}},
+			{Text: "split", Relevant: []Span{
+				{Path: "notes.txt", StartByte: first.EndByte - 2, EndByte: second.StartByte + 2},
+				{Path: "notes.txt", StartByte: 3, EndByte: 3},
+			}},
+		},
+		Strategies: map[string][]chunkx.Option{
+			"small": small,
+			"large": {chunkx.WithLanguage(languages.Generic), chunkx.WithMaxSize(10000)},
+		},
+	}
+	results, err := e.Run(context.Background())
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(results) != 2 || results[0].Strategy != "large" || results[1].Strategy != "small" {
+		t.Fatalf("expected the results of both strategies by name, got %+v", results)
+	}
+
+	large, got := results[0], results[1]
+	if large.Chunks != 1 || large.Preserved != 1 || large.Fragmentation != 0 || large.Focus >= 0.5 {
+		t.Errorf("expected one chunk preserving both spans with little focus, got %+v", large)
+	}
+	if got.Chunks != len(chunks) || got.Spans != 2 || got.Preserved != 0.5 || got.Fragmentation != 0.5 {
+		t.Errorf("expected one of two spans preserved, got %+v", got)
+	}
+	if got.Coverage <= 0.5 || got.Coverage >= 1 || got.Focus <= large.Focus {
+		t.Errorf("expected partial coverage and more focus than one chunk, got %+v", got)
+	}
+
+	var table strings.Builder
+	if err := WriteTable(&table, results); err != nil {
+		t.Fatal(err)
+	}
+	if !strings.Contains(table.String(), "PRESERVED") || !strings.Contains(table.String(), "50.0%") {
+		t.Errorf("unexpected table:\n%s", table.String())
+	}
+
+	e.Queries = []Query{{Text: "beyond", Relevant: []Span{{Path: "notes.txt", StartByte: 0, EndByte: len(content) + 1}}}}
+	if _, err := e.Run(context.Background()); err == nil {
+		t.Error("expected an error for a span beyond the end of its file")
+	}
+}
+
+func TestReadQueries(t *testing.T) {
+	input := `{"query": "retries", "relevant": [{"path": "retry.go", "start_byte": 120, "end_byte": 480}]}
+
+{"query": "config", "relevant": []}
+`
+	queries, err := ReadQueries(strings.NewReader(input))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(queries) != 2 || queries[0].Text != "retries" || queries[0].Relevant[0] != (Span{Path: "retry.go", StartByte: 120, EndByte: 480}) {
+		t.Errorf("unexpected queries %+v", queries)
+	}
+
+	if _, err := ReadQueries(strings.NewReader("{\"query\": 1}\n")); err == nil || !strings.Contains(err.Error(), "line 1") {
+		t.Errorf("expected an error naming the line, got %v", err)
+	}
+}
@@ -0,0 +1,121 @@
+package chunkx
+
+import (
+	"context"
+	"fmt"
+)
+
+// ChunkEventKind identifies what happened to the chunks of a file.
+type ChunkEventKind int
+
+const (
+	ChunkCreated ChunkEventKind = iota // The file was chunked for the first time
+	ChunkUpdated                       // The file changed and was chunked again
+	ChunkDeleted                       // The file was deleted or failed to chunk, and its chunks are gone
+)
+
+// String returns the lowercase name of the event kind.
+func (k ChunkEventKind) String() string {
+	switch k {
+	case ChunkCreated:
+		return "created"
+	case ChunkUpdated:
+		return "updated"
+	case ChunkDeleted:
+		return "deleted"
+	default:
+		return "unknown"
+	}
+}
+
+// ChunkEvent reports a change to the chunks of a file, so a downstream index
+// can upsert Chunks and delete the chunks with the IDs in Removed.
+type ChunkEvent struct {
+	Kind    ChunkEventKind
+	Path    string
+	Chunks  []Chunk  // Chunks to upsert; empty for ChunkDeleted
+	Removed []string // IDs of the chunks to delete, if known
+}
+
+// ChunkEventListener receives the chunk events of a Watcher or ChunkPipeline,
+// to push changes to downstream indexes as they happen instead of polling
+// output files. The webhook package implements it by posting events as JSON.
+//
+// Listeners are called from the pipeline's workers and must be safe for
+// concurrent use. Slow listeners slow down chunking.
+type ChunkEventListener interface {
+	HandleChunkEvent(ctx context.Context, event ChunkEvent) error
+}
+
+// WithEventListener sets a listener for chunk events.
+//
+// A Watcher reports the chunks added and removed by every change, so Chunks
+// holds only new chunks and Removed the IDs of the chunks they replace. If
+// the listener fails, the Watcher reports a WatchError for the file after
+// its event.
+//
+// A ChunkPipeline reports every file chunked successfully with all of its
+// chunks. Files are created unless WithIndex has a record of them, in which
+// case they are updated; the IDs of their earlier chunks are unknown, so
+// Removed is empty and consumers should replace every chunk of the path. If
+// the listener fails, the file's result carries the error. Deleted files are
+// reported only by a Watcher.
+func WithEventListener(listener ChunkEventListener) Option {
+	return func(c *config) {
+		c.events = listener
+	}
+}
+
+// watchChunkEvent converts a watch event into a chunk event. It reports false
+// for errors, which have no chunk event unless they drop the chunks of the
+// file.
+func watchChunkEvent(event WatchEvent) (ChunkEvent, bool) {
+	var kind ChunkEventKind
+	switch event.Kind {
+	case WatchAdded:
+		kind = ChunkCreated
+	case WatchUpdated:
+		kind = ChunkUpdated
+	case WatchRemoved:
+		kind = ChunkDeleted
+	case WatchError:
+		if event.Diff == nil || len(event.Diff.Removed) == 0 {
+			return ChunkEvent{}, false
+		}
+		kind = ChunkDeleted
+	default:
+		return ChunkEvent{}, false
+	}
+
+	// Chunks with the same content share an ID, so count them rather than
+	// assume each ID is added once
+	added := make(map[string]int, len(event.Diff.Added))
+	for _, id := range event.Diff.Added {
+		added[id]++
+	}
+	var chunks []Chunk
+	for _, chunk := range event.Chunks {
+		if id := chunk.ID(); added[id] > 0 {
+			added[id]--
+			chunks = append(chunks, chunk)
+		}
+	}
+
+	return ChunkEvent{Kind: kind, Path: event.Path, Chunks: chunks, Removed: event.Diff.Removed}, true
+}
+
+// notify passes the chunk event for a watch event to the listener, if there
+// is one. It returns a WatchError event to report when the listener fails.
+func notify(ctx context.Context, listener ChunkEventListener, event WatchEvent) (WatchEvent, bool) {
+	if listener == nil {
+		return WatchEvent{}, false
+	}
+	chunkEvent, ok := watchChunkEvent(event)
+	if !ok {
+		return WatchEvent{}, false
+	}
+	if err := listener.HandleChunkEvent(ctx, chunkEvent); err != nil {
+		return WatchEvent{Kind: WatchError, Path: event.Path, Err: fmt.Errorf("event listener: %w", err)}, true
+	}
+	return WatchEvent{}, false
+}
@@ -0,0 +1,144 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+)
+
+// chanListener sends chunk events to a channel, failing with err if set.
+type chanListener struct {
+	events chan ChunkEvent
+	err    error
+}
+
+func (l *chanListener) HandleChunkEvent(ctx context.Context, event ChunkEvent) error {
+	if l.err != nil {
+		return l.err
+	}
+	l.events <- event
+	return nil
+}
+
+// nextChunkEvent waits for the next chunk event.
+func nextChunkEvent(t *testing.T, events <-chan ChunkEvent) ChunkEvent {
+	t.Helper()
+
+	select {
+	case event := <-events:
+		return event
+	case <-time.After(5 * time.Second):
+		t.Fatal("timed out waiting for a chunk event")
+	}
+	return ChunkEvent{}
+}
+
+func TestWatcher_WithEventListener(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{"a.txt": "one\ntwo\n"})
+	path := filepath.Join(dir, "a.txt")
+
+	ctx, cancel := context.WithCancel(context.Background())
+	defer cancel()
+
+	listener := &chanListener{events: make(chan ChunkEvent, 8)}
+	watcher := NewWatcher(dir, WithPollInterval(10*time.Millisecond), WithMaxSize(1), WithEventListener(listener))
+	events := watcher.Run(ctx)
+
+	nextEvent(t, events)
+	created := nextChunkEvent(t, listener.events)
+	if created.Kind != ChunkCreated || created.Path != path || len(created.Chunks) != 2 || len(created.Removed) != 0 {
+		t.Fatalf("unexpected created event: %+v", created)
+	}
+
+	if err := os.WriteFile(path, []byte("one\nthree\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	nextEvent(t, events)
+	updated := nextChunkEvent(t, listener.events)
+	if updated.Kind != ChunkUpdated || len(updated.Chunks) != 1 || updated.Chunks[0].Content != "three\n" {
+		t.Fatalf("expected only the changed chunk to be upserted, got %+v", updated)
+	}
+	if len(updated.Removed) != 1 || updated.Removed[0] != created.Chunks[1].ID() {
+		t.Errorf("expected the replaced chunk to be removed, got %v", updated.Removed)
+	}
+
+	if err := os.Remove(path); err != nil {
+		t.Fatal(err)
+	}
+	nextEvent(t, events)
+	deleted := nextChunkEvent(t, listener.events)
+	if deleted.Kind != ChunkDeleted || len(deleted.Chunks) != 0 || len(deleted.Removed) != 2 {
+		t.Errorf("unexpected deleted event: %+v", deleted)
+	}
+
+	cancel()
+	for range events {
+	}
+}
+
+func TestWatcher_EventListenerError(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{"a.txt": "one\n"})
+
+	ctx, cancel := context.WithCancel(context.Background())
+	defer cancel()
+
+	listener := &chanListener{err: errors.New("unreachable")}
+	events := NewWatcher(dir, WithPollInterval(10*time.Millisecond), WithEventListener(listener)).Run(ctx)
+
+	if event := nextEvent(t, events); event.Kind != WatchAdded {
+		t.Fatalf("expected the file to be added first, got %s", event.Kind)
+	}
+	event := nextEvent(t, events)
+	if event.Kind != WatchError || !errors.Is(event.Err, listener.err) {
+		t.Errorf("expected the listener error, got %s %v", event.Kind, event.Err)
+	}
+
+	cancel()
+	for range events {
+	}
+}
+
+func TestChunkPipeline_WithEventListener(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{"a.txt": "one\n"})
+	path := filepath.Join(dir, "a.txt")
+	index := &memIndex{hashes: make(map[string]string)}
+	listener := &chanListener{events: make(chan ChunkEvent, 1)}
+
+	run := func() FileResult {
+		var last FileResult
+		for result := range NewChunkPipeline(1, WithIndex(index), WithEventListener(listener)).RunDir(context.Background(), dir) {
+			last = result
+		}
+		return last
+	}
+
+	if result := run(); result.Err != nil {
+		t.Fatalf("unexpected error: %v", result.Err)
+	}
+	if event := nextChunkEvent(t, listener.events); event.Kind != ChunkCreated || event.Path != path || len(event.Chunks) != 1 {
+		t.Errorf("unexpected event for a new file: %+v", event)
+	}
+
+	if err := os.WriteFile(path, []byte("two\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	run()
+	if event := nextChunkEvent(t, listener.events); event.Kind != ChunkUpdated {
+		t.Errorf("expected an indexed file to be updated, got %s", event.Kind)
+	}
+
+	// Undelivered events fail the file and leave it to the next run
+	if err := os.WriteFile(path, []byte("three\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	listener.err = errors.New("unreachable")
+	if result := run(); !errors.Is(result.Err, listener.err) {
+		t.Errorf("expected the listener error, got %v", result.Err)
+	}
+	if index.hashes[path] == ContentHash([]byte("three\n")) {
+		t.Error("expected a file whose event failed not to be recorded")
+	}
+}
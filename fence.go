@@ -0,0 +1,72 @@
+package chunkx
+
+import (
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// MetadataFenceLanguage is set to the language of the fenced code block that
+// WithFencedCode split a chunk from.
+const MetadataFenceLanguage = "fence_language"
+
+// fenceAliases maps language tags of fenced code blocks that are neither a
+// language name nor an extension to their language.
+var fenceAliases = map[string]languages.LanguageName{
+	"c++":       languages.CPP,
+	"c#":        languages.CSharp,
+	"f#":        languages.FSharp,
+	"golang":    languages.Go,
+	"shell":     languages.Bash,
+	"zsh":       languages.Bash,
+	"pwsh":      languages.PowerShell,
+	"terraform": languages.HCL,
+	"dosini":    languages.INI,
+	"docker":    languages.Dockerfile,
+	"proto":     languages.Protobuf,
+	"makefile":  languages.Make,
+	"octave":    languages.MATLAB,
+	"delphi":    languages.Pascal,
+	"racket":    languages.Scheme,
+	"bazel":     languages.Starlark,
+	"nasm":      languages.Assembly,
+	"gas":       languages.Assembly,
+}
+
+// WithFencedCode splits a fenced code block of Markdown, or of MDX, that
+// alone exceeds the maximum size with the splitter of the language its fence
+// names, such as "```python", rather than as Markdown, which can only split
+// it by line. The chunks of such a block are in its language, their first and
+// last also span its fences, and they record MetadataFenceLanguage. Blocks
+// that fit, and blocks without a supported language, are chunked as usual.
+// Fenced code is found in the Markdown syntax tree, so builds without cgo
+// ignore this option.
+func WithFencedCode() Option {
+	return func(c *config) {
+		c.fencedCode = true
+	}
+}
+
+// fenceLanguage returns the language an info string of a fenced code block
+// names, such as "python" or "{.py title=x}", or "" if it names none that is
+// supported other than Markdown itself.
+func fenceLanguage(info string) languages.LanguageName {
+	fields := strings.Fields(strings.Trim(strings.TrimSpace(info), "{}"))
+	if len(fields) == 0 {
+		return ""
+	}
+	tag := strings.ToLower(strings.TrimPrefix(fields[0], "."))
+	tag, _, _ = strings.Cut(tag, ",")
+
+	if lang, ok := fenceAliases[tag]; ok {
+		return lang
+	}
+	lang, ok := languages.GetLanguageConfig(languages.LanguageName(tag))
+	if !ok && tag != "" {
+		lang, _ = languages.DetectLanguage("." + tag)
+	}
+	if lang.Name == languages.Generic || lang.Name == languages.Markdown {
+		return ""
+	}
+	return lang.Name
+}
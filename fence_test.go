@@ -0,0 +1,31 @@
+package chunkx
+
+import (
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestFenceLanguage(t *testing.T) {
+	tests := []struct {
+		info string
+		want languages.LanguageName
+	}{
+		{"python", languages.Python},
+		{"py", languages.Python},
+		{"Go", languages.Go},
+		{"golang", languages.Go},
+		{"rust,no_run", languages.Rust},
+		{"{.js title=app.js}", languages.JavaScript},
+		{"ts {1,3}", languages.TypeScript},
+		{"md", ""},
+		{"text", ""},
+		{"", ""},
+	}
+
+	for _, tt := range tests {
+		if got := fenceLanguage(tt.info); got != tt.want {
+			t.Errorf("fenceLanguage(%q) = %q, want %q", tt.info, got, tt.want)
+		}
+	}
+}
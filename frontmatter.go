@@ -0,0 +1,113 @@
+package chunkx
+
+import (
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// MetadataFrontMatterPrefix prefixes the keys of front matter in the metadata
+// of chunks made with WithFrontMatter, such as "front_matter.title".
+const MetadataFrontMatterPrefix = "front_matter."
+
+// WithFrontMatter leaves the front matter of Markdown and HTML pages, such as
+// those of Jekyll and Hugo, out of the chunks and records it in their
+// metadata instead. Front matter is a YAML block between "---" lines or a TOML
+// block between "+++" lines at the start of the page, in the subset of YAML and
+// TOML LoadConfig reads.
+//
+// The first chunk records every key, under MetadataFrontMatterPrefix, and
+// the given keys are copied onto every chunk of the page, so each can be
+// filtered by them on its own. Lists are joined with ", ", and the keys of
+// nested tables are joined to their table's key with a dot. Front matter that
+// can't be parsed is chunked with the page.
+func WithFrontMatter(keys ...string) Option {
+	return func(c *config) {
+		c.frontMatter = true
+		c.frontMatterKeys = keys
+	}
+}
+
+// frontMatter returns the length of the front matter at the start of text,
+// including the blank lines after it, and its values, or 0 and nil if text in
+// lang starts with none.
+func frontMatter(text string, lang languages.LanguageName) (int, map[string]string) {
+	if lang != languages.Markdown && lang != languages.HTML {
+		return 0, nil
+	}
+
+	parse := parseYAMLConfig
+	delim := "---"
+	if strings.HasPrefix(text, "+++") {
+		parse = parseTOMLConfig
+		delim = "+++"
+	}
+	line, _, ok := strings.Cut(text, "\n")
+	if !ok || strings.TrimRight(line, " \t\r") != delim {
+		return 0, nil
+	}
+
+	bodyStart := len(line) + 1
+	for offset := bodyStart; offset < len(text); {
+		line, _, _ := strings.Cut(text[offset:], "\n")
+		next := min(offset+len(line)+1, len(text))
+		trimmed := strings.TrimRight(line, " \t\r")
+		if trimmed != delim && (delim != "---" || trimmed != "...") {
+			offset = next
+			continue
+		}
+
+		values, err := parse(text[bodyStart:offset])
+		if err != nil {
+			return 0, nil
+		}
+		fields := make(map[string]string, len(values))
+		flattenFrontMatter(fields, "", values)
+
+		end := next
+		for end < len(text) {
+			blank, _, _ := strings.Cut(text[end:], "\n")
+			if strings.TrimSpace(blank) != "" || end+len(blank) >= len(text) {
+				break
+			}
+			end += len(blank) + 1
+		}
+		return end, fields
+	}
+	return 0, nil
+}
+
+// flattenFrontMatter adds values to fields, prefixing their keys with prefix.
+func flattenFrontMatter(fields map[string]string, prefix string, values []configValue) {
+	for _, v := range values {
+		switch value := v.value.(type) {
+		case string:
+			fields[prefix+v.key] = value
+		case []string:
+			fields[prefix+v.key] = strings.Join(value, ", ")
+		case []configValue:
+			flattenFrontMatter(fields, prefix+v.key+".", value)
+		}
+	}
+}
+
+// addFrontMatter records the front matter fields in chunks: every field in
+// the first, and the fields of keys in all of them.
+func addFrontMatter(chunks []Chunk, fields map[string]string, keys []string) {
+	for i := range chunks {
+		if chunks[i].Metadata == nil {
+			chunks[i].Metadata = make(map[string]string, len(keys))
+		}
+		if i == 0 {
+			for key, value := range fields {
+				chunks[i].Metadata[MetadataFrontMatterPrefix+key] = value
+			}
+			continue
+		}
+		for _, key := range keys {
+			if value, ok := fields[key]; ok {
+				chunks[i].Metadata[MetadataFrontMatterPrefix+key] = value
+			}
+		}
+	}
+}
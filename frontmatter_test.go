@@ -0,0 +1,91 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestWithFrontMatter(t *testing.T) {
+	body := "# Heading\n\nThe first paragraph.\n\nThe second paragraph.\n"
+	tests := []struct {
+		name  string
+		front string
+		want  map[string]string
+	}{
+		{
+			"yaml", "---\ntitle: Hello: World\ntags:\n  - go\n  - rag\ndate: 2024-05-01\n---\n\n",
+			map[string]string{"title": "Hello: World", "tags": "go, rag", "date": "2024-05-01"},
+		},
+		{
+			"toml", "+++\ntitle = \"Hello\"\ntags = [\"go\", \"rag\"]\n\n[params]\nauthor = \"Ada\"\n+++\n",
+			map[string]string{"title": "Hello", "tags": "go, rag", "params.author": "Ada"},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			code := tt.front + body
+			opts := []Option{WithLanguage(languages.Markdown), WithMaxSize(3), WithFrontMatter("title")}
+			chunks, err := NewChunker().Chunk(code, opts...)
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if len(chunks) < 2 {
+				t.Fatalf("expected several chunks, got %+v", chunks)
+			}
+			if err := ValidateChunks(chunks, code, opts...); err != nil {
+				t.Errorf("unexpected validation error: %v", err)
+			}
+
+			if chunks[0].StartByte != len(tt.front) || !strings.HasPrefix(chunks[0].Content, "# Heading") {
+				t.Errorf("expected the first chunk to start after the front matter, got %+v", chunks[0])
+			}
+			for key, want := range tt.want {
+				if got := chunks[0].Metadata[MetadataFrontMatterPrefix+key]; got != want {
+					t.Errorf("expected %s of %q, got %q", key, want, got)
+				}
+			}
+			for _, chunk := range chunks[1:] {
+				if chunk.Metadata[MetadataFrontMatterPrefix+"title"] != tt.want["title"] {
+					t.Errorf("expected the title copied onto every chunk, got %v", chunk.Metadata)
+				}
+				if _, ok := chunk.Metadata[MetadataFrontMatterPrefix+"tags"]; ok {
+					t.Errorf("expected only the title copied, got %v", chunk.Metadata)
+				}
+			}
+		})
+	}
+}
+
+func TestWithFrontMatter_Kept(t *testing.T) {
+	tests := []struct {
+		name string
+		code string
+		lang languages.LanguageName
+	}{
+		{"unterminated", "---\ntitle: Hello\n\n# Heading\n", languages.Markdown},
+		{"unparseable", "---\n  indented: first\n---\n# Heading\n", languages.Markdown},
+		{"not a page", "---\ntitle: Hello\n---\n", languages.Generic},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			chunks, err := NewChunker().Chunk(tt.code, WithLanguage(tt.lang), WithFrontMatter())
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if len(chunks) == 0 || chunks[0].StartByte != 0 {
+				t.Errorf("expected the front matter chunked with the page, got %+v", chunks)
+			}
+			for _, chunk := range chunks {
+				for key := range chunk.Metadata {
+					if strings.HasPrefix(key, MetadataFrontMatterPrefix) {
+						t.Errorf("unexpected front matter metadata %s", key)
+					}
+				}
+			}
+		})
+	}
+}
@@ -0,0 +1,233 @@
+package chunkx
+
+import (
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"testing"
+	"unicode"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// fuzzLanguages are the languages the fuzz targets pick from.
+var fuzzLanguages = []languages.LanguageName{
+	languages.Generic, languages.Go, languages.Python, languages.JavaScript,
+	languages.TypeScript, languages.Rust, languages.Java, languages.CPP,
+	languages.Markdown, languages.HTML, languages.YAML, languages.Bash,
+}
+
+// fuzzCounters are the token counters the fuzz targets pick from.
+var fuzzCounters = []TokenCounter{&SimpleTokenCounter{}, &ByteCounter{}, &LineCounter{}}
+
+// addFuzzSeeds seeds f with the example sources and inputs with unusual line
+// endings and encodings.
+func addFuzzSeeds(f *testing.F) {
+	paths, _ := filepath.Glob(filepath.Join("testdata", "sources", "*"))
+	for _, path := range paths {
+		content, err := os.ReadFile(path)
+		if err != nil {
+			continue
+		}
+		lang, _ := languages.DetectLanguage(path)
+		if i := slices.Index(fuzzLanguages, lang.Name); i >= 0 {
+			f.Add(content, uint16(50), uint8(0), uint8(i), uint8(0), false)
+		}
+	}
+	f.Add([]byte(""), uint16(1), uint8(0), uint8(0), uint8(0), false)
+	f.Add([]byte("\uFEFFone\rtwo\r\nthree\n"), uint16(1), uint8(0), uint8(0), uint8(1), true)
+	f.Add([]byte("package main\r\rfunc main() {}\r"), uint16(2), uint8(0), uint8(1), uint8(2), false)
+	f.Add([]byte("def f(:\n  return )\n\nclass"), uint16(3), uint8(20), uint8(2), uint8(0), false)
+	f.Add([]byte("caf\xe9\x00\xff\n\n\n"), uint16(1), uint8(50), uint8(0), uint8(1), true)
+}
+
+// FuzzChunk feeds arbitrary code and settings through the chunker and checks
+// the invariants every chunking must keep.
+func FuzzChunk(f *testing.F) {
+	addFuzzSeeds(f)
+
+	f.Fuzz(func(t *testing.T, code []byte, maxSize uint16, overlap uint8, lang uint8, counter uint8, normalize bool) {
+		cfg := fuzzConfig(maxSize, overlap, lang, counter, normalize)
+		opts := []Option{
+			WithMaxSize(cfg.maxSize),
+			WithOverlap(cfg.overlap),
+			WithLanguage(cfg.language),
+			WithTokenCounter(cfg.tokenCounter),
+		}
+		if normalize {
+			opts = append(opts, WithNormalizedNewlines())
+		}
+
+		chunks, err := NewChunker().Chunk(string(code), opts...)
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		checkChunks(t, string(code), chunks, cfg)
+	})
+}
+
+// FuzzChunkFile feeds arbitrary file content through transcoding and the
+// binary and generated file policies.
+func FuzzChunkFile(f *testing.F) {
+	addFuzzSeeds(f)
+
+	f.Fuzz(func(t *testing.T, content []byte, maxSize uint16, overlap uint8, lang uint8, policy uint8, strict bool) {
+		mode := TranscodeLossy
+		if strict {
+			mode = TranscodeStrict
+		}
+		opts := []Option{
+			WithMaxSize(max(int(maxSize), 1)),
+			WithOverlap(float64(overlap % (MaxOverlap + 1))),
+			WithLanguage(fuzzLanguages[int(lang)%len(fuzzLanguages)]),
+			WithTranscoding(mode),
+			WithBinaryFiles(BinaryPolicy(policy % 5)),
+			WithGeneratedFiles(GeneratedPolicy(policy / 5 % 3)),
+		}
+
+		chunks, err := NewChunker().(*castChunker).chunkFileContent(t.Context(), "fuzz", string(content), opts)
+		if err != nil {
+			if !strict && BinaryPolicy(policy%5) != BinaryError {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			return
+		}
+
+		for i, chunk := range chunks {
+			if chunk.StartByte < 0 || chunk.StartByte > chunk.EndByte || chunk.EndByte > len(content) {
+				t.Fatalf("chunk %d has offsets [%d, %d) outside %d bytes", i, chunk.StartByte, chunk.EndByte, len(content))
+			}
+			if i > 0 && chunk.StartByte < chunks[i-1].StartByte {
+				t.Fatalf("chunk %d starts at %d, before chunk %d at %d", i, chunk.StartByte, i-1, chunks[i-1].StartByte)
+			}
+		}
+	})
+}
+
+// FuzzParseConfig checks that no configuration file makes the parsers or
+// option conversion panic.
+func FuzzParseConfig(f *testing.F) {
+	f.Add([]byte("strategy = \"ast\"\nmax_size = 500\n[languages]\n\"*.tpl\" = \"html\"\n"), uint8(0))
+	f.Add([]byte("ignore:\n  - vendor/**\nlanguages:\n  \"*.x\": go\n"), uint8(1))
+	f.Add([]byte(`{"overlap": 10, "languages": {"*.x": "go"}}`), uint8(2))
+
+	formats := []string{"toml", "yaml", "json"}
+	f.Fuzz(func(t *testing.T, data []byte, format uint8) {
+		cfg, err := ParseConfig(data, formats[int(format)%len(formats)])
+		if err != nil {
+			return
+		}
+		_, _ = cfg.Options()
+	})
+}
+
+// fuzzConfig builds the config a fuzz target's settings describe.
+func fuzzConfig(maxSize uint16, overlap, lang, counter uint8, normalize bool) *config {
+	cfg := newDefaultConfig()
+	cfg.maxSize = max(int(maxSize), 1)
+	cfg.overlap = float64(overlap % (MaxOverlap + 1))
+	cfg.language = fuzzLanguages[int(lang)%len(fuzzLanguages)]
+	cfg.tokenCounter = fuzzCounters[int(counter)%len(fuzzCounters)]
+	cfg.normalizeNewlines = normalize
+	return cfg
+}
+
+// checkChunks checks the invariants of chunks of code:
+//
+//   - offsets lie within the code, and chunks are in order
+//   - line numbers are those of the offsets, counting "\n", "\r\n", and a
+//     lone "\r" as line endings
+//   - each chunk's content is its bytes of the code
+//   - without overlap, chunks don't overlap, and the bytes between chunks
+//     are only whitespace or a leading byte order mark
+//   - with overlap, consecutive chunks leave no gap and Reconstruct joins
+//     them back into the code they cover
+//   - without overlap, line-based chunks of more than one line fit the
+//     maximum size
+func checkChunks(t *testing.T, code string, chunks []Chunk, cfg *config) {
+	t.Helper()
+
+	prevEnd := 0
+	for i, chunk := range chunks {
+		if chunk.StartByte < 0 || chunk.StartByte > chunk.EndByte || chunk.EndByte > len(code) {
+			t.Fatalf("chunk %d has offsets [%d, %d) outside %d bytes", i, chunk.StartByte, chunk.EndByte, len(code))
+		}
+		if i > 0 && chunk.StartByte < chunks[i-1].StartByte {
+			t.Fatalf("chunk %d starts at %d, before chunk %d at %d", i, chunk.StartByte, i-1, chunks[i-1].StartByte)
+		}
+		if want := lineAt(code, chunk.StartByte); chunk.StartLine != want {
+			t.Fatalf("chunk %d starts on line %d, want %d", i, chunk.StartLine, want)
+		}
+		if chunk.EndLine < chunk.StartLine {
+			t.Fatalf("chunk %d ends on line %d, before it starts on %d", i, chunk.EndLine, chunk.StartLine)
+		}
+
+		want := code[chunk.StartByte:chunk.EndByte]
+		if cfg.normalizeNewlines {
+			want = normalizeNewlines(want)
+		}
+		if chunk.Content != want {
+			t.Fatalf("chunk %d content %q isn't its bytes %q", i, chunk.Content, want)
+		}
+		if want := lineAt(code, chunk.EndByte); chunk.EndLine != want {
+			t.Fatalf("chunk %d ends on line %d, want %d", i, chunk.EndLine, want)
+		}
+
+		if cfg.overlap > 0 {
+			if i > 0 && chunk.StartByte > chunks[i-1].EndByte {
+				t.Fatalf("chunk %d starts at %d, after the previous chunk ending at %d", i, chunk.StartByte, chunks[i-1].EndByte)
+			}
+			continue
+		}
+
+		if chunk.StartByte < prevEnd {
+			t.Fatalf("chunk %d starts at %d, inside the previous chunk ending at %d", i, chunk.StartByte, prevEnd)
+		}
+		gap := code[prevEnd:chunk.StartByte]
+		if prevEnd == 0 {
+			gap = strings.TrimPrefix(gap, utf8BOM)
+		}
+		if strings.TrimFunc(gap, unicode.IsSpace) != "" {
+			t.Fatalf("bytes %q before chunk %d are in no chunk", gap, i)
+		}
+		prevEnd = chunk.EndByte
+
+		if _, words := cfg.tokenCounter.(*SimpleTokenCounter); words && cfg.language == languages.Generic && countLines(chunk.Content) > 1 {
+			if size, _ := cfg.tokenCounter.CountTokens(chunk.Content); size > cfg.maxSize {
+				t.Fatalf("chunk %d of %d lines has size %d, over the maximum %d", i, countLines(chunk.Content), size, cfg.maxSize)
+			}
+		}
+	}
+
+	if cfg.overlap > 0 {
+		if len(chunks) == 0 {
+			return
+		}
+		want := code[chunks[0].StartByte:chunks[len(chunks)-1].EndByte]
+		if cfg.normalizeNewlines {
+			want = normalizeNewlines(want)
+		}
+		if got := Reconstruct(chunks); got != want {
+			t.Fatalf("chunks reconstruct %q, want %q", got, want)
+		}
+		return
+	}
+	tail := code[prevEnd:]
+	if prevEnd == 0 {
+		tail = strings.TrimPrefix(tail, utf8BOM)
+	}
+	if strings.TrimFunc(tail, unicode.IsSpace) != "" {
+		t.Fatalf("trailing bytes %q are in no chunk", tail)
+	}
+}
+
+// lineAt returns the line of the byte at offset in code. An offset between
+// "\r" and "\n" is still on the line they end.
+func lineAt(code string, offset int) int {
+	prefix := code[:offset]
+	if strings.HasSuffix(prefix, "\r") && strings.HasPrefix(code[offset:], "\n") {
+		prefix = prefix[:len(prefix)-1]
+	}
+	return max(countLines(prefix), 1)
+}
@@ -0,0 +1,69 @@
+package chunkx
+
+import (
+	"path"
+	"path/filepath"
+	"strings"
+)
+
+// matchGlob reports whether name matches the glob pattern.
+//
+// Patterns without a slash match the file name only, so "*.min.js" matches at
+// any depth. Patterns with a slash match any trailing part of the path, and a
+// "**" segment matches any number of segments, so "vendor/**" matches every
+// path inside a vendor directory. Other syntax follows path.Match.
+func matchGlob(pattern, name string) bool {
+	name = filepath.ToSlash(name)
+
+	if !strings.Contains(pattern, "/") {
+		ok, _ := path.Match(pattern, path.Base(name))
+		return ok
+	}
+
+	patternSegments := strings.Split(strings.Trim(pattern, "/"), "/")
+	segments := strings.Split(strings.Trim(name, "/"), "/")
+	for i := range segments {
+		if matchSegments(patternSegments, segments[i:]) {
+			return true
+		}
+	}
+	return false
+}
+
+// matchAnyGlob reports whether name matches any of the patterns.
+func matchAnyGlob(patterns []string, name string) bool {
+	for _, pattern := range patterns {
+		if matchGlob(pattern, name) {
+			return true
+		}
+	}
+	return false
+}
+
+// matchSegments matches path segments against pattern segments, where a "**"
+// segment matches zero or more path segments.
+func matchSegments(pattern, segments []string) bool {
+	for len(pattern) > 0 {
+		if pattern[0] == "**" {
+			pattern = pattern[1:]
+			if len(pattern) == 0 {
+				return true
+			}
+			for i := range len(segments) + 1 {
+				if matchSegments(pattern, segments[i:]) {
+					return true
+				}
+			}
+			return false
+		}
+
+		if len(segments) == 0 {
+			return false
+		}
+		if ok, _ := path.Match(pattern[0], segments[0]); !ok {
+			return false
+		}
+		pattern, segments = pattern[1:], segments[1:]
+	}
+	return len(segments) == 0
+}
@@ -0,0 +1,31 @@
+package chunkx
+
+import "testing"
+
+func TestMatchGlob(t *testing.T) {
+	tests := []struct {
+		pattern string
+		name    string
+		want    bool
+	}{
+		{"*.go", "main.go", true},
+		{"*.go", "cmd/chunkx/main.go", true},
+		{"*.go", "main.py", false},
+		{"Jenkinsfile", "ci/Jenkinsfile", true},
+		{"vendor/**", "vendor/lib/a.go", true},
+		{"vendor/**", "src/vendor/lib/a.go", true},
+		{"vendor/**", "vendor", true},
+		{"vendor/**", "src/vendored/a.go", false},
+		{"docs/*.md", "docs/intro.md", true},
+		{"docs/*.md", "docs/guide/intro.md", false},
+		{"src/**/*.test.js", "src/a/b/c.test.js", true},
+		{"src/**/*.test.js", "src/c.test.js", true},
+		{"src/**/*.test.js", "src/c.js", false},
+	}
+
+	for _, tt := range tests {
+		if got := matchGlob(tt.pattern, tt.name); got != tt.want {
+			t.Errorf("matchGlob(%q, %q) = %v, want %v", tt.pattern, tt.name, got, tt.want)
+		}
+	}
+}
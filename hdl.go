@@ -0,0 +1,358 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+// MetadataProcess is the metadata key of the label, or the head if it has
+// none, of the VHDL process or Verilog always, initial, or final block
+// holding the chunk, such as "always_ff @(posedge clk)".
+const MetadataProcess = "process"
+
+var (
+	// vhdlUnit matches the line of VHDL starting a design unit, such as
+	// "entity counter is" or "architecture rtl of counter is", and its kind,
+	// name, and entity.
+	vhdlUnit = regexp.MustCompile(`(?i)^[ \t]*(entity|architecture|package[ \t]+body|package|configuration|context)[ \t]+(\w+)(?:[ \t]+of[ \t]+(\w+))?[ \t]+is\b`)
+	// vhdlContextClause matches a library, use, or context clause of VHDL,
+	// which belongs to the design unit after it.
+	vhdlContextClause = regexp.MustCompile(`(?i)^(?:library|use|context)\b[^;]*;[ \t]*(?:--.*)?$`)
+	// vhdlSubprogram matches the line of VHDL starting a function or
+	// procedure, and its name.
+	vhdlSubprogram = regexp.MustCompile(`(?i)^[ \t]*(?:(?:pure|impure)[ \t]+)?(?:function|procedure)[ \t]+("[^"\n]*"|\w+)`)
+	// vhdlProcess matches the line of VHDL starting a process, and its label.
+	vhdlProcess = regexp.MustCompile(`(?i)^[ \t]*(?:(\w+)[ \t]*:[ \t]*)?(?:postponed[ \t]+)?process\b`)
+	// vhdlProcessEnd matches the line of VHDL ending a process.
+	vhdlProcessEnd = regexp.MustCompile(`(?i)^[ \t]*end[ \t]+(?:postponed[ \t]+)?process\b`)
+	// vhdlEnd matches a VHDL statement starting with "end", and the word
+	// after it, if any.
+	vhdlEnd = regexp.MustCompile(`(?i)^[ \t]*end\b[ \t]*(\w*)`)
+	// verilogUnit matches the line of Verilog or SystemVerilog starting a
+	// module, interface, package, class, or other unit of design, and its
+	// kind and name.
+	verilogUnit = regexp.MustCompile(`^[ \t]*(?:(?:virtual|static|automatic)[ \t]+)*(module|macromodule|interface|program|package|class|primitive|checker|config)\b[ \t]+(?:(?:automatic|static)[ \t]+)?([A-Za-z_]\w*)`)
+	// verilogUnitEnd matches the keywords ending units of Verilog, such as
+	// "endmodule", and the kind they end.
+	verilogUnitEnd = regexp.MustCompile(`\bend(module|interface|program|package|class|primitive|checker|config)\b`)
+	// verilogProcess matches the line of Verilog starting an always,
+	// initial, or final block, its label, and its head.
+	verilogProcess = regexp.MustCompile(`^[ \t]*(?:([A-Za-z_]\w*)[ \t]*:[ \t]*)?((?:always(?:_ff|_comb|_latch)?|initial|final)\b.*)$`)
+	// verilogRoutine matches the line of Verilog starting a function or task,
+	// its modifiers, and its kind.
+	verilogRoutine = regexp.MustCompile(`^[ \t]*((?:(?:virtual|static|automatic|protected|local|pure|extern)[ \t]+)*)(function|task)\b`)
+	// verilogBlockLabel matches the label of a begin-end block of Verilog.
+	verilogBlockLabel = regexp.MustCompile(`\bbegin[ \t]*:[ \t]*([A-Za-z_]\w*)`)
+	// verilogBlockWord matches the keywords of Verilog opening and closing
+	// blocks of statements.
+	verilogBlockWord = regexp.MustCompile(`\b(?:begin|end|fork|join(?:_any|_none)?)\b`)
+)
+
+// vhdlEndKeywords are the words after "end" that end a statement of VHDL
+// rather than a design unit or subprogram.
+var vhdlEndKeywords = map[string]bool{
+	"if":        true,
+	"loop":      true,
+	"case":      true,
+	"process":   true,
+	"postponed": true,
+	"generate":  true,
+	"component": true,
+	"record":    true,
+	"block":     true,
+	"units":     true,
+	"protected": true,
+	"for":       true,
+}
+
+// vhdlSpans returns the design units of the VHDL code, such as its entities,
+// architectures, and packages, each with the comment lines and context
+// clauses right above it, recording their kind and name as MetadataBlock,
+// such as "architecture rtl of counter".
+func vhdlSpans(code string) []textSpan {
+	var spans []textSpan
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		m := vhdlUnit.FindStringSubmatch(line)
+		end := -1
+		if m != nil {
+			end = vhdlUnitEnd(code, next)
+		}
+		if end < 0 {
+			offset = next
+			continue
+		}
+
+		head := strings.ToLower(strings.Join(strings.Fields(m[1]), " ")) + " " + m[2]
+		if m[3] != "" {
+			head += " of " + m[3]
+		}
+		spans = append(spans, textSpan{
+			start:    contextClauses(code, offset, spanEnd(spans), vhdlContextClause),
+			end:      end,
+			metadata: map[string]string{MetadataBlock: head},
+		})
+		offset = end
+	}
+	return spans
+}
+
+// vhdlNested returns the processes, recording MetadataProcess, and the
+// subprogram bodies, recording MetadataDefinition, of the VHDL design unit.
+func vhdlNested(unit string) []textSpan {
+	offset := 0
+	for offset < len(unit) {
+		line, next := codeLine(unit, offset)
+		offset = next
+		if vhdlUnit.MatchString(line) {
+			break
+		}
+	}
+
+	var spans []textSpan
+	for offset < len(unit) {
+		line, next := codeLine(unit, offset)
+		end := -1
+		var metadata map[string]string
+		if m := vhdlProcess.FindStringSubmatch(line); m != nil {
+			for scan := next; scan < len(unit); {
+				line, following := codeLine(unit, scan)
+				if vhdlProcessEnd.MatchString(line) {
+					end = following
+					break
+				}
+				scan = following
+			}
+			metadata = map[string]string{MetadataProcess: m[1]}
+			if m[1] == "" {
+				metadata[MetadataProcess] = "process"
+			}
+		} else if m := vhdlSubprogram.FindStringSubmatch(line); m != nil && vhdlIsBody(unit, offset) {
+			end = vhdlUnitEnd(unit, next)
+			metadata = map[string]string{MetadataDefinition: m[1]}
+		}
+		if end < 0 {
+			offset = next
+			continue
+		}
+
+		spans = append(spans, textSpan{
+			start:    leadingComments(unit, offset, spanEnd(spans), "--"),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// vhdlUnitEnd returns the offset after the end statement of the VHDL design
+// unit or subprogram whose lines after the first start at offset in code,
+// skipping the subprogram bodies within it, or -1 if it isn't ended.
+func vhdlUnitEnd(code string, offset int) int {
+	depth := 1
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		text, _, _ := strings.Cut(line, "--")
+		if vhdlSubprogram.MatchString(text) && vhdlIsBody(code, offset) {
+			depth++
+		} else if m := vhdlEnd.FindStringSubmatch(text); m != nil && !vhdlEndKeywords[strings.ToLower(m[1])] {
+			if depth--; depth == 0 {
+				return next
+			}
+		}
+		offset = next
+	}
+	return -1
+}
+
+// vhdlIsBody reports whether the VHDL subprogram starting at offset in code
+// has a body, which its declaration ends with "is" rather than ";".
+func vhdlIsBody(code string, offset int) bool {
+	depth := 0
+	for i := offset; i < len(code); i++ {
+		switch c := code[i]; {
+		case c == '(':
+			depth++
+		case c == ')':
+			depth--
+		case c == ';' && depth == 0:
+			return false
+		case c == '"':
+			for i++; i < len(code) && code[i] != '"' && code[i] != '\n'; i++ {
+			}
+		case strings.HasPrefix(code[i:], "--"):
+			for i+1 < len(code) && code[i+1] != '\n' {
+				i++
+			}
+		case depth == 0 && i > 0 && !isWordByte(code[i-1]) && i+2 <= len(code) && strings.EqualFold(code[i:i+2], "is") &&
+			(i+2 == len(code) || !isWordByte(code[i+2])):
+			return true
+		}
+	}
+	return false
+}
+
+// contextClauses returns the start of the "--" comment lines and the context
+// clauses matching clause right above the line at offset of the VHDL or Ada
+// code, along with the blank lines between them, not before from, or offset
+// if there are none.
+func contextClauses(code string, offset, from int, clause *regexp.Regexp) int {
+	start := offset
+	for scan := offset; scan > from; {
+		prev := strings.LastIndexByte(code[:scan-1], '\n') + 1
+		line := strings.TrimSpace(code[prev:scan])
+		if prev < from || line != "" && !strings.HasPrefix(line, "--") && !clause.MatchString(line) {
+			break
+		}
+		if line != "" {
+			start = prev
+		}
+		scan = prev
+	}
+	return start
+}
+
+// verilogSpans returns the modules, interfaces, packages, classes, and other
+// units of the Verilog or SystemVerilog code, each with the comment lines
+// right above it, recording their kind and name as MetadataBlock, such as
+// "module counter".
+func verilogSpans(code string) []textSpan {
+	var spans []textSpan
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		m := verilogUnit.FindStringSubmatch(line)
+		end := -1
+		if m != nil {
+			end = verilogEnd(code, next, m[1])
+		}
+		if end < 0 {
+			offset = next
+			continue
+		}
+
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "//", "/*", "*"),
+			end:      end,
+			metadata: map[string]string{MetadataBlock: m[1] + " " + m[2]},
+		})
+		offset = end
+	}
+	return spans
+}
+
+// verilogNested returns the always, initial, and final blocks of the Verilog
+// unit, recording MetadataProcess, and its functions and tasks, recording
+// MetadataDefinition.
+func verilogNested(unit string) []textSpan {
+	offset := 0
+	for offset < len(unit) {
+		line, next := codeLine(unit, offset)
+		offset = next
+		if verilogUnit.MatchString(line) {
+			break
+		}
+	}
+
+	var spans []textSpan
+	for offset < len(unit) {
+		line, next := codeLine(unit, offset)
+		text, _, _ := strings.Cut(line, "//")
+		end := -1
+		var metadata map[string]string
+		if m := verilogProcess.FindStringSubmatch(text); m != nil {
+			end = verilogProcessEnd(unit, offset)
+			head, _, _ := strings.Cut(m[2], "begin")
+			process := strings.Join(strings.Fields(head), " ")
+			if m[1] != "" {
+				process = m[1]
+			} else if label := verilogBlockLabel.FindStringSubmatch(text); label != nil {
+				process = label[1]
+			}
+			metadata = map[string]string{MetadataProcess: process}
+		} else if m := verilogRoutine.FindStringSubmatch(text); m != nil && !strings.Contains(m[1], "extern") && !strings.Contains(m[1], "pure") {
+			end = verilogEnd(unit, next, m[2])
+			// The name comes last before the ports, after the return type
+			signature, _, _ := strings.Cut(text[len(m[0]):], "(")
+			signature, _, _ = strings.Cut(signature, ";")
+			if fields := strings.Fields(signature); len(fields) > 0 {
+				metadata = map[string]string{MetadataDefinition: fields[len(fields)-1]}
+			} else {
+				end = -1
+			}
+		}
+		if end < 0 {
+			offset = next
+			continue
+		}
+
+		spans = append(spans, textSpan{
+			start:    leadingComments(unit, offset, spanEnd(spans), "//", "/*", "*"),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// verilogEnd returns the offset after the line ending the Verilog unit,
+// function, or task of the kind whose lines after the first start at offset
+// in code, such as the line of "endmodule", or -1 if it isn't ended.
+func verilogEnd(code string, offset int, kind string) int {
+	if kind == "macromodule" {
+		kind = "module"
+	}
+	depth := 1
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		text, _, _ := strings.Cut(line, "//")
+		if m := verilogUnit.FindStringSubmatch(text); m != nil && m[1] == kind {
+			depth++
+		} else if m := verilogRoutine.FindStringSubmatch(text); m != nil && m[2] == kind {
+			depth++
+		}
+		for _, m := range verilogUnitEnd.FindAllStringSubmatch(text, -1) {
+			if m[1] == kind {
+				depth--
+			}
+		}
+		if (kind == "function" || kind == "task") && strings.Contains(text, "end"+kind) {
+			depth--
+		}
+		if depth <= 0 {
+			return next
+		}
+		offset = next
+	}
+	return -1
+}
+
+// verilogProcessEnd returns the offset after the last line of the always,
+// initial, or final block of Verilog starting on the line at offset in code:
+// the line of the end matching its begin, or of the semicolon ending its
+// statement if it has no begin.
+func verilogProcessEnd(code string, offset int) int {
+	depth, opened := 0, false
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		text, _, _ := strings.Cut(line, "//")
+		for _, word := range verilogBlockWord.FindAllString(text, -1) {
+			if word == "begin" || word == "fork" {
+				depth, opened = depth+1, true
+			} else {
+				depth--
+			}
+		}
+		if opened && depth <= 0 || !opened && strings.Contains(text, ";") {
+			return next
+		}
+		offset = next
+	}
+	return len(code)
+}
+
+// isWordByte reports whether b can be part of an identifier.
+func isWordByte(b byte) bool {
+	return b == '_' || '0' <= b && b <= '9' || 'a' <= b && b <= 'z' || 'A' <= b && b <= 'Z'
+}
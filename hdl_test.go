@@ -0,0 +1,69 @@
+package chunkx
+
+import (
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_VHDL(t *testing.T) {
+	code := `library ieee;
+use ieee.std_logic_1164.all;
+
+-- A 4-bit counter
+entity counter is
+  port (clk, rst : in std_logic; q : out std_logic_vector(3 downto 0));
+end entity counter;
+
+architecture rtl of counter is
+  function inc(v : unsigned) return unsigned is
+  begin
+    return v + 1;
+  end function;
+begin
+  tick : process (clk)
+  begin
+    if rising_edge(clk) then
+      if rst = '1' then
+        count <= (others => '0');
+      end if;
+    end if;
+  end process tick;
+end architecture rtl;
+`
+	testChunkMetadata(t, code, languages.VHDL, 25, []struct{ prefix, key, value string }{
+		{"library ieee;\n", MetadataBlock, "entity counter"},
+		{"  function inc(v : unsigned) return unsigned is\n", MetadataDefinition, "inc"},
+		{"  function inc(v : unsigned) return unsigned is\n", MetadataBlock, "architecture rtl of counter"},
+		{"  tick : process (clk)\n", MetadataProcess, "tick"},
+	})
+}
+
+func TestChunker_Verilog(t *testing.T) {
+	code := `// Counts clock edges
+module counter #(parameter W = 4) (
+  input  logic         clk, rst,
+  output logic [W-1:0] q
+);
+  function automatic logic [W-1:0] next(input logic [W-1:0] v);
+    return v + 1'b1;
+  endfunction
+
+  always_ff @(posedge clk) begin
+    if (rst) q <= '0;
+    else q <= next(q);
+  end
+
+  initial begin : init
+    q = '0;
+  end
+endmodule
+`
+	testChunkMetadata(t, code, languages.Verilog, 15, []struct{ prefix, key, value string }{
+		{"// Counts clock edges\nmodule counter", MetadataBlock, "module counter"},
+		{"  function automatic logic [W-1:0] next(", MetadataDefinition, "next"},
+		{"  function automatic logic [W-1:0] next(", MetadataBlock, "module counter"},
+		{"  always_ff @(posedge clk) begin\n", MetadataProcess, "always_ff @(posedge clk)"},
+		{"  initial begin : init\n", MetadataProcess, "init"},
+	})
+}
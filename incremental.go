@@ -0,0 +1,207 @@
+package chunkx
+
+import (
+	"context"
+	"fmt"
+	"slices"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Edit describes a change to a document: the bytes in [StartByte, OldEndByte)
+// are replaced with NewText.
+type Edit struct {
+	StartByte  int
+	OldEndByte int
+	NewText    string
+}
+
+// ChunkDiff reports how the chunks of a document changed after a set of edits.
+// Each list holds chunk IDs in document order.
+type ChunkDiff struct {
+	Added     []string
+	Removed   []string
+	Unchanged []string
+}
+
+// Document keeps the state needed to re-chunk code incrementally as it is
+// edited, as editors and indexers do on every keystroke or commit.
+type Document struct {
+	chunker *castChunker
+	path    string // Path of a document created for a file, chunked as ChunkFile does
+	opts    []Option
+	code    string
+	state   *incrementalRun // Nil until the document is chunked, or after a failed edit
+	chunks  []Chunk
+}
+
+// incrementalRun is the state of chunking the code of a Document once: its
+// syntax tree and the sizes of its nodes, which the next run reuses for the
+// parts of the code its edits left alone.
+type incrementalRun struct {
+	code     string
+	language languages.LanguageName // Language the code was parsed as, if it was
+	tree     syntaxTree             // Nil when the code wasn't parsed
+	sizes    map[nodeSpan]int       // Sizes of the nodes of the code by their byte range
+	prev     *incrementalRun        // Previous run, its tree and sizes edited to match the code
+}
+
+// nodeSpan is the byte range of a syntax node.
+type nodeSpan struct {
+	start, end uint32
+}
+
+// NewDocument chunks code and keeps its syntax tree for incremental updates.
+// A language must be specified with WithLanguage.
+func NewDocument(code string, opts ...Option) (*Document, error) {
+	cfg := newDefaultConfig()
+
+	for _, opt := range opts {
+		opt(cfg)
+	}
+
+	if cfg.language == "" {
+		return nil, ErrLanguageNotSpecified
+	}
+
+	d := &Document{
+		chunker: newCastChunker(),
+		opts:    slices.Clip(opts),
+	}
+	if err := d.rechunk(code); err != nil {
+		return nil, err
+	}
+
+	return d, nil
+}
+
+// newFileDocument chunks code as the content of the file at path, like
+// ChunkFile, and keeps its state for incremental updates.
+func newFileDocument(path, code string, opts ...Option) (*Document, error) {
+	d := &Document{
+		chunker: newCastChunker(),
+		path:    path,
+		opts:    slices.Clip(opts),
+	}
+	if err := d.rechunk(code); err != nil {
+		return nil, err
+	}
+
+	return d, nil
+}
+
+// Code returns the current content of the document.
+func (d *Document) Code() string {
+	return d.code
+}
+
+// Chunks returns the current chunks of the document.
+func (d *Document) Chunks() []Chunk {
+	return d.chunks
+}
+
+// Apply applies the edits in order and re-chunks the document.
+//
+// The offsets of each edit refer to the content produced by the edits before
+// it. The document is re-chunked with all of its options, so its chunks are
+// those of chunking the new code from scratch. The previous syntax tree is
+// reused, so only the edited regions are re-parsed, as are the sizes of the
+// syntax nodes outside them. Chunk IDs are content-addressed, so chunks
+// outside the edited regions are reported as unchanged even when their
+// offsets shift.
+func (d *Document) Apply(edits ...Edit) (*ChunkDiff, error) {
+	code := d.code
+	for _, edit := range edits {
+		if edit.StartByte < 0 || edit.StartByte > edit.OldEndByte || edit.OldEndByte > len(code) {
+			// Edits already applied to the state don't match the content anymore
+			d.state = nil
+			return nil, fmt.Errorf("%w: [%d, %d) out of range for %d bytes",
+				ErrInvalidEdit, edit.StartByte, edit.OldEndByte, len(code))
+		}
+
+		if d.state != nil {
+			d.state.edit(code, edit)
+		}
+		code = code[:edit.StartByte] + edit.NewText + code[edit.OldEndByte:]
+	}
+
+	prev := d.chunks
+	if err := d.rechunk(code); err != nil {
+		d.state = nil
+		return nil, err
+	}
+
+	return diffChunks(prev, d.chunks), nil
+}
+
+// rechunk chunks code, reusing the state of the document when it has one.
+func (d *Document) rechunk(code string) error {
+	run := &incrementalRun{code: code, sizes: make(map[nodeSpan]int), prev: d.state}
+	opts := append(d.opts, func(c *config) {
+		c.incremental = run
+	})
+
+	var chunks []Chunk
+	var err error
+	if d.path != "" {
+		chunks, err = d.chunker.chunkFileContent(context.Background(), d.path, code, opts)
+	} else {
+		chunks, err = d.chunker.Chunk(code, opts...)
+	}
+	if err != nil {
+		return err
+	}
+
+	run.prev = nil
+	d.code, d.state, d.chunks = code, run, chunks
+	return nil
+}
+
+// edit records an edit against the code of the run in its tree and sizes, so
+// the next run can reuse what lies outside the edit. Sizes of nodes
+// overlapping the edit are dropped, and those after it shifted.
+func (run *incrementalRun) edit(code string, edit Edit) {
+	editTree(run.tree, code, edit)
+
+	shift := len(edit.NewText) - (edit.OldEndByte - edit.StartByte)
+	sizes := make(map[nodeSpan]int, len(run.sizes))
+	for span, size := range run.sizes {
+		switch {
+		case int(span.end) <= edit.StartByte:
+			sizes[span] = size
+		case int(span.start) >= edit.OldEndByte:
+			sizes[nodeSpan{uint32(int(span.start) + shift), uint32(int(span.end) + shift)}] = size
+		}
+	}
+	run.sizes = sizes
+}
+
+// diffChunks compares two chunk lists by ID. Lists are treated as multisets so
+// duplicate chunks are matched one-to-one.
+func diffChunks(prev, next []Chunk) *ChunkDiff {
+	remaining := make(map[string]int, len(prev))
+	for _, chunk := range prev {
+		remaining[chunk.ID()]++
+	}
+
+	diff := &ChunkDiff{}
+	for _, chunk := range next {
+		id := chunk.ID()
+		if remaining[id] > 0 {
+			remaining[id]--
+			diff.Unchanged = append(diff.Unchanged, id)
+		} else {
+			diff.Added = append(diff.Added, id)
+		}
+	}
+
+	for _, chunk := range prev {
+		id := chunk.ID()
+		if remaining[id] > 0 {
+			remaining[id]--
+			diff.Removed = append(diff.Removed, id)
+		}
+	}
+
+	return diff
+}
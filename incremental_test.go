@@ -0,0 +1,197 @@
+//go:build cgo
+
+package chunkx
+
+import (
+	"errors"
+	"os"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+const incrementalCode = `package main
+
+func add(a, b int) int {
+	return a + b
+}
+
+func subtract(a, b int) int {
+	return a - b
+}
+
+func multiply(a, b int) int {
+	return a * b
+}`
+
+func TestDocument_Apply(t *testing.T) {
+	doc, err := NewDocument(incrementalCode, WithLanguage(languages.Go), WithMaxSize(15))
+	if err != nil {
+		t.Fatalf("NewDocument() error = %v", err)
+	}
+
+	start := strings.Index(incrementalCode, "a * b")
+	diff, err := doc.Apply(Edit{
+		StartByte:  start,
+		OldEndByte: start + len("a * b"),
+		NewText:    "a * b * 2",
+	})
+	if err != nil {
+		t.Fatalf("Apply() error = %v", err)
+	}
+
+	wantCode := strings.Replace(incrementalCode, "a * b", "a * b * 2", 1)
+	if doc.Code() != wantCode {
+		t.Fatalf("Code() = %q, want %q", doc.Code(), wantCode)
+	}
+
+	if len(diff.Added) == 0 || len(diff.Removed) == 0 {
+		t.Errorf("expected added and removed chunks, got %+v", diff)
+	}
+	if len(diff.Unchanged) == 0 {
+		t.Errorf("expected unchanged chunks before the edit, got %+v", diff)
+	}
+
+	// Incremental results must match chunking the new code from scratch
+	want, err := NewChunker().Chunk(wantCode, WithLanguage(languages.Go), WithMaxSize(15))
+	if err != nil {
+		t.Fatalf("Chunk() error = %v", err)
+	}
+	got := doc.Chunks()
+	if len(got) != len(want) {
+		t.Fatalf("Chunks() returned %d chunks, want %d", len(got), len(want))
+	}
+	for i := range want {
+		if got[i].Content != want[i].Content || got[i].StartByte != want[i].StartByte {
+			t.Errorf("chunk %d = %q at %d, want %q at %d",
+				i, got[i].Content, got[i].StartByte, want[i].Content, want[i].StartByte)
+		}
+	}
+}
+
+func TestDocument_ApplyMultipleEdits(t *testing.T) {
+	doc, err := NewDocument("line one\nline two", WithLanguage(languages.Generic), WithMaxSize(2))
+	if err != nil {
+		t.Fatalf("NewDocument() error = %v", err)
+	}
+
+	diff, err := doc.Apply(
+		Edit{StartByte: 0, OldEndByte: 4, NewText: "row"},
+		Edit{StartByte: 8, OldEndByte: 12, NewText: "row"},
+	)
+	if err != nil {
+		t.Fatalf("Apply() error = %v", err)
+	}
+
+	if doc.Code() != "row one\nrow two" {
+		t.Errorf("Code() = %q", doc.Code())
+	}
+	if len(diff.Added) != 2 || len(diff.Removed) != 2 || len(diff.Unchanged) != 0 {
+		t.Errorf("unexpected diff %+v", diff)
+	}
+}
+
+func TestDocument_ApplyInvalidEdit(t *testing.T) {
+	doc, err := NewDocument("package main", WithLanguage(languages.Go))
+	if err != nil {
+		t.Fatalf("NewDocument() error = %v", err)
+	}
+
+	_, err = doc.Apply(Edit{StartByte: 5, OldEndByte: 100})
+	if !errors.Is(err, ErrInvalidEdit) {
+		t.Errorf("Apply() error = %v, want ErrInvalidEdit", err)
+	}
+}
+
+// textCounter counts words as SimpleTokenCounter does, recording the texts
+// it counted.
+type textCounter struct {
+	counted []string
+}
+
+func (c *textCounter) CountTokens(text string) (int, error) {
+	c.counted = append(c.counted, text)
+	return (&SimpleTokenCounter{}).CountTokens(text)
+}
+
+func TestDocument_ApplyReusesSizes(t *testing.T) {
+	counter := &textCounter{}
+	doc, err := NewDocument(incrementalCode, WithLanguage(languages.Go), WithMaxSize(15), WithTokenCounter(counter))
+	if err != nil {
+		t.Fatalf("NewDocument() error = %v", err)
+	}
+
+	counter.counted = nil
+	start := strings.Index(incrementalCode, "a * b")
+	if _, err := doc.Apply(Edit{StartByte: start, OldEndByte: start + len("a * b"), NewText: "a * b * 2"}); err != nil {
+		t.Fatalf("Apply() error = %v", err)
+	}
+
+	// The functions before the edit aren't measured again
+	for _, text := range counter.counted {
+		if strings.HasPrefix(text, "func add") || strings.HasPrefix(text, "func subtract") {
+			t.Errorf("expected the size of %q to be reused", text)
+		}
+	}
+}
+
+func TestDocument_Options(t *testing.T) {
+	code := "all: app\n\tcurl -H 'key: sk-abcdefghijklmnopqrstuvwx'\n\nclean:\n\trm -f app\n"
+	opts := []Option{WithRedaction(), WithContextTemplate("{file}:{lines}")}
+	path := filepath.Join(t.TempDir(), "Makefile")
+	if err := os.WriteFile(path, []byte(code), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	doc, err := newFileDocument(path, code, opts...)
+	if err != nil {
+		t.Fatalf("newFileDocument() error = %v", err)
+	}
+	edited := strings.Replace(code, "rm -f app", "rm -f app *.o", 1)
+	start := strings.Index(code, "rm -f app")
+	if _, err := doc.Apply(Edit{StartByte: start, OldEndByte: start + len("rm -f app"), NewText: "rm -f app *.o"}); err != nil {
+		t.Fatalf("Apply() error = %v", err)
+	}
+
+	// Documents apply every option, as ChunkFile does
+	if err := os.WriteFile(path, []byte(edited), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	want, err := NewChunker().ChunkFile(path, opts...)
+	if err != nil {
+		t.Fatalf("ChunkFile() error = %v", err)
+	}
+	if !reflect.DeepEqual(doc.Chunks(), want) {
+		t.Errorf("Chunks() = %+v, want %+v", doc.Chunks(), want)
+	}
+	for _, chunk := range doc.Chunks() {
+		if strings.Contains(chunk.Content, "sk-abcdefghijklmnopqrstuvwx") {
+			t.Errorf("expected the key to be redacted in %q", chunk.Content)
+		}
+	}
+}
+
+func TestNewDocument_MissingLanguage(t *testing.T) {
+	if _, err := NewDocument("package main"); !errors.Is(err, ErrLanguageNotSpecified) {
+		t.Errorf("NewDocument() error = %v, want ErrLanguageNotSpecified", err)
+	}
+}
+
+func TestChunk_ID(t *testing.T) {
+	a := Chunk{Content: "func a() {}", Language: languages.Go, StartByte: 0}
+	b := Chunk{Content: "func a() {}", Language: languages.Go, StartByte: 42}
+	c := Chunk{Content: "func a() {}", Language: languages.Rust}
+
+	if a.ID() != b.ID() {
+		t.Error("ID() should not depend on position")
+	}
+	if a.ID() == c.ID() {
+		t.Error("ID() should depend on language")
+	}
+	if len(a.ID()) != 32 {
+		t.Errorf("ID() length = %d, want 32", len(a.ID()))
+	}
+}
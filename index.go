@@ -0,0 +1,41 @@
+package chunkx
+
+import (
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+)
+
+// FileIndex records the files chunked by earlier runs with a hash of their
+// content, so a ChunkPipeline can skip unchanged files and an interrupted run
+// can resume where it stopped. The chunkstore package implements it with
+// SQLite.
+//
+// Implementations must be safe for concurrent use.
+type FileIndex interface {
+	// Hash returns the content hash recorded for the file at path, or an
+	// empty string if it hasn't been recorded.
+	Hash(ctx context.Context, path string) (string, error)
+
+	// Record records the chunks of a file chunked successfully and the hash
+	// of its content, replacing any earlier record of the file.
+	Record(ctx context.Context, result FileResult, hash string) error
+}
+
+// WithIndex makes a ChunkPipeline skip files whose content hash matches the
+// one recorded in index, reporting them with Skipped set and no chunks, and
+// record every other file once it has been chunked successfully. Failed files
+// aren't recorded, so they are retried by the next run. It has no effect on
+// single-file chunking.
+func WithIndex(index FileIndex) Option {
+	return func(c *config) {
+		c.index = index
+	}
+}
+
+// ContentHash returns the hash of file content recorded by WithIndex: its
+// SHA-256 digest in hex.
+func ContentHash(content []byte) string {
+	sum := sha256.Sum256(content)
+	return hex.EncodeToString(sum[:])
+}
@@ -0,0 +1,61 @@
+package chunkx
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"sync"
+	"testing"
+)
+
+// memIndex is a FileIndex in memory.
+type memIndex struct {
+	mu     sync.Mutex
+	hashes map[string]string
+}
+
+func (ix *memIndex) Hash(ctx context.Context, path string) (string, error) {
+	ix.mu.Lock()
+	defer ix.mu.Unlock()
+	return ix.hashes[path], nil
+}
+
+func (ix *memIndex) Record(ctx context.Context, result FileResult, hash string) error {
+	ix.mu.Lock()
+	defer ix.mu.Unlock()
+	ix.hashes[result.Path] = hash
+	return nil
+}
+
+func TestChunkPipeline_WithIndex(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"a.txt": "one\n",
+		"b.txt": "two\n",
+	})
+	index := &memIndex{hashes: make(map[string]string)}
+
+	run := func() (chunked, skipped int) {
+		for result := range NewChunkPipeline(2, WithIndex(index)).RunDir(context.Background(), dir) {
+			if result.Err != nil {
+				t.Fatalf("unexpected error: %v", result.Err)
+			}
+			if result.Skipped {
+				skipped++
+			} else if len(result.Chunks) > 0 {
+				chunked++
+			}
+		}
+		return chunked, skipped
+	}
+
+	if chunked, skipped := run(); chunked != 2 || skipped != 0 {
+		t.Errorf("first run chunked %d and skipped %d files, want 2 and 0", chunked, skipped)
+	}
+
+	if err := os.WriteFile(filepath.Join(dir, "b.txt"), []byte("three\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	if chunked, skipped := run(); chunked != 1 || skipped != 1 {
+		t.Errorf("second run chunked %d and skipped %d files, want 1 and 1", chunked, skipped)
+	}
+}
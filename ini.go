@@ -0,0 +1,108 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+// iniSection matches the line of an INI file starting a section, such as
+// "[database]" or `[remote "origin"]`, and its name.
+var iniSection = regexp.MustCompile(`^[ \t]*\[([^\]\n]+)\][ \t]*(?:[;#].*)?\r?$`)
+
+// iniSpans returns the sections of the INI code, each with the comment lines
+// right above its header, recording its name as MetadataSection, and the
+// entries before the first of them, as propertiesSpans returns them.
+func iniSpans(code string) []textSpan {
+	var spans []textSpan
+	first, from := len(code), 0 // Start of the first section, and end of the last header
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		if m := iniSection.FindStringSubmatch(line); m != nil {
+			start := leadingComments(code, offset, from, ";", "#")
+			if n := len(spans); n > 0 {
+				spans[n-1].end = start
+			} else {
+				first = start
+			}
+			spans = append(spans, textSpan{start: start, end: len(code), metadata: map[string]string{MetadataSection: strings.TrimSpace(m[1])}})
+			from = next
+		}
+		offset = next
+	}
+	return append(configEntries(code[:first], 0, ";", "#"), spans...)
+}
+
+// iniNested returns the entries of the INI section too large for a chunk
+// after its header, so consecutive ones that fit are chunked together.
+func iniNested(section string) []textSpan {
+	for offset := 0; offset < len(section); {
+		line, next := codeLine(section, offset)
+		if iniSection.MatchString(line) {
+			return configEntries(section, next, ";", "#")
+		}
+		offset = next
+	}
+	return nil
+}
+
+// propertiesSpans returns the entries of the Java properties code, each with
+// the comment lines right above it, so consecutive ones that fit are chunked
+// together and no chunk ends within an entry or between it and its comments.
+func propertiesSpans(code string) []textSpan {
+	return configEntries(code, 0, "#", "!")
+}
+
+// envSpans returns the variables of the .env code, each with the comment
+// lines right above it, as propertiesSpans returns entries.
+func envSpans(code string) []textSpan {
+	return configEntries(code, 0, "#")
+}
+
+// configEntries returns the key-value entries of the configuration code on
+// the lines from offset on, each with the comment lines right above it whose
+// prefix is one of prefixes. Entries record no metadata, so consecutive ones
+// are chunked together while they fit.
+func configEntries(code string, offset int, prefixes ...string) []textSpan {
+	var spans []textSpan
+	from := offset
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		if text := strings.TrimSpace(line); text == "" || hasAnyPrefix(text, prefixes) {
+			offset = next
+			continue
+		}
+
+		end := configEntryEnd(code, offset)
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, max(spanEnd(spans), from), prefixes...),
+			end:      end,
+			metadata: map[string]string{},
+		})
+		offset = end
+	}
+	return spans
+}
+
+// configEntryEnd returns the offset after the configuration entry starting on
+// the line at offset in code, and after the lines its value continues onto:
+// with a trailing backslash, within a quoted value left open, or indented
+// below it, as multi-line INI values are.
+func configEntryEnd(code string, offset int) int {
+	line, next := codeLine(code, offset)
+	if i := strings.IndexAny(line, "=:"); i >= 0 {
+		value := strings.TrimSpace(line[i+1:])
+		if value != "" && (value[0] == '"' || value[0] == '\'') && !strings.Contains(value[1:], value[:1]) {
+			_, next = codeLine(code, skipPast(code, next, len(code), value[:1])-1)
+			return next
+		}
+	}
+	next = continuedLine(code, offset)
+	for next < len(code) {
+		line, after := codeLine(code, next)
+		if strings.TrimSpace(line) == "" || line[0] != ' ' && line[0] != '\t' {
+			break
+		}
+		next = after
+	}
+	return next
+}
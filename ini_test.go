@@ -0,0 +1,70 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_INI(t *testing.T) {
+	code := `; Global settings
+debug = false
+
+[database]
+; Primary host
+host = db.example.com
+port = 5432
+
+[server]
+# Listen address
+listen = 0.0.0.0:8080
+banner = Welcome
+  to the server
+`
+	testChunkMetadata(t, code, languages.INI, 8, []struct{ prefix, key, value string }{
+		{"; Global settings\ndebug = false\n", MetadataSection, ""},
+		{"; Primary host\nhost = db.example.com\n", MetadataSection, "database"},
+		{"# Listen address\nlisten = 0.0.0.0:8080\n", MetadataSection, "server"},
+		{"banner = Welcome\n  to the server\n", MetadataSection, "server"},
+	})
+}
+
+func TestChunker_Properties(t *testing.T) {
+	code := `# Greetings
+greeting.hello = Hello
+greeting.bye = Goodbye
+! Errors
+error.missing = Missing \
+    value
+error.denied = Denied
+`
+	chunks := testChunkMetadata(t, code, languages.Properties, 8, []struct{ prefix, key, value string }{
+		{"# Greetings\ngreeting.hello = Hello\ngreeting.bye = Goodbye\n", MetadataSection, ""},
+		{"! Errors\nerror.missing = Missing \\\n    value\n", MetadataSection, ""},
+	})
+	for _, chunk := range chunks {
+		if strings.Contains(chunk.Content, "Missing") != strings.Contains(chunk.Content, "value") {
+			t.Errorf("expected the continued entry in one chunk, got %q", chunk.Content)
+		}
+	}
+}
+
+func TestChunker_DotEnv(t *testing.T) {
+	code := `# Database
+DATABASE_URL=postgres://localhost/app
+# Signing key
+PRIVATE_KEY="-----BEGIN KEY-----
+abc def ghi
+-----END KEY-----"
+PORT=8080
+`
+	chunks := testChunkMetadata(t, code, languages.DotEnv, 12, []struct{ prefix, key, value string }{
+		{"# Signing key\nPRIVATE_KEY=", MetadataSection, ""},
+	})
+	for _, chunk := range chunks {
+		if strings.Contains(chunk.Content, "BEGIN") != strings.Contains(chunk.Content, "END KEY") {
+			t.Errorf("expected the quoted value in one chunk, got %q", chunk.Content)
+		}
+	}
+}
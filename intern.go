@@ -0,0 +1,48 @@
+//go:build cgo
+
+package chunkx
+
+import (
+	"github.com/gomantics/chunkx/languages"
+	sitter "github.com/smacker/go-tree-sitter"
+)
+
+// nodeTypeKey identifies a node type within a grammar.
+type nodeTypeKey struct {
+	language languages.LanguageName
+	symbol   uint16
+}
+
+// nodeTypeInfo is an interned node type name and whether it is reported in
+// Chunk.NodeTypes.
+type nodeTypeInfo struct {
+	name    string
+	include bool
+}
+
+// nodeType returns the interned type of node.
+//
+// Node.Type allocates a new string on every call, which dominates allocations
+// when chunking large corpora. Interning by grammar symbol means each type
+// name is allocated once per chunker and shared by every chunk, including
+// chunks made by concurrent calls.
+//
+// The other strings of a chunk aren't interned, as they aren't copied per
+// chunk: a FileResult holds its path once, language names are constants, and
+// cloning the metadata a pipeline attaches from the source of a file, such as
+// its repository or archive, copies the headers of its strings rather than
+// their bytes. Metadata computed for a chunk, such as its symbol path, is
+// allocated once per chunk rather than per node.
+func (c *castChunker) nodeType(node *sitter.Node, language languages.LanguageName) nodeTypeInfo {
+	key := nodeTypeKey{language: language, symbol: uint16(node.Symbol())}
+	if info, ok := c.nodeTypes.Load(key); ok {
+		return info.(nodeTypeInfo)
+	}
+
+	name := node.Type()
+	info, _ := c.nodeTypes.LoadOrStore(key, nodeTypeInfo{
+		name:    name,
+		include: shouldIncludeNodeType(name),
+	})
+	return info.(nodeTypeInfo)
+}
@@ -0,0 +1,69 @@
+//go:build cgo
+
+package chunkx
+
+import (
+	"testing"
+	"unsafe"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_InternsNodeTypes(t *testing.T) {
+	code := `package main
+
+func add(a, b int) int {
+	return a + b
+}
+
+func subtract(a, b int) int {
+	return a - b
+}`
+
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Go), WithMaxSize(12))
+	if err != nil {
+		t.Fatalf("Chunk() error = %v", err)
+	}
+	if len(chunks) < 2 {
+		t.Fatalf("expected at least 2 chunks, got %d", len(chunks))
+	}
+
+	// The same node type in different chunks must share its backing memory
+	seen := make(map[string]*byte)
+	shared := 0
+	for _, chunk := range chunks {
+		for _, nodeType := range chunk.NodeTypes {
+			ptr := unsafe.StringData(nodeType)
+			if prev, ok := seen[nodeType]; ok {
+				if prev != ptr {
+					t.Errorf("node type %q is not interned", nodeType)
+				}
+				shared++
+			}
+			seen[nodeType] = ptr
+		}
+	}
+	if shared == 0 {
+		t.Error("expected node types shared between chunks")
+	}
+}
+
+func TestNodeType_MatchesTreeSitter(t *testing.T) {
+	result, err := NewParser().Parse(`func hello() { return "world" }`, languages.Go)
+	if err != nil {
+		t.Fatalf("Parse() error = %v", err)
+	}
+
+	c := &castChunker{}
+	root := result.Tree.RootNode()
+	for i := range int(root.ChildCount()) {
+		child := root.Child(i)
+		info := c.nodeType(child, languages.Go)
+		if info.name != child.Type() {
+			t.Errorf("nodeType() = %q, want %q", info.name, child.Type())
+		}
+		if info.include != shouldIncludeNodeType(child.Type()) {
+			t.Errorf("nodeType(%q).include = %v", info.name, info.include)
+		}
+	}
+}
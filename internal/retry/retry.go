@@ -0,0 +1,155 @@
+// Package retry retries operations against remote services with exponential
+// backoff and jitter, honoring the Retry-After of throttled requests.
+package retry
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"math/rand/v2"
+	"net/http"
+	"strconv"
+	"time"
+)
+
+// PermanentError marks an error that retrying won't fix, such as a rejected
+// request.
+type PermanentError struct {
+	Err error
+}
+
+func (e *PermanentError) Error() string {
+	return e.Err.Error()
+}
+
+func (e *PermanentError) Unwrap() error {
+	return e.Err
+}
+
+// ThrottledError marks an error of a service that asked to be retried after
+// a delay, as with the Retry-After header of 429 Too Many Requests.
+type ThrottledError struct {
+	Err   error
+	After time.Duration // Delay the service asked for
+}
+
+func (e *ThrottledError) Error() string {
+	return e.Err.Error()
+}
+
+func (e *ThrottledError) Unwrap() error {
+	return e.Err
+}
+
+// Do calls fn until it succeeds, it returns a PermanentError, ctx is done, or
+// it has been retried the given number of times. The first retry waits for
+// backoff and each further retry twice as long as the one before, less a
+// random jitter of up to half the delay, so clients that failed together
+// don't retry together. A ThrottledError waits at least the delay it asks
+// for.
+func Do(ctx context.Context, retries int, backoff time.Duration, fn func() error) error {
+	delay := backoff
+	for attempt := 0; ; attempt++ {
+		err := fn()
+		if err == nil {
+			return nil
+		}
+
+		var permanent *PermanentError
+		if attempt >= retries || errors.As(err, &permanent) || ctx.Err() != nil {
+			return err
+		}
+
+		wait := delay - jitter(delay/2)
+		var throttled *ThrottledError
+		if errors.As(err, &throttled) {
+			wait = max(wait, throttled.After)
+		}
+		select {
+		case <-ctx.Done():
+			return ctx.Err()
+		case <-time.After(wait):
+		}
+		delay *= 2
+	}
+}
+
+// jitter returns a random duration of at most d.
+func jitter(d time.Duration) time.Duration {
+	if d <= 0 {
+		return 0
+	}
+	return rand.N(d + 1)
+}
+
+// retryAfter returns the delay of a Retry-After header, in seconds or as an
+// HTTP date, or 0 if there is none.
+func retryAfter(header string, now time.Time) time.Duration {
+	if header == "" {
+		return 0
+	}
+	if seconds, err := strconv.Atoi(header); err == nil {
+		return time.Duration(max(seconds, 0)) * time.Second
+	}
+	if at, err := http.ParseTime(header); err == nil {
+		return max(at.Sub(now), 0)
+	}
+	return 0
+}
+
+// ErrTooLarge is the error of a response body larger than the limit of
+// SendLimited.
+var ErrTooLarge = errors.New("response body too large")
+
+// Send sends req and returns the response body, turning an unsuccessful
+// response into an error that names the service. Client errors other than
+// 429 Too Many Requests are permanent, and a Retry-After header makes the
+// error a ThrottledError. A nil client uses http.DefaultClient.
+func Send(client *http.Client, req *http.Request, service string) ([]byte, error) {
+	return SendLimited(client, req, service, 0)
+}
+
+// SendLimited is Send, reading at most max bytes of the response body if max
+// is positive. A larger body fails permanently with ErrTooLarge, without
+// being read at all if its Content-Length says so.
+func SendLimited(client *http.Client, req *http.Request, service string, max int64) ([]byte, error) {
+	if client == nil {
+		client = http.DefaultClient
+	}
+
+	resp, err := client.Do(req)
+	if err != nil {
+		return nil, fmt.Errorf("%s: %w", service, err)
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode < 300 {
+		var r io.Reader = resp.Body
+		if max > 0 {
+			if resp.ContentLength > max {
+				return nil, &PermanentError{Err: fmt.Errorf("%s: %w", service, ErrTooLarge)}
+			}
+			r = io.LimitReader(resp.Body, max+1)
+		}
+		body, err := io.ReadAll(r)
+		if err != nil {
+			return nil, fmt.Errorf("%s: %w", service, err)
+		}
+		if max > 0 && int64(len(body)) > max {
+			return nil, &PermanentError{Err: fmt.Errorf("%s: %w", service, ErrTooLarge)}
+		}
+		return body, nil
+	}
+
+	msg, _ := io.ReadAll(io.LimitReader(resp.Body, 1024))
+	err = fmt.Errorf("%s: %s: %s", service, resp.Status, bytes.TrimSpace(msg))
+	if resp.StatusCode >= 400 && resp.StatusCode < 500 && resp.StatusCode != http.StatusTooManyRequests {
+		return nil, &PermanentError{Err: err}
+	}
+	if after := retryAfter(resp.Header.Get("Retry-After"), time.Now()); after > 0 {
+		return nil, &ThrottledError{Err: err, After: after}
+	}
+	return nil, err
+}
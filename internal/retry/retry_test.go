@@ -0,0 +1,125 @@
+package retry
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+	"time"
+)
+
+func TestDo(t *testing.T) {
+	errFail := errors.New("fail")
+	tests := []struct {
+		name      string
+		failures  int
+		err       error
+		wantCalls int
+		wantErr   bool
+	}{
+		{"succeeds", 0, errFail, 1, false},
+		{"recovers", 2, errFail, 3, false},
+		{"gives up", 10, errFail, 4, true},
+		{"permanent", 10, &PermanentError{Err: errFail}, 1, true},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			calls := 0
+			err := Do(context.Background(), 3, time.Millisecond, func() error {
+				calls++
+				if calls <= tt.failures {
+					return tt.err
+				}
+				return nil
+			})
+			if (err != nil) != tt.wantErr || calls != tt.wantCalls {
+				t.Errorf("err = %v, calls = %d; want error %v and %d calls", err, calls, tt.wantErr, tt.wantCalls)
+			}
+		})
+	}
+}
+
+func TestDo_Cancelled(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	calls := 0
+	err := Do(ctx, 3, time.Hour, func() error {
+		calls++
+		cancel()
+		return errors.New("fail")
+	})
+	if err == nil || calls != 1 {
+		t.Errorf("err = %v, calls = %d", err, calls)
+	}
+}
+
+func TestSend_Status(t *testing.T) {
+	tests := []struct {
+		status    int
+		wantErr   bool
+		permanent bool
+	}{
+		{http.StatusOK, false, false},
+		{http.StatusBadRequest, true, true},
+		{http.StatusNotFound, true, true},
+		{http.StatusTooManyRequests, true, false},
+		{http.StatusServiceUnavailable, true, false},
+	}
+
+	for _, tt := range tests {
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, _ *http.Request) {
+			w.WriteHeader(tt.status)
+			_, _ = w.Write([]byte("body"))
+		}))
+
+		req, _ := http.NewRequest(http.MethodGet, server.URL, nil)
+		body, err := Send(nil, req, "test")
+		server.Close()
+
+		var permanent *PermanentError
+		if (err != nil) != tt.wantErr || errors.As(err, &permanent) != tt.permanent {
+			t.Errorf("status %d: err = %v, want error %v, permanent %v", tt.status, err, tt.wantErr, tt.permanent)
+		}
+		if err == nil && string(body) != "body" {
+			t.Errorf("status %d: body = %q", tt.status, body)
+		}
+	}
+}
+
+func TestDo_Throttled(t *testing.T) {
+	calls := 0
+	start := time.Now()
+	err := Do(context.Background(), 3, time.Millisecond, func() error {
+		if calls++; calls == 1 {
+			return &ThrottledError{Err: errors.New("slow down"), After: 50 * time.Millisecond}
+		}
+		return nil
+	})
+	if err != nil || calls != 2 {
+		t.Fatalf("err = %v, calls = %d", err, calls)
+	}
+	if elapsed := time.Since(start); elapsed < 50*time.Millisecond {
+		t.Errorf("retried after %v, want at least the 50ms asked for", elapsed)
+	}
+}
+
+func TestSend_RetryAfter(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, _ *http.Request) {
+		w.Header().Set("Retry-After", "2")
+		w.WriteHeader(http.StatusTooManyRequests)
+	}))
+	defer server.Close()
+
+	req, _ := http.NewRequest(http.MethodGet, server.URL, nil)
+	_, err := Send(nil, req, "test")
+	var throttled *ThrottledError
+	if !errors.As(err, &throttled) || throttled.After != 2*time.Second {
+		t.Errorf("err = %v, want a ThrottledError of 2s", err)
+	}
+
+	now := time.Date(2024, 1, 1, 0, 0, 0, 0, time.UTC)
+	if got := retryAfter(now.Add(time.Minute).Format(http.TimeFormat), now); got != time.Minute {
+		t.Errorf("retryAfter of a date = %v, want 1m0s", got)
+	}
+}
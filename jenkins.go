@@ -0,0 +1,83 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+// MetadataStage is the metadata key of the name of the Jenkins pipeline stage
+// holding the chunk, such as "Build".
+const MetadataStage = "stage"
+
+var (
+	// jenkinsStage matches the head of a Jenkins pipeline stage, such as
+	// "stage('Build')", and its name.
+	jenkinsStage = regexp.MustCompile(`^stage[ \t]*\([ \t]*(?:name:[ \t]*)?["']([^"'\n]*)["']`)
+	// jenkinsStageLine matches a line of a Jenkinsfile opening a stage.
+	jenkinsStageLine = regexp.MustCompile(`(?m)^[ \t]*stage[ \t]*\(`)
+	// jenkinsFunction matches the head of a function of a Jenkinsfile, such
+	// as "def deploy(String env)", and its name.
+	jenkinsFunction = regexp.MustCompile(`^(?:(?:private|public|static)[ \t]+)*(?:def|void)[ \t]+([A-Za-z_]\w*)[ \t]*\(`)
+)
+
+// jenkinsSpans returns the blocks of the Jenkinsfile code, splitting those
+// holding stages, such as the pipeline and stages blocks of a declarative
+// pipeline or the node closures of a scripted one, at their stages. Stages
+// record MetadataStage, functions MetadataDefinition, and other blocks, such
+// as post or environment, their head as MetadataBlock.
+func jenkinsSpans(code string) []textSpan {
+	return jenkinsBlocks(code, 0)
+}
+
+// jenkinsNested returns the blocks within the block of a Jenkinsfile, after
+// the line opening it, such as the steps and post blocks of a stage.
+func jenkinsNested(block string) []textSpan {
+	for offset := 0; offset < len(block); {
+		line, next := codeLine(block, offset)
+		if gradleBlock.MatchString(line) {
+			return jenkinsBlocks(block, next)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// jenkinsBlocks returns the blocks of the Jenkinsfile code opened on the
+// lines from offset on.
+func jenkinsBlocks(code string, offset int) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := gradleBlock.FindStringSubmatchIndex(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+
+		closing := braceClosing(code, offset+m[3]+strings.IndexByte(line[m[3]:], '{'))
+		_, end := codeLine(code, closing)
+		head := strings.Join(strings.Fields(line[m[2]:m[3]]), " ")
+		stage := jenkinsStage.FindStringSubmatch(head)
+		// Blocks that aren't stages but hold some are left out in favor of
+		// their blocks, so the pipeline is chunked at its stages
+		if stage == nil && jenkinsStageLine.MatchString(code[next:end]) {
+			spans = append(spans, jenkinsBlocks(code[:end], next)...)
+			offset = end
+			continue
+		}
+
+		metadata := map[string]string{MetadataBlock: head}
+		if stage != nil {
+			metadata = map[string]string{MetadataStage: stage[1]}
+		} else if function := jenkinsFunction.FindStringSubmatch(head); function != nil {
+			metadata = map[string]string{MetadataDefinition: function[1]}
+		}
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "//", "/*", "*"),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
@@ -0,0 +1,95 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Jenkinsfile(t *testing.T) {
+	tests := []struct {
+		name   string
+		code   string
+		chunks []struct{ prefix, key, value string }
+	}{
+		{
+			name: "declarative",
+			code: `// Builds and ships the service
+pipeline {
+    agent any
+    environment {
+        GOFLAGS = '-mod=vendor'
+    }
+    stages {
+        stage('Build') {
+            steps {
+                sh 'make build'
+            }
+        }
+        // Runs the unit tests
+        stage("Test") {
+            steps {
+                sh 'make test'
+            }
+        }
+    }
+}
+`,
+			chunks: []struct{ prefix, key, value string }{
+				{"    environment {\n", MetadataBlock, "environment"},
+				{"        stage('Build') {\n", MetadataStage, "Build"},
+				{"        // Runs the unit tests\n        stage(\"Test\") {\n", MetadataStage, "Test"},
+			},
+		},
+		{
+			name: "scripted",
+			code: `def deploy(String env) {
+    sh "./deploy.sh ${env}"
+}
+
+node('linux') {
+    stage('Checkout') {
+        checkout scm
+    }
+    stage('Deploy') {
+        deploy('prod')
+    }
+}
+`,
+			chunks: []struct{ prefix, key, value string }{
+				{"def deploy(String env) {\n", MetadataDefinition, "deploy"},
+				{"    stage('Checkout') {\n", MetadataStage, "Checkout"},
+				{"    stage('Deploy') {\n", MetadataStage, "Deploy"},
+			},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			opts := []Option{WithLanguage(languages.Jenkins)}
+			chunks, err := NewChunker().Chunk(tt.code, opts...)
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if err := ValidateChunks(chunks, tt.code, opts...); err != nil {
+				t.Errorf("unexpected validation error: %v", err)
+			}
+
+			for _, want := range tt.chunks {
+				var found bool
+				for _, chunk := range chunks {
+					if strings.HasPrefix(chunk.Content, want.prefix) {
+						found = true
+						if got := chunk.Metadata[want.key]; got != want.value {
+							t.Errorf("expected %s %q of %q, got %q", want.key, want.value, chunk.Content, got)
+						}
+					}
+				}
+				if !found {
+					t.Errorf("expected a chunk starting with %q, got %+v", want.prefix, chunks)
+				}
+			}
+		})
+	}
+}
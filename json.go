@@ -0,0 +1,186 @@
+package chunkx
+
+import (
+	"bytes"
+	"strconv"
+	"strings"
+)
+
+// MetadataJSONPointer is set on chunks of JSON documents chunked with
+// WithJSONSubtrees to the JSON pointer of the deepest value holding the
+// chunk, such as "/resources/3/instances/0", or "" for the whole document.
+const MetadataJSONPointer = "json_pointer"
+
+// jsonPointerEscaper escapes the keys of a JSON pointer, as RFC 6901 does.
+var jsonPointerEscaper = strings.NewReplacer("~", "~0", "/", "~1")
+
+// WithJSONSubtrees chunks JSON documents by their subtrees, so large
+// machine-generated JSON, such as Terraform state or a package-lock.json,
+// can be navigated by where each chunk sits in the document. A value that
+// fits is a chunk of its own; the members of an object or the elements of an
+// array that doesn't fit are chunked together as long as they fit, and the
+// splitter descends into those that don't until every chunk fits. Strings
+// too large on their own are split by lines.
+//
+// Chunks record MetadataJSONPointer. The braces, brackets, and keys of the
+// values descended into are left out of the chunks. Content that isn't a JSON
+// object or array is chunked as usual.
+func WithJSONSubtrees() Option {
+	return func(c *config) {
+		c.jsonSubtrees = true
+	}
+}
+
+// looksLikeJSON reports whether head starts a JSON object or array.
+func looksLikeJSON(head []byte) bool {
+	head = bytes.TrimLeft(head, " \t\r\n")
+	return bytes.HasPrefix(head, []byte("{")) || bytes.HasPrefix(head, []byte("["))
+}
+
+// jsonSplitter splits a JSON document into chunks of its subtrees.
+type jsonSplitter struct {
+	c              *castChunker
+	code           string
+	cfg            *config
+	chunks         []Chunk
+	from, fromLine int // Start and line of the last chunk, to count lines from
+}
+
+// chunkJSON chunks the JSON document code by its subtrees, returning false if
+// code isn't a JSON object or array.
+func (c *castChunker) chunkJSON(code string, cfg *config) ([]Chunk, bool, error) {
+	root, ok := parseJSON(code)
+	if !ok || (code[root.start] != '{' && code[root.start] != '[') {
+		return nil, false, nil
+	}
+
+	s := jsonSplitter{c: c, code: code, cfg: cfg, fromLine: 1}
+	if err := s.split(root, ""); err != nil {
+		return nil, true, err
+	}
+	if err := cfg.limits.checkChunks(s.chunks); err != nil {
+		return nil, true, err
+	}
+	return s.chunks, true, nil
+}
+
+// split chunks the value v at pointer.
+func (s *jsonSplitter) split(v jsonValue, pointer string) error {
+	if err := s.cfg.ctx.Err(); err != nil {
+		return err
+	}
+	if err := s.cfg.step(); err != nil {
+		return err
+	}
+
+	size, err := s.size(v.start, v.end)
+	if err != nil {
+		return err
+	}
+	if size <= s.cfg.maxSize {
+		s.add(v.start, v.end, pointer)
+		return nil
+	}
+	if len(v.values) == 0 {
+		return s.splitLines(v.start, v.end, pointer)
+	}
+
+	object := s.code[v.start] == '{'
+	start := func(i int) int {
+		if object {
+			return v.keyStarts[i]
+		}
+		return v.values[i].start
+	}
+	child := func(i int) string {
+		if object {
+			return pointer + "/" + jsonPointerEscaper.Replace(v.keys[i])
+		}
+		return pointer + "/" + strconv.Itoa(i)
+	}
+
+	// Chunk runs of members together, descending into those too large
+	first := -1 // First member of the current run, if any
+	flush := func(last int) {
+		if first < 0 {
+			return
+		}
+		p := pointer
+		if first == last {
+			p = child(first)
+		}
+		s.add(start(first), v.values[last].end, p)
+		first = -1
+	}
+	for i := range v.values {
+		if first >= 0 {
+			size, err := s.size(start(first), v.values[i].end)
+			if err != nil {
+				return err
+			}
+			if size <= s.cfg.maxSize {
+				continue
+			}
+			flush(i - 1)
+		}
+
+		size, err := s.size(start(i), v.values[i].end)
+		if err != nil {
+			return err
+		}
+		if size <= s.cfg.maxSize {
+			first = i
+			continue
+		}
+		if err := s.split(v.values[i], child(i)); err != nil {
+			return err
+		}
+	}
+	flush(len(v.values) - 1)
+	return nil
+}
+
+// size returns the size of the document from start to end.
+func (s *jsonSplitter) size(start, end int) (int, error) {
+	return s.cfg.tokenCounter.CountTokens(s.code[start:end])
+}
+
+// add adds the chunk of the document from start to end, at pointer.
+func (s *jsonSplitter) add(start, end int, pointer string) {
+	line := lineOf(s.code, s.from, s.fromLine, start)
+	s.from, s.fromLine = start, line
+	s.chunks = append(s.chunks, Chunk{
+		Content:   s.code[start:end],
+		StartLine: line,
+		EndLine:   lineOf(s.code, start, line, end),
+		StartByte: start,
+		EndByte:   end,
+		Language:  s.cfg.language,
+		Metadata:  map[string]string{MetadataJSONPointer: pointer},
+	})
+}
+
+// splitLines chunks the value from start to end, a string or a literal too
+// large on its own, by lines.
+func (s *jsonSplitter) splitLines(start, end int, pointer string) error {
+	// The call's timeout already runs over the whole document
+	inner := *s.cfg
+	inner.jsonSubtrees = false
+	inner.timeout = 0
+	chunks, err := s.c.splitLines(s.code[start:end], &inner)
+	if err != nil {
+		return err
+	}
+
+	line := lineOf(s.code, s.from, s.fromLine, start)
+	s.from, s.fromLine = start, line
+	shiftChunks(chunks, start, line-1)
+	for i := range chunks {
+		if chunks[i].Metadata == nil {
+			chunks[i].Metadata = make(map[string]string, 1)
+		}
+		chunks[i].Metadata[MetadataJSONPointer] = pointer
+	}
+	s.chunks = append(s.chunks, chunks...)
+	return nil
+}
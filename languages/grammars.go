@@ -0,0 +1,87 @@
+//go:build cgo
+
+package languages
+
+import (
+	sitter "github.com/smacker/go-tree-sitter"
+	"github.com/smacker/go-tree-sitter/bash"
+	"github.com/smacker/go-tree-sitter/c"
+	"github.com/smacker/go-tree-sitter/cpp"
+	"github.com/smacker/go-tree-sitter/csharp"
+	"github.com/smacker/go-tree-sitter/css"
+	"github.com/smacker/go-tree-sitter/cue"
+	"github.com/smacker/go-tree-sitter/dockerfile"
+	"github.com/smacker/go-tree-sitter/elixir"
+	"github.com/smacker/go-tree-sitter/elm"
+	"github.com/smacker/go-tree-sitter/golang"
+	"github.com/smacker/go-tree-sitter/groovy"
+	"github.com/smacker/go-tree-sitter/hcl"
+	"github.com/smacker/go-tree-sitter/html"
+	"github.com/smacker/go-tree-sitter/java"
+	"github.com/smacker/go-tree-sitter/javascript"
+	"github.com/smacker/go-tree-sitter/kotlin"
+	"github.com/smacker/go-tree-sitter/lua"
+	tree_sitter_markdown "github.com/smacker/go-tree-sitter/markdown/tree-sitter-markdown"
+	"github.com/smacker/go-tree-sitter/ocaml"
+	"github.com/smacker/go-tree-sitter/php"
+	"github.com/smacker/go-tree-sitter/protobuf"
+	"github.com/smacker/go-tree-sitter/python"
+	"github.com/smacker/go-tree-sitter/ruby"
+	"github.com/smacker/go-tree-sitter/rust"
+	"github.com/smacker/go-tree-sitter/scala"
+	"github.com/smacker/go-tree-sitter/sql"
+	"github.com/smacker/go-tree-sitter/svelte"
+	"github.com/smacker/go-tree-sitter/swift"
+	"github.com/smacker/go-tree-sitter/toml"
+	"github.com/smacker/go-tree-sitter/typescript/typescript"
+	"github.com/smacker/go-tree-sitter/yaml"
+)
+
+// LanguageConfig represents a language configuration.
+type LanguageConfig struct {
+	Name       LanguageName
+	Extensions []string
+	GetParser  func() *sitter.Language // Nil for languages without a tree-sitter grammar
+}
+
+// grammars maps languages to their tree-sitter grammars.
+var grammars = map[LanguageName]func() *sitter.Language{
+	Bash:       bash.GetLanguage,
+	C:          c.GetLanguage,
+	CPP:        cpp.GetLanguage,
+	CSharp:     csharp.GetLanguage,
+	CSS:        css.GetLanguage,
+	Cue:        cue.GetLanguage,
+	Dockerfile: dockerfile.GetLanguage,
+	Elixir:     elixir.GetLanguage,
+	Elm:        elm.GetLanguage,
+	Go:         golang.GetLanguage,
+	Groovy:     groovy.GetLanguage,
+	HCL:        hcl.GetLanguage,
+	HTML:       html.GetLanguage,
+	Java:       java.GetLanguage,
+	JavaScript: javascript.GetLanguage,
+	Kotlin:     kotlin.GetLanguage,
+	Lua:        lua.GetLanguage,
+	Markdown:   tree_sitter_markdown.GetLanguage,
+	OCaml:      ocaml.GetLanguage,
+	PHP:        php.GetLanguage,
+	Protobuf:   protobuf.GetLanguage,
+	Python:     python.GetLanguage,
+	Ruby:       ruby.GetLanguage,
+	Rust:       rust.GetLanguage,
+	Scala:      scala.GetLanguage,
+	SQL:        sql.GetLanguage,
+	Svelte:     svelte.GetLanguage,
+	Swift:      swift.GetLanguage,
+	TOML:       toml.GetLanguage,
+	TypeScript: typescript.GetLanguage,
+	YAML:       yaml.GetLanguage,
+}
+
+func init() {
+	for key, lang := range registry {
+		lang.GetParser = grammars[lang.Name]
+		registry[key] = lang
+	}
+}
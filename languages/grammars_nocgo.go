@@ -0,0 +1,10 @@
+//go:build !cgo
+
+package languages
+
+// LanguageConfig represents a language configuration. Tree-sitter grammars
+// need cgo, so builds without it have no parsers.
+type LanguageConfig struct {
+	Name       LanguageName
+	Extensions []string
+}
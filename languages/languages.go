@@ -5,36 +5,74 @@ type LanguageName string
 
 // Supported language constants.
 const (
+	Ada        LanguageName = "ada"
+	Apache     LanguageName = "apache"
+	Apex       LanguageName = "apex"
+	Assembly   LanguageName = "assembly"
 	Bash       LanguageName = "bash"
+	BibTeX     LanguageName = "bibtex"
 	C          LanguageName = "c"
+	Caddy      LanguageName = "caddy"
+	Clojure    LanguageName = "clojure"
+	CMake      LanguageName = "cmake"
+	COBOL      LanguageName = "cobol"
 	CPP        LanguageName = "cpp"
 	CSharp     LanguageName = "csharp"
 	CSS        LanguageName = "css"
 	Cue        LanguageName = "cue"
 	Dockerfile LanguageName = "dockerfile"
+	DotEnv     LanguageName = "dotenv"
 	Elixir     LanguageName = "elixir"
 	Elm        LanguageName = "elm"
+	EmacsLisp  LanguageName = "elisp"
+	Erlang     LanguageName = "erlang"
+	Fortran    LanguageName = "fortran"
+	FSharp     LanguageName = "fsharp"
 	Go         LanguageName = "go"
+	Gradle     LanguageName = "gradle"
 	Groovy     LanguageName = "groovy"
 	HCL        LanguageName = "hcl"
+	HL7        LanguageName = "hl7"
 	HTML       LanguageName = "html"
+	INI        LanguageName = "ini"
 	Java       LanguageName = "java"
 	JavaScript LanguageName = "javascript"
+	Jenkins    LanguageName = "jenkins"
 	Kotlin     LanguageName = "kotlin"
+	Linker     LanguageName = "linker"
 	Lua        LanguageName = "lua"
+	Make       LanguageName = "make"
 	Markdown   LanguageName = "markdown"
+	MATLAB     LanguageName = "matlab"
+	Maven      LanguageName = "maven"
+	NDJSON     LanguageName = "ndjson"
+	Nginx      LanguageName = "nginx"
+	Nix        LanguageName = "nix"
+	ObjectiveC LanguageName = "objc"
 	OCaml      LanguageName = "ocaml"
+	Pascal     LanguageName = "pascal"
+	Perl       LanguageName = "perl"
 	PHP        LanguageName = "php"
+	PowerShell LanguageName = "powershell"
+	Properties LanguageName = "properties"
 	Protobuf   LanguageName = "protobuf"
 	Python     LanguageName = "python"
+	RIS        LanguageName = "ris"
+	Robots     LanguageName = "robots"
 	Ruby       LanguageName = "ruby"
 	Rust       LanguageName = "rust"
+	Salesforce LanguageName = "salesforce"
 	Scala      LanguageName = "scala"
+	Scheme     LanguageName = "scheme"
 	SQL        LanguageName = "sql"
+	Starlark   LanguageName = "starlark"
 	Svelte     LanguageName = "svelte"
 	Swift      LanguageName = "swift"
 	TOML       LanguageName = "toml"
 	TypeScript LanguageName = "typescript"
+	VBNet      LanguageName = "vbnet"
+	Verilog    LanguageName = "verilog"
+	VHDL       LanguageName = "vhdl"
 	YAML       LanguageName = "yaml"
 	Generic    LanguageName = "generic" // Fallback for unsupported languages
 )
@@ -1,209 +1,287 @@
 package languages
 
-import (
-	"strings"
-
-	sitter "github.com/smacker/go-tree-sitter"
-	"github.com/smacker/go-tree-sitter/bash"
-	"github.com/smacker/go-tree-sitter/c"
-	"github.com/smacker/go-tree-sitter/cpp"
-	"github.com/smacker/go-tree-sitter/csharp"
-	"github.com/smacker/go-tree-sitter/css"
-	"github.com/smacker/go-tree-sitter/cue"
-	"github.com/smacker/go-tree-sitter/dockerfile"
-	"github.com/smacker/go-tree-sitter/elixir"
-	"github.com/smacker/go-tree-sitter/elm"
-	"github.com/smacker/go-tree-sitter/golang"
-	"github.com/smacker/go-tree-sitter/groovy"
-	"github.com/smacker/go-tree-sitter/hcl"
-	"github.com/smacker/go-tree-sitter/html"
-	"github.com/smacker/go-tree-sitter/java"
-	"github.com/smacker/go-tree-sitter/javascript"
-	"github.com/smacker/go-tree-sitter/kotlin"
-	"github.com/smacker/go-tree-sitter/lua"
-	tree_sitter_markdown "github.com/smacker/go-tree-sitter/markdown/tree-sitter-markdown"
-	"github.com/smacker/go-tree-sitter/ocaml"
-	"github.com/smacker/go-tree-sitter/php"
-	"github.com/smacker/go-tree-sitter/protobuf"
-	"github.com/smacker/go-tree-sitter/python"
-	"github.com/smacker/go-tree-sitter/ruby"
-	"github.com/smacker/go-tree-sitter/rust"
-	"github.com/smacker/go-tree-sitter/scala"
-	"github.com/smacker/go-tree-sitter/sql"
-	"github.com/smacker/go-tree-sitter/svelte"
-	"github.com/smacker/go-tree-sitter/swift"
-	"github.com/smacker/go-tree-sitter/toml"
-	"github.com/smacker/go-tree-sitter/typescript/typescript"
-	"github.com/smacker/go-tree-sitter/yaml"
-)
-
-// LanguageConfig represents a language configuration.
-type LanguageConfig struct {
-	Name       LanguageName
-	Extensions []string
-	GetParser  func() *sitter.Language
-}
+import "strings"
 
 var registry = map[string]LanguageConfig{
+	"ada": {
+		Name:       Ada,
+		Extensions: []string{".adb", ".ads", ".ada"},
+	},
+	"apache": {
+		Name:       Apache,
+		Extensions: []string{".htaccess", "httpd.conf", "apache2.conf"},
+	},
+	"apex": {
+		Name:       Apex,
+		Extensions: []string{".cls", ".trigger"},
+	},
+	"assembly": {
+		Name:       Assembly,
+		Extensions: []string{".s", ".asm"},
+	},
 	"bash": {
 		Name:       Bash,
 		Extensions: []string{".sh", ".bash"},
-		GetParser:  bash.GetLanguage,
+	},
+	"bibtex": {
+		Name:       BibTeX,
+		Extensions: []string{".bib"},
 	},
 	"c": {
 		Name:       C,
 		Extensions: []string{".c", ".h"},
-		GetParser:  c.GetLanguage,
+	},
+	"caddy": {
+		Name:       Caddy,
+		Extensions: []string{"Caddyfile", ".caddyfile"},
+	},
+	"clojure": {
+		Name:       Clojure,
+		Extensions: []string{".clj", ".cljs", ".cljc", ".edn"},
+	},
+	"cmake": {
+		Name:       CMake,
+		Extensions: []string{"CMakeLists.txt", ".cmake"},
+	},
+	"cobol": {
+		Name:       COBOL,
+		Extensions: []string{".cbl", ".cob", ".cpy"},
 	},
 	"cpp": {
 		Name:       CPP,
 		Extensions: []string{".cpp", ".cc", ".cxx", ".hpp", ".h", ".hh", ".hxx"},
-		GetParser:  cpp.GetLanguage,
 	},
 	"csharp": {
 		Name:       CSharp,
 		Extensions: []string{".cs"},
-		GetParser:  csharp.GetLanguage,
 	},
 	"css": {
 		Name:       CSS,
 		Extensions: []string{".css"},
-		GetParser:  css.GetLanguage,
 	},
 	"cue": {
 		Name:       Cue,
 		Extensions: []string{".cue"},
-		GetParser:  cue.GetLanguage,
 	},
 	"dockerfile": {
 		Name:       Dockerfile,
 		Extensions: []string{"Dockerfile", ".dockerfile"},
-		GetParser:  dockerfile.GetLanguage,
+	},
+	"dotenv": {
+		Name:       DotEnv,
+		Extensions: []string{".env", ".env.local", ".env.development", ".env.production", ".env.test", ".env.example"},
 	},
 	"elixir": {
 		Name:       Elixir,
 		Extensions: []string{".ex", ".exs"},
-		GetParser:  elixir.GetLanguage,
 	},
 	"elm": {
 		Name:       Elm,
 		Extensions: []string{".elm"},
-		GetParser:  elm.GetLanguage,
+	},
+	"elisp": {
+		Name:       EmacsLisp,
+		Extensions: []string{".el"},
+	},
+	"erlang": {
+		Name:       Erlang,
+		Extensions: []string{".erl", ".hrl"},
+	},
+	"fortran": {
+		Name:       Fortran,
+		Extensions: []string{".f", ".for", ".f77", ".f90", ".f95", ".f03", ".f08"},
+	},
+	"fsharp": {
+		Name:       FSharp,
+		Extensions: []string{".fs", ".fsi", ".fsx"},
 	},
 	"go": {
 		Name:       Go,
 		Extensions: []string{".go"},
-		GetParser:  golang.GetLanguage,
+	},
+	"gradle": {
+		Name:       Gradle,
+		Extensions: []string{".gradle", "build.gradle.kts", "settings.gradle.kts"},
 	},
 	"groovy": {
 		Name:       Groovy,
-		Extensions: []string{".groovy", ".gradle"},
-		GetParser:  groovy.GetLanguage,
+		Extensions: []string{".groovy"},
 	},
 	"hcl": {
 		Name:       HCL,
 		Extensions: []string{".hcl", ".tf"},
-		GetParser:  hcl.GetLanguage,
+	},
+	"hl7": {
+		Name:       HL7,
+		Extensions: []string{".hl7"},
 	},
 	"html": {
 		Name:       HTML,
 		Extensions: []string{".html", ".htm"},
-		GetParser:  html.GetLanguage,
+	},
+	"ini": {
+		Name:       INI,
+		Extensions: []string{".ini", ".cfg", ".editorconfig", ".gitconfig"},
 	},
 	"java": {
 		Name:       Java,
 		Extensions: []string{".java"},
-		GetParser:  java.GetLanguage,
 	},
 	"javascript": {
 		Name:       JavaScript,
 		Extensions: []string{".js", ".jsx", ".mjs", ".cjs"},
-		GetParser:  javascript.GetLanguage,
+	},
+	"jenkins": {
+		Name:       Jenkins,
+		Extensions: []string{"Jenkinsfile", ".jenkinsfile"},
 	},
 	"kotlin": {
 		Name:       Kotlin,
 		Extensions: []string{".kt", ".kts"},
-		GetParser:  kotlin.GetLanguage,
+	},
+	"linker": {
+		Name:       Linker,
+		Extensions: []string{".ld", ".lds"},
 	},
 	"lua": {
 		Name:       Lua,
 		Extensions: []string{".lua"},
-		GetParser:  lua.GetLanguage,
+	},
+	"make": {
+		Name:       Make,
+		Extensions: []string{"Makefile", "makefile", "GNUmakefile", ".mk", ".mak"},
 	},
 	"markdown": {
 		Name:       Markdown,
-		Extensions: []string{".md", ".markdown"},
-		GetParser:  tree_sitter_markdown.GetLanguage,
+		Extensions: []string{".md", ".markdown", ".mdx"},
+	},
+	"matlab": {
+		Name:       MATLAB,
+		Extensions: []string{".m"},
+	},
+	"maven": {
+		Name:       Maven,
+		Extensions: []string{"pom.xml"},
+	},
+	"ndjson": {
+		Name:       NDJSON,
+		Extensions: []string{".ndjson", ".jsonl"},
+	},
+	"nginx": {
+		Name:       Nginx,
+		Extensions: []string{"nginx.conf", ".nginx"},
+	},
+	"nix": {
+		Name:       Nix,
+		Extensions: []string{".nix"},
+	},
+	"objc": {
+		Name:       ObjectiveC,
+		Extensions: []string{".mm"},
 	},
 	"ocaml": {
 		Name:       OCaml,
 		Extensions: []string{".ml", ".mli"},
-		GetParser:  ocaml.GetLanguage,
+	},
+	"pascal": {
+		Name:       Pascal,
+		Extensions: []string{".pas", ".dpr", ".dpk", ".lpr"},
+	},
+	"perl": {
+		Name:       Perl,
+		Extensions: []string{".pl", ".pm", ".t", ".pod"},
 	},
 	"php": {
 		Name:       PHP,
 		Extensions: []string{".php", ".phtml"},
-		GetParser:  php.GetLanguage,
+	},
+	"powershell": {
+		Name:       PowerShell,
+		Extensions: []string{".ps1", ".psm1", ".psd1"},
+	},
+	"properties": {
+		Name:       Properties,
+		Extensions: []string{".properties"},
 	},
 	"protobuf": {
 		Name:       Protobuf,
 		Extensions: []string{".proto"},
-		GetParser:  protobuf.GetLanguage,
 	},
 	"python": {
 		Name:       Python,
 		Extensions: []string{".py", ".pyi", ".pyw"},
-		GetParser:  python.GetLanguage,
+	},
+	"ris": {
+		Name:       RIS,
+		Extensions: []string{".ris"},
+	},
+	"robots": {
+		Name:       Robots,
+		Extensions: []string{"robots.txt"},
 	},
 	"ruby": {
 		Name:       Ruby,
 		Extensions: []string{".rb", ".rake", ".gemspec"},
-		GetParser:  ruby.GetLanguage,
 	},
 	"rust": {
 		Name:       Rust,
 		Extensions: []string{".rs"},
-		GetParser:  rust.GetLanguage,
+	},
+	"salesforce": {
+		Name:       Salesforce,
+		Extensions: []string{".object", ".object-meta.xml", ".field-meta.xml", ".layout", ".layout-meta.xml", ".profile", ".profile-meta.xml", ".permissionset", ".permissionset-meta.xml"},
 	},
 	"scala": {
 		Name:       Scala,
 		Extensions: []string{".scala", ".sc"},
-		GetParser:  scala.GetLanguage,
+	},
+	"scheme": {
+		Name:       Scheme,
+		Extensions: []string{".scm", ".ss", ".sld", ".rkt"},
 	},
 	"sql": {
 		Name:       SQL,
 		Extensions: []string{".sql"},
-		GetParser:  sql.GetLanguage,
+	},
+	"starlark": {
+		Name:       Starlark,
+		Extensions: []string{".bzl", ".star", ".bazel", "BUILD", "WORKSPACE"},
 	},
 	"svelte": {
 		Name:       Svelte,
 		Extensions: []string{".svelte"},
-		GetParser:  svelte.GetLanguage,
 	},
 	"swift": {
 		Name:       Swift,
 		Extensions: []string{".swift"},
-		GetParser:  swift.GetLanguage,
 	},
 	"toml": {
 		Name:       TOML,
 		Extensions: []string{".toml"},
-		GetParser:  toml.GetLanguage,
 	},
 	"typescript": {
 		Name:       TypeScript,
 		Extensions: []string{".ts", ".tsx"},
-		GetParser:  typescript.GetLanguage,
+	},
+	"vbnet": {
+		Name:       VBNet,
+		Extensions: []string{".vb"},
+	},
+	"verilog": {
+		Name:       Verilog,
+		Extensions: []string{".v", ".vh", ".sv", ".svh"},
+	},
+	"vhdl": {
+		Name:       VHDL,
+		Extensions: []string{".vhd", ".vhdl"},
 	},
 	"yaml": {
 		Name:       YAML,
 		Extensions: []string{".yaml", ".yml"},
-		GetParser:  yaml.GetLanguage,
 	},
 	"generic": {
 		Name:       Generic,
 		Extensions: []string{}, // Generic doesn't have specific extensions
-		GetParser:  nil,        // Generic doesn't use tree-sitter
 	},
 }
 
@@ -223,15 +301,24 @@ func DetectLanguage(filepath string) (LanguageConfig, bool) {
 		}
 	}
 
-	// Check for exact filename matches (e.g., "Dockerfile")
+	// Check for exact filename matches (e.g., "Dockerfile") and extensions of
+	// several parts (e.g., ".object-meta.xml") first, so that
+	// "build.gradle.kts" isn't detected as Kotlin by its extension
 	filename := filepath
 	if idx := strings.LastIndex(filepath, "/"); idx >= 0 {
 		filename = filepath[idx+1:]
 	}
+	for _, lang := range registry {
+		for _, langExt := range lang.Extensions {
+			if filename == langExt || strings.Count(langExt, ".") > 1 && strings.HasPrefix(langExt, ".") && strings.HasSuffix(strings.ToLower(filename), langExt) {
+				return lang, true
+			}
+		}
+	}
 
 	for _, lang := range registry {
 		for _, langExt := range lang.Extensions {
-			if ext == langExt || filename == langExt {
+			if ext == langExt {
 				return lang, true
 			}
 		}
@@ -0,0 +1,324 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+// MetadataDivision is the metadata key of the COBOL division holding the
+// chunk, such as "PROCEDURE".
+const MetadataDivision = "division"
+
+// Levels of the headers of COBOL code, from the outermost.
+const (
+	cobolDivisionLevel = iota
+	cobolSectionLevel
+	cobolParagraphLevel // Paragraphs and data entries such as 01 records
+)
+
+var (
+	// cobolFree matches COBOL code in free format, which has a directive
+	// saying so or starts a division or section in the sequence area.
+	cobolFree = regexp.MustCompile(`(?im)^[ \t]*>>[ \t]*SOURCE[ \t]+(?:FORMAT[ \t]+)?(?:IS[ \t]+)?FREE\b|^[A-Z0-9][A-Z0-9-]*[ \t]+(?:DIVISION|SECTION)\b`)
+	// cobolHeading matches the text of a COBOL division or section header,
+	// such as "PROCEDURE DIVISION USING X." or "MAIN-LOGIC SECTION.", and its
+	// name and kind.
+	cobolHeading = regexp.MustCompile(`(?i)^([A-Z0-9][A-Z0-9-]*)[ \t]+(DIVISION|SECTION)\b`)
+	// cobolParagraph matches the text of a COBOL paragraph header, such as
+	// "MAIN-PARA.", or of a data entry, such as "01 CUSTOMER-RECORD.", and
+	// its name.
+	cobolParagraph = regexp.MustCompile(`(?i)^(?:([A-Z0-9][A-Z0-9-]*)[ \t]*\.[ \t]*$|(?:FD|SD|RD|01|77)[ \t]+([A-Z0-9][A-Z0-9-]*))`)
+	// fortranUnit matches the text of the statement starting a Fortran
+	// program unit or procedure, such as "recursive subroutine solve(a)" or
+	// "real(8) function norm(v)", and its kind and name.
+	fortranUnit = regexp.MustCompile(`(?i)^(?:(?:recursive|non_recursive|pure|impure|elemental|module)[ \t]+|(?:integer|real|logical|complex|character|double[ \t]+precision|type|class)(?:[ \t]*\([^)]*\)|[ \t]*\*[ \t]*\d+)?[ \t]+)*(program|module|submodule|subroutine|function|block[ \t]*data)\b[ \t]*(?:\([^)]*\)[ \t]*)?([A-Za-z_]\w*)?`)
+	// fortranEnd matches the text of the statement ending a Fortran program
+	// unit or procedure, such as "end subroutine solve" or a bare "END".
+	fortranEnd = regexp.MustCompile(`(?i)^end(?:[ \t]*(?:program|module|submodule|subroutine|function|block[ \t]*data)(?:[ \t]+\w+)?)?[ \t]*$`)
+)
+
+// cobolParagraphWords are the statements that may stand alone on a line of
+// COBOL in free format like a paragraph header.
+var cobolParagraphWords = map[string]bool{
+	"EXIT":     true,
+	"GOBACK":   true,
+	"CONTINUE": true,
+}
+
+// fortranContainers are the kinds of Fortran program unit that hold
+// procedures after their CONTAINS statement.
+var fortranContainers = map[string]bool{
+	"program":   true,
+	"module":    true,
+	"submodule": true,
+}
+
+// cobolSpans returns the divisions of the COBOL code, such as its DATA and
+// PROCEDURE divisions, recording MetadataDivision, each with the comment
+// lines right above it. The columns of fixed format are respected: the
+// sequence area and the identification area past column 72 are ignored, and
+// headers start in area A.
+func cobolSpans(code string) []textSpan {
+	return cobolUnits(code, 0, cobolDivisionLevel, !cobolFree.MatchString(code))
+}
+
+// cobolNested returns the sections of the COBOL division, recording
+// MetadataSection, or the paragraphs and data entries of the section or
+// division without sections, recording MetadataDefinition.
+func cobolNested(unit string) []textSpan {
+	fixed := !cobolFree.MatchString(unit)
+	for offset := 0; offset < len(unit); {
+		line, next := codeLine(unit, offset)
+		if level, _, ok := cobolHeader(line, fixed); ok {
+			return cobolUnits(unit, next, level+1, fixed)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// cobolUnits returns the units of the COBOL code from offset on at the
+// outermost level of header from level on, each running up to the comment
+// lines above the next header at that level.
+func cobolUnits(code string, offset, level int, fixed bool) []textSpan {
+	outermost := -1
+	for scan := offset; scan < len(code); {
+		line, next := codeLine(code, scan)
+		if l, _, ok := cobolHeader(line, fixed); ok && l >= level && (outermost < 0 || l < outermost) {
+			outermost = l
+		}
+		scan = next
+	}
+
+	var spans []textSpan
+	from := offset // End of the line of the last header
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		l, name, ok := cobolHeader(line, fixed)
+		if !ok || l != outermost {
+			offset = next
+			continue
+		}
+
+		start := cobolComments(code, offset, from, fixed)
+		if n := len(spans); n > 0 {
+			spans[n-1].end = start
+		}
+		key := MetadataDefinition
+		switch l {
+		case cobolDivisionLevel:
+			key = MetadataDivision
+		case cobolSectionLevel:
+			key = MetadataSection
+		}
+		spans = append(spans, textSpan{start: start, end: len(code), metadata: map[string]string{key: name}})
+		from, offset = next, next
+	}
+	return spans
+}
+
+// cobolHeader reports whether the line of COBOL is the header of a division,
+// section, paragraph, or data entry starting in area A, and returns its
+// level and its name in upper case.
+func cobolHeader(line string, fixed bool) (int, string, bool) {
+	text, comment := cobolText(line, fixed)
+	trimmed := strings.TrimLeft(text, " \t")
+	if comment || trimmed == "" || fixed && len(text)-len(trimmed) >= 4 {
+		return 0, "", false
+	}
+	if m := cobolHeading.FindStringSubmatch(trimmed); m != nil {
+		if strings.EqualFold(m[2], "DIVISION") {
+			return cobolDivisionLevel, strings.ToUpper(m[1]), true
+		}
+		return cobolSectionLevel, strings.ToUpper(m[1]), true
+	}
+	if m := cobolParagraph.FindStringSubmatch(trimmed); m != nil {
+		name := strings.ToUpper(m[1] + m[2])
+		if cobolParagraphWords[name] || strings.HasPrefix(name, "END-") {
+			return 0, "", false
+		}
+		return cobolParagraphLevel, name, true
+	}
+	return 0, "", false
+}
+
+// cobolText returns the text of the line of COBOL, from area A through area
+// B in fixed format, and reports whether the line is a comment. Continuation
+// lines have no text of their own.
+func cobolText(line string, fixed bool) (string, bool) {
+	line = strings.TrimRight(line, "\r")
+	if !fixed {
+		return line, strings.HasPrefix(strings.TrimSpace(line), "*>")
+	}
+	if len(line) <= 7 {
+		return "", false
+	}
+	switch line[6] {
+	case '*', '/':
+		return "", true
+	case '-':
+		return "", false
+	}
+	text := line[7:min(len(line), 72)]
+	return text, strings.HasPrefix(strings.TrimSpace(text), "*>")
+}
+
+// cobolComments returns the start of the comment lines right above the line
+// at offset of the COBOL code, not before from, or offset if there are none.
+func cobolComments(code string, offset, from int, fixed bool) int {
+	for offset > from {
+		prev := strings.LastIndexByte(code[:offset-1], '\n') + 1
+		if _, comment := cobolText(code[prev : offset-1], fixed); prev < from || !comment {
+			break
+		}
+		offset = prev
+	}
+	return offset
+}
+
+// fortranSpans returns the program units of the Fortran code, such as its
+// programs, modules, and external procedures, each with the comment lines
+// right above it, in fixed or free form. Programs and modules record their
+// name as MetadataBlock, and subroutines and functions record
+// MetadataDefinition.
+func fortranSpans(code string) []textSpan {
+	return fortranUnits(code, 0, fortranFixed(code))
+}
+
+// fortranNested returns the procedures the Fortran program unit contains,
+// after the statement starting it.
+func fortranNested(unit string) []textSpan {
+	fixed := fortranFixed(unit)
+	for offset := 0; offset < len(unit); {
+		line, next := codeLine(unit, offset)
+		if fortranUnit.MatchString(fortranText(line, fixed)) {
+			return fortranUnits(unit, next, fixed)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// fortranUnits returns the program units of the Fortran code started on the
+// lines from offset on, each up to the END statement matching it.
+func fortranUnits(code string, offset int, fixed bool) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := fortranUnit.FindStringSubmatch(fortranText(line, fixed))
+		// A module procedure statement names procedures of an interface
+		// rather than starting a module
+		if m == nil || strings.EqualFold(m[2], "procedure") {
+			offset = next
+			continue
+		}
+		end := fortranUnitEnd(code, next, fixed)
+		if end < 0 {
+			offset = next
+			continue
+		}
+
+		kind := strings.ToLower(m[1])
+		name := m[2]
+		if name == "" {
+			name = kind
+		}
+		metadata := map[string]string{MetadataDefinition: name}
+		if fortranContainers[kind] || strings.HasPrefix(kind, "block") {
+			metadata = map[string]string{MetadataBlock: name}
+		}
+		spans = append(spans, textSpan{
+			start:    fortranComments(code, offset, spanEnd(spans), fixed),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// fortranUnitEnd returns the offset after the END statement of the Fortran
+// program unit whose statements after the first start at offset in code, or
+// -1 if it isn't ended.
+func fortranUnitEnd(code string, offset int, fixed bool) int {
+	depth := 1
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		text := fortranText(line, fixed)
+		if m := fortranUnit.FindStringSubmatch(text); m != nil && !strings.EqualFold(m[2], "procedure") {
+			depth++
+		} else if fortranEnd.MatchString(text) {
+			if depth--; depth == 0 {
+				return next
+			}
+		}
+		offset = next
+	}
+	return -1
+}
+
+// fortranText returns the statement text of the line of Fortran without its
+// comment, or "" for comment lines and continuation lines of fixed form. Lines
+// of fixed form starting with a tab have their statement right after it.
+func fortranText(line string, fixed bool) string {
+	line = strings.TrimRight(line, "\r")
+	if fixed {
+		switch {
+		case line == "" || strings.ContainsRune("Cc*!", rune(line[0])):
+			return ""
+		case line[0] == '\t':
+			line = line[1:]
+		case len(line) <= 6 || line[5] != ' ' && line[5] != '0':
+			return ""
+		default:
+			line = line[6:min(len(line), 72)]
+		}
+	}
+
+	var quote byte
+	for i := 0; i < len(line); i++ {
+		switch c := line[i]; {
+		case quote != 0:
+			if c == quote {
+				quote = 0
+			}
+		case c == '\'' || c == '"':
+			quote = c
+		case c == '!':
+			line = line[:i]
+		}
+	}
+	return strings.TrimSpace(line)
+}
+
+// fortranComments returns the start of the comment lines right above the
+// line at offset of the Fortran code, not before from, or offset if there are
+// none.
+func fortranComments(code string, offset, from int, fixed bool) int {
+	for offset > from {
+		prev := strings.LastIndexByte(code[:offset-1], '\n') + 1
+		line := code[prev : offset-1]
+		if prev < from || strings.TrimSpace(line) == "" || fortranText(line, fixed) != "" {
+			break
+		}
+		offset = prev
+	}
+	return offset
+}
+
+// fortranFixed reports whether the Fortran code is in fixed form: its lines
+// are comments, starting with C, c, *, or !, or leave the first five
+// columns to a statement label, or start with a tab.
+func fortranFixed(code string) bool {
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		offset = next
+		if strings.TrimSpace(line) == "" || strings.ContainsRune("Cc*!\t", rune(line[0])) {
+			continue
+		}
+		if strings.TrimLeft(line[:min(len(line), 5)], " 0123456789") != "" {
+			return false
+		}
+	}
+	return true
+}
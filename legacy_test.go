@@ -0,0 +1,82 @@
+package chunkx
+
+import (
+	"fmt"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_COBOL(t *testing.T) {
+	// Fixed format, with sequence numbers and an identification area past
+	// column 72
+	code := "000100 IDENTIFICATION DIVISION.\n" +
+		"000200 PROGRAM-ID. PAYROLL.\n" +
+		"000300 DATA DIVISION.\n" +
+		"000400 WORKING-STORAGE SECTION.\n" +
+		"000500 01  WS-TOTAL        PIC 9(5) VALUE 0.\n" +
+		"000600 PROCEDURE DIVISION.\n" +
+		"000700* Adds up the pay\n" +
+		fmt.Sprintf("%-72s%s\n", "000800 MAIN-PARA.", "PAYROLL1") +
+		"000900     PERFORM ADD-PAY.\n" +
+		"001000     STOP RUN.\n" +
+		"001100 ADD-PAY.\n" +
+		"001200     ADD 1 TO WS-TOTAL.\n"
+	testChunkMetadata(t, code, languages.COBOL, 14, []struct{ prefix, key, value string }{
+		{"000100 IDENTIFICATION DIVISION.\n", MetadataDivision, "IDENTIFICATION"},
+		{"000300 DATA DIVISION.\n000400 WORKING-STORAGE SECTION.\n", MetadataDivision, "DATA"},
+		{"000700* Adds up the pay\n000800 MAIN-PARA.", MetadataDefinition, "MAIN-PARA"},
+		{"000700* Adds up the pay\n000800 MAIN-PARA.", MetadataDivision, "PROCEDURE"},
+		{"001100 ADD-PAY.\n", MetadataDefinition, "ADD-PAY"},
+	})
+}
+
+func TestChunker_Fortran(t *testing.T) {
+	free := `! Linear algebra helpers
+module linalg
+  implicit none
+contains
+  ! Euclidean norm of v
+  pure real(8) function norm(v)
+    real(8), intent(in) :: v(:)
+    norm = sqrt(sum(v**2))
+  end function norm
+
+  subroutine scale(v, a)
+    real(8), intent(inout) :: v(:)
+    real(8), intent(in) :: a
+    v = v * a
+  end subroutine
+end module linalg
+
+program main
+  use linalg
+  print *, norm([3d0, 4d0])
+end program main
+`
+	testChunkMetadata(t, free, languages.Fortran, 20, []struct{ prefix, key, value string }{
+		{"  ! Euclidean norm of v\n  pure real(8) function norm(v)\n", MetadataDefinition, "norm"},
+		{"  ! Euclidean norm of v\n  pure real(8) function norm(v)\n", MetadataBlock, "linalg"},
+		{"  subroutine scale(v, a)\n", MetadataDefinition, "scale"},
+		{"program main\n", MetadataBlock, "main"},
+	})
+
+	fixed := `C     Halves the elements of A
+      SUBROUTINE HALVE(A, N)
+      INTEGER N
+      REAL A(N)
+      DO 10 I = 1, N
+         A(I) = A(I) / 2
+   10 CONTINUE
+      END
+
+      PROGRAM TEST
+      REAL X(2)
+      CALL HALVE(X, 2)
+      END
+`
+	testChunkMetadata(t, fixed, languages.Fortran, 100, []struct{ prefix, key, value string }{
+		{"C     Halves the elements of A\n      SUBROUTINE HALVE(A, N)\n", MetadataDefinition, "HALVE"},
+		{"      PROGRAM TEST\n", MetadataBlock, "TEST"},
+	})
+}
@@ -0,0 +1,372 @@
+package chunkx
+
+import (
+	"strings"
+	"sync"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// MetadataLicenseHeader is the metadata key of the first chunk of a text whose
+// license header was stripped or excluded. Its value is the length of the
+// header in bytes.
+const MetadataLicenseHeader = "license_header"
+
+// DefaultBoilerplateFiles is the number of files a banner must start before
+// the Boilerplate of Config.Options treats it as a license header.
+const DefaultBoilerplateFiles = 3
+
+// LicensePolicy selects what happens to license headers.
+type LicensePolicy int
+
+const (
+	LicenseKeep    LicensePolicy = iota // Chunk license headers like other code
+	LicenseStrip                        // Leave license headers out of the chunks
+	LicenseExclude                      // Keep license headers in the first chunk without counting them toward its size
+)
+
+// licenseMarkers are phrases of license headers and copyright banners, as
+// fingerprint writes them.
+var licenseMarkers = []string{
+	"copyright",
+	"spdx license identifier",
+	"all rights reserved",
+	"licensed under",
+	"license version",
+	"public license",
+	"permission is hereby granted",
+}
+
+// commentSyntax is how comments are written in a language.
+type commentSyntax struct {
+	line   []string    // Markers starting line comments
+	blocks [][2]string // Delimiters of block comments
+	quotes string      // Quotes of strings, which can't hold comments
+}
+
+var (
+	cComments    = commentSyntax{line: []string{"//"}, blocks: [][2]string{{"/*", "*/"}}, quotes: "\"'`"}
+	hashComments = commentSyntax{line: []string{"#"}, quotes: `"'`}
+	htmlComments = commentSyntax{blocks: [][2]string{{"<!--", "-->"}}}
+	lispComments = commentSyntax{line: []string{";"}, blocks: [][2]string{{"#|", "|#"}}, quotes: `"`}
+)
+
+// commentSyntaxes are the comments of languages; others use those of Generic.
+var commentSyntaxes = map[languages.LanguageName]commentSyntax{
+	languages.Ada:        {line: []string{"--"}, quotes: `"`},
+	languages.Apache:     hashComments,
+	languages.Apex:       {line: []string{"//"}, blocks: [][2]string{{"/*", "*/"}}, quotes: `'`},
+	languages.Assembly:   {line: []string{";", "#", "//"}, blocks: [][2]string{{"/*", "*/"}}, quotes: `"'`},
+	languages.Bash:       hashComments,
+	languages.BibTeX:     {line: []string{"%"}},
+	languages.C:          cComments,
+	languages.Caddy:      hashComments,
+	languages.Clojure:    lispComments,
+	languages.CMake:      hashComments,
+	languages.COBOL:      {line: []string{"*>"}, quotes: `"'`},
+	languages.CPP:        cComments,
+	languages.CSharp:     cComments,
+	languages.CSS:        {blocks: [][2]string{{"/*", "*/"}}, quotes: `"'`},
+	languages.Cue:        {line: []string{"//"}, quotes: `"`},
+	languages.Dockerfile: hashComments,
+	languages.DotEnv:     hashComments,
+	languages.Elixir:     hashComments,
+	languages.Elm:        {line: []string{"--"}, blocks: [][2]string{{"{-", "-}"}}, quotes: `"`},
+	languages.EmacsLisp:  lispComments,
+	languages.Erlang:     {line: []string{"%"}, quotes: `"`},
+	languages.Fortran:    {line: []string{"!"}, quotes: `"'`},
+	languages.FSharp:     {line: []string{"//"}, blocks: [][2]string{{"(*", "*)"}}, quotes: `"`},
+	languages.Go:         cComments,
+	languages.Gradle:     cComments,
+	languages.Groovy:     cComments,
+	languages.HCL:        {line: []string{"#", "//"}, blocks: [][2]string{{"/*", "*/"}}, quotes: `"`},
+	languages.HTML:       htmlComments,
+	languages.INI:        {line: []string{";", "#"}, quotes: `"`},
+	languages.Java:       cComments,
+	languages.JavaScript: cComments,
+	languages.Jenkins:    cComments,
+	languages.Kotlin:     cComments,
+	languages.Linker:     {blocks: [][2]string{{"/*", "*/"}}, quotes: `"`},
+	languages.Lua:        {line: []string{"--"}, blocks: [][2]string{{"--[[", "]]"}}, quotes: `"'`},
+	languages.Make:       hashComments,
+	languages.Markdown:   htmlComments,
+	languages.MATLAB:     {line: []string{"%", "#"}, blocks: [][2]string{{"%{", "%}"}}, quotes: `"`},
+	languages.Maven:      htmlComments,
+	languages.Nginx:      hashComments,
+	languages.Nix:        {line: []string{"#"}, blocks: [][2]string{{"/*", "*/"}}, quotes: `"`},
+	languages.ObjectiveC: cComments,
+	languages.OCaml:      {blocks: [][2]string{{"(*", "*)"}}, quotes: `"`},
+	languages.Pascal:     {line: []string{"//"}, blocks: [][2]string{{"{", "}"}, {"(*", "*)"}}, quotes: `'`},
+	languages.Perl:       hashComments,
+	languages.PHP:        {line: []string{"//", "#"}, blocks: [][2]string{{"/*", "*/"}}, quotes: `"'`},
+	languages.PowerShell: {line: []string{"#"}, blocks: [][2]string{{"<#", "#>"}}, quotes: `"'`},
+	languages.Properties: {line: []string{"#", "!"}},
+	languages.Protobuf:   cComments,
+	languages.Python:     hashComments,
+	languages.Robots:     {line: []string{"#"}},
+	languages.Ruby:       {line: []string{"#"}, blocks: [][2]string{{"=begin", "=end"}}, quotes: `"'`},
+	languages.Rust:       {line: []string{"//"}, blocks: [][2]string{{"/*", "*/"}}, quotes: `"`},
+	languages.Salesforce: htmlComments,
+	languages.Scala:      cComments,
+	languages.Scheme:     lispComments,
+	languages.SQL:        {line: []string{"--"}, blocks: [][2]string{{"/*", "*/"}}, quotes: `"'`},
+	languages.Starlark:   hashComments,
+	languages.Svelte:     htmlComments,
+	languages.Swift:      cComments,
+	languages.TOML:       hashComments,
+	languages.TypeScript: cComments,
+	languages.VBNet:      {line: []string{"'"}, quotes: `"`},
+	languages.Verilog:    {line: []string{"//"}, blocks: [][2]string{{"/*", "*/"}}, quotes: `"`},
+	languages.VHDL:       {line: []string{"--"}, blocks: [][2]string{{"/*", "*/"}}, quotes: `"`},
+	languages.YAML:       hashComments,
+	languages.Generic:    {line: []string{"//", "#", "--", ";"}, blocks: [][2]string{{"/*", "*/"}, {"<!--", "-->"}}, quotes: "\"'`"},
+}
+
+// WithLicenseHeaders sets the policy for license headers and copyright
+// banners, which otherwise dominate the first chunk of every file. A license
+// header is the run of comments at the start of a text up to the last of its
+// paragraphs that mentions a copyright or license, or that a Boilerplate set
+// WithBoilerplate has seen repeated; a text that is nothing but comments has
+// none. The first chunk records the length of the header in its metadata
+// under MetadataLicenseHeader.
+func WithLicenseHeaders(policy LicensePolicy) Option {
+	return func(c *config) {
+		c.license = policy
+	}
+}
+
+// WithBoilerplate sets the Boilerplate that WithLicenseHeaders records the
+// leading comments of every text in, and recognizes repeated banners with.
+//
+// A ChunkPipeline records the banners of its files in the order it received
+// them, whatever the order its workers chunk them in, so which files keep a
+// banner doesn't depend on scheduling.
+func WithBoilerplate(b *Boilerplate) Option {
+	return func(c *config) {
+		// A nil Boilerplate must leave the interface nil
+		if b == nil {
+			c.boilerplate = nil
+			return
+		}
+		c.boilerplate = b
+	}
+}
+
+// banners recognizes the repeated banners leading texts.
+type banners interface {
+	// observe records that a text starts with a paragraph of the given
+	// fingerprint, and reports whether the paragraph is a repeated banner.
+	observe(key string) bool
+}
+
+// Boilerplate recognizes banners repeated across a corpus as license headers,
+// even when they mention no license. Share one across the calls and
+// pipelines chunking a corpus; it is safe for concurrent use.
+//
+// Paragraphs of comments are matched by their words, ignoring case,
+// punctuation, comment markers, and numbers such as years. A banner counts
+// once it starts the given number of texts, so the texts chunked before that
+// keep it; Add registers known banners up front.
+type Boilerplate struct {
+	minFiles int
+
+	mu    sync.Mutex
+	seen  map[string]int  // Number of texts started by each paragraph, by fingerprint
+	added map[string]bool // Banners registered with Add
+}
+
+// NewBoilerplate returns a Boilerplate recognizing banners that start at
+// least minFiles texts. A minFiles below 2 counts as 2.
+func NewBoilerplate(minFiles int) *Boilerplate {
+	return &Boilerplate{minFiles: max(minFiles, 2), seen: make(map[string]int), added: make(map[string]bool)}
+}
+
+// Add registers a banner, such as the license header of a project, so that
+// it is recognized from the first text it starts.
+func (b *Boilerplate) Add(banner string) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	key := fingerprint(banner)
+	b.seen[key] = b.minFiles
+	b.added[key] = true
+}
+
+// observe implements banners.
+func (b *Boilerplate) observe(key string) bool {
+	if key == "" {
+		return false
+	}
+
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	b.seen[key]++
+	return b.seen[key] >= b.minFiles
+}
+
+// snapshot records the banners leading text in b, as chunking text would,
+// and returns the banners b recognized in it then. Chunking text with the
+// snapshot rather than b recognizes the same banners whenever it happens.
+func (b *Boilerplate) snapshot(text string, lang languages.LanguageName) banners {
+	recorder := &bannerSnapshot{b: b, known: make(map[string]bool), recording: true}
+	licenseHeader(text, lang, recorder)
+	recorder.recording = false
+	return recorder
+}
+
+// bannerSnapshot is the banners a Boilerplate recognized in a text, recorded
+// while it observed the text.
+type bannerSnapshot struct {
+	b         *Boilerplate
+	known     map[string]bool
+	recording bool
+}
+
+// observe implements banners. Once recorded, paragraphs that weren't
+// observed then are banners only if they were added to the Boilerplate.
+func (s *bannerSnapshot) observe(key string) bool {
+	if s.recording {
+		repeated := s.b.observe(key)
+		s.known[key] = s.known[key] || repeated
+		return repeated
+	}
+	if key == "" {
+		return false
+	}
+	if s.known[key] {
+		return true
+	}
+	s.b.mu.Lock()
+	defer s.b.mu.Unlock()
+	return s.b.added[key]
+}
+
+// fingerprint returns the lowercase words of s, separated by single spaces.
+func fingerprint(s string) string {
+	var b strings.Builder
+	space := false
+	for _, r := range strings.ToLower(s) {
+		if r >= 'a' && r <= 'z' || r > 0x7f && !isSpace(r) {
+			if space && b.Len() > 0 {
+				b.WriteByte(' ')
+			}
+			b.WriteRune(r)
+			space = false
+		} else {
+			space = true
+		}
+	}
+	return b.String()
+}
+
+// isSpace reports whether r is Unicode white space.
+func isSpace(r rune) bool {
+	return strings.TrimSpace(string(r)) == ""
+}
+
+// licenseHeader returns the length of the license header at the start of
+// text, which ends at the start of a line, or 0 if it has none.
+func licenseHeader(text string, lang languages.LanguageName, b banners) int {
+	syntax, ok := commentSyntaxes[lang]
+	if !ok {
+		syntax = commentSyntaxes[languages.Generic]
+	}
+
+	header := 0   // End of the header found so far
+	start := -1   // Start of the paragraph of comments being read, if any
+	closing := "" // Delimiter closing the open block comment, if any
+	for offset := 0; offset < len(text); {
+		next := len(text)
+		if i := strings.IndexByte(text[offset:], '\n'); i >= 0 {
+			next = offset + i + 1
+		}
+		line := strings.TrimSpace(text[offset:next])
+
+		switch {
+		case closing != "":
+			if i := strings.Index(line, closing); i >= 0 {
+				if strings.TrimSpace(line[i+len(closing):]) != "" {
+					return header // Code follows the comment on its last line
+				}
+				closing = ""
+			}
+		case line == "":
+			if start >= 0 && isLicense(text[start:offset], b) {
+				header = next
+			} else if header > 0 && header == offset {
+				header = next // Blank lines after the header belong to it
+			}
+			start = -1
+		default:
+			var comment bool
+			if comment, closing = syntax.comment(line); !comment {
+				if start >= 0 && isLicense(text[start:offset], b) {
+					header = offset
+				}
+				return header
+			}
+			if start < 0 {
+				start = offset
+			}
+		}
+		offset = next
+	}
+
+	// A text of nothing but comments has no header to leave out
+	return 0
+}
+
+// comment reports whether a trimmed line starts a comment, and returns the
+// delimiter closing it if it is a block comment the line doesn't close.
+func (s commentSyntax) comment(line string) (bool, string) {
+	for _, delims := range s.blocks {
+		rest, ok := strings.CutPrefix(line, delims[0])
+		if !ok {
+			continue
+		}
+		i := strings.Index(rest, delims[1])
+		if i < 0 {
+			return true, delims[1]
+		}
+		return strings.TrimSpace(rest[i+len(delims[1]):]) == "", ""
+	}
+	for _, marker := range s.line {
+		if strings.HasPrefix(line, marker) {
+			return true, ""
+		}
+	}
+	return false, ""
+}
+
+// isLicense reports whether a paragraph of comments mentions a copyright or
+// license, or is a banner b has seen repeated.
+func isLicense(paragraph string, b banners) bool {
+	words := fingerprint(paragraph)
+	for _, marker := range licenseMarkers {
+		if strings.Contains(words, marker) {
+			return true
+		}
+	}
+	return b != nil && b.observe(words)
+}
+
+// shiftChunks moves chunks of a text that starts offset bytes and lines
+// lines into the text they are part of.
+func shiftChunks(chunks []Chunk, offset, lines int) {
+	for i := range chunks {
+		chunks[i].StartByte += offset
+		chunks[i].EndByte += offset
+		chunks[i].StartLine += lines
+		chunks[i].EndLine += lines
+	}
+}
+
+// DetectLicenseHeader returns the length of the license header at the start of
+// content, as WithLicenseHeaders finds it in code of the language, or 0 if
+// content starts with none.
+func DetectLicenseHeader(content []byte, lang languages.LanguageName) int {
+	text, bom, _ := prepareText(string(content))
+	if header := licenseHeader(text, lang, nil); header > 0 {
+		return bom + header
+	}
+	return 0
+}
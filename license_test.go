@@ -0,0 +1,184 @@
+package chunkx
+
+import (
+	"context"
+	"fmt"
+	"path/filepath"
+	"strconv"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestDetectLicenseHeader(t *testing.T) {
+	tests := []struct {
+		name string
+		lang languages.LanguageName
+		code string
+		body string // Start of the code after the header, or "" for none
+	}{
+		{"line comments", languages.Go, "// Copyright 2024 Acme\n// SPDX-License-Identifier: MIT\n\npackage main\n", "package main"},
+		{
+			"block comment", languages.Go,
+			"/*\nCopyright 2020 The Authors.\n\nLicensed under the Apache License, Version 2.0\n*/\n\n// Package foo does things.\npackage foo\n",
+			"// Package foo",
+		},
+		{"build constraint", languages.Go, "// Copyright 2024 Acme\n\n//go:build linux\n\npackage main\n", "//go:build"},
+		{"shebang", languages.Python, "#!/usr/bin/env python\n# Copyright 2024 Acme\nimport os\n", "import os"},
+		{"preprocessor", languages.C, "/* Copyright 2024 Acme */\n#include <stdio.h>\n", "#include"},
+		{"byte order mark", languages.TOML, "\uFEFF# Copyright 2024 Acme\n\nkey = 1\n", "key = 1"},
+		{"no license", languages.Go, "// Package main runs.\npackage main\n", ""},
+		{"only comments", languages.Go, "// Copyright 2024 Acme\n\n", ""},
+		{"code after comment", languages.C, "/* Copyright\n   2024 Acme */ int x;\n", ""},
+		{"leading blank lines", languages.Generic, "\n\nx = 1\n", ""},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			want := 0
+			if tt.body != "" {
+				want = strings.Index(tt.code, tt.body)
+			}
+			if got := DetectLicenseHeader([]byte(tt.code), tt.lang); got != want {
+				t.Errorf("header length = %d, want %d", got, want)
+			}
+		})
+	}
+}
+
+func TestBoilerplate(t *testing.T) {
+	b := NewBoilerplate(2)
+	texts := []string{
+		"// Acme Widgets internal build\n\nfunc a() {}\n",
+		"// ACME widgets: internal build!\n\nfunc b() {}\n",
+		"// Acme Widgets internal build\n\nfunc c() {}\n",
+	}
+	for i, text := range texts {
+		got := licenseHeader(text, languages.Go, b)
+		if want := strings.Index(text, "func"); i == 0 && got != 0 || i > 0 && got != want {
+			t.Errorf("header length of text %d = %d", i, got)
+		}
+	}
+
+	b = NewBoilerplate(10)
+	b.Add("Acme Widgets internal build")
+	if got := licenseHeader(texts[0], languages.Go, b); got != strings.Index(texts[0], "func") {
+		t.Errorf("header length of added banner = %d", got)
+	}
+}
+
+func TestWithLicenseHeaders(t *testing.T) {
+	code := "# Copyright 2024 Acme\n# Licensed under the MIT License\n\nalpha beta\ngamma"
+	header := strings.Index(code, "alpha")
+
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithMaxSize(3))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) < 2 {
+		t.Fatalf("expected the header to count toward chunk sizes, got %+v", chunks)
+	}
+
+	tests := []struct {
+		policy    LicensePolicy
+		content   string
+		startLine int
+		startByte int
+	}{
+		{LicenseStrip, "alpha beta\ngamma", 4, header},
+		{LicenseExclude, code, 1, 0},
+	}
+	for _, tt := range tests {
+		chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithMaxSize(3), WithLicenseHeaders(tt.policy))
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		if len(chunks) != 1 {
+			t.Fatalf("policy %d: expected 1 chunk, got %+v", tt.policy, chunks)
+		}
+
+		chunk := chunks[0]
+		if chunk.Content != tt.content {
+			t.Errorf("policy %d: content = %q, want %q", tt.policy, chunk.Content, tt.content)
+		}
+		if chunk.StartLine != tt.startLine || chunk.EndLine != 5 {
+			t.Errorf("policy %d: lines = %d-%d, want %d-5", tt.policy, chunk.StartLine, chunk.EndLine, tt.startLine)
+		}
+		if chunk.StartByte != tt.startByte || chunk.EndByte != len(code) {
+			t.Errorf("policy %d: bytes = %d-%d, want %d-%d", tt.policy, chunk.StartByte, chunk.EndByte, tt.startByte, len(code))
+		}
+		if got := chunk.Metadata[MetadataLicenseHeader]; got != strconv.Itoa(header) {
+			t.Errorf("policy %d: license header = %q, want %d", tt.policy, got, header)
+		}
+	}
+}
+
+func TestWithBoilerplate_Pipeline(t *testing.T) {
+	files := make(map[string]string)
+	for i := range 20 {
+		files[fmt.Sprintf("f%02d.go", i)] = fmt.Sprintf("// Acme Widgets internal build\n\npackage p\n\nfunc f%d() {}\n", i)
+	}
+	dir := writeTestFiles(t, files)
+
+	// The files before the banner is seen repeated keep it, whichever of the
+	// workers reaches them first
+	for range 5 {
+		b := NewBoilerplate(3)
+		pipeline := NewChunkPipeline(8, WithOrderedOutput(), WithLicenseHeaders(LicenseStrip), WithBoilerplate(b))
+		i := 0
+		for result := range pipeline.RunDir(context.Background(), dir) {
+			if result.Err != nil {
+				t.Fatalf("%s: unexpected error: %v", result.Path, result.Err)
+			}
+			kept := strings.HasPrefix(result.Chunks[0].Content, "// Acme")
+			if want := i < 2; kept != want {
+				t.Fatalf("%s: banner kept = %v, want %v", result.Path, kept, want)
+			}
+			i++
+		}
+	}
+}
+
+func TestWithBoilerplate_PipelineErrors(t *testing.T) {
+	files := make(map[string]string)
+	for i := range 20 {
+		files[fmt.Sprintf("f%02d.go", i)] = fmt.Sprintf("// Acme Widgets internal build\n\npackage p\n\nfunc f%d() {}\n", i)
+	}
+	dir := writeTestFiles(t, files)
+
+	// Files failing to read take no turn of their own, but must not let the
+	// files after them go before the ones ahead of them
+	for range 5 {
+		paths := make(chan string)
+		go func() {
+			defer close(paths)
+			for i := range 20 {
+				paths <- filepath.Join(dir, fmt.Sprintf("f%02d.go", i))
+				if i < 3 {
+					paths <- filepath.Join(dir, fmt.Sprintf("missing%d.go", i))
+				}
+			}
+		}()
+
+		b := NewBoilerplate(3)
+		pipeline := NewChunkPipeline(8, WithOrderedOutput(), WithLicenseHeaders(LicenseStrip), WithBoilerplate(b))
+		i := 0
+		for result := range pipeline.Run(context.Background(), paths) {
+			if strings.Contains(result.Path, "missing") {
+				if result.Err == nil {
+					t.Fatalf("%s: expected an error", result.Path)
+				}
+				continue
+			}
+			if result.Err != nil {
+				t.Fatalf("%s: unexpected error: %v", result.Path, result.Err)
+			}
+			kept := strings.HasPrefix(result.Chunks[0].Content, "// Acme")
+			if want := i < 2; kept != want {
+				t.Fatalf("%s: banner kept = %v, want %v", result.Path, kept, want)
+			}
+			i++
+		}
+	}
+}
@@ -0,0 +1,148 @@
+package chunkx
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"os"
+)
+
+// Limits that LimitExceeded reports.
+const (
+	LimitBytes  = "bytes"  // Limits.MaxBytes
+	LimitChunks = "chunks" // Limits.MaxChunks
+	LimitDepth  = "depth"  // Limits.MaxDepth
+	LimitSteps  = "steps"  // Limits.MaxSteps
+)
+
+// DefaultMaxReadBytes is the largest archive entry or object read into
+// memory when Limits.MaxBytes isn't set, so a compression bomb in an archive
+// or a huge object in a bucket fails instead of exhausting memory.
+const DefaultMaxReadBytes = 1 << 30
+
+// Limits bounds the resources spent chunking a single input, so adversarial
+// inputs such as deeply nested JSON or huge files fail with a LimitExceeded
+// error instead of exhausting the stack or memory. Zero fields are unlimited.
+type Limits struct {
+	MaxBytes  int // Largest input, in bytes
+	MaxChunks int // Most chunks of an input
+	MaxDepth  int // Deepest nesting of the syntax tree
+	MaxSteps  int // Most syntax tree nodes and lines visited while chunking
+}
+
+// WithLimits sets the limits of the resources spent chunking a single input.
+// Files that ChunkFile and ChunkPipeline read from disk are rejected by size
+// before they are read when they are larger than Limits.MaxBytes, and
+// archive entries and objects are read no further than the limit. Code passed
+// in memory is rejected before it is chunked. ChunkReader chunks streams of
+// any length, so MaxBytes doesn't apply to it.
+func WithLimits(limits Limits) Option {
+	return func(c *config) {
+		c.limits = limits
+	}
+}
+
+// LimitExceeded is the error of an input that exceeds one of its Limits. It
+// matches ErrLimitExceeded with errors.Is.
+type LimitExceeded struct {
+	Limit string // LimitBytes, LimitChunks, LimitDepth, or LimitSteps
+	Max   int    // The value of the limit
+}
+
+func (e *LimitExceeded) Error() string {
+	return fmt.Sprintf("%v: %s is limited to %d", ErrLimitExceeded, e.Limit, e.Max)
+}
+
+func (e *LimitExceeded) Unwrap() error {
+	return ErrLimitExceeded
+}
+
+// checkBytes fails if an input of n bytes is larger than the limit.
+func (l Limits) checkBytes(n int) error {
+	if l.MaxBytes > 0 && n > l.MaxBytes {
+		return &LimitExceeded{Limit: LimitBytes, Max: l.MaxBytes}
+	}
+	return nil
+}
+
+// checkChunks fails if an input has more chunks than the limit.
+func (l Limits) checkChunks(chunks []Chunk) error {
+	if l.MaxChunks > 0 && len(chunks) > l.MaxChunks {
+		return &LimitExceeded{Limit: LimitChunks, Max: l.MaxChunks}
+	}
+	return nil
+}
+
+// checkFileSize fails if the file at path is larger than the byte limit set
+// by opts, without reading it.
+func checkFileSize(path string, opts []Option) error {
+	cfg := newDefaultConfig()
+	for _, opt := range opts {
+		opt(cfg)
+	}
+	return cfg.limits.checkFile(path)
+}
+
+// checkFile fails if the file at path is larger than the byte limit, without
+// reading it. Files that can't be stat'ed pass, leaving the error to reading
+// them.
+func (l Limits) checkFile(path string) error {
+	if l.MaxBytes <= 0 {
+		return nil
+	}
+
+	info, err := os.Stat(path)
+	if err != nil || info.Size() <= int64(l.MaxBytes) {
+		return nil
+	}
+	return &FileError{Path: path, Kind: ErrorLimit, Err: &LimitExceeded{Limit: LimitBytes, Max: l.MaxBytes}}
+}
+
+// maxRead returns the most bytes of an archive entry or object read into
+// memory: Limits.MaxBytes, or DefaultMaxReadBytes if it isn't set.
+func (l Limits) maxRead() int {
+	if l.MaxBytes > 0 {
+		return l.MaxBytes
+	}
+	return DefaultMaxReadBytes
+}
+
+// readAtMost reads r to the end, failing with a LimitExceeded error without
+// reading further once it holds more than max bytes.
+func readAtMost(r io.Reader, max int) ([]byte, error) {
+	content, err := io.ReadAll(io.LimitReader(r, int64(max)+1))
+	if err != nil {
+		return nil, err
+	}
+	if len(content) > max {
+		return nil, &LimitExceeded{Limit: LimitBytes, Max: max}
+	}
+	return content, nil
+}
+
+// readErrorKind classifies an error reading an input: ErrorLimit if it was
+// larger than its limit, and ErrorIO otherwise.
+func readErrorKind(err error) ErrorKind {
+	if errors.Is(err, ErrLimitExceeded) {
+		return ErrorLimit
+	}
+	return ErrorIO
+}
+
+// stepBudget counts down the steps of Limits.MaxSteps left to the input
+// being chunked.
+type stepBudget struct {
+	left int
+	max  int
+}
+
+// step spends a step of the budget of cfg, failing once it is spent.
+func (cfg *config) step() error {
+	if cfg.steps == nil {
+		return nil
+	}
+	if cfg.steps.left--; cfg.steps.left < 0 {
+		return &LimitExceeded{Limit: LimitSteps, Max: cfg.steps.max}
+	}
+	return nil
+}
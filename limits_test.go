@@ -0,0 +1,64 @@
+package chunkx
+
+import (
+	"errors"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestWithLimits(t *testing.T) {
+	code := strings.Repeat("one two three\n", 100)
+
+	tests := []struct {
+		name   string
+		limits Limits
+		want   string // Limit exceeded, or "" for none
+	}{
+		{"unlimited", Limits{}, ""},
+		{"bytes", Limits{MaxBytes: 100}, LimitBytes},
+		{"enough bytes", Limits{MaxBytes: len(code)}, ""},
+		{"chunks", Limits{MaxChunks: 10}, LimitChunks},
+		{"enough chunks", Limits{MaxChunks: 100}, ""},
+		{"steps", Limits{MaxSteps: 50}, LimitSteps},
+		{"enough steps", Limits{MaxSteps: 1000}, ""},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithMaxSize(3), WithLimits(tt.limits))
+			if tt.want == "" {
+				if err != nil || len(chunks) != 100 {
+					t.Errorf("expected 100 chunks, got %d and %v", len(chunks), err)
+				}
+				return
+			}
+
+			var limitErr *LimitExceeded
+			if !errors.As(err, &limitErr) || limitErr.Limit != tt.want || !errors.Is(err, ErrLimitExceeded) {
+				t.Fatalf("expected the %s limit exceeded, got %v", tt.want, err)
+			}
+			if chunks != nil {
+				t.Errorf("expected no chunks, got %d", len(chunks))
+			}
+		})
+	}
+}
+
+func TestWithLimits_File(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"big.txt": strings.Repeat("x", 1000),
+	})
+
+	_, err := NewChunker().ChunkFile(filepath.Join(dir, "big.txt"), WithLimits(Limits{MaxBytes: 999}))
+	var fileErr *FileError
+	if !errors.As(err, &fileErr) || fileErr.Kind != ErrorLimit || !errors.Is(err, ErrLimitExceeded) {
+		t.Fatalf("expected a limit FileError, got %v", err)
+	}
+
+	if _, err := NewChunker().ChunkFile(filepath.Join(dir, "big.txt"), WithLimits(Limits{MaxBytes: 1000})); err != nil {
+		t.Errorf("unexpected error: %v", err)
+	}
+}
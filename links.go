@@ -0,0 +1,192 @@
+package chunkx
+
+import (
+	"regexp"
+	"sort"
+	"strconv"
+	"strings"
+	"unicode"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Metadata keys set on chunks of Markdown and HTML made with WithLinks.
+const (
+	MetadataAnchor = "anchor" // ID of the last heading or anchor at or before the start of the chunk
+	MetadataLinks  = "links"  // Space-separated targets of the links in the chunk, in order and without duplicates
+)
+
+var (
+	// atxHeading matches a Markdown heading such as "## Title {#custom-id}".
+	atxHeading = regexp.MustCompile(`^ {0,3}#{1,6}[ \t]+(.*?)(?:[ \t]+#+)?[ \t]*$`)
+	// setextUnderline matches the line under a Markdown heading of the line
+	// above it.
+	setextUnderline = regexp.MustCompile(`^ {0,3}(?:=+|-+)[ \t]*$`)
+	// headingID matches an explicit ID at the end of a Markdown heading.
+	headingID = regexp.MustCompile(`[ \t]*\{#([^}\s]+)\}$`)
+	// htmlAnchor matches an HTML tag with an id, or an <a> with a name.
+	htmlAnchor = regexp.MustCompile(`<(?:[a-zA-Z][a-zA-Z0-9-]*[^>]*?\sid|a[^>]*?\sname)\s*=\s*["']([^"']+)["']`)
+	// markdownLink matches the target of an inline Markdown link or image,
+	// an autolink, or a reference definition.
+	markdownLink = regexp.MustCompile(`\]\(\s*<?([^)\s>]+)>?(?:\s+["'(][^)]*)?\)|<((?:https?|mailto|ftp):[^>\s]+)>|^ {0,3}\[[^\]]+\]:\s*<?([^\s>]+)`)
+	// htmlLink matches the target of an HTML href attribute.
+	htmlLink = regexp.MustCompile(`\shref\s*=\s*["']([^"']+)["']`)
+)
+
+// WithLinks records in the metadata of chunks of Markdown and HTML the links
+// they contain and the ID of the section they start in, so a retrieved chunk
+// can link to "page.md#section" rather than just to its page. IDs are those
+// headings are given explicitly, with "{#id}" in Markdown or an id attribute
+// in HTML, or else the GitHub slugs of Markdown headings. Headings and links
+// in fenced code blocks are ignored.
+func WithLinks() Option {
+	return func(c *config) {
+		c.links = true
+	}
+}
+
+// linkRef is an anchor or a link target at an offset of a document.
+type linkRef struct {
+	offset int
+	value  string
+	level  int    // Level of a Markdown heading, or 0 for other anchors and links
+	title  string // Text of a Markdown heading
+}
+
+// addLinks records the anchors and links of code in its chunks, ignoring the
+// bytes before from, such as front matter.
+func addLinks(chunks []Chunk, code string, from int, lang languages.LanguageName) {
+	anchors, links := scanLinks(code, from, lang)
+	for i := range chunks {
+		chunk := &chunks[i]
+		var anchor string
+		if j := sort.Search(len(anchors), func(j int) bool { return anchors[j].offset > chunk.StartByte }); j > 0 {
+			anchor = anchors[j-1].value
+		}
+
+		var targets []string
+		seen := make(map[string]bool)
+		first := sort.Search(len(links), func(j int) bool { return links[j].offset >= chunk.StartByte })
+		for _, link := range links[first:] {
+			if link.offset >= chunk.EndByte {
+				break
+			}
+			if !seen[link.value] {
+				seen[link.value] = true
+				targets = append(targets, link.value)
+			}
+		}
+
+		if anchor == "" && len(targets) == 0 {
+			continue
+		}
+		if chunk.Metadata == nil {
+			chunk.Metadata = make(map[string]string, 2)
+		}
+		if anchor != "" {
+			chunk.Metadata[MetadataAnchor] = anchor
+		}
+		if len(targets) > 0 {
+			chunk.Metadata[MetadataLinks] = strings.Join(targets, " ")
+		}
+	}
+}
+
+// scanLinks returns the anchors and the link targets of code from the offset
+// from, in order of their offsets, skipping fenced code blocks of Markdown.
+func scanLinks(code string, from int, lang languages.LanguageName) (anchors, links []linkRef) {
+	markdown := lang == languages.Markdown
+	slugs := make(map[string]int) // Uses of each slug, which numbers repeated ones
+	var fence string              // Opening fence of the code block being read, if any
+	prev, prevStart := "", 0      // Previous line, for setext headings
+
+	for start := from; start < len(code); {
+		end := strings.IndexByte(code[start:], '\n')
+		if end < 0 {
+			end = len(code)
+		} else {
+			end += start
+		}
+		line := strings.TrimRight(code[start:end], "\r")
+		trimmed := strings.TrimLeft(line, " ")
+		text := line // The line as text a setext heading can be made of
+
+		switch {
+		case markdown && fence != "":
+			if strings.HasPrefix(trimmed, fence) && strings.TrimSpace(strings.TrimLeft(trimmed, fence[:1])) == "" {
+				fence = ""
+			}
+			text = ""
+		case markdown && (strings.HasPrefix(trimmed, "```") || strings.HasPrefix(trimmed, "~~~")):
+			fence = trimmed[:len(trimmed)-len(strings.TrimLeft(trimmed, trimmed[:1]))]
+			text = ""
+		default:
+			if markdown {
+				if m := atxHeading.FindStringSubmatch(line); m != nil {
+					level := len(trimmed) - len(strings.TrimLeft(trimmed, "#"))
+					anchors = append(anchors, linkRef{start, headingAnchor(m[1], slugs), level, headingTitle(m[1])})
+					text = ""
+				} else if strings.TrimSpace(prev) != "" && setextUnderline.MatchString(line) {
+					level := 1
+					if trimmed[0] == '-' {
+						level = 2
+					}
+					heading := strings.TrimSpace(prev)
+					anchors = append(anchors, linkRef{prevStart, headingAnchor(heading, slugs), level, headingTitle(heading)})
+					text = ""
+				}
+				for _, m := range markdownLink.FindAllStringSubmatchIndex(line, -1) {
+					for g := 2; g < len(m); g += 2 {
+						if m[g] >= 0 {
+							links = append(links, linkRef{offset: start + m[g], value: line[m[g]:m[g+1]]})
+						}
+					}
+				}
+			}
+			for _, m := range htmlAnchor.FindAllStringSubmatchIndex(line, -1) {
+				anchors = append(anchors, linkRef{offset: start + m[0], value: line[m[2]:m[3]]})
+			}
+			for _, m := range htmlLink.FindAllStringSubmatchIndex(line, -1) {
+				links = append(links, linkRef{offset: start + m[2], value: line[m[2]:m[3]]})
+			}
+		}
+
+		prev, prevStart = text, start
+		start = end + 1
+	}
+
+	// Setext headings are found a line late, and links of a line by kind
+	sort.SliceStable(anchors, func(i, j int) bool { return anchors[i].offset < anchors[j].offset })
+	sort.SliceStable(links, func(i, j int) bool { return links[i].offset < links[j].offset })
+	return anchors, links
+}
+
+// headingTitle returns the text of a Markdown heading without its explicit ID.
+func headingTitle(text string) string {
+	return strings.TrimSpace(headingID.ReplaceAllString(text, ""))
+}
+
+// headingAnchor returns the ID of a Markdown heading: its explicit ID, or else
+// its GitHub slug, numbered if an earlier heading has the same slug.
+func headingAnchor(text string, slugs map[string]int) string {
+	if m := headingID.FindStringSubmatch(text); m != nil {
+		return m[1]
+	}
+
+	var b strings.Builder
+	for _, r := range strings.ToLower(text) {
+		switch {
+		case unicode.IsLetter(r) || unicode.IsDigit(r) || r == '-' || r == '_':
+			b.WriteRune(r)
+		case r == ' ':
+			b.WriteByte('-')
+		}
+	}
+	slug := b.String()
+	n := slugs[slug]
+	slugs[slug]++
+	if n > 0 {
+		slug += "-" + strconv.Itoa(n)
+	}
+	return slug
+}
@@ -0,0 +1,81 @@
+package chunkx
+
+import (
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestScanLinks(t *testing.T) {
+	code := "Intro with [a link](https://example.com/a).\n\n" +
+		"## Getting Started\n\nSee [setup](setup.md#install \"Setup\") and <https://example.com/b>.\n\n" +
+		"```bash\n# not a heading\ncurl [x](https://example.com/code)\n```\n\n" +
+		"Custom {#custom}\n================\n\nText.\n\n" +
+		"## Getting Started\n\n<a id=\"legacy\"></a>\nUse the <a href=\"https://example.com/c\">docs</a>.\n\n" +
+		"[ref]: https://example.com/ref\n"
+
+	anchors, links := scanLinks(code, 0, languages.Markdown)
+	var got []string
+	for _, anchor := range anchors {
+		got = append(got, anchor.value)
+		if anchor.offset > strings.Index(code, "Text.") && anchor.value == "custom" {
+			t.Errorf("expected the setext heading at the line of its text, got offset %d", anchor.offset)
+		}
+	}
+	if want := []string{"getting-started", "custom", "getting-started-1", "legacy"}; !reflect.DeepEqual(got, want) {
+		t.Errorf("got anchors %v, want %v", got, want)
+	}
+
+	got = nil
+	for _, link := range links {
+		got = append(got, link.value)
+		if !strings.HasPrefix(code[link.offset:], link.value) {
+			t.Errorf("expected %q at offset %d", link.value, link.offset)
+		}
+	}
+	want := []string{"https://example.com/a", "setup.md#install", "https://example.com/b", "https://example.com/c", "https://example.com/ref"}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("got links %v, want %v", got, want)
+	}
+}
+
+func TestWithLinks(t *testing.T) {
+	code := "# One\n\nalpha beta gamma delta.\n\n# Two\n\nSee [x](x.md) now.\n"
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Markdown), WithMaxSize(5), WithLinks())
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	for _, tt := range []struct{ text, anchor, links string }{
+		{"alpha", "one", ""},
+		{"See", "two", "x.md"},
+	} {
+		for _, chunk := range chunks {
+			if !strings.Contains(chunk.Content, tt.text) {
+				continue
+			}
+			if got := chunk.Metadata[MetadataAnchor]; got != tt.anchor {
+				t.Errorf("expected the chunk %q in section %q, got %q", chunk.Content, tt.anchor, got)
+			}
+			if got := chunk.Metadata[MetadataLinks]; got != tt.links {
+				t.Errorf("expected the chunk %q to link to %q, got %q", chunk.Content, tt.links, got)
+			}
+		}
+	}
+}
+
+func TestHeadingAnchor(t *testing.T) {
+	slugs := make(map[string]int)
+	for _, tt := range []struct{ text, want string }{
+		{"Getting Started", "getting-started"},
+		{"What's `new` in v1.2?", "whats-new-in-v12"},
+		{"Getting Started", "getting-started-1"},
+		{"Install {#setup}", "setup"},
+	} {
+		if got := headingAnchor(tt.text, slugs); got != tt.want {
+			t.Errorf("headingAnchor(%q) = %q, want %q", tt.text, got, tt.want)
+		}
+	}
+}
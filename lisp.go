@@ -0,0 +1,138 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+var (
+	// lispDefinition matches the start of a Lisp form defining a name, such
+	// as "(defn parse", "(defun my-mode-hook", or "(define (square", and its
+	// name.
+	lispDefinition = regexp.MustCompile(`^\((?:[\w.-]+/)?(?:def|defn-?|defmacro|defonce|defprotocol|defrecord|deftype|defmulti|defmethod|definterface|defstruct|defun|defsubst|defvar|defvar-local|defcustom|defconst|defgroup|defface|defalias|defclass|defgeneric|defparameter|defconstant|define(?:-[\w-]+)?|cl-def\w+)\s+(?:\^\S+\s+)*[(']?\s*([^\s()\[\]{}"';]+)`)
+)
+
+// lispSpans returns the top-level forms of the Lisp code, such as Clojure,
+// Scheme, or Emacs Lisp, each with the comment lines right above it, so no
+// chunk ends within a form that fits. Forms defining a name, such as defn,
+// defmacro, defun, or define, record it as MetadataDefinition, with their
+// docstring. The forms before the first of them, such as ns, require, or
+// import, record MetadataBlock "header" so they are chunked together.
+func lispSpans(code string) []textSpan {
+	var spans []textSpan
+	header := true
+	for offset := lispSkip(code, 0, len(code)); offset < len(code); offset = lispSkip(code, offset, len(code)) {
+		// A stray closing bracket is a form of its own
+		end := max(lispFormEnd(code, offset, len(code)), offset+1)
+		metadata := map[string]string{}
+		if m := lispDefinition.FindStringSubmatch(code[offset:end]); m != nil {
+			metadata[MetadataDefinition] = m[1]
+			header = false
+		} else if header {
+			metadata[MetadataBlock] = "header"
+		}
+
+		span := textSpan{start: offset, end: end, metadata: metadata}
+		if lineStart := strings.LastIndexByte(code[:offset], '\n') + 1; strings.TrimSpace(code[lineStart:offset]) == "" {
+			span.start = leadingComments(code, lineStart, spanEnd(spans), ";")
+		}
+		if line, next := codeLine(code, end); strings.TrimSpace(line) == "" || strings.HasPrefix(strings.TrimSpace(line), ";") {
+			span.end = next
+		}
+		spans = append(spans, span)
+		offset = span.end
+	}
+	return spans
+}
+
+// lispNested returns the elements of the Lisp form too large for a chunk,
+// such as the arities of a Clojure function or the body forms of a let, so
+// consecutive ones that fit are chunked together and the form is split
+// between its elements rather than within them.
+func lispNested(form string) []textSpan {
+	offset := lispSkip(form, 0, len(form))
+	for offset < len(form) && !strings.ContainsRune("([{\"; \t\r\n", rune(form[offset])) {
+		offset++ // Reader prefixes, such as ' or #
+	}
+	if offset == len(form) || !strings.ContainsRune("([{", rune(form[offset])) {
+		return nil
+	}
+
+	closing := lispFormEnd(form, offset, len(form)) - 1
+	var spans []textSpan
+	for start := lispSkip(form, offset+1, closing); start < closing; start = lispSkip(form, start, closing) {
+		end := max(lispFormEnd(form, start, closing), start+1)
+		spans = append(spans, textSpan{start: start, end: end, metadata: map[string]string{}})
+		start = end
+	}
+	// The opening and closing brackets go with the first and last elements,
+	// unless the form holds only one, which would then be the whole form
+	if n := len(spans); n > 1 {
+		spans[0].start, spans[n-1].end = 0, len(form)
+	}
+	return spans
+}
+
+// lispSkip returns the offset of the first form of the Lisp code from offset
+// to end, skipping whitespace and comments, or end if there is none.
+func lispSkip(code string, offset, end int) int {
+	for offset < end {
+		switch rest := code[offset:end]; {
+		case strings.ContainsRune(" \t\r\n", rune(code[offset])):
+			offset++
+		case rest[0] == ';':
+			_, next := codeLine(code, offset)
+			offset = min(next, end)
+		case strings.HasPrefix(rest, "#|"):
+			offset = skipPast(code, offset, end, "|#")
+		default:
+			return offset
+		}
+	}
+	return end
+}
+
+// lispFormEnd returns the offset after the Lisp form at offset in code, such
+// as a list, vector, map, string, or atom with its reader prefixes, skipping
+// strings, comments, and character literals such as \( or ?\(, or end if it
+// isn't closed before.
+func lispFormEnd(code string, offset, end int) int {
+	depth := 0
+	for i := offset; i < end; i++ {
+		switch c := code[i]; {
+		case c == '(' || c == '[' || c == '{':
+			depth++
+		case c == ')' || c == ']' || c == '}':
+			if depth == 0 {
+				return i
+			}
+			if depth--; depth == 0 {
+				return i + 1
+			}
+		case c == '"':
+			for i+1 < end && code[i+1] != '"' {
+				if code[i+1] == '\\' {
+					i++
+				}
+				i++
+			}
+			if i++; depth == 0 {
+				return min(i+1, end)
+			}
+		case c == '\\':
+			i++
+		case strings.HasPrefix(code[i:end], "#|"):
+			i = skipPast(code, i, end, "|#") - 1
+		case c == ';' || c == ' ' || c == '\t' || c == '\r' || c == '\n':
+			if depth == 0 {
+				return i
+			}
+			if c == ';' {
+				for i+1 < end && code[i+1] != '\n' {
+					i++
+				}
+			}
+		}
+	}
+	return end
+}
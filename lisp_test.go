@@ -0,0 +1,73 @@
+package chunkx
+
+import (
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Clojure(t *testing.T) {
+	code := `(ns app.core
+  (:require [clojure.string :as str]))
+
+;; Parses a line of input
+(defn parse
+  "Splits the line into words."
+  [line]
+  (str/split line #"\s+"))
+
+(defmacro unless [test & body]
+  ` + "`" + `(if ~test nil (do ~@body)))
+
+(defn handle
+  ([req] (handle req {}))
+  ([req opts]
+   (let [words (parse (:body req))]
+     {:status 200
+      :body (str/join " " words)})))
+`
+	testChunkMetadata(t, code, languages.Clojure, 18, []struct{ prefix, key, value string }{
+		{"(ns app.core\n", MetadataBlock, "header"},
+		{";; Parses a line of input\n(defn parse\n  \"Splits the line into words.\"\n", MetadataDefinition, "parse"},
+		{"(defmacro unless", MetadataDefinition, "unless"},
+		{"(defn handle\n  ([req] (handle req {}))", MetadataDefinition, "handle"},
+		{"([req opts]\n", MetadataDefinition, "handle"},
+	})
+}
+
+func TestChunker_EmacsLisp(t *testing.T) {
+	code := `;;; demo.el --- Demo -*- lexical-binding: t -*-
+(require 'subr-x)
+
+(defvar demo-count 0
+  "Number of calls.")
+
+(defun demo-greet (name)
+  "Greet NAME."
+  (message "Hello, %s" name))
+
+(provide 'demo)
+`
+	testChunkMetadata(t, code, languages.EmacsLisp, 10, []struct{ prefix, key, value string }{
+		{";;; demo.el", MetadataBlock, "header"},
+		{"(defvar demo-count 0\n", MetadataDefinition, "demo-count"},
+		{"(defun demo-greet (name)\n  \"Greet NAME.\"\n", MetadataDefinition, "demo-greet"},
+	})
+}
+
+func TestChunker_Scheme(t *testing.T) {
+	code := `#| Geometry helpers |#
+(define (square x)
+  (* x x))
+
+(define-record-type point
+  (make-point x y)
+  point?
+  (x point-x)
+  (y point-y))
+`
+	testChunkMetadata(t, code, languages.Scheme, 10, []struct{ prefix, key, value string }{
+		{"(define (square x)\n", MetadataDefinition, "square"},
+		{"(define-record-type point\n", MetadataDefinition, "point"},
+	})
+}
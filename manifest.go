@@ -0,0 +1,158 @@
+package chunkx
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"maps"
+	"slices"
+	"sync"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// ManifestVersion is the version of the JSON document Manifest.WriteJSON
+// writes.
+const ManifestVersion = 1
+
+// Manifest lists the files of a run: the hash of every file, the language
+// it was chunked as, its number of chunks, and whether it failed. It makes a
+// run auditable, and lets later runs tell which files changed without a
+// FileIndex, by comparing ContentHash with the recorded hash.
+//
+// The zero value is an empty manifest ready to use. A Manifest is safe for
+// concurrent use.
+type Manifest struct {
+	mu    sync.Mutex
+	files map[string]ManifestFile
+}
+
+// ManifestFile is the entry of a file in a Manifest.
+type ManifestFile struct {
+	Path      string                 `json:"path"`
+	Hash      string                 `json:"hash,omitempty"`     // ContentHash of the file, if it could be read
+	Language  languages.LanguageName `json:"language,omitempty"` // Language the file was or would be chunked as
+	Chunks    int                    `json:"chunks"`
+	Skipped   bool                   `json:"skipped,omitempty"`    // Unchanged since it was recorded by WithIndex
+	Error     string                 `json:"error,omitempty"`      // Why the file failed, if it did
+	ErrorKind string                 `json:"error_kind,omitempty"` // ErrorKind of a failed file
+}
+
+// manifestDocument is the JSON document of a Manifest.
+type manifestDocument struct {
+	Version int            `json:"version"`
+	Files   []ManifestFile `json:"files"`
+}
+
+// WithManifest records every file chunked by a ChunkPipeline, ChunkFile,
+// ChunkFileContext, or ChunkMapped in manifest, including files that failed
+// or were skipped by WithIndex. Files left out by WithSampling aren't
+// chunked, and so aren't listed. Write the manifest with WriteJSON once the
+// run is done.
+func WithManifest(manifest *Manifest) Option {
+	return func(c *config) {
+		c.manifest = manifest
+	}
+}
+
+// Add adds file to the manifest, replacing any earlier entry of its path.
+func (m *Manifest) Add(file ManifestFile) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	if m.files == nil {
+		m.files = make(map[string]ManifestFile)
+	}
+	m.files[file.Path] = file
+}
+
+// File returns the entry of the file at path, if the manifest lists it.
+func (m *Manifest) File(path string) (ManifestFile, bool) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	file, ok := m.files[path]
+	return file, ok
+}
+
+// Files returns the entries of the manifest ordered by path.
+func (m *Manifest) Files() []ManifestFile {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	files := make([]ManifestFile, 0, len(m.files))
+	for _, path := range slices.Sorted(maps.Keys(m.files)) {
+		files = append(files, m.files[path])
+	}
+	return files
+}
+
+// WriteJSON writes the manifest to w as an indented JSON document of its
+// version and its files ordered by path, so the manifests of identical runs
+// are identical.
+func (m *Manifest) WriteJSON(w io.Writer) error {
+	data, err := json.MarshalIndent(manifestDocument{Version: ManifestVersion, Files: m.Files()}, "", "  ")
+	if err != nil {
+		return err
+	}
+	_, err = w.Write(append(data, '\n'))
+	return err
+}
+
+// ReadManifest reads a manifest written by WriteJSON.
+func ReadManifest(r io.Reader) (*Manifest, error) {
+	var doc manifestDocument
+	if err := json.NewDecoder(r).Decode(&doc); err != nil {
+		return nil, invalidRecord(err)
+	}
+	if doc.Version != ManifestVersion {
+		return nil, invalidRecord(fmt.Errorf("unsupported manifest version %d", doc.Version))
+	}
+	m := &Manifest{}
+	for _, file := range doc.Files {
+		m.Add(file)
+	}
+	return m, nil
+}
+
+// manifestConfig returns the config of opts if they record a manifest, or
+// nil otherwise.
+func manifestConfig(opts []Option) *config {
+	cfg := newDefaultConfig()
+	for _, opt := range opts {
+		opt(cfg)
+	}
+	if cfg.manifest == nil {
+		return nil
+	}
+	return cfg
+}
+
+// recordFile adds the result of a file to the manifest of cfg, if there is
+// one. Content is nil if the file couldn't be read, and name is the path its
+// language is detected from. A nil config records nothing.
+func (cfg *config) recordFile(result FileResult, name string, content []byte) {
+	if cfg == nil || cfg.manifest == nil {
+		return
+	}
+
+	file := ManifestFile{Path: result.Path, Chunks: len(result.Chunks), Skipped: result.Skipped}
+	if content != nil {
+		file.Hash = ContentHash(content)
+	}
+
+	var fileErr *FileError
+	if errors.As(result.Err, &fileErr) {
+		file.Error, file.ErrorKind = result.Err.Error(), fileErr.Kind.String()
+	} else if result.Err != nil {
+		file.Error, file.ErrorKind = result.Err.Error(), errorKind(result.Err).String()
+	}
+
+	switch {
+	case len(result.Chunks) > 0:
+		file.Language = result.Chunks[0].Language
+	case fileErr != nil && fileErr.Language != "":
+		file.Language = fileErr.Language
+	case content != nil:
+		file.Language = cfg.languageFor(name, string(content[:min(len(content), documentSample)]))
+	}
+	cfg.manifest.Add(file)
+}
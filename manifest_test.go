@@ -0,0 +1,97 @@
+package chunkx
+
+import (
+	"bytes"
+	"context"
+	"errors"
+	"os"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestWithManifest(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"a.txt":   "one\n",
+		"b.txt":   "two\n",
+		"big.txt": strings.Repeat("word ", 400),
+	})
+	index := &memIndex{hashes: map[string]string{filepath.Join(dir, "b.txt"): ContentHash([]byte("two\n"))}}
+	manifest := &Manifest{}
+
+	for range NewChunkPipeline(2, WithIndex(index), WithManifest(manifest), WithLimits(Limits{MaxBytes: 1000})).RunDir(context.Background(), dir) {
+	}
+
+	files := manifest.Files()
+	if len(files) != 3 {
+		t.Fatalf("expected three files, got %+v", files)
+	}
+	want := []ManifestFile{
+		{Path: filepath.Join(dir, "a.txt"), Hash: ContentHash([]byte("one\n")), Language: languages.Generic, Chunks: 1},
+		{Path: filepath.Join(dir, "b.txt"), Hash: ContentHash([]byte("two\n")), Language: languages.Generic, Skipped: true},
+		{Path: filepath.Join(dir, "big.txt"), Hash: ContentHash([]byte(strings.Repeat("word ", 400))), Language: languages.Generic, ErrorKind: "limit"},
+	}
+	for i := range want {
+		if i == 2 {
+			if files[i].Error == "" {
+				t.Errorf("expected the error of %s", files[i].Path)
+			}
+			files[i].Error = ""
+		}
+		if files[i] != want[i] {
+			t.Errorf("file %d = %+v, want %+v", i, files[i], want[i])
+		}
+	}
+
+	// Files chunked on their own are listed too
+	missing := filepath.Join(dir, "missing.txt")
+	if _, err := NewChunker().ChunkFile(missing, WithManifest(manifest)); err == nil {
+		t.Fatal("expected an error for a missing file")
+	}
+	if file, ok := manifest.File(missing); !ok || file.ErrorKind != "io" || file.Hash != "" || file.Language != "" {
+		t.Errorf("expected an io error without hash for the missing file, got %+v", file)
+	}
+
+	var buf bytes.Buffer
+	if err := manifest.WriteJSON(&buf); err != nil {
+		t.Fatal(err)
+	}
+	read, err := ReadManifest(&buf)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if !reflect.DeepEqual(read.Files(), manifest.Files()) {
+		t.Errorf("ReadManifest() = %+v, want %+v", read.Files(), manifest.Files())
+	}
+}
+
+func TestReadManifest_Invalid(t *testing.T) {
+	for _, input := range []string{"", "{\"version\": 2, \"files\": []}", "[]"} {
+		if _, err := ReadManifest(strings.NewReader(input)); !errors.Is(err, ErrInvalidRecord) {
+			t.Errorf("ReadManifest(%q) = %v, want ErrInvalidRecord", input, err)
+		}
+	}
+}
+
+func TestWithManifest_Mapped(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "notes.txt")
+	if err := os.WriteFile(path, []byte("one two\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	file, err := OpenMapped(path)
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer file.Close()
+
+	var manifest Manifest
+	if _, err := NewChunker().(MappedChunker).ChunkMapped(file, WithManifest(&manifest)); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if got, _ := manifest.File(path); got.Hash != ContentHash([]byte("one two\n")) || got.Chunks != 1 {
+		t.Errorf("unexpected entry %+v", got)
+	}
+}
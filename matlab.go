@@ -0,0 +1,51 @@
+package chunkx
+
+import "regexp"
+
+var (
+	// matlabFunction matches the line of MATLAB starting a function, such as
+	// "function [m, s] = stats(x)" or "function value = get.Radius(obj)", its
+	// indentation, and the name of the function.
+	matlabFunction = regexp.MustCompile(`^([ \t]*)function\b[ \t]*(?:(?:\[[^\]\n]*\]|[A-Za-z_]\w*)[ \t]*=[ \t]*)?([A-Za-z_][\w.]*)`)
+	// matlabCell matches the marker of a MATLAB code section, such as
+	// "%% Load the data", or "##" in Octave, its indentation, and its title.
+	matlabCell = regexp.MustCompile(`^([ \t]*)(?:%%|##)(?:[ \t]+(.*?))?[ \t\r]*$`)
+)
+
+// matlabSpans returns the functions of the MATLAB or Octave code, recording
+// MetadataDefinition, and its code sections, recording their title as
+// MetadataSection. A function runs up to the next function or section marker
+// not indented past it, so it holds its nested functions and its help text,
+// and takes the comment lines right above it too. A section runs up to the
+// next section marker or function.
+func matlabSpans(code string) []textSpan {
+	var spans []textSpan
+	from := 0    // End of the line of the last function or section marker
+	indent := -1 // Indentation of the last function, or -1 after a section marker
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		start := offset
+		var metadata map[string]string
+		if m := matlabFunction.FindStringSubmatch(line); m != nil && (indent < 0 || len(m[1]) <= indent) {
+			start = leadingComments(code, offset, from, "%", "#")
+			metadata = map[string]string{MetadataDefinition: m[2]}
+			indent = len(m[1])
+		} else if m := matlabCell.FindStringSubmatch(line); m != nil && (indent < 0 || len(m[1]) <= indent) {
+			metadata = map[string]string{}
+			if m[2] != "" {
+				metadata[MetadataSection] = m[2]
+			}
+			indent = -1
+		} else {
+			offset = next
+			continue
+		}
+
+		if n := len(spans); n > 0 {
+			spans[n-1].end = start
+		}
+		spans = append(spans, textSpan{start: start, end: len(code), metadata: metadata})
+		from, offset = next, next
+	}
+	return spans
+}
@@ -0,0 +1,43 @@
+package chunkx
+
+import (
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_MATLAB(t *testing.T) {
+	code := `%% Load the data
+data = load('samples.mat');
+x = data.x;
+
+%% Fit a line
+p = polyfit(x, data.y, 1);
+
+% Helpers used by the script
+function [m, s] = stats(x)
+%STATS Mean and standard deviation of x.
+%   [M, S] = STATS(X) returns both at once.
+    m = mean(x);
+    s = std(x);
+end
+
+function y = scaled(x)
+    y = x / helper(x);
+    function h = helper(v)
+        h = max(v);
+    end
+end
+`
+	chunks := testChunkMetadata(t, code, languages.MATLAB, 100, []struct{ prefix, key, value string }{
+		{"%% Load the data\n", MetadataSection, "Load the data"},
+		{"%% Fit a line\n", MetadataSection, "Fit a line"},
+		{"% Helpers used by the script\nfunction [m, s] = stats(x)\n%STATS", MetadataDefinition, "stats"},
+		{"function y = scaled(x)\n", MetadataDefinition, "scaled"},
+	})
+	for _, chunk := range chunks {
+		if chunk.Metadata[MetadataDefinition] == "helper" {
+			t.Errorf("expected the nested function in the chunk of scaled, got %q", chunk.Content)
+		}
+	}
+}
@@ -0,0 +1,78 @@
+package chunkx
+
+import (
+	"fmt"
+	"os"
+	"unsafe"
+)
+
+// MappedFile is a read-only memory mapping of a file on disk.
+//
+// Chunks produced from a MappedFile borrow their Content from the mapping
+// instead of copying it, which keeps memory usage flat for very large files.
+// Borrowed chunks are only valid until Close is called; use Chunk.Clone to
+// keep a chunk around after that.
+type MappedFile struct {
+	path string
+	data []byte
+}
+
+// OpenMapped maps the file at path into memory for reading.
+func OpenMapped(path string) (*MappedFile, error) {
+	f, err := os.Open(path)
+	if err != nil {
+		return nil, fmt.Errorf("failed to open file: %w", err)
+	}
+	defer f.Close()
+
+	info, err := f.Stat()
+	if err != nil {
+		return nil, fmt.Errorf("failed to stat file: %w", err)
+	}
+
+	data, err := mapFile(f, info.Size())
+	if err != nil {
+		return nil, fmt.Errorf("failed to map file: %w", err)
+	}
+
+	return &MappedFile{
+		path: path,
+		data: data,
+	}, nil
+}
+
+// Path returns the path the file was opened from.
+func (m *MappedFile) Path() string {
+	return m.path
+}
+
+// Len returns the size of the mapping in bytes.
+func (m *MappedFile) Len() int {
+	return len(m.data)
+}
+
+// String returns the mapped contents as a string without copying them.
+// The string is only valid until Close is called.
+func (m *MappedFile) String() string {
+	if len(m.data) == 0 {
+		return ""
+	}
+	return unsafe.String(&m.data[0], len(m.data))
+}
+
+// Close releases the mapping. Chunks borrowed from the file must not be used
+// after Close returns.
+func (m *MappedFile) Close() error {
+	data := m.data
+	m.data = nil
+	return unmapFile(data)
+}
+
+// stringBytes returns the bytes of s without copying them.
+// The returned slice must not be modified.
+func stringBytes(s string) []byte {
+	if s == "" {
+		return []byte{}
+	}
+	return unsafe.Slice(unsafe.StringData(s), len(s))
+}
@@ -0,0 +1,18 @@
+//go:build !(darwin || dragonfly || freebsd || linux || netbsd || openbsd)
+
+package chunkx
+
+import (
+	"io"
+	"os"
+)
+
+// mapFile reads f into memory on platforms without mmap support.
+func mapFile(f *os.File, _ int64) ([]byte, error) {
+	return io.ReadAll(f)
+}
+
+// unmapFile is a no-op on platforms without mmap support.
+func unmapFile(_ []byte) error {
+	return nil
+}
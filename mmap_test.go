@@ -0,0 +1,72 @@
+package chunkx
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+)
+
+func TestChunker_ChunkMapped(t *testing.T) {
+	path := filepath.Join("testdata", "sources", "example.go")
+	chunker := NewChunker().(MappedChunker)
+
+	want, err := chunker.ChunkFile(path, WithMaxSize(50))
+	if err != nil {
+		t.Fatalf("ChunkFile() error = %v", err)
+	}
+
+	file, err := OpenMapped(path)
+	if err != nil {
+		t.Fatalf("OpenMapped() error = %v", err)
+	}
+
+	chunks, err := chunker.ChunkMapped(file, WithMaxSize(50))
+	if err != nil {
+		t.Fatalf("ChunkMapped() error = %v", err)
+	}
+
+	owned := make([]Chunk, len(chunks))
+	for i, chunk := range chunks {
+		owned[i] = chunk.Clone()
+	}
+
+	if err := file.Close(); err != nil {
+		t.Fatalf("Close() error = %v", err)
+	}
+
+	if len(owned) != len(want) {
+		t.Fatalf("ChunkMapped() returned %d chunks, want %d", len(owned), len(want))
+	}
+	for i := range want {
+		if owned[i].Content != want[i].Content {
+			t.Errorf("chunk %d content mismatch", i)
+		}
+		if owned[i].StartByte != want[i].StartByte || owned[i].EndByte != want[i].EndByte {
+			t.Errorf("chunk %d bytes = [%d, %d), want [%d, %d)",
+				i, owned[i].StartByte, owned[i].EndByte, want[i].StartByte, want[i].EndByte)
+		}
+	}
+}
+
+func TestOpenMapped_EmptyFile(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "empty.txt")
+	if err := os.WriteFile(path, nil, 0o644); err != nil {
+		t.Fatalf("failed to write file: %v", err)
+	}
+
+	file, err := OpenMapped(path)
+	if err != nil {
+		t.Fatalf("OpenMapped() error = %v", err)
+	}
+	defer file.Close()
+
+	if file.Len() != 0 || file.String() != "" {
+		t.Errorf("expected empty mapping, got %d bytes", file.Len())
+	}
+}
+
+func TestOpenMapped_MissingFile(t *testing.T) {
+	if _, err := OpenMapped(filepath.Join(t.TempDir(), "missing.go")); err == nil {
+		t.Error("OpenMapped() expected error for missing file")
+	}
+}
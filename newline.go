@@ -0,0 +1,80 @@
+package chunkx
+
+import "strings"
+
+// utf8BOM is the UTF-8 encoding of the byte order mark.
+const utf8BOM = "\uFEFF"
+
+// WithNormalizedNewlines makes chunk content use "\n" for every line ending,
+// replacing the "\r\n" of Windows and the lone "\r" of classic Mac OS files.
+// Line numbers and byte offsets still refer to the original content, so a
+// chunk's content may be shorter than EndByte-StartByte.
+//
+// Line endings are recognized either way: a lone "\r" ends a line for line
+// numbers, and a leading UTF-8 byte order mark is left out of chunks but
+// counted in their offsets.
+func WithNormalizedNewlines() Option {
+	return func(c *config) {
+		c.normalizeNewlines = true
+	}
+}
+
+// prepareText returns code without a leading byte order mark and with lone
+// carriage returns replaced by line feeds, which is the text the splitters
+// understand, along with the length of the byte order mark. Replacing a
+// carriage return keeps the length, so offsets in the text differ from those
+// in code only by the byte order mark.
+func prepareText(code string) (text string, bom int, replaced bool) {
+	if strings.HasPrefix(code, utf8BOM) {
+		bom = len(utf8BOM)
+	}
+	text = code[bom:]
+
+	if !hasLoneCR(text) {
+		return text, bom, false
+	}
+	b := []byte(text)
+	for i, c := range b {
+		if c == '\r' && (i+1 == len(b) || b[i+1] != '\n') {
+			b[i] = '\n'
+		}
+	}
+	return string(b), bom, true
+}
+
+// hasLoneCR reports whether s has a carriage return that isn't followed by a
+// line feed.
+func hasLoneCR(s string) bool {
+	for i := strings.IndexByte(s, '\r'); i >= 0; {
+		if i+1 == len(s) || s[i+1] != '\n' {
+			return true
+		}
+		next := strings.IndexByte(s[i+1:], '\r')
+		if next < 0 {
+			return false
+		}
+		i += 1 + next
+	}
+	return false
+}
+
+// restoreText moves chunks of the text prepareText returned back onto code,
+// shifting their offsets past the byte order mark and, if carriage returns
+// were replaced, taking their content from code again.
+func restoreText(chunks []Chunk, code string, bom int, replaced bool) {
+	for i := range chunks {
+		chunks[i].StartByte += bom
+		chunks[i].EndByte += bom
+		if replaced {
+			chunks[i].Content = code[chunks[i].StartByte:chunks[i].EndByte]
+		}
+	}
+}
+
+// normalizeNewlines replaces "\r\n" and lone "\r" in s with "\n".
+func normalizeNewlines(s string) string {
+	if strings.IndexByte(s, '\r') < 0 {
+		return s
+	}
+	return strings.ReplaceAll(strings.ReplaceAll(s, "\r\n", "\n"), "\r", "\n")
+}
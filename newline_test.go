@@ -0,0 +1,99 @@
+package chunkx
+
+import (
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunk_LineEndings(t *testing.T) {
+	tests := []struct {
+		name      string
+		code      string
+		opts      []Option
+		want      []string
+		wantLines [][2]int
+		wantBytes [][2]int
+	}{
+		{
+			name:      "bom",
+			code:      "\uFEFFone\ntwo\n",
+			want:      []string{"one", "two\n"},
+			wantLines: [][2]int{{1, 1}, {2, 3}},
+			wantBytes: [][2]int{{3, 6}, {7, 11}},
+		},
+		{
+			name:      "lone carriage returns",
+			code:      "one\rtwo\rthree",
+			want:      []string{"one", "two", "three"},
+			wantLines: [][2]int{{1, 1}, {2, 2}, {3, 3}},
+			wantBytes: [][2]int{{0, 3}, {4, 7}, {8, 13}},
+		},
+		{
+			name:      "crlf",
+			code:      "one\r\ntwo\r\n",
+			want:      []string{"one\r", "two\r\n"},
+			wantLines: [][2]int{{1, 1}, {2, 3}},
+			wantBytes: [][2]int{{0, 4}, {5, 10}},
+		},
+		{
+			name:      "crlf normalized",
+			code:      "one\r\ntwo\r\n",
+			opts:      []Option{WithNormalizedNewlines()},
+			want:      []string{"one\n", "two\n"},
+			wantLines: [][2]int{{1, 1}, {2, 3}},
+			wantBytes: [][2]int{{0, 4}, {5, 10}},
+		},
+		{
+			name:      "bom and mixed endings normalized",
+			code:      "\uFEFFone\rtwo\r\nthree",
+			opts:      []Option{WithNormalizedNewlines()},
+			want:      []string{"one", "two\n", "three"},
+			wantLines: [][2]int{{1, 1}, {2, 2}, {3, 3}},
+			wantBytes: [][2]int{{3, 6}, {7, 11}, {12, 17}},
+		},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			opts := append([]Option{WithLanguage(languages.Generic), WithMaxSize(1), WithOverlap(0)}, tt.opts...)
+			chunks, err := NewChunker().Chunk(tt.code, opts...)
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if len(chunks) != len(tt.want) {
+				t.Fatalf("got %d chunks, want %d: %+v", len(chunks), len(tt.want), chunks)
+			}
+			for i, chunk := range chunks {
+				if chunk.Content != tt.want[i] {
+					t.Errorf("chunk %d content = %q, want %q", i, chunk.Content, tt.want[i])
+				}
+				if got := [2]int{chunk.StartLine, chunk.EndLine}; got != tt.wantLines[i] {
+					t.Errorf("chunk %d lines = %v, want %v", i, got, tt.wantLines[i])
+				}
+				if got := [2]int{chunk.StartByte, chunk.EndByte}; got != tt.wantBytes[i] {
+					t.Errorf("chunk %d bytes = %v, want %v", i, got, tt.wantBytes[i])
+				}
+			}
+		})
+	}
+}
+
+func TestChunk_LineEndingsSyntax(t *testing.T) {
+	code := "\uFEFFpackage main\r\rfunc main() {}\r"
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Go))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) == 0 {
+		t.Fatal("expected chunks")
+	}
+	for _, chunk := range chunks {
+		if got := code[chunk.StartByte:chunk.EndByte]; got != chunk.Content {
+			t.Errorf("content %q doesn't match original bytes %q", chunk.Content, got)
+		}
+	}
+	if last := chunks[len(chunks)-1]; last.EndLine != 3 {
+		t.Errorf("last chunk ends on line %d, want 3", last.EndLine)
+	}
+}
@@ -0,0 +1,61 @@
+//go:build !cgo
+
+package chunkx
+
+// Tree-sitter grammars are C libraries, so syntax-aware chunking needs cgo.
+// Without it, such as when building for WebAssembly, chunkx still builds and
+// every language is chunked line by line. Chunks keep the detected language
+// but report the "generic" node type. Parser and the node helpers are not
+// available in such builds.
+
+// castChunker falls back to line-based chunking for every language.
+type castChunker struct{}
+
+// newCastChunker creates a chunker.
+func newCastChunker() *castChunker {
+	return &castChunker{}
+}
+
+// chunkSyntax never parses, so the caller falls back to line-based chunking.
+func (c *castChunker) chunkSyntax(string, *config) ([]Chunk, bool, error) {
+	return nil, false, nil
+}
+
+// syntaxComments never parses, so the caller finds comments by their
+// delimiters.
+func (c *castChunker) syntaxComments(string, *config) ([][2]int, bool, error) {
+	return nil, false, nil
+}
+
+// syntaxTree is never set, as nothing is parsed.
+type syntaxTree = *struct{}
+
+// editTree does nothing, as there is no tree to edit.
+func editTree(syntaxTree, string, Edit) {}
+
+// CacheStats reports the activity of a ParseCache.
+type CacheStats struct {
+	Hits      uint64 // Lookups served from the cache
+	Misses    uint64 // Lookups that required parsing
+	Evictions uint64 // Entries dropped to stay within the memory bound
+	Entries   int    // Trees currently cached
+	Bytes     int64  // Estimated memory held by cached trees
+}
+
+// ParseCache caches parsed syntax trees. Nothing is parsed without cgo, so
+// the cache stays empty; it exists so code using it builds either way.
+type ParseCache struct{}
+
+// NewParseCache creates a parse cache. The bound is ignored without cgo.
+func NewParseCache(int64) *ParseCache {
+	return &ParseCache{}
+}
+
+// Stats returns a snapshot of the cache statistics, which are always zero
+// without cgo.
+func (pc *ParseCache) Stats() CacheStats {
+	return CacheStats{}
+}
+
+// Clear removes all cached trees.
+func (pc *ParseCache) Clear() {}
@@ -0,0 +1,46 @@
+//go:build !cgo
+
+package chunkx
+
+import (
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunk_NoCgoFallsBackToLines(t *testing.T) {
+	code := "package main\n\nfunc main() {\n\tprintln(1)\n}\n"
+
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Go), WithMaxSize(3))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	want, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithMaxSize(3))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) != len(want) {
+		t.Fatalf("got %d chunks, want %d as with generic chunking", len(chunks), len(want))
+	}
+	for i, chunk := range chunks {
+		if chunk.Content != want[i].Content || chunk.Language != languages.Go {
+			t.Errorf("chunk %d = %+v, want content %q in go", i, chunk, want[i].Content)
+		}
+	}
+}
+
+func TestDocument_NoCgo(t *testing.T) {
+	doc, err := NewDocument("one\ntwo\n", WithLanguage(languages.Go), WithMaxSize(1))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	diff, err := doc.Apply(Edit{StartByte: 0, OldEndByte: 3, NewText: "three"})
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(diff.Added) != 1 || len(diff.Removed) != 1 || len(diff.Unchanged) != 1 {
+		t.Errorf("unexpected diff %+v", diff)
+	}
+}
@@ -0,0 +1,492 @@
+package chunkx
+
+import (
+	"encoding/json"
+	"sort"
+	"strconv"
+	"strings"
+	"unicode/utf16"
+	"unicode/utf8"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Metadata keys set on chunks of notebooks chunked with WithNotebookCells.
+const (
+	MetadataCell     = "cell"      // Index of the cell the chunk starts in
+	MetadataCellType = "cell_type" // Type of the cells of the chunk, such as "code" or "markdown"
+)
+
+// OutputPolicy selects what WithNotebookCells does with the outputs of code
+// cells.
+type OutputPolicy int
+
+const (
+	OutputsDrop         OutputPolicy = iota // Leave outputs out
+	OutputsText                             // Keep text outputs, truncated to NotebookOutputLines lines
+	OutputsPlaceholders                     // Keep text outputs, and replace images, HTML, and other data with placeholders
+)
+
+// NotebookOutputLines is the number of lines of each text output that
+// OutputsText and OutputsPlaceholders keep.
+const NotebookOutputLines = 20
+
+// maxJSONDepth is the deepest nesting of JSON a notebook may have.
+const maxJSONDepth = 512
+
+// jsonEscapes maps the character after a backslash in a JSON string to the
+// character it stands for.
+var jsonEscapes = map[byte]rune{'"': '"', '\\': '\\', '/': '/', 'b': '\b', 'f': '\f', 'n': '\n', 'r': '\r', 't': '\t'}
+
+// WithNotebookCells chunks Jupyter notebooks by their cells, rather than as
+// the JSON they are stored in. Consecutive cells of the same type are chunked
+// together: code cells in the language of the notebook's kernel, or in the
+// language of the call if it names none or an unsupported one, Markdown cells
+// as Markdown, and other cells as plain text. Outputs are left out unless
+// WithNotebookOutputs keeps them.
+//
+// Chunks span the sources of their cells in the notebook, so their offsets
+// and lines point into its JSON, while their content is the decoded source.
+// They record MetadataCell and MetadataCellType. Content that isn't a
+// notebook in the nbformat 4 layout is chunked as usual.
+func WithNotebookCells() Option {
+	return func(c *config) {
+		c.notebooks = true
+	}
+}
+
+// WithNotebookOutputs sets the policy for the outputs of code cells of
+// notebooks chunked with WithNotebookCells. Kept outputs follow the source of
+// their cell in its text, one after another: streams, plain text results,
+// and errors as their text, truncated to NotebookOutputLines lines, and with
+// OutputsPlaceholders, images and other rich data as placeholders such as
+// "[image output: 640x480 png]" or "[html output]". The base64 of images
+// never reaches the chunks. The offsets of outputs span the rest of their
+// cell after its source.
+func WithNotebookOutputs(policy OutputPolicy) Option {
+	return func(c *config) {
+		c.notebookOutputs = policy
+	}
+}
+
+// chunkNotebook chunks the cells of the notebook code, returning false if
+// code isn't a notebook.
+func (c *castChunker) chunkNotebook(code string, cfg *config) ([]Chunk, bool, error) {
+	root, ok := parseJSON(code)
+	if !ok {
+		return nil, false, nil
+	}
+	cells, ok := root.get("cells")
+	if !ok || code[cells.start] != '[' {
+		return nil, false, nil
+	}
+
+	lang := notebookLanguage(code, root)
+	if lang == "" {
+		lang = cfg.language
+	}
+
+	// The call's timeout already runs over the whole notebook
+	inner := *cfg
+	inner.notebooks = false
+	inner.timeout = 0
+
+	var chunks []Chunk
+	for start := 0; start < len(cells.values); {
+		kind := cellType(code, cells.values[start])
+		end := start + 1
+		for end < len(cells.values) && cellType(code, cells.values[end]) == kind {
+			end++
+		}
+
+		switch kind {
+		case "code":
+			inner.language = lang
+		case "markdown":
+			inner.language = languages.Markdown
+		default:
+			inner.language = languages.Generic
+		}
+		cellChunks, err := c.chunkCells(code, cells.values[start:end], start, &inner)
+		if err != nil {
+			return nil, true, err
+		}
+		for i := range cellChunks {
+			cellChunks[i].Metadata[MetadataCellType] = kind
+		}
+		chunks = append(chunks, cellChunks...)
+		start = end
+	}
+	if err := cfg.limits.checkChunks(chunks); err != nil {
+		return nil, true, err
+	}
+	return chunks, true, nil
+}
+
+// chunkCells chunks the sources of cells, the first of which is the cell at
+// index first of the notebook code, and maps the chunks back onto code.
+func (c *castChunker) chunkCells(code string, cells []jsonValue, first int, cfg *config) ([]Chunk, error) {
+	var tb textBuilder
+	starts := make([]int, len(cells)) // Offsets of the cells in the text
+	for i, cell := range cells {
+		starts[i] = tb.b.Len()
+		for j, line := range cellSource(code, cell) {
+			// A line feed between cells stands for the JSON between them
+			if j == 0 && tb.b.Len() > 0 && !strings.HasSuffix(tb.b.String(), "\n") {
+				tb.writeRune('\n', line.start-tb.offsets.origEnd)
+				starts[i] = tb.b.Len()
+			}
+			writeJSONString(&tb, code, line)
+		}
+
+		if outputs := cellOutputs(code, cell, cfg.notebookOutputs); outputs != "" {
+			if tb.b.Len() > 0 && !strings.HasSuffix(tb.b.String(), "\n") {
+				outputs = "\n" + outputs
+			}
+			writeSpanning(&tb, outputs, max(tb.offsets.origEnd, cell.start), cell.end)
+		}
+	}
+
+	text := tb.b.String()
+	if strings.TrimSpace(text) == "" {
+		return nil, nil
+	}
+	chunks, err := c.chunk(text, cfg)
+	if err != nil {
+		return nil, err
+	}
+	for i := range chunks {
+		cell := sort.Search(len(starts), func(j int) bool { return starts[j] > chunks[i].StartByte }) - 1
+		if chunks[i].Metadata == nil {
+			chunks[i].Metadata = make(map[string]string, 2)
+		}
+		chunks[i].Metadata[MetadataCell] = strconv.Itoa(first + max(cell, 0))
+	}
+	return mapChunks(chunks, code, &tb.offsets, ""), nil
+}
+
+// notebookLanguage returns the language of the kernel of the notebook code,
+// or "" if it names none or an unsupported one.
+func notebookLanguage(code string, root jsonValue) languages.LanguageName {
+	metadata, _ := root.get("metadata")
+	for _, path := range [][2]string{{"kernelspec", "language"}, {"language_info", "name"}} {
+		parent, ok := metadata.get(path[0])
+		if !ok {
+			continue
+		}
+		value, ok := parent.get(path[1])
+		if !ok {
+			continue
+		}
+		if lang, ok := languages.GetLanguageConfig(languages.LanguageName(jsonString(code, value))); ok {
+			return lang.Name
+		}
+	}
+	return ""
+}
+
+// cellSource returns the strings of the source of the notebook cell, which
+// is either a string or an array of lines.
+func cellSource(code string, cell jsonValue) []jsonValue {
+	source, ok := cell.get("source")
+	if !ok {
+		return nil
+	}
+	if code[source.start] == '"' {
+		return []jsonValue{source}
+	}
+	var lines []jsonValue
+	for _, line := range source.values {
+		if code[line.start] == '"' {
+			lines = append(lines, line)
+		}
+	}
+	return lines
+}
+
+// cellOutputs returns the text of the outputs of the notebook cell kept under
+// policy, or "" if it keeps none.
+func cellOutputs(code string, cell jsonValue, policy OutputPolicy) string {
+	outputs, ok := cell.get("outputs")
+	if !ok || policy == OutputsDrop {
+		return ""
+	}
+
+	var texts []string
+	for _, output := range outputs.values {
+		var text string
+		kind, _ := output.get("output_type")
+		switch jsonString(code, kind) {
+		case "stream":
+			value, _ := output.get("text")
+			text = truncateOutput(jsonText(code, value))
+		case "execute_result", "display_data":
+			text = dataOutput(code, output, policy)
+		case "error":
+			name, _ := output.get("ename")
+			value, _ := output.get("evalue")
+			text = strings.TrimSuffix(jsonString(code, name)+": "+jsonString(code, value), ": ")
+		}
+		if text != "" {
+			texts = append(texts, text)
+		}
+	}
+	return strings.Join(texts, "\n")
+}
+
+// dataOutput returns the text of the rich output of a notebook cell kept
+// under policy: the placeholder of its image with OutputsPlaceholders, its
+// plain text, or the placeholder of its other data with OutputsPlaceholders.
+func dataOutput(code string, output jsonValue, policy OutputPolicy) string {
+	data, _ := output.get("data")
+	if policy == OutputsPlaceholders {
+		for _, mime := range data.keys {
+			if !strings.HasPrefix(mime, "image/") {
+				continue
+			}
+			metadata, _ := output.get("metadata")
+			image, _ := metadata.get(mime)
+			width, _ := image.get("width")
+			height, _ := image.get("height")
+			if width.end > width.start && height.end > height.start {
+				return "[image output: " + code[width.start:width.end] + "x" + code[height.start:height.end] + " " + mimeSubtype(mime) + "]"
+			}
+			return "[image output: " + mimeSubtype(mime) + "]"
+		}
+	}
+	if text, ok := data.get("text/plain"); ok {
+		return truncateOutput(jsonText(code, text))
+	}
+	if policy == OutputsPlaceholders && len(data.keys) > 0 {
+		return "[" + mimeSubtype(data.keys[0]) + " output]"
+	}
+	return ""
+}
+
+// jsonText returns the text of the multiline string v of data, which is
+// either a string or an array of lines.
+func jsonText(data string, v jsonValue) string {
+	if v.end > v.start && data[v.start] == '"' {
+		return jsonString(data, v)
+	}
+	var b strings.Builder
+	for _, line := range v.values {
+		b.WriteString(jsonString(data, line))
+	}
+	return b.String()
+}
+
+// truncateOutput truncates the text of an output to NotebookOutputLines
+// lines, noting how many were left out.
+func truncateOutput(text string) string {
+	lines := strings.Split(strings.TrimRight(text, "\n"), "\n")
+	if len(lines) <= NotebookOutputLines {
+		return strings.Join(lines, "\n")
+	}
+	return strings.Join(lines[:NotebookOutputLines], "\n") + "\n[" + strconv.Itoa(len(lines)-NotebookOutputLines) + " more lines of output]"
+}
+
+// mimeSubtype returns the subtype of a MIME type without its suffix, such as
+// "svg" for "image/svg+xml".
+func mimeSubtype(mime string) string {
+	_, subtype, _ := strings.Cut(mime, "/")
+	subtype, _, _ = strings.Cut(subtype, "+")
+	return subtype
+}
+
+// cellType returns the type of the notebook cell.
+func cellType(code string, cell jsonValue) string {
+	value, _ := cell.get("cell_type")
+	return jsonString(code, value)
+}
+
+// jsonValue is a JSON value parsed by its offsets in the document, keeping
+// the members of objects and the elements of arrays.
+type jsonValue struct {
+	start, end int
+	keys       []string    // Decoded keys of an object
+	keyStarts  []int       // Offsets of the keys of an object
+	values     []jsonValue // Values of an object or elements of an array
+}
+
+// get returns the member key of the object v.
+func (v jsonValue) get(key string) (jsonValue, bool) {
+	for i, k := range v.keys {
+		if k == key {
+			return v.values[i], true
+		}
+	}
+	return jsonValue{}, false
+}
+
+// jsonString decodes the string v of data, or returns "" if v isn't one.
+func jsonString(data string, v jsonValue) string {
+	var s string
+	if v.end > v.start && data[v.start] == '"' {
+		_ = json.Unmarshal([]byte(data[v.start:v.end]), &s)
+	}
+	return s
+}
+
+// parseJSON parses the JSON document data, reporting whether it is valid.
+// Numbers and literals are only checked to be made of their characters.
+func parseJSON(data string) (jsonValue, bool) {
+	p := jsonParser{data: data}
+	v, ok := p.value(0)
+	p.space()
+	return v, ok && p.pos == len(data)
+}
+
+// jsonParser parses JSON by hand to keep the offsets of values, which
+// encoding/json doesn't report.
+type jsonParser struct {
+	data string
+	pos  int
+}
+
+// space skips white space.
+func (p *jsonParser) space() {
+	for p.pos < len(p.data) && strings.IndexByte(" \t\r\n", p.data[p.pos]) >= 0 {
+		p.pos++
+	}
+}
+
+// next reports whether the next byte after white space is b, and skips it if
+// so.
+func (p *jsonParser) next(b byte) bool {
+	p.space()
+	if p.pos < len(p.data) && p.data[p.pos] == b {
+		p.pos++
+		return true
+	}
+	return false
+}
+
+// value parses the value at the current position, nested depth levels deep.
+func (p *jsonParser) value(depth int) (jsonValue, bool) {
+	p.space()
+	v := jsonValue{start: p.pos}
+	if p.pos >= len(p.data) || depth > maxJSONDepth {
+		return v, false
+	}
+
+	switch p.data[p.pos] {
+	case '{', '[':
+		object := p.data[p.pos] == '{'
+		closing := byte(']')
+		if object {
+			closing = '}'
+		}
+		p.pos++
+		for !p.next(closing) {
+			if len(v.values) > 0 && !p.next(',') {
+				return v, false
+			}
+			if object {
+				key, ok := p.value(depth + 1)
+				if !ok || p.data[key.start] != '"' || !p.next(':') {
+					return v, false
+				}
+				v.keys = append(v.keys, jsonString(p.data, key))
+				v.keyStarts = append(v.keyStarts, key.start)
+			}
+			value, ok := p.value(depth + 1)
+			if !ok {
+				return v, false
+			}
+			v.values = append(v.values, value)
+		}
+	case '"':
+		for p.pos++; p.pos < len(p.data) && p.data[p.pos] != '"'; p.pos++ {
+			if p.data[p.pos] == '\\' {
+				p.pos++
+			}
+		}
+		if p.pos >= len(p.data) {
+			return v, false
+		}
+		p.pos++
+	default:
+		for p.pos < len(p.data) && strings.IndexByte(",:]}\"{[ \t\r\n", p.data[p.pos]) < 0 {
+			p.pos++
+		}
+		if p.pos == v.start {
+			return v, false
+		}
+	}
+	v.end = p.pos
+	return v, true
+}
+
+// writeJSONString appends the decoded string v of data to tb, mapping each
+// character to its escape sequence or bytes in data.
+func writeJSONString(tb *textBuilder, data string, v jsonValue) {
+	tb.offsets.skip(v.start + 1 - tb.offsets.origEnd)
+	for i := v.start + 1; i < v.end-1; {
+		switch {
+		case data[i] != '\\':
+			r, size := utf8.DecodeRuneInString(data[i : v.end-1])
+			tb.writeRune(r, size)
+			i += size
+		case data[i+1] == 'u':
+			r, size := decodeJSONEscape(data[i : v.end-1])
+			tb.writeRune(r, size)
+			i += size
+		default:
+			r, ok := jsonEscapes[data[i+1]]
+			if !ok {
+				r = rune(data[i+1])
+			}
+			tb.writeRune(r, 2)
+			i += 2
+		}
+	}
+	tb.offsets.skip(1)
+}
+
+// writeSpanning appends s, derived from the original bytes from start to end
+// rather than decoded from them. Its bytes stand for one original byte each,
+// but for the last, which stands for the rest, so the offsets of any part of s
+// stay within the original bytes.
+func writeSpanning(tb *textBuilder, s string, start, end int) {
+	tb.offsets.skip(start - tb.offsets.origEnd)
+	tb.b.WriteString(s)
+	rest := end - start
+	for i := range len(s) {
+		size := 0
+		switch {
+		case i == len(s)-1:
+			size = rest
+		case rest > 1:
+			size = 1
+		}
+		tb.offsets.add(1, size)
+		rest -= size
+	}
+}
+
+// decodeJSONEscape decodes the \u escape at the start of s, joining a
+// surrogate pair, and returns the character and the length of its escape.
+func decodeJSONEscape(s string) (rune, int) {
+	unit := func(s string) (rune, bool) {
+		if len(s) < 6 || s[0] != '\\' || s[1] != 'u' {
+			return 0, false
+		}
+		n, err := strconv.ParseUint(s[2:6], 16, 16)
+		return rune(n), err == nil
+	}
+
+	r, ok := unit(s)
+	if !ok {
+		return utf8.RuneError, min(len(s), 2)
+	}
+	if utf16.IsSurrogate(r) {
+		if low, ok := unit(s[6:]); ok {
+			if pair := utf16.DecodeRune(r, low); pair != utf8.RuneError {
+				return pair, 12
+			}
+		}
+		return utf8.RuneError, 6
+	}
+	return r, 6
+}
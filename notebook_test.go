@@ -0,0 +1,149 @@
+package chunkx
+
+import (
+	"encoding/json"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+const testNotebook = `{
+ "cells": [
+  {"cell_type": "markdown", "metadata": {}, "source": ["# Title\n", "Some text"]},
+  {"cell_type": "code", "execution_count": 1, "metadata": {}, "outputs": [], "source": "x = 1\n"},
+  {"cell_type": "code", "execution_count": 2, "metadata": {}, "outputs": [{"output_type": "stream", "text": ["a\tb\n"]}],
+   "source": ["y = \"a\\tb\"\n", "print(y)"]},
+  {"cell_type": "raw", "metadata": {}, "source": ["café"]}
+ ],
+ "metadata": {"kernelspec": {"display_name": "Python 3", "language": "python", "name": "python3"}},
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+`
+
+func TestWithNotebookCells(t *testing.T) {
+	opts := []Option{WithLanguage(languages.Generic), WithNotebookCells()}
+	chunks, err := NewChunker().Chunk(testNotebook, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, testNotebook, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	types := map[string]languages.LanguageName{"markdown": languages.Markdown, "code": languages.Python, "raw": languages.Generic}
+	var code strings.Builder
+	for _, chunk := range chunks {
+		kind := chunk.Metadata[MetadataCellType]
+		if want, ok := types[kind]; !ok || chunk.Language != want {
+			t.Errorf("unexpected %s chunk in %s: %+v", kind, chunk.Language, chunk)
+		}
+		if kind == "code" {
+			code.WriteString(chunk.Content)
+		}
+		if strings.Contains(chunk.Content, "output_type") {
+			t.Errorf("expected outputs left out, got %q", chunk.Content)
+		}
+		if strings.Contains(chunk.Content, "x = 1") && chunk.Metadata[MetadataCell] != "1" {
+			t.Errorf("expected the chunk of x to start in cell 1, got %s", chunk.Metadata[MetadataCell])
+		}
+		if span := testNotebook[chunk.StartByte:chunk.EndByte]; strings.HasSuffix(chunk.Content, "print(y)") && !strings.HasSuffix(span, "print(y)") {
+			t.Errorf("expected the chunk to end at the end of its source, got %q", span)
+		}
+	}
+	if want := "y = \"a\\tb\"\nprint(y)"; !strings.Contains(code.String(), want) {
+		t.Errorf("expected the code cells decoded to contain %q, got %q", want, code.String())
+	}
+}
+
+func TestWithNotebookCells_NotANotebook(t *testing.T) {
+	code := `{"cells": 3}`
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithNotebookCells())
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) != 1 || chunks[0].Content != code {
+		t.Errorf("expected the content chunked as usual, got %+v", chunks)
+	}
+}
+
+const testOutputsNotebook = `{
+ "cells": [
+  {"cell_type": "code", "execution_count": 1, "metadata": {}, "outputs": [
+    {"output_type": "display_data", "data": {"image/png": "iVBORw0KGgo=", "text/plain": ["<Figure size 640x480 with 1 Axes>"]}, "metadata": {"image/png": {"width": 640, "height": 480}}},
+    {"output_type": "execute_result", "execution_count": 1, "data": {"text/html": ["<table></table>"]}, "metadata": {}},
+    {"output_type": "error", "ename": "ValueError", "evalue": "bad value", "traceback": []}
+   ], "source": ["plot()"]},
+  {"cell_type": "code", "execution_count": 2, "metadata": {}, "outputs": [{"output_type": "stream", "name": "stdout", "text": "LINES"}], "source": "loop()"}
+ ],
+ "metadata": {},
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+`
+
+func TestWithNotebookOutputs(t *testing.T) {
+	code := strings.Replace(testOutputsNotebook, "LINES", strings.Repeat(`line\n`, NotebookOutputLines+5), 1)
+	tests := []struct {
+		policy      OutputPolicy
+		contains    []string
+		notContains []string
+	}{
+		{OutputsDrop, nil, []string{"Figure", "ValueError", "line"}},
+		{OutputsText, []string{"plot()\n<Figure size 640x480 with 1 Axes>\nValueError: bad value\nloop()\nline\n", "line\n[5 more lines of output]"}, []string{"[image output", "table"}},
+		{OutputsPlaceholders, []string{"plot()\n[image output: 640x480 png]\n[html output]\nValueError: bad value\nloop()"}, []string{"Figure", "iVBOR"}},
+	}
+	for _, tt := range tests {
+		opts := []Option{WithLanguage(languages.Generic), WithNotebookCells(), WithNotebookOutputs(tt.policy)}
+		chunks, err := NewChunker().Chunk(code, opts...)
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		if err := ValidateChunks(chunks, code, opts...); err != nil {
+			t.Errorf("unexpected validation error: %v", err)
+		}
+
+		var text strings.Builder
+		for _, chunk := range chunks {
+			text.WriteString(chunk.Content)
+		}
+		for _, want := range tt.contains {
+			if !strings.Contains(text.String(), want) {
+				t.Errorf("policy %d: expected %q in %q", tt.policy, want, text.String())
+			}
+		}
+		for _, unwanted := range tt.notContains {
+			if strings.Contains(text.String(), unwanted) {
+				t.Errorf("policy %d: expected no %q in %q", tt.policy, unwanted, text.String())
+			}
+		}
+		if got := strings.Count(text.String(), "line\n"); tt.policy != OutputsDrop && got != NotebookOutputLines {
+			t.Errorf("policy %d: expected %d lines of output kept, got %d", tt.policy, NotebookOutputLines, got)
+		}
+	}
+}
+
+func TestWriteJSONString(t *testing.T) {
+	raw := `"a\nb\u00e9\ud83d\ude00\"c"`
+	value, ok := parseJSON(raw)
+	if !ok {
+		t.Fatalf("failed to parse %s", raw)
+	}
+
+	var tb textBuilder
+	writeJSONString(&tb, raw, value)
+	var want string
+	if err := json.Unmarshal([]byte(raw), &want); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if got := tb.b.String(); got != want {
+		t.Errorf("got %q, want %q", got, want)
+	}
+	if got := tb.offsets.original(strings.Index(want, "é")); got != strings.Index(raw, `\u00e9`) {
+		t.Errorf("expected é to map to its escape, got offset %d", got)
+	}
+	if got := tb.offsets.originalEnd(len(want)); got != len(raw)-1 {
+		t.Errorf("expected the text to end before the closing quote, got offset %d", got)
+	}
+}
@@ -0,0 +1,218 @@
+package chunkx
+
+import (
+	"path/filepath"
+	"regexp"
+	"strings"
+)
+
+var (
+	// objcUnit matches the line of Objective-C starting a class interface,
+	// implementation, or protocol, such as "@interface Widget : NSObject" or
+	// "@implementation Widget (Drawing)", and its kind, name, and category.
+	objcUnit = regexp.MustCompile(`^[ \t]*@(interface|implementation|protocol)[ \t]+(\w+)(?:[ \t]*\([ \t]*(\w*)[ \t]*\))?`)
+	// objcEnd matches the line of Objective-C ending a unit.
+	objcEnd = regexp.MustCompile(`^[ \t]*@end\b`)
+	// objcMark matches a pragma mark of Objective-C, such as "#pragma mark -
+	// Drawing" or "// MARK: Drawing", and its title.
+	objcMark = regexp.MustCompile(`^[ \t]*(?:#pragma[ \t]+mark|//[ \t]*MARK:)[ \t]*-?[ \t]*(.*?)[ \t]*\r?$`)
+	// objcMethod matches the line of Objective-C starting a method, such as
+	// "- (void)drawRect:(CGRect)rect" or "+ shared", and its first word.
+	objcMethod = regexp.MustCompile(`^[ \t]*[-+][ \t]*(?:\([^)]*\)[ \t]*)?(\w+)`)
+	// objcMarker matches a line only Objective-C starts so, telling it apart
+	// from MATLAB in .m files and from C in .h files.
+	objcMarker = regexp.MustCompile(`(?m)^[ \t]*(?:#import\b|@(?:interface|implementation|protocol)\b)`)
+	// objcType matches a parenthesized type of an Objective-C method, such as
+	// "(NSString *)", without any nested within it.
+	objcType = regexp.MustCompile(`\([^()]*\)`)
+	// objcKeyword matches a keyword of an Objective-C selector, such as
+	// "setName:".
+	objcKeyword = regexp.MustCompile(`(\w+)[ \t]*:`)
+)
+
+// isObjectiveC reports whether the file at path, with the given content, is
+// Objective-C though its extension is also used by MATLAB or C headers.
+func isObjectiveC(path, content string) bool {
+	if ext := filepath.Ext(path); ext != ".m" && ext != ".h" {
+		return false
+	}
+	return objcMarker.MatchString(content[:min(len(content), documentSample)])
+}
+
+// objcSpans returns the class interfaces, implementations, and protocols of
+// the Objective-C code, each up to its @end and with the comment lines right
+// above it, recording the kind and name, and the category if any, as
+// MetadataBlock, such as "@implementation Widget (Drawing)".
+func objcSpans(code string) []textSpan {
+	var spans []textSpan
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		m := objcUnit.FindStringSubmatchIndex(line)
+		// Forward declarations, such as "@protocol Drawable;", have no body
+		if m == nil || strings.HasPrefix(strings.TrimSpace(line[m[1]:]), ";") {
+			offset = next
+			continue
+		}
+		end := objcUnitEnd(code, next)
+		if end < 0 {
+			offset = next
+			continue
+		}
+
+		block := "@" + line[m[2]:m[3]] + " " + line[m[4]:m[5]]
+		if m[6] >= 0 {
+			block += " (" + line[m[6]:m[7]] + ")"
+		}
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "//", "/*", "*"),
+			end:      end,
+			metadata: map[string]string{MetadataBlock: block},
+		})
+		offset = end
+	}
+	return spans
+}
+
+// objcNested returns the pieces of the Objective-C unit or pragma mark section
+// too large for a chunk. A unit is split at its pragma marks, which record
+// their title as MetadataSection, and at the methods before the first of
+// them, and a section, or a unit without marks, at its methods, recording
+// their selector as MetadataDefinition.
+func objcNested(block string) []textSpan {
+	for offset := 0; offset < len(block); {
+		line, next := codeLine(block, offset)
+		switch {
+		case objcUnit.MatchString(line):
+			if spans := objcSections(block, next); spans != nil {
+				return spans
+			}
+			return objcMethods(block, next, len(block))
+		case objcMark.MatchString(line):
+			return objcMethods(block, next, len(block))
+		case objcMethod.MatchString(line):
+			return nil
+		}
+		offset = next
+	}
+	return nil
+}
+
+// objcSections returns the methods of the Objective-C unit on the lines from
+// offset on before its first pragma mark, then the sections starting at each
+// mark up to the next one or the @end, or nil if it has no marks.
+func objcSections(code string, offset int) []textSpan {
+	var marks []int
+	end := len(code)
+	for i := offset; i < len(code); {
+		line, next := codeLine(code, i)
+		if objcMark.MatchString(line) {
+			marks = append(marks, i)
+		} else if objcEnd.MatchString(line) {
+			end = i
+		} else if objcMethod.MatchString(line) {
+			if _, closing := objcMethodEnd(code, i); closing > 0 {
+				_, next = codeLine(code, closing-1)
+			}
+		}
+		i = next
+	}
+	if len(marks) == 0 {
+		return nil
+	}
+
+	spans := objcMethods(code, offset, marks[0])
+	for i, mark := range marks {
+		stop := end
+		if i+1 < len(marks) {
+			stop = marks[i+1]
+		}
+		metadata := map[string]string{}
+		if title := objcMark.FindStringSubmatch(code[mark:stop])[1]; title != "" {
+			metadata[MetadataSection] = title
+		}
+		spans = append(spans, textSpan{start: mark, end: stop, metadata: metadata})
+	}
+	return spans
+}
+
+// objcMethods returns the methods of the Objective-C code on the lines from
+// offset to end, each with the comment lines right above it, recording their
+// selector, such as "setName:age:", as MetadataDefinition. Declarations in an
+// interface end at their ";", and definitions at the brace closing their
+// body.
+func objcMethods(code string, offset, end int) []textSpan {
+	var spans []textSpan
+	from := offset
+	for offset < end {
+		line, next := codeLine(code, offset)
+		if !objcMethod.MatchString(line) {
+			offset = next
+			continue
+		}
+		header, closing := objcMethodEnd(code[:end], offset)
+		if closing < 0 {
+			offset = next
+			continue
+		}
+
+		_, next = codeLine(code, closing-1)
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, max(spanEnd(spans), from), "//", "/*", "*"),
+			end:      min(next, end),
+			metadata: map[string]string{MetadataDefinition: objcSelector(header)},
+		})
+		offset = min(next, end)
+	}
+	return spans
+}
+
+// objcMethodEnd returns the header of the Objective-C method starting at
+// offset in code, up to its body or the ";" ending its declaration, and the
+// offset after the brace closing the body or after the ";", or -1 if there
+// is neither.
+func objcMethodEnd(code string, offset int) (string, int) {
+	i := strings.IndexAny(code[offset:], "{;")
+	if i < 0 {
+		return "", -1
+	}
+	header := code[offset : offset+i]
+	if code[offset+i] == ';' {
+		return header, offset + i + 1
+	}
+	if closing := braceClosing(code, offset+i); closing > offset+i {
+		return header, closing
+	}
+	return "", -1
+}
+
+// objcSelector returns the selector of the Objective-C method with the given
+// header, such as "setName:age:" for "- (void)setName:(NSString *)name
+// age:(int)age", or its first word if it takes no arguments.
+func objcSelector(header string) string {
+	for prev := ""; prev != header; {
+		prev, header = header, objcType.ReplaceAllString(header, " ")
+	}
+	header = strings.TrimLeft(strings.TrimSpace(header), "-+")
+	var selector strings.Builder
+	for _, m := range objcKeyword.FindAllStringSubmatch(header, -1) {
+		selector.WriteString(m[1] + ":")
+	}
+	if selector.Len() == 0 {
+		return strings.Fields(header)[0]
+	}
+	return selector.String()
+}
+
+// objcUnitEnd returns the offset after the @end line closing the Objective-C
+// unit whose lines after the one starting it start at offset in code, or -1
+// if there is none.
+func objcUnitEnd(code string, offset int) int {
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		if objcEnd.MatchString(line) {
+			return next
+		}
+		offset = next
+	}
+	return -1
+}
@@ -0,0 +1,63 @@
+package chunkx
+
+import (
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_ObjectiveC(t *testing.T) {
+	code := `#import <UIKit/UIKit.h>
+
+@protocol Drawable;
+
+// A view drawing shapes.
+@implementation ShapeView
+
+#pragma mark - Drawing
+
+- (void)drawRect:(CGRect)rect {
+    [self drawShapes];
+    [self drawBorder];
+}
+
+// Draws the border
+- (void)drawBorder {
+    [[UIColor blackColor] setStroke];
+}
+
+#pragma mark - Layout
+
+- (void)setWidth:(CGFloat)width height:(CGFloat)height {
+    self.frame = CGRectMake(0, 0, width, height);
+}
+
+@end
+`
+	testChunkMetadata(t, code, languages.ObjectiveC, 12, []struct{ prefix, key, value string }{
+		{"// A view drawing shapes.\n@implementation ShapeView\n", MetadataBlock, "@implementation ShapeView"},
+		{"- (void)drawRect:", MetadataDefinition, "drawRect:"},
+		{"- (void)drawRect:", MetadataSection, "Drawing"},
+		{"// Draws the border\n- (void)drawBorder {", MetadataDefinition, "drawBorder"},
+		{"- (void)setWidth:", MetadataDefinition, "setWidth:height:"},
+		{"- (void)setWidth:", MetadataSection, "Layout"},
+		{"- (void)setWidth:", MetadataBlock, "@implementation ShapeView"},
+	})
+}
+
+func TestIsObjectiveC(t *testing.T) {
+	for _, tt := range []struct {
+		path, content string
+		want          bool
+	}{
+		{"Widget.m", "#import \"Widget.h\"\n\n@implementation Widget\n@end\n", true},
+		{"Widget.h", "@interface Widget : NSObject\n@end\n", true},
+		{"fit.m", "function y = fit(x)\n  y = x;\nend\n", false},
+		{"util.h", "#include <stdio.h>\n\nint add(int a, int b);\n", false},
+		{"Widget.c", "#import \"Widget.h\"\n", false},
+	} {
+		if got := isObjectiveC(tt.path, tt.content); got != tt.want {
+			t.Errorf("isObjectiveC(%q) = %v, want %v", tt.path, got, tt.want)
+		}
+	}
+}
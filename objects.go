@@ -0,0 +1,99 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"path"
+	"strings"
+)
+
+// Metadata keys set on chunks of objects read from an ObjectStore.
+const (
+	MetadataObjectURL = "object_url" // URL of the object, such as s3://bucket/key
+	MetadataObjectKey = "object_key" // Key of the object in its bucket
+)
+
+// ObjectStore lists and reads the objects of a bucket in an object store
+// such as Amazon S3 or Google Cloud Storage. The objectstore package
+// implements it for both.
+//
+// Implementations must be safe for concurrent use.
+type ObjectStore interface {
+	// List calls fn with the key of every object whose key starts with
+	// prefix, in lexical order. Listing stops when fn returns an error.
+	List(ctx context.Context, prefix string, fn func(key string) error) error
+
+	// Get reads the content of the object at key. Objects larger than
+	// maxBytes fail with a LimitExceeded error, without reading more than
+	// maxBytes of them.
+	Get(ctx context.Context, key string, maxBytes int) ([]byte, error)
+
+	// URL returns the URL of the object at key, used as the path of its
+	// result.
+	URL(key string) string
+}
+
+// RunObjects chunks every object whose key starts with prefix, without
+// staging them to disk. Keys are filtered like paths in RunDir: objects below
+// hidden directories and keys matching WithIgnore patterns, relative to the
+// prefix, are skipped. Objects are fetched by the workers, so several are
+// downloaded at once. Objects larger than Limits.MaxBytes, or
+// DefaultMaxReadBytes without a limit, fail without being read whole.
+//
+// Results are reported with the URL of each object, and every chunk records
+// the URL and key in Metadata under MetadataObjectURL and MetadataObjectKey.
+// The language of each object is detected from its key. Errors listing the
+// bucket are reported as a result for the URL of the prefix.
+func (p *ChunkPipeline) RunObjects(ctx context.Context, store ObjectStore, prefix string) <-chan FileResult {
+	sources := make(chan source, p.workers)
+
+	// send queues an object for the workers, stopping the listing on
+	// cancellation
+	send := func(src source) error {
+		select {
+		case <-ctx.Done():
+			return ctx.Err()
+		case sources <- src:
+			return nil
+		}
+	}
+
+	go func() {
+		defer close(sources)
+		var n int
+		err := store.List(ctx, prefix, func(key string) error {
+			// Keys ending in a slash are placeholders for directories
+			if strings.HasSuffix(key, "/") {
+				return nil
+			}
+			name := strings.TrimPrefix(strings.TrimPrefix(key, prefix), "/")
+			if name == "" {
+				// The prefix names a single object
+				name = path.Base(key)
+			}
+			if skippedPath(p.ignore, name) {
+				return nil
+			}
+
+			url := store.URL(key)
+			if !p.queued(url, &n) {
+				return errSampleFull
+			}
+			return send(source{
+				path: url,
+				fetch: func(ctx context.Context) ([]byte, error) {
+					return store.Get(ctx, key, p.limits.maxRead())
+				},
+				metadata: map[string]string{
+					MetadataObjectURL: url,
+					MetadataObjectKey: key,
+				},
+			})
+		})
+		if err != nil && ctx.Err() == nil && !errors.Is(err, errSampleFull) && p.queued(store.URL(prefix), &n) {
+			_ = send(source{path: store.URL(prefix), err: err})
+		}
+	}()
+
+	return p.run(ctx, sources)
+}
@@ -0,0 +1,90 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"slices"
+	"sort"
+	"strings"
+	"testing"
+)
+
+// memStore is an ObjectStore over a map of keys to contents.
+type memStore map[string]string
+
+func (s memStore) List(ctx context.Context, prefix string, fn func(key string) error) error {
+	if _, ok := s["list-error"]; ok {
+		return errors.New("access denied")
+	}
+
+	keys := make([]string, 0, len(s))
+	for key := range s {
+		if strings.HasPrefix(key, prefix) {
+			keys = append(keys, key)
+		}
+	}
+	sort.Strings(keys)
+	for _, key := range keys {
+		if err := fn(key); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+func (s memStore) Get(ctx context.Context, key string, maxBytes int) ([]byte, error) {
+	content, ok := s[key]
+	if !ok {
+		return nil, errors.New("no such key")
+	}
+	if len(content) > maxBytes {
+		return nil, &LimitExceeded{Limit: LimitBytes, Max: maxBytes}
+	}
+	return []byte(content), nil
+}
+
+func (s memStore) URL(key string) string {
+	return "mem://bucket/" + key
+}
+
+func TestChunkPipeline_RunObjects(t *testing.T) {
+	store := memStore{
+		"docs/a.txt":        "hello\n",
+		"docs/sub/b.md":     "# Title\n",
+		"docs/sub/":         "",
+		"docs/.hidden/c.go": "package c\n",
+		"docs/vendor/d.txt": "vendored\n",
+		"other/e.txt":       "elsewhere\n",
+	}
+
+	pipeline := NewChunkPipeline(2, WithOrderedOutput(), WithIgnore("vendor"))
+	var paths []string
+	for result := range pipeline.RunObjects(context.Background(), store, "docs/") {
+		if result.Err != nil {
+			t.Fatalf("%s: unexpected error: %v", result.Path, result.Err)
+		}
+		paths = append(paths, result.Path)
+
+		chunk := result.Chunks[0]
+		if chunk.Metadata[MetadataObjectURL] != result.Path || !strings.HasPrefix(chunk.Metadata[MetadataObjectKey], "docs/") {
+			t.Errorf("unexpected metadata %v", chunk.Metadata)
+		}
+	}
+
+	want := []string{"mem://bucket/docs/a.txt", "mem://bucket/docs/sub/b.md"}
+	if !slices.Equal(paths, want) {
+		t.Errorf("paths = %q, want %q", paths, want)
+	}
+}
+
+func TestChunkPipeline_RunObjectsListError(t *testing.T) {
+	store := memStore{"list-error": ""}
+
+	var results []FileResult
+	for result := range NewChunkPipeline(1).RunObjects(context.Background(), store, "docs/") {
+		results = append(results, result)
+	}
+	if len(results) != 1 || results[0].Path != "mem://bucket/docs/" || results[0].Err == nil {
+		t.Errorf("unexpected results %+v", results)
+	}
+}
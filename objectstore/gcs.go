@@ -0,0 +1,87 @@
+package objectstore
+
+import (
+	"context"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"net/url"
+	"strings"
+
+	"github.com/gomantics/chunkx/internal/retry"
+)
+
+// GCS reads objects from a Google Cloud Storage bucket over the JSON API.
+type GCS struct {
+	Bucket   string       // Name of the bucket
+	Token    string       // OAuth 2.0 access token; requests are anonymous if empty
+	Endpoint string       // Defaults to https://storage.googleapis.com
+	Client   *http.Client // Defaults to http.DefaultClient
+}
+
+// gcsList is the response to an objects list request.
+type gcsList struct {
+	Items []struct {
+		Name string `json:"name"`
+	} `json:"items"`
+	NextPageToken string `json:"nextPageToken"`
+}
+
+// List implements chunkx.ObjectStore.
+func (g *GCS) List(ctx context.Context, prefix string, fn func(key string) error) error {
+	token := ""
+	for {
+		query := url.Values{"prefix": {prefix}, "fields": {"items(name),nextPageToken"}}
+		if token != "" {
+			query.Set("pageToken", token)
+		}
+
+		body, err := g.get(ctx, "/storage/v1/b/"+url.PathEscape(g.Bucket)+"/o", query, 0)
+		if err != nil {
+			return err
+		}
+		var page gcsList
+		if err := json.Unmarshal(body, &page); err != nil {
+			return fmt.Errorf("gcs: invalid list response: %w", err)
+		}
+
+		for _, item := range page.Items {
+			if err := fn(item.Name); err != nil {
+				return err
+			}
+		}
+		if page.NextPageToken == "" {
+			return nil
+		}
+		token = page.NextPageToken
+	}
+}
+
+// Get implements chunkx.ObjectStore.
+func (g *GCS) Get(ctx context.Context, key string, maxBytes int) ([]byte, error) {
+	content, err := g.get(ctx, "/storage/v1/b/"+url.PathEscape(g.Bucket)+"/o/"+url.PathEscape(key), url.Values{"alt": {"media"}}, maxBytes)
+	return limited(content, err, maxBytes)
+}
+
+// URL implements chunkx.ObjectStore.
+func (g *GCS) URL(key string) string {
+	return "gs://" + g.Bucket + "/" + key
+}
+
+// get sends an authorized GET request for path, reading at most max bytes of
+// the response if max is positive.
+func (g *GCS) get(ctx context.Context, path string, query url.Values, max int) ([]byte, error) {
+	endpoint := g.Endpoint
+	if endpoint == "" {
+		endpoint = "https://storage.googleapis.com"
+	}
+
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, strings.TrimSuffix(endpoint, "/")+path+"?"+query.Encode(), nil)
+	if err != nil {
+		return nil, err
+	}
+	if g.Token != "" {
+		req.Header.Set("Authorization", "Bearer "+g.Token)
+	}
+	return retry.SendLimited(g.Client, req, "gcs", int64(max))
+}
@@ -0,0 +1,56 @@
+package objectstore
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"slices"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+func TestGCS_ListAndGet(t *testing.T) {
+	srv := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Header.Get("Authorization") != "Bearer token" {
+			http.Error(w, "unauthorized", http.StatusUnauthorized)
+			return
+		}
+
+		switch {
+		case r.URL.Path == "/storage/v1/b/bucket/o" && r.URL.Query().Get("pageToken") == "":
+			fmt.Fprint(w, `{"items": [{"name": "docs/a.txt"}], "nextPageToken": "next"}`)
+		case r.URL.Path == "/storage/v1/b/bucket/o":
+			fmt.Fprint(w, `{"items": [{"name": "docs/sub/b.txt"}]}`)
+		case r.URL.EscapedPath() == "/storage/v1/b/bucket/o/docs%2Fa.txt" && r.URL.Query().Get("alt") == "media":
+			fmt.Fprint(w, "hello")
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	defer srv.Close()
+
+	g := &GCS{Bucket: "bucket", Token: "token", Endpoint: srv.URL}
+
+	var keys []string
+	err := g.List(context.Background(), "docs/", func(key string) error {
+		keys = append(keys, key)
+		return nil
+	})
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if want := []string{"docs/a.txt", "docs/sub/b.txt"}; !slices.Equal(keys, want) {
+		t.Errorf("keys = %q, want %q", keys, want)
+	}
+
+	content, err := g.Get(context.Background(), "docs/a.txt", 100)
+	if err != nil || string(content) != "hello" {
+		t.Errorf("Get = %q, %v", content, err)
+	}
+	if _, err := g.Get(context.Background(), "docs/a.txt", 4); !errors.Is(err, chunkx.ErrLimitExceeded) {
+		t.Errorf("Get of an object larger than the limit = %v, want ErrLimitExceeded", err)
+	}
+}
@@ -0,0 +1,91 @@
+// Package objectstore reads objects from Amazon S3, S3-compatible servers
+// such as MinIO, and Google Cloud Storage, for chunking with
+// ChunkPipeline.RunObjects:
+//
+//	store, prefix, err := objectstore.Open("s3://bucket/docs/")
+//	if err != nil {
+//		return err
+//	}
+//	for result := range pipeline.RunObjects(ctx, store, prefix) {
+//		// ...
+//	}
+//
+// The stores speak the REST APIs directly and don't depend on the cloud
+// SDKs, so they only support static credentials: access keys for S3 and an
+// OAuth 2.0 access token for Cloud Storage.
+package objectstore
+
+import (
+	"errors"
+	"fmt"
+	"net/url"
+	"os"
+	"strings"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/internal/retry"
+)
+
+// IsURL reports whether path is an object store URL that Open accepts.
+func IsURL(path string) bool {
+	return strings.HasPrefix(path, "s3://") || strings.HasPrefix(path, "gs://")
+}
+
+// Open returns the store for an s3://bucket/prefix or gs://bucket/prefix URL,
+// and the key prefix to list.
+//
+// S3 credentials are read from AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY, and
+// AWS_SESSION_TOKEN, the region from AWS_REGION or AWS_DEFAULT_REGION, and a
+// custom endpoint from AWS_ENDPOINT_URL. The Cloud Storage token is read from
+// GOOGLE_OAUTH_ACCESS_TOKEN and an emulator address from
+// STORAGE_EMULATOR_HOST. Without credentials, requests are anonymous.
+func Open(rawURL string) (chunkx.ObjectStore, string, error) {
+	u, err := url.Parse(rawURL)
+	if err != nil {
+		return nil, "", err
+	}
+	if u.Host == "" {
+		return nil, "", fmt.Errorf("object store URL %q has no bucket", rawURL)
+	}
+	prefix := strings.TrimPrefix(u.Path, "/")
+
+	switch u.Scheme {
+	case "s3":
+		region := os.Getenv("AWS_REGION")
+		if region == "" {
+			region = os.Getenv("AWS_DEFAULT_REGION")
+		}
+		return &S3{
+			Bucket:          u.Host,
+			Region:          region,
+			Endpoint:        os.Getenv("AWS_ENDPOINT_URL"),
+			AccessKeyID:     os.Getenv("AWS_ACCESS_KEY_ID"),
+			SecretAccessKey: os.Getenv("AWS_SECRET_ACCESS_KEY"),
+			SessionToken:    os.Getenv("AWS_SESSION_TOKEN"),
+		}, prefix, nil
+	case "gs":
+		endpoint := ""
+		if host := os.Getenv("STORAGE_EMULATOR_HOST"); host != "" {
+			endpoint = host
+			if !strings.Contains(host, "://") {
+				endpoint = "http://" + host
+			}
+		}
+		return &GCS{
+			Bucket:   u.Host,
+			Token:    os.Getenv("GOOGLE_OAUTH_ACCESS_TOKEN"),
+			Endpoint: endpoint,
+		}, prefix, nil
+	default:
+		return nil, "", fmt.Errorf("unsupported object store scheme %q", u.Scheme)
+	}
+}
+
+// limited turns the error of an object larger than maxBytes into the
+// LimitExceeded error chunkx.ObjectStore reports.
+func limited(content []byte, err error, maxBytes int) ([]byte, error) {
+	if errors.Is(err, retry.ErrTooLarge) {
+		return nil, &chunkx.LimitExceeded{Limit: chunkx.LimitBytes, Max: maxBytes}
+	}
+	return content, err
+}
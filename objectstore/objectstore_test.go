@@ -0,0 +1,33 @@
+package objectstore
+
+import "testing"
+
+func TestOpen(t *testing.T) {
+	t.Setenv("AWS_REGION", "eu-west-1")
+	t.Setenv("AWS_ACCESS_KEY_ID", "key")
+	t.Setenv("STORAGE_EMULATOR_HOST", "localhost:4443")
+
+	store, prefix, err := Open("s3://bucket/docs/")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	s3, ok := store.(*S3)
+	if !ok || s3.Bucket != "bucket" || s3.Region != "eu-west-1" || s3.AccessKeyID != "key" || prefix != "docs/" {
+		t.Errorf("Open = %+v, %q", store, prefix)
+	}
+
+	store, prefix, err = Open("gs://bucket")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	gcs, ok := store.(*GCS)
+	if !ok || gcs.Bucket != "bucket" || gcs.Endpoint != "http://localhost:4443" || prefix != "" {
+		t.Errorf("Open = %+v, %q", store, prefix)
+	}
+
+	for _, invalid := range []string{"s3:///docs", "ftp://bucket/docs", "docs/"} {
+		if _, _, err := Open(invalid); err == nil {
+			t.Errorf("Open(%q): expected an error", invalid)
+		}
+	}
+}
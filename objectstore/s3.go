@@ -0,0 +1,208 @@
+package objectstore
+
+import (
+	"context"
+	"crypto/hmac"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/xml"
+	"fmt"
+	"net/http"
+	"net/url"
+	"slices"
+	"strings"
+	"time"
+
+	"github.com/gomantics/chunkx/internal/retry"
+)
+
+// emptyHash is the SHA-256 hash of an empty request body.
+const emptyHash = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+
+// S3 reads objects from an Amazon S3 bucket or an S3-compatible server,
+// signing requests with AWS Signature Version 4.
+type S3 struct {
+	Bucket          string       // Name of the bucket
+	Region          string       // Defaults to us-east-1
+	Endpoint        string       // Base URL of an S3-compatible server, which is addressed with path-style URLs
+	AccessKeyID     string       // Requests are anonymous if empty
+	SecretAccessKey string       // Secret of the access key
+	SessionToken    string       // Sent if set, for temporary credentials
+	Client          *http.Client // Defaults to http.DefaultClient
+}
+
+// listBucketResult is the response to a ListObjectsV2 request.
+type listBucketResult struct {
+	Contents []struct {
+		Key string
+	}
+	IsTruncated           bool
+	NextContinuationToken string
+}
+
+// List implements chunkx.ObjectStore.
+func (s *S3) List(ctx context.Context, prefix string, fn func(key string) error) error {
+	token := ""
+	for {
+		query := url.Values{"list-type": {"2"}, "prefix": {prefix}}
+		if token != "" {
+			query.Set("continuation-token", token)
+		}
+
+		body, err := s.get(ctx, "", query, 0)
+		if err != nil {
+			return err
+		}
+		var result listBucketResult
+		if err := xml.Unmarshal(body, &result); err != nil {
+			return fmt.Errorf("s3: invalid list response: %w", err)
+		}
+
+		for _, object := range result.Contents {
+			if err := fn(object.Key); err != nil {
+				return err
+			}
+		}
+		if !result.IsTruncated || result.NextContinuationToken == "" {
+			return nil
+		}
+		token = result.NextContinuationToken
+	}
+}
+
+// Get implements chunkx.ObjectStore.
+func (s *S3) Get(ctx context.Context, key string, maxBytes int) ([]byte, error) {
+	content, err := s.get(ctx, key, nil, maxBytes)
+	return limited(content, err, maxBytes)
+}
+
+// URL implements chunkx.ObjectStore.
+func (s *S3) URL(key string) string {
+	return "s3://" + s.Bucket + "/" + key
+}
+
+// get sends a signed GET request for key, or for the bucket if key is empty,
+// reading at most max bytes of the response if max is positive.
+func (s *S3) get(ctx context.Context, key string, query url.Values, max int) ([]byte, error) {
+	region := s.Region
+	if region == "" {
+		region = "us-east-1"
+	}
+
+	endpoint := fmt.Sprintf("https://%s.s3.%s.amazonaws.com/%s", s.Bucket, region, escapeKey(key))
+	if s.Endpoint != "" {
+		endpoint = fmt.Sprintf("%s/%s/%s", strings.TrimSuffix(s.Endpoint, "/"), s.Bucket, escapeKey(key))
+	}
+
+	req, err := http.NewRequestWithContext(ctx, http.MethodGet, endpoint, nil)
+	if err != nil {
+		return nil, err
+	}
+	req.URL.RawQuery = canonicalQuery(query)
+	if s.AccessKeyID != "" {
+		s.sign(req, region, time.Now())
+	}
+	return retry.SendLimited(s.Client, req, "s3", int64(max))
+}
+
+// sign signs a request with an empty body, setting its X-Amz-Date,
+// X-Amz-Content-Sha256, and Authorization headers. Every header already set
+// on the request is signed.
+func (s *S3) sign(req *http.Request, region string, now time.Time) {
+	amzDate := now.UTC().Format("20060102T150405Z")
+	scope := amzDate[:8] + "/" + region + "/s3/aws4_request"
+
+	req.Header.Set("X-Amz-Date", amzDate)
+	req.Header.Set("X-Amz-Content-Sha256", emptyHash)
+	if s.SessionToken != "" {
+		req.Header.Set("X-Amz-Security-Token", s.SessionToken)
+	}
+
+	host := req.Host
+	if host == "" {
+		host = req.URL.Host
+	}
+	headers := map[string]string{"host": host}
+	for name, values := range req.Header {
+		headers[strings.ToLower(name)] = strings.TrimSpace(strings.Join(values, ","))
+	}
+	names := make([]string, 0, len(headers))
+	for name := range headers {
+		names = append(names, name)
+	}
+	slices.Sort(names)
+
+	var canonicalHeaders strings.Builder
+	for _, name := range names {
+		canonicalHeaders.WriteString(name + ":" + headers[name] + "\n")
+	}
+	signedHeaders := strings.Join(names, ";")
+
+	canonicalRequest := strings.Join([]string{
+		req.Method,
+		req.URL.EscapedPath(),
+		req.URL.RawQuery,
+		canonicalHeaders.String(),
+		signedHeaders,
+		emptyHash,
+	}, "\n")
+	requestHash := sha256.Sum256([]byte(canonicalRequest))
+	stringToSign := "AWS4-HMAC-SHA256\n" + amzDate + "\n" + scope + "\n" + hex.EncodeToString(requestHash[:])
+
+	key := []byte("AWS4" + s.SecretAccessKey)
+	for _, part := range []string{amzDate[:8], region, "s3", "aws4_request"} {
+		key = hmacSHA256(key, part)
+	}
+	signature := hex.EncodeToString(hmacSHA256(key, stringToSign))
+
+	req.Header.Set("Authorization", fmt.Sprintf("AWS4-HMAC-SHA256 Credential=%s/%s,SignedHeaders=%s,Signature=%s",
+		s.AccessKeyID, scope, signedHeaders, signature))
+}
+
+// hmacSHA256 returns the HMAC-SHA256 of data with key.
+func hmacSHA256(key []byte, data string) []byte {
+	mac := hmac.New(sha256.New, key)
+	mac.Write([]byte(data))
+	return mac.Sum(nil)
+}
+
+// escapeKey escapes an object key for the request path, encoding every byte
+// but unreserved characters and slashes as Signature Version 4 requires.
+func escapeKey(key string) string {
+	return uriEncode(key, false)
+}
+
+// canonicalQuery encodes query with its keys sorted, as Signature Version 4
+// requires.
+func canonicalQuery(query url.Values) string {
+	keys := make([]string, 0, len(query))
+	for key := range query {
+		keys = append(keys, key)
+	}
+	slices.Sort(keys)
+
+	var parts []string
+	for _, key := range keys {
+		for _, value := range query[key] {
+			parts = append(parts, uriEncode(key, true)+"="+uriEncode(value, true))
+		}
+	}
+	return strings.Join(parts, "&")
+}
+
+// uriEncode percent-encodes every byte of s but unreserved characters, and
+// slashes unless encodeSlash is set.
+func uriEncode(s string, encodeSlash bool) string {
+	var b strings.Builder
+	for i := 0; i < len(s); i++ {
+		c := s[i]
+		switch {
+		case 'A' <= c && c <= 'Z', 'a' <= c && c <= 'z', '0' <= c && c <= '9',
+			c == '-', c == '_', c == '.', c == '~', c == '/' && !encodeSlash:
+			b.WriteByte(c)
+		default:
+			fmt.Fprintf(&b, "%%%02X", c)
+		}
+	}
+	return b.String()
+}
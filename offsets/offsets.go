@@ -0,0 +1,214 @@
+// Package offsets converts between the byte offsets of chunks, UTF-16 code
+// units, and line and column positions, so editor integrations can place
+// chunks without re-deriving these mappings:
+//
+//	index := offsets.New(code)
+//	chunks, _ := chunkx.NewChunker().Chunk(code, chunkx.WithLanguage(languages.Go))
+//	start, end := index.LSPRange(chunks[0])
+//
+// Lines end at "\n", "\r\n", or a lone "\r", as they do for chunk line
+// numbers and in the Language Server Protocol. Text is read as UTF-8, with
+// every invalid byte counting as one U+FFFD replacement character. Index the
+// text the chunk offsets refer to, which for files is their content as read,
+// even if WithNormalizedNewlines changed the content of their chunks.
+package offsets
+
+import (
+	"sort"
+	"unicode/utf8"
+
+	"github.com/gomantics/chunkx"
+)
+
+// Position is a line and column in a text, as chunks number lines.
+type Position struct {
+	Line   int // 1-based line
+	Column int // 1-based column, in bytes
+}
+
+// LSPPosition is a position as the Language Server Protocol counts it.
+type LSPPosition struct {
+	Line      int `json:"line"`      // 0-based line
+	Character int `json:"character"` // 0-based column, in UTF-16 code units
+}
+
+// Index maps offsets in a text. Build one per text with New; an Index is
+// safe for concurrent use.
+//
+// Offsets outside the text are clamped to it, and an offset inside a
+// character or between the "\r" and "\n" of a line ending counts as the
+// start of the character or line ending.
+type Index struct {
+	text   string
+	starts []int // Byte offset of the start of every line
+	units  []int // UTF-16 code units before the start of every line
+}
+
+// New indexes text.
+func New(text string) *Index {
+	ix := &Index{text: text, starts: []int{0}, units: []int{0}}
+
+	units := 0
+	for i := 0; i < len(text); {
+		r, size := utf8.DecodeRuneInString(text[i:])
+		units += utf16Len(r)
+		i += size
+
+		switch {
+		case r == '\r' && i < len(text) && text[i] == '\n':
+			continue
+		case r == '\r' || r == '\n':
+			ix.starts = append(ix.starts, i)
+			ix.units = append(ix.units, units)
+		}
+	}
+	return ix
+}
+
+// Position returns the line and column of a byte offset.
+func (ix *Index) Position(offset int) Position {
+	offset = ix.runeStart(ix.clamp(offset))
+	line := ix.line(offset)
+	return Position{Line: line + 1, Column: offset - ix.starts[line] + 1}
+}
+
+// Offset returns the byte offset of a line and column. Columns past the end
+// of their line are clamped to its line ending.
+func (ix *Index) Offset(pos Position) int {
+	if pos.Line < 1 {
+		return 0
+	}
+	if pos.Line > len(ix.starts) {
+		return len(ix.text)
+	}
+	start := ix.starts[pos.Line-1]
+	return ix.runeStart(min(start+max(pos.Column-1, 0), ix.lineEnd(pos.Line-1)))
+}
+
+// UTF16 returns the number of UTF-16 code units before a byte offset, as
+// JavaScript and Java count string indexes.
+func (ix *Index) UTF16(offset int) int {
+	offset = ix.runeStart(ix.clamp(offset))
+	line := ix.line(offset)
+	return ix.units[line] + countUTF16(ix.text[ix.starts[line]:offset])
+}
+
+// FromUTF16 returns the byte offset after a number of UTF-16 code units. A
+// count that splits a surrogate pair is rounded down to the start of the pair.
+func (ix *Index) FromUTF16(units int) int {
+	if units <= 0 {
+		return 0
+	}
+	line := sort.Search(len(ix.units), func(i int) bool { return ix.units[i] > units }) - 1
+	return ix.advance(ix.starts[line], len(ix.text), units-ix.units[line])
+}
+
+// LSP returns the Language Server Protocol position of a byte offset.
+func (ix *Index) LSP(offset int) LSPPosition {
+	offset = ix.runeStart(ix.clamp(offset))
+	line := ix.line(offset)
+	return LSPPosition{Line: line, Character: countUTF16(ix.text[ix.starts[line]:offset])}
+}
+
+// FromLSP returns the byte offset of a Language Server Protocol position.
+// Characters past the end of their line are clamped to its line ending, as
+// the protocol specifies.
+func (ix *Index) FromLSP(pos LSPPosition) int {
+	if pos.Line < 0 {
+		return 0
+	}
+	if pos.Line >= len(ix.starts) {
+		return len(ix.text)
+	}
+	return ix.advance(ix.starts[pos.Line], ix.lineEnd(pos.Line), pos.Character)
+}
+
+// Range returns the positions of the first byte of a chunk and of the byte
+// after its last.
+func (ix *Index) Range(chunk chunkx.Chunk) (start, end Position) {
+	return ix.Position(chunk.StartByte), ix.Position(chunk.EndByte)
+}
+
+// LSPRange returns the Language Server Protocol range of a chunk.
+func (ix *Index) LSPRange(chunk chunkx.Chunk) (start, end LSPPosition) {
+	return ix.LSP(chunk.StartByte), ix.LSP(chunk.EndByte)
+}
+
+// UTF16Range returns the UTF-16 offsets of the start and end of a chunk.
+func (ix *Index) UTF16Range(chunk chunkx.Chunk) (start, end int) {
+	return ix.UTF16(chunk.StartByte), ix.UTF16(chunk.EndByte)
+}
+
+// clamp clamps offset to the text.
+func (ix *Index) clamp(offset int) int {
+	return min(max(offset, 0), len(ix.text))
+}
+
+// line returns the 0-based line of a byte offset within the text.
+func (ix *Index) line(offset int) int {
+	return sort.Search(len(ix.starts), func(i int) bool { return ix.starts[i] > offset }) - 1
+}
+
+// lineEnd returns the byte offset of the line ending of a 0-based line, or the
+// end of the text for the last line.
+func (ix *Index) lineEnd(line int) int {
+	if line+1 == len(ix.starts) {
+		return len(ix.text)
+	}
+	end := ix.starts[line+1] - 1
+	if end > ix.starts[line] && ix.text[end] == '\n' && ix.text[end-1] == '\r' {
+		end--
+	}
+	return end
+}
+
+// runeStart moves offset back to the start of the character it is in, and
+// before the "\r" of a "\r\n" it splits.
+func (ix *Index) runeStart(offset int) int {
+	start := ix.starts[ix.line(offset)]
+	i := start
+	for i < offset {
+		_, size := utf8.DecodeRuneInString(ix.text[i:])
+		if i+size > offset {
+			break
+		}
+		i += size
+	}
+	if i > start && i < len(ix.text) && ix.text[i] == '\n' && ix.text[i-1] == '\r' {
+		i--
+	}
+	return i
+}
+
+// advance returns the byte offset after units UTF-16 code units from start,
+// stopping at limit.
+func (ix *Index) advance(start, limit, units int) int {
+	offset := start
+	for offset < limit && units > 0 {
+		r, size := utf8.DecodeRuneInString(ix.text[offset:limit])
+		n := utf16Len(r)
+		if n > units {
+			break
+		}
+		units -= n
+		offset += size
+	}
+	return offset
+}
+
+// countUTF16 returns the number of UTF-16 code units of s.
+func countUTF16(s string) int {
+	units := 0
+	for _, r := range s {
+		units += utf16Len(r)
+	}
+	return units
+}
+
+// utf16Len returns the number of UTF-16 code units of r.
+func utf16Len(r rune) int {
+	if r >= 0x10000 {
+		return 2
+	}
+	return 1
+}
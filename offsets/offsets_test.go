@@ -0,0 +1,109 @@
+package offsets
+
+import (
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+// text has every kind of line ending and characters of one to four bytes.
+const text = "a\r\nb€\rc😀d\n"
+
+func TestIndex(t *testing.T) {
+	tests := []struct {
+		name   string
+		offset int
+		pos    Position
+		lsp    LSPPosition
+		utf16  int
+	}{
+		{"start", 0, Position{1, 1}, LSPPosition{0, 0}, 0},
+		{"carriage return of crlf", 1, Position{1, 2}, LSPPosition{0, 1}, 1},
+		{"inside crlf", 2, Position{1, 2}, LSPPosition{0, 1}, 1},
+		{"after crlf", 3, Position{2, 1}, LSPPosition{1, 0}, 3},
+		{"three byte character", 4, Position{2, 2}, LSPPosition{1, 1}, 4},
+		{"inside character", 5, Position{2, 2}, LSPPosition{1, 1}, 4},
+		{"lone carriage return", 7, Position{2, 5}, LSPPosition{1, 2}, 5},
+		{"surrogate pair", 9, Position{3, 2}, LSPPosition{2, 1}, 7},
+		{"after surrogate pair", 13, Position{3, 6}, LSPPosition{2, 3}, 9},
+		{"end", 15, Position{4, 1}, LSPPosition{3, 0}, 11},
+		{"past end", 99, Position{4, 1}, LSPPosition{3, 0}, 11},
+		{"before start", -1, Position{1, 1}, LSPPosition{0, 0}, 0},
+	}
+
+	ix := New(text)
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := ix.Position(tt.offset); got != tt.pos {
+				t.Errorf("Position(%d) = %+v, want %+v", tt.offset, got, tt.pos)
+			}
+			if got := ix.LSP(tt.offset); got != tt.lsp {
+				t.Errorf("LSP(%d) = %+v, want %+v", tt.offset, got, tt.lsp)
+			}
+			if got := ix.UTF16(tt.offset); got != tt.utf16 {
+				t.Errorf("UTF16(%d) = %d, want %d", tt.offset, got, tt.utf16)
+			}
+		})
+	}
+}
+
+func TestIndex_RoundTrip(t *testing.T) {
+	ix := New(text)
+	for _, offset := range []int{0, 1, 3, 4, 7, 8, 9, 13, 14, 15} {
+		if got := ix.Offset(ix.Position(offset)); got != offset {
+			t.Errorf("Offset(Position(%d)) = %d", offset, got)
+		}
+		if got := ix.FromLSP(ix.LSP(offset)); got != offset {
+			t.Errorf("FromLSP(LSP(%d)) = %d", offset, got)
+		}
+		if got := ix.FromUTF16(ix.UTF16(offset)); got != offset {
+			t.Errorf("FromUTF16(UTF16(%d)) = %d", offset, got)
+		}
+	}
+}
+
+func TestIndex_Clamping(t *testing.T) {
+	ix := New(text)
+	tests := []struct {
+		name string
+		got  int
+		want int
+	}{
+		{"column past line end", ix.Offset(Position{3, 99}), 14},
+		{"column past crlf line end", ix.Offset(Position{1, 99}), 1},
+		{"column inside character", ix.Offset(Position{2, 3}), 4},
+		{"line past end", ix.Offset(Position{9, 1}), 15},
+		{"character past line end", ix.FromLSP(LSPPosition{1, 99}), 7},
+		{"character past crlf line end", ix.FromLSP(LSPPosition{0, 5}), 1},
+		{"lsp line past end", ix.FromLSP(LSPPosition{9, 0}), 15},
+		{"units inside surrogate pair", ix.FromUTF16(8), 9},
+		{"units past end", ix.FromUTF16(99), 15},
+	}
+	for _, tt := range tests {
+		if tt.got != tt.want {
+			t.Errorf("%s: got %d, want %d", tt.name, tt.got, tt.want)
+		}
+	}
+}
+
+func TestIndex_Range(t *testing.T) {
+	code := "package a\n\nfunc 😀() {}\n"
+	chunk := chunkx.Chunk{Content: "func 😀() {}", StartByte: 11, EndByte: 25, StartLine: 3, EndLine: 3}
+
+	ix := New(code)
+	start, end := ix.Range(chunk)
+	if start != (Position{3, 1}) || end != (Position{3, 15}) {
+		t.Errorf("Range = %+v, %+v", start, end)
+	}
+	if start.Line != chunk.StartLine {
+		t.Errorf("range starts on line %d, chunk on line %d", start.Line, chunk.StartLine)
+	}
+
+	lspStart, lspEnd := ix.LSPRange(chunk)
+	if lspStart != (LSPPosition{2, 0}) || lspEnd != (LSPPosition{2, 12}) {
+		t.Errorf("LSPRange = %+v, %+v", lspStart, lspEnd)
+	}
+	if from, to := ix.UTF16Range(chunk); from != 11 || to != 23 {
+		t.Errorf("UTF16Range = %d, %d", from, to)
+	}
+}
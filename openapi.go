@@ -0,0 +1,226 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+// Metadata keys set on chunks of OpenAPI documents chunked with WithOpenAPI.
+const (
+	MetadataRoute       = "route"        // Path of the operation, such as "/pets/{id}"
+	MetadataMethod      = "method"       // HTTP method of the operation, in upper case
+	MetadataOperationID = "operation_id" // operationId of the operation, if set
+	MetadataComponent   = "component"    // Reference of the component, such as "#/components/schemas/Pet"
+)
+
+// openAPIMethods are the keys of the operations of a path item.
+var openAPIMethods = map[string]bool{
+	"get": true, "put": true, "post": true, "delete": true,
+	"options": true, "head": true, "patch": true, "trace": true,
+}
+
+// openAPIVersion matches the top-level key naming the version of an OpenAPI
+// or Swagger document.
+var openAPIVersion = regexp.MustCompile(`(?m)^(?:openapi|swagger)[ \t]*:|"(?:openapi|swagger)"[ \t\r\n]*:`)
+
+// WithOpenAPI chunks OpenAPI 3 and Swagger 2 documents, in YAML or JSON, by
+// their operations and components, as API docs are best retrieved an
+// operation at a time. Each operation of a path, such as "get" under
+// "/pets", and each component, such as a schema under components.schemas or
+// definitions, is a chunk of its own, split only when it doesn't fit in one.
+// The rest of the document, such as its info and servers, is chunked as
+// usual between them.
+//
+// Chunks of operations record MetadataRoute, MetadataMethod, and
+// MetadataOperationID, and chunks of components record MetadataComponent.
+// YAML is read by its indentation, so operations and components must be in
+// block style. Content that isn't an OpenAPI document is chunked as usual.
+func WithOpenAPI() Option {
+	return func(c *config) {
+		c.openAPI = true
+	}
+}
+
+// looksLikeOpenAPI reports whether head starts an OpenAPI or Swagger
+// document.
+func looksLikeOpenAPI(head []byte) bool {
+	return openAPIVersion.Match(head)
+}
+
+// chunkOpenAPI chunks the OpenAPI document code by its operations and
+// components, returning false if code has none.
+func (c *castChunker) chunkOpenAPI(code string, cfg *config) ([]Chunk, bool, error) {
+	var units []textSpan
+	if root, ok := parseJSON(code); ok && code[root.start] == '{' {
+		units = jsonAPIUnits(code, root)
+	} else {
+		units = yamlAPIUnits(code)
+	}
+	if len(units) == 0 {
+		return nil, false, nil
+	}
+
+	// The call's timeout already runs over the whole document
+	inner := *cfg
+	inner.openAPI = false
+	inner.timeout = 0
+	chunks, err := chunkSpans(code, cfg, units, nil, func(code string) ([]Chunk, error) {
+		return c.chunk(code, &inner)
+	})
+	if err != nil {
+		return nil, true, err
+	}
+
+	if err := cfg.limits.checkChunks(chunks); err != nil {
+		return nil, true, err
+	}
+	return chunks, true, nil
+}
+
+// operationMetadata returns the metadata of the operation of method on route.
+func operationMetadata(route, method, operationID string) map[string]string {
+	metadata := map[string]string{MetadataRoute: route, MetadataMethod: strings.ToUpper(method)}
+	if operationID != "" {
+		metadata[MetadataOperationID] = operationID
+	}
+	return metadata
+}
+
+// jsonAPIUnits returns the operations and components of the JSON document
+// code, each from its key to the end of its value.
+func jsonAPIUnits(code string, root jsonValue) []textSpan {
+	var units []textSpan
+	paths, _ := root.get("paths")
+	for i, route := range paths.keys {
+		item := paths.values[i]
+		for j, method := range item.keys {
+			if !openAPIMethods[method] {
+				continue
+			}
+			operation := item.values[j]
+			id, _ := operation.get("operationId")
+			units = append(units, textSpan{
+				start:    item.keyStarts[j],
+				end:      operation.end,
+				metadata: operationMetadata(route, method, jsonString(code, id)),
+			})
+		}
+	}
+
+	add := func(prefix string, section jsonValue) {
+		for i, name := range section.keys {
+			units = append(units, textSpan{
+				start:    section.keyStarts[i],
+				end:      section.values[i].end,
+				metadata: map[string]string{MetadataComponent: prefix + name},
+			})
+		}
+	}
+	components, _ := root.get("components")
+	for i, kind := range components.keys {
+		add("#/components/"+kind+"/", components.values[i])
+	}
+	definitions, _ := root.get("definitions")
+	add("#/definitions/", definitions)
+	return units
+}
+
+// yamlAPIUnits returns the operations and components of the YAML document
+// code, each from the line of its key to the end of the last line of its
+// value.
+func yamlAPIUnits(code string) []textSpan {
+	var units []textSpan
+	add := func(prefix string, section yamlEntry) {
+		for _, entry := range yamlChildren(code, section) {
+			units = append(units, textSpan{
+				start:    entry.start,
+				end:      entry.end,
+				metadata: map[string]string{MetadataComponent: prefix + entry.key},
+			})
+		}
+	}
+
+	for _, top := range yamlEntries(code, 0, len(code), -1) {
+		switch top.key {
+		case "paths":
+			for _, route := range yamlChildren(code, top) {
+				for _, method := range yamlChildren(code, route) {
+					if !openAPIMethods[method.key] {
+						continue
+					}
+					var id string
+					for _, field := range yamlChildren(code, method) {
+						if field.key == "operationId" {
+							id = field.value
+						}
+					}
+					units = append(units, textSpan{
+						start:    method.start,
+						end:      method.end,
+						metadata: operationMetadata(route.key, method.key, id),
+					})
+				}
+			}
+		case "components":
+			for _, kind := range yamlChildren(code, top) {
+				add("#/components/"+kind.key+"/", kind)
+			}
+		case "definitions":
+			add("#/definitions/", top)
+		}
+	}
+	return units
+}
+
+// yamlEntry is a key of a block mapping of YAML, with the lines of its value.
+type yamlEntry struct {
+	key        string
+	value      string // Scalar on the line of the key, if any
+	indent     int
+	start, end int // Offsets of the line of the key and after the last line of the value
+}
+
+// yamlChildren returns the keys of the block mapping that is the value of
+// entry.
+func yamlChildren(code string, entry yamlEntry) []yamlEntry {
+	line, _, _ := strings.Cut(code[entry.start:entry.end], "\n")
+	return yamlEntries(code, min(entry.start+len(line)+1, entry.end), entry.end, entry.indent)
+}
+
+// yamlEntries returns the keys from start to end of code indented the least,
+// and deeper than indent. The value of a key runs over the lines indented
+// deeper than it, leaving out blank and comment lines after them.
+func yamlEntries(code string, start, end, indent int) []yamlEntry {
+	var entries []yamlEntry
+	level := -1   // Indentation of the keys
+	open := false // Whether lines indented deeper belong to the last key
+	for offset := start; offset < end; {
+		line, _, _ := strings.Cut(code[offset:end], "\n")
+		next := min(offset+len(line)+1, end)
+		content := strings.TrimSpace(stripConfigComment(line))
+		depth := len(line) - len(strings.TrimLeft(line, " "))
+		if content == "" || content == "---" {
+			offset = next
+			continue
+		}
+		if depth <= indent || (level >= 0 && depth < level) {
+			break
+		}
+
+		if level < 0 {
+			level = depth
+		}
+		if depth == level {
+			key, value, ok := cutYAMLKey(content)
+			open = ok && !strings.HasPrefix(content, "- ")
+			if open {
+				value, _ = parseConfigScalar(value)
+				entries = append(entries, yamlEntry{key: key, value: value, indent: depth, start: offset, end: next})
+			}
+		} else if open {
+			entries[len(entries)-1].end = next
+		}
+		offset = next
+	}
+	return entries
+}
@@ -0,0 +1,84 @@
+package chunkx
+
+import (
+	"strconv"
+	"strings"
+)
+
+// Metadata keys set on chunks made with overlap.
+const (
+	MetadataOverlapBefore = "overlap_before" // Leading bytes of Content the previous chunk also holds
+	MetadataOverlapAfter  = "overlap_after"  // Trailing bytes of Content the next chunk also holds
+)
+
+// Overlap returns the number of leading bytes of the chunk's content that
+// the previous chunk also holds, and the number of trailing bytes that the
+// next chunk also holds. Both are zero for chunks made without overlap. A
+// chunk shorter than the overlaps of its neighbors is entirely shared, and
+// its two overlaps can then cover some bytes twice.
+func (c Chunk) Overlap() (before, after int) {
+	return c.overlapLen(MetadataOverlapBefore), c.overlapLen(MetadataOverlapAfter)
+}
+
+// UniqueContent returns the part of the chunk's content that neither of its
+// neighbors holds, which is empty for chunks that are entirely shared.
+func (c Chunk) UniqueContent() string {
+	before, after := c.Overlap()
+	if before+after >= len(c.Content) {
+		return ""
+	}
+	return c.Content[before : len(c.Content)-after]
+}
+
+// overlapLen returns the overlap recorded under key, clamped to the content.
+func (c Chunk) overlapLen(key string) int {
+	n, err := strconv.Atoi(c.Metadata[key])
+	if err != nil {
+		return 0
+	}
+	return min(max(n, 0), len(c.Content))
+}
+
+// setOverlap records an overlap of n bytes under key.
+func setOverlap(chunk *Chunk, key string, n int) {
+	if chunk.Metadata == nil {
+		chunk.Metadata = make(map[string]string, 2)
+	}
+	chunk.Metadata[key] = strconv.Itoa(n)
+}
+
+// normalizeChunk normalizes the line endings of a chunk's content, keeping
+// its overlaps on the same bytes.
+func normalizeChunk(chunk *Chunk) {
+	content := chunk.Content
+	chunk.Content = normalizeNewlines(content)
+	if len(chunk.Content) == len(content) {
+		return
+	}
+
+	before, after := chunk.Overlap()
+	if before > 0 {
+		setOverlap(chunk, MetadataOverlapBefore, len(normalizeNewlines(content[:before])))
+	}
+	if after > 0 {
+		setOverlap(chunk, MetadataOverlapAfter, len(normalizeNewlines(content[len(content)-after:])))
+	}
+}
+
+// Reconstruct returns the text covered by the chunks of a single input, from
+// the start of its first chunk to the end of its last, by joining each chunk's
+// content without the bytes it shares with the previous one.
+//
+// Chunks made with overlap cover every byte of that text, so it is
+// reconstructed exactly, with the line endings of WithNormalizedNewlines if
+// it was set. Whitespace and a byte order mark before the first chunk or after
+// the last are in no chunk and are not reconstructed. Without overlap, the
+// whitespace between chunks is also left out.
+func Reconstruct(chunks []Chunk) string {
+	var b strings.Builder
+	for _, chunk := range chunks {
+		before, _ := chunk.Overlap()
+		b.WriteString(chunk.Content[before:])
+	}
+	return b.String()
+}
@@ -0,0 +1,136 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+	"testing/quick"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunk_Overlap(t *testing.T) {
+	code := "alpha beta\ngamma delta\nepsilon zeta\neta theta"
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Generic), WithMaxSize(2), WithOverlap(20))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	want := []struct {
+		content       string
+		lines         [2]int
+		before, after int
+		unique        string
+	}{
+		{"alpha beta\nga", [2]int{1, 2}, 0, 5, "alpha be"},
+		{"ta\ngamma delta\nep", [2]int{1, 3}, 5, 5, "mma del"},
+		{"ta\nepsilon zeta\net", [2]int{2, 4}, 5, 4, "silon zet"},
+		{"a\neta theta", [2]int{3, 4}, 4, 0, "a theta"},
+	}
+	if len(chunks) != len(want) {
+		t.Fatalf("expected %d chunks, got %+v", len(want), chunks)
+	}
+	for i, w := range want {
+		chunk := chunks[i]
+		if chunk.Content != w.content {
+			t.Errorf("chunk %d content = %q, want %q", i, chunk.Content, w.content)
+		}
+		if code[chunk.StartByte:chunk.EndByte] != chunk.Content {
+			t.Errorf("chunk %d content %q isn't its bytes", i, chunk.Content)
+		}
+		if lines := [2]int{chunk.StartLine, chunk.EndLine}; lines != w.lines {
+			t.Errorf("chunk %d lines = %v, want %v", i, lines, w.lines)
+		}
+		if before, after := chunk.Overlap(); before != w.before || after != w.after {
+			t.Errorf("chunk %d overlap = %d, %d, want %d, %d", i, before, after, w.before, w.after)
+		}
+		if got := chunk.UniqueContent(); got != w.unique {
+			t.Errorf("chunk %d unique content = %q, want %q", i, got, w.unique)
+		}
+	}
+
+	if got := Reconstruct(chunks); got != code {
+		t.Errorf("chunks reconstruct %q, want %q", got, code)
+	}
+}
+
+func TestReconstruct_WithoutOverlap(t *testing.T) {
+	chunks := []Chunk{{Content: "one"}, {Content: "two"}}
+	if got := Reconstruct(chunks); got != "onetwo" {
+		t.Errorf("chunks reconstruct %q, want %q", got, "onetwo")
+	}
+	if got := Reconstruct(nil); got != "" {
+		t.Errorf("no chunks reconstruct %q, want nothing", got)
+	}
+}
+
+func TestReconstruct_NormalizedNewlines(t *testing.T) {
+	code := "one\r\ntwo\r\n\r\nthree\rfour\r\n"
+	chunks, err := NewChunker().Chunk(code,
+		WithLanguage(languages.Generic), WithMaxSize(1), WithOverlap(50), WithNormalizedNewlines())
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	want := normalizeNewlines(code[chunks[0].StartByte:chunks[len(chunks)-1].EndByte])
+	if got := Reconstruct(chunks); got != want {
+		t.Errorf("chunks reconstruct %q, want %q", got, want)
+	}
+}
+
+// TestReconstruct_Property checks that chunks made with overlap reconstruct
+// the text they cover, whatever the text, size, and overlap.
+func TestReconstruct_Property(t *testing.T) {
+	pieces := []string{"a", "word", "func", " ", "  ", "\t", "\n", "\n\n", "\r\n", "\r", "é", "{", "}"}
+
+	property := func(picks []uint8, maxSize, overlap uint8, lang bool, normalize bool) bool {
+		var b strings.Builder
+		for _, pick := range picks {
+			b.WriteString(pieces[int(pick)%len(pieces)])
+		}
+		code := b.String()
+
+		opts := []Option{WithMaxSize(int(maxSize%20) + 1), WithOverlap(float64(overlap%MaxOverlap) + 1)}
+		if lang {
+			opts = append(opts, WithLanguage(languages.Go))
+		} else {
+			opts = append(opts, WithLanguage(languages.Generic))
+		}
+		if normalize {
+			opts = append(opts, WithNormalizedNewlines())
+		}
+
+		chunks, err := NewChunker().Chunk(code, opts...)
+		if err != nil {
+			t.Logf("unexpected error: %v", err)
+			return false
+		}
+		if len(chunks) == 0 {
+			return true
+		}
+
+		span := code[chunks[0].StartByte:chunks[len(chunks)-1].EndByte]
+		if normalize {
+			span = normalizeNewlines(span)
+		}
+		if got := Reconstruct(chunks); got != span {
+			t.Logf("chunks of %q reconstruct %q, want %q", code, got, span)
+			return false
+		}
+
+		// Consecutive chunks agree on the bytes they share
+		for i := 1; i < len(chunks); i++ {
+			_, after := chunks[i-1].Overlap()
+			before, _ := chunks[i].Overlap()
+			prev := chunks[i-1].Content
+			if before != after || prev[len(prev)-after:] != chunks[i].Content[:before] {
+				t.Logf("chunks %d and %d of %q disagree on their overlap", i-1, i, code)
+				return false
+			}
+		}
+		return true
+	}
+
+	if err := quick.Check(property, &quick.Config{MaxCount: 500}); err != nil {
+		t.Error(err)
+	}
+}
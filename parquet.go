@@ -0,0 +1,385 @@
+package chunkx
+
+import (
+	"encoding/binary"
+	"errors"
+	"io"
+	"maps"
+	"slices"
+)
+
+// errWriterClosed is returned when writing to a closed writer.
+var errWriterClosed = errors.New("chunkx: writer is closed")
+
+// parquetRowGroupBytes is the amount of buffered column data at which a
+// ParquetWriter flushes a row group.
+const parquetRowGroupBytes = 64 << 20
+
+// Parquet physical types, repetition types, and converted types used by
+// ParquetWriter. See https://github.com/apache/parquet-format.
+const (
+	parquetInt64     = 2
+	parquetByteArray = 6
+
+	parquetRequired = 0
+	parquetOptional = 1
+	parquetRepeated = 2
+
+	parquetUTF8        = 0
+	parquetMap         = 1
+	parquetMapKeyValue = 2
+
+	parquetPlain = 0
+	parquetRLE   = 3
+)
+
+// parquetColumns are the leaf columns written by ParquetWriter, in schema order.
+var parquetColumns = []struct {
+	path    []string
+	integer bool
+	levels  bool // Part of the metadata map, with repetition and definition levels
+}{
+	{path: []string{"id"}},
+	{path: []string{"path"}},
+	{path: []string{"language"}},
+	{path: []string{"text"}},
+	{path: []string{"start_line"}, integer: true},
+	{path: []string{"end_line"}, integer: true},
+	{path: []string{"start_byte"}, integer: true},
+	{path: []string{"end_byte"}, integer: true},
+	{path: []string{"metadata", "key_value", "key"}, levels: true},
+	{path: []string{"metadata", "key_value", "value"}, levels: true},
+}
+
+// ParquetWriter writes chunks as a Parquet file, for loading into DuckDB,
+// Spark, or pandas without a JSON intermediate. Each chunk is a row with the
+// columns id, path, language, text, start_line, end_line, start_byte,
+// end_byte, and metadata, a map of strings.
+//
+// Rows are buffered and written in row groups of about 64 MiB of column data.
+// Values are stored uncompressed with plain encoding. The output is written
+// sequentially, so w need not support seeking.
+type ParquetWriter struct {
+	w       io.Writer
+	offset  int64
+	err     error
+	columns [][]byte // Plain-encoded values of each leaf column
+	rep     []byte   // Repetition levels of the metadata columns
+	def     []byte   // Definition levels of the metadata columns
+	rows    int64    // Rows buffered in the current row group
+	total   int64    // Rows written in earlier row groups
+	groups  []thriftStruct
+}
+
+// NewParquetWriter creates a writer that writes a Parquet file to w. Close
+// must be called to write the file footer.
+func NewParquetWriter(w io.Writer) *ParquetWriter {
+	return &ParquetWriter{
+		w:       w,
+		columns: make([][]byte, len(parquetColumns)),
+	}
+}
+
+// Write adds a row for each chunk of the file at path.
+func (pw *ParquetWriter) Write(path string, chunks ...Chunk) error {
+	if pw.err != nil {
+		return pw.err
+	}
+
+	for _, chunk := range chunks {
+		for i, s := range []string{chunk.ID(), path, string(chunk.Language), chunk.Content} {
+			pw.columns[i] = appendParquetBytes(pw.columns[i], s)
+		}
+		for i, n := range []int{chunk.StartLine, chunk.EndLine, chunk.StartByte, chunk.EndByte} {
+			pw.columns[4+i] = binary.LittleEndian.AppendUint64(pw.columns[4+i], uint64(n))
+		}
+
+		// An empty map is written as null: defined at level 0
+		if len(chunk.Metadata) == 0 {
+			pw.rep = append(pw.rep, 0)
+			pw.def = append(pw.def, 0)
+		}
+		for i, key := range slices.Sorted(maps.Keys(chunk.Metadata)) {
+			// Only the first entry of a map starts a new row
+			rep := byte(1)
+			if i == 0 {
+				rep = 0
+			}
+			pw.rep = append(pw.rep, rep)
+			pw.def = append(pw.def, 2)
+			pw.columns[8] = appendParquetBytes(pw.columns[8], key)
+			pw.columns[9] = appendParquetBytes(pw.columns[9], chunk.Metadata[key])
+		}
+		pw.rows++
+	}
+
+	size := 0
+	for _, column := range pw.columns {
+		size += len(column)
+	}
+	if size >= parquetRowGroupBytes {
+		pw.err = pw.flush()
+	}
+	return pw.err
+}
+
+// Close flushes buffered rows and writes the file footer. It does not close
+// the underlying writer.
+func (pw *ParquetWriter) Close() error {
+	if pw.err != nil {
+		return pw.err
+	}
+	if pw.offset == 0 {
+		pw.write([]byte("PAR1"))
+	}
+	if pw.rows > 0 {
+		pw.err = pw.flush()
+	}
+	if pw.err != nil {
+		return pw.err
+	}
+
+	schema := thriftList{elem: thriftTypeStruct, items: []any{
+		thriftStruct{{4, "schema"}, {5, int32(9)}},
+		parquetStringElement("id"),
+		parquetStringElement("path"),
+		parquetStringElement("language"),
+		parquetStringElement("text"),
+		parquetInt64Element("start_line"),
+		parquetInt64Element("end_line"),
+		parquetInt64Element("start_byte"),
+		parquetInt64Element("end_byte"),
+		thriftStruct{{3, int32(parquetOptional)}, {4, "metadata"}, {5, int32(1)}, {6, int32(parquetMap)}},
+		thriftStruct{{3, int32(parquetRepeated)}, {4, "key_value"}, {5, int32(2)}, {6, int32(parquetMapKeyValue)}},
+		parquetStringElement("key"),
+		parquetStringElement("value"),
+	}}
+
+	groups := make([]any, len(pw.groups))
+	for i, group := range pw.groups {
+		groups[i] = group
+	}
+
+	footer := appendThriftStruct(nil, thriftStruct{
+		{1, int32(1)},
+		{2, schema},
+		{3, pw.total},
+		{4, thriftList{elem: thriftTypeStruct, items: groups}},
+		{6, "chunkx"},
+	})
+	footer = binary.LittleEndian.AppendUint32(footer, uint32(len(footer)))
+	footer = append(footer, "PAR1"...)
+	pw.write(footer)
+	if pw.err != nil {
+		return pw.err
+	}
+
+	// Reject writes after the footer
+	pw.err = errWriterClosed
+	return nil
+}
+
+// flush writes the buffered rows as a row group.
+func (pw *ParquetWriter) flush() error {
+	if pw.offset == 0 {
+		pw.write([]byte("PAR1"))
+	}
+
+	var chunks []any
+	var groupSize int64
+	for i, col := range parquetColumns {
+		var page []byte
+		numValues := pw.rows
+		if col.levels {
+			page = appendParquetLevels(page, pw.rep)
+			page = appendParquetLevels(page, pw.def)
+			numValues = int64(len(pw.def))
+		}
+		page = append(page, pw.columns[i]...)
+
+		header := appendThriftStruct(nil, thriftStruct{
+			{1, int32(0)}, // DATA_PAGE
+			{2, int32(len(page))},
+			{3, int32(len(page))},
+			{5, thriftStruct{
+				{1, int32(numValues)},
+				{2, int32(parquetPlain)},
+				{3, int32(parquetRLE)},
+				{4, int32(parquetRLE)},
+			}},
+		})
+
+		physical := int32(parquetByteArray)
+		if col.integer {
+			physical = parquetInt64
+		}
+		path := make([]any, len(col.path))
+		for j, name := range col.path {
+			path[j] = name
+		}
+
+		start := pw.offset
+		size := int64(len(header) + len(page))
+		chunks = append(chunks, thriftStruct{
+			{2, start},
+			{3, thriftStruct{
+				{1, physical},
+				{2, thriftList{elem: thriftTypeI32, items: []any{int32(parquetPlain), int32(parquetRLE)}}},
+				{3, thriftList{elem: thriftTypeBinary, items: path}},
+				{4, int32(0)}, // UNCOMPRESSED
+				{5, numValues},
+				{6, size},
+				{7, size},
+				{9, start},
+			}},
+		})
+		groupSize += size
+
+		pw.write(header)
+		pw.write(page)
+		pw.columns[i] = pw.columns[i][:0]
+	}
+
+	pw.groups = append(pw.groups, thriftStruct{
+		{1, thriftList{elem: thriftTypeStruct, items: chunks}},
+		{2, groupSize},
+		{3, pw.rows},
+	})
+	pw.total += pw.rows
+	pw.rows = 0
+	pw.rep, pw.def = pw.rep[:0], pw.def[:0]
+
+	return pw.err
+}
+
+// write writes b, keeping track of the offset and the first error.
+func (pw *ParquetWriter) write(b []byte) {
+	if pw.err != nil {
+		return
+	}
+	n, err := pw.w.Write(b)
+	pw.offset += int64(n)
+	pw.err = err
+}
+
+// parquetStringElement describes a required UTF-8 string column.
+func parquetStringElement(name string) thriftStruct {
+	return thriftStruct{{1, int32(parquetByteArray)}, {3, int32(parquetRequired)}, {4, name}, {6, int32(parquetUTF8)}}
+}
+
+// parquetInt64Element describes a required 64-bit integer column.
+func parquetInt64Element(name string) thriftStruct {
+	return thriftStruct{{1, int32(parquetInt64)}, {3, int32(parquetRequired)}, {4, name}}
+}
+
+// appendParquetBytes appends a plain-encoded byte array value.
+func appendParquetBytes(b []byte, s string) []byte {
+	b = binary.LittleEndian.AppendUint32(b, uint32(len(s)))
+	return append(b, s...)
+}
+
+// appendParquetLevels appends levels with the RLE/bit-packing hybrid
+// encoding, as runs of repeated values, prefixed by their length. Levels are
+// at most 2, so each run's value fits in a single byte.
+func appendParquetLevels(b []byte, levels []byte) []byte {
+	var runs []byte
+	for i := 0; i < len(levels); {
+		j := i
+		for j < len(levels) && levels[j] == levels[i] {
+			j++
+		}
+		runs = binary.AppendUvarint(runs, uint64(j-i)<<1)
+		runs = append(runs, levels[i])
+		i = j
+	}
+
+	b = binary.LittleEndian.AppendUint32(b, uint32(len(runs)))
+	return append(b, runs...)
+}
+
+// Thrift compact protocol type identifiers.
+const (
+	thriftTypeI32    = 5
+	thriftTypeI64    = 6
+	thriftTypeBinary = 8
+	thriftTypeList   = 9
+	thriftTypeStruct = 12
+)
+
+// thriftStruct is a struct encoded with the Thrift compact protocol, as used
+// by Parquet metadata. Field values are int32, int64, string, thriftStruct, or
+// thriftList.
+type thriftStruct []thriftField
+
+// thriftField is a field of a thriftStruct.
+type thriftField struct {
+	id    int16
+	value any
+}
+
+// thriftList is a list of values of a single type.
+type thriftList struct {
+	elem  byte
+	items []any
+}
+
+// appendThriftStruct appends the compact encoding of s.
+func appendThriftStruct(b []byte, s thriftStruct) []byte {
+	var last int16
+	for _, f := range s {
+		typ := thriftType(f.value)
+		if delta := f.id - last; delta > 0 && delta <= 15 {
+			b = append(b, byte(delta)<<4|typ)
+		} else {
+			b = append(b, typ)
+			b = binary.AppendVarint(b, int64(f.id))
+		}
+		b = appendThriftValue(b, f.value)
+		last = f.id
+	}
+	return append(b, 0) // Stop field
+}
+
+// appendThriftValue appends the compact encoding of a value.
+func appendThriftValue(b []byte, v any) []byte {
+	switch v := v.(type) {
+	case int32:
+		return binary.AppendVarint(b, int64(v))
+	case int64:
+		return binary.AppendVarint(b, v)
+	case string:
+		b = binary.AppendUvarint(b, uint64(len(v)))
+		return append(b, v...)
+	case thriftStruct:
+		return appendThriftStruct(b, v)
+	case thriftList:
+		if len(v.items) < 15 {
+			b = append(b, byte(len(v.items))<<4|v.elem)
+		} else {
+			b = append(b, 0xf0|v.elem)
+			b = binary.AppendUvarint(b, uint64(len(v.items)))
+		}
+		for _, item := range v.items {
+			b = appendThriftValue(b, item)
+		}
+		return b
+	default:
+		panic("chunkx: unsupported thrift value")
+	}
+}
+
+// thriftType returns the compact type identifier of a value.
+func thriftType(v any) byte {
+	switch v.(type) {
+	case int32:
+		return thriftTypeI32
+	case int64:
+		return thriftTypeI64
+	case string:
+		return thriftTypeBinary
+	case thriftList:
+		return thriftTypeList
+	default:
+		return thriftTypeStruct
+	}
+}
@@ -0,0 +1,160 @@
+package chunkx
+
+import (
+	"bytes"
+	"encoding/binary"
+	"testing"
+)
+
+// readThriftStruct decodes a compact-protocol struct into its field values by
+// ID: int64 for integers, string for binaries, map for structs, and []any for
+// lists. It returns the remaining input.
+func readThriftStruct(t *testing.T, b []byte) (map[int16]any, []byte) {
+	t.Helper()
+
+	fields := make(map[int16]any)
+	var last int16
+	for {
+		header := b[0]
+		b = b[1:]
+		if header == 0 {
+			return fields, b
+		}
+
+		id := last + int16(header>>4)
+		if header>>4 == 0 {
+			v, n := binary.Varint(b)
+			id, b = int16(v), b[n:]
+		}
+		fields[id], b = readThriftValue(t, header&0x0f, b)
+		last = id
+	}
+}
+
+// readThriftValue decodes a compact-protocol value of the given type.
+func readThriftValue(t *testing.T, typ byte, b []byte) (any, []byte) {
+	t.Helper()
+
+	switch typ {
+	case thriftTypeI32, thriftTypeI64:
+		v, n := binary.Varint(b)
+		return v, b[n:]
+	case thriftTypeBinary:
+		size, n := binary.Uvarint(b)
+		b = b[n:]
+		return string(b[:size]), b[size:]
+	case thriftTypeStruct:
+		return readThriftStruct(t, b)
+	case thriftTypeList:
+		size, elem := int(b[0]>>4), b[0]&0x0f
+		b = b[1:]
+		if size == 15 {
+			v, n := binary.Uvarint(b)
+			size, b = int(v), b[n:]
+		}
+		items := make([]any, size)
+		for i := range items {
+			items[i], b = readThriftValue(t, elem, b)
+		}
+		return items, b
+	default:
+		t.Fatalf("unexpected thrift type %d", typ)
+		return nil, nil
+	}
+}
+
+func TestParquetWriter(t *testing.T) {
+	chunks := []Chunk{
+		{Content: "package main", StartLine: 1, EndLine: 1, EndByte: 12, Language: "go"},
+		{Content: "func main() {}", StartLine: 3, EndLine: 3, StartByte: 14, EndByte: 28, Language: "go",
+			Metadata: map[string]string{MetadataArchive: "repo.zip", MetadataArchivePath: "main.go"}},
+	}
+
+	var buf bytes.Buffer
+	pw := NewParquetWriter(&buf)
+	if err := pw.Write("main.go", chunks...); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := pw.Close(); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := pw.Write("main.go", chunks...); err == nil {
+		t.Error("expected an error writing after Close")
+	}
+
+	file := buf.Bytes()
+	if !bytes.HasPrefix(file, []byte("PAR1")) || !bytes.HasSuffix(file, []byte("PAR1")) {
+		t.Fatal("missing Parquet magic")
+	}
+
+	footerLen := int(binary.LittleEndian.Uint32(file[len(file)-8:]))
+	meta, rest := readThriftStruct(t, file[len(file)-8-footerLen:len(file)-8])
+	if len(rest) != 0 {
+		t.Errorf("%d trailing bytes after the footer", len(rest))
+	}
+	if meta[3] != int64(2) {
+		t.Errorf("num_rows = %v, want 2", meta[3])
+	}
+	if schema := meta[2].([]any); len(schema) != 13 {
+		t.Errorf("expected 13 schema elements, got %d", len(schema))
+	}
+
+	groups := meta[4].([]any)
+	if len(groups) != 1 {
+		t.Fatalf("expected 1 row group, got %d", len(groups))
+	}
+	columns := groups[0].(map[int16]any)[1].([]any)
+	if len(columns) != len(parquetColumns) {
+		t.Fatalf("expected %d columns, got %d", len(parquetColumns), len(columns))
+	}
+
+	// pageData returns the values of the data page of column i
+	pageData := func(i int) (map[int16]any, []byte) {
+		column := columns[i].(map[int16]any)[3].(map[int16]any)
+		offset := column[9].(int64)
+		header, page := readThriftStruct(t, file[offset:])
+		return header, page[:header[2].(int64)]
+	}
+
+	_, ids := pageData(0)
+	first := binary.LittleEndian.Uint32(ids)
+	if got := string(ids[4 : 4+first]); got != chunks[0].ID() {
+		t.Errorf("first id = %q, want %q", got, chunks[0].ID())
+	}
+
+	_, starts := pageData(6)
+	if got := binary.LittleEndian.Uint64(starts[8:]); got != 14 {
+		t.Errorf("second start_byte = %d, want 14", got)
+	}
+
+	// The key column holds a null for the first row and two keys for the
+	// second, encoded as runs of levels: one 0, then two 2s
+	header, keys := pageData(8)
+	if n := header[5].(map[int16]any)[1]; n != int64(3) {
+		t.Errorf("key column num_values = %v, want 3", n)
+	}
+	repLen := binary.LittleEndian.Uint32(keys)
+	keys = keys[4+repLen:]
+	defLen := binary.LittleEndian.Uint32(keys)
+	if want := []byte{2, 0, 4, 2}; !bytes.Equal(keys[4:4+defLen], want) {
+		t.Errorf("definition levels = %v, want %v", keys[4:4+defLen], want)
+	}
+	keys = keys[4+defLen:]
+	if got := string(keys[4 : 4+binary.LittleEndian.Uint32(keys)]); got != MetadataArchive {
+		t.Errorf("first key = %q, want %q", got, MetadataArchive)
+	}
+}
+
+func TestParquetWriter_Empty(t *testing.T) {
+	var buf bytes.Buffer
+	if err := NewParquetWriter(&buf).Close(); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	file := buf.Bytes()
+	footerLen := int(binary.LittleEndian.Uint32(file[len(file)-8:]))
+	meta, _ := readThriftStruct(t, file[len(file)-8-footerLen:len(file)-8])
+	if meta[3] != int64(0) || len(meta[4].([]any)) != 0 {
+		t.Errorf("expected an empty file, got %v", meta)
+	}
+}
@@ -0,0 +1,151 @@
+//go:build cgo
+
+package chunkx
+
+import (
+	"container/list"
+	"context"
+	"crypto/sha256"
+	"sync"
+
+	"github.com/gomantics/chunkx/languages"
+	sitter "github.com/smacker/go-tree-sitter"
+)
+
+// CacheStats reports the activity of a ParseCache.
+type CacheStats struct {
+	Hits      uint64 // Lookups served from the cache
+	Misses    uint64 // Lookups that required parsing
+	Evictions uint64 // Entries dropped to stay within the memory bound
+	Entries   int    // Trees currently cached
+	Bytes     int64  // Estimated memory held by cached trees
+}
+
+// ParseCache caches parsed syntax trees keyed by a hash of the language and
+// content, so repeated chunking of unchanged files skips parsing entirely.
+//
+// Memory use is estimated from the size of the cached sources, and the least
+// recently used trees are evicted once the bound is exceeded. A ParseCache is
+// safe for concurrent use and may be shared between chunkers.
+type ParseCache struct {
+	mu       sync.Mutex
+	maxBytes int64
+	entries  map[cacheKey]*list.Element
+	lru      *list.List
+	stats    CacheStats
+}
+
+// cacheKey identifies a parsed source.
+type cacheKey struct {
+	language languages.LanguageName
+	hash     [sha256.Size]byte
+}
+
+// cacheEntry is a cached parse result. Its mutex serializes use of the tree,
+// which isn't safe to walk from several goroutines at once. The entry holds
+// no source: every caller gets the tree with its own source, so chunks never
+// borrow the memory of an earlier caller, which may be gone, as a closed
+// MappedFile is.
+type cacheEntry struct {
+	mu       sync.Mutex
+	key      cacheKey
+	tree     *sitter.Tree
+	language languages.LanguageName
+	size     int64
+}
+
+// NewParseCache creates a parse cache holding at most maxBytes of estimated
+// tree memory. A maxBytes of zero or less leaves the cache unbounded.
+func NewParseCache(maxBytes int64) *ParseCache {
+	return &ParseCache{
+		maxBytes: maxBytes,
+		entries:  make(map[cacheKey]*list.Element),
+		lru:      list.New(),
+	}
+}
+
+// Stats returns a snapshot of the cache statistics.
+func (pc *ParseCache) Stats() CacheStats {
+	pc.mu.Lock()
+	defer pc.mu.Unlock()
+
+	stats := pc.stats
+	stats.Entries = pc.lru.Len()
+	return stats
+}
+
+// Clear removes all cached trees. Statistics are kept.
+func (pc *ParseCache) Clear() {
+	pc.mu.Lock()
+	defer pc.mu.Unlock()
+
+	pc.entries = make(map[cacheKey]*list.Element)
+	pc.lru.Init()
+	pc.stats.Bytes = 0
+}
+
+// parse returns the cached tree for code, parsing and caching it on a miss.
+// The returned release function must be called once the tree is no longer in use.
+func (pc *ParseCache) parse(ctx context.Context, parser *Parser, code string, language languages.LanguageName) (*ParseResult, func(), error) {
+	key := cacheKey{
+		language: language,
+		hash:     sha256.Sum256(stringBytes(code)),
+	}
+
+	pc.mu.Lock()
+	if elem, ok := pc.entries[key]; ok {
+		pc.lru.MoveToFront(elem)
+		pc.stats.Hits++
+		pc.mu.Unlock()
+
+		entry := elem.Value.(*cacheEntry)
+		entry.mu.Lock()
+		return &ParseResult{Tree: entry.tree, Language: entry.language, Source: stringBytes(code)}, entry.mu.Unlock, nil
+	}
+	pc.stats.Misses++
+	pc.mu.Unlock()
+
+	result, err := parser.ParseCtx(ctx, code, language)
+	if err != nil {
+		return nil, nil, err
+	}
+
+	entry := &cacheEntry{
+		key:      key,
+		tree:     result.Tree,
+		language: result.Language,
+		size:     int64(len(code)),
+	}
+	entry.mu.Lock()
+	pc.add(entry)
+
+	return result, entry.mu.Unlock, nil
+}
+
+// add inserts an entry and evicts the least recently used entries until the
+// cache fits its memory bound again.
+func (pc *ParseCache) add(entry *cacheEntry) {
+	pc.mu.Lock()
+	defer pc.mu.Unlock()
+
+	// Entries larger than the whole cache are never stored
+	if pc.maxBytes > 0 && entry.size > pc.maxBytes {
+		return
+	}
+
+	// Another goroutine may have cached the same content meanwhile
+	if _, ok := pc.entries[entry.key]; ok {
+		return
+	}
+
+	pc.entries[entry.key] = pc.lru.PushFront(entry)
+	pc.stats.Bytes += entry.size
+
+	for pc.maxBytes > 0 && pc.stats.Bytes > pc.maxBytes {
+		oldest := pc.lru.Back()
+		evicted := pc.lru.Remove(oldest).(*cacheEntry)
+		delete(pc.entries, evicted.key)
+		pc.stats.Bytes -= evicted.size
+		pc.stats.Evictions++
+	}
+}
@@ -0,0 +1,141 @@
+//go:build cgo
+
+package chunkx
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"unsafe"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestParseCache_HitsAndMisses(t *testing.T) {
+	cache := NewParseCache(0)
+	chunker := NewChunker()
+	code := `func hello() { return "world" }`
+
+	first, err := chunker.Chunk(code, WithLanguage(languages.Go), WithParseCache(cache))
+	if err != nil {
+		t.Fatalf("Chunk() error = %v", err)
+	}
+	second, err := chunker.Chunk(code, WithLanguage(languages.Go), WithParseCache(cache))
+	if err != nil {
+		t.Fatalf("Chunk() error = %v", err)
+	}
+
+	if len(first) != len(second) || first[0].Content != second[0].Content {
+		t.Errorf("cached chunking differs: %v vs %v", first, second)
+	}
+
+	// Same content in another language is parsed separately
+	if _, err := chunker.Chunk(code, WithLanguage(languages.JavaScript), WithParseCache(cache)); err != nil {
+		t.Fatalf("Chunk() error = %v", err)
+	}
+
+	stats := cache.Stats()
+	if stats.Hits != 1 || stats.Misses != 2 {
+		t.Errorf("Stats() hits = %d, misses = %d, want 1 and 2", stats.Hits, stats.Misses)
+	}
+	if stats.Entries != 2 || stats.Bytes != int64(2*len(code)) {
+		t.Errorf("Stats() entries = %d, bytes = %d", stats.Entries, stats.Bytes)
+	}
+}
+
+func TestParseCache_ClosedMapping(t *testing.T) {
+	dir := t.TempDir()
+	code := "package main\n\nfunc main() {\n\tprintln(\"hello\")\n}\n"
+	for _, name := range []string{"a.go", "b.go"} {
+		if err := os.WriteFile(filepath.Join(dir, name), []byte(code), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+	cache := NewParseCache(0)
+	chunker := NewChunker().(MappedChunker)
+
+	first, err := OpenMapped(filepath.Join(dir, "a.go"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, err := chunker.ChunkMapped(first, WithParseCache(cache)); err != nil {
+		t.Fatalf("ChunkMapped() error = %v", err)
+	}
+	if err := first.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	// The hit must slice the second mapping, not the unmapped first one
+	second, err := OpenMapped(filepath.Join(dir, "b.go"))
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer second.Close()
+	chunks, err := chunker.ChunkMapped(second, WithParseCache(cache))
+	if err != nil {
+		t.Fatalf("ChunkMapped() error = %v", err)
+	}
+	if cache.Stats().Hits != 1 {
+		t.Fatalf("expected a cache hit, got %+v", cache.Stats())
+	}
+	mapping := second.String()
+	start := uintptr(unsafe.Pointer(unsafe.StringData(mapping)))
+	for i, chunk := range chunks {
+		if chunk.Content != code[chunk.StartByte:chunk.EndByte] {
+			t.Errorf("chunk %d = %q, want %q", i, chunk.Content, code[chunk.StartByte:chunk.EndByte])
+		}
+		if p := uintptr(unsafe.Pointer(unsafe.StringData(chunk.Content))); chunk.Content != "" && (p < start || p >= start+uintptr(len(mapping))) {
+			t.Errorf("chunk %d doesn't borrow from the mapping it was chunked from", i)
+		}
+	}
+}
+
+func TestParseCache_Eviction(t *testing.T) {
+	code := strings.Repeat("x", 60)
+	cache := NewParseCache(100)
+	chunker := NewChunker()
+
+	for _, c := range []string{"var a = 1 //" + code, "var b = 2 //" + code, "var a = 1 //" + code} {
+		if _, err := chunker.Chunk(c, WithLanguage(languages.Go), WithParseCache(cache)); err != nil {
+			t.Fatalf("Chunk() error = %v", err)
+		}
+	}
+
+	stats := cache.Stats()
+	if stats.Entries != 1 {
+		t.Errorf("Stats() entries = %d, want 1", stats.Entries)
+	}
+	if stats.Evictions != 2 {
+		t.Errorf("Stats() evictions = %d, want 2", stats.Evictions)
+	}
+	if stats.Hits != 0 || stats.Misses != 3 {
+		t.Errorf("Stats() hits = %d, misses = %d, want 0 and 3", stats.Hits, stats.Misses)
+	}
+	if stats.Bytes > 100 {
+		t.Errorf("Stats() bytes = %d exceeds bound", stats.Bytes)
+	}
+}
+
+func TestParseCache_SkipsOversizedEntries(t *testing.T) {
+	cache := NewParseCache(4)
+	if _, err := NewChunker().Chunk("package main", WithLanguage(languages.Go), WithParseCache(cache)); err != nil {
+		t.Fatalf("Chunk() error = %v", err)
+	}
+
+	if stats := cache.Stats(); stats.Entries != 0 || stats.Bytes != 0 {
+		t.Errorf("oversized entry was cached: %+v", stats)
+	}
+}
+
+func TestParseCache_Clear(t *testing.T) {
+	cache := NewParseCache(0)
+	if _, err := NewChunker().Chunk("package main", WithLanguage(languages.Go), WithParseCache(cache)); err != nil {
+		t.Fatalf("Chunk() error = %v", err)
+	}
+
+	cache.Clear()
+	if stats := cache.Stats(); stats.Entries != 0 || stats.Bytes != 0 || stats.Misses != 1 {
+		t.Errorf("unexpected stats after Clear(): %+v", stats)
+	}
+}
@@ -1,8 +1,11 @@
+//go:build cgo
+
 package chunkx
 
 import (
 	"context"
 	"fmt"
+	"unsafe"
 
 	"github.com/gomantics/chunkx/languages"
 	sitter "github.com/smacker/go-tree-sitter"
@@ -21,6 +24,8 @@ func NewParser() *Parser {
 }
 
 // ParseResult contains the parsed AST and metadata.
+//
+// Source aliases the memory of the parsed code and must not be modified.
 type ParseResult struct {
 	Tree     *sitter.Tree
 	Language languages.LanguageName
@@ -29,6 +34,17 @@ type ParseResult struct {
 
 // Parse parses the given code using the specified language.
 func (p *Parser) Parse(code string, language languages.LanguageName) (*ParseResult, error) {
+	return p.parse(context.Background(), code, language, nil)
+}
+
+// ParseCtx parses the given code like Parse, aborting when ctx is cancelled.
+func (p *Parser) ParseCtx(ctx context.Context, code string, language languages.LanguageName) (*ParseResult, error) {
+	return p.parse(ctx, code, language, nil)
+}
+
+// parse parses code, reusing oldTree for incremental parsing when it is not nil.
+// The old tree must already have been edited to match code.
+func (p *Parser) parse(ctx context.Context, code string, language languages.LanguageName, oldTree *sitter.Tree) (*ParseResult, error) {
 	lang, ok := languages.GetLanguageConfig(language)
 	if !ok {
 		return nil, &LanguageError{
@@ -47,8 +63,8 @@ func (p *Parser) Parse(code string, language languages.LanguageName) (*ParseResu
 
 	p.parser.SetLanguage(lang.GetParser())
 
-	sourceCode := []byte(code)
-	tree, err := p.parser.ParseCtx(context.Background(), nil, sourceCode)
+	sourceCode := stringBytes(code)
+	tree, err := p.parser.ParseCtx(ctx, oldTree, sourceCode)
 	if err != nil {
 		return nil, &LanguageError{
 			Language: language,
@@ -88,3 +104,12 @@ func GetNodeSize(node *sitter.Node, source []byte, counter TokenCounter) (int, e
 func GetLineNumbers(node *sitter.Node) (int, int) {
 	return int(node.StartPoint().Row) + 1, int(node.EndPoint().Row) + 1
 }
+
+// sliceSource returns source[start:end] as a string without copying it.
+// The source must not be modified while the returned string is in use.
+func sliceSource(source []byte, start, end uint32) string {
+	if start >= end {
+		return ""
+	}
+	return unsafe.String(&source[start], int(end-start))
+}
@@ -0,0 +1,156 @@
+package chunkx
+
+import (
+	"cmp"
+	"context"
+	"runtime"
+	"strings"
+	"sync"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// DefaultPartitionBytes is the size of the partitions of WithPartitions unless
+// Partitions.Bytes is set.
+const DefaultPartitionBytes = 64 << 20
+
+// Partitions configures WithPartitions.
+type Partitions struct {
+	Workers int // Partitions chunked at once, or runtime.GOMAXPROCS(0) if 0
+	Bytes   int // Size of a partition, up to its boundary, or DefaultPartitionBytes if 0
+}
+
+// WithPartitions chunks a single huge input, such as a multi-gigabyte log or
+// SQL dump, on several threads. The input is cut into partitions of about
+// Partitions.Bytes bytes, each ending at the first safe boundary past that
+// size, and the partitions are chunked at once, with their chunks stitched
+// back together at their offsets and lines in the input. Inputs no larger
+// than a partition are chunked as usual.
+//
+// Boundaries are the ends of lines for generic text, of lines ending a
+// statement with ";" for SQL, and of blank lines for other languages, so they
+// separate the records or top-level definitions that dumps and logs are made
+// of. No chunk spans a boundary, so chunks next to one may be smaller than
+// chunking on one thread makes them, and a boundary in a multiline string,
+// which a blank or ";" line may be, splits it as a syntax error would.
+// Inputs limited by Limits.MaxSteps are chunked on one thread, as the steps
+// are spent in order.
+func WithPartitions(p Partitions) Option {
+	return func(c *config) {
+		c.partitions = &p
+	}
+}
+
+// partitionBoundary reports whether code, which ends a line, ends with a safe
+// boundary of code of the language. Lines may end with "\n" or "\r\n".
+func partitionBoundary(code string, lang languages.LanguageName) bool {
+	switch lang {
+	case languages.Generic:
+		return true
+	case languages.SQL:
+		return strings.HasSuffix(code, ";\n") || strings.HasSuffix(code, ";\r\n")
+	default:
+		return strings.HasSuffix(code, "\n\n") || strings.HasSuffix(code, "\r\n\r\n")
+	}
+}
+
+// partitionBounds returns the offsets of code that partitions of about size
+// bytes start at, after the first.
+func partitionBounds(code string, size int, lang languages.LanguageName) []int {
+	var bounds []int
+	for at := size; at < len(code); {
+		i := strings.IndexByte(code[at:], '\n')
+		if i < 0 {
+			break
+		}
+		cut := at + i + 1
+		if cut >= len(code) {
+			break
+		}
+		if !partitionBoundary(code[:cut], lang) {
+			at = cut
+			continue
+		}
+		bounds = append(bounds, cut)
+		at = cut + size
+	}
+	return bounds
+}
+
+// splitPartitioned splits code as split does, on the partitions of
+// WithPartitions at once if it is set and code is larger than a partition.
+func (c *castChunker) splitPartitioned(code string, cfg *config) ([]Chunk, error) {
+	if cfg.partitions == nil || cfg.steps != nil {
+		return c.split(code, cfg)
+	}
+	bounds := partitionBounds(code, cmp.Or(cfg.partitions.Bytes, DefaultPartitionBytes), cfg.language)
+	if len(bounds) == 0 {
+		return c.split(code, cfg)
+	}
+	starts := append([]int{0}, bounds...)
+	ends := append(bounds, len(code))
+
+	// The first partition to fail cancels the rest
+	ctx, cancel := context.WithCancel(cfg.ctx)
+	defer cancel()
+	var failed sync.Once
+	var err error
+	fail := func(partErr error) {
+		failed.Do(func() {
+			err = partErr
+			cancel()
+		})
+	}
+
+	parts := make([][]Chunk, len(starts))
+	lines := make([]int, len(starts)) // Line breaks of each partition
+	next := make(chan int)
+	var wg sync.WaitGroup
+	for range min(cmp.Or(cfg.partitions.Workers, runtime.GOMAXPROCS(0)), len(starts)) {
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			for i := range next {
+				part := code[starts[i]:ends[i]]
+				partCfg := *cfg
+				partCfg.ctx = ctx
+				chunks, partErr := c.split(part, &partCfg)
+				if partErr != nil {
+					fail(partErr)
+					continue
+				}
+				parts[i], lines[i] = chunks, lineOf(code, starts[i], 0, ends[i])
+			}
+		}()
+	}
+	func() {
+		defer close(next)
+		for i := range starts {
+			select {
+			case next <- i:
+			case <-ctx.Done():
+				return
+			}
+		}
+	}()
+	wg.Wait()
+	if err != nil {
+		return nil, err
+	}
+	if ctxErr := cfg.ctx.Err(); ctxErr != nil {
+		return nil, ctxErr
+	}
+
+	var n int
+	for _, part := range parts {
+		n += len(part)
+	}
+	chunks := make([]Chunk, 0, n)
+	line := 0
+	for i, part := range parts {
+		shiftChunks(part, starts[i], line)
+		chunks = append(chunks, part...)
+		line += lines[i]
+	}
+	return chunks, nil
+}
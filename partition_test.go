@@ -0,0 +1,104 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"slices"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestWithPartitions(t *testing.T) {
+	code := randomText(4, 256<<10)
+	opts := []Option{WithLanguage(languages.Generic), WithMaxSize(100), WithPartitions(Partitions{Workers: 4, Bytes: 16 << 10})}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Fatalf("invalid chunks: %v", err)
+	}
+
+	// Every partition starts a chunk
+	bounds := partitionBounds(code, 16<<10, languages.Generic)
+	if len(bounds) < 10 {
+		t.Fatalf("expected at least 10 partitions, got %d", len(bounds)+1)
+	}
+	starts := make([]int, len(chunks))
+	for i, chunk := range chunks {
+		starts[i] = chunk.StartByte
+	}
+	for _, bound := range bounds {
+		if _, ok := slices.BinarySearch(starts, bound); !ok {
+			t.Errorf("expected a chunk to start at partition boundary %d", bound)
+		}
+	}
+
+	if _, err := NewChunker().Chunk(code, append(opts, WithTokenCounter(refusingCounter{}))...); err == nil {
+		t.Error("expected the error of a failing partition")
+	}
+
+	// Lines ended by a lone "\r" are counted across partitions
+	code = strings.ReplaceAll(code, " ", "\r")
+	chunks, err = NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("invalid chunks of \\r lines: %v", err)
+	}
+
+	// Statements ending CRLF lines are boundaries too
+	var sql strings.Builder
+	for i := 0; sql.Len() < 256<<10; i++ {
+		fmt.Fprintf(&sql, "INSERT INTO t VALUES (%d);\r\n", i)
+	}
+	code = sql.String()
+	opts = []Option{WithLanguage(languages.SQL), WithMaxSize(100), WithPartitions(Partitions{Workers: 4, Bytes: 16 << 10})}
+	if bounds := partitionBounds(code, 16<<10, languages.SQL); len(bounds) < 10 {
+		t.Fatalf("expected at least 10 partitions of CRLF statements, got %d", len(bounds)+1)
+	}
+	chunks, err = NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("invalid chunks of CRLF statements: %v", err)
+	}
+}
+
+func TestPartitionBounds(t *testing.T) {
+	tests := []struct {
+		name string
+		code string
+		lang languages.LanguageName
+		want []int
+	}{
+		{"lines", "aaaa\nbb\ncccc\nd", languages.Generic, []int{5, 13}},
+		{"statements", "INSERT 1;\nINSERT\n2;\nINSERT 3;\n", languages.SQL, []int{10, 20}},
+		{"blank lines", "func a() {\n\n}\n\nfunc b() {}\n", languages.Go, []int{12}},
+		{"crlf statements", "INSERT 1;\r\nINSERT\r\n2;\r\nINSERT 3;\r\n", languages.SQL, []int{11, 23}},
+		{"crlf blank lines", "func a() {\r\n\r\n}\r\n\r\nfunc b() {}\r\n", languages.Go, []int{14, 19}},
+		{"no boundary", "one line without end", languages.Generic, nil},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := partitionBounds(tt.code, 4, tt.lang); !slices.Equal(got, tt.want) {
+				t.Errorf("partitionBounds() = %v, want %v", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestWithPartitions_Cancelled(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	_, err := NewChunker().(ContextChunker).ChunkContext(ctx, randomText(5, 64<<10),
+		WithLanguage(languages.Generic), WithPartitions(Partitions{Bytes: 4 << 10}))
+	if !errors.Is(err, context.Canceled) {
+		t.Errorf("expected the context's error, got %v", err)
+	}
+}
@@ -0,0 +1,232 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+var (
+	// pascalSection matches a line of a Pascal unit starting one of its
+	// sections, and the keyword.
+	pascalSection = regexp.MustCompile(`(?im)^[ \t]*(interface|implementation|initialization|finalization)[ \t]*(?:(?://|\{|\(\*).*)?\r?$`)
+	// pascalRoutine matches the line of Pascal or Delphi starting a routine,
+	// such as "procedure TForm1.Button1Click(Sender: TObject);" or "class
+	// function TList<T>.Create: TList<T>;", and its name.
+	pascalRoutine = regexp.MustCompile(`(?i)^[ \t]*(?:class[ \t]+)?(?:procedure|function|constructor|destructor|operator)[ \t]+([\w.]+(?:<[\w, ]*>)?(?:\.\w+)*)`)
+	// pascalType matches the line of Pascal or Delphi declaring a structured
+	// type, such as "TForm1 = class(TForm)" or "TPoint = packed record", and
+	// its name.
+	pascalType = regexp.MustCompile(`(?i)^[ \t]*(\w+)(?:<[\w, ]*>)?[ \t]*=[ \t]*(?:packed[ \t]+)?(?:class|record|object|interface|dispinterface)\b`)
+)
+
+// pascalSpans returns the sections of the Pascal or Delphi unit code, such as
+// its interface and implementation, recording the keyword as
+// MetadataSection. Programs and other code without sections are split at
+// their routines and structured types instead, as pascalNested splits
+// sections.
+func pascalSpans(code string) []textSpan {
+	var spans []textSpan
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		if m := pascalSection.FindStringSubmatch(line); m != nil {
+			start := leadingComments(code, offset, spanEnd(spans), "//", "{", "(*")
+			if n := len(spans); n > 0 {
+				spans[n-1].end = start
+			}
+			spans = append(spans, textSpan{start: start, end: len(code), metadata: map[string]string{MetadataSection: strings.ToLower(m[1])}})
+		}
+		offset = next
+	}
+	if len(spans) == 0 {
+		return pascalBlocks(code, 0, true)
+	}
+	return spans
+}
+
+// pascalNested returns the routines of the section of a Pascal unit, such as
+// the procedures and methods of its implementation, recording
+// MetadataDefinition, and its structured types, such as the classes its
+// interface declares, recording MetadataBlock.
+func pascalNested(block string) []textSpan {
+	m := pascalSection.FindStringSubmatchIndex(block)
+	if m == nil {
+		return pascalBlocks(block, 0, true)
+	}
+	_, offset := codeLine(block, m[1])
+	// The routines an interface declares have their bodies in the
+	// implementation
+	return pascalBlocks(block, offset, !strings.EqualFold(block[m[2]:m[3]], "interface"))
+}
+
+// pascalBlocks returns the structured types of the Pascal code declared on
+// the lines from offset on, and its routines with a body if routines is
+// true, each with the comment lines right above it.
+func pascalBlocks(code string, offset int, routines bool) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		end := -1
+		var metadata map[string]string
+		if m := pascalRoutine.FindStringSubmatch(line); m != nil && routines {
+			end = pascalRoutineEnd(code, offset)
+			metadata = map[string]string{MetadataDefinition: m[1]}
+		} else if m := pascalType.FindStringSubmatch(line); m != nil {
+			end = pascalBlockEnd(code, offset, "")
+			metadata = map[string]string{MetadataBlock: m[1]}
+		}
+		if end < 0 {
+			offset = next
+			continue
+		}
+
+		_, end = codeLine(code, end)
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "//", "{", "(*"),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// pascalRoutineEnd returns the offset after the end of the body of the Pascal
+// routine whose header starts at offset in code, skipping its declarations
+// and nested routines, or -1 if it has no body, as forward and external
+// declarations don't.
+func pascalRoutineEnd(code string, offset int) int {
+	if offset = pascalHeaderEnd(code, offset); offset < 0 {
+		return -1
+	}
+	prev := ";"
+	for {
+		tok, start, next := pascalToken(code, offset)
+		switch tok {
+		case "":
+			return -1
+		case "forward", "external":
+			if prev == ";" {
+				return -1
+			}
+		case "procedure", "function", "constructor", "destructor":
+			// Procedural types, such as "TProc = procedure(X: Integer)", are
+			// left out
+			if prev != "=" && prev != ":" {
+				if next = pascalRoutineEnd(code, start); next < 0 {
+					return -1
+				}
+			}
+		case "begin", "asm":
+			return pascalBlockEnd(code, start, prev)
+		case "record", "object", "class", "interface":
+			if pascalOpens(code, tok, prev, next, nil) {
+				if next = pascalBlockEnd(code, start, prev); next < 0 {
+					return -1
+				}
+			}
+		}
+		prev, offset = tok, next
+	}
+}
+
+// pascalHeaderEnd returns the offset after the ";" ending the header of the
+// Pascal routine starting at offset in code, outside its parameter list, or
+// -1 if there is none.
+func pascalHeaderEnd(code string, offset int) int {
+	depth := 0
+	for {
+		tok, _, next := pascalToken(code, offset)
+		switch {
+		case tok == "":
+			return -1
+		case tok == "(":
+			depth++
+		case tok == ")":
+			depth--
+		case tok == ";" && depth == 0:
+			return next
+		}
+		offset = next
+	}
+}
+
+// pascalBlockEnd returns the offset after the "end" closing the first block
+// opened from offset in the Pascal code, after the token prev, such as a
+// begin block or a class or record type, or -1 if it isn't closed or a ";"
+// comes before it, as in a forward class declaration.
+func pascalBlockEnd(code string, offset int, prev string) int {
+	var open []string
+	for {
+		tok, _, next := pascalToken(code, offset)
+		switch {
+		case tok == "" || tok == ";" && len(open) == 0:
+			return -1
+		case tok == "end":
+			if len(open) <= 1 {
+				return next
+			}
+			open = open[:len(open)-1]
+		case pascalOpens(code, tok, prev, next, open):
+			open = append(open, tok)
+		}
+		prev, offset = tok, next
+	}
+}
+
+// pascalOpens reports whether the token tok of Pascal, after prev and before
+// the code from next on, opens a block closed by "end" within the blocks
+// open. The case of a variant record and forward declarations of classes,
+// such as "TNode = class;", don't.
+func pascalOpens(code, tok, prev string, next int, open []string) bool {
+	switch tok {
+	case "begin", "try", "asm", "record":
+		return true
+	case "case":
+		return len(open) == 0 || open[len(open)-1] != "record"
+	case "object":
+		return prev != "of"
+	case "class", "interface", "dispinterface":
+		if prev != "=" {
+			return false
+		}
+		after, _, rest := pascalToken(code, next)
+		if after == "(" {
+			for after != ")" && after != "" {
+				after, _, rest = pascalToken(code, rest)
+			}
+			after, _, _ = pascalToken(code, rest)
+		}
+		return after != ";" && after != "of"
+	}
+	return false
+}
+
+// pascalToken returns the next word of the Pascal code from offset in lower
+// case, or the next of the punctuation ";", "(", ")", ":", and "=", with its
+// start and the offset after it, skipping comments, compiler directives, and
+// strings. It returns "" at the end of the code.
+func pascalToken(code string, offset int) (string, int, int) {
+	for i := offset; i < len(code); {
+		switch rest := code[i:]; {
+		case rest[0] == '{':
+			i = skipPast(code, i, len(code), "}")
+		case strings.HasPrefix(rest, "(*"):
+			i = skipPast(code, i, len(code), "*)")
+		case strings.HasPrefix(rest, "//"):
+			_, i = codeLine(code, i)
+		case rest[0] == '\'':
+			i = skipPast(code, i+1, len(code), "'")
+		case strings.ContainsRune(";():=", rune(rest[0])):
+			return rest[:1], i, i + 1
+		case isWordByte(rest[0]):
+			start := i
+			for i < len(code) && isWordByte(code[i]) {
+				i++
+			}
+			return strings.ToLower(code[start:i]), start, i
+		default:
+			i++
+		}
+	}
+	return "", len(code), len(code)
+}
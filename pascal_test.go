@@ -0,0 +1,64 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Pascal(t *testing.T) {
+	code := `unit Shapes;
+
+interface
+
+type
+  { A circle }
+  TCircle = class(TObject)
+  private
+    FRadius: Double;
+  public
+    function Area: Double;
+  end;
+
+implementation
+
+uses Math;
+
+function TCircle.Area: Double;
+var
+  R: Double;
+begin
+  R := FRadius;
+  if R > 0 then
+  begin
+    Result := Pi * R * R;
+  end
+  else
+    Result := 0;
+end;
+
+// Logs the area
+procedure LogArea(C: TCircle);
+begin
+  case Round(C.Area) of
+    0: WriteLn('no end');
+  else
+    WriteLn(C.Area);
+  end;
+end;
+
+end.
+`
+	chunks := testChunkMetadata(t, code, languages.Pascal, 32, []struct{ prefix, key, value string }{
+		{"interface\n", MetadataSection, "interface"},
+		{"function TCircle.Area: Double;\n", MetadataDefinition, "TCircle.Area"},
+		{"function TCircle.Area: Double;\n", MetadataSection, "implementation"},
+		{"// Logs the area\nprocedure LogArea(", MetadataDefinition, "LogArea"},
+	})
+	for _, chunk := range chunks {
+		if strings.HasPrefix(chunk.Content, "// Logs the area") && !strings.HasSuffix(chunk.Content, "  end;\nend;\n") {
+			t.Errorf("expected the procedure chunked up to its end, got %q", chunk.Content)
+		}
+	}
+}
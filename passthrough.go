@@ -0,0 +1,55 @@
+package chunkx
+
+import "strings"
+
+// Passthrough configures the fast path WithPassthrough takes for small
+// inputs.
+type Passthrough struct {
+	MaxBytes int  // Largest input taken as is, in bytes, or 0 for the maximum size
+	Metadata bool // Whether inputs taken as is still get front matter, license header, link, and heading path metadata
+}
+
+// WithPassthrough returns every input of at most Passthrough.MaxBytes bytes as
+// a single chunk, without counting its tokens, parsing it, or sniffing its
+// content, which speeds up corpora dominated by small files. A zero MaxBytes
+// uses the maximum size, which keeps every chunk within it for token counters
+// that count at most one token per byte, as the built-in ones and tiktoken
+// do.
+//
+// Without Passthrough.Metadata, and unless WithComments splits comments from
+// code or WithLicenseHeaders leaves out license headers, such an input does
+// no other work than masking the secrets of WithRedaction. With it, the
+// input is still split from its front matter or license header and its chunk
+// gets the metadata its options ask for; only splitting it is skipped. Inputs
+// that are empty or only white space are chunked as usual.
+func WithPassthrough(p Passthrough) Option {
+	return func(c *config) {
+		c.passthrough = &p
+	}
+}
+
+// passes reports whether code is small enough for the fast path of
+// WithPassthrough.
+func (cfg *config) passes(code string) bool {
+	if cfg.passthrough == nil || strings.TrimSpace(code) == "" {
+		return false
+	}
+	limit := cfg.passthrough.MaxBytes
+	if limit <= 0 {
+		limit = cfg.maxSize
+	}
+	return len(code) <= limit
+}
+
+// passthroughChunk returns the code from start on as a single chunk.
+func passthroughChunk(code string, start int, cfg *config) Chunk {
+	chunk := Chunk{
+		Content:   code[start:],
+		StartLine: 1,
+		EndLine:   lineOf(code, start, 1, len(code)),
+		StartByte: start,
+		EndByte:   len(code),
+		Language:  cfg.language,
+	}
+	return chunk
+}
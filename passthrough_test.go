@@ -0,0 +1,96 @@
+package chunkx
+
+import (
+	"errors"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// refusingCounter fails every count, to check that no tokens are counted.
+type refusingCounter struct{}
+
+func (refusingCounter) CountTokens(string) (int, error) {
+	return 0, errors.New("counted tokens")
+}
+
+func TestWithPassthrough(t *testing.T) {
+	code := "package main\n\nfunc main() {}\n"
+	chunks, err := NewChunker().Chunk(code,
+		WithLanguage(languages.Go),
+		WithTokenCounter(refusingCounter{}),
+		WithPassthrough(Passthrough{}))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) != 1 || chunks[0].Content != code || chunks[0].StartLine != 1 || chunks[0].EndLine != 4 || chunks[0].EndByte != len(code) {
+		t.Errorf("expected the whole input as one chunk, got %+v", chunks)
+	}
+
+	// Inputs over the limit are chunked as usual
+	_, err = NewChunker().Chunk(code,
+		WithLanguage(languages.Go),
+		WithTokenCounter(refusingCounter{}),
+		WithPassthrough(Passthrough{MaxBytes: 10}))
+	if err == nil {
+		t.Error("expected the input over the limit to be counted")
+	}
+}
+
+func TestWithPassthrough_Metadata(t *testing.T) {
+	code := "---\ntitle: Notes\n---\n# Notes\n\nSee [the guide](guide.md).\n"
+	chunks, err := NewChunker().Chunk(code,
+		WithLanguage(languages.Markdown),
+		WithTokenCounter(refusingCounter{}),
+		WithFrontMatter("title"),
+		WithLinks(),
+		WithPassthrough(Passthrough{Metadata: true}))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(chunks) != 1 || !strings.HasPrefix(chunks[0].Content, "# Notes") {
+		t.Fatalf("expected the page after its front matter as one chunk, got %+v", chunks)
+	}
+	if got := chunks[0].Metadata[MetadataFrontMatterPrefix+"title"]; got != "Notes" {
+		t.Errorf("expected the title of the front matter, got %q", got)
+	}
+	if got := chunks[0].Metadata[MetadataLinks]; got != "guide.md" {
+		t.Errorf("expected the link of the page, got %q", got)
+	}
+}
+
+func TestWithPassthrough_Policies(t *testing.T) {
+	code := "# Copyright 2024 Acme\n# Licensed under the MIT License\n\nalpha beta # note\ngamma\n"
+	tests := []struct {
+		name string
+		opt  Option
+	}{
+		{"license strip", WithLicenseHeaders(LicenseStrip)},
+		{"license exclude", WithLicenseHeaders(LicenseExclude)},
+		{"comments strip", WithComments(CommentsStrip)},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			// Small inputs get the same content with and without passthrough
+			opts := []Option{WithLanguage(languages.Generic), tt.opt}
+			want, err := NewChunker().Chunk(code, opts...)
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			got, err := NewChunker().Chunk(code, append(opts, WithPassthrough(Passthrough{}))...)
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if len(got) != len(want) {
+				t.Fatalf("chunks with passthrough = %+v, want %+v", got, want)
+			}
+			for i := range want {
+				if got[i].Content != want[i].Content || got[i].StartByte != want[i].StartByte || !reflect.DeepEqual(got[i].Metadata, want[i].Metadata) {
+					t.Errorf("chunk %d with passthrough = %+v, want %+v", i, got[i], want[i])
+				}
+			}
+		})
+	}
+}
@@ -0,0 +1,133 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+// Metadata keys set on chunks of Perl.
+const (
+	MetadataPOD         = "pod"          // Title of the POD block of the chunk, such as "NAME" for "=head1 NAME"
+	MetadataDataSection = "data_section" // "__END__" or "__DATA__" for chunks of the data after the code
+)
+
+var (
+	// perlSub matches the definition of a named Perl sub, and its name.
+	perlSub = regexp.MustCompile(`^[ \t]*sub[ \t]+([A-Za-z_][\w:']*)`)
+	// perlPackage matches a Perl package declaration, and its name.
+	perlPackage = regexp.MustCompile(`^[ \t]*package[ \t]+([A-Za-z_][\w:]*)`)
+	// perlPOD matches the command starting a POD block, such as "=head1 NAME",
+	// and its text.
+	perlPOD = regexp.MustCompile(`^=[A-Za-z]\S*[ \t]*(.*?)[ \t\r]*$`)
+	// perlCut matches the command ending a POD block.
+	perlCut = regexp.MustCompile(`^=cut\b`)
+)
+
+// perlSpans returns the named subs of the Perl code, each with the comment
+// lines right above it, recording MetadataDefinition qualified by the package
+// they are in, such as "Foo::Bar::new". POD blocks are spans of their own
+// recording MetadataPOD, with MetadataStream set to StreamComments so they
+// can be told from the code they document, and the data after an __END__ or
+// __DATA__ line records MetadataDataSection.
+func perlSpans(code string) []textSpan {
+	var spans []textSpan
+	var pkg, data string
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		if m := perlPOD.FindStringSubmatch(line); m != nil {
+			end := next
+			for end < len(code) {
+				line, next := codeLine(code, end)
+				end = next
+				if perlCut.MatchString(line) {
+					break
+				}
+			}
+			spans = append(spans, textSpan{
+				start:    offset,
+				end:      end,
+				metadata: map[string]string{MetadataPOD: m[1], MetadataStream: StreamComments},
+			})
+			offset = end
+			continue
+		}
+
+		if marker := strings.TrimRight(line, " \t\r"); data == "" && (marker == "__END__" || marker == "__DATA__") {
+			data = marker
+		}
+		if data != "" {
+			// The data runs up to the next POD block, which documentation
+			// after __END__ often is
+			end := next
+			for end < len(code) {
+				line, next := codeLine(code, end)
+				if perlPOD.MatchString(line) {
+					break
+				}
+				end = next
+			}
+			spans = append(spans, textSpan{start: offset, end: end, metadata: map[string]string{MetadataDataSection: data}})
+			offset = end
+			continue
+		}
+
+		if m := perlPackage.FindStringSubmatch(line); m != nil {
+			pkg = m[1]
+		}
+		m := perlSub.FindStringSubmatch(line)
+		// A declaration without a body, such as "sub name;", isn't a block
+		open := strings.IndexAny(code[offset:], "{;")
+		if m == nil || open < 0 || code[offset+open] == ';' {
+			offset = next
+			continue
+		}
+
+		name := m[1]
+		if pkg != "" && pkg != "main" && !strings.Contains(name, "::") {
+			name = pkg + "::" + name
+		}
+		_, end := codeLine(code, perlClosing(code, offset+open))
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans)),
+			end:      end,
+			metadata: map[string]string{MetadataDefinition: name},
+		})
+		offset = end
+	}
+	return spans
+}
+
+// perlClosing returns the offset after the brace closing the one at open in
+// the Perl code, skipping strings and comments, or len(code) if it isn't
+// closed.
+func perlClosing(code string, open int) int {
+	depth := 0
+	for i := open; i < len(code); i++ {
+		switch code[i] {
+		case '{':
+			depth++
+		case '}':
+			if depth--; depth == 0 {
+				return i + 1
+			}
+		case '\\':
+			i++
+		case '#':
+			// $# is the last index of an array rather than a comment
+			if code[i-1] == '$' {
+				continue
+			}
+			for i+1 < len(code) && code[i+1] != '\n' {
+				i++
+			}
+		case '"', '\'', '`':
+			for quote := code[i]; i+1 < len(code) && code[i+1] != quote; i++ {
+				if code[i+1] == '\\' {
+					i++
+				}
+			}
+			i++
+		}
+	}
+	return len(code)
+}
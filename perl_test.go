@@ -0,0 +1,73 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Perl(t *testing.T) {
+	code := `package Counter;
+use strict;
+
+=head1 NAME
+
+Counter - counts things
+
+=cut
+
+# Makes a counter
+sub new {
+    my ($class) = @_;
+    return bless { count => 0 }, $class;
+}
+
+sub inc { $_[0]{count}++ }
+
+1;
+__END__
+apples,1
+pears,2
+
+=head1 DESCRIPTION
+
+Reads the counts above.
+
+=cut
+`
+	opts := []Option{WithLanguage(languages.Perl)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+
+	tests := []struct{ prefix, key, value string }{
+		{"=head1 NAME\n", MetadataPOD, "NAME"},
+		{"# Makes a counter\nsub new {", MetadataDefinition, "Counter::new"},
+		{"sub inc {", MetadataDefinition, "Counter::inc"},
+		{"__END__\napples,1\npears,2\n", MetadataDataSection, "__END__"},
+		{"=head1 DESCRIPTION\n", MetadataPOD, "DESCRIPTION"},
+	}
+	for _, tt := range tests {
+		var found bool
+		for _, chunk := range chunks {
+			if !strings.HasPrefix(chunk.Content, tt.prefix) {
+				continue
+			}
+			found = true
+			if got := chunk.Metadata[tt.key]; got != tt.value {
+				t.Errorf("expected %s %q of %q, got %q", tt.key, tt.value, chunk.Content, got)
+			}
+			if pod := tt.key == MetadataPOD; pod != (chunk.Metadata[MetadataStream] == StreamComments) {
+				t.Errorf("expected only POD chunks in the comments stream, got %q with metadata %v", chunk.Content, chunk.Metadata)
+			}
+		}
+		if !found {
+			t.Errorf("expected a chunk starting with %q, got %+v", tt.prefix, chunks)
+		}
+	}
+}
@@ -0,0 +1,603 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"io/fs"
+	"maps"
+	"os"
+	"path/filepath"
+	"runtime"
+	"slices"
+	"strings"
+	"sync"
+	"time"
+)
+
+// FileResult holds the outcome of chunking a single file.
+type FileResult struct {
+	Path    string
+	Chunks  []Chunk
+	Err     error // A *FileError when the file failed
+	Skipped bool // Unchanged since it was recorded by WithIndex, and not chunked again
+}
+
+// ChunkPipeline chunks files concurrently with a fixed pool of workers.
+//
+// Results are delivered on an unbuffered channel, so workers block until the
+// consumer is ready for the next file. Memory use is bounded by the number of
+// workers rather than by the size of the corpus.
+type ChunkPipeline struct {
+	workers  int
+	opts     []Option
+	progress ProgressHooks
+	ordered  bool
+	ignore   []string
+	tracer   Tracer
+	meter    Meter
+	counter  TokenCounter // Measures chunks for the oversized metric
+	maxSize  int
+	index    FileIndex
+	events   ChunkEventListener
+	sampling Sampling
+	limits   Limits
+	manifest *config // Options of every file, if they record a manifest
+
+	boilerplate  *Boilerplate // Banners recognized in the order files are received, if set
+	bannerConfig *config      // Options of every file, to detect the languages of their banners
+}
+
+// NewChunkPipeline creates a pipeline running the given number of workers.
+// A non-positive worker count uses one worker per CPU. The options apply to
+// every file; the language is auto-detected per file unless set explicitly.
+// Use WithProgress, WithTracer, and WithMeter to observe the pipeline,
+// WithEventListener to push its changes downstream, and WithOrderedOutput to
+// get results in a deterministic order.
+func NewChunkPipeline(workers int, opts ...Option) *ChunkPipeline {
+	if workers <= 0 {
+		workers = runtime.GOMAXPROCS(0)
+	}
+
+	cfg := newDefaultConfig()
+
+	for _, opt := range opts {
+		opt(cfg)
+	}
+
+	p := &ChunkPipeline{
+		workers:  workers,
+		opts:     opts,
+		progress: cfg.progress,
+		ordered:  cfg.ordered,
+		ignore:   cfg.ignore,
+		tracer:   cfg.tracer,
+		meter:    cfg.meter,
+		counter:  cfg.tokenCounter,
+		maxSize:  cfg.maxSize,
+		index:    cfg.index,
+		events:   cfg.events,
+		sampling: cfg.sampling,
+		limits:   cfg.limits,
+	}
+	// A sample doesn't describe the whole corpus, so it is neither recorded
+	// nor announced downstream
+	if cfg.sampling != (Sampling{}) {
+		p.index, p.events = nil, nil
+	}
+	if cfg.manifest != nil {
+		p.manifest = cfg
+	}
+	if b, ok := cfg.boilerplate.(*Boilerplate); ok && cfg.license != LicenseKeep {
+		p.boilerplate, p.bannerConfig = b, cfg
+	}
+	return p
+}
+
+// WithOrderedOutput makes a ChunkPipeline deliver results in the order the
+// paths were received, regardless of how workers are scheduled. RunDir
+// receives paths in lexical walk order (see filepath.WalkDir), and chunks
+// within a file are always ordered by offset, so output is identical across
+// runs. It has no effect on single-file chunking.
+func WithOrderedOutput() Option {
+	return func(c *config) {
+		c.ordered = true
+	}
+}
+
+// source is a file to chunk. Files read from archives carry their content,
+// objects are fetched by the worker, and other files are read from disk by
+// the worker.
+type source struct {
+	path     string // Path reported in the result
+	file     string // Location on disk, if different from path
+	content  []byte
+	inMemory bool
+	fetch    func(ctx context.Context) ([]byte, error) // Reads the content in the worker, if set
+	metadata map[string]string                         // Metadata attached to every chunk
+	err      error                                     // Error reading the file, reported as its result
+}
+
+// job is a file queued for chunking, numbered in the order it was received.
+type job struct {
+	seq  int
+	src  source
+	turn *turn // Turn of the file to record its banners, if the pipeline has a Boilerplate
+}
+
+// turn orders the files of a pipeline recording their banners in its
+// Boilerplate, so they take turns in the order they were received rather
+// than the order workers reach them.
+type turn struct {
+	prev   <-chan struct{} // Closed once the previous file has taken its turn
+	done   chan struct{}
+	once   sync.Once
+	waited bool // Whether the earlier files have taken their turns
+}
+
+// wait blocks until every earlier file has taken its turn.
+func (t *turn) wait(ctx context.Context) error {
+	select {
+	case <-ctx.Done():
+		return ctx.Err()
+	case <-t.prev:
+		t.waited = true
+		return nil
+	}
+}
+
+// end ends the turn, if it wasn't already, letting the next file take its
+// own. A file that didn't wait, such as one that failed to read, still lets
+// the earlier files go first. A nil turn does nothing.
+func (t *turn) end() {
+	if t == nil {
+		return
+	}
+	t.once.Do(func() {
+		if t.waited {
+			close(t.done)
+			return
+		}
+		go func() {
+			<-t.prev
+			close(t.done)
+		}()
+	})
+}
+
+// jobResult is the outcome of a job.
+type jobResult struct {
+	seq    int
+	result FileResult
+}
+
+// Run chunks every file path received from paths. The returned channel is
+// closed once paths is closed and all files have been processed, or once ctx
+// is cancelled. Callers must drain the results channel or cancel ctx.
+//
+// Combine with WithTimeout to bound the time spent on any single file; files
+// that time out are reported with context.DeadlineExceeded.
+func (p *ChunkPipeline) Run(ctx context.Context, paths <-chan string) <-chan FileResult {
+	sources := make(chan source)
+	go func() {
+		defer close(sources)
+		for {
+			var path string
+			select {
+			case <-ctx.Done():
+				return
+			case next, ok := <-paths:
+				if !ok {
+					return
+				}
+				path = next
+			}
+
+			select {
+			case <-ctx.Done():
+				return
+			case sources <- source{path: path}:
+			}
+		}
+	}()
+
+	return p.run(ctx, sources)
+}
+
+// errSampleFull stops the listing of a source once Sampling.MaxFiles files
+// were queued.
+var errSampleFull = errors.New("sample is full")
+
+// queued reports that the file at path was discovered, unless it is left
+// out of the sample, counting the files queued by the source in n. Files that
+// failed to be read are queued too, as the workers start and finish them like
+// any other. It returns false once n reached Sampling.MaxFiles, for the
+// source to stop listing files that won't be chunked.
+func (p *ChunkPipeline) queued(path string, n *int) bool {
+	if !p.sampling.sampled(path) {
+		return true
+	}
+	if p.sampling.MaxFiles > 0 && *n >= p.sampling.MaxFiles {
+		return false
+	}
+	*n++
+	p.progress.fileQueued(path)
+	return true
+}
+
+// run chunks every file received from sources.
+func (p *ChunkPipeline) run(ctx context.Context, sources <-chan source) <-chan FileResult {
+	jobs := make(chan job)
+	done := make(chan jobResult)
+
+	// In ordered mode, bound the number of files in flight so results
+	// waiting on a slow file can't pile up without limit
+	var window chan struct{}
+	if p.ordered {
+		window = make(chan struct{}, 2*p.workers)
+	}
+
+	// Files take turns recording their banners, the first right away
+	var prevTurn chan struct{}
+	if p.boilerplate != nil {
+		prevTurn = make(chan struct{})
+		close(prevTurn)
+	}
+
+	go func() {
+		defer close(jobs)
+		for seq := 0; ; {
+			var src source
+			select {
+			case <-ctx.Done():
+				return
+			case next, ok := <-sources:
+				if !ok {
+					return
+				}
+				src = next
+			}
+
+			// Files left out of the sample take no sequence number, and the
+			// rest are drained so their producer isn't left blocked
+			if !p.sampling.sampled(src.path) {
+				continue
+			}
+			if p.sampling.MaxFiles > 0 && seq >= p.sampling.MaxFiles {
+				for range sources {
+				}
+				return
+			}
+
+			if window != nil {
+				select {
+				case <-ctx.Done():
+					return
+				case window <- struct{}{}:
+				}
+			}
+
+			j := job{seq: seq, src: src}
+			if prevTurn != nil {
+				j.turn = &turn{prev: prevTurn, done: make(chan struct{})}
+				prevTurn = j.turn.done
+			}
+
+			select {
+			case <-ctx.Done():
+				return
+			case jobs <- j:
+			}
+			seq++
+		}
+	}()
+
+	// Workers share a chunker, and so the node types it interns
+	chunker := newCastChunker()
+	var wg sync.WaitGroup
+	for range p.workers {
+		wg.Go(func() {
+			for j := range jobs {
+				p.progress.fileStart(j.src.path)
+				result := p.chunkSource(ctx, chunker, j.src, j.turn)
+				p.progress.fileDone(result)
+
+				select {
+				case <-ctx.Done():
+					return
+				case done <- jobResult{seq: j.seq, result: result}:
+				}
+			}
+		})
+	}
+
+	go func() {
+		wg.Wait()
+		close(done)
+	}()
+
+	results := make(chan FileResult)
+	go func() {
+		defer close(results)
+
+		emit := func(result FileResult) bool {
+			select {
+			case <-ctx.Done():
+				return false
+			case results <- result:
+				return true
+			}
+		}
+
+		pending := make(map[int]FileResult)
+		next := 0
+		for r := range done {
+			if !p.ordered {
+				if !emit(r.result) {
+					return
+				}
+				continue
+			}
+
+			// Hold results back until every earlier file has been delivered
+			pending[r.seq] = r.result
+			for {
+				result, ok := pending[next]
+				if !ok {
+					break
+				}
+				delete(pending, next)
+				next++
+
+				if !emit(result) {
+					return
+				}
+				<-window
+			}
+		}
+	}()
+
+	return results
+}
+
+// RunDir chunks every regular file below dir. Hidden directories such as .git
+// and paths matching WithIgnore patterns are skipped. Paths that cannot be read
+// are reported as results with an error.
+func (p *ChunkPipeline) RunDir(ctx context.Context, dir string) <-chan FileResult {
+	paths := make(chan string, p.workers)
+
+	// send queues a path for the workers, stopping the walk on cancellation
+	send := func(path string) error {
+		select {
+		case <-ctx.Done():
+			return ctx.Err()
+		case paths <- path:
+			return nil
+		}
+	}
+
+	go func() {
+		defer close(paths)
+		var n int
+		_ = walkFiles(dir, p.ignore, func(path string, err error) error {
+			// Let the worker surface the error for this path
+			if !p.queued(path, &n) {
+				return errSampleFull
+			}
+			return send(path)
+		})
+	}()
+
+	return p.Run(ctx, paths)
+}
+
+// chunkSource chunks a single file, attaching the source's metadata to every
+// chunk.
+func (p *ChunkPipeline) chunkSource(ctx context.Context, chunker *castChunker, src source, turn *turn) FileResult {
+	// Files that aren't chunked take their turn all the same
+	defer turn.end()
+
+	if src.err != nil {
+		result := FileResult{Path: src.path, Err: &FileError{Path: src.path, Kind: readErrorKind(src.err), Err: src.err}}
+		p.manifest.recordFile(result, src.path, nil)
+		return result
+	}
+
+	start := time.Now()
+	cfg := &config{tracer: p.tracer, ctx: ctx}
+	endFile := cfg.startSpan(SpanFile, Attr{Key: "path", Value: src.path})
+
+	// Languages are detected from the path inside an archive or bucket
+	var content []byte
+	var err error
+	name := src.path
+	switch {
+	case src.inMemory:
+		content, name = src.content, src.metadata[MetadataArchivePath]
+	case src.fetch != nil:
+		name = src.metadata[MetadataObjectKey]
+		if content, err = readSource(cfg, src.fetch); err != nil {
+			err = &FileError{Kind: readErrorKind(err), Err: fmt.Errorf("failed to read object: %w", err)}
+		}
+	default:
+		file := src.file
+		if file == "" {
+			file = src.path
+		}
+		if err = p.limits.checkFile(file); err != nil {
+			break
+		}
+		content, err = readSource(cfg, func(context.Context) ([]byte, error) {
+			return os.ReadFile(file)
+		})
+		if err != nil {
+			err = &FileError{Kind: ErrorIO, Err: fmt.Errorf("failed to read file: %w", err)}
+		}
+	}
+	if err != nil {
+		content = nil
+	}
+
+	// Files unchanged since they were indexed aren't chunked again
+	var hash, recorded string
+	if err == nil && p.index != nil {
+		hash = ContentHash(content)
+		if recorded, err = p.index.Hash(cfg.ctx, src.path); err == nil && recorded == hash {
+			endFile(nil)
+			result := FileResult{Path: src.path, Skipped: true}
+			p.manifest.recordFile(result, name, content)
+			return result
+		}
+	}
+
+	opts := p.opts
+	if err == nil && turn != nil {
+		opts, err = p.recordBanners(cfg.ctx, turn, name, string(content))
+	}
+	var chunks []Chunk
+	if err == nil {
+		chunks, err = chunker.chunkFileContent(cfg.ctx, name, string(content), opts)
+	}
+	if p.sampling.MaxChunksPerFile > 0 {
+		chunks = sampleChunks(chunks, p.sampling.MaxChunksPerFile)
+	}
+	for i := range chunks {
+		if chunks[i].Metadata == nil {
+			chunks[i].Metadata = maps.Clone(src.metadata)
+			continue
+		}
+		maps.Copy(chunks[i].Metadata, src.metadata)
+	}
+
+	// Notify before recording, so the next run retries files whose event
+	// couldn't be delivered
+	if err == nil && p.events != nil {
+		event := ChunkEvent{Kind: ChunkCreated, Path: src.path, Chunks: chunks}
+		if recorded != "" {
+			event.Kind = ChunkUpdated
+		}
+		if err = p.events.HandleChunkEvent(cfg.ctx, event); err != nil {
+			err = fmt.Errorf("event listener: %w", err)
+		}
+	}
+	if err == nil && p.index != nil {
+		err = p.index.Record(cfg.ctx, FileResult{Path: src.path, Chunks: chunks}, hash)
+	}
+	endFile(err)
+
+	// Errors identify the file by its path in the pipeline, even when chunking
+	// saw the path inside an archive or bucket
+	if err != nil {
+		var fileErr *FileError
+		if !errors.As(err, &fileErr) {
+			fileErr = &FileError{Kind: errorKind(err), Err: err}
+			err = fileErr
+		}
+		fileErr.Path = src.path
+	}
+
+	result := FileResult{Path: src.path, Chunks: chunks, Err: err}
+	p.record(result, time.Since(start))
+	p.manifest.recordFile(result, name, content)
+	return result
+}
+
+// recordBanners records the banners leading the content of the file at path
+// in the Boilerplate of the pipeline once it is the file's turn, and returns
+// the options chunking the file with the banners recognized then.
+func (p *ChunkPipeline) recordBanners(ctx context.Context, turn *turn, path, content string) ([]Option, error) {
+	if err := turn.wait(ctx); err != nil {
+		return nil, err
+	}
+	text, _, _ := prepareText(content)
+	snapshot := p.boilerplate.snapshot(text, p.bannerConfig.languageFor(path, content[:min(len(content), documentSample)]))
+	turn.end()
+
+	return append(slices.Clip(p.opts), func(c *config) {
+		if c.boilerplate != nil {
+			c.boilerplate = snapshot
+		}
+	}), nil
+}
+
+// readSource reads the content of a source in a read span.
+func readSource(cfg *config, read func(ctx context.Context) ([]byte, error)) ([]byte, error) {
+	endRead := cfg.startSpan(SpanRead)
+	content, err := read(cfg.ctx)
+	endRead(err)
+	return content, err
+}
+
+// record records the metrics of a chunked file, if a meter is configured.
+func (p *ChunkPipeline) record(result FileResult, elapsed time.Duration) {
+	if p.meter == nil {
+		return
+	}
+
+	var attrs []Attr
+	if len(result.Chunks) > 0 {
+		attrs = []Attr{{Key: "language", Value: result.Chunks[0].Language.String()}}
+	}
+
+	p.meter.Add(MetricFiles, 1, attrs...)
+	p.meter.Record(MetricFileDuration, elapsed.Seconds(), attrs...)
+	if result.Err != nil {
+		p.meter.Add(MetricFilesFailed, 1, attrs...)
+		return
+	}
+	p.meter.Add(MetricChunks, int64(len(result.Chunks)), attrs...)
+
+	oversized := 0
+	for _, chunk := range result.Chunks {
+		if size, err := p.counter.CountTokens(chunk.Content); err == nil && size > p.maxSize {
+			oversized++
+		}
+	}
+	if oversized > 0 {
+		p.meter.Add(MetricOversizedChunks, int64(oversized), attrs...)
+	}
+}
+
+// walkFiles calls fn for every regular file below dir in lexical order,
+// skipping hidden directories and paths matching the ignore patterns. Paths
+// that cannot be walked are passed to fn with their error. The walk stops when
+// fn returns an error.
+func walkFiles(dir string, ignore []string, fn func(path string, err error) error) error {
+	return filepath.WalkDir(dir, func(path string, d fs.DirEntry, err error) error {
+		if err != nil {
+			return fn(path, err)
+		}
+
+		if path != dir && ignored(ignore, dir, path) {
+			if d.IsDir() {
+				return filepath.SkipDir
+			}
+			return nil
+		}
+
+		if d.IsDir() {
+			if path != dir && strings.HasPrefix(d.Name(), ".") {
+				return filepath.SkipDir
+			}
+			return nil
+		}
+
+		if d.Type().IsRegular() {
+			return fn(path, nil)
+		}
+		return nil
+	})
+}
+
+// ignored reports whether path, relative to the walked dir, matches an ignore
+// pattern.
+func ignored(patterns []string, dir, path string) bool {
+	if len(patterns) == 0 {
+		return false
+	}
+	rel, err := filepath.Rel(dir, path)
+	if err != nil {
+		rel = path
+	}
+	return matchAnyGlob(patterns, rel)
+}
@@ -0,0 +1,176 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"os"
+	"path/filepath"
+	"sort"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func writeTestFiles(t *testing.T, files map[string]string) string {
+	t.Helper()
+
+	dir := t.TempDir()
+	for name, content := range files {
+		path := filepath.Join(dir, name)
+		if err := os.MkdirAll(filepath.Dir(path), 0o755); err != nil {
+			t.Fatalf("failed to create directory: %v", err)
+		}
+		if err := os.WriteFile(path, []byte(content), 0o644); err != nil {
+			t.Fatalf("failed to write file: %v", err)
+		}
+	}
+	return dir
+}
+
+func TestChunkPipeline_RunDir(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"main.go":        "package main\n\nfunc main() {}\n",
+		"lib/util.py":    "def util():\n    return 1\n",
+		"notes.txt":      "just some text\n",
+		".git/config":    "[core]\n",
+		"lib/.hidden.js": "const x = 1;\n",
+	})
+
+	var paths []string
+	for result := range NewChunkPipeline(2, WithMaxSize(50)).RunDir(context.Background(), dir) {
+		if result.Err != nil {
+			t.Fatalf("unexpected error for %s: %v", result.Path, result.Err)
+		}
+		if len(result.Chunks) == 0 {
+			t.Errorf("no chunks for %s", result.Path)
+		}
+		rel, _ := filepath.Rel(dir, result.Path)
+		paths = append(paths, filepath.ToSlash(rel))
+	}
+	sort.Strings(paths)
+
+	want := []string{"lib/.hidden.js", "lib/util.py", "main.go", "notes.txt"}
+	if len(paths) != len(want) {
+		t.Fatalf("got paths %v, want %v", paths, want)
+	}
+	for i := range want {
+		if paths[i] != want[i] {
+			t.Errorf("got paths %v, want %v", paths, want)
+			break
+		}
+	}
+}
+
+func TestChunkPipeline_RunReportsErrors(t *testing.T) {
+	paths := make(chan string, 1)
+	paths <- filepath.Join(t.TempDir(), "missing.go")
+	close(paths)
+
+	var results []FileResult
+	for result := range NewChunkPipeline(0).Run(context.Background(), paths) {
+		results = append(results, result)
+	}
+
+	if len(results) != 1 || results[0].Err == nil {
+		t.Errorf("expected a single error result, got %+v", results)
+	}
+}
+
+func TestChunkPipeline_Cancellation(t *testing.T) {
+	files := make(map[string]string)
+	for i := range 20 {
+		files[filepath.Join("pkg", string(rune('a'+i))+".go")] = "package pkg\n"
+	}
+	dir := writeTestFiles(t, files)
+
+	ctx, cancel := context.WithCancel(context.Background())
+	results := NewChunkPipeline(2).RunDir(ctx, dir)
+
+	// Take one result, then stop consuming; the pipeline must shut down
+	<-results
+	cancel()
+
+	done := make(chan struct{})
+	go func() {
+		for range results {
+		}
+		close(done)
+	}()
+
+	select {
+	case <-done:
+	case <-time.After(5 * time.Second):
+		t.Fatal("pipeline did not stop after cancellation")
+	}
+}
+
+func TestChunker_ChunkContextCancelled(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+
+	code := strings.Repeat("func f() {}\n", 100)
+	_, err := NewChunker().(ContextChunker).ChunkContext(ctx, code, WithLanguage(languages.Go), WithMaxSize(5))
+	if !errors.Is(err, context.Canceled) {
+		t.Errorf("ChunkContext() error = %v, want context.Canceled", err)
+	}
+}
+
+func TestChunker_WithTimeout(t *testing.T) {
+	code := strings.Repeat("line of text\n", 5000)
+
+	counter := &slowCounter{delay: 100 * time.Microsecond}
+	_, err := NewChunker().Chunk(code,
+		WithLanguage(languages.Generic),
+		WithTokenCounter(counter),
+		WithTimeout(10*time.Millisecond))
+	if !errors.Is(err, context.DeadlineExceeded) {
+		t.Errorf("Chunk() error = %v, want context.DeadlineExceeded", err)
+	}
+}
+
+// slowCounter is a token counter that sleeps on every call
+type slowCounter struct {
+	delay time.Duration
+}
+
+func (s *slowCounter) CountTokens(text string) (int, error) {
+	time.Sleep(s.delay)
+	return len(strings.Fields(text)), nil
+}
+
+func TestChunkPipeline_OrderedOutput(t *testing.T) {
+	files := make(map[string]string)
+	for i := range 30 {
+		name := filepath.Join(string(rune('a'+i%3)), string(rune('a'+i))+".go")
+		// Vary the file sizes so workers finish out of order
+		files[name] = "package p\n" + strings.Repeat("func f() { x := 1; _ = x }\n", (30-i)*10)
+	}
+	dir := writeTestFiles(t, files)
+
+	var want []string
+	for result := range NewChunkPipeline(1).RunDir(context.Background(), dir) {
+		want = append(want, result.Path)
+	}
+
+	for run := range 3 {
+		var got []string
+		pipeline := NewChunkPipeline(4, WithOrderedOutput(), WithMaxSize(20))
+		for result := range pipeline.RunDir(context.Background(), dir) {
+			got = append(got, result.Path)
+		}
+
+		if len(got) != len(want) {
+			t.Fatalf("run %d: got %d results, want %d", run, len(got), len(want))
+		}
+		for i := range want {
+			if got[i] != want[i] {
+				t.Fatalf("run %d: result %d = %s, want %s", run, i, got[i], want[i])
+			}
+		}
+		if !sort.SliceIsSorted(got, func(i, j int) bool { return got[i] < got[j] }) {
+			t.Errorf("run %d: paths are not sorted: %v", run, got)
+		}
+	}
+}
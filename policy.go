@@ -0,0 +1,202 @@
+package chunkx
+
+import (
+	"bytes"
+	"fmt"
+	"strings"
+)
+
+// Metadata keys set by binary and generated file policies.
+const (
+	MetadataBinary    = "binary"    // "strings" or "hex" for chunks extracted from a binary file
+	MetadataGenerated = "generated" // Why the file was detected as generated, such as GeneratedMarker
+)
+
+// Reasons DetectGenerated reports for generated files.
+const (
+	GeneratedMarker    = "marker"    // A header such as "Code generated ... DO NOT EDIT." or @generated
+	GeneratedSourceMap = "sourcemap" // A sourceMappingURL comment left by a bundler or minifier
+	GeneratedMinified  = "minified"  // Lines far longer than people write
+)
+
+// GeneratedSampleSize is the number of chunks GeneratedSample keeps of each
+// generated file.
+const GeneratedSampleSize = 8
+
+// binarySample is how much of a file IsBinary looks at, as in git.
+const binarySample = 8000
+
+// minifiedLineLength is the average line length, over the start of a file,
+// above which DetectGenerated considers it minified.
+const minifiedLineLength = 300
+
+// minStringLength is the shortest run of printable characters BinaryStrings
+// extracts, as in strings(1).
+const minStringLength = 4
+
+// generatedMarkers are the headers of generated files, looked for near the
+// start of a file.
+var generatedMarkers = []string{"DO NOT EDIT", "@generated", "<auto-generated", "autogenerated by"}
+
+// BinaryPolicy selects what happens to binary files.
+type BinaryPolicy int
+
+const (
+	BinaryChunk   BinaryPolicy = iota // Chunk binary files as if they were text
+	BinarySkip                        // Skip binary files, returning no chunks
+	BinaryError                       // Fail with ErrBinaryFile
+	BinaryStrings                     // Chunk the runs of printable text in binary files, one per line
+	BinaryHex                         // Chunk a hex dump of binary files, 16 bytes per line
+)
+
+// GeneratedPolicy selects what happens to generated and minified files.
+type GeneratedPolicy int
+
+const (
+	GeneratedChunk  GeneratedPolicy = iota // Chunk generated files like any other
+	GeneratedSample                        // Keep GeneratedSampleSize chunks spread over each generated file
+	GeneratedSkip                          // Skip generated files, returning no chunks
+)
+
+// WithBinaryFiles sets the policy for files ChunkFile, ChunkMapped, and
+// pipelines detect as binary with IsBinary. Chunks extracted with
+// BinaryStrings or BinaryHex are chunked line by line, record the mode in
+// their metadata under MetadataBinary, and have byte offsets in the original
+// file.
+func WithBinaryFiles(policy BinaryPolicy) Option {
+	return func(c *config) {
+		c.binary = policy
+	}
+}
+
+// WithGeneratedFiles sets the policy for files ChunkFile, ChunkMapped, and
+// pipelines detect as generated or minified with DetectGenerated, which
+// otherwise flood an index with chunks nobody wrote. Sampled chunks record
+// the reason in their metadata under MetadataGenerated.
+func WithGeneratedFiles(policy GeneratedPolicy) Option {
+	return func(c *config) {
+		c.generated = policy
+	}
+}
+
+// IsBinary reports whether content looks like a binary file: like git, it
+// looks for a zero byte in the first 8000 bytes. UTF-16 text, which has zero
+// bytes too, isn't binary.
+func IsBinary(content []byte) bool {
+	sample := content[:min(len(content), binarySample)]
+	if bytes.IndexByte(sample, 0) < 0 {
+		return false
+	}
+	switch DetectEncoding(sample) {
+	case EncodingUTF16LE, EncodingUTF16BE:
+		return false
+	}
+	return true
+}
+
+// DetectGenerated reports why content looks generated or minified, or ""
+// if it doesn't: a generated file marker in its first lines, a source map
+// comment, or long lines over its first 4 KiB.
+func DetectGenerated(content []byte) string {
+	return detectGenerated(string(content))
+}
+
+// detectGenerated implements DetectGenerated.
+func detectGenerated(text string) string {
+	head := text[:min(len(text), detectSample)]
+	for _, marker := range generatedMarkers {
+		if strings.Contains(head, marker) {
+			return GeneratedMarker
+		}
+	}
+
+	tail := text[max(len(text)-detectSample, 0):]
+	if strings.Contains(tail, "# sourceMappingURL=") {
+		return GeneratedSourceMap
+	}
+
+	if len(head) == detectSample && len(head)/(strings.Count(head, "\n")+1) > minifiedLineLength {
+		return GeneratedMinified
+	}
+	return ""
+}
+
+// extractStrings returns the runs of printable ASCII in content, one per
+// line, like strings(1), with the map from offsets in the text to offsets in
+// content. The line feed after a run stands for the bytes up to the next one.
+func extractStrings(content string) (string, *offsetMap) {
+	var tb textBuilder
+	start := -1
+	for i := 0; i <= len(content); i++ {
+		if i < len(content) && (content[i] >= ' ' && content[i] <= '~' || content[i] == '\t') {
+			if start < 0 {
+				start = i
+			}
+			continue
+		}
+		if start < 0 {
+			continue
+		}
+
+		if i-start >= minStringLength {
+			gap := start - tb.offsets.origEnd
+			if tb.b.Len() == 0 {
+				tb.offsets.skip(gap)
+			} else {
+				tb.writeRune('\n', gap)
+			}
+			for j := start; j < i; j++ {
+				tb.writeRune(rune(content[j]), 1)
+			}
+		}
+		start = -1
+	}
+	return tb.b.String(), &tb.offsets
+}
+
+// hexDump returns a hex dump of content like hexdump -C, with the map from
+// offsets in the text to offsets in content.
+func hexDump(content string) (string, *offsetMap) {
+	var tb textBuilder
+	tb.b.Grow(len(content) / 16 * 78)
+	for offset := 0; offset < len(content); offset += 16 {
+		line := content[offset:min(offset+16, len(content))]
+
+		var hex, ascii strings.Builder
+		for i := range 16 {
+			if i == 8 {
+				hex.WriteByte(' ')
+			}
+			if i >= len(line) {
+				hex.WriteString("   ")
+				continue
+			}
+			fmt.Fprintf(&hex, " %02x", line[i])
+			if c := line[i]; c >= ' ' && c <= '~' {
+				ascii.WriteByte(c)
+			} else {
+				ascii.WriteByte('.')
+			}
+		}
+
+		// The line feed stands for no bytes, so chunks ending before it end
+		// after the line's bytes
+		n, _ := fmt.Fprintf(&tb.b, "%08x %s  |%s|", offset, hex.String(), ascii.String())
+		tb.offsets.add(n, len(line))
+		tb.writeRune('\n', 0)
+	}
+	return tb.b.String(), &tb.offsets
+}
+
+// sampleChunks returns n chunks spread evenly over chunks, starting with the
+// first.
+func sampleChunks(chunks []Chunk, n int) []Chunk {
+	if len(chunks) <= n {
+		return chunks
+	}
+	sample := make([]Chunk, n)
+	for i := range sample {
+		sample[i] = chunks[i*len(chunks)/n]
+	}
+	return sample
+}
@@ -0,0 +1,164 @@
+package chunkx
+
+import (
+	"errors"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestIsBinary(t *testing.T) {
+	tests := []struct {
+		name    string
+		content []byte
+		want    bool
+	}{
+		{"text", []byte("package main\n"), false},
+		{"empty", nil, false},
+		{"elf", []byte("\x7fELF\x02\x01\x01\x00\x00\x00"), true},
+		{"utf-16", utf16Bytes("def main():\n    pass\n", false), false},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := IsBinary(tt.content); got != tt.want {
+				t.Errorf("IsBinary() = %v, want %v", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestDetectGenerated(t *testing.T) {
+	tests := []struct {
+		name    string
+		content string
+		want    string
+	}{
+		{"handwritten", "package main\n\nfunc main() {}\n", ""},
+		{"go generate", "// Code generated by protoc-gen-go. DO NOT EDIT.\n\npackage pb\n", GeneratedMarker},
+		{"generated tag", "/**\n * @generated\n */\nexport const x = 1;\n", GeneratedMarker},
+		{"source map", "var a=1;\n//# sourceMappingURL=app.min.js.map\n", GeneratedSourceMap},
+		{"minified", strings.Repeat("var a=function(b){return b+1};", 200), GeneratedMinified},
+		{"long but short file", strings.Repeat("x", 1000), ""},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := DetectGenerated([]byte(tt.content)); got != tt.want {
+				t.Errorf("DetectGenerated() = %q, want %q", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestExtractStrings(t *testing.T) {
+	content := "\x00\x01hello\x00ab\x00world!\x02"
+	text, offsets := extractStrings(content)
+	if text != "hello\nworld!" {
+		t.Fatalf("extractStrings() = %q", text)
+	}
+
+	// Each string maps back to its bytes
+	for _, s := range []string{"hello", "world!"} {
+		start := strings.Index(text, s)
+		orig := offsets.original(start)
+		if got := content[orig : orig+len(s)]; got != s {
+			t.Errorf("%q maps to %q", s, got)
+		}
+		if end := offsets.original(start + len(s)); end != orig+len(s) {
+			t.Errorf("%q ends at %d, want %d", s, end, orig+len(s))
+		}
+	}
+}
+
+func TestHexDump(t *testing.T) {
+	content := "0123456789abcdef\x00\x01"
+	text, offsets := hexDump(content)
+
+	want := "00000000  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 66  |0123456789abcdef|\n" +
+		"00000010  00 01                                             |..|\n"
+	if text != want {
+		t.Fatalf("hexDump() =\n%s\nwant\n%s", text, want)
+	}
+
+	second := strings.IndexByte(text, '\n') + 1
+	if got := offsets.original(second); got != 16 {
+		t.Errorf("second line starts at byte %d, want 16", got)
+	}
+	if got := offsets.original(second - 1); got != 16 {
+		t.Errorf("first line ends at byte %d, want 16", got)
+	}
+	if got := offsets.original(len(text)); got != len(content) {
+		t.Errorf("dump ends at byte %d, want %d", got, len(content))
+	}
+}
+
+func TestWithBinaryFiles(t *testing.T) {
+	path := filepath.Join(t.TempDir(), "app.bin")
+	content := "\x7fELF\x00\x00\x00usage: app [flags]\x00\x00"
+	if err := os.WriteFile(path, []byte(content), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	chunker := NewChunker()
+
+	chunks, err := chunker.ChunkFile(path, WithLanguage(languages.Generic), WithBinaryFiles(BinarySkip))
+	if err != nil || len(chunks) != 0 {
+		t.Errorf("expected the file to be skipped, got %+v, %v", chunks, err)
+	}
+
+	_, err = chunker.ChunkFile(path, WithLanguage(languages.Generic), WithBinaryFiles(BinaryError))
+	var fileErr *FileError
+	if !errors.Is(err, ErrBinaryFile) || !errors.As(err, &fileErr) || fileErr.Kind != ErrorBinary {
+		t.Errorf("expected a binary FileError, got %v", err)
+	}
+
+	chunks, err = chunker.ChunkFile(path, WithLanguage(languages.Generic), WithBinaryFiles(BinaryStrings))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	// "ELF" is too short to be extracted
+	if len(chunks) != 1 || chunks[0].Content != "usage: app [flags]" {
+		t.Fatalf("unexpected chunks: %+v", chunks)
+	}
+	if chunks[0].StartByte != 7 || chunks[0].EndByte != 25 {
+		t.Errorf("offsets = [%d, %d)", chunks[0].StartByte, chunks[0].EndByte)
+	}
+	if got := chunks[0].Metadata[MetadataBinary]; got != "strings" {
+		t.Errorf("binary metadata = %q, want strings", got)
+	}
+}
+
+func TestWithGeneratedFiles(t *testing.T) {
+	var code strings.Builder
+	code.WriteString("// Code generated by stringer. DO NOT EDIT.\n")
+	for range 100 {
+		code.WriteString("x\n")
+	}
+	path := filepath.Join(t.TempDir(), "gen.txt")
+	if err := os.WriteFile(path, []byte(code.String()), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	opts := []Option{WithLanguage(languages.Generic), WithMaxSize(5), WithOverlap(0)}
+	chunker := NewChunker()
+
+	all, err := chunker.ChunkFile(path, opts...)
+	if err != nil || len(all) <= GeneratedSampleSize {
+		t.Fatalf("expected more than %d chunks, got %d, %v", GeneratedSampleSize, len(all), err)
+	}
+
+	sampled, err := chunker.ChunkFile(path, append(opts, WithGeneratedFiles(GeneratedSample))...)
+	if err != nil || len(sampled) != GeneratedSampleSize {
+		t.Fatalf("expected %d chunks, got %d, %v", GeneratedSampleSize, len(sampled), err)
+	}
+	if sampled[0].StartByte != 0 || sampled[0].Metadata[MetadataGenerated] != GeneratedMarker {
+		t.Errorf("unexpected first sampled chunk: %+v", sampled[0])
+	}
+
+	skipped, err := chunker.ChunkFile(path, append(opts, WithGeneratedFiles(GeneratedSkip))...)
+	if err != nil || len(skipped) != 0 {
+		t.Errorf("expected the file to be skipped, got %d chunks, %v", len(skipped), err)
+	}
+}
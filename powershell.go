@@ -0,0 +1,132 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+var (
+	// psDeclaration matches the line of PowerShell starting a function,
+	// filter, workflow, DSC configuration, class, or enum, such as
+	// "function Get-Widget {" or "class Widget : Base {", and its kind and
+	// name without its scope.
+	psDeclaration = regexp.MustCompile(`(?i)^[ \t]*(function|filter|workflow|configuration|class|enum)[ \t]+(?:(?:global|script|local|private):)?([\w.-]+)`)
+	// psHereString matches the opening of a PowerShell here-string, which
+	// ends its line, and its quote.
+	psHereString = regexp.MustCompile(`^@(["'])[ \t]*\r?$`)
+)
+
+// psSpans returns the functions, filters, workflows, configurations, classes,
+// and enums of the PowerShell code, each with the comment-based help or the
+// comment lines right above it. Classes and enums record their name as
+// MetadataBlock, and the others record MetadataDefinition.
+func psSpans(code string) []textSpan {
+	var spans []textSpan
+	for offset := 0; offset < len(code); {
+		line, _ := codeLine(code, offset)
+		m := psDeclaration.FindStringSubmatch(line)
+		if m == nil {
+			offset = psLineEnd(code, offset)
+			continue
+		}
+		closing := psBlockEnd(code, offset)
+		if closing < 0 {
+			offset = psLineEnd(code, offset)
+			continue
+		}
+
+		_, end := codeLine(code, closing)
+		metadata := map[string]string{MetadataDefinition: m[2]}
+		if kind := strings.ToLower(m[1]); kind == "class" || kind == "enum" {
+			metadata = map[string]string{MetadataBlock: m[2]}
+		}
+		spans = append(spans, textSpan{
+			start:    psComments(code, offset, spanEnd(spans)),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// psLineEnd returns the offset after the line of PowerShell at offset, and
+// after the here-string or block comment it opens, if any, so their lines
+// aren't taken for declarations.
+func psLineEnd(code string, offset int) int {
+	line, next := codeLine(code, offset)
+	if i := strings.LastIndexByte(line, '@'); i >= 0 {
+		if m := psHereString.FindStringSubmatch(line[i:]); m != nil {
+			_, next = codeLine(code, skipPast(code, next-1, len(code), "\n"+m[1]+"@"))
+			return next
+		}
+	}
+	if i := strings.LastIndex(line, "<#"); i >= 0 && !strings.Contains(line[i:], "#>") {
+		_, next = codeLine(code, skipPast(code, offset+i, len(code), "#>"))
+	}
+	return next
+}
+
+// psBlockEnd returns the offset after the brace closing the first one from
+// offset in the PowerShell code, skipping strings, here-strings, comments,
+// and escaped characters, or -1 if there is none.
+func psBlockEnd(code string, offset int) int {
+	depth := 0
+	for i := offset; i < len(code); i++ {
+		switch c := code[i]; {
+		case c == '{':
+			depth++
+		case c == '}':
+			if depth--; depth == 0 {
+				return i + 1
+			}
+		case c == '`':
+			i++
+		case c == '#':
+			for i+1 < len(code) && code[i+1] != '\n' {
+				i++
+			}
+		case c == '<' && strings.HasPrefix(code[i:], "<#"):
+			i = skipPast(code, i, len(code), "#>") - 1
+		case c == '@':
+			line, next := codeLine(code, i)
+			if m := psHereString.FindStringSubmatch(line); m != nil {
+				i = skipPast(code, next-1, len(code), "\n"+m[1]+"@") - 1
+			}
+		case c == '"' || c == '\'':
+			for quote := code[i]; i+1 < len(code) && code[i+1] != quote; i++ {
+				if quote == '"' && code[i+1] == '`' {
+					i++
+				}
+			}
+			i++
+		}
+	}
+	return -1
+}
+
+// psComments returns the start of the comment-based help or the comment lines
+// right above the line at offset of the PowerShell code, not before from, or
+// offset if there are none.
+func psComments(code string, offset, from int) int {
+	for offset > from {
+		prev := strings.LastIndexByte(code[:offset-1], '\n') + 1
+		line := strings.TrimSpace(code[prev : offset-1])
+		if prev < from {
+			break
+		}
+		if strings.HasSuffix(line, "#>") {
+			open := strings.LastIndex(code[from:offset], "<#")
+			if open < 0 {
+				break
+			}
+			offset = strings.LastIndexByte(code[:from+open], '\n') + 1
+			continue
+		}
+		if !strings.HasPrefix(line, "#") {
+			break
+		}
+		offset = prev
+	}
+	return offset
+}
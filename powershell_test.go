@@ -0,0 +1,44 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_PowerShell(t *testing.T) {
+	code := `#Requires -Version 5.1
+
+<#
+.SYNOPSIS
+Gets a widget.
+.EXAMPLE
+function Fake { }
+#>
+function Get-Widget {
+    param([string]$Name)
+    $template = @"
+Widget: $Name }
+"@
+    Write-Output $template
+}
+
+# Tracks widgets
+class Widget {
+    [string]$Name
+}
+
+filter Select-Big { if ($_.Size -gt 10) { $_ } }
+`
+	chunks := testChunkMetadata(t, code, languages.PowerShell, 30, []struct{ prefix, key, value string }{
+		{"<#\n.SYNOPSIS\n", MetadataDefinition, "Get-Widget"},
+		{"# Tracks widgets\nclass Widget {\n", MetadataBlock, "Widget"},
+		{"filter Select-Big", MetadataDefinition, "Select-Big"},
+	})
+	for _, chunk := range chunks {
+		if strings.HasPrefix(chunk.Content, "<#") && !strings.Contains(chunk.Content, "Write-Output") {
+			t.Errorf("expected the function chunked past its here-string, got %q", chunk.Content)
+		}
+	}
+}
@@ -0,0 +1,54 @@
+package chunkx
+
+// ProgressHooks receives progress events from a ChunkPipeline so callers can
+// render progress bars and ETAs. Any hook may be nil.
+//
+// Hooks are called from the pipeline's goroutines and must be safe for
+// concurrent use. Slow hooks slow down the pipeline.
+type ProgressHooks struct {
+	// OnFileQueued is called when RunDir discovers a file to chunk.
+	OnFileQueued func(path string)
+
+	// OnFileStart is called when a worker starts chunking a file.
+	OnFileStart func(path string)
+
+	// OnChunk is called for every chunk produced, before the file's result is delivered.
+	OnChunk func(path string, chunk Chunk)
+
+	// OnFileDone is called when a file has been chunked, successfully or not.
+	OnFileDone func(result FileResult)
+}
+
+// WithProgress sets hooks that report the progress of a ChunkPipeline.
+// It has no effect on single-file chunking.
+func WithProgress(hooks ProgressHooks) Option {
+	return func(c *config) {
+		c.progress = hooks
+	}
+}
+
+// fileQueued reports that a file was discovered.
+func (h *ProgressHooks) fileQueued(path string) {
+	if h.OnFileQueued != nil {
+		h.OnFileQueued(path)
+	}
+}
+
+// fileStart reports that chunking of a file started.
+func (h *ProgressHooks) fileStart(path string) {
+	if h.OnFileStart != nil {
+		h.OnFileStart(path)
+	}
+}
+
+// fileDone reports the chunks and outcome of a file.
+func (h *ProgressHooks) fileDone(result FileResult) {
+	if h.OnChunk != nil {
+		for _, chunk := range result.Chunks {
+			h.OnChunk(result.Path, chunk)
+		}
+	}
+	if h.OnFileDone != nil {
+		h.OnFileDone(result)
+	}
+}
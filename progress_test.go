@@ -0,0 +1,94 @@
+package chunkx
+
+import (
+	"context"
+	"path/filepath"
+	"sync/atomic"
+	"testing"
+)
+
+func TestChunkPipeline_Progress(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"a.go":  "package a\n\nfunc A() {}\n",
+		"b.py":  "def b():\n    pass\n",
+		"c.txt": "plain text\n",
+	})
+
+	var queued, started, done, chunkEvents atomic.Int64
+	hooks := ProgressHooks{
+		OnFileQueued: func(string) { queued.Add(1) },
+		OnFileStart:  func(string) { started.Add(1) },
+		OnChunk:      func(string, Chunk) { chunkEvents.Add(1) },
+		OnFileDone:   func(FileResult) { done.Add(1) },
+	}
+
+	totalChunks := 0
+	for result := range NewChunkPipeline(2, WithProgress(hooks)).RunDir(context.Background(), dir) {
+		totalChunks += len(result.Chunks)
+	}
+
+	if queued.Load() != 3 || started.Load() != 3 || done.Load() != 3 {
+		t.Errorf("queued = %d, started = %d, done = %d, want 3 each",
+			queued.Load(), started.Load(), done.Load())
+	}
+	if chunkEvents.Load() != int64(totalChunks) {
+		t.Errorf("OnChunk called %d times, want %d", chunkEvents.Load(), totalChunks)
+	}
+}
+
+func TestChunkPipeline_ProgressErrors(t *testing.T) {
+	var queued, started, done atomic.Int64
+	hooks := ProgressHooks{
+		OnFileQueued: func(string) { queued.Add(1) },
+		OnFileStart:  func(string) { started.Add(1) },
+		OnFileDone:   func(FileResult) { done.Add(1) },
+	}
+
+	// A path that can't be walked is reported, and so queued too
+	missing := filepath.Join(t.TempDir(), "missing")
+	for result := range NewChunkPipeline(1, WithProgress(hooks)).RunDir(context.Background(), missing) {
+		if result.Err == nil {
+			t.Errorf("expected an error for %s", result.Path)
+		}
+	}
+	if queued.Load() != 1 || started.Load() != 1 || done.Load() != 1 {
+		t.Errorf("queued = %d, started = %d, done = %d, want 1 each",
+			queued.Load(), started.Load(), done.Load())
+	}
+
+	// The files past MaxFiles are neither chunked nor queued, so progress
+	// still reaches every queued file
+	files := map[string]string{}
+	store := memStore{}
+	for _, name := range []string{"a.txt", "b.txt", "c.txt", "d.txt", "e.txt"} {
+		files[name] = "text\n"
+		store[name] = "text\n"
+	}
+	dir := writeTestFiles(t, files)
+	pipeline := NewChunkPipeline(2, WithProgress(hooks), WithSampling(Sampling{MaxFiles: 2}))
+	for name, results := range map[string]func() <-chan FileResult{
+		"RunDir":     func() <-chan FileResult { return pipeline.RunDir(context.Background(), dir) },
+		"RunObjects": func() <-chan FileResult { return pipeline.RunObjects(context.Background(), store, "") },
+	} {
+		queued.Store(0)
+		started.Store(0)
+		done.Store(0)
+		n := 0
+		for range results() {
+			n++
+		}
+		if n != 2 || queued.Load() != 2 || started.Load() != 2 || done.Load() != 2 {
+			t.Errorf("%s: results = %d, queued = %d, started = %d, done = %d, want 2 each",
+				name, n, queued.Load(), started.Load(), done.Load())
+		}
+	}
+}
+
+func TestProgressHooks_NilHooks(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{"a.go": "package a\n"})
+
+	// A partially populated set of hooks must not panic
+	hooks := ProgressHooks{OnFileDone: func(FileResult) {}}
+	for range NewChunkPipeline(1, WithProgress(hooks)).RunDir(context.Background(), dir) {
+	}
+}
@@ -0,0 +1,111 @@
+package chunkx
+
+import (
+	"strings"
+)
+
+// Metadata keys of the healthcare record holding the chunk.
+const (
+	MetadataResourceType = "resource_type" // Type of the FHIR resource, such as "Patient", or of the HL7 message, such as "ADT^A01"
+	MetadataResourceID   = "resource_id"   // ID of the FHIR resource, or control ID of the HL7 message
+)
+
+// ndjsonSpans returns the records of the newline-delimited JSON code, such as
+// the resources of a FHIR bulk export, one per line. Records are never split,
+// even when too large for a chunk. FHIR resources record their
+// resourceType as MetadataResourceType and their id as MetadataResourceID, so
+// each is chunked apart, and other records are chunked together while they
+// fit.
+func ndjsonSpans(code string) []textSpan {
+	var spans []textSpan
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		if strings.TrimSpace(line) == "" {
+			offset = next
+			continue
+		}
+
+		metadata := map[string]string{}
+		if root, ok := parseJSON(line); ok {
+			if kind, ok := root.get("resourceType"); ok {
+				metadata[MetadataResourceType] = jsonString(line, kind)
+			}
+			if id, ok := root.get("id"); ok {
+				metadata[MetadataResourceID] = jsonString(line, id)
+			}
+		}
+		spans = append(spans, textSpan{start: offset, end: next, metadata: metadata, whole: true})
+		offset = next
+	}
+	return spans
+}
+
+// hl7Spans returns the messages of the HL7 v2 code, each from its MSH segment
+// to the next one or a batch or file segment, recording the message type of
+// MSH-9, such as "ADT^A01", as MetadataResourceType and the control ID of
+// MSH-10 as MetadataResourceID. Messages are never split, even when too large
+// for a chunk. Segments may end with a carriage return, as the standard has
+// them, or a line feed.
+func hl7Spans(code string) []textSpan {
+	var spans []textSpan
+	open := false // Whether the last message takes the segments after it
+	for offset := 0; offset < len(code); {
+		end := strings.IndexAny(code[offset:], "\r\n")
+		if end < 0 {
+			end = len(code)
+		} else {
+			end += offset
+		}
+		next := end
+		for next < len(code) && (code[next] == '\r' || code[next] == '\n') {
+			next++
+		}
+
+		switch segment := code[offset:end]; {
+		case hl7Segment(segment, "MSH"):
+			spans = append(spans, textSpan{start: offset, end: next, metadata: hl7Metadata(segment), whole: true})
+			open = true
+		case hl7Segment(segment, "FHS", "BHS", "BTS", "FTS"):
+			// File and batch headers and trailers surround messages rather than
+			// belong to them
+			open = false
+		case open:
+			spans[len(spans)-1].end = next
+		}
+		offset = next
+	}
+	return spans
+}
+
+// hl7Segment reports whether the HL7 segment has one of the IDs, followed by
+// its field separator.
+func hl7Segment(segment string, ids ...string) bool {
+	for _, id := range ids {
+		if len(segment) > len(id) && strings.HasPrefix(segment, id) && !isWordByte(segment[len(id)]) {
+			return true
+		}
+	}
+	return false
+}
+
+// hl7Metadata returns the metadata of the HL7 message whose MSH segment is
+// msh, whose fourth byte is the field separator and fifth the component
+// separator.
+func hl7Metadata(msh string) map[string]string {
+	// The field separator is MSH-1, so MSH-n is the field at index n-1
+	fields := strings.Split(msh, msh[3:4])
+	metadata := map[string]string{}
+	if len(fields) > 8 && fields[8] != "" {
+		kind := fields[8]
+		if len(fields[1]) > 0 {
+			// The message structure, such as "ADT_A01", is left out
+			parts := strings.Split(kind, fields[1][:1])
+			kind = strings.Join(parts[:min(len(parts), 2)], fields[1][:1])
+		}
+		metadata[MetadataResourceType] = kind
+	}
+	if len(fields) > 9 && fields[9] != "" {
+		metadata[MetadataResourceID] = fields[9]
+	}
+	return metadata
+}
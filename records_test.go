@@ -0,0 +1,51 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_NDJSON(t *testing.T) {
+	code := `{"resourceType": "Patient", "id": "p1", "name": [{"family": "Chalmers"}]}
+{"resourceType": "Observation", "id": "o1", "status": "final"}
+`
+	testChunkMetadata(t, code, languages.NDJSON, 100, []struct{ prefix, key, value string }{
+		{`{"resourceType": "Patient"`, MetadataResourceType, "Patient"},
+		{`{"resourceType": "Patient"`, MetadataResourceID, "p1"},
+		{`{"resourceType": "Observation"`, MetadataResourceID, "o1"},
+	})
+}
+
+func TestChunker_HL7(t *testing.T) {
+	code := "FHS|^~\\&|LAB\r" +
+		"MSH|^~\\&|LAB|HOSP|EHR|HOSP|20240101120000||ORU^R01^ORU_R01|MSG001|P|2.5\r" +
+		"PID|1||12345^^^HOSP||Doe^Jane\r" +
+		"OBX|1|NM|GLU^Glucose||5.4|mmol/L\r" +
+		"OBX|2|NM|HGB^Hemoglobin||13.5|g/dL\r" +
+		"MSH|^~\\&|ADT|HOSP|EHR|HOSP|20240101130000||ADT^A01|MSG002|P|2.5\r" +
+		"PID|1||67890^^^HOSP||Roe^Richard\r" +
+		"FTS|1\r"
+	testChunkMetadata(t, code, languages.HL7, 100, []struct{ prefix, key, value string }{
+		{"MSH|^~\\&|LAB|", MetadataResourceType, "ORU^R01"},
+		{"MSH|^~\\&|LAB|", MetadataResourceID, "MSG001"},
+		{"MSH|^~\\&|ADT|", MetadataResourceType, "ADT^A01"},
+	})
+
+	// A message too large for a chunk is kept whole
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.HL7), WithMaxSize(1))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	for _, chunk := range chunks {
+		if chunk.Metadata[MetadataResourceID] == "MSG001" {
+			if !strings.HasSuffix(chunk.Content, "g/dL\r") || chunk.Metadata[MetadataOverBudget] != "true" {
+				t.Errorf("expected the whole message flagged over budget, got %q with %v", chunk.Content, chunk.Metadata)
+			}
+		}
+		if strings.Contains(chunk.Content, "FTS") && chunk.Metadata[MetadataResourceID] != "" {
+			t.Errorf("expected the file trailer left out of the messages, got %q", chunk.Content)
+		}
+	}
+}
@@ -0,0 +1,262 @@
+package chunkx
+
+import (
+	"fmt"
+	"math"
+	"regexp"
+	"slices"
+	"strings"
+)
+
+// MetadataRedacted is the metadata key of chunks with masked data. Its value
+// lists the kinds of data masked in the chunk, such as "api_key,email".
+const MetadataRedacted = "redacted"
+
+// Kinds of sensitive data found by the built-in detectors.
+const (
+	RedactEmail       = "email"        // Email addresses
+	RedactCreditCard  = "credit_card"  // Payment card numbers passing the Luhn check
+	RedactAPIKey      = "api_key"      // Keys and tokens of well-known services, and private keys
+	RedactHighEntropy = "high_entropy" // Random-looking strings, such as unknown keys
+)
+
+// Defaults of the high entropy detector of DefaultDetectors.
+const (
+	DefaultEntropyLength = 24  // Shortest string considered
+	DefaultMinEntropy    = 4.0 // Bits of entropy per character
+)
+
+// redactMask replaces every byte of the data WithRedaction masks.
+const redactMask = '*'
+
+// Detector finds sensitive data, such as secrets and personal information,
+// for WithRedaction to mask.
+type Detector interface {
+	Detect(text string) []Match
+}
+
+// Match is sensitive data found by a Detector.
+type Match struct {
+	Start, End int    // Byte offsets of the data in the text
+	Kind       string // Kind of data, such as RedactEmail
+}
+
+// WithRedaction masks the data that detectors find before chunking, so that
+// secrets and personal information never reach an embedding API or an index.
+// Without detectors, DefaultDetectors are used.
+//
+// Every byte of masked data is replaced with "*", which keeps offsets and
+// line numbers, and chunks with masked data list its kinds in their
+// metadata under MetadataRedacted.
+func WithRedaction(detectors ...Detector) Option {
+	if len(detectors) == 0 {
+		detectors = DefaultDetectors()
+	}
+	return func(c *config) {
+		c.detectors = detectors
+	}
+}
+
+// DefaultDetectors returns the built-in detectors for emails, credit card
+// numbers, API keys, and high entropy strings.
+func DefaultDetectors() []Detector {
+	return []Detector{
+		EmailDetector(),
+		CreditCardDetector(),
+		APIKeyDetector(),
+		EntropyDetector(DefaultEntropyLength, DefaultMinEntropy),
+	}
+}
+
+// detectorsByKind maps the kinds accepted in Config.Redact to detectors.
+var detectorsByKind = map[string]func() Detector{
+	RedactEmail:       EmailDetector,
+	RedactCreditCard:  CreditCardDetector,
+	RedactAPIKey:      APIKeyDetector,
+	RedactHighEntropy: func() Detector { return EntropyDetector(DefaultEntropyLength, DefaultMinEntropy) },
+}
+
+// RegexpDetector returns a Detector reporting the matches of re as kind.
+func RegexpDetector(kind string, re *regexp.Regexp) Detector {
+	return &regexpDetector{kind: kind, re: re}
+}
+
+// regexpDetector reports the matches of a regular expression.
+type regexpDetector struct {
+	kind  string
+	re    *regexp.Regexp
+	valid func(string) bool // Filters matches, if set
+}
+
+// Detect reports the matches of the regular expression in text.
+func (d *regexpDetector) Detect(text string) []Match {
+	var matches []Match
+	for _, loc := range d.re.FindAllStringIndex(text, -1) {
+		if d.valid == nil || d.valid(text[loc[0]:loc[1]]) {
+			matches = append(matches, Match{Start: loc[0], End: loc[1], Kind: d.kind})
+		}
+	}
+	return matches
+}
+
+var (
+	emailPattern      = regexp.MustCompile(`[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}`)
+	creditCardPattern = regexp.MustCompile(`\b\d(?:[ -]?\d){12,18}\b`)
+	apiKeyPattern     = regexp.MustCompile(strings.Join([]string{
+		`\bAKIA[0-9A-Z]{16}\b`,                                   // AWS access key IDs
+		`\bgh[pousr]_[A-Za-z0-9]{36,}`,                           // GitHub tokens
+		`\bgithub_pat_[A-Za-z0-9_]{22,}`,                         // GitHub fine-grained tokens
+		`\bglpat-[A-Za-z0-9_-]{20,}`,                             // GitLab tokens
+		`\bxox[abposr]-[A-Za-z0-9-]{10,}`,                        // Slack tokens
+		`\bsk-(?:proj-)?[A-Za-z0-9_-]{20,}`,                      // OpenAI keys
+		`\b[sr]k_live_[A-Za-z0-9]{20,}`,                          // Stripe secret keys
+		`\bAIza[0-9A-Za-z_-]{35}`,                                // Google API keys
+		`\beyJ[A-Za-z0-9_-]+\.eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+`, // JSON Web Tokens
+		// PEM private keys
+		`(?s)-----BEGIN [A-Z ]*PRIVATE KEY-----.*?-----END [A-Z ]*PRIVATE KEY-----`,
+	}, "|"))
+	entropyCandidate = regexp.MustCompile(`[A-Za-z0-9+/=_-]+`)
+)
+
+// EmailDetector returns a Detector for email addresses.
+func EmailDetector() Detector {
+	return &regexpDetector{kind: RedactEmail, re: emailPattern}
+}
+
+// CreditCardDetector returns a Detector for payment card numbers of 13 to 19
+// digits, optionally grouped with spaces or dashes, that pass the Luhn check.
+func CreditCardDetector() Detector {
+	return &regexpDetector{kind: RedactCreditCard, re: creditCardPattern, valid: luhnValid}
+}
+
+// APIKeyDetector returns a Detector for the keys and tokens of well-known
+// services, such as AWS, GitHub, Slack, OpenAI, Stripe, and Google, and for
+// PEM private keys.
+func APIKeyDetector() Detector {
+	return &regexpDetector{kind: RedactAPIKey, re: apiKeyPattern}
+}
+
+// EntropyDetector returns a Detector for runs of at least minLength base64
+// characters that mix upper and lower case letters with digits and have at
+// least minEntropy bits of Shannon entropy per character, which catches
+// keys no pattern knows about.
+func EntropyDetector(minLength int, minEntropy float64) Detector {
+	return &regexpDetector{kind: RedactHighEntropy, re: entropyCandidate, valid: func(s string) bool {
+		return len(s) >= minLength && mixedCharacters(s) && entropy(s) >= minEntropy
+	}}
+}
+
+// luhnValid reports whether the digits of s pass the Luhn check.
+func luhnValid(s string) bool {
+	sum, n := 0, 0
+	for i := len(s) - 1; i >= 0; i-- {
+		if s[i] < '0' || s[i] > '9' {
+			continue
+		}
+		d := int(s[i] - '0')
+		if n%2 == 1 {
+			if d *= 2; d > 9 {
+				d -= 9
+			}
+		}
+		sum += d
+		n++
+	}
+	return n >= 13 && n <= 19 && sum%10 == 0
+}
+
+// mixedCharacters reports whether s has upper and lower case letters and
+// digits.
+func mixedCharacters(s string) bool {
+	var upper, lower, digit bool
+	for i := 0; i < len(s); i++ {
+		switch c := s[i]; {
+		case c >= 'A' && c <= 'Z':
+			upper = true
+		case c >= 'a' && c <= 'z':
+			lower = true
+		case c >= '0' && c <= '9':
+			digit = true
+		}
+	}
+	return upper && lower && digit
+}
+
+// entropy returns the Shannon entropy of the bytes of s, in bits per byte.
+func entropy(s string) float64 {
+	var counts [256]int
+	for i := 0; i < len(s); i++ {
+		counts[s[i]]++
+	}
+
+	var bits float64
+	for _, count := range counts {
+		if count > 0 {
+			p := float64(count) / float64(len(s))
+			bits -= p * math.Log2(p)
+		}
+	}
+	return bits
+}
+
+// redact returns code with the data the detectors find masked, along with the
+// matches sorted by offset.
+func redact(code string, detectors []Detector) (string, []Match) {
+	var matches []Match
+	for _, d := range detectors {
+		matches = append(matches, d.Detect(code)...)
+	}
+	if len(matches) == 0 {
+		return code, nil
+	}
+	slices.SortFunc(matches, func(a, b Match) int { return a.Start - b.Start })
+
+	masked := []byte(code)
+	for _, m := range matches {
+		for i := max(m.Start, 0); i < min(m.End, len(masked)); i++ {
+			masked[i] = redactMask
+		}
+	}
+	return string(masked), matches
+}
+
+// flagRedacted lists the kinds of the matches within every chunk in its
+// metadata.
+func flagRedacted(chunks []Chunk, matches []Match) {
+	for i := range chunks {
+		var kinds []string
+		for _, m := range matches {
+			if m.Start >= chunks[i].EndByte {
+				break
+			}
+			if m.End > chunks[i].StartByte && !slices.Contains(kinds, m.Kind) {
+				kinds = append(kinds, m.Kind)
+			}
+		}
+		if len(kinds) == 0 {
+			continue
+		}
+
+		slices.Sort(kinds)
+		if chunks[i].Metadata == nil {
+			chunks[i].Metadata = make(map[string]string, 1)
+		}
+		chunks[i].Metadata[MetadataRedacted] = strings.Join(kinds, ",")
+	}
+}
+
+// redactDetectors returns the detectors for the kinds of Config.Redact.
+func redactDetectors(kinds []string) ([]Detector, error) {
+	var detectors []Detector
+	for _, kind := range kinds {
+		if kind == "all" {
+			return DefaultDetectors(), nil
+		}
+		detector, ok := detectorsByKind[kind]
+		if !ok {
+			return nil, fmt.Errorf("%w: unknown redaction kind %q", ErrInvalidConfig, kind)
+		}
+		detectors = append(detectors, detector())
+	}
+	return detectors, nil
+}
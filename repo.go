@@ -0,0 +1,168 @@
+package chunkx
+
+import (
+	"bytes"
+	"context"
+	"fmt"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"strings"
+)
+
+// Metadata keys set on chunks of a cloned repository.
+const (
+	MetadataRepo     = "repo"      // URL of the repository
+	MetadataCommit   = "commit"    // Commit the chunk was read at
+	MetadataRepoPath = "repo_path" // Path of the file inside the repository
+)
+
+// Repo is a shallow clone of a remote git repository in a temporary
+// directory. Close removes the clone.
+type Repo struct {
+	URL    string // URL the repository was cloned from
+	Commit string // Commit that was checked out
+	Dir    string // Directory holding the clone
+}
+
+// CloneRepo fetches the given ref of the repository at url with a depth of
+// one and checks it out into a temporary directory. The ref may be a branch,
+// a tag, or, where the server allows it, a commit; an empty ref selects the
+// default branch. CloneRepo runs the git command, which must be installed,
+// and never prompts for credentials. A url or ref starting with "-" is
+// rejected, as git would read it as an option.
+func CloneRepo(ctx context.Context, url, ref string) (*Repo, error) {
+	if strings.HasPrefix(url, "-") {
+		return nil, fmt.Errorf("invalid repository URL %q", url)
+	}
+	if strings.HasPrefix(ref, "-") {
+		return nil, fmt.Errorf("invalid ref %q", ref)
+	}
+
+	dir, err := os.MkdirTemp("", "chunkx-repo-")
+	if err != nil {
+		return nil, err
+	}
+
+	if ref == "" {
+		ref = "HEAD"
+	}
+
+	steps := [][]string{
+		{"init", "--quiet"},
+		{"remote", "add", "--", "origin", url},
+		{"fetch", "--quiet", "--depth", "1", "--", "origin", ref},
+		{"checkout", "--quiet", "FETCH_HEAD"},
+	}
+	for _, args := range steps {
+		if _, err := runGit(ctx, dir, args...); err != nil {
+			os.RemoveAll(dir)
+			return nil, fmt.Errorf("failed to clone %s: %w", url, err)
+		}
+	}
+
+	commit, err := runGit(ctx, dir, "rev-parse", "HEAD")
+	if err != nil {
+		os.RemoveAll(dir)
+		return nil, fmt.Errorf("failed to clone %s: %w", url, err)
+	}
+
+	return &Repo{URL: url, Commit: strings.TrimSpace(commit), Dir: dir}, nil
+}
+
+// Files returns the paths of the files tracked in the repository, relative to
+// its directory and with forward slashes. Files excluded by .gitignore are not
+// tracked and so never listed.
+func (r *Repo) Files(ctx context.Context) ([]string, error) {
+	out, err := runGit(ctx, r.Dir, "ls-files", "-z")
+	if err != nil {
+		return nil, err
+	}
+
+	var files []string
+	for name := range strings.SplitSeq(out, "\x00") {
+		if name != "" {
+			files = append(files, name)
+		}
+	}
+	return files, nil
+}
+
+// Close removes the clone from disk.
+func (r *Repo) Close() error {
+	return os.RemoveAll(r.Dir)
+}
+
+// RunRepo chunks every file tracked in the repository. Hidden directories and
+// paths matching WithIgnore patterns are skipped as in RunDir.
+//
+// Results are reported with paths relative to the repository, and every chunk
+// records the repository URL, commit, and path in Metadata under MetadataRepo,
+// MetadataCommit, and MetadataRepoPath.
+func (p *ChunkPipeline) RunRepo(ctx context.Context, repo *Repo) <-chan FileResult {
+	sources := make(chan source, p.workers)
+
+	go func() {
+		defer close(sources)
+
+		var n int
+		files, err := repo.Files(ctx)
+		if err != nil {
+			p.queued(repo.URL, &n)
+			select {
+			case <-ctx.Done():
+			case sources <- source{path: repo.URL, err: err}:
+			}
+			return
+		}
+
+		for _, name := range files {
+			// Submodules and symlinks are tracked but aren't regular files
+			file := filepath.Join(repo.Dir, filepath.FromSlash(name))
+			if info, err := os.Lstat(file); err != nil || !info.Mode().IsRegular() {
+				continue
+			}
+			if skippedPath(p.ignore, name) {
+				continue
+			}
+
+			if !p.queued(name, &n) {
+				return
+			}
+			src := source{
+				path: name,
+				file: file,
+				metadata: map[string]string{
+					MetadataRepo:     repo.URL,
+					MetadataCommit:   repo.Commit,
+					MetadataRepoPath: name,
+				},
+			}
+			select {
+			case <-ctx.Done():
+				return
+			case sources <- src:
+			}
+		}
+	}()
+
+	return p.run(ctx, sources)
+}
+
+// runGit runs a git command in dir and returns its output.
+func runGit(ctx context.Context, dir string, args ...string) (string, error) {
+	cmd := exec.CommandContext(ctx, "git", args...)
+	cmd.Dir = dir
+	cmd.Env = append(os.Environ(), "GIT_TERMINAL_PROMPT=0")
+
+	var stdout, stderr bytes.Buffer
+	cmd.Stdout = &stdout
+	cmd.Stderr = &stderr
+	if err := cmd.Run(); err != nil {
+		if msg := strings.TrimSpace(stderr.String()); msg != "" {
+			return "", fmt.Errorf("git %s: %w: %s", args[0], err, msg)
+		}
+		return "", fmt.Errorf("git %s: %w", args[0], err)
+	}
+	return stdout.String(), nil
+}
@@ -0,0 +1,113 @@
+package chunkx
+
+import (
+	"context"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"testing"
+)
+
+// createGitRepo creates a git repository with a tagged commit and returns its
+// directory. The test is skipped if git is not installed.
+func createGitRepo(t *testing.T, files map[string]string) string {
+	t.Helper()
+
+	if _, err := exec.LookPath("git"); err != nil {
+		t.Skip("git is not installed")
+	}
+
+	dir := writeTestFiles(t, files)
+	for _, args := range [][]string{
+		{"init", "--quiet"},
+		{"add", "--all"},
+		{"-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "--quiet", "-m", "initial"},
+		{"tag", "v1"},
+	} {
+		if _, err := runGit(context.Background(), dir, args...); err != nil {
+			t.Fatalf("failed to create repository: %v", err)
+		}
+	}
+	return dir
+}
+
+func TestCloneRepo(t *testing.T) {
+	src := createGitRepo(t, map[string]string{
+		"main.go":       "package main\n\nfunc main() {}\n",
+		"docs/guide.md": "# Guide\n",
+		".gitignore":    "*.log\n",
+	})
+
+	// Untracked and ignored files never reach the clone
+	if err := os.WriteFile(filepath.Join(src, "debug.log"), []byte("noise\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+
+	ctx := context.Background()
+	repo, err := CloneRepo(ctx, src, "v1")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	defer repo.Close()
+
+	if len(repo.Commit) != 40 {
+		t.Errorf("expected a commit hash, got %q", repo.Commit)
+	}
+
+	var paths []string
+	pipeline := NewChunkPipeline(2, WithOrderedOutput())
+	for result := range pipeline.RunRepo(ctx, repo) {
+		if result.Err != nil {
+			t.Fatalf("unexpected error for %s: %v", result.Path, result.Err)
+		}
+		paths = append(paths, result.Path)
+
+		for _, chunk := range result.Chunks {
+			want := map[string]string{
+				MetadataRepo:     src,
+				MetadataCommit:   repo.Commit,
+				MetadataRepoPath: result.Path,
+			}
+			if !reflect.DeepEqual(chunk.Metadata, want) {
+				t.Errorf("unexpected metadata: %v", chunk.Metadata)
+			}
+		}
+	}
+
+	// .gitignore itself is tracked but lives at the top level, so only
+	// hidden directories are skipped
+	want := []string{".gitignore", "docs/guide.md", "main.go"}
+	if !reflect.DeepEqual(paths, want) {
+		t.Errorf("got paths %v, want %v", paths, want)
+	}
+
+	if err := repo.Close(); err != nil {
+		t.Fatal(err)
+	}
+	if _, err := os.Stat(repo.Dir); !os.IsNotExist(err) {
+		t.Errorf("expected the clone to be removed, got %v", err)
+	}
+}
+
+func TestCloneRepo_InvalidRef(t *testing.T) {
+	src := createGitRepo(t, map[string]string{"main.go": "package main\n"})
+
+	_, err := CloneRepo(context.Background(), src, "no-such-branch")
+	if err == nil || !strings.Contains(err.Error(), "failed to clone") {
+		t.Errorf("expected a clone error, got %v", err)
+	}
+}
+
+func TestCloneRepo_RejectsOptions(t *testing.T) {
+	tests := []struct{ url, ref string }{
+		{"--upload-pack=touch /tmp/pwned", ""},
+		{"https://example.com/repo.git", "--upload-pack=touch /tmp/pwned"},
+	}
+	for _, tt := range tests {
+		if _, err := CloneRepo(context.Background(), tt.url, tt.ref); err == nil || !strings.Contains(err.Error(), "invalid") {
+			t.Errorf("CloneRepo(%q, %q) = %v, want an invalid argument error", tt.url, tt.ref, err)
+		}
+	}
+}
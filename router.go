@@ -0,0 +1,157 @@
+package chunkx
+
+import (
+	"bytes"
+	"path/filepath"
+	"regexp"
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// DocumentType is the kind of document a file holds, which decides how a
+// DocumentRouter chunks it.
+type DocumentType string
+
+// Document types reported by DetectDocumentType.
+const (
+	DocumentCode     DocumentType = "code"     // Source code in a supported language
+	DocumentMarkdown DocumentType = "markdown" // Markdown prose
+	DocumentHTML     DocumentType = "html"     // HTML pages
+	DocumentNotebook DocumentType = "notebook" // Jupyter notebooks
+	DocumentPDF      DocumentType = "pdf"      // PDF documents
+	DocumentLog      DocumentType = "log"      // Log files of timestamped lines
+	DocumentText     DocumentType = "text"     // Any other text
+)
+
+// MetadataDocumentType is set to the document type of every chunk of a file
+// chunked with WithDocumentRouter.
+const MetadataDocumentType = "document_type"
+
+// documentSample is the number of bytes DetectDocumentType inspects.
+const documentSample = 4096
+
+// logLine matches a line starting with a timestamp, as most log formats do.
+var logLine = regexp.MustCompile(`^\[?(\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}|\d{2}:\d{2}:\d{2}|[A-Z][a-z]{2} [ \d]\d \d{2}:\d{2}:\d{2})`)
+
+// DetectDocumentType returns the type of the document at path from its
+// extension and the start of its content.
+func DetectDocumentType(path string, content []byte) DocumentType {
+	lang, _ := languages.DetectLanguage(path)
+	return detectDocumentType(lang.Name, path, content)
+}
+
+// detectDocumentType returns the type of the document at path, to be chunked
+// as lang.
+func detectDocumentType(lang languages.LanguageName, path string, content []byte) DocumentType {
+	switch lang {
+	case languages.Markdown:
+		return DocumentMarkdown
+	case languages.HTML:
+		return DocumentHTML
+	case languages.Generic, "":
+	default:
+		return DocumentCode
+	}
+
+	head := content[:min(len(content), documentSample)]
+	switch strings.ToLower(filepath.Ext(path)) {
+	case ".ipynb":
+		return DocumentNotebook
+	case ".pdf":
+		return DocumentPDF
+	case ".log":
+		return DocumentLog
+	}
+	switch {
+	case bytes.HasPrefix(head, []byte("%PDF-")):
+		return DocumentPDF
+	case looksLikeNotebook(head):
+		return DocumentNotebook
+	case looksLikeHTML(head):
+		return DocumentHTML
+	case looksLikeLog(head):
+		return DocumentLog
+	}
+	return DocumentText
+}
+
+// looksLikeNotebook reports whether head starts a JSON object with cells.
+func looksLikeNotebook(head []byte) bool {
+	return bytes.HasPrefix(bytes.TrimLeft(head, " \t\r\n"), []byte("{")) && bytes.Contains(head, []byte(`"cells"`))
+}
+
+// looksLikeHTML reports whether head starts an HTML page.
+func looksLikeHTML(head []byte) bool {
+	head = bytes.ToLower(bytes.TrimLeft(head, " \t\r\n"))
+	return bytes.HasPrefix(head, []byte("<!doctype html")) || bytes.HasPrefix(head, []byte("<html"))
+}
+
+// looksLikeLog reports whether most of the lines in head start with a
+// timestamp. The last line is left out, as it may be cut short.
+func looksLikeLog(head []byte) bool {
+	lines := bytes.Split(head, []byte("\n"))
+	if len(lines) > 1 && len(head) == documentSample {
+		lines = lines[:len(lines)-1]
+	}
+	var total, stamped int
+	for _, line := range lines {
+		if len(bytes.TrimSpace(line)) == 0 {
+			continue
+		}
+		total++
+		if logLine.Match(line) {
+			stamped++
+		}
+	}
+	return total >= 2 && stamped*4 >= total*3
+}
+
+// DocumentRouter chunks each file of a mixed corpus by its document type, as
+// DetectDocumentType reports it, so code, prose, pages, and notebooks can be
+// chunked in one run. Each type has its own options, applied after the
+// options of the call.
+type DocumentRouter struct {
+	routes map[DocumentType][]Option
+	skip   map[DocumentType]bool
+}
+
+// NewDocumentRouter returns a router that chunks Markdown and HTML with their
+// grammars, even when found by content alone, notebooks by their cells, and
+// other documents as the call's options say. PDF documents are skipped, as
+// they have no text to chunk without an extractor; routing them chunks them
+// under WithBinaryFiles.
+func NewDocumentRouter() *DocumentRouter {
+	return &DocumentRouter{
+		routes: map[DocumentType][]Option{
+			DocumentMarkdown: {WithLanguage(languages.Markdown)},
+			DocumentHTML:     {WithLanguage(languages.HTML)},
+			DocumentNotebook: {WithNotebookCells()},
+		},
+		skip: map[DocumentType]bool{DocumentPDF: true},
+	}
+}
+
+// Route adds options for documents of type docType, after those already set,
+// and chunks them if they were skipped. Options setting the language take
+// precedence over the language of the path.
+func (r *DocumentRouter) Route(docType DocumentType, opts ...Option) *DocumentRouter {
+	r.routes[docType] = append(r.routes[docType], opts...)
+	delete(r.skip, docType)
+	return r
+}
+
+// Skip leaves documents of type docType out of the results.
+func (r *DocumentRouter) Skip(docType DocumentType) *DocumentRouter {
+	r.skip[docType] = true
+	return r
+}
+
+// WithDocumentRouter chunks files by their document type with router. Files
+// are routed by their path and content, so it has no effect when chunking
+// content without a path. A nil router turns routing off.
+func WithDocumentRouter(router *DocumentRouter) Option {
+	return func(c *config) {
+		c.router = router
+	}
+}
@@ -0,0 +1,73 @@
+package chunkx
+
+import (
+	"context"
+	"path/filepath"
+	"testing"
+)
+
+func TestDetectDocumentType(t *testing.T) {
+	tests := []struct {
+		path    string
+		content string
+		want    DocumentType
+	}{
+		{"main.go", "package main\n", DocumentCode},
+		{"README.md", "# Title\n", DocumentMarkdown},
+		{"index.html", "<p>hi</p>", DocumentHTML},
+		{"page.txt", "<!DOCTYPE html>\n<html></html>\n", DocumentHTML},
+		{"analysis.ipynb", "{}", DocumentNotebook},
+		{"export.json", `{"cells": [], "nbformat": 4}`, DocumentNotebook},
+		{"report.pdf", "", DocumentPDF},
+		{"download", "%PDF-1.7\n", DocumentPDF},
+		{"server.log", "started\n", DocumentLog},
+		{"output.txt", "2024-05-01 12:00:01 INFO started\n2024-05-01 12:00:02 WARN slow\n[2024-05-01T12:00:03] done\n", DocumentLog},
+		{"notes.txt", "just some text\nover two lines\n", DocumentText},
+	}
+
+	for _, tt := range tests {
+		if got := DetectDocumentType(tt.path, []byte(tt.content)); got != tt.want {
+			t.Errorf("DetectDocumentType(%q) = %q, want %q", tt.path, got, tt.want)
+		}
+	}
+}
+
+func TestWithDocumentRouter(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"util.py":     "def util():\n    return 1\n",
+		"manual.pdf":  "%PDF-1.7\n",
+		"server.log":  "12:00:01 started\n12:00:02 listening\n12:00:03 stopped\n",
+		"README.md":   "# Title\n\nSome text.\n",
+		"drafts/a.md": "# Draft\n",
+	})
+
+	router := NewDocumentRouter().Route(DocumentLog, WithMaxSize(1)).Skip(DocumentMarkdown)
+	results := make(map[string][]Chunk)
+	for result := range NewChunkPipeline(2, WithDocumentRouter(router)).RunDir(context.Background(), dir) {
+		if result.Err != nil {
+			t.Fatalf("unexpected error for %s: %v", result.Path, result.Err)
+		}
+		rel, _ := filepath.Rel(dir, result.Path)
+		results[filepath.ToSlash(rel)] = result.Chunks
+	}
+
+	for _, path := range []string{"manual.pdf", "README.md", "drafts/a.md"} {
+		if len(results[path]) != 0 {
+			t.Errorf("expected %s to be skipped, got %+v", path, results[path])
+		}
+	}
+	if chunks := results["server.log"]; len(chunks) < 3 {
+		t.Errorf("expected a chunk per line of server.log, got %+v", chunks)
+	}
+	for path, want := range map[string]DocumentType{"util.py": DocumentCode, "server.log": DocumentLog} {
+		chunks := results[path]
+		if len(chunks) == 0 {
+			t.Fatalf("no chunks for %s", path)
+		}
+		for _, chunk := range chunks {
+			if got := chunk.Metadata[MetadataDocumentType]; got != string(want) {
+				t.Errorf("expected %s chunks of type %q, got %q", path, want, got)
+			}
+		}
+	}
+}
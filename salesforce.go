@@ -0,0 +1,90 @@
+package chunkx
+
+import (
+	"regexp"
+	"strings"
+)
+
+var (
+	// apexType matches the head of an Apex class, interface, enum, or
+	// trigger, such as "public with sharing class AccountService" or
+	// "trigger AccountTrigger on Account (before insert)", and its kind and
+	// name.
+	apexType = regexp.MustCompile(`(?i)\b(class|interface|enum|trigger)[ \t]+(\w+)`)
+	// apexMethod matches the head of an Apex method or constructor, such as
+	// "public static void run(List<Account> accounts)", and its name.
+	apexMethod = regexp.MustCompile(`^[^(]*?(\w+)[ \t]*\(`)
+)
+
+// apexSpans returns the classes, interfaces, enums, and triggers of the Apex
+// code, each with the comment and annotation lines right above it, recording
+// their name as MetadataBlock.
+func apexSpans(code string) []textSpan {
+	return apexBlocks(code, 0)
+}
+
+// apexNested returns the members of the Apex class, interface, or enum after
+// the line opening it, such as its methods, constructors, properties with
+// accessors, and inner classes. The statements of triggers and methods are
+// split by line instead.
+func apexNested(block string) []textSpan {
+	for offset := 0; offset < len(block); {
+		line, next := codeLine(block, offset)
+		if m := gradleBlock.FindStringSubmatch(line); m != nil {
+			if t := apexType.FindStringSubmatch(m[1]); t != nil && !strings.EqualFold(t[1], "trigger") {
+				return apexBlocks(block, next)
+			}
+			return nil
+		}
+		offset = next
+	}
+	return nil
+}
+
+// apexBlocks returns the blocks of the Apex code opened on the lines from
+// offset on. Types record their name as MetadataBlock, and methods and
+// properties record theirs as MetadataDefinition.
+func apexBlocks(code string, offset int) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := gradleBlock.FindStringSubmatchIndex(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+
+		closing := braceClosing(code, offset+m[3]+strings.IndexByte(line[m[3]:], '{'))
+		_, end := codeLine(code, closing)
+		head := line[m[2]:m[3]]
+		var metadata map[string]string
+		if t := apexType.FindStringSubmatch(head); t != nil {
+			metadata = map[string]string{MetadataBlock: t[2]}
+		} else if method := apexMethod.FindStringSubmatch(head); method != nil {
+			metadata = map[string]string{MetadataDefinition: method[1]}
+		} else {
+			// A property with accessors, such as "public Integer count"
+			fields := strings.Fields(head)
+			metadata = map[string]string{MetadataDefinition: fields[len(fields)-1]}
+		}
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "//", "/*", "*", "@"),
+			end:      end,
+			metadata: metadata,
+		})
+		offset = end
+	}
+	return spans
+}
+
+// salesforceSpans returns the elements of the Salesforce metadata code that
+// hold other elements, such as the fields, list views, and validation rules
+// of a custom object or the field permissions of a profile, recording their
+// name as MetadataBlock so consecutive ones that fit are chunked together.
+func salesforceSpans(code string) []textSpan {
+	roots := xmlElements(code, 0, len(code))
+	if len(roots) == 0 {
+		return nil
+	}
+	return xmlSpans(code, roots[0], nil)
+}
@@ -0,0 +1,71 @@
+package chunkx
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Apex(t *testing.T) {
+	code := `/**
+ * Services for accounts.
+ */
+public with sharing class AccountService {
+    private Integer calls = 0;
+
+    public Integer count {
+        get { return calls; }
+    }
+
+    // Renames the accounts
+    @TestVisible
+    public static void rename(List<Account> accounts, String name) {
+        for (Account a : accounts) {
+            a.Name = name;
+        }
+        update accounts;
+    }
+
+    public class Result {
+        public Boolean ok;
+    }
+}
+`
+	testChunkMetadata(t, code, languages.Apex, 30, []struct{ prefix, key, value string }{
+		{"/**\n * Services for accounts.\n */\npublic with sharing class AccountService {\n", MetadataBlock, "AccountService"},
+		{"    public Integer count {\n", MetadataDefinition, "count"},
+		{"    // Renames the accounts\n    @TestVisible\n    public static void rename(", MetadataDefinition, "rename"},
+		{"    // Renames the accounts\n", MetadataBlock, "AccountService"},
+		{"    public class Result {\n", MetadataBlock, "Result"},
+	})
+}
+
+func TestChunker_Salesforce(t *testing.T) {
+	code := `<?xml version="1.0" encoding="UTF-8"?>
+<CustomObject xmlns="http://soap.sforce.com/2006/04/metadata">
+    <label>Invoice</label>
+    <fields>
+        <fullName>Amount__c</fullName>
+        <type>Currency</type>
+    </fields>
+    <fields>
+        <fullName>Due__c</fullName>
+        <type>Date</type>
+    </fields>
+    <validationRules>
+        <fullName>Positive_Amount</fullName>
+        <errorConditionFormula>Amount__c &lt; 0</errorConditionFormula>
+    </validationRules>
+</CustomObject>
+`
+	chunks := testChunkMetadata(t, code, languages.Salesforce, 10, []struct{ prefix, key, value string }{
+		{"    <fields>\n        <fullName>Amount__c", MetadataBlock, "fields"},
+		{"    <validationRules>\n", MetadataBlock, "validationRules"},
+	})
+	for _, chunk := range chunks {
+		if strings.Contains(chunk.Content, "Amount__c</fullName>") && !strings.Contains(chunk.Content, "Due__c") {
+			t.Errorf("expected the fields chunked together, got %q", chunk.Content)
+		}
+	}
+}
@@ -0,0 +1,40 @@
+package chunkx
+
+import (
+	"hash/fnv"
+	"math"
+)
+
+// Sampling selects a subset of the files of a ChunkPipeline, so a huge corpus
+// can be previewed to estimate its cost and evaluate options before a full
+// run. Zero fields don't limit the sample.
+type Sampling struct {
+	Ratio            float64 // Share of the files chunked, between 0 and 1
+	MaxFiles         int     // Most files chunked
+	MaxChunksPerFile int     // Most chunks kept of each file, spread evenly over it
+}
+
+// WithSampling makes a ChunkPipeline chunk only a sample of its files.
+//
+// Files are picked by a hash of their path rather than at random, so the
+// same files are sampled on every run and their results can be compared
+// across options. Once MaxFiles files were picked, Run still receives the
+// remaining paths but neither reads nor chunks them, while RunDir, RunArchive,
+// RunObjects, and RunRepo stop listing files. A sampled run doesn't
+// describe the whole corpus, so it skips WithIndex and WithEventListener.
+// It has no effect on single-file chunking.
+func WithSampling(sampling Sampling) Option {
+	return func(c *config) {
+		c.sampling = sampling
+	}
+}
+
+// sampled reports whether the file at path is in the ratio of the sample.
+func (s Sampling) sampled(path string) bool {
+	if s.Ratio <= 0 || s.Ratio >= 1 {
+		return true
+	}
+	h := fnv.New64a()
+	h.Write([]byte(path))
+	return float64(h.Sum64()) < s.Ratio*math.MaxUint64
+}
@@ -0,0 +1,54 @@
+package chunkx
+
+import (
+	"context"
+	"fmt"
+	"path/filepath"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestWithSampling(t *testing.T) {
+	files := make(map[string]string)
+	for i := range 100 {
+		files[fmt.Sprintf("f%02d.txt", i)] = strings.Repeat("word\n", 10)
+	}
+	dir := writeTestFiles(t, files)
+
+	run := func(sampling Sampling) (paths []string, chunks []int) {
+		pipeline := NewChunkPipeline(4, WithLanguage(languages.Generic), WithMaxSize(1), WithOrderedOutput(), WithSampling(sampling))
+		for result := range pipeline.RunDir(context.Background(), dir) {
+			if result.Err != nil {
+				t.Fatalf("unexpected error for %s: %v", result.Path, result.Err)
+			}
+			paths = append(paths, filepath.Base(result.Path))
+			chunks = append(chunks, len(result.Chunks))
+		}
+		return paths, chunks
+	}
+
+	sampled, _ := run(Sampling{Ratio: 0.5})
+	if len(sampled) < 30 || len(sampled) > 70 {
+		t.Errorf("expected about half of the files sampled, got %d", len(sampled))
+	}
+	if again, _ := run(Sampling{Ratio: 0.5}); !reflect.DeepEqual(again, sampled) {
+		t.Errorf("expected the same files sampled again, got %v, then %v", sampled, again)
+	}
+
+	if paths, _ := run(Sampling{Ratio: 0.5, MaxFiles: 5}); !reflect.DeepEqual(paths, sampled[:5]) {
+		t.Errorf("expected the first 5 sampled files, got %v", paths)
+	}
+
+	paths, chunks := run(Sampling{MaxChunksPerFile: 3})
+	if len(paths) != 100 {
+		t.Errorf("expected every file chunked, got %d", len(paths))
+	}
+	for i, n := range chunks {
+		if n != 3 {
+			t.Errorf("expected 3 chunks of %s, got %d", paths[i], n)
+		}
+	}
+}
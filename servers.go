@@ -0,0 +1,246 @@
+package chunkx
+
+import (
+	"path/filepath"
+	"regexp"
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+var (
+	// confBlock matches a line of an nginx config or a Caddyfile opening a
+	// block, such as "location /api/ {" or "example.com {", and the selector
+	// before its brace, which is empty for the global options of a Caddyfile.
+	confBlock = regexp.MustCompile(`^[ \t]*([^#{};\s][^#{};\n]*?)?[ \t]*\{[ \t]*(?:#.*)?\r?$`)
+	// nginxServerName matches the server_name directive of an nginx server
+	// block, and its first name.
+	nginxServerName = regexp.MustCompile(`(?m)^[ \t]*server_name[ \t]+([^;\s]+)`)
+	// apacheSection matches the line of an Apache config opening a section,
+	// such as "<VirtualHost *:443>" or `<Directory "/var/www">`, and its name
+	// and arguments.
+	apacheSection = regexp.MustCompile(`^[ \t]*<([A-Za-z]\w*)(?:[ \t]+([^>]*?))?[ \t]*>`)
+	// apacheEnd matches the line of an Apache config closing a section, and
+	// its name.
+	apacheEnd = regexp.MustCompile(`^[ \t]*</([A-Za-z]\w*)[ \t]*>`)
+	// robotsUserAgent matches a User-agent line of a robots.txt file, and the
+	// agent it names.
+	robotsUserAgent = regexp.MustCompile(`(?i)^[ \t]*user-agent[ \t]*:[ \t]*([^#\s]*)`)
+	// nginxMarker and apacheMarker match a line only an nginx or an Apache
+	// config starts so, telling the .conf files of either apart.
+	nginxMarker  = regexp.MustCompile(`(?m)^[ \t]*(?:http|events|server|upstream|location)\b[^;{}\n]*\{`)
+	apacheMarker = regexp.MustCompile(`(?im)^[ \t]*<(?:VirtualHost|Directory|IfModule|Location|Files)\b`)
+)
+
+// confLanguage returns the language of the .conf file at path, with the given
+// content, if it is an nginx or an Apache config, or "" if it is neither.
+func confLanguage(path, content string) languages.LanguageName {
+	if filepath.Ext(path) != ".conf" {
+		return ""
+	}
+	head := content[:min(len(content), documentSample)]
+	switch {
+	case apacheMarker.MatchString(head):
+		return languages.Apache
+	case nginxMarker.MatchString(head):
+		return languages.Nginx
+	}
+	return ""
+}
+
+// confSpans returns the blocks of the nginx config or Caddyfile code, such as
+// the http, server, and upstream blocks of nginx or the site blocks and
+// snippets of a Caddyfile, each with the comment lines right above it,
+// recording their selector as MetadataBlock, such as "location /api/". Server
+// blocks of nginx add their first server name, such as "server example.com",
+// and the global options block of a Caddyfile records "global".
+func confSpans(code string) []textSpan {
+	return confBlocks(code, 0)
+}
+
+// confNested returns the blocks within the block of an nginx config or a
+// Caddyfile after the line opening it, such as the location blocks of a
+// server or the handle blocks of a site.
+func confNested(block string) []textSpan {
+	for offset := 0; offset < len(block); {
+		line, next := codeLine(block, offset)
+		if confBlock.MatchString(line) {
+			return confBlocks(block, next)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// confBlocks returns the blocks of the nginx config or Caddyfile code opened
+// on the lines from offset on.
+func confBlocks(code string, offset int) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := confBlock.FindStringSubmatch(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+
+		closing := confClosing(code, offset+strings.LastIndexByte(strings.SplitN(line, "#", 2)[0], '{'))
+		_, end := codeLine(code, closing)
+		selector := strings.Join(strings.Fields(m[1]), " ")
+		switch {
+		case selector == "":
+			selector = "global"
+		case selector == "server":
+			if name := nginxServerName.FindStringSubmatch(code[next:end]); name != nil {
+				selector += " " + name[1]
+			}
+		}
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "#"),
+			end:      end,
+			metadata: map[string]string{MetadataBlock: selector},
+		})
+		offset = end
+	}
+	return spans
+}
+
+// confClosing returns the offset after the brace closing the one at open in
+// the nginx config or Caddyfile code, skipping quoted strings and comments,
+// or len(code) if it isn't closed.
+func confClosing(code string, open int) int {
+	depth := 0
+	for i := open; i < len(code); i++ {
+		switch code[i] {
+		case '{':
+			depth++
+		case '}':
+			if depth--; depth == 0 {
+				return i + 1
+			}
+		case '"', '\'':
+			for quote := code[i]; i+1 < len(code) && code[i+1] != quote && code[i+1] != '\n'; i++ {
+				if code[i+1] == '\\' {
+					i++
+				}
+			}
+			i++
+		case '#':
+			for i < len(code) && code[i] != '\n' {
+				i++
+			}
+		}
+	}
+	return len(code)
+}
+
+// apacheSpans returns the sections of the Apache config code, such as its
+// VirtualHost, Directory, and IfModule sections, each up to the line closing
+// it and with the comment lines right above it, recording its name and
+// arguments as MetadataBlock, such as "VirtualHost *:443".
+func apacheSpans(code string) []textSpan {
+	return apacheSections(code, 0)
+}
+
+// apacheNested returns the sections within the section of an Apache config
+// after the line opening it, such as the Directory sections of a
+// VirtualHost.
+func apacheNested(section string) []textSpan {
+	for offset := 0; offset < len(section); {
+		line, next := codeLine(section, offset)
+		if apacheSection.MatchString(line) {
+			return apacheSections(section, next)
+		}
+		offset = next
+	}
+	return nil
+}
+
+// apacheSections returns the sections of the Apache config code opened on the
+// lines from offset on.
+func apacheSections(code string, offset int) []textSpan {
+	var spans []textSpan
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		m := apacheSection.FindStringSubmatch(line)
+		if m == nil {
+			offset = next
+			continue
+		}
+		end := apacheSectionEnd(code, next, m[1])
+		if end < 0 {
+			offset = next
+			continue
+		}
+
+		selector := m[1]
+		if args := strings.Join(strings.Fields(m[2]), " "); args != "" {
+			selector += " " + args
+		}
+		spans = append(spans, textSpan{
+			start:    leadingComments(code, offset, spanEnd(spans), "#"),
+			end:      end,
+			metadata: map[string]string{MetadataBlock: selector},
+		})
+		offset = end
+	}
+	return spans
+}
+
+// apacheSectionEnd returns the offset after the line closing the Apache
+// section name whose lines after the one opening it start at offset in code,
+// skipping the sections of the same name nested within it, or -1 if it isn't
+// closed.
+func apacheSectionEnd(code string, offset int, name string) int {
+	depth := 1
+	for offset < len(code) {
+		line, next := codeLine(code, offset)
+		if m := apacheSection.FindStringSubmatch(line); m != nil && strings.EqualFold(m[1], name) {
+			depth++
+		} else if m := apacheEnd.FindStringSubmatch(line); m != nil && strings.EqualFold(m[1], name) {
+			if depth--; depth == 0 {
+				return next
+			}
+		}
+		offset = next
+	}
+	return -1
+}
+
+// robotsSpans returns the groups of the robots.txt code, each from its
+// User-agent lines to the next group, with the comment lines right above it,
+// recording the agents it applies to as MetadataBlock, such as "Googlebot,
+// Bingbot". Sitemap lines and other lines outside a group are chunked as is.
+func robotsSpans(code string) []textSpan {
+	var spans []textSpan
+	rules := false // Whether the last group has rules, so a User-agent line starts the next
+	for offset := 0; offset < len(code); {
+		line, next := codeLine(code, offset)
+		text, _, _ := strings.Cut(line, "#")
+		text = strings.TrimSpace(text)
+		n := len(spans)
+		switch m := robotsUserAgent.FindStringSubmatch(line); {
+		case m != nil && (n == 0 || rules || spans[n-1].end != offset):
+			// The comment lines above the line, which the last group took, go
+			// with this one
+			from := 0
+			if n > 0 {
+				from = spans[n-1].start
+			}
+			start := leadingComments(code, offset, from, "#")
+			if n > 0 {
+				spans[n-1].end = min(spans[n-1].end, start)
+			}
+			spans = append(spans, textSpan{start: start, end: next, metadata: map[string]string{MetadataBlock: m[1]}})
+			rules = false
+		case m != nil:
+			spans[n-1].metadata[MetadataBlock] += ", " + m[1]
+			spans[n-1].end = next
+		case n > 0 && spans[n-1].end == offset && !strings.HasPrefix(strings.ToLower(text), "sitemap:"):
+			spans[n-1].end = next
+			rules = rules || text != ""
+		}
+		offset = next
+	}
+	return spans
+}
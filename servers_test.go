@@ -0,0 +1,115 @@
+package chunkx
+
+import (
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunker_Nginx(t *testing.T) {
+	code := `events {
+    worker_connections 1024;
+}
+
+http {
+    # Main site
+    server {
+        listen 443 ssl;
+        server_name example.com www.example.com;
+
+        location /api/ {
+            proxy_pass http://backend;
+            proxy_set_header Host $host;
+        }
+
+        location ~ \.(png|jpg)$ {
+            root /var/www/static; # "{" is no block
+            expires 30d;
+        }
+    }
+}
+`
+	testChunkMetadata(t, code, languages.Nginx, 14, []struct{ prefix, key, value string }{
+		{"events {\n", MetadataBlock, "events"},
+		{"    # Main site\n    server {\n", MetadataBlock, "server example.com"},
+		{"        location /api/ {\n", MetadataBlock, "location /api/"},
+		{"        location ~ \\.(png|jpg)$ {\n", MetadataBlock, `location ~ \.(png|jpg)$`},
+	})
+}
+
+func TestChunker_Apache(t *testing.T) {
+	code := `ServerRoot "/etc/httpd"
+
+<VirtualHost *:443>
+    ServerName example.com
+    DocumentRoot "/var/www/html"
+
+    # Static files
+    <Directory "/var/www/html">
+        Options -Indexes
+        AllowOverride None
+    </Directory>
+
+    <IfModule mod_ssl.c>
+        SSLEngine on
+    </IfModule>
+</VirtualHost>
+`
+	testChunkMetadata(t, code, languages.Apache, 10, []struct{ prefix, key, value string }{
+		{"<VirtualHost *:443>\n", MetadataBlock, "VirtualHost *:443"},
+		{"    # Static files\n    <Directory", MetadataBlock, `Directory "/var/www/html"`},
+		{"    <IfModule mod_ssl.c>\n", MetadataBlock, "IfModule mod_ssl.c"},
+	})
+}
+
+func TestChunker_Caddy(t *testing.T) {
+	code := `{
+	email admin@example.com
+}
+
+example.com {
+	handle /api/* {
+		reverse_proxy localhost:8080
+	}
+	file_server
+}
+`
+	testChunkMetadata(t, code, languages.Caddy, 100, []struct{ prefix, key, value string }{
+		{"{\n\temail", MetadataBlock, "global"},
+		{"example.com {\n", MetadataBlock, "example.com"},
+	})
+}
+
+func TestChunker_Robots(t *testing.T) {
+	code := `User-agent: Googlebot
+User-agent: Bingbot
+Disallow: /private/
+
+# Everyone else
+User-agent: *
+Allow: /
+
+Sitemap: https://example.com/sitemap.xml
+`
+	testChunkMetadata(t, code, languages.Robots, 100, []struct{ prefix, key, value string }{
+		{"User-agent: Googlebot\nUser-agent: Bingbot\nDisallow: /private/\n", MetadataBlock, "Googlebot, Bingbot"},
+		{"# Everyone else\nUser-agent: *\n", MetadataBlock, "*"},
+		{"Sitemap:", MetadataBlock, ""},
+	})
+}
+
+func TestConfLanguage(t *testing.T) {
+	for _, tt := range []struct {
+		path, content string
+		want          languages.LanguageName
+	}{
+		{"conf.d/site.conf", "server {\n    listen 80;\n}\n", languages.Nginx},
+		{"sites/site.conf", "<VirtualHost *:80>\n</VirtualHost>\n", languages.Apache},
+		{"etc/resolv.conf", "nameserver 1.1.1.1\n", ""},
+		{"site.txt", "server {\n}\n", ""},
+	} {
+		if got := confLanguage(tt.path, tt.content); got != tt.want {
+			t.Errorf("confLanguage(%q) = %q, want %q", tt.path, got, tt.want)
+		}
+	}
+}
@@ -0,0 +1,278 @@
+package sinks
+
+import (
+	"bufio"
+	"context"
+	"crypto/rand"
+	"crypto/tls"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"net/url"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+)
+
+// DefaultAckTimeout is how long NATS waits for a batch to be acknowledged
+// when the context has no deadline.
+const DefaultAckTimeout = 10 * time.Second
+
+// NATS publishes records to a NATS JetStream subject, so a fleet of
+// embedding workers can consume them from a durable stream.
+//
+// Each record is published as its JSON message, with the record's payload
+// and vector, and a batch succeeds once JetStream has acknowledged every
+// message as stored. A stream must capture the subject. Delivery is at least
+// once: when a batch fails part way, retrying it publishes the messages that
+// were stored again, so consumers should deduplicate by the id field.
+//
+// The connection is kept open between batches and redialed after an error.
+// NATS is safe for concurrent use; batches are sent one at a time.
+type NATS struct {
+	URL        string        // Server URL, such as nats://localhost:4222, with optional user:password
+	Subject    string        // Subject the records are published to
+	Token      string        // Authentication token, if required
+	TLS        *tls.Config   // Connects over TLS, if set
+	AckTimeout time.Duration // Defaults to DefaultAckTimeout
+
+	mu    sync.Mutex
+	conn  net.Conn
+	r     *bufio.Reader
+	inbox string // Prefix of the reply subjects acknowledgements are sent to
+}
+
+// natsAck is a JetStream publish acknowledgement.
+type natsAck struct {
+	Stream string `json:"stream"`
+	Error  *struct {
+		Code        int    `json:"code"`
+		Description string `json:"description"`
+	} `json:"error"`
+}
+
+// Upsert implements ChunkSink.
+func (n *NATS) Upsert(ctx context.Context, records []Record) error {
+	if len(records) == 0 {
+		return nil
+	}
+
+	n.mu.Lock()
+	defer n.mu.Unlock()
+
+	if n.conn == nil {
+		if err := n.dial(ctx); err != nil {
+			return fmt.Errorf("nats: %w", err)
+		}
+	}
+
+	err := n.publish(ctx, records)
+	if err != nil {
+		n.close()
+		return fmt.Errorf("nats: %w", err)
+	}
+	return nil
+}
+
+// Close closes the connection, if one is open.
+func (n *NATS) Close() error {
+	n.mu.Lock()
+	defer n.mu.Unlock()
+	return n.close()
+}
+
+// publish publishes every record with its own reply subject and waits until
+// all of them are acknowledged.
+func (n *NATS) publish(ctx context.Context, records []Record) error {
+	_ = n.conn.SetDeadline(n.deadline(ctx))
+
+	var buf []byte
+	for i, r := range records {
+		msg, err := r.message()
+		if err != nil {
+			return &PermanentError{Err: err}
+		}
+		buf = fmt.Appendf(buf, "PUB %s %s%d %d\r\n", n.Subject, n.inbox, i, len(msg))
+		buf = append(append(buf, msg...), "\r\n"...)
+	}
+	if _, err := n.conn.Write(buf); err != nil {
+		return err
+	}
+
+	acked := make([]bool, len(records))
+	pending := len(records)
+	for pending > 0 {
+		reply, payload, err := n.next()
+		if err != nil {
+			return err
+		}
+		i, err := strconv.Atoi(strings.TrimPrefix(reply, n.inbox))
+		if err != nil || !strings.HasPrefix(reply, n.inbox) || i < 0 || i >= len(records) || acked[i] {
+			continue
+		}
+
+		var ack natsAck
+		if err := json.Unmarshal(payload, &ack); err != nil {
+			return fmt.Errorf("invalid acknowledgement %q", payload)
+		}
+		if ack.Error != nil {
+			return fmt.Errorf("jetstream: %s (%d)", ack.Error.Description, ack.Error.Code)
+		}
+		acked[i] = true
+		pending--
+	}
+	return nil
+}
+
+// next reads protocol messages until the next MSG, answering pings, and
+// returns its subject and payload.
+func (n *NATS) next() (string, []byte, error) {
+	for {
+		line, err := n.r.ReadString('\n')
+		if err != nil {
+			return "", nil, err
+		}
+		line = strings.TrimSuffix(line, "\r\n")
+		op, args, _ := strings.Cut(line, " ")
+
+		switch strings.ToUpper(op) {
+		case "MSG":
+			// MSG <subject> <sid> [reply-to] <size>
+			fields := strings.Fields(args)
+			if len(fields) < 3 {
+				return "", nil, fmt.Errorf("invalid message %q", line)
+			}
+			size, err := strconv.Atoi(fields[len(fields)-1])
+			if err != nil {
+				return "", nil, fmt.Errorf("invalid message %q", line)
+			}
+			payload := make([]byte, size+2)
+			if _, err := io.ReadFull(n.r, payload); err != nil {
+				return "", nil, err
+			}
+			return fields[0], payload[:size], nil
+		case "PING":
+			if _, err := n.conn.Write([]byte("PONG\r\n")); err != nil {
+				return "", nil, err
+			}
+		case "-ERR":
+			return "", nil, errors.New(strings.Trim(args, "'"))
+		}
+	}
+}
+
+// dial connects to the server, authenticates, and subscribes to the inbox
+// acknowledgements are sent to.
+func (n *NATS) dial(ctx context.Context) error {
+	u, err := url.Parse(n.URL)
+	if err != nil {
+		return &PermanentError{Err: err}
+	}
+	host := u.Host
+	if u.Port() == "" {
+		host = net.JoinHostPort(u.Hostname(), "4222")
+	}
+
+	var dialer net.Dialer
+	conn, err := dialer.DialContext(ctx, "tcp", host)
+	if err != nil {
+		return err
+	}
+	_ = conn.SetDeadline(n.deadline(ctx))
+	r := bufio.NewReader(conn)
+
+	// The server greets with INFO before anything else, and upgrades to TLS
+	// afterwards
+	line, err := r.ReadString('\n')
+	if err != nil {
+		conn.Close()
+		return err
+	}
+	if !strings.HasPrefix(strings.ToUpper(line), "INFO ") {
+		conn.Close()
+		return fmt.Errorf("unexpected greeting %q", strings.TrimSpace(line))
+	}
+	if n.TLS != nil || u.Scheme == "tls" {
+		config := n.TLS
+		if config == nil {
+			config = &tls.Config{ServerName: u.Hostname()}
+		}
+		tlsConn := tls.Client(conn, config)
+		if err := tlsConn.HandshakeContext(ctx); err != nil {
+			conn.Close()
+			return err
+		}
+		conn, r = tlsConn, bufio.NewReader(tlsConn)
+	}
+
+	options := map[string]any{"verbose": false, "pedantic": false, "lang": "go", "name": "chunkx"}
+	if u.User != nil {
+		options["user"] = u.User.Username()
+		options["pass"], _ = u.User.Password()
+	}
+	if n.Token != "" {
+		options["auth_token"] = n.Token
+	}
+	connect, err := json.Marshal(options)
+	if err != nil {
+		conn.Close()
+		return err
+	}
+
+	id := make([]byte, 8)
+	_, _ = rand.Read(id)
+	inbox := "_INBOX." + hex.EncodeToString(id) + "."
+
+	// The PING makes the server report a rejected CONNECT before the first
+	// batch goes out
+	handshake := fmt.Sprintf("CONNECT %s\r\nSUB %s* 1\r\nPING\r\n", connect, inbox)
+	if _, err := conn.Write([]byte(handshake)); err != nil {
+		conn.Close()
+		return err
+	}
+	for {
+		line, err := r.ReadString('\n')
+		if err != nil {
+			conn.Close()
+			return err
+		}
+		op, args, _ := strings.Cut(strings.TrimSuffix(line, "\r\n"), " ")
+		if strings.EqualFold(op, "PONG") {
+			break
+		}
+		if strings.EqualFold(op, "-ERR") {
+			conn.Close()
+			return &PermanentError{Err: errors.New(strings.Trim(args, "'"))}
+		}
+	}
+
+	n.conn, n.r, n.inbox = conn, r, inbox
+	return nil
+}
+
+// deadline returns the deadline of ctx, or the acknowledgement timeout from
+// now if it has none.
+func (n *NATS) deadline(ctx context.Context) time.Time {
+	if deadline, ok := ctx.Deadline(); ok {
+		return deadline
+	}
+	timeout := n.AckTimeout
+	if timeout <= 0 {
+		timeout = DefaultAckTimeout
+	}
+	return time.Now().Add(timeout)
+}
+
+// close closes the connection, if one is open.
+func (n *NATS) close() error {
+	if n.conn == nil {
+		return nil
+	}
+	err := n.conn.Close()
+	n.conn, n.r = nil, nil
+	return err
+}
@@ -0,0 +1,107 @@
+package sinks
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"fmt"
+	"io"
+	"net"
+	"strconv"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+// fakeJetStream serves the NATS protocol on a local listener, acknowledging
+// every published message with ack and reporting the CONNECT options and
+// payloads it receives.
+func fakeJetStream(t *testing.T, ack string) (url string, connect <-chan map[string]any, published <-chan []byte) {
+	t.Helper()
+
+	ln, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	t.Cleanup(func() { ln.Close() })
+
+	connects := make(chan map[string]any, 1)
+	payloads := make(chan []byte, 16)
+	go func() {
+		conn, err := ln.Accept()
+		if err != nil {
+			return
+		}
+		defer conn.Close()
+		fmt.Fprint(conn, "INFO {\"server_id\":\"test\"}\r\n")
+
+		r := bufio.NewReader(conn)
+		for {
+			line, err := r.ReadString('\n')
+			if err != nil {
+				return
+			}
+			op, args, _ := strings.Cut(strings.TrimSuffix(line, "\r\n"), " ")
+			switch op {
+			case "CONNECT":
+				var options map[string]any
+				_ = json.Unmarshal([]byte(args), &options)
+				connects <- options
+			case "PING":
+				fmt.Fprint(conn, "PONG\r\n")
+			case "PUB":
+				// PUB <subject> <reply-to> <size>
+				fields := strings.Fields(args)
+				size, _ := strconv.Atoi(fields[2])
+				payload := make([]byte, size+2)
+				if _, err := io.ReadFull(r, payload); err != nil {
+					return
+				}
+				payloads <- payload[:size]
+				fmt.Fprintf(conn, "PING\r\nMSG %s 1 %d\r\n%s\r\n", fields[1], len(ack), ack)
+			}
+		}
+	}()
+	return "nats://alice:secret@" + ln.Addr().String(), connects, payloads
+}
+
+func TestNATS_Upsert(t *testing.T) {
+	url, connect, published := fakeJetStream(t, `{"stream":"CHUNKS","seq":1}`)
+
+	records := []Record{
+		NewRecord("a.go", chunkx.Chunk{Content: "package a"}),
+		NewRecord("b.go", chunkx.Chunk{Content: "package b"}),
+	}
+	n := &NATS{URL: url, Subject: "chunks.new"}
+	defer n.Close()
+	if err := n.Upsert(context.Background(), records); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if options := <-connect; options["user"] != "alice" || options["pass"] != "secret" {
+		t.Errorf("unexpected CONNECT options: %v", options)
+	}
+	for i, r := range records {
+		var msg map[string]any
+		if err := json.Unmarshal(<-published, &msg); err != nil {
+			t.Fatalf("invalid message: %v", err)
+		}
+		if msg["id"] != r.ID || msg["path"] != r.Path {
+			t.Errorf("message %d = %v", i, msg)
+		}
+	}
+}
+
+func TestNATS_AckError(t *testing.T) {
+	url, _, _ := fakeJetStream(t, `{"error":{"code":503,"description":"no responders"}}`)
+
+	n := &NATS{URL: url, Subject: "chunks.new"}
+	err := n.Upsert(context.Background(), []Record{NewRecord("a", chunkx.Chunk{Content: "x"})})
+	if err == nil || !strings.Contains(err.Error(), "no responders") {
+		t.Errorf("err = %v, want the JetStream error", err)
+	}
+	if n.conn != nil {
+		t.Error("expected the connection to be closed after an error")
+	}
+}
@@ -0,0 +1,155 @@
+package sinks
+
+import (
+	"context"
+	"database/sql"
+	"encoding/json"
+	"fmt"
+	"strconv"
+	"strings"
+)
+
+// postgresColumns are the columns written by Postgres, in order.
+var postgresColumns = []string{
+	"id", "path", "language", "start_line", "end_line",
+	"start_byte", "end_byte", "metadata", "content", "embedding",
+}
+
+// postgresMaxParams is the most bind parameters Postgres accepts in one
+// statement.
+const postgresMaxParams = 65535
+
+// Postgres upserts records into a Postgres table, storing vectors with the
+// pgvector extension. The caller opens DB with a driver of their choice, such
+// as pgx. The table must have this shape, with the vector size matching the
+// embedding model:
+//
+//	CREATE TABLE chunks (
+//		id         text PRIMARY KEY,
+//		path       text NOT NULL,
+//		language   text NOT NULL,
+//		start_line integer NOT NULL,
+//		end_line   integer NOT NULL,
+//		start_byte bigint NOT NULL,
+//		end_byte   bigint NOT NULL,
+//		metadata   jsonb,
+//		content    text NOT NULL,
+//		embedding  vector(1536)
+//	);
+//
+// Each batch is written with a single INSERT ... ON CONFLICT statement, or
+// several for batches beyond Postgres's limit of 65535 bind parameters, that
+// is 6553 records.
+type Postgres struct {
+	DB    *sql.DB
+	Table string // Table name, optionally schema-qualified; defaults to chunks
+}
+
+// Upsert implements ChunkSink.
+func (p *Postgres) Upsert(ctx context.Context, records []Record) error {
+	if len(records) == 0 {
+		return nil
+	}
+
+	table := p.Table
+	if table == "" {
+		table = "chunks"
+	}
+
+	per := postgresMaxParams / len(postgresColumns)
+	for len(records) > 0 {
+		n := min(len(records), per)
+		if err := p.insert(ctx, table, records[:n]); err != nil {
+			return err
+		}
+		records = records[n:]
+	}
+	return nil
+}
+
+// insert upserts records into table with a single statement.
+func (p *Postgres) insert(ctx context.Context, table string, records []Record) error {
+	var query strings.Builder
+	fmt.Fprintf(&query, "INSERT INTO %s (%s) VALUES ", quoteIdentifier(table), strings.Join(postgresColumns, ", "))
+
+	args := make([]any, 0, len(records)*len(postgresColumns))
+	for i, r := range records {
+		metadata, err := postgresMetadata(r)
+		if err != nil {
+			return &PermanentError{Err: err}
+		}
+
+		if i > 0 {
+			query.WriteString(", ")
+		}
+		query.WriteString("(")
+		for j := range postgresColumns {
+			if j > 0 {
+				query.WriteString(", ")
+			}
+			fmt.Fprintf(&query, "$%d", len(args)+j+1)
+			switch postgresColumns[j] {
+			case "metadata":
+				query.WriteString("::jsonb")
+			case "embedding":
+				query.WriteString("::vector")
+			}
+		}
+		query.WriteString(")")
+
+		args = append(args, r.ID, r.Path, string(r.Chunk.Language),
+			r.Chunk.StartLine, r.Chunk.EndLine, r.Chunk.StartByte, r.Chunk.EndByte,
+			metadata, r.Chunk.Content, postgresVector(r.Vector))
+	}
+
+	query.WriteString(" ON CONFLICT (id) DO UPDATE SET ")
+	for i, column := range postgresColumns[1:] {
+		if i > 0 {
+			query.WriteString(", ")
+		}
+		fmt.Fprintf(&query, "%s = EXCLUDED.%s", column, column)
+	}
+
+	if _, err := p.DB.ExecContext(ctx, query.String(), args...); err != nil {
+		return fmt.Errorf("postgres: %w", err)
+	}
+	return nil
+}
+
+// postgresMetadata encodes the metadata of a record as JSON, or NULL when
+// there is none.
+func postgresMetadata(r Record) (any, error) {
+	if len(r.Chunk.Metadata) == 0 {
+		return nil, nil
+	}
+	data, err := json.Marshal(r.Chunk.Metadata)
+	if err != nil {
+		return nil, err
+	}
+	return string(data), nil
+}
+
+// postgresVector encodes a vector in pgvector's text format, or NULL when
+// there is none.
+func postgresVector(vector []float32) any {
+	if vector == nil {
+		return nil
+	}
+	b := []byte{'['}
+	for i, v := range vector {
+		if i > 0 {
+			b = append(b, ',')
+		}
+		b = strconv.AppendFloat(b, float64(v), 'g', -1, 32)
+	}
+	return string(append(b, ']'))
+}
+
+// quoteIdentifier quotes each part of a possibly schema-qualified name.
+func quoteIdentifier(name string) string {
+	parts := strings.Split(name, ".")
+	for i, part := range parts {
+		parts[i] = `"` + strings.ReplaceAll(part, `"`, `""`) + `"`
+	}
+	return strings.Join(parts, ".")
+}
@@ -0,0 +1,133 @@
+package sinks
+
+import (
+	"context"
+	"database/sql"
+	"database/sql/driver"
+	"errors"
+	"strconv"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+// execRecorder is a database/sql driver that records executed statements.
+type execRecorder struct {
+	query string
+	args  []driver.Value
+	execs int
+}
+
+func (d *execRecorder) Open(string) (driver.Conn, error) {
+	return d, nil
+}
+
+func (d *execRecorder) Prepare(query string) (driver.Stmt, error) {
+	return &recordedStmt{d: d, query: query}, nil
+}
+
+func (d *execRecorder) Close() error {
+	return nil
+}
+
+func (d *execRecorder) Begin() (driver.Tx, error) {
+	return nil, errors.New("not supported")
+}
+
+// recordedStmt is a statement of execRecorder.
+type recordedStmt struct {
+	d     *execRecorder
+	query string
+}
+
+func (s *recordedStmt) Close() error {
+	return nil
+}
+
+func (s *recordedStmt) NumInput() int {
+	return -1
+}
+
+func (s *recordedStmt) Exec(args []driver.Value) (driver.Result, error) {
+	s.d.query, s.d.args = s.query, args
+	s.d.execs++
+	return driver.RowsAffected(0), nil
+}
+
+func (s *recordedStmt) Query([]driver.Value) (driver.Rows, error) {
+	return nil, errors.New("not supported")
+}
+
+func TestPostgres_Upsert(t *testing.T) {
+	recorder := &execRecorder{}
+	sql.Register("sinks-recorder", recorder)
+	db, err := sql.Open("sinks-recorder", "")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	defer db.Close()
+
+	a := NewRecord("a.go", chunkx.Chunk{Content: "package a", Language: "go", StartLine: 1, EndLine: 2, EndByte: 9})
+	a.Vector = []float32{0.25, -1}
+	b := NewRecord("b.go", chunkx.Chunk{Content: "package b", Metadata: map[string]string{"k": "v"}})
+
+	p := &Postgres{DB: db, Table: `public.my"chunks`}
+	if err := p.Upsert(context.Background(), []Record{a, b}); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	for _, want := range []string{
+		`INSERT INTO "public"."my""chunks" (id, path, language,`,
+		`($1, $2, $3, $4, $5, $6, $7, $8::jsonb, $9, $10::vector), ($11,`,
+		`$20::vector) ON CONFLICT (id) DO UPDATE SET path = EXCLUDED.path,`,
+		`embedding = EXCLUDED.embedding`,
+	} {
+		if !strings.Contains(recorder.query, want) {
+			t.Errorf("query missing %q:\n%s", want, recorder.query)
+		}
+	}
+
+	if len(recorder.args) != 20 {
+		t.Fatalf("expected 20 arguments, got %d", len(recorder.args))
+	}
+	if recorder.args[0] != a.ID || recorder.args[8] != "package a" {
+		t.Errorf("unexpected arguments for first record: %v", recorder.args[:10])
+	}
+	if recorder.args[7] != nil || recorder.args[9] != "[0.25,-1]" {
+		t.Errorf("metadata = %v, embedding = %v", recorder.args[7], recorder.args[9])
+	}
+	if recorder.args[17] != `{"k":"v"}` || recorder.args[19] != nil {
+		t.Errorf("metadata = %v, embedding = %v", recorder.args[17], recorder.args[19])
+	}
+}
+
+func TestPostgres_UpsertParameterLimit(t *testing.T) {
+	recorder := &execRecorder{}
+	sql.Register("sinks-recorder-limit", recorder)
+	db, err := sql.Open("sinks-recorder-limit", "")
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	defer db.Close()
+
+	// 6553 records fill one statement; the rest go in a second one
+	records := make([]Record, 7000)
+	for i := range records {
+		records[i] = NewRecord("a.go", chunkx.Chunk{Content: strconv.Itoa(i)})
+	}
+
+	p := &Postgres{DB: db}
+	if err := p.Upsert(context.Background(), records); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if recorder.execs != 2 {
+		t.Fatalf("expected 2 statements, got %d", recorder.execs)
+	}
+	if want := (7000 - 6553) * len(postgresColumns); len(recorder.args) != want {
+		t.Errorf("expected %d arguments in the last statement, got %d", want, len(recorder.args))
+	}
+	if recorder.args[0] != records[6553].ID {
+		t.Errorf("last statement starts with %v, want %s", recorder.args[0], records[6553].ID)
+	}
+}
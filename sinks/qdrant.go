@@ -0,0 +1,71 @@
+package sinks
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"net/url"
+	"strings"
+
+	"github.com/gomantics/chunkx/internal/retry"
+)
+
+// Qdrant upserts records into a Qdrant collection over the REST API.
+//
+// Point IDs are the record IDs formatted as UUIDs, and the payload holds the
+// fields of Record.Payload. Records without a vector are stored with no
+// vectors, which requires a collection created without a vector config.
+type Qdrant struct {
+	URL        string       // Base URL, such as http://localhost:6333
+	Collection string       // Name of the collection
+	APIKey     string       // Sent in the api-key header, if set
+	Client     *http.Client // Defaults to http.DefaultClient
+}
+
+// qdrantPoint is a point in an upsert request.
+type qdrantPoint struct {
+	ID      string         `json:"id"`
+	Vector  any            `json:"vector"`
+	Payload map[string]any `json:"payload"`
+}
+
+// Upsert implements ChunkSink.
+func (q *Qdrant) Upsert(ctx context.Context, records []Record) error {
+	points := make([]qdrantPoint, len(records))
+	for i, r := range records {
+		var vector any = map[string]any{}
+		if r.Vector != nil {
+			vector = r.Vector
+		}
+		points[i] = qdrantPoint{ID: uuid(r.ID), Vector: vector, Payload: r.Payload()}
+	}
+
+	body, err := json.Marshal(map[string]any{"points": points})
+	if err != nil {
+		return &PermanentError{Err: err}
+	}
+
+	endpoint := fmt.Sprintf("%s/collections/%s/points?wait=true",
+		strings.TrimSuffix(q.URL, "/"), url.PathEscape(q.Collection))
+	req, err := http.NewRequestWithContext(ctx, http.MethodPut, endpoint, bytes.NewReader(body))
+	if err != nil {
+		return &PermanentError{Err: err}
+	}
+	req.Header.Set("Content-Type", "application/json")
+	if q.APIKey != "" {
+		req.Header.Set("api-key", q.APIKey)
+	}
+
+	_, err = retry.Send(q.Client, req, "qdrant")
+	return err
+}
+
+// uuid formats a 32-digit hex ID as a UUID.
+func uuid(id string) string {
+	if len(id) != 32 {
+		return id
+	}
+	return id[:8] + "-" + id[8:12] + "-" + id[12:16] + "-" + id[16:20] + "-" + id[20:]
+}
@@ -0,0 +1,93 @@
+package sinks
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+func TestQdrant_Upsert(t *testing.T) {
+	var req *http.Request
+	var body struct {
+		Points []struct {
+			ID      string         `json:"id"`
+			Vector  any            `json:"vector"`
+			Payload map[string]any `json:"payload"`
+		} `json:"points"`
+	}
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		req = r
+		if err := json.NewDecoder(r.Body).Decode(&body); err != nil {
+			t.Errorf("invalid request body: %v", err)
+		}
+		_, _ = w.Write([]byte(`{"result":{"status":"completed"},"status":"ok"}`))
+	}))
+	defer server.Close()
+
+	with := NewRecord("a.go", chunkx.Chunk{Content: "package a", Language: "go", StartLine: 1, EndLine: 1})
+	with.Vector = []float32{0.5, 1}
+	without := NewRecord("b.go", chunkx.Chunk{Content: "package b", Metadata: map[string]string{"k": "v"}})
+
+	q := &Qdrant{URL: server.URL + "/", Collection: "code", APIKey: "secret"}
+	if err := q.Upsert(context.Background(), []Record{with, without}); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if req.Method != http.MethodPut || req.URL.Path != "/collections/code/points" || req.URL.Query().Get("wait") != "true" {
+		t.Errorf("unexpected request %s %s", req.Method, req.URL)
+	}
+	if got := req.Header.Get("api-key"); got != "secret" {
+		t.Errorf("api-key = %q", got)
+	}
+
+	if len(body.Points) != 2 {
+		t.Fatalf("expected 2 points, got %d", len(body.Points))
+	}
+	if got := body.Points[0].ID; got != uuid(with.ID) || len(got) != 36 {
+		t.Errorf("point ID = %q, want UUID of %s", got, with.ID)
+	}
+	if vector, ok := body.Points[0].Vector.([]any); !ok || len(vector) != 2 {
+		t.Errorf("vector = %v", body.Points[0].Vector)
+	}
+	if vector, ok := body.Points[1].Vector.(map[string]any); !ok || len(vector) != 0 {
+		t.Errorf("vector of record without one = %v, want {}", body.Points[1].Vector)
+	}
+	if got := body.Points[0].Payload["text"]; got != "package a" {
+		t.Errorf("payload text = %v", got)
+	}
+	if got := body.Points[1].Payload["metadata"]; got == nil {
+		t.Error("payload is missing metadata")
+	}
+}
+
+func TestQdrant_ErrorStatus(t *testing.T) {
+	tests := []struct {
+		status    int
+		permanent bool
+	}{
+		{http.StatusBadRequest, true},
+		{http.StatusNotFound, true},
+		{http.StatusTooManyRequests, false},
+		{http.StatusServiceUnavailable, false},
+	}
+
+	for _, tt := range tests {
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, _ *http.Request) {
+			http.Error(w, "nope", tt.status)
+		}))
+
+		q := &Qdrant{URL: server.URL, Collection: "code"}
+		err := q.Upsert(context.Background(), []Record{NewRecord("a", chunkx.Chunk{Content: "x"})})
+		server.Close()
+
+		var permanent *PermanentError
+		if err == nil || errors.As(err, &permanent) != tt.permanent {
+			t.Errorf("status %d: err = %v, want permanent = %v", tt.status, err, tt.permanent)
+		}
+	}
+}
@@ -0,0 +1,97 @@
+package sinks
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"fmt"
+	"net/http"
+	"net/url"
+	"regexp"
+	"strings"
+
+	"github.com/gomantics/chunkx/internal/retry"
+)
+
+// Quickwit adds records to a Tantivy full-text index served by Quickwit, for
+// BM25 search alongside, or instead of, a vector store. Combine it with a
+// vector sink using Tee to get both from a single chunking pass.
+//
+// Each record becomes a document with the fields id, text, path, symbol, and
+// language. The index must be created with a matching doc mapping:
+//
+//	version: 0.8
+//	index_id: chunks
+//	doc_mapping:
+//	  field_mappings:
+//	    - {name: id, type: text, tokenizer: raw}
+//	    - {name: text, type: text, tokenizer: default, record: position}
+//	    - {name: path, type: text, tokenizer: raw}
+//	    - {name: symbol, type: text, tokenizer: default}
+//	    - {name: language, type: text, tokenizer: raw, fast: true}
+//	search_settings:
+//	  default_search_fields: [text, symbol]
+//
+// Tantivy indexes are append-only, so a retried batch or a re-indexed file
+// adds documents rather than replacing them. Collapse hits by id, or index
+// into a fresh index, when that matters.
+type Quickwit struct {
+	URL    string       // Base URL, such as http://localhost:7280
+	Index  string       // ID of the index
+	Client *http.Client // Defaults to http.DefaultClient
+}
+
+// quickwitDoc is a document in an ingest request.
+type quickwitDoc struct {
+	ID       string `json:"id"`
+	Text     string `json:"text"`
+	Path     string `json:"path"`
+	Symbol   string `json:"symbol,omitempty"`
+	Language string `json:"language"`
+}
+
+// Upsert implements ChunkSink.
+func (q *Quickwit) Upsert(ctx context.Context, records []Record) error {
+	var body bytes.Buffer
+	enc := json.NewEncoder(&body)
+	for _, r := range records {
+		doc := quickwitDoc{
+			ID:       r.ID,
+			Text:     r.Chunk.Content,
+			Path:     r.Path,
+			Symbol:   symbol(r.Chunk.Content),
+			Language: string(r.Chunk.Language),
+		}
+		if err := enc.Encode(doc); err != nil {
+			return &PermanentError{Err: err}
+		}
+	}
+
+	// Make the documents searchable before returning, as the other sinks do
+	endpoint := fmt.Sprintf("%s/api/v1/%s/ingest?commit=wait_for",
+		strings.TrimSuffix(q.URL, "/"), url.PathEscape(q.Index))
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, endpoint, &body)
+	if err != nil {
+		return &PermanentError{Err: err}
+	}
+	req.Header.Set("Content-Type", "application/x-ndjson")
+
+	_, err = retry.Send(q.Client, req, "quickwit")
+	return err
+}
+
+// declaration matches a declaration keyword followed by the declared name,
+// skipping the receiver of Go methods.
+var declaration = regexp.MustCompile(`(?m)^[ \t]*(?:(?:export|pub(?:\([a-z]+\))?|public|private|protected|static|async|abstract|final|default)\s+)*` +
+	`(?:func|def|class|fn|struct|enum|trait|impl|interface|type|function|module|object)\s+(?:\([^)]*\)\s*)?([A-Za-z_$][\w$]*)`)
+
+// symbol returns the name of the first function, type, or class declared at
+// the start of a line in content, or an empty string if there is none. It
+// recognizes declarations by keyword, so it works for every language without
+// a syntax tree, if less precisely.
+func symbol(content string) string {
+	if m := declaration.FindStringSubmatch(content); m != nil {
+		return m[1]
+	}
+	return ""
+}
@@ -0,0 +1,75 @@
+package sinks
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+func TestQuickwit_Upsert(t *testing.T) {
+	var req *http.Request
+	var docs []quickwitDoc
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		req = r
+		scanner := bufio.NewScanner(r.Body)
+		for scanner.Scan() {
+			var doc quickwitDoc
+			if err := json.Unmarshal(scanner.Bytes(), &doc); err != nil {
+				t.Errorf("invalid document %q: %v", scanner.Text(), err)
+			}
+			docs = append(docs, doc)
+		}
+		_, _ = w.Write([]byte(`{"num_docs_for_processing":2}`))
+	}))
+	defer server.Close()
+
+	records := []Record{
+		NewRecord("a.go", chunkx.Chunk{Content: "// A does a\nfunc (s *S) A() {}\n", Language: "go"}),
+		NewRecord("b.txt", chunkx.Chunk{Content: "plain text"}),
+	}
+	q := &Quickwit{URL: server.URL + "/", Index: "chunks"}
+	if err := q.Upsert(context.Background(), records); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if req.Method != http.MethodPost || req.URL.Path != "/api/v1/chunks/ingest" || req.URL.Query().Get("commit") != "wait_for" {
+		t.Errorf("unexpected request: %s %s", req.Method, req.URL)
+	}
+	if len(docs) != 2 {
+		t.Fatalf("expected 2 documents, got %d", len(docs))
+	}
+	want := quickwitDoc{ID: records[0].ID, Text: records[0].Chunk.Content, Path: "a.go", Symbol: "A", Language: "go"}
+	if docs[0] != want {
+		t.Errorf("document = %+v, want %+v", docs[0], want)
+	}
+	if docs[1].Symbol != "" || docs[1].Path != "b.txt" {
+		t.Errorf("unexpected document: %+v", docs[1])
+	}
+}
+
+func TestSymbol(t *testing.T) {
+	tests := []struct {
+		content string
+		want    string
+	}{
+		{"func Parse(code string) {}", "Parse"},
+		{"func (p *Parser) Parse() {}", "Parse"},
+		{"type Chunk struct {}", "Chunk"},
+		{"@decorator\ndef chunk(text):\n    pass", "chunk"},
+		{"export default function App() {}", "App"},
+		{"pub(crate) fn split(s: &str) {}", "split"},
+		{"public class Main {}", "Main"},
+		{"x := 1 // the func in here is not a declaration", ""},
+	}
+
+	for _, tt := range tests {
+		if got := symbol(tt.content); got != tt.want {
+			t.Errorf("symbol(%q) = %q, want %q", tt.content, got, tt.want)
+		}
+	}
+}
@@ -0,0 +1,235 @@
+package sinks
+
+import (
+	"bufio"
+	"context"
+	"crypto/tls"
+	"errors"
+	"fmt"
+	"net"
+	"strconv"
+	"strings"
+	"sync"
+	"time"
+)
+
+// redisTransientErrors are the prefixes of Redis error replies that a retry
+// may get past, such as a server still loading its data set.
+var redisTransientErrors = []string{"LOADING", "BUSY", "TRYAGAIN", "MASTERDOWN", "CLUSTERDOWN"}
+
+// RedisStream pushes records onto a Redis stream, so a fleet of embedding
+// workers can consume them with consumer groups (XREADGROUP).
+//
+// Each record is added with XADD as an entry with the fields id, holding the
+// record ID, and record, holding the JSON message with the record's payload
+// and vector. A batch is pipelined over one connection and succeeds once
+// Redis has acknowledged every entry. Delivery is at least once: when a batch
+// fails part way, retrying it adds the entries that did succeed again, so
+// consumers should deduplicate by id.
+//
+// The connection is kept open between batches and redialed after an error.
+// RedisStream is safe for concurrent use; batches are sent one at a time.
+type RedisStream struct {
+	Addr     string      // Address of the server, such as localhost:6379
+	Username string      // Sent with AUTH, if set, for Redis 6 ACLs
+	Password string      // Sent with AUTH, if set
+	Stream   string      // Key of the stream
+	MaxLen   int         // Approximate cap on the stream's length, if positive
+	TLS      *tls.Config // Connects over TLS, if set
+
+	mu   sync.Mutex
+	conn net.Conn
+	rw   *bufio.ReadWriter
+}
+
+// Upsert implements ChunkSink.
+func (s *RedisStream) Upsert(ctx context.Context, records []Record) error {
+	if len(records) == 0 {
+		return nil
+	}
+
+	var buf []byte
+	for _, r := range records {
+		msg, err := r.message()
+		if err != nil {
+			return &PermanentError{Err: err}
+		}
+		args := []string{"XADD", s.Stream}
+		if s.MaxLen > 0 {
+			args = append(args, "MAXLEN", "~", strconv.Itoa(s.MaxLen))
+		}
+		buf = appendRedisCommand(buf, append(args, "*", "id", r.ID, "record", string(msg))...)
+	}
+
+	s.mu.Lock()
+	defer s.mu.Unlock()
+
+	err := s.send(ctx, buf, len(records))
+	if err != nil {
+		// The connection may be out of sync with its replies
+		s.close()
+		var reply redisError
+		if errors.As(err, &reply) && !reply.transient() {
+			return &PermanentError{Err: fmt.Errorf("redis: %w", err)}
+		}
+		return fmt.Errorf("redis: %w", err)
+	}
+	return nil
+}
+
+// Close closes the connection, if one is open.
+func (s *RedisStream) Close() error {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	return s.close()
+}
+
+// send writes pipelined commands and reads their replies, dialing first if
+// needed. Every reply is read even after an error reply, so the connection
+// stays usable.
+func (s *RedisStream) send(ctx context.Context, commands []byte, replies int) error {
+	if s.conn == nil {
+		if err := s.dial(ctx); err != nil {
+			return err
+		}
+	}
+
+	if deadline, ok := ctx.Deadline(); ok {
+		_ = s.conn.SetDeadline(deadline)
+	} else {
+		_ = s.conn.SetDeadline(time.Time{})
+	}
+
+	if _, err := s.rw.Write(commands); err != nil {
+		return err
+	}
+	if err := s.rw.Flush(); err != nil {
+		return err
+	}
+
+	var first error
+	for range replies {
+		if err := readRedisReply(s.rw.Reader); err != nil {
+			var reply redisError
+			if !errors.As(err, &reply) {
+				return err
+			}
+			if first == nil {
+				first = err
+			}
+		}
+	}
+	return first
+}
+
+// dial connects to the server and authenticates.
+func (s *RedisStream) dial(ctx context.Context) error {
+	var dialer net.Dialer
+	conn, err := dialer.DialContext(ctx, "tcp", s.Addr)
+	if err != nil {
+		return err
+	}
+	if s.TLS != nil {
+		tlsConn := tls.Client(conn, s.TLS)
+		if err := tlsConn.HandshakeContext(ctx); err != nil {
+			conn.Close()
+			return err
+		}
+		conn = tlsConn
+	}
+	s.conn = conn
+	s.rw = bufio.NewReadWriter(bufio.NewReader(conn), bufio.NewWriter(conn))
+
+	if s.Password == "" {
+		return nil
+	}
+	auth := []string{"AUTH", s.Password}
+	if s.Username != "" {
+		auth = []string{"AUTH", s.Username, s.Password}
+	}
+	if err := s.send(ctx, appendRedisCommand(nil, auth...), 1); err != nil {
+		s.close()
+		return fmt.Errorf("auth: %w", err)
+	}
+	return nil
+}
+
+// close closes the connection, if one is open.
+func (s *RedisStream) close() error {
+	if s.conn == nil {
+		return nil
+	}
+	err := s.conn.Close()
+	s.conn, s.rw = nil, nil
+	return err
+}
+
+// redisError is an error reply from the server.
+type redisError string
+
+func (e redisError) Error() string {
+	return string(e)
+}
+
+// transient reports whether retrying the command may succeed.
+func (e redisError) transient() bool {
+	for _, prefix := range redisTransientErrors {
+		if strings.HasPrefix(string(e), prefix) {
+			return true
+		}
+	}
+	return false
+}
+
+// appendRedisCommand appends a command in the RESP wire format, as an array
+// of bulk strings.
+func appendRedisCommand(b []byte, args ...string) []byte {
+	b = fmt.Appendf(b, "*%d\r\n", len(args))
+	for _, arg := range args {
+		b = fmt.Appendf(b, "$%d\r\n%s\r\n", len(arg), arg)
+	}
+	return b
+}
+
+// readRedisReply reads and discards a reply, returning a redisError for an
+// error reply.
+func readRedisReply(r *bufio.Reader) error {
+	line, err := r.ReadString('\n')
+	if err != nil {
+		return err
+	}
+	line = strings.TrimSuffix(line, "\r\n")
+	if line == "" {
+		return errors.New("empty reply")
+	}
+
+	switch line[0] {
+	case '+', ':':
+		return nil
+	case '-':
+		return redisError(line[1:])
+	case '$':
+		n, err := strconv.Atoi(line[1:])
+		if err != nil {
+			return fmt.Errorf("invalid reply %q", line)
+		}
+		if n < 0 {
+			return nil
+		}
+		_, err = r.Discard(n + 2)
+		return err
+	case '*':
+		n, err := strconv.Atoi(line[1:])
+		if err != nil {
+			return fmt.Errorf("invalid reply %q", line)
+		}
+		for range max(n, 0) {
+			if err := readRedisReply(r); err != nil {
+				return err
+			}
+		}
+		return nil
+	default:
+		return fmt.Errorf("invalid reply %q", line)
+	}
+}
@@ -0,0 +1,172 @@
+package sinks
+
+import (
+	"bufio"
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"strconv"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+// fakeRedis serves RESP commands on a local listener, recording them and
+// replying with reply, which returns an error reply if it starts with "-".
+func fakeRedis(t *testing.T, reply func(args []string) string) (addr string, commands <-chan []string) {
+	t.Helper()
+
+	ln, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	t.Cleanup(func() { ln.Close() })
+
+	ch := make(chan []string, 16)
+	go func() {
+		for {
+			conn, err := ln.Accept()
+			if err != nil {
+				return
+			}
+			go func() {
+				defer conn.Close()
+				r := bufio.NewReader(conn)
+				for {
+					args, err := readRedisCommand(r)
+					if err != nil {
+						return
+					}
+					ch <- args
+					fmt.Fprintf(conn, "%s\r\n", reply(args))
+				}
+			}()
+		}
+	}()
+	return ln.Addr().String(), ch
+}
+
+// readRedisCommand reads a command sent as an array of bulk strings.
+func readRedisCommand(r *bufio.Reader) ([]string, error) {
+	header := func(prefix byte) (int, error) {
+		line, err := r.ReadString('\n')
+		if err != nil {
+			return 0, err
+		}
+		if line[0] != prefix {
+			return 0, fmt.Errorf("unexpected line %q", line)
+		}
+		return strconv.Atoi(strings.TrimSuffix(line[1:], "\r\n"))
+	}
+
+	n, err := header('*')
+	if err != nil {
+		return nil, err
+	}
+	args := make([]string, n)
+	for i := range args {
+		size, err := header('$')
+		if err != nil {
+			return nil, err
+		}
+		arg := make([]byte, size+2)
+		if _, err := io.ReadFull(r, arg); err != nil {
+			return nil, err
+		}
+		args[i] = string(arg[:size])
+	}
+	return args, nil
+}
+
+func TestRedisStream_Upsert(t *testing.T) {
+	addr, commands := fakeRedis(t, func(args []string) string {
+		if args[0] == "AUTH" {
+			return "+OK"
+		}
+		return "$3\r\n1-0"
+	})
+
+	records := []Record{
+		NewRecord("a.go", chunkx.Chunk{Content: "package a", Language: "go"}),
+		NewRecord("b.go", chunkx.Chunk{Content: "package b", Language: "go"}),
+	}
+	records[0].Vector = []float32{0.5}
+
+	s := &RedisStream{Addr: addr, Password: "secret", Stream: "chunks", MaxLen: 1000}
+	defer s.Close()
+	if err := s.Upsert(context.Background(), records); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if auth := <-commands; strings.Join(auth, " ") != "AUTH secret" {
+		t.Errorf("first command = %v, want AUTH", auth)
+	}
+	for i, r := range records {
+		args := <-commands
+		want := []string{"XADD", "chunks", "MAXLEN", "~", "1000", "*", "id", r.ID, "record"}
+		if len(args) != len(want)+1 || strings.Join(args[:len(want)], " ") != strings.Join(want, " ") {
+			t.Fatalf("command %d = %v", i, args)
+		}
+
+		var msg map[string]any
+		if err := json.Unmarshal([]byte(args[len(want)]), &msg); err != nil {
+			t.Fatalf("invalid record: %v", err)
+		}
+		if msg["id"] != r.ID || msg["text"] != r.Chunk.Content || (i == 0) != (msg["vector"] != nil) {
+			t.Errorf("record %d = %v", i, msg)
+		}
+	}
+}
+
+func TestRedisStream_ErrorReply(t *testing.T) {
+	tests := []struct {
+		reply     string
+		permanent bool
+	}{
+		{"-WRONGTYPE Operation against a key holding the wrong kind of value", true},
+		{"-LOADING Redis is loading the dataset in memory", false},
+	}
+
+	for _, tt := range tests {
+		addr, _ := fakeRedis(t, func([]string) string { return tt.reply })
+
+		s := &RedisStream{Addr: addr, Stream: "chunks"}
+		err := s.Upsert(context.Background(), []Record{NewRecord("a", chunkx.Chunk{Content: "x"})})
+
+		var permanent *PermanentError
+		if err == nil || errors.As(err, &permanent) != tt.permanent {
+			t.Errorf("reply %q: err = %v, want permanent = %v", tt.reply, err, tt.permanent)
+		}
+	}
+}
+
+func TestReadRedisReply(t *testing.T) {
+	tests := []struct {
+		reply string
+		err   bool
+	}{
+		{"+OK\r\n", false},
+		{":1\r\n", false},
+		{"$-1\r\n", false},
+		{"*2\r\n$1\r\na\r\n:2\r\n", false},
+		{"-ERR unknown command\r\n", true},
+		{"?\r\n", true},
+	}
+
+	for _, tt := range tests {
+		r := bufio.NewReader(strings.NewReader(tt.reply + "+NEXT\r\n"))
+		err := readRedisReply(r)
+		if (err != nil) != tt.err {
+			t.Errorf("readRedisReply(%q) = %v, want error = %v", tt.reply, err, tt.err)
+		}
+		if !tt.err {
+			if next, _ := r.ReadString('\n'); next != "+NEXT\r\n" {
+				t.Errorf("readRedisReply(%q) left %q unread, want the next reply", tt.reply, next)
+			}
+		}
+	}
+}
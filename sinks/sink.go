@@ -0,0 +1,317 @@
+// Package sinks writes chunks straight into vector stores and databases.
+//
+// A ChunkSink upserts batches of records. Write drains the results of a
+// chunkx.ChunkPipeline into a sink, batching records and retrying failed
+// batches with exponential backoff. With WithEmbedder, records are embedded
+// on the way:
+//
+//	pipeline := chunkx.NewChunkPipeline(0)
+//	sink := &sinks.Qdrant{URL: "http://localhost:6333", Collection: "code"}
+//	model := embed.New(&embed.OpenAI{APIKey: key, Model: "text-embedding-3-small"})
+//	err := sinks.Write(ctx, sink, pipeline.RunDir(ctx, "./src"), sinks.WithEmbedder(model))
+//
+// Qdrant and Quickwit are reached over their REST APIs and Postgres through
+// database/sql, so the package has no dependencies beyond the Postgres driver
+// the caller registers. Tee writes to several sinks at once, such as a vector
+// store and a Quickwit full-text index for hybrid retrieval. RedisStream and
+// NATS push records onto a queue instead, for a fleet of embedding workers to
+// consume, speaking the wire protocols directly. Other stores can be
+// supported by implementing ChunkSink.
+package sinks
+
+import (
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"strconv"
+	"time"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/embed"
+	"github.com/gomantics/chunkx/internal/retry"
+)
+
+// Defaults for batching and retrying.
+const (
+	DefaultBatchSize = 64
+	DefaultRetries   = 3
+	DefaultBackoff   = 500 * time.Millisecond
+)
+
+// Record is a chunk ready to be stored.
+type Record struct {
+	ID     string       // Stable key derived from the path, chunk ID, and occurrence
+	Path   string       // Path of the file the chunk was read from
+	Chunk  chunkx.Chunk // The chunk itself
+	Vector []float32    // Embedding of the chunk, if any
+}
+
+// NewRecord creates the record for a chunk of the file at path.
+//
+// Chunk IDs depend only on content, so identical chunks share an ID. The
+// record ID also covers the path, so each file keeps its own copy and
+// re-running over a changed file overwrites only its own records. Use
+// NewRecords for all the chunks of a file, which tells repeated chunks apart.
+func NewRecord(path string, chunk chunkx.Chunk) Record {
+	return newRecord(path, chunk, chunk.ID(), 0)
+}
+
+// NewRecords creates the records for the chunks of the file at path.
+//
+// Repeated chunks of a file, such as identical license headers or test
+// fixtures, are keyed by how many came before them, so they don't overwrite
+// one another within a batch. Unlike a byte offset, the count doesn't change
+// when an edit moves a chunk, so re-running over an edited file keeps the IDs
+// of its unchanged chunks.
+//
+// Sinks never delete records: those of chunks an edit removed, and of
+// deleted files, stay in the store until the caller deletes them, for example
+// by deleting the records of the path whose IDs aren't among its new ones.
+func NewRecords(path string, chunks []chunkx.Chunk) []Record {
+	records := make([]Record, len(chunks))
+	seen := make(map[string]int, len(chunks))
+	for i, chunk := range chunks {
+		id := chunk.ID()
+		records[i] = newRecord(path, chunk, id, seen[id])
+		seen[id]++
+	}
+	return records
+}
+
+// newRecord creates the record for the nth occurrence of a chunk with the
+// given ID in the file at path. The first occurrence hashes like NewRecord.
+func newRecord(path string, chunk chunkx.Chunk, id string, n int) Record {
+	h := sha256.New()
+	h.Write([]byte(path))
+	h.Write([]byte{0})
+	h.Write([]byte(id))
+	if n > 0 {
+		h.Write([]byte{0})
+		h.Write(strconv.AppendInt(nil, int64(n), 10))
+	}
+	return Record{
+		ID:    hex.EncodeToString(h.Sum(nil)[:16]),
+		Path:  path,
+		Chunk: chunk,
+	}
+}
+
+// Payload returns the fields stored alongside the record: the path, language,
+// position, node types, metadata, and text of the chunk.
+func (r Record) Payload() map[string]any {
+	payload := map[string]any{
+		"chunk_id":   r.Chunk.ID(),
+		"path":       r.Path,
+		"language":   string(r.Chunk.Language),
+		"start_line": r.Chunk.StartLine,
+		"end_line":   r.Chunk.EndLine,
+		"start_byte": r.Chunk.StartByte,
+		"end_byte":   r.Chunk.EndByte,
+		"node_types": r.Chunk.NodeTypes,
+		"text":       r.Chunk.Content,
+	}
+	if len(r.Chunk.Metadata) > 0 {
+		payload["metadata"] = r.Chunk.Metadata
+	}
+	return payload
+}
+
+// message encodes a record as the JSON message pushed by queue sinks: the
+// fields of Payload, the record ID, and the vector, if any.
+func (r Record) message() ([]byte, error) {
+	msg := r.Payload()
+	msg["id"] = r.ID
+	if r.Vector != nil {
+		msg["vector"] = r.Vector
+	}
+	return json.Marshal(msg)
+}
+
+// ChunkSink stores records, replacing any existing record with the same ID.
+type ChunkSink interface {
+	Upsert(ctx context.Context, records []Record) error
+}
+
+// Tee is a sink that upserts every batch into each of its sinks in turn, to
+// fill a vector store and a full-text index from a single chunking pass. A
+// failure in any sink fails the batch, so a retry sends it to every sink
+// again.
+type Tee []ChunkSink
+
+// Upsert implements ChunkSink.
+func (t Tee) Upsert(ctx context.Context, records []Record) error {
+	for _, sink := range t {
+		if err := sink.Upsert(ctx, records); err != nil {
+			return err
+		}
+	}
+	return nil
+}
+
+// PermanentError marks a sink error that retrying won't fix, such as a
+// rejected request. Sinks wrap such errors so the batch fails immediately.
+type PermanentError = retry.PermanentError
+
+// Option configures batching and retrying.
+type Option func(*Batcher)
+
+// WithBatchSize sets the number of records sent to the sink at once.
+// Default: DefaultBatchSize.
+func WithBatchSize(n int) Option {
+	return func(b *Batcher) {
+		b.size = n
+	}
+}
+
+// WithRetries sets how many times a failed batch is retried before giving
+// up. Default: DefaultRetries.
+func WithRetries(n int) Option {
+	return func(b *Batcher) {
+		b.retries = n
+	}
+}
+
+// WithBackoff sets the delay before the first retry; each further retry
+// waits twice as long. Default: DefaultBackoff.
+func WithBackoff(d time.Duration) Option {
+	return func(b *Batcher) {
+		b.backoff = d
+	}
+}
+
+// WithEmbedder computes the vector of every record that has none with e
+// before it is sent to the sink. Records are embedded a batch at a time; wrap
+// e in an embed.Client to rate limit and retry requests.
+func WithEmbedder(e embed.Embedder) Option {
+	return func(b *Batcher) {
+		b.embedder = e
+	}
+}
+
+// Batcher buffers records and upserts them into a sink in batches.
+type Batcher struct {
+	sink     ChunkSink
+	embedder embed.Embedder
+	size     int
+	retries  int
+	backoff  time.Duration
+	pending  []Record
+}
+
+// NewBatcher creates a batcher writing to sink.
+func NewBatcher(sink ChunkSink, opts ...Option) *Batcher {
+	b := &Batcher{
+		sink:    sink,
+		size:    DefaultBatchSize,
+		retries: DefaultRetries,
+		backoff: DefaultBackoff,
+	}
+	for _, opt := range opts {
+		opt(b)
+	}
+	b.size = max(b.size, 1)
+	return b
+}
+
+// Add queues records, sending a batch to the sink whenever enough are
+// pending.
+func (b *Batcher) Add(ctx context.Context, records ...Record) error {
+	for _, r := range records {
+		b.pending = append(b.pending, r)
+		if len(b.pending) >= b.size {
+			if err := b.Flush(ctx); err != nil {
+				return err
+			}
+		}
+	}
+	return nil
+}
+
+// Flush sends any pending records to the sink. A failed batch is kept, so
+// Flush can be called again once the sink recovers.
+func (b *Batcher) Flush(ctx context.Context) error {
+	if len(b.pending) == 0 {
+		return nil
+	}
+	if err := b.embed(ctx, b.pending); err != nil {
+		return err
+	}
+	if err := b.upsert(ctx, b.pending); err != nil {
+		return err
+	}
+	b.pending = b.pending[:0]
+	return nil
+}
+
+// embed fills in the vectors of records that have none.
+func (b *Batcher) embed(ctx context.Context, records []Record) error {
+	if b.embedder == nil {
+		return nil
+	}
+
+	var missing []int
+	var texts []string
+	for i, r := range records {
+		if r.Vector == nil {
+			missing = append(missing, i)
+			texts = append(texts, r.Chunk.Content)
+		}
+	}
+	if len(texts) == 0 {
+		return nil
+	}
+
+	vectors, err := b.embedder.Embed(ctx, texts)
+	if err != nil {
+		return err
+	}
+	if len(vectors) != len(texts) {
+		return fmt.Errorf("embed %d records: got %d vectors", len(texts), len(vectors))
+	}
+	for i, j := range missing {
+		records[j].Vector = vectors[i]
+	}
+	return nil
+}
+
+// upsert sends a batch, retrying failures that aren't permanent.
+func (b *Batcher) upsert(ctx context.Context, records []Record) error {
+	err := retry.Do(ctx, b.retries, b.backoff, func() error {
+		return b.sink.Upsert(ctx, records)
+	})
+	if err != nil {
+		return fmt.Errorf("upsert %d records: %w", len(records), err)
+	}
+	return nil
+}
+
+// Write upserts the chunks of every result into sink until results is
+// closed. Files that failed to chunk are skipped and reported together in the
+// returned error; a batch that still fails after retrying stops the write.
+// Records are created with NewRecords, so Write leaves the records of removed
+// chunks and deleted files in the sink.
+// When Write returns early, cancel the pipeline's context so it stops.
+func Write(ctx context.Context, sink ChunkSink, results <-chan chunkx.FileResult, opts ...Option) error {
+	b := NewBatcher(sink, opts...)
+
+	var failed []error
+	for result := range results {
+		if result.Err != nil {
+			failed = append(failed, fmt.Errorf("%s: %w", result.Path, result.Err))
+			continue
+		}
+		for _, record := range NewRecords(result.Path, result.Chunks) {
+			if err := b.Add(ctx, record); err != nil {
+				return err
+			}
+		}
+	}
+
+	if err := b.Flush(ctx); err != nil {
+		return err
+	}
+	return errors.Join(failed...)
+}
@@ -0,0 +1,248 @@
+package sinks
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+
+	"github.com/gomantics/chunkx"
+)
+
+// fakeSink records the batches it receives and fails the first calls.
+type fakeSink struct {
+	batches [][]Record
+	fail    int
+	err     error
+	calls   int
+}
+
+func (s *fakeSink) Upsert(_ context.Context, records []Record) error {
+	s.calls++
+	if s.calls <= s.fail {
+		return s.err
+	}
+	s.batches = append(s.batches, append([]Record(nil), records...))
+	return nil
+}
+
+// results returns a closed channel holding the given results.
+func results(rs ...chunkx.FileResult) <-chan chunkx.FileResult {
+	ch := make(chan chunkx.FileResult, len(rs))
+	for _, r := range rs {
+		ch <- r
+	}
+	close(ch)
+	return ch
+}
+
+func TestNewRecord_IDCoversPath(t *testing.T) {
+	chunk := chunkx.Chunk{Content: "same", Language: "go"}
+
+	a, b := NewRecord("a.go", chunk), NewRecord("b.go", chunk)
+	if a.ID == b.ID {
+		t.Errorf("records of different files share ID %s", a.ID)
+	}
+	if again := NewRecord("a.go", chunk); again.ID != a.ID {
+		t.Errorf("ID is not stable: %s != %s", again.ID, a.ID)
+	}
+	moved := chunk
+	moved.StartByte = 10
+	if NewRecord("a.go", moved).ID != a.ID {
+		t.Errorf("moving a chunk changed its ID")
+	}
+	if len(a.ID) != 32 {
+		t.Errorf("ID %q is not 32 hex digits", a.ID)
+	}
+}
+
+func TestWrite_RepeatedChunks(t *testing.T) {
+	// A file repeating a chunk, as fixtures and license headers do, must not
+	// send a batch upserting one ID twice, which Postgres rejects
+	header := "// Copyright the authors\n"
+	chunks := []chunkx.Chunk{
+		{Content: header, StartByte: 0, EndByte: len(header)},
+		{Content: header, StartByte: 100, EndByte: 100 + len(header)},
+	}
+
+	sink := &fakeSink{}
+	if err := Write(context.Background(), sink, results(chunkx.FileResult{Path: "a.go", Chunks: chunks})); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if len(sink.batches) != 1 || len(sink.batches[0]) != 2 {
+		t.Fatalf("expected one batch of two records, got %+v", sink.batches)
+	}
+	if first, second := sink.batches[0][0], sink.batches[0][1]; first.ID == second.ID {
+		t.Errorf("repeated chunks share ID %s", first.ID)
+	}
+}
+
+func TestNewRecords_Edit(t *testing.T) {
+	// Inserting a chunk must keep the IDs of the chunks after it, repeats
+	// included, so re-running over an edited file overwrites them in place
+	header := "// Copyright the authors\n"
+	before := []chunkx.Chunk{
+		{Content: header, StartByte: 0},
+		{Content: "func a() {}\n", StartByte: 25},
+		{Content: header, StartByte: 37},
+	}
+	after := []chunkx.Chunk{
+		{Content: header, StartByte: 0},
+		{Content: "func inserted() {}\n", StartByte: 25},
+		{Content: "func a() {}\n", StartByte: 44},
+		{Content: header, StartByte: 56},
+	}
+
+	old, edited := NewRecords("a.go", before), NewRecords("a.go", after)
+	if old[0].ID == old[2].ID {
+		t.Errorf("repeated chunks share ID %s", old[0].ID)
+	}
+	if old[0].ID != NewRecord("a.go", before[0]).ID {
+		t.Errorf("first occurrence doesn't match NewRecord")
+	}
+	for i, j := range []int{0, 2, 3} {
+		if old[i].ID != edited[j].ID {
+			t.Errorf("record %d changed ID after the edit", i)
+		}
+	}
+}
+
+func TestWrite_Batches(t *testing.T) {
+	chunks := make([]chunkx.Chunk, 5)
+	for i := range chunks {
+		chunks[i] = chunkx.Chunk{Content: string(rune('a' + i))}
+	}
+
+	sink := &fakeSink{}
+	err := Write(context.Background(), sink, results(
+		chunkx.FileResult{Path: "one.txt", Chunks: chunks[:3]},
+		chunkx.FileResult{Path: "two.txt", Chunks: chunks[3:]},
+	), WithBatchSize(2))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	var sizes []int
+	for _, batch := range sink.batches {
+		sizes = append(sizes, len(batch))
+	}
+	if len(sizes) != 3 || sizes[0] != 2 || sizes[1] != 2 || sizes[2] != 1 {
+		t.Errorf("batch sizes = %v, want [2 2 1]", sizes)
+	}
+	if got := sink.batches[1][1].Path; got != "two.txt" {
+		t.Errorf("fourth record path = %q, want two.txt", got)
+	}
+}
+
+func TestWrite_ReportsFailedFiles(t *testing.T) {
+	sink := &fakeSink{}
+	errRead := errors.New("read failed")
+	err := Write(context.Background(), sink, results(
+		chunkx.FileResult{Path: "bad.txt", Err: errRead},
+		chunkx.FileResult{Path: "good.txt", Chunks: []chunkx.Chunk{{Content: "x"}}},
+	))
+	if !errors.Is(err, errRead) {
+		t.Errorf("expected the file error, got %v", err)
+	}
+	if len(sink.batches) != 1 || len(sink.batches[0]) != 1 {
+		t.Errorf("expected the good file to be written, got %v", sink.batches)
+	}
+}
+
+func TestBatcher_Retries(t *testing.T) {
+	sink := &fakeSink{fail: 2, err: errors.New("unavailable")}
+	b := NewBatcher(sink, WithBackoff(time.Millisecond))
+
+	if err := b.Add(context.Background(), Record{ID: "1"}); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := b.Flush(context.Background()); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if sink.calls != 3 || len(sink.batches) != 1 {
+		t.Errorf("calls = %d, batches = %d; want 3 and 1", sink.calls, len(sink.batches))
+	}
+}
+
+func TestBatcher_GivesUp(t *testing.T) {
+	errDown := errors.New("down")
+	sink := &fakeSink{fail: 10, err: errDown}
+	b := NewBatcher(sink, WithRetries(2), WithBackoff(time.Millisecond))
+
+	_ = b.Add(context.Background(), Record{ID: "1"})
+	if err := b.Flush(context.Background()); !errors.Is(err, errDown) {
+		t.Errorf("expected %v, got %v", errDown, err)
+	}
+	if sink.calls != 3 {
+		t.Errorf("calls = %d, want 3", sink.calls)
+	}
+
+	// The failed batch is kept for the next flush
+	sink.fail = 0
+	if err := b.Flush(context.Background()); err != nil || len(sink.batches) != 1 {
+		t.Errorf("retrying flush: err = %v, batches = %d", err, len(sink.batches))
+	}
+}
+
+func TestBatcher_PermanentErrorNotRetried(t *testing.T) {
+	sink := &fakeSink{fail: 10, err: &PermanentError{Err: errors.New("bad request")}}
+	b := NewBatcher(sink, WithBackoff(time.Millisecond))
+
+	_ = b.Add(context.Background(), Record{ID: "1"})
+	if err := b.Flush(context.Background()); err == nil {
+		t.Fatal("expected an error")
+	}
+	if sink.calls != 1 {
+		t.Errorf("calls = %d, want 1", sink.calls)
+	}
+}
+
+// constantEmbedder embeds every text as the same vector.
+type constantEmbedder struct {
+	texts []string
+}
+
+func (e *constantEmbedder) Embed(_ context.Context, texts []string) ([][]float32, error) {
+	e.texts = append(e.texts, texts...)
+	vectors := make([][]float32, len(texts))
+	for i := range vectors {
+		vectors[i] = []float32{1}
+	}
+	return vectors, nil
+}
+
+func TestBatcher_Embeds(t *testing.T) {
+	sink := &fakeSink{}
+	e := &constantEmbedder{}
+	b := NewBatcher(sink, WithEmbedder(e))
+
+	embedded := Record{ID: "1", Chunk: chunkx.Chunk{Content: "has vector"}, Vector: []float32{2}}
+	plain := Record{ID: "2", Chunk: chunkx.Chunk{Content: "needs vector"}}
+	_ = b.Add(context.Background(), embedded, plain)
+	if err := b.Flush(context.Background()); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if len(e.texts) != 1 || e.texts[0] != "needs vector" {
+		t.Errorf("embedded texts = %q, want only the record without a vector", e.texts)
+	}
+	batch := sink.batches[0]
+	if batch[0].Vector[0] != 2 || batch[1].Vector[0] != 1 {
+		t.Errorf("vectors = %v, %v", batch[0].Vector, batch[1].Vector)
+	}
+}
+
+func TestTee(t *testing.T) {
+	first, second := &fakeSink{}, &fakeSink{fail: 1, err: errors.New("unavailable")}
+	b := NewBatcher(Tee{first, second}, WithBackoff(time.Millisecond))
+
+	_ = b.Add(context.Background(), Record{ID: "1"})
+	if err := b.Flush(context.Background()); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	// The retry after the second sink failed sends the batch to both again
+	if len(first.batches) != 2 || len(second.batches) != 1 {
+		t.Errorf("sinks received %d and %d batches, want 2 and 1", len(first.batches), len(second.batches))
+	}
+}
@@ -0,0 +1,35 @@
+package chunkx
+
+// MetadataOverBudget is set to "true" on chunks that WithSoftLimit let exceed
+// the maximum size, and on records too large for a chunk that are never split,
+// such as HL7 messages.
+const MetadataOverBudget = "over_budget"
+
+// WithSoftLimit lets a syntax node exceed the maximum size by up to percent
+// of it and still become a single chunk, rather than being split into its
+// children at a poor boundary, such as a function a few tokens too large
+// split between its signature and its body. Such chunks record
+// MetadataOverBudget in their metadata, so they can be told apart from
+// chunks that fit. Lines are never merged past the maximum size.
+func WithSoftLimit(percent float64) Option {
+	return func(c *config) {
+		c.softLimit = max(percent, 0)
+	}
+}
+
+// softMaxSize returns the largest size of a node that is kept whole, which is
+// the maximum size raised by the soft limit.
+func (cfg *config) softMaxSize() int {
+	return cfg.maxSize + int(float64(cfg.maxSize)*cfg.softLimit/100)
+}
+
+// overBudget flags chunk as over budget if its size exceeds the maximum size.
+func overBudget(chunk Chunk, size int, cfg *config) Chunk {
+	if size > cfg.maxSize {
+		if chunk.Metadata == nil {
+			chunk.Metadata = make(map[string]string, 1)
+		}
+		chunk.Metadata[MetadataOverBudget] = "true"
+	}
+	return chunk
+}
@@ -0,0 +1,114 @@
+package chunkx
+
+import (
+	"maps"
+	"sort"
+	"strings"
+)
+
+// textSpan is a part of a document to chunk on its own, such as an operation
+// of an OpenAPI document or a rule of a Makefile, with the metadata of its
+// chunks.
+type textSpan struct {
+	start, end int
+	metadata   map[string]string
+	whole      bool // Whether the span is a chunk of its own even if too large for one, as a record is
+}
+
+// chunkSpans chunks code by spans, each a chunk of its own when it fits.
+// Consecutive spans with the same metadata are chunked together as long as
+// they fit. A span too large for a chunk is chunked by the spans nested finds
+// in it, if nested isn't nil, and chunks take the metadata of the innermost
+// span holding them. The code between spans, and spans too large for a chunk
+// without nested spans, are chunked with chunkRest, unless they are whole,
+// which makes them a chunk of their own flagged MetadataOverBudget. Spans
+// overlapping an earlier one are left out.
+func chunkSpans(code string, cfg *config, spans []textSpan, nested func(code string) []textSpan, chunkRest func(code string) ([]Chunk, error)) ([]Chunk, error) {
+	sort.SliceStable(spans, func(i, j int) bool { return spans[i].start < spans[j].start })
+
+	var chunks []Chunk
+	from, fromLine := 0, 1
+	split := func(start, end int, metadata map[string]string, whole bool) error {
+		line := lineOf(code, from, fromLine, start)
+		from, fromLine = start, line
+		if strings.TrimSpace(code[start:end]) == "" {
+			return nil
+		}
+		size, err := cfg.tokenCounter.CountTokens(code[start:end])
+		if err != nil {
+			return err
+		}
+		if (size <= cfg.maxSize || whole) && metadata != nil {
+			chunks = append(chunks, overBudget(Chunk{
+				Content:   code[start:end],
+				StartLine: line,
+				EndLine:   lineOf(code, start, line, end),
+				StartByte: start,
+				EndByte:   end,
+				Language:  cfg.language,
+				Metadata:  maps.Clone(metadata),
+			}, size, cfg))
+			return nil
+		}
+
+		var inner []textSpan
+		if metadata != nil && nested != nil {
+			inner = nested(code[start:end])
+		}
+		var pieces []Chunk
+		if len(inner) > 0 {
+			pieces, err = chunkSpans(code[start:end], cfg, inner, nested, chunkRest)
+		} else {
+			pieces, err = chunkRest(code[start:end])
+		}
+		if err != nil {
+			return err
+		}
+		shiftChunks(pieces, start, line-1)
+		if metadata != nil {
+			for i := range pieces {
+				if pieces[i].Metadata == nil {
+					pieces[i].Metadata = make(map[string]string, len(metadata))
+				}
+				for key, value := range metadata {
+					if _, ok := pieces[i].Metadata[key]; !ok {
+						pieces[i].Metadata[key] = value
+					}
+				}
+			}
+		}
+		chunks = append(chunks, pieces...)
+		return nil
+	}
+
+	offset := 0
+	for i := 0; i < len(spans); i++ {
+		span := spans[i]
+		if span.start < offset {
+			continue
+		}
+		for i+1 < len(spans) && spans[i+1].start >= span.end && maps.Equal(spans[i+1].metadata, span.metadata) {
+			size, err := cfg.tokenCounter.CountTokens(code[span.start:spans[i+1].end])
+			if err != nil {
+				return nil, err
+			}
+			if size > cfg.maxSize {
+				break
+			}
+			span.end = spans[i+1].end
+			i++
+		}
+
+		if err := split(offset, span.start, nil, false); err != nil {
+			return nil, err
+		}
+		if err := split(span.start, span.end, span.metadata, span.whole); err != nil {
+			return nil, err
+		}
+		offset = span.end
+	}
+	if err := split(offset, len(code), nil, false); err != nil {
+		return nil, err
+	}
+	return chunks, nil
+}
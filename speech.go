@@ -0,0 +1,293 @@
+package chunkx
+
+import (
+	"bytes"
+	"maps"
+	"math"
+	"regexp"
+	"strconv"
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Metadata keys set on chunks of transcripts chunked with WithSpeakerTurns.
+const (
+	MetadataSpeaker   = "speaker"    // Speaker of the chunk, if labeled
+	MetadataStartTime = "start_time" // Time the first segment of the chunk starts at
+	MetadataEndTime   = "end_time"   // Time the last segment of the chunk ends at
+)
+
+var (
+	// cueTiming matches the timing line of a WebVTT or SRT cue, such as
+	// "00:01:02.500 --> 00:01:04.000".
+	cueTiming = regexp.MustCompile(`^((?:\d+:)?\d{1,2}:\d{2}[.,]\d{1,3})[ \t]+-->[ \t]+((?:\d+:)?\d{1,2}:\d{2}[.,]\d{1,3})`)
+	// voiceTag matches the WebVTT voice tag a cue starts with, such as
+	// "<v Roger Bingham>".
+	voiceTag = regexp.MustCompile(`^<v(?:\.[\w.-]+)?[ \t]+([^>]+)>`)
+	// speakerLabel matches the speaker a line of a transcript starts with,
+	// such as "SPEAKER_01: ".
+	speakerLabel = regexp.MustCompile(`^([\p{L}\d_][\p{L}\d_ .'-]{0,39}):[ \t]+`)
+)
+
+// WithSpeakerTurns chunks diarized transcripts on the turns of their
+// speakers, so an answer found in a meeting recording can be traced to who
+// said it and when. Transcripts are WebVTT or SRT files, whose cues name
+// their speaker with a voice tag such as "<v Alice>" or a label such as
+// "Alice: ", and JSON arrays of segments with a text, a speaker, and start
+// and end times, such as those of WhisperX, alone or under "segments".
+// Cues without a speaker are spoken by the speaker of the cue before them.
+//
+// A turn is the consecutive segments of a speaker. Chunks never span two
+// turns, and a segment is only split when it doesn't fit in a chunk on its
+// own. Chunks record MetadataSpeaker, MetadataStartTime, and MetadataEndTime:
+// times of cues in seconds, and times of JSON segments as they are given.
+//
+// Chunks span the texts of their segments in the transcript, so their offsets
+// and lines point into it, while their content is the text of the segments,
+// one line feed apart, without timings and labels. Content that isn't a
+// transcript is chunked as usual.
+func WithSpeakerTurns() Option {
+	return func(c *config) {
+		c.speakers = true
+	}
+}
+
+// speechSegment is a segment of a transcript, by the offsets of its text in
+// the text of the transcript.
+type speechSegment struct {
+	speaker    string
+	from, to   string // Start and end times
+	start, end int
+}
+
+// looksLikeSpeech reports whether head starts a WebVTT or SRT file, or a JSON
+// document with speakers.
+func looksLikeSpeech(head []byte) bool {
+	head = bytes.TrimLeft(bytes.TrimPrefix(head, []byte("\xef\xbb\xbf")), " \t\r\n")
+	if bytes.HasPrefix(head, []byte("WEBVTT")) {
+		return true
+	}
+	if bytes.HasPrefix(head, []byte("[")) || bytes.HasPrefix(head, []byte("{")) {
+		return bytes.Contains(head, []byte(`"speaker"`))
+	}
+	// An SRT file starts with the index and the timing of its first cue
+	_, rest, _ := bytes.Cut(head, []byte("\n"))
+	return cueTiming.Match(rest)
+}
+
+// chunkSpeech chunks the transcript code by the turns of its speakers,
+// returning false if code isn't a transcript.
+func (c *castChunker) chunkSpeech(code string, cfg *config) ([]Chunk, bool, error) {
+	var tb textBuilder
+	segments, ok := jsonSegments(code, &tb)
+	if !ok {
+		tb = textBuilder{}
+		segments, ok = cueSegments(code, &tb)
+	}
+	if !ok {
+		return nil, false, nil
+	}
+	text := tb.b.String()
+
+	// The call's timeout already runs over the whole transcript
+	inner := *cfg
+	inner.speakers = false
+	inner.timeout = 0
+	inner.language = languages.Generic
+
+	var chunks []Chunk
+	for first := 0; first < len(segments); {
+		start := segments[first].start
+		if start == segments[first].end {
+			first++
+			continue
+		}
+
+		size, err := cfg.tokenCounter.CountTokens(text[start:segments[first].end])
+		if err != nil {
+			return nil, true, err
+		}
+		if size > cfg.maxSize {
+			pieces, err := c.chunk(text[start:segments[first].end], &inner)
+			if err != nil {
+				return nil, true, err
+			}
+			shiftChunks(pieces, start, 0)
+			metadata := speechMetadata(segments[first], segments[first])
+			for i := range pieces {
+				if pieces[i].Metadata == nil {
+					pieces[i].Metadata = make(map[string]string, len(metadata))
+				}
+				maps.Copy(pieces[i].Metadata, metadata)
+			}
+			chunks = append(chunks, pieces...)
+			first++
+			continue
+		}
+
+		// Add the segments of the turn after the first while they fit
+		last := first + 1
+		for ; last < len(segments) && segments[last].speaker == segments[first].speaker; last++ {
+			size, err := cfg.tokenCounter.CountTokens(text[start:segments[last].end])
+			if err != nil {
+				return nil, true, err
+			}
+			if size > cfg.maxSize {
+				break
+			}
+		}
+
+		end := segments[last-1].end
+		chunks = append(chunks, Chunk{
+			Content:   text[start:end],
+			StartByte: start,
+			EndByte:   end,
+			Language:  languages.Generic,
+			Metadata:  speechMetadata(segments[first], segments[last-1]),
+		})
+		first = last
+	}
+
+	chunks = mapChunks(chunks, code, &tb.offsets, "")
+	if err := cfg.limits.checkChunks(chunks); err != nil {
+		return nil, true, err
+	}
+	return chunks, true, nil
+}
+
+// speechMetadata returns the metadata of a chunk of the segments from first
+// to last.
+func speechMetadata(first, last speechSegment) map[string]string {
+	metadata := make(map[string]string, 3)
+	if first.speaker != "" {
+		metadata[MetadataSpeaker] = first.speaker
+	}
+	if first.from != "" {
+		metadata[MetadataStartTime] = first.from
+	}
+	if last.to != "" {
+		metadata[MetadataEndTime] = last.to
+	}
+	return metadata
+}
+
+// jsonSegments appends the texts of the segments of the JSON transcript code
+// to tb, one line feed apart, and returns them, or false if code isn't a JSON
+// transcript.
+func jsonSegments(code string, tb *textBuilder) ([]speechSegment, bool) {
+	root, ok := parseJSON(code)
+	if !ok {
+		return nil, false
+	}
+	list := root
+	if code[root.start] == '{' {
+		if list, ok = root.get("segments"); !ok {
+			return nil, false
+		}
+	}
+	if code[list.start] != '[' || len(list.values) == 0 {
+		return nil, false
+	}
+
+	segments := make([]speechSegment, 0, len(list.values))
+	for _, value := range list.values {
+		text, ok := value.get("text")
+		if !ok || code[text.start] != '"' {
+			return nil, false
+		}
+
+		var segment speechSegment
+		if speaker, ok := value.get("speaker"); ok {
+			segment.speaker = jsonScalar(code, speaker)
+		}
+		if from, ok := value.get("start"); ok {
+			segment.from = jsonScalar(code, from)
+		}
+		if to, ok := value.get("end"); ok {
+			segment.to = jsonScalar(code, to)
+		}
+
+		// A line feed between segments stands for the JSON between them
+		if tb.b.Len() > 0 {
+			tb.writeRune('\n', text.start-tb.offsets.origEnd)
+		}
+		segment.start = tb.b.Len()
+		writeJSONString(tb, code, text)
+		segment.end = tb.b.Len()
+		segments = append(segments, segment)
+	}
+	return segments, true
+}
+
+// jsonScalar returns the string v of data decoded, "" for null, or the text
+// of any other value.
+func jsonScalar(data string, v jsonValue) string {
+	switch value := data[v.start:v.end]; {
+	case value[0] == '"':
+		return jsonString(data, v)
+	case value == "null":
+		return ""
+	default:
+		return value
+	}
+}
+
+// cueSegments appends the texts of the cues of the WebVTT or SRT transcript
+// code to tb, one line feed apart, and returns them, or false if code has no
+// cues.
+func cueSegments(code string, tb *textBuilder) ([]speechSegment, bool) {
+	var segments []speechSegment
+	speaker := ""
+	for offset := 0; offset < len(code); {
+		line, _, _ := strings.Cut(code[offset:], "\n")
+		offset = min(offset+len(line)+1, len(code))
+		timing := cueTiming.FindStringSubmatch(line)
+		if timing == nil {
+			continue
+		}
+
+		// The text of the cue runs to the next blank line
+		start, end := offset, offset
+		for end < len(code) {
+			line, _, _ := strings.Cut(code[end:], "\n")
+			if strings.TrimSpace(line) == "" {
+				break
+			}
+			end = min(end+len(line)+1, len(code))
+		}
+		offset = end
+		cue := strings.TrimRight(code[start:end], "\r\n")
+		label := 0
+		if m := voiceTag.FindStringSubmatch(cue); m != nil {
+			speaker, label = strings.TrimSpace(m[1]), len(m[0])
+		} else if m := speakerLabel.FindStringSubmatch(cue); m != nil {
+			speaker, label = m[1], len(m[0])
+		}
+		cue = strings.TrimSuffix(cue[label:], "</v>")
+		start += label
+
+		// A line feed between cues stands for the timings and labels between
+		// them
+		if tb.b.Len() > 0 {
+			tb.writeRune('\n', start-tb.offsets.origEnd)
+		} else {
+			tb.offsets.skip(start - tb.offsets.origEnd)
+		}
+		segment := speechSegment{speaker: speaker, from: cueSeconds(timing[1]), to: cueSeconds(timing[2]), start: tb.b.Len()}
+		tb.writeString(cue)
+		segment.end = tb.b.Len()
+		segments = append(segments, segment)
+	}
+	return segments, len(segments) > 0
+}
+
+// cueSeconds returns the cue time t, such as "01:02.500", in seconds.
+func cueSeconds(t string) string {
+	var millis int64
+	for _, part := range strings.Split(strings.Replace(t, ",", ".", 1), ":") {
+		n, _ := strconv.ParseFloat(part, 64)
+		millis = millis*60 + int64(math.Round(n*1000))
+	}
+	return strconv.FormatFloat(float64(millis)/1000, 'f', -1, 64)
+}
@@ -0,0 +1,158 @@
+package chunkx
+
+import (
+	"fmt"
+	"hash/fnv"
+	"math"
+	"strings"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// Sizes of the chunks of WithStableBoundaries, as shares of the maximum size.
+const (
+	stableMinShare     = 0.5  // Size a chunk needs to end at an anchor
+	stableAnchorSpread = 0.25 // Tokens between anchors, on average
+)
+
+// WithStableBoundaries splits Markdown and plain text between sentences at
+// anchors picked by a hash of each sentence, rather than by packing as much
+// as fits into every chunk. Once a chunk holds half the maximum size, it ends
+// at the next anchor, and it always ends before a Markdown heading, so an
+// edited paragraph only moves the boundaries of the chunks around it and the
+// rest of a document keeps its chunks and their Chunk.ID, which saves
+// re-embedding continuously updated wikis.
+//
+// Chunks are split between sentences and lines, without regard to the
+// syntax of Markdown, which WithFencedCode and WithTableHeaders then don't
+// apply to.
+func WithStableBoundaries() Option {
+	return func(c *config) {
+		c.stableBoundaries = true
+	}
+}
+
+// prose reports whether the language is split by WithStableBoundaries.
+func prose(lang languages.LanguageName) bool {
+	return lang == languages.Generic || lang == languages.Markdown
+}
+
+// sentences returns the byte ranges of the sentences of code, without the
+// white space around them. Sentences end at ".", "!", or "?" followed by
+// white space, after any closing quotes or brackets, or at the end of a line.
+func sentences(code string) [][2]int {
+	var units [][2]int
+	add := func(start, end int) {
+		for start < end && isSpaceByte(code[start]) {
+			start++
+		}
+		for end > start && isSpaceByte(code[end-1]) {
+			end--
+		}
+		if start < end {
+			units = append(units, [2]int{start, end})
+		}
+	}
+
+	for offset := 0; offset < len(code); {
+		end := strings.IndexByte(code[offset:], '\n')
+		if end < 0 {
+			end = len(code)
+		} else {
+			end += offset
+		}
+
+		start := offset
+		for i := offset; i < end; i++ {
+			if c := code[i]; c != '.' && c != '!' && c != '?' {
+				continue
+			}
+			j := i + 1
+			for j < end && strings.IndexByte(`"')]`, code[j]) >= 0 {
+				j++
+			}
+			if j < end && isSpaceByte(code[j]) {
+				add(start, j)
+				start, i = j, j
+			}
+		}
+		add(start, end)
+		offset = end + 1
+	}
+	return units
+}
+
+// isSpaceByte reports whether b is ASCII white space.
+func isSpaceByte(b byte) bool {
+	return b == ' ' || b == '\t' || b == '\r' || b == '\n' || b == '\f' || b == '\v'
+}
+
+// isAnchor reports whether a sentence of the given tokens may end a chunk,
+// with a chance that puts spread tokens between anchors on average. It
+// depends only on the sentence, so it holds wherever the sentence moves.
+func isAnchor(sentence string, tokens, spread int) bool {
+	if tokens >= spread {
+		return true
+	}
+	h := fnv.New64a()
+	h.Write([]byte(sentence))
+	return float64(h.Sum64()) < float64(tokens)/float64(spread)*math.MaxUint64
+}
+
+// splitStable splits prose between sentences at the anchors of
+// WithStableBoundaries.
+func (c *castChunker) splitStable(code string, cfg *config) (chunks []Chunk, err error) {
+	endSpan := cfg.startSpan(SpanSplit)
+	defer func() { endSpan(err) }()
+
+	minSize := int(float64(cfg.maxSize) * stableMinShare)
+	spread := max(int(float64(cfg.maxSize)*stableAnchorSpread), 1)
+
+	// Lines are counted from the last offset looked up, as chunks are in order
+	line, lineAt := 1, 0
+	lineAtOffset := func(offset int) int {
+		line, lineAt = lineOf(code, lineAt, line, offset), offset
+		return line
+	}
+
+	start, end, size := -1, 0, 0
+	flush := func() {
+		if start >= 0 {
+			startLine := lineAtOffset(start)
+			chunks = append(chunks, genericChunk(code, start, end, startLine, lineAtOffset(end), cfg.language))
+		}
+		start, size = -1, 0
+	}
+
+	for i, unit := range sentences(code) {
+		if i%1024 == 0 {
+			if err := cfg.ctx.Err(); err != nil {
+				return nil, err
+			}
+		}
+		if err := cfg.step(); err != nil {
+			return nil, err
+		}
+
+		text := code[unit[0]:unit[1]]
+		tokens, err := cfg.tokenCounter.CountTokens(text)
+		if err != nil {
+			return nil, fmt.Errorf("%w: %w", ErrNodeSize, err)
+		}
+
+		heading := cfg.language == languages.Markdown && text[0] == '#' && (unit[0] == 0 || code[unit[0]-1] == '\n')
+		if start >= 0 && (size+tokens > cfg.maxSize || heading) {
+			flush()
+		}
+		if start < 0 {
+			start = unit[0]
+		}
+		end = unit[1]
+		size += tokens
+		if size >= minSize && isAnchor(text, tokens, spread) {
+			flush()
+		}
+	}
+	flush()
+	return chunks, nil
+}
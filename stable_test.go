@@ -0,0 +1,86 @@
+package chunkx
+
+import (
+	"fmt"
+	"math/rand/v2"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// randomProse returns Markdown of random sentences in paragraphs under
+// headings, with a heading every ten paragraphs.
+func randomProse(seed uint64, paragraphs int) string {
+	r := rand.New(rand.NewPCG(seed, seed))
+	var b strings.Builder
+	for p := range paragraphs {
+		if p%10 == 0 {
+			fmt.Fprintf(&b, "# Section %d\n\n", p/10+1)
+		}
+		for s := range r.IntN(4) + 2 {
+			if s > 0 {
+				b.WriteByte(' ')
+			}
+			for w := range r.IntN(10) + 3 {
+				if w > 0 {
+					b.WriteByte(' ')
+				}
+				fmt.Fprintf(&b, "word%d", r.IntN(500))
+			}
+			b.WriteByte(".!?"[r.IntN(3)])
+		}
+		b.WriteString("\n\n")
+	}
+	return b.String()
+}
+
+func TestWithStableBoundaries(t *testing.T) {
+	code := randomProse(1, 100)
+	opts := []Option{WithLanguage(languages.Markdown), WithMaxSize(60), WithStableBoundaries()}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if err := ValidateChunks(chunks, code, opts...); err != nil {
+		t.Fatalf("invalid chunks: %v", err)
+	}
+	for i, chunk := range chunks {
+		if strings.Contains(chunk.Content[1:], "\n# ") {
+			t.Errorf("chunk %d: expected headings to start chunks, got %q", i, chunk.Content)
+		}
+	}
+
+	// Lengthening a sentence early on only changes the chunks around it
+	edit := strings.Index(code, ".") + 1
+	edited := code[:edit] + " An inserted sentence of seven words." + code[edit:]
+	editedChunks, err := NewChunker().Chunk(edited, opts...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	ids := make(map[string]bool, len(chunks))
+	for _, chunk := range chunks {
+		ids[chunk.ID()] = true
+	}
+	var changed int
+	for _, chunk := range editedChunks {
+		if !ids[chunk.ID()] {
+			changed++
+		}
+	}
+	if changed > 4 {
+		t.Errorf("expected at most 4 changed chunks, got %d of %d", changed, len(editedChunks))
+	}
+}
+
+func TestSentences(t *testing.T) {
+	code := "One sentence. \"Another!\" A third?\n  An e.g. line\n\n"
+	var got []string
+	for _, s := range sentences(code) {
+		got = append(got, code[s[0]:s[1]])
+	}
+	want := []string{"One sentence.", `"Another!"`, "A third?", "An e.g.", "line"}
+	if strings.Join(got, "|") != strings.Join(want, "|") {
+		t.Errorf("got sentences %q, want %q", got, want)
+	}
+}
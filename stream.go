@@ -0,0 +1,157 @@
+package chunkx
+
+import (
+	"bufio"
+	"bytes"
+	"context"
+	"errors"
+	"fmt"
+	"io"
+	"iter"
+	"strings"
+	"unicode/utf8"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// streamPieceBytes is the most ChunkReader reads of a line at once.
+const streamPieceBytes = 64 << 10
+
+// ChunkReader chunks an unbounded stream line by line, yielding each chunk as
+// soon as it is complete. Lines are read in pieces of at most 64 KiB, so
+// memory use is bounded by the maximum chunk size and that piece size rather
+// than by the length of the stream or of its lines, and it suits logs and
+// pipes that never end.
+//
+// Chunks match those of generic chunking of the whole input, except that
+// overlap is not applied and that a line longer than a piece may end a chunk
+// in its middle. The language defaults to languages.Generic and only labels
+// the chunks. Iteration stops at the first error, including cancellation of
+// ctx.
+func ChunkReader(ctx context.Context, r io.Reader, opts ...Option) iter.Seq2[Chunk, error] {
+	cfg := newDefaultConfig()
+
+	for _, opt := range opts {
+		opt(cfg)
+	}
+
+	language := cfg.language
+	if language == "" {
+		language = languages.Generic
+	}
+
+	return func(yield func(Chunk, error) bool) {
+		br := bufio.NewReaderSize(r, streamPieceBytes)
+
+		var current strings.Builder
+		currentPieces := 0
+		currentSize := 0
+		currentStartLine := 1
+		currentEndLine := 1
+		currentStartByte := 0
+		offset := 0
+
+		flush := func() bool {
+			content := current.String()
+			current.Reset()
+			chunk := Chunk{
+				Content:   content,
+				StartLine: currentStartLine,
+				EndLine:   currentEndLine,
+				StartByte: currentStartByte,
+				EndByte:   currentStartByte + len(content),
+				NodeTypes: []string{"generic"},
+				Language:  language,
+			}
+			return yield(chunk, nil)
+		}
+
+		// Pieces of a line after its first continue it without a newline
+		continued := false
+		for lineNum := 1; ; {
+			if err := ctx.Err(); err != nil {
+				yield(Chunk{}, err)
+				return
+			}
+
+			piece, ended, err := readPiece(br)
+			last := err != nil
+			if last && !errors.Is(err, io.EOF) {
+				yield(Chunk{}, err)
+				return
+			}
+
+			pieceSize, err := cfg.tokenCounter.CountTokens(piece)
+			if err != nil {
+				yield(Chunk{}, fmt.Errorf("%w: %w", ErrNodeSize, err))
+				return
+			}
+
+			// If adding this piece would exceed max size and we have content
+			if currentPieces > 0 && currentSize+pieceSize > cfg.maxSize {
+				if !flush() {
+					return
+				}
+				currentPieces = 0
+				currentSize = 0
+				currentStartLine = lineNum
+				currentStartByte = offset
+			}
+
+			if currentPieces > 0 && !continued {
+				current.WriteByte('\n')
+			}
+			current.WriteString(piece)
+			currentPieces++
+			currentSize += pieceSize
+			currentEndLine = lineNum
+			offset += len(piece)
+			if ended {
+				offset++
+				lineNum++
+			}
+			continued = !ended
+
+			if last {
+				break
+			}
+		}
+
+		// Don't forget the last chunk
+		flush()
+	}
+}
+
+// readPiece reads the next line of br without its newline, or only its next
+// streamPieceBytes or so if it is longer, cut between runes. Ended reports
+// whether the piece ends its line, and the error is io.EOF for the last line.
+func readPiece(br *bufio.Reader) (piece string, ended bool, err error) {
+	data, err := br.Peek(streamPieceBytes)
+	if err != nil && !errors.Is(err, io.EOF) {
+		return "", true, err
+	}
+	if i := bytes.IndexByte(data, '\n'); i >= 0 {
+		piece = string(data[:i])
+		_, _ = br.Discard(i + 1)
+		return piece, true, nil
+	}
+	if err != nil {
+		piece = string(data)
+		_, _ = br.Discard(len(data))
+		return piece, true, err
+	}
+
+	// Keep a rune cut by the end of the buffer for the next piece
+	n := len(data)
+	for i := n - 1; i >= max(n-utf8.UTFMax, 0); i-- {
+		if utf8.RuneStart(data[i]) {
+			if !utf8.FullRune(data[i:]) && i > 0 {
+				n = i
+			}
+			break
+		}
+	}
+	piece = string(data[:n])
+	_, _ = br.Discard(n)
+	return piece, false, nil
+}
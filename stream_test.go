@@ -0,0 +1,92 @@
+package chunkx
+
+import (
+	"context"
+	"errors"
+	"reflect"
+	"strings"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestChunkReader_MatchesGenericChunking(t *testing.T) {
+	inputs := []string{
+		"",
+		"one line",
+		"line one\nline two\n",
+		"a b c\nd e f\n\ng h i\nj k l m n o p\nq\n",
+		strings.Repeat("word word word\n", 100),
+	}
+
+	chunker := NewChunker()
+	for _, input := range inputs {
+		want, err := chunker.Chunk(input, WithLanguage(languages.Generic), WithMaxSize(7))
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+
+		var got []Chunk
+		for chunk, err := range ChunkReader(context.Background(), strings.NewReader(input), WithMaxSize(7)) {
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			got = append(got, chunk)
+		}
+
+		if !reflect.DeepEqual(got, want) {
+			t.Errorf("ChunkReader(%q) = %+v, want %+v", input, got, want)
+		}
+	}
+}
+
+func TestChunkReader_LongLines(t *testing.T) {
+	// A line of multibyte runes longer than a piece, between short lines
+	input := "a\n" + strings.Repeat("é", streamPieceBytes) + "\nb\n"
+
+	var got []Chunk
+	for chunk, err := range ChunkReader(context.Background(), strings.NewReader(input), WithMaxSize(1)) {
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		got = append(got, chunk)
+	}
+	if len(got) != 4 {
+		t.Fatalf("expected the long line in two chunks, got %d chunks", len(got))
+	}
+	if got[1].StartLine != 2 || got[2].EndLine != 2 || len(got[1].Content) > streamPieceBytes {
+		t.Errorf("expected line 2 in pieces, got lines %d-%d of %d bytes", got[1].StartLine, got[2].EndLine, len(got[1].Content))
+	}
+	if err := ValidateChunks(got, input, WithLanguage(languages.Generic), WithMaxSize(1)); err != nil {
+		t.Errorf("invalid chunks: %v", err)
+	}
+}
+
+func TestChunkReader_StopsEarly(t *testing.T) {
+	input := strings.Repeat("some words here\n", 1000)
+
+	count := 0
+	for _, err := range ChunkReader(context.Background(), strings.NewReader(input), WithMaxSize(3)) {
+		if err != nil {
+			t.Fatalf("unexpected error: %v", err)
+		}
+		count++
+		if count == 5 {
+			break
+		}
+	}
+	if count != 5 {
+		t.Errorf("expected to stop after 5 chunks, got %d", count)
+	}
+}
+
+func TestChunkReader_Cancelled(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+
+	for _, err := range ChunkReader(ctx, strings.NewReader("a\nb\n")) {
+		if !errors.Is(err, context.Canceled) {
+			t.Errorf("expected context.Canceled, got %v", err)
+		}
+	}
+}
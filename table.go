@@ -0,0 +1,20 @@
+package chunkx
+
+// MetadataTableHeader is set to the header rows of a table, as in the
+// source, on the chunks WithTableHeaders split from it after the first one,
+// which holds the header itself.
+const MetadataTableHeader = "table_header"
+
+// WithTableHeaders splits a Markdown or HTML table that exceeds the maximum
+// size into groups of whole rows, rather than along whatever children of the
+// table fit. Each group after the first records the header rows of the table
+// under MetadataTableHeader, and is sized so that it still fits with them, so
+// the header can be put back in front of its content to embed every group as
+// a table of its own. Rows too large for a chunk are split further. Tables
+// without a header row, and tables that fit, are chunked as usual. Tables are
+// found in the syntax tree, so builds without cgo ignore this option.
+func WithTableHeaders() Option {
+	return func(c *config) {
+		c.tableHeaders = true
+	}
+}
@@ -0,0 +1,89 @@
+package chunkx
+
+import "context"
+
+// Span names started by chunkx. File and read spans are started by a
+// ChunkPipeline; parse and split spans by every call that chunks code.
+const (
+	SpanFile  = "chunkx.file"  // Reading and chunking a file, with a "path" attribute
+	SpanRead  = "chunkx.read"  // Reading a file from disk
+	SpanParse = "chunkx.parse" // Parsing code, with a "language" attribute
+	SpanSplit = "chunkx.split" // Splitting parsed code or lines into chunks
+)
+
+// Metric names recorded by a ChunkPipeline. Counters only grow; backends
+// derive rates such as files or chunks per second from them.
+const (
+	MetricFiles           = "chunkx.files"            // Counter of files chunked
+	MetricFilesFailed     = "chunkx.files.failed"     // Counter of files that failed
+	MetricChunks          = "chunkx.chunks"           // Counter of chunks produced
+	MetricOversizedChunks = "chunkx.chunks.oversized" // Counter of chunks over the maximum size
+	MetricFileDuration    = "chunkx.file.duration"    // Histogram of seconds spent per file
+)
+
+// Attr is a key-value attribute of a span or metric.
+type Attr struct {
+	Key   string
+	Value string
+}
+
+// Tracer starts spans around the stages of chunking, so production indexers
+// can see where time goes. Its shape follows OpenTelemetry's trace.Tracer, so
+// an adapter only has to convert attributes and record errors on End.
+//
+// Implementations must be safe for concurrent use.
+type Tracer interface {
+	// Start starts a span as a child of any span in ctx and returns a context
+	// carrying the new span.
+	Start(ctx context.Context, name string, attrs ...Attr) (context.Context, Span)
+}
+
+// Span is a span started by a Tracer.
+type Span interface {
+	// End ends the span, recording err if it is not nil.
+	End(err error)
+}
+
+// Meter records metrics of a ChunkPipeline. Its shape follows OpenTelemetry's
+// Int64Counter and Float64Histogram instruments.
+//
+// Implementations must be safe for concurrent use.
+type Meter interface {
+	// Add adds delta to the named counter.
+	Add(name string, delta int64, attrs ...Attr)
+
+	// Record records a value of the named histogram.
+	Record(name string, value float64, attrs ...Attr)
+}
+
+// WithTracer traces chunking with t. Without it no spans are started.
+func WithTracer(t Tracer) Option {
+	return func(c *config) {
+		c.tracer = t
+	}
+}
+
+// WithMeter records the metrics of a ChunkPipeline with m. Without it no
+// metrics are recorded, and oversized chunks aren't measured. It has no
+// effect on single-file chunking.
+func WithMeter(m Meter) Option {
+	return func(c *config) {
+		c.meter = m
+	}
+}
+
+// startSpan starts a span with the configured tracer, if any, and makes it
+// the parent of the spans started until the returned function ends it.
+func (cfg *config) startSpan(name string, attrs ...Attr) func(err error) {
+	if cfg.tracer == nil {
+		return func(error) {}
+	}
+
+	parent := cfg.ctx
+	ctx, span := cfg.tracer.Start(parent, name, attrs...)
+	cfg.ctx = ctx
+	return func(err error) {
+		cfg.ctx = parent
+		span.End(err)
+	}
+}
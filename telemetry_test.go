@@ -0,0 +1,86 @@
+package chunkx
+
+import (
+	"context"
+	"slices"
+	"sync"
+	"testing"
+)
+
+// spanKey is the context key of the current span in testTracer.
+type spanKey struct{}
+
+// testTracer records the spans started and the name of their parents.
+type testTracer struct {
+	mu      sync.Mutex
+	parents map[string][]string
+}
+
+func (t *testTracer) Start(ctx context.Context, name string, attrs ...Attr) (context.Context, Span) {
+	parent, _ := ctx.Value(spanKey{}).(string)
+
+	t.mu.Lock()
+	defer t.mu.Unlock()
+	if t.parents == nil {
+		t.parents = make(map[string][]string)
+	}
+	t.parents[name] = append(t.parents[name], parent)
+	return context.WithValue(ctx, spanKey{}, name), testSpan{}
+}
+
+type testSpan struct{}
+
+func (testSpan) End(error) {}
+
+// testMeter sums the counters added.
+type testMeter struct {
+	mu       sync.Mutex
+	counters map[string]int64
+	records  int
+}
+
+func (m *testMeter) Add(name string, delta int64, attrs ...Attr) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	if m.counters == nil {
+		m.counters = make(map[string]int64)
+	}
+	m.counters[name] += delta
+}
+
+func (m *testMeter) Record(name string, value float64, attrs ...Attr) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	m.records++
+}
+
+func TestChunkPipeline_Telemetry(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"a.txt": "one two three\nfour\n",
+		"b.txt": "five\n",
+	})
+
+	tracer := &testTracer{}
+	meter := &testMeter{}
+	chunks := 0
+	for result := range NewChunkPipeline(2, WithMaxSize(2), WithTracer(tracer), WithMeter(meter)).RunDir(context.Background(), dir) {
+		if result.Err != nil {
+			t.Fatalf("unexpected error: %v", result.Err)
+		}
+		chunks += len(result.Chunks)
+	}
+
+	for name, want := range map[string]string{SpanFile: "", SpanRead: SpanFile, SpanSplit: SpanFile} {
+		parents := tracer.parents[name]
+		if len(parents) != 2 || slices.ContainsFunc(parents, func(p string) bool { return p != want }) {
+			t.Errorf("%s spans have parents %q, want two under %q", name, parents, want)
+		}
+	}
+
+	if meter.counters[MetricFiles] != 2 || meter.counters[MetricChunks] != int64(chunks) || meter.records != 2 {
+		t.Errorf("unexpected metrics %v, %d records", meter.counters, meter.records)
+	}
+	if meter.counters[MetricOversizedChunks] != 1 {
+		t.Errorf("oversized = %d, want 1", meter.counters[MetricOversizedChunks])
+	}
+}
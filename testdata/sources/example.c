@@ -0,0 +1,66 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+/* A growable buffer of bytes. */
+typedef struct {
+    char *data;
+    size_t len;
+    size_t cap;
+} buffer;
+
+static void buffer_init(buffer *b) {
+    b->data = NULL;
+    b->len = 0;
+    b->cap = 0;
+}
+
+/* Ensures room for n more bytes, doubling the capacity as needed. */
+static int buffer_reserve(buffer *b, size_t n) {
+    if (b->len + n <= b->cap) {
+        return 0;
+    }
+    size_t cap = b->cap ? b->cap : 64;
+    while (cap < b->len + n) {
+        cap *= 2;
+    }
+    char *data = realloc(b->data, cap);
+    if (data == NULL) {
+        return -1;
+    }
+    b->data = data;
+    b->cap = cap;
+    return 0;
+}
+
+static int buffer_append(buffer *b, const char *s, size_t n) {
+    if (buffer_reserve(b, n) != 0) {
+        return -1;
+    }
+    memcpy(b->data + b->len, s, n);
+    b->len += n;
+    return 0;
+}
+
+static void buffer_free(buffer *b) {
+    free(b->data);
+    buffer_init(b);
+}
+
+int main(int argc, char **argv) {
+    buffer b;
+    buffer_init(&b);
+
+    for (int i = 1; i < argc; i++) {
+        if (buffer_append(&b, argv[i], strlen(argv[i])) != 0 || buffer_append(&b, " ", 1) != 0) {
+            fprintf(stderr, "out of memory\n");
+            buffer_free(&b);
+            return EXIT_FAILURE;
+        }
+    }
+
+    fwrite(b.data, 1, b.len, stdout);
+    putchar('\n');
+    buffer_free(&b);
+    return EXIT_SUCCESS;
+}
@@ -0,0 +1,64 @@
+using System;
+using System.Collections.Generic;
+using System.Linq;
+
+namespace Inventory
+{
+    /// <summary>An item held in stock.</summary>
+    public record Item(string Sku, string Name, decimal Price, int Quantity);
+
+    public interface IInventory
+    {
+        void Add(Item item);
+        bool Remove(string sku, int quantity);
+        decimal TotalValue();
+    }
+
+    /// <summary>Keeps items in memory, keyed by SKU.</summary>
+    public class MemoryInventory : IInventory
+    {
+        private readonly Dictionary<string, Item> _items = new();
+
+        public void Add(Item item)
+        {
+            if (_items.TryGetValue(item.Sku, out var existing))
+            {
+                _items[item.Sku] = existing with { Quantity = existing.Quantity + item.Quantity };
+                return;
+            }
+            _items[item.Sku] = item;
+        }
+
+        public bool Remove(string sku, int quantity)
+        {
+            if (!_items.TryGetValue(sku, out var item) || item.Quantity < quantity)
+            {
+                return false;
+            }
+            _items[sku] = item with { Quantity = item.Quantity - quantity };
+            return true;
+        }
+
+        public decimal TotalValue() => _items.Values.Sum(i => i.Price * i.Quantity);
+
+        public IEnumerable<Item> LowStock(int threshold) =>
+            _items.Values.Where(i => i.Quantity < threshold).OrderBy(i => i.Quantity);
+    }
+
+    public static class Program
+    {
+        public static void Main()
+        {
+            var inventory = new MemoryInventory();
+            inventory.Add(new Item("A-1", "Widget", 2.50m, 100));
+            inventory.Add(new Item("B-2", "Gadget", 10.00m, 3));
+            inventory.Remove("A-1", 40);
+
+            Console.WriteLine($"Total value: {inventory.TotalValue():C}");
+            foreach (var item in inventory.LowStock(10))
+            {
+                Console.WriteLine($"Low stock: {item.Name} ({item.Quantity})");
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+package deploy
+
+import "strings"
+
+// #Service describes a deployable service.
+#Service: {
+	name:     string & =~"^[a-z][a-z0-9-]*$"
+	image:    string
+	replicas: int & >=1 & <=20 | *2
+	port:     int & >0 & <65536 | *8080
+	env: [string]: string
+	resources: {
+		cpu:    string | *"250m"
+		memory: string | *"256Mi"
+	}
+}
+
+// #Environment sets defaults shared by every service in it.
+#Environment: {
+	name:   "staging" | "production"
+	domain: string
+	services: [Name=string]: #Service & {name: Name}
+}
+
+staging: #Environment & {
+	name:   "staging"
+	domain: "staging.example.com"
+	services: {
+		api: {
+			image: "registry.example.com/api:1.4.2"
+			env: LOG_LEVEL: "debug"
+		}
+		worker: {
+			image:    "registry.example.com/worker:1.4.2"
+			replicas: 1
+		}
+	}
+}
+
+production: #Environment & {
+	name:   "production"
+	domain: "example.com"
+	services: {
+		api: {
+			image:    "registry.example.com/api:1.4.1"
+			replicas: 6
+			env: LOG_LEVEL: "info"
+			resources: memory: "1Gi"
+		}
+	}
+}
+
+hosts: [for env in [staging, production] for name, _ in env.services {
+	strings.Join([name, env.domain], ".")
+}]
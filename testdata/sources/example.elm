@@ -0,0 +1,92 @@
+module Main exposing (main)
+
+import Browser
+import Html exposing (Html, button, div, input, li, text, ul)
+import Html.Attributes exposing (placeholder, value)
+import Html.Events exposing (onClick, onInput)
+
+
+-- MODEL
+
+
+type alias Todo =
+    { id : Int
+    , title : String
+    , done : Bool
+    }
+
+
+type alias Model =
+    { todos : List Todo
+    , draft : String
+    , nextId : Int
+    }
+
+
+init : Model
+init =
+    { todos = [], draft = "", nextId = 1 }
+
+
+
+-- UPDATE
+
+
+type Msg
+    = UpdateDraft String
+    | AddTodo
+    | Toggle Int
+
+
+update : Msg -> Model -> Model
+update msg model =
+    case msg of
+        UpdateDraft draft ->
+            { model | draft = draft }
+
+        AddTodo ->
+            if String.isEmpty (String.trim model.draft) then
+                model
+
+            else
+                { model
+                    | todos = model.todos ++ [ Todo model.nextId model.draft False ]
+                    , draft = ""
+                    , nextId = model.nextId + 1
+                }
+
+        Toggle id ->
+            { model | todos = List.map (toggleIf id) model.todos }
+
+
+toggleIf : Int -> Todo -> Todo
+toggleIf id todo =
+    if todo.id == id then
+        { todo | done = not todo.done }
+
+    else
+        todo
+
+
+
+-- VIEW
+
+
+view : Model -> Html Msg
+view model =
+    div []
+        [ input [ placeholder "What needs doing?", value model.draft, onInput UpdateDraft ] []
+        , button [ onClick AddTodo ] [ text "Add" ]
+        , ul [] (List.map viewTodo model.todos)
+        ]
+
+
+viewTodo : Todo -> Html Msg
+viewTodo todo =
+    li [ onClick (Toggle todo.id) ]
+        [ text (if todo.done then "✓ " ++ todo.title else todo.title) ]
+
+
+main : Program () Model Msg
+main =
+    Browser.sandbox { init = init, update = update, view = view }
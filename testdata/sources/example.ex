@@ -0,0 +1,55 @@
+defmodule Shop.Cart do
+  @moduledoc """
+  A shopping cart holding line items and applying discounts.
+  """
+
+  alias Shop.Cart.LineItem
+
+  defstruct items: %{}, discount: nil
+
+  @type t :: %__MODULE__{items: %{String.t() => LineItem.t()}, discount: {atom(), number()} | nil}
+
+  @doc "Creates an empty cart."
+  @spec new() :: t()
+  def new, do: %__MODULE__{}
+
+  @doc "Adds quantity units of a product, merging with an existing line."
+  @spec add(t(), String.t(), pos_integer(), Decimal.t()) :: t()
+  def add(%__MODULE__{items: items} = cart, sku, quantity, price) when quantity > 0 do
+    items =
+      Map.update(items, sku, %LineItem{sku: sku, quantity: quantity, price: price}, fn item ->
+        %LineItem{item | quantity: item.quantity + quantity}
+      end)
+
+    %{cart | items: items}
+  end
+
+  @doc "Removes a product from the cart."
+  def remove(%__MODULE__{items: items} = cart, sku) do
+    %{cart | items: Map.delete(items, sku)}
+  end
+
+  def apply_discount(cart, {:percent, value}) when value in 0..100 do
+    %{cart | discount: {:percent, value}}
+  end
+
+  def apply_discount(cart, {:fixed, amount}) when amount >= 0 do
+    %{cart | discount: {:fixed, amount}}
+  end
+
+  @doc "Returns the total price after discounts."
+  def total(%__MODULE__{items: items, discount: discount}) do
+    subtotal =
+      items
+      |> Map.values()
+      |> Enum.reduce(Decimal.new(0), fn item, acc ->
+        Decimal.add(acc, Decimal.mult(item.price, item.quantity))
+      end)
+
+    case discount do
+      nil -> subtotal
+      {:percent, value} -> Decimal.mult(subtotal, Decimal.div(100 - value, 100))
+      {:fixed, amount} -> Decimal.max(Decimal.sub(subtotal, amount), 0)
+    end
+  end
+end
@@ -0,0 +1,56 @@
+package com.example.reports
+
+import groovy.transform.CompileStatic
+import groovy.transform.ToString
+
+@ToString(includeNames = true)
+class Sale {
+    String region
+    String product
+    BigDecimal amount
+    Date date
+}
+
+@CompileStatic
+class SalesReport {
+    private final List<Sale> sales
+
+    SalesReport(List<Sale> sales) {
+        this.sales = sales
+    }
+
+    /** Returns the total amount sold per region, highest first. */
+    Map<String, BigDecimal> totalsByRegion() {
+        Map<String, BigDecimal> totals = [:]
+        sales.each { Sale sale ->
+            totals[sale.region] = (totals[sale.region] ?: 0G) + sale.amount
+        }
+        totals.sort { a, b -> b.value <=> a.value }
+    }
+
+    List<String> topProducts(int limit) {
+        sales.groupBy { it.product }
+             .collectEntries { product, items -> [product, items.sum { it.amount }] }
+             .sort { -it.value }
+             .take(limit)
+             .keySet()
+             .toList()
+    }
+
+    String render() {
+        def lines = totalsByRegion().collect { region, total ->
+            String.format('%-10s %12.2f', region, total)
+        }
+        (['Region         Total'] + lines).join('\n')
+    }
+}
+
+def sales = [
+    new Sale(region: 'north', product: 'widget', amount: 120.50G, date: new Date()),
+    new Sale(region: 'south', product: 'gadget', amount: 75.00G, date: new Date()),
+    new Sale(region: 'north', product: 'gadget', amount: 310.25G, date: new Date()),
+]
+
+def report = new SalesReport(sales)
+println report.render()
+println "Top products: ${report.topProducts(2).join(', ')}"
@@ -0,0 +1,58 @@
+package com.example.weather
+
+import kotlinx.coroutines.async
+import kotlinx.coroutines.awaitAll
+import kotlinx.coroutines.coroutineScope
+import kotlinx.coroutines.runBlocking
+
+/** A temperature reading from one station. */
+data class Reading(val station: String, val celsius: Double) {
+    val fahrenheit: Double
+        get() = celsius * 9 / 5 + 32
+}
+
+sealed interface Forecast {
+    data class Sunny(val high: Double) : Forecast
+    data class Rainy(val millimeters: Double) : Forecast
+    object Unknown : Forecast
+}
+
+interface WeatherClient {
+    suspend fun reading(station: String): Reading?
+}
+
+class WeatherService(private val client: WeatherClient) {
+
+    /** Fetches readings from every station concurrently, skipping failures. */
+    suspend fun readings(stations: List<String>): List<Reading> = coroutineScope {
+        stations
+            .map { station -> async { runCatching { client.reading(station) }.getOrNull() } }
+            .awaitAll()
+            .filterNotNull()
+    }
+
+    fun forecast(readings: List<Reading>): Forecast {
+        if (readings.isEmpty()) return Forecast.Unknown
+        val average = readings.map { it.celsius }.average()
+        return when {
+            average > 20 -> Forecast.Sunny(high = readings.maxOf { it.celsius })
+            average < 5 -> Forecast.Rainy(millimeters = 12.0)
+            else -> Forecast.Unknown
+        }
+    }
+}
+
+fun describe(forecast: Forecast): String = when (forecast) {
+    is Forecast.Sunny -> "Sunny, up to %.1f°C".format(forecast.high)
+    is Forecast.Rainy -> "Rain, about ${forecast.millimeters} mm"
+    Forecast.Unknown -> "No forecast"
+}
+
+fun main() = runBlocking {
+    val client = object : WeatherClient {
+        override suspend fun reading(station: String) = Reading(station, station.length * 3.0)
+    }
+    val service = WeatherService(client)
+    val readings = service.readings(listOf("oslo", "lisbon", "cairo"))
+    println(describe(service.forecast(readings)))
+}
@@ -0,0 +1,66 @@
+-- A small least-recently-used cache.
+local LRU = {}
+LRU.__index = LRU
+
+--- Creates a cache holding at most capacity entries.
+function LRU.new(capacity)
+  assert(capacity > 0, "capacity must be positive")
+  local self = setmetatable({}, LRU)
+  self.capacity = capacity
+  self.size = 0
+  self.items = {}
+  self.head = { key = nil }
+  self.tail = { key = nil }
+  self.head.next, self.tail.prev = self.tail, self.head
+  return self
+end
+
+local function unlink(node)
+  node.prev.next = node.next
+  node.next.prev = node.prev
+end
+
+function LRU:push_front(node)
+  node.next = self.head.next
+  node.prev = self.head
+  self.head.next.prev = node
+  self.head.next = node
+end
+
+function LRU:get(key)
+  local node = self.items[key]
+  if not node then
+    return nil
+  end
+  unlink(node)
+  self:push_front(node)
+  return node.value
+end
+
+function LRU:set(key, value)
+  local node = self.items[key]
+  if node then
+    node.value = value
+    unlink(node)
+  else
+    if self.size == self.capacity then
+      local oldest = self.tail.prev
+      unlink(oldest)
+      self.items[oldest.key] = nil
+      self.size = self.size - 1
+    end
+    node = { key = key, value = value }
+    self.items[key] = node
+    self.size = self.size + 1
+  end
+  self:push_front(node)
+end
+
+local cache = LRU.new(2)
+cache:set("a", 1)
+cache:set("b", 2)
+cache:get("a")
+cache:set("c", 3)
+print(cache:get("b"), cache:get("a"), cache:get("c"))
+
+return LRU
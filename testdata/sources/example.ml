@@ -0,0 +1,53 @@
+(* A binary search tree of integers with an in-order fold. *)
+
+type tree =
+  | Leaf
+  | Node of tree * int * tree
+
+let empty = Leaf
+
+(** [insert x t] adds [x] to [t], keeping duplicates out. *)
+let rec insert x = function
+  | Leaf -> Node (Leaf, x, Leaf)
+  | Node (l, v, r) as t ->
+    if x < v then Node (insert x l, v, r)
+    else if x > v then Node (l, v, insert x r)
+    else t
+
+let rec mem x = function
+  | Leaf -> false
+  | Node (l, v, r) -> if x = v then true else if x < v then mem x l else mem x r
+
+let rec fold f acc = function
+  | Leaf -> acc
+  | Node (l, v, r) ->
+    let acc = fold f acc l in
+    let acc = f acc v in
+    fold f acc r
+
+let to_list t = List.rev (fold (fun acc x -> x :: acc) [] t)
+
+let of_list xs = List.fold_left (fun t x -> insert x t) empty xs
+
+let rec height = function
+  | Leaf -> 0
+  | Node (l, _, r) -> 1 + max (height l) (height r)
+
+module Stats = struct
+  let sum t = fold ( + ) 0 t
+
+  let count t = fold (fun n _ -> n + 1) 0 t
+
+  let mean t =
+    match count t with
+    | 0 -> None
+    | n -> Some (float_of_int (sum t) /. float_of_int n)
+end
+
+let () =
+  let t = of_list [ 5; 3; 8; 1; 4; 7; 9; 3 ] in
+  Printf.printf "sorted: %s\n" (String.concat ", " (List.map string_of_int (to_list t)));
+  Printf.printf "height: %d, contains 4: %b\n" (height t) (mem 4 t);
+  match Stats.mean t with
+  | Some m -> Printf.printf "mean: %.2f\n" m
+  | None -> print_endline "empty"
@@ -0,0 +1,55 @@
+<?php
+
+declare(strict_types=1);
+
+namespace App\Http;
+
+use InvalidArgumentException;
+
+/**
+ * A minimal router matching request paths against patterns like /users/{id}.
+ */
+final class Router
+{
+    /** @var array<string, array<int, array{string, callable}>> */
+    private array $routes = [];
+
+    public function get(string $pattern, callable $handler): self
+    {
+        return $this->add('GET', $pattern, $handler);
+    }
+
+    public function post(string $pattern, callable $handler): self
+    {
+        return $this->add('POST', $pattern, $handler);
+    }
+
+    private function add(string $method, string $pattern, callable $handler): self
+    {
+        if ($pattern === '' || $pattern[0] !== '/') {
+            throw new InvalidArgumentException("Pattern must start with a slash: $pattern");
+        }
+        $regex = '#^' . preg_replace('#\{(\w+)\}#', '(?P<$1>[^/]+)', $pattern) . '$#';
+        $this->routes[$method][] = [$regex, $handler];
+        return $this;
+    }
+
+    public function dispatch(string $method, string $path): string
+    {
+        foreach ($this->routes[$method] ?? [] as [$regex, $handler]) {
+            if (preg_match($regex, $path, $matches)) {
+                $params = array_filter($matches, 'is_string', ARRAY_FILTER_USE_KEY);
+                return (string) $handler(...$params);
+            }
+        }
+        http_response_code(404);
+        return 'Not Found';
+    }
+}
+
+$router = (new Router())
+    ->get('/', fn () => 'Home')
+    ->get('/users/{id}', fn (string $id) => "User $id")
+    ->post('/users', fn () => 'Created');
+
+echo $router->dispatch($_SERVER['REQUEST_METHOD'] ?? 'GET', parse_url($_SERVER['REQUEST_URI'] ?? '/', PHP_URL_PATH));
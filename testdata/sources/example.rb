@@ -0,0 +1,61 @@
+# frozen_string_literal: true
+
+require "json"
+require "time"
+
+module Billing
+  class Error < StandardError; end
+
+  # An invoice line: a description, a quantity, and a unit price in cents.
+  LineItem = Struct.new(:description, :quantity, :unit_cents, keyword_init: true) do
+    def total_cents
+      quantity * unit_cents
+    end
+  end
+
+  class Invoice
+    attr_reader :number, :customer, :items, :issued_at
+
+    TAX_RATE = 0.2
+
+    def initialize(number:, customer:, issued_at: Time.now)
+      raise Error, "invoice number is required" if number.to_s.empty?
+
+      @number = number
+      @customer = customer
+      @issued_at = issued_at
+      @items = []
+    end
+
+    def add(description, quantity: 1, unit_cents:)
+      items << LineItem.new(description: description, quantity: quantity, unit_cents: unit_cents)
+      self
+    end
+
+    def subtotal_cents
+      items.sum(&:total_cents)
+    end
+
+    def tax_cents
+      (subtotal_cents * TAX_RATE).round
+    end
+
+    def total_cents
+      subtotal_cents + tax_cents
+    end
+
+    def to_json(*args)
+      {
+        number: number,
+        customer: customer,
+        issued_at: issued_at.iso8601,
+        items: items.map(&:to_h),
+        total_cents: total_cents
+      }.to_json(*args)
+    end
+  end
+end
+
+invoice = Billing::Invoice.new(number: "INV-1001", customer: "ACME")
+invoice.add("Consulting", quantity: 3, unit_cents: 15_000).add("Support", unit_cents: 4_900)
+puts invoice.to_json
@@ -0,0 +1,50 @@
+package example.graph
+
+import scala.collection.immutable.Queue
+
+/** A directed graph stored as adjacency lists. */
+final case class Graph[A](edges: Map[A, Set[A]]):
+
+  def addEdge(from: A, to: A): Graph[A] =
+    Graph(edges.updated(from, edges.getOrElse(from, Set.empty) + to).updatedWith(to) {
+      case None => Some(Set.empty)
+      case some => some
+    })
+
+  def neighbors(node: A): Set[A] = edges.getOrElse(node, Set.empty)
+
+  /** Returns the nodes reachable from start, in breadth-first order. */
+  def bfs(start: A): List[A] =
+    @annotation.tailrec
+    def loop(queue: Queue[A], seen: Set[A], order: List[A]): List[A] =
+      queue.dequeueOption match
+        case None => order.reverse
+        case Some((node, rest)) =>
+          val next = neighbors(node).diff(seen)
+          loop(rest.enqueueAll(next), seen ++ next, node :: order)
+    loop(Queue(start), Set(start), Nil)
+
+  /** Returns a topological order, or None if the graph has a cycle. */
+  def topologicalSort: Option[List[A]] =
+    val indegree = edges.keys.map(n => n -> edges.values.count(_.contains(n))).toMap
+    @annotation.tailrec
+    def loop(ready: List[A], indegree: Map[A, Int], order: List[A]): Option[List[A]] =
+      ready match
+        case Nil => if order.size == edges.size then Some(order.reverse) else None
+        case node :: rest =>
+          val updated = neighbors(node).foldLeft(indegree)((m, n) => m.updated(n, m(n) - 1))
+          val newlyReady = neighbors(node).filter(updated(_) == 0).toList
+          loop(rest ++ newlyReady, updated, node :: order)
+    loop(indegree.collect { case (n, 0) => n }.toList, indegree, Nil)
+
+object Graph:
+  def empty[A]: Graph[A] = Graph(Map.empty)
+
+@main def run(): Unit =
+  val g = Graph.empty[String]
+    .addEdge("fetch", "parse")
+    .addEdge("parse", "chunk")
+    .addEdge("chunk", "embed")
+    .addEdge("parse", "index")
+  println(g.bfs("fetch").mkString(" -> "))
+  println(g.topologicalSort.getOrElse(Nil).mkString(", "))
@@ -0,0 +1,53 @@
+#!/usr/bin/env bash
+# Deploys a release to the configured hosts.
+set -euo pipefail
+
+readonly RELEASE_DIR="/srv/releases"
+readonly KEEP_RELEASES=5
+
+log() {
+  printf '[%s] %s\n' "$(date +%H:%M:%S)" "$*" >&2
+}
+
+die() {
+  log "error: $*"
+  exit 1
+}
+
+# upload copies the build archive to a host and unpacks it.
+upload() {
+  local host="$1" archive="$2" version="$3"
+  scp "$archive" "$host:/tmp/release.tar.gz"
+  ssh "$host" "mkdir -p $RELEASE_DIR/$version && tar -xzf /tmp/release.tar.gz -C $RELEASE_DIR/$version"
+}
+
+# activate points the current symlink at a release and restarts the service.
+activate() {
+  local host="$1" version="$2"
+  ssh "$host" "ln -sfn $RELEASE_DIR/$version $RELEASE_DIR/current && systemctl restart app"
+}
+
+# prune removes all but the newest releases.
+prune() {
+  local host="$1"
+  ssh "$host" "ls -1dt $RELEASE_DIR/*/ | tail -n +$((KEEP_RELEASES + 1)) | xargs -r rm -rf"
+}
+
+main() {
+  [[ $# -ge 2 ]] || die "usage: $0 VERSION HOST..."
+  local version="$1"
+  shift
+
+  local archive="build/app-$version.tar.gz"
+  [[ -f "$archive" ]] || die "missing archive $archive"
+
+  for host in "$@"; do
+    log "deploying $version to $host"
+    upload "$host" "$archive" "$version"
+    activate "$host" "$version"
+    prune "$host"
+  done
+  log "done"
+}
+
+main "$@"
@@ -0,0 +1,57 @@
+-- Schema and reports for a small order system.
+
+CREATE TABLE customers (
+    id          BIGSERIAL PRIMARY KEY,
+    email       TEXT NOT NULL UNIQUE,
+    name        TEXT NOT NULL,
+    created_at  TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE products (
+    id          BIGSERIAL PRIMARY KEY,
+    sku         TEXT NOT NULL UNIQUE,
+    name        TEXT NOT NULL,
+    price_cents INTEGER NOT NULL CHECK (price_cents >= 0)
+);
+
+CREATE TABLE orders (
+    id          BIGSERIAL PRIMARY KEY,
+    customer_id BIGINT NOT NULL REFERENCES customers (id),
+    status      TEXT NOT NULL DEFAULT 'pending'
+                CHECK (status IN ('pending', 'paid', 'shipped', 'cancelled')),
+    placed_at   TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE order_items (
+    order_id    BIGINT NOT NULL REFERENCES orders (id) ON DELETE CASCADE,
+    product_id  BIGINT NOT NULL REFERENCES products (id),
+    quantity    INTEGER NOT NULL CHECK (quantity > 0),
+    PRIMARY KEY (order_id, product_id)
+);
+
+CREATE INDEX orders_customer_placed_idx ON orders (customer_id, placed_at DESC);
+
+-- Revenue per month from paid and shipped orders.
+SELECT date_trunc('month', o.placed_at) AS month,
+       SUM(oi.quantity * p.price_cents) / 100.0 AS revenue
+FROM orders o
+JOIN order_items oi ON oi.order_id = o.id
+JOIN products p ON p.id = oi.product_id
+WHERE o.status IN ('paid', 'shipped')
+GROUP BY 1
+ORDER BY 1;
+
+-- Customers whose last order is more than 90 days old.
+WITH last_orders AS (
+    SELECT customer_id, MAX(placed_at) AS last_placed_at
+    FROM orders
+    GROUP BY customer_id
+)
+SELECT c.email, c.name, lo.last_placed_at
+FROM customers c
+JOIN last_orders lo ON lo.customer_id = c.id
+WHERE lo.last_placed_at < now() - INTERVAL '90 days'
+ORDER BY lo.last_placed_at;
+
+UPDATE orders SET status = 'cancelled'
+WHERE status = 'pending' AND placed_at < now() - INTERVAL '7 days';
@@ -0,0 +1,64 @@
+import Foundation
+
+/// A single expense in a budget.
+struct Expense: Codable, Identifiable {
+    let id: UUID
+    var title: String
+    var amount: Decimal
+    var category: Category
+    var date: Date
+
+    enum Category: String, Codable, CaseIterable {
+        case food, rent, travel, other
+    }
+}
+
+enum BudgetError: Error, LocalizedError {
+    case overLimit(category: Expense.Category, by: Decimal)
+
+    var errorDescription: String? {
+        switch self {
+        case let .overLimit(category, amount):
+            return "Over the \(category.rawValue) limit by \(amount)"
+        }
+    }
+}
+
+final class Budget {
+    private(set) var expenses: [Expense] = []
+    private let limits: [Expense.Category: Decimal]
+
+    init(limits: [Expense.Category: Decimal]) {
+        self.limits = limits
+    }
+
+    func total(for category: Expense.Category) -> Decimal {
+        expenses
+            .filter { $0.category == category }
+            .reduce(0) { $0 + $1.amount }
+    }
+
+    /// Records an expense, throwing if it would exceed its category limit.
+    func record(_ expense: Expense) throws {
+        let newTotal = total(for: expense.category) + expense.amount
+        if let limit = limits[expense.category], newTotal > limit {
+            throw BudgetError.overLimit(category: expense.category, by: newTotal - limit)
+        }
+        expenses.append(expense)
+    }
+
+    func summary() -> String {
+        Expense.Category.allCases
+            .map { "\($0.rawValue): \(total(for: $0))" }
+            .joined(separator: "\n")
+    }
+}
+
+let budget = Budget(limits: [.food: 300, .travel: 500])
+do {
+    try budget.record(Expense(id: UUID(), title: "Groceries", amount: 120, category: .food, date: Date()))
+    try budget.record(Expense(id: UUID(), title: "Flight", amount: 650, category: .travel, date: Date()))
+} catch {
+    print(error.localizedDescription)
+}
+print(budget.summary())
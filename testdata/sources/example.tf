@@ -0,0 +1,62 @@
+terraform {
+  required_version = ">= 1.5"
+
+  required_providers {
+    aws = {
+      source  = "hashicorp/aws"
+      version = "~> 5.0"
+    }
+  }
+}
+
+variable "environment" {
+  type        = string
+  description = "Deployment environment, such as staging or production."
+}
+
+variable "instance_count" {
+  type    = number
+  default = 2
+}
+
+locals {
+  name = "web-${var.environment}"
+  tags = {
+    Environment = var.environment
+    ManagedBy   = "terraform"
+  }
+}
+
+resource "aws_security_group" "web" {
+  name        = local.name
+  description = "Allow HTTPS in and everything out"
+
+  ingress {
+    from_port   = 443
+    to_port     = 443
+    protocol    = "tcp"
+    cidr_blocks = ["0.0.0.0/0"]
+  }
+
+  egress {
+    from_port   = 0
+    to_port     = 0
+    protocol    = "-1"
+    cidr_blocks = ["0.0.0.0/0"]
+  }
+
+  tags = local.tags
+}
+
+resource "aws_instance" "web" {
+  count                  = var.instance_count
+  ami                    = data.aws_ami.ubuntu.id
+  instance_type          = var.environment == "production" ? "m6i.large" : "t3.small"
+  vpc_security_group_ids = [aws_security_group.web.id]
+
+  tags = merge(local.tags, { Name = "${local.name}-${count.index}" })
+}
+
+output "instance_ids" {
+  value = aws_instance.web[*].id
+}
@@ -2,6 +2,8 @@ package chunkx
 
 import (
 	"strings"
+	"unicode"
+	"unicode/utf8"
 )
 
 // TokenCounter defines the interface for counting tokens in text.
@@ -14,10 +16,33 @@ type SimpleTokenCounter struct{}
 
 // CountTokens returns the number of whitespace-separated words in the text.
 func (s *SimpleTokenCounter) CountTokens(text string) (int, error) {
-	if text == "" {
-		return 0, nil
+	return countFields(text), nil
+}
+
+// asciiSpace marks the ASCII bytes that unicode.IsSpace reports as whitespace.
+var asciiSpace = [utf8.RuneSelf]bool{'\t': true, '\n': true, '\v': true, '\f': true, '\r': true, ' ': true}
+
+// countFields returns len(strings.Fields(s)) without allocating the fields.
+func countFields(s string) int {
+	count := 0
+	inField := false
+	for i := 0; i < len(s); {
+		var isSpace bool
+		if b := s[i]; b < utf8.RuneSelf {
+			isSpace = asciiSpace[b]
+			i++
+		} else {
+			r, size := utf8.DecodeRuneInString(s[i:])
+			isSpace = unicode.IsSpace(r)
+			i += size
+		}
+
+		if !isSpace && !inField {
+			count++
+		}
+		inField = !isSpace
 	}
-	return len(strings.Fields(text)), nil
+	return count
 }
 
 // ByteCounter counts bytes instead of tokens.
@@ -1,6 +1,7 @@
 package chunkx
 
 import (
+	"strings"
 	"testing"
 )
 
@@ -89,3 +90,22 @@ func TestLineCounter(t *testing.T) {
 		})
 	}
 }
+
+func TestCountFieldsMatchesStringsFields(t *testing.T) {
+	inputs := []string{
+		"",
+		"   ",
+		"hello",
+		"  leading and trailing  ",
+		"tabs\tand\nnewlines\r\nand\vvertical\ftabs",
+		"unicode\u00a0no-break\u2003em space\u0085next",
+		"世界 你好\u3000ideographic",
+		"invalid \xff utf8",
+	}
+
+	for _, input := range inputs {
+		if got, want := countFields(input), len(strings.Fields(input)); got != want {
+			t.Errorf("countFields(%q) = %d, want %d", input, got, want)
+		}
+	}
+}
@@ -0,0 +1,210 @@
+package chunkx
+
+import (
+	"errors"
+	"fmt"
+	"strconv"
+	"strings"
+	"unicode/utf8"
+)
+
+// Checks that ValidationError reports.
+const (
+	CheckOffsets = "offsets" // Byte offsets outside the input or out of order
+	CheckUTF8    = "utf8"    // Byte offsets inside a UTF-8 sequence
+	CheckLines   = "lines"   // Line numbers that don't match the byte offsets
+	CheckContent = "content" // Content that isn't the bytes the chunk spans
+	CheckSize    = "size"    // Content over the maximum size
+	CheckOverlap = "overlap" // Overlap that neighboring chunks don't agree on
+)
+
+// ValidationError is the error of a chunk that fails one of the checks of
+// Validate or ValidateChunks. It matches ErrInvalidChunk with errors.Is.
+type ValidationError struct {
+	Index  int    // Index of the chunk in the chunks passed to ValidateChunks, or 0
+	Check  string // CheckOffsets, CheckUTF8, CheckLines, CheckContent, CheckSize, or CheckOverlap
+	Reason string // What is wrong with the chunk
+}
+
+func (e *ValidationError) Error() string {
+	return fmt.Sprintf("%v: chunk %d: %s: %s", ErrInvalidChunk, e.Index, e.Check, e.Reason)
+}
+
+func (e *ValidationError) Unwrap() error {
+	return ErrInvalidChunk
+}
+
+// Validate checks that the chunk is one of the chunks opts make of original,
+// so pipelines can assert the integrity of chunks after their own
+// post-processing. Original is the text that was chunked, after transcoding,
+// and opts are the options it was chunked with.
+//
+// The chunk's byte offsets must lie within original on UTF-8 boundaries, its
+// lines must be the lines of those offsets, and its content must be the bytes
+// it spans, after the newline normalization and redaction opts set. Content
+// isn't checked with a CommentMode other than CommentsKeep, as comments and
+// code are then left out of it, nor with WithNotebookCells, as it is then
+// decoded from the notebook. The content outside the chunk's overlaps and
+// license header must fit the maximum size, or the soft limit for chunks that
+// record MetadataOverBudget, or with WithContentDefined its largest chunk in
+// bytes. Chunks of a single node or line too large to split fail this check
+// as well, so callers that accept them can ignore errors of CheckSize.
+func (c Chunk) Validate(original string, opts ...Option) error {
+	cfg := newDefaultConfig()
+	for _, opt := range opts {
+		opt(cfg)
+	}
+	return c.validate(original, cfg)
+}
+
+// ValidateChunks checks every chunk of original like Validate, and checks
+// that consecutive chunks are in order and agree on the bytes they share. The
+// comment and code streams of CommentsSeparate are checked separately.
+func ValidateChunks(chunks []Chunk, original string, opts ...Option) error {
+	cfg := newDefaultConfig()
+	for _, opt := range opts {
+		opt(cfg)
+	}
+
+	prevs := make(map[string]*Chunk, 1) // Previous chunk of each stream
+	for i := range chunks {
+		chunk := &chunks[i]
+		if err := chunk.validate(original, cfg); err != nil {
+			var validationErr *ValidationError
+			if errors.As(err, &validationErr) {
+				validationErr.Index = i
+			}
+			return err
+		}
+
+		stream := chunk.Metadata[MetadataStream]
+		if prev := prevs[stream]; prev != nil {
+			if reason := checkNeighbors(*prev, *chunk, original, cfg); reason != "" {
+				check := CheckOverlap
+				if chunk.StartByte < prev.StartByte {
+					check = CheckOffsets
+				}
+				return &ValidationError{Index: i, Check: check, Reason: reason}
+			}
+		}
+		prevs[stream] = chunk
+	}
+	return nil
+}
+
+// validate checks the chunk against original and cfg.
+func (c Chunk) validate(original string, cfg *config) error {
+	invalid := func(check, format string, args ...any) error {
+		return &ValidationError{Check: check, Reason: fmt.Sprintf(format, args...)}
+	}
+
+	if c.StartByte < 0 || c.StartByte > c.EndByte || c.EndByte > len(original) {
+		return invalid(CheckOffsets, "bytes %d-%d are outside the %d bytes of the input", c.StartByte, c.EndByte, len(original))
+	}
+	for _, offset := range []int{c.StartByte, c.EndByte} {
+		if offset < len(original) && !utf8.RuneStart(original[offset]) {
+			return invalid(CheckUTF8, "byte %d is inside a UTF-8 sequence", offset)
+		}
+	}
+
+	startLine := lineOf(original, 0, 1, c.StartByte)
+	endLine := lineOf(original, c.StartByte, startLine, c.EndByte)
+	if c.StartLine != startLine || c.EndLine != endLine {
+		return invalid(CheckLines, "lines %d-%d, but bytes %d-%d are on lines %d-%d",
+			c.StartLine, c.EndLine, c.StartByte, c.EndByte, startLine, endLine)
+	}
+
+	if cfg.contentCopied() {
+		want := original[c.StartByte:c.EndByte]
+		if cfg.normalizeNewlines {
+			want = normalizeNewlines(want)
+		}
+		if !matchesRedacted(c.Content, want, len(cfg.detectors) > 0) {
+			return invalid(CheckContent, "content differs from bytes %d-%d of the input", c.StartByte, c.EndByte)
+		}
+	}
+
+	// Content-defined chunks are sized in bytes
+	if cfg.contentDefined != nil {
+		if _, _, maxBytes, _ := cfg.contentDefined.sizes(); len(c.Content) > maxBytes {
+			return invalid(CheckSize, "%d bytes are over the maximum of %d", len(c.Content), maxBytes)
+		}
+		return nil
+	}
+
+	// Overlaps also span the white space between chunks, which isn't part of
+	// the size the chunk was split to
+	content := c.UniqueContent()
+	if before, after := c.Overlap(); before > 0 || after > 0 {
+		content = strings.TrimSpace(content)
+	}
+	if header, err := strconv.Atoi(c.Metadata[MetadataLicenseHeader]); err == nil && cfg.license == LicenseExclude {
+		content = content[min(max(header, 0), len(content)):]
+	}
+	size, err := cfg.tokenCounter.CountTokens(content)
+	if err != nil {
+		return fmt.Errorf("%w: %w", ErrNodeSize, err)
+	}
+	limit := cfg.maxSize
+	if c.Metadata[MetadataOverBudget] == "true" {
+		limit = cfg.softMaxSize()
+	}
+	if size > limit {
+		return invalid(CheckSize, "%d tokens are over the maximum of %d", size, limit)
+	}
+	return nil
+}
+
+// matchesRedacted reports whether content is want, allowing bytes of want to
+// be masked when redacted is set.
+func matchesRedacted(content, want string, redacted bool) bool {
+	if !redacted || len(content) != len(want) {
+		return content == want
+	}
+	for i := 0; i < len(content); i++ {
+		if content[i] != want[i] && content[i] != redactMask {
+			return false
+		}
+	}
+	return true
+}
+
+// checkNeighbors returns why chunk can't follow prev among the chunks of
+// original, or "" if it can.
+func checkNeighbors(prev, chunk Chunk, original string, cfg *config) string {
+	if chunk.StartByte < prev.StartByte {
+		return fmt.Sprintf("starts at byte %d, before the previous chunk at byte %d", chunk.StartByte, prev.StartByte)
+	}
+
+	before, _ := chunk.Overlap()
+	_, prevAfter := prev.Overlap()
+	if before != prevAfter {
+		return fmt.Sprintf("shares %d bytes with the previous chunk, which shares %d", before, prevAfter)
+	}
+	if !strings.HasSuffix(prev.Content, chunk.Content[:before]) {
+		return "the bytes shared with the previous chunk differ from its content"
+	}
+
+	// Content is derived from the input with comment modes and notebooks, so
+	// only the bytes of chunks of the input itself can be checked
+	if !cfg.contentCopied() {
+		return ""
+	}
+	shared := ""
+	if prev.EndByte > chunk.StartByte {
+		shared = original[chunk.StartByte:prev.EndByte]
+	}
+	if cfg.normalizeNewlines {
+		shared = normalizeNewlines(shared)
+	}
+	if len(shared) != before {
+		return fmt.Sprintf("shares %d bytes with the previous chunk, but spans %d of its bytes", before, len(shared))
+	}
+	return ""
+}
+
+// contentCopied reports whether the content of chunks is copied from the
+// input, rather than derived from it.
+func (cfg *config) contentCopied() bool {
+	return cfg.commentMode() == CommentsKeep && !cfg.notebooks && !cfg.transcripts && !cfg.speakers
+}
@@ -0,0 +1,103 @@
+package chunkx
+
+import (
+	"errors"
+	"slices"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestValidateChunks(t *testing.T) {
+	tests := []struct {
+		name string
+		code string
+		opts []Option
+	}{
+		{"lines", "alpha beta\ngamma delta\nepsilon zeta", []Option{WithMaxSize(2)}},
+		{"overlap", "alpha beta\ngamma delta\nepsilon zeta\neta theta", []Option{WithMaxSize(2), WithOverlap(20)}},
+		{"normalized newlines", "alpha beta\r\ngamma delta\r\nepsilon\r\n", []Option{WithMaxSize(2), WithOverlap(20), WithNormalizedNewlines()}},
+		{"redaction", "mail alice@example.com\nor bob@example.com", []Option{WithMaxSize(2), WithRedaction()}},
+		{
+			"license header", "# Copyright 2024 Acme\n# Licensed under the MIT License\n\nalpha beta\ngamma",
+			[]Option{WithMaxSize(3), WithLicenseHeaders(LicenseExclude)},
+		},
+		{"comments", "# header\nx = 1  # set x\n\n# trailing\nz = 3\n", []Option{WithComments(CommentsSeparate)}},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			opts := append([]Option{WithLanguage(languages.Generic)}, tt.opts...)
+			chunks, err := NewChunker().Chunk(tt.code, opts...)
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if err := ValidateChunks(chunks, tt.code, opts...); err != nil {
+				t.Errorf("unexpected validation error: %v", err)
+			}
+		})
+	}
+}
+
+func TestValidateChunks_Invalid(t *testing.T) {
+	code := "alpha beta\ngamma delta\nepsilon zeta"
+	opts := []Option{WithLanguage(languages.Generic), WithMaxSize(2)}
+	chunks, err := NewChunker().Chunk(code, opts...)
+	if err != nil || len(chunks) != 3 {
+		t.Fatalf("expected 3 chunks, got %+v and %v", chunks, err)
+	}
+	overlapped, err := NewChunker().Chunk(code, append(opts, WithOverlap(20))...)
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	tests := []struct {
+		name   string
+		chunks []Chunk
+		opts   []Option
+		edit   func(chunks []Chunk)
+		check  string
+		index  int
+	}{
+		{"outside the input", chunks, nil, func(c []Chunk) { c[2].EndByte++ }, CheckOffsets, 2},
+		{"out of order", chunks, nil, func(c []Chunk) { c[0], c[1] = c[1], c[0] }, CheckOffsets, 1},
+		{"wrong lines", chunks, nil, func(c []Chunk) { c[1].StartLine++ }, CheckLines, 1},
+		{"edited content", chunks, nil, func(c []Chunk) { c[0].Content = "alpha" }, CheckContent, 0},
+		{"over the maximum size", chunks, []Option{WithMaxSize(1)}, func([]Chunk) {}, CheckSize, 0},
+		{"missing overlap", overlapped, []Option{WithMaxSize(10)}, func(c []Chunk) { delete(c[1].Metadata, MetadataOverlapBefore) }, CheckOverlap, 1},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			chunks := make([]Chunk, len(tt.chunks))
+			for i, chunk := range tt.chunks {
+				chunks[i] = chunk.Clone()
+			}
+			tt.edit(chunks)
+
+			err := ValidateChunks(chunks, code, slices.Concat(opts, tt.opts)...)
+			var validationErr *ValidationError
+			if !errors.As(err, &validationErr) || validationErr.Check != tt.check || validationErr.Index != tt.index {
+				t.Fatalf("expected chunk %d to fail the %s check, got %v", tt.index, tt.check, err)
+			}
+			if !errors.Is(err, ErrInvalidChunk) {
+				t.Errorf("expected %v to match ErrInvalidChunk", err)
+			}
+		})
+	}
+}
+
+func TestChunk_Validate_UTF8(t *testing.T) {
+	code := "héllo"
+	chunk := Chunk{Content: code[2:], StartLine: 1, EndLine: 1, StartByte: 2, EndByte: len(code)}
+
+	var validationErr *ValidationError
+	if err := chunk.Validate(code); !errors.As(err, &validationErr) || validationErr.Check != CheckUTF8 {
+		t.Errorf("expected the UTF-8 check to fail, got %v", err)
+	}
+
+	chunk.Content, chunk.StartByte = code, 0
+	if err := chunk.Validate(code); err != nil {
+		t.Errorf("unexpected validation error: %v", err)
+	}
+}
@@ -0,0 +1,270 @@
+package chunkx
+
+import (
+	"context"
+	"os"
+	"slices"
+	"time"
+)
+
+// DefaultPollInterval is the default interval at which a Watcher scans for changes.
+const DefaultPollInterval = time.Second
+
+// WatchEventKind identifies what happened to a watched file.
+type WatchEventKind int
+
+const (
+	WatchAdded   WatchEventKind = iota // The file was seen for the first time
+	WatchUpdated                       // The file's chunks changed
+	WatchRemoved                       // The file was deleted or is now ignored
+	WatchError                         // The file could not be read or chunked
+)
+
+// String returns the lowercase name of the event kind.
+func (k WatchEventKind) String() string {
+	switch k {
+	case WatchAdded:
+		return "added"
+	case WatchUpdated:
+		return "updated"
+	case WatchRemoved:
+		return "removed"
+	case WatchError:
+		return "error"
+	default:
+		return "unknown"
+	}
+}
+
+// WatchEvent reports a change to the chunks of a watched file.
+//
+// To keep an index in sync, upsert the chunks whose IDs are in Diff.Added and
+// delete those in Diff.Removed.
+type WatchEvent struct {
+	Kind   WatchEventKind
+	Path   string
+	Chunks []Chunk    // Current chunks of the file; empty for WatchRemoved and WatchError
+	Diff   *ChunkDiff // Changes since the previous event for the file; nil for WatchError unless it drops the chunks of the file
+	Err    error      // Set for WatchError
+}
+
+// Watcher keeps the chunks of a directory up to date as files change.
+//
+// It polls the directory rather than relying on platform notification APIs,
+// so it works everywhere, including network file systems. Changed files are
+// detected by size and modification time and re-chunked incrementally with a
+// Document, so only the edited region of a file is re-parsed. A file that
+// fails to chunk is reported with WatchError once, dropping its chunks, and
+// again only if it fails after it changes.
+type Watcher struct {
+	dir      string
+	interval time.Duration
+	opts     []Option
+	cfg      *config
+	files    map[string]*watchedFile
+}
+
+// watchedFile is the last known state of a watched file.
+type watchedFile struct {
+	size    int64
+	modTime time.Time
+	doc     *Document // Nil if the file failed
+	failed  bool      // Whether the file failed to chunk in this state
+}
+
+// chunks returns the current chunks of the file, if it didn't fail.
+func (f *watchedFile) chunks() []Chunk {
+	if f.doc == nil {
+		return nil
+	}
+	return f.doc.Chunks()
+}
+
+// NewWatcher creates a watcher for dir. The options apply to every file; the
+// language is detected per file unless set explicitly, and WithIgnore,
+// WithPollInterval, and WithEventListener are honored.
+func NewWatcher(dir string, opts ...Option) *Watcher {
+	cfg := newDefaultConfig()
+
+	for _, opt := range opts {
+		opt(cfg)
+	}
+
+	return &Watcher{
+		dir:      dir,
+		interval: cfg.pollInterval,
+		opts:     opts,
+		cfg:      cfg,
+		files:    make(map[string]*watchedFile),
+	}
+}
+
+// WithPollInterval sets how often a Watcher scans for changes.
+// Defaults to DefaultPollInterval.
+func WithPollInterval(d time.Duration) Option {
+	return func(c *config) {
+		if d > 0 {
+			c.pollInterval = d
+		}
+	}
+}
+
+// Run watches the directory until ctx is cancelled. The first scan reports
+// every existing file with WatchAdded; later scans report only changes. The
+// returned channel is closed when ctx is cancelled.
+func (w *Watcher) Run(ctx context.Context) <-chan WatchEvent {
+	events := make(chan WatchEvent)
+
+	go func() {
+		defer close(events)
+
+		ticker := time.NewTicker(w.interval)
+		defer ticker.Stop()
+
+		for {
+			if err := w.scan(ctx, events); err != nil {
+				return
+			}
+
+			select {
+			case <-ctx.Done():
+				return
+			case <-ticker.C:
+			}
+		}
+	}()
+
+	return events
+}
+
+// scan compares the directory against the known files and emits an event for
+// every difference. It returns an error only when ctx is cancelled.
+func (w *Watcher) scan(ctx context.Context, events chan<- WatchEvent) error {
+	send := func(event WatchEvent) error {
+		select {
+		case <-ctx.Done():
+			return ctx.Err()
+		case events <- event:
+			return nil
+		}
+	}
+
+	// Listener failures are reported after the event they failed on
+	emit := func(event WatchEvent) error {
+		if err := send(event); err != nil {
+			return err
+		}
+		if failure, failed := notify(ctx, w.cfg.events, event); failed {
+			return send(failure)
+		}
+		return nil
+	}
+
+	seen := make(map[string]bool, len(w.files))
+	err := walkFiles(w.dir, w.cfg.ignore, func(path string, err error) error {
+		if err != nil {
+			return emit(WatchEvent{Kind: WatchError, Path: path, Err: err})
+		}
+
+		seen[path] = true
+		event, changed := w.update(path)
+		if !changed {
+			return nil
+		}
+		return emit(event)
+	})
+	if err != nil {
+		return err
+	}
+
+	// Report deletions in a stable order
+	var removed []string
+	for path := range w.files {
+		if !seen[path] {
+			removed = append(removed, path)
+		}
+	}
+	slices.Sort(removed)
+
+	for _, path := range removed {
+		prev := w.files[path].chunks()
+		delete(w.files, path)
+		if err := emit(WatchEvent{Kind: WatchRemoved, Path: path, Diff: diffChunks(prev, nil)}); err != nil {
+			return err
+		}
+	}
+
+	return nil
+}
+
+// update re-chunks the file at path if it changed since the last scan.
+func (w *Watcher) update(path string) (WatchEvent, bool) {
+	info, err := os.Stat(path)
+	if err != nil {
+		return WatchEvent{Kind: WatchError, Path: path, Err: err}, true
+	}
+
+	file := w.files[path]
+	if file != nil && file.size == info.Size() && file.modTime.Equal(info.ModTime()) {
+		return WatchEvent{}, false
+	}
+
+	content, err := os.ReadFile(path)
+	if err != nil {
+		return WatchEvent{Kind: WatchError, Path: path, Err: err}, true
+	}
+	code := string(content)
+
+	// Files that failed start over once they change
+	if file == nil || file.failed {
+		doc, err := w.newDocument(path, code)
+		if err != nil {
+			w.files[path] = &watchedFile{size: info.Size(), modTime: info.ModTime(), failed: true}
+			return WatchEvent{Kind: WatchError, Path: path, Err: err}, true
+		}
+		w.files[path] = &watchedFile{size: info.Size(), modTime: info.ModTime(), doc: doc}
+		return WatchEvent{Kind: WatchAdded, Path: path, Chunks: doc.Chunks(), Diff: diffChunks(nil, doc.Chunks())}, true
+	}
+
+	prev := file.doc.Chunks()
+	diff, err := file.doc.Apply(fileEdit(file.doc.Code(), code))
+	if err != nil {
+		// Start over from a fresh document rather than keeping stale state
+		doc, err := w.newDocument(path, code)
+		if err != nil {
+			w.files[path] = &watchedFile{size: info.Size(), modTime: info.ModTime(), failed: true}
+			return WatchEvent{Kind: WatchError, Path: path, Err: err, Diff: diffChunks(prev, nil)}, true
+		}
+		file.doc = doc
+		diff = diffChunks(prev, doc.Chunks())
+	}
+	file.size, file.modTime = info.Size(), info.ModTime()
+
+	if len(diff.Added) == 0 && len(diff.Removed) == 0 {
+		return WatchEvent{}, false
+	}
+	return WatchEvent{Kind: WatchUpdated, Path: path, Chunks: file.doc.Chunks(), Diff: diff}, true
+}
+
+// newDocument creates the document for a newly seen file, chunked as
+// ChunkFile chunks it.
+func (w *Watcher) newDocument(path, code string) (*Document, error) {
+	return newFileDocument(path, code, w.opts...)
+}
+
+// fileEdit returns a single edit turning prev into next, spanning everything
+// between their common prefix and suffix.
+func fileEdit(prev, next string) Edit {
+	start := 0
+	for start < len(prev) && start < len(next) && prev[start] == next[start] {
+		start++
+	}
+
+	prevEnd, nextEnd := len(prev), len(next)
+	for prevEnd > start && nextEnd > start && prev[prevEnd-1] == next[nextEnd-1] {
+		prevEnd--
+		nextEnd--
+	}
+
+	return Edit{StartByte: start, OldEndByte: prevEnd, NewText: next[start:nextEnd]}
+}
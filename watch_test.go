@@ -0,0 +1,161 @@
+package chunkx
+
+import (
+	"context"
+	"os"
+	"path/filepath"
+	"testing"
+	"time"
+)
+
+// nextEvent waits for the next watch event.
+func nextEvent(t *testing.T, events <-chan WatchEvent) WatchEvent {
+	t.Helper()
+
+	select {
+	case event, ok := <-events:
+		if !ok {
+			t.Fatal("events channel closed")
+		}
+		return event
+	case <-time.After(5 * time.Second):
+		t.Fatal("timed out waiting for a watch event")
+	}
+	return WatchEvent{}
+}
+
+func TestWatcher(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{
+		"a.go":                 "package a\n\nfunc A() {}\n",
+		"b.go":                 "package b\n\nfunc B() {}\n",
+		"vendor/lib/vendor.go": "package lib\n",
+	})
+
+	ctx, cancel := context.WithCancel(context.Background())
+	defer cancel()
+
+	watcher := NewWatcher(dir, WithPollInterval(10*time.Millisecond), WithMaxSize(5), WithIgnore("vendor/**"))
+	events := watcher.Run(ctx)
+
+	// The initial scan reports existing files in walk order
+	for _, name := range []string{"a.go", "b.go"} {
+		event := nextEvent(t, events)
+		if event.Kind != WatchAdded || event.Path != filepath.Join(dir, name) {
+			t.Fatalf("expected %s added, got %s %s", name, event.Kind, event.Path)
+		}
+		if len(event.Chunks) == 0 || len(event.Diff.Added) != len(event.Chunks) {
+			t.Errorf("expected every chunk of %s to be added, got %+v", name, event.Diff)
+		}
+	}
+
+	// Appending a function adds chunks and leaves the rest unchanged
+	aPath := filepath.Join(dir, "a.go")
+	if err := os.WriteFile(aPath, []byte("package a\n\nfunc A() {}\n\nfunc C() { println(\"c\") }\n"), 0o644); err != nil {
+		t.Fatal(err)
+	}
+	event := nextEvent(t, events)
+	if event.Kind != WatchUpdated || event.Path != aPath {
+		t.Fatalf("expected a.go updated, got %s %s", event.Kind, event.Path)
+	}
+	if len(event.Diff.Added) == 0 || len(event.Diff.Unchanged) == 0 {
+		t.Errorf("expected added and unchanged chunks, got %+v", event.Diff)
+	}
+
+	bPath := filepath.Join(dir, "b.go")
+	if err := os.Remove(bPath); err != nil {
+		t.Fatal(err)
+	}
+	event = nextEvent(t, events)
+	if event.Kind != WatchRemoved || event.Path != bPath {
+		t.Fatalf("expected b.go removed, got %s %s", event.Kind, event.Path)
+	}
+	if len(event.Diff.Removed) == 0 || len(event.Chunks) != 0 {
+		t.Errorf("expected every chunk of b.go to be removed, got %+v", event.Diff)
+	}
+
+	cancel()
+	for range events {
+	}
+}
+
+func TestWatcher_Errors(t *testing.T) {
+	dir := writeTestFiles(t, map[string]string{"a.txt": "one two\n"})
+	path := filepath.Join(dir, "a.txt")
+	watcher := NewWatcher(dir, WithLimits(Limits{MaxBytes: 10}))
+	ctx := context.Background()
+
+	// scan returns the events of one scan
+	scan := func() []WatchEvent {
+		t.Helper()
+		events := make(chan WatchEvent, 10)
+		if err := watcher.scan(ctx, events); err != nil {
+			t.Fatal(err)
+		}
+		close(events)
+		var got []WatchEvent
+		for event := range events {
+			got = append(got, event)
+		}
+		return got
+	}
+	write := func(content string) {
+		t.Helper()
+		if err := os.WriteFile(path, []byte(content), 0o644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	if events := scan(); len(events) != 1 || events[0].Kind != WatchAdded {
+		t.Fatalf("expected a.txt added, got %+v", events)
+	}
+
+	// A file over the limit drops its chunks once, until it changes again
+	write("one two three four\n")
+	events := scan()
+	if len(events) != 1 || events[0].Kind != WatchError || events[0].Diff == nil || len(events[0].Diff.Removed) != 1 {
+		t.Fatalf("expected an error removing the chunk of a.txt, got %+v", events)
+	}
+	if events := scan(); len(events) != 0 {
+		t.Errorf("expected the error to be reported once, got %+v", events)
+	}
+
+	write("one\n")
+	if events := scan(); len(events) != 1 || events[0].Kind != WatchAdded {
+		t.Fatalf("expected a.txt added again, got %+v", events)
+	}
+
+	// Files that failed are removed like the rest
+	write("one two three four five\n")
+	if events := scan(); len(events) != 1 || events[0].Kind != WatchError {
+		t.Fatalf("expected an error for a.txt, got %+v", events)
+	}
+	if err := os.Remove(path); err != nil {
+		t.Fatal(err)
+	}
+	if events := scan(); len(events) != 1 || events[0].Kind != WatchRemoved {
+		t.Errorf("expected a.txt removed, got %+v", events)
+	}
+}
+
+func TestFileEdit(t *testing.T) {
+	tests := []struct {
+		prev, next string
+		want       Edit
+	}{
+		{"abc", "abc", Edit{StartByte: 3, OldEndByte: 3}},
+		{"abc", "abXc", Edit{StartByte: 2, OldEndByte: 2, NewText: "X"}},
+		{"abc", "ac", Edit{StartByte: 1, OldEndByte: 2}},
+		{"aaa", "aaaa", Edit{StartByte: 3, OldEndByte: 3, NewText: "a"}},
+		{"", "new", Edit{StartByte: 0, OldEndByte: 0, NewText: "new"}},
+	}
+
+	for _, tt := range tests {
+		edit := fileEdit(tt.prev, tt.next)
+		if edit != tt.want {
+			t.Errorf("fileEdit(%q, %q) = %+v, want %+v", tt.prev, tt.next, edit, tt.want)
+		}
+		if got := tt.prev[:edit.StartByte] + edit.NewText + tt.prev[edit.OldEndByte:]; got != tt.next {
+			t.Errorf("applying %+v to %q gave %q, want %q", edit, tt.prev, got, tt.next)
+		}
+	}
+}
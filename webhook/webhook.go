@@ -0,0 +1,106 @@
+// Package webhook posts chunk events to an HTTP endpoint, to push changes
+// to downstream indexes as files are chunked:
+//
+//	hook := &webhook.Listener{URL: "https://indexer.internal/chunks"}
+//	watcher := chunkx.NewWatcher("./src", chunkx.WithEventListener(hook))
+//
+// Every event is sent as one JSON object in a POST request:
+//
+//	{"event": "updated", "path": "src/main.go", "chunks": [...], "removed": ["..."]}
+//
+// Chunks take the shape of the records printed by the chunkx command. Failed
+// requests are retried with exponential backoff; client errors other than
+// 429 Too Many Requests are not retried.
+package webhook
+
+import (
+	"bytes"
+	"context"
+	"encoding/json"
+	"net/http"
+	"time"
+
+	"github.com/gomantics/chunkx"
+	"github.com/gomantics/chunkx/internal/retry"
+)
+
+// Defaults for retrying.
+const (
+	DefaultRetries = 3
+	DefaultBackoff = 500 * time.Millisecond
+)
+
+// Listener posts chunk events as JSON to a URL. It implements
+// chunkx.ChunkEventListener.
+type Listener struct {
+	URL     string       // Endpoint events are posted to
+	Header  http.Header  // Sent with every request, such as an Authorization header
+	Client  *http.Client // Defaults to http.DefaultClient
+	Retries int          // Retries per event; zero uses DefaultRetries, negative disables retries
+}
+
+// Event is the JSON body of a request.
+type Event struct {
+	Event   string   `json:"event"`
+	Path    string   `json:"path"`
+	Chunks  []Chunk  `json:"chunks,omitempty"`
+	Removed []string `json:"removed,omitempty"`
+}
+
+// Chunk is a chunk in an event.
+type Chunk struct {
+	ID        string            `json:"id"`
+	Language  string            `json:"language"`
+	StartLine int               `json:"start_line"`
+	EndLine   int               `json:"end_line"`
+	StartByte int               `json:"start_byte"`
+	EndByte   int               `json:"end_byte"`
+	NodeTypes []string          `json:"node_types"`
+	Metadata  map[string]string `json:"metadata,omitempty"`
+	Content   string            `json:"content"`
+}
+
+// NewEvent creates the request body for a chunk event.
+func NewEvent(event chunkx.ChunkEvent) Event {
+	e := Event{Event: event.Kind.String(), Path: event.Path, Removed: event.Removed}
+	for _, chunk := range event.Chunks {
+		e.Chunks = append(e.Chunks, Chunk{
+			ID:        chunk.ID(),
+			Language:  chunk.Language.String(),
+			StartLine: chunk.StartLine,
+			EndLine:   chunk.EndLine,
+			StartByte: chunk.StartByte,
+			EndByte:   chunk.EndByte,
+			NodeTypes: chunk.NodeTypes,
+			Metadata:  chunk.Metadata,
+			Content:   chunk.Content,
+		})
+	}
+	return e
+}
+
+// HandleChunkEvent implements chunkx.ChunkEventListener.
+func (l *Listener) HandleChunkEvent(ctx context.Context, event chunkx.ChunkEvent) error {
+	body, err := json.Marshal(NewEvent(event))
+	if err != nil {
+		return err
+	}
+
+	retries := l.Retries
+	if retries == 0 {
+		retries = DefaultRetries
+	}
+	return retry.Do(ctx, max(retries, 0), DefaultBackoff, func() error {
+		req, err := http.NewRequestWithContext(ctx, http.MethodPost, l.URL, bytes.NewReader(body))
+		if err != nil {
+			return &retry.PermanentError{Err: err}
+		}
+		for key, values := range l.Header {
+			req.Header[key] = values
+		}
+		req.Header.Set("Content-Type", "application/json")
+
+		_, err = retry.Send(l.Client, req, "webhook")
+		return err
+	})
+}
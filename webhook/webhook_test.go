@@ -0,0 +1,73 @@
+package webhook
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"net/http/httptest"
+	"sync/atomic"
+	"testing"
+
+	"github.com/gomantics/chunkx"
+)
+
+func TestListener_HandleChunkEvent(t *testing.T) {
+	var req *http.Request
+	var body Event
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		req = r
+		if err := json.NewDecoder(r.Body).Decode(&body); err != nil {
+			t.Errorf("invalid request body: %v", err)
+		}
+	}))
+	defer server.Close()
+
+	chunk := chunkx.Chunk{Content: "package a", Language: "go", StartLine: 1, EndLine: 1, EndByte: 9}
+	event := chunkx.ChunkEvent{Kind: chunkx.ChunkUpdated, Path: "a.go", Chunks: []chunkx.Chunk{chunk}, Removed: []string{"old"}}
+
+	l := &Listener{URL: server.URL, Header: http.Header{"Authorization": {"Bearer secret"}}}
+	if err := l.HandleChunkEvent(context.Background(), event); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	if req.Method != http.MethodPost || req.Header.Get("Authorization") != "Bearer secret" || req.Header.Get("Content-Type") != "application/json" {
+		t.Errorf("unexpected request: %s %v", req.Method, req.Header)
+	}
+	if body.Event != "updated" || body.Path != "a.go" || len(body.Removed) != 1 || body.Removed[0] != "old" {
+		t.Errorf("unexpected event: %+v", body)
+	}
+	if len(body.Chunks) != 1 || body.Chunks[0].ID != chunk.ID() || body.Chunks[0].Content != chunk.Content || body.Chunks[0].EndByte != 9 {
+		t.Errorf("unexpected chunks: %+v", body.Chunks)
+	}
+}
+
+func TestListener_Retries(t *testing.T) {
+	tests := []struct {
+		name     string
+		status   int
+		attempts int32
+	}{
+		{"server error", http.StatusBadGateway, 2},
+		{"rate limited", http.StatusTooManyRequests, 2},
+		{"client error", http.StatusBadRequest, 1},
+	}
+
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			var attempts atomic.Int32
+			server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+				attempts.Add(1)
+				w.WriteHeader(tt.status)
+			}))
+			defer server.Close()
+
+			l := &Listener{URL: server.URL, Retries: 1}
+			if err := l.HandleChunkEvent(context.Background(), chunkx.ChunkEvent{Kind: chunkx.ChunkDeleted, Path: "a.go"}); err == nil {
+				t.Fatal("expected an error")
+			}
+			if got := attempts.Load(); got != tt.attempts {
+				t.Errorf("attempts = %d, want %d", got, tt.attempts)
+			}
+		})
+	}
+}
@@ -0,0 +1,779 @@
+package chunkx
+
+import (
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"math/bits"
+)
+
+// Zstandard frame format constants. See RFC 8878.
+const (
+	zstdMagic          = 0xFD2FB528
+	zstdSkippableMagic = 0x184D2A50 // The low 4 bits are free
+	zstdSkippableMask  = 0xFFFFFFF0
+	zstdBlockMax       = 128 << 10
+
+	zstdBlockRaw        = 0
+	zstdBlockRLE        = 1
+	zstdBlockCompressed = 2
+
+	zstdHashLog   = 16
+	zstdMinMatch  = 4
+	zstdMaxOffset = 1 << 27 // Farthest match, well within the predefined offset codes
+	zstdMaxFrame  = 1 << 31 // Largest content of a frame decoded
+)
+
+// errZstd is the error of a frame that can't be decoded.
+var errZstd = errors.New("invalid zstd frame")
+
+// Baselines and extra bits of the literal length and match length codes.
+var (
+	zstdLLBase = [36]uint32{
+		0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+		16, 18, 20, 22, 24, 28, 32, 40, 48, 64, 128, 256, 512, 1024, 2048, 4096,
+		8192, 16384, 32768, 65536,
+	}
+	zstdLLBits = [36]uint8{
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		1, 1, 1, 1, 2, 2, 3, 3, 4, 6, 7, 8, 9, 10, 11, 12,
+		13, 14, 15, 16,
+	}
+	zstdMLBase = [53]uint32{
+		3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
+		19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34,
+		35, 37, 39, 41, 43, 47, 51, 59, 67, 83, 99, 131, 259, 515, 1027, 2051,
+		4099, 8195, 16387, 32771, 65539,
+	}
+	zstdMLBits = [53]uint8{
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		1, 1, 1, 1, 2, 2, 3, 3, 4, 4, 5, 7, 8, 9, 10, 11,
+		12, 13, 14, 15, 16,
+	}
+)
+
+// The predefined FSE tables of literal lengths, match lengths, and offsets,
+// the only ones ZstdWriter encodes sequences with.
+var (
+	zstdLLTable = newFSETable([]int16{
+		4, 3, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 2, 1, 1, 1,
+		2, 2, 2, 2, 2, 2, 2, 2, 2, 3, 2, 1, 1, 1, 1, 1,
+		-1, -1, -1, -1,
+	}, 6)
+	zstdMLTable = newFSETable([]int16{
+		1, 4, 3, 2, 2, 2, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1,
+		1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
+		1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, -1, -1,
+		-1, -1, -1, -1, -1,
+	}, 6)
+	zstdOFTable = newFSETable([]int16{
+		1, 1, 1, 1, 1, 1, 2, 2, 2, 1, 1, 1, 1, 1, 1, 1,
+		1, 1, 1, 1, 1, 1, 1, 1, -1, -1, -1, -1, -1,
+	}, 5)
+)
+
+// fseCell is a state of an FSE decoding table: the symbol it decodes and how
+// to get to the next state.
+type fseCell struct {
+	symbol uint8
+	bits   uint8
+	base   uint16
+}
+
+// fseTable is an FSE table, with the states that encode each symbol.
+type fseTable struct {
+	log   uint8
+	cells []fseCell
+	cover [][]uint16 // For each symbol, the state that moves to each next state
+}
+
+// newFSETable builds the table of a normalized distribution as RFC 8878
+// spreads it.
+func newFSETable(norm []int16, log uint8) *fseTable {
+	size := 1 << log
+	t := &fseTable{log: log, cells: make([]fseCell, size), cover: make([][]uint16, len(norm))}
+
+	// Symbols less likely than 1/size take the last states
+	high := size - 1
+	next := make([]int, len(norm))
+	for s, n := range norm {
+		next[s] = int(n)
+		if n == -1 {
+			t.cells[high].symbol = uint8(s)
+			high--
+			next[s] = 1
+		}
+	}
+
+	pos, step, mask := 0, size>>1+size>>3+3, size-1
+	for s, n := range norm {
+		for range max(int(n), 0) {
+			t.cells[pos].symbol = uint8(s)
+			for pos = (pos + step) & mask; pos > high; pos = (pos + step) & mask {
+			}
+		}
+	}
+
+	for i := range t.cells {
+		cell := &t.cells[i]
+		state := next[cell.symbol]
+		next[cell.symbol]++
+		cell.bits = log - uint8(bits.Len(uint(state))-1)
+		cell.base = uint16(state<<cell.bits - size)
+
+		if t.cover[cell.symbol] == nil {
+			t.cover[cell.symbol] = make([]uint16, size)
+		}
+		for j := int(cell.base); j < int(cell.base)+1<<cell.bits; j++ {
+			t.cover[cell.symbol][j] = uint16(i)
+		}
+	}
+	return t
+}
+
+// zstdCode returns the code of value in a table of baselines.
+func zstdCode(base []uint32, value uint32) uint8 {
+	code := len(base) - 1
+	for base[code] > value {
+		code--
+	}
+	return uint8(code)
+}
+
+// bitWriter writes the bitstream of sequences, which is read backward.
+type bitWriter struct {
+	out []byte
+	acc uint64
+	n   uint
+}
+
+// add writes the low n bits of v.
+func (w *bitWriter) add(v uint64, n uint8) {
+	w.acc |= (v & (1<<n - 1)) << w.n
+	w.n += uint(n)
+	for w.n >= 8 {
+		w.out = append(w.out, byte(w.acc))
+		w.acc >>= 8
+		w.n -= 8
+	}
+}
+
+// close ends the stream with the bit that marks its end.
+func (w *bitWriter) close() []byte {
+	w.add(1, 1)
+	if w.n > 0 {
+		w.out = append(w.out, byte(w.acc))
+	}
+	return w.out
+}
+
+// bitReader reads a bitstream of sequences from its end.
+type bitReader struct {
+	data []byte
+	pos  int // Bits left to read
+}
+
+func newBitReader(data []byte) (*bitReader, error) {
+	if len(data) == 0 || data[len(data)-1] == 0 {
+		return nil, fmt.Errorf("%w: bitstream without end mark", errZstd)
+	}
+	return &bitReader{data: data, pos: (len(data)-1)*8 + bits.Len8(data[len(data)-1]) - 1}, nil
+}
+
+// read reads a value of n bits.
+func (r *bitReader) read(n uint8) (uint64, error) {
+	if n == 0 {
+		return 0, nil
+	}
+	if int(n) > r.pos {
+		return 0, fmt.Errorf("%w: bitstream overflow", errZstd)
+	}
+	r.pos -= int(n)
+	var window uint64
+	for i, at := 0, r.pos>>3; i < 8 && at+i < len(r.data); i++ {
+		window |= uint64(r.data[at+i]) << (8 * i)
+	}
+	return window >> (r.pos & 7) & (1<<n - 1), nil
+}
+
+// zstdSequence is a run of literals followed by a match.
+type zstdSequence struct {
+	litLen, matchLen, offset uint32
+}
+
+// zstdEncoder compresses frames of Zstandard. It finds matches with a hash
+// table and stores literals raw, encoding sequences with the predefined FSE
+// tables, which keeps it small while still compressing the repetition of
+// chunk records.
+type zstdEncoder struct {
+	table [1 << zstdHashLog]int32 // Position plus one of the last 4 bytes hashed to each slot
+	seqs  []zstdSequence
+	lits  []byte
+}
+
+// zstdHash hashes the 4 bytes at the start of b.
+func zstdHash(b []byte) uint32 {
+	return binary.LittleEndian.Uint32(b) * 2654435761 >> (32 - zstdHashLog)
+}
+
+// appendFrame appends src compressed as a single frame to dst.
+func (e *zstdEncoder) appendFrame(dst, src []byte) []byte {
+	clear(e.table[:])
+
+	// A single segment frame with a 4-byte content size and a checksum
+	dst = binary.LittleEndian.AppendUint32(dst, zstdMagic)
+	dst = append(dst, 0xA4)
+	dst = binary.LittleEndian.AppendUint32(dst, uint32(len(src)))
+
+	if len(src) == 0 {
+		dst = appendBlockHeader(dst, true, zstdBlockRaw, 0)
+	}
+	for start := 0; start < len(src); start += zstdBlockMax {
+		end := min(start+zstdBlockMax, len(src))
+		last := end == len(src)
+
+		block := e.compressBlock(src, start, end)
+		if len(block) >= end-start {
+			dst = appendBlockHeader(dst, last, zstdBlockRaw, end-start)
+			dst = append(dst, src[start:end]...)
+			continue
+		}
+		dst = appendBlockHeader(dst, last, zstdBlockCompressed, len(block))
+		dst = append(dst, block...)
+	}
+	return binary.LittleEndian.AppendUint32(dst, zstdChecksum(src))
+}
+
+// appendBlockHeader appends the header of a block of size bytes.
+func appendBlockHeader(dst []byte, last bool, kind, size int) []byte {
+	h := uint32(size)<<3 | uint32(kind)<<1
+	if last {
+		h |= 1
+	}
+	return append(dst, byte(h), byte(h>>8), byte(h>>16))
+}
+
+// compressBlock returns the compressed block of src[start:end], with matches
+// reaching back into the frame before start.
+func (e *zstdEncoder) compressBlock(src []byte, start, end int) []byte {
+	e.seqs, e.lits = e.seqs[:0], e.lits[:0]
+	anchor := start
+	for i := start; i+zstdMinMatch <= end; {
+		h := zstdHash(src[i:])
+		candidate := int(e.table[h]) - 1
+		e.table[h] = int32(i + 1)
+		if candidate < 0 || i-candidate > zstdMaxOffset ||
+			binary.LittleEndian.Uint32(src[candidate:]) != binary.LittleEndian.Uint32(src[i:]) {
+			i++
+			continue
+		}
+
+		n := zstdMinMatch
+		for i+n < end && src[candidate+n] == src[i+n] {
+			n++
+		}
+		e.seqs = append(e.seqs, zstdSequence{litLen: uint32(i - anchor), matchLen: uint32(n), offset: uint32(i - candidate)})
+		e.lits = append(e.lits, src[anchor:i]...)
+		i += n
+		anchor = i
+	}
+	e.lits = append(e.lits, src[anchor:end]...)
+
+	// Raw literals, with the shortest header that holds their size
+	var b []byte
+	switch n := len(e.lits); {
+	case n < 1<<5:
+		b = append(b, byte(n<<3))
+	case n < 1<<12:
+		b = append(b, byte(0x04|n<<4), byte(n>>4))
+	default:
+		b = append(b, byte(0x0C|n<<4), byte(n>>4), byte(n>>12))
+	}
+	b = append(b, e.lits...)
+
+	switch n := len(e.seqs); {
+	case n == 0:
+		return append(b, 0)
+	case n < 128:
+		b = append(b, byte(n))
+	case n < 0x7F00:
+		b = append(b, byte(n>>8+128), byte(n))
+	default:
+		b = append(b, 255, byte(n-0x7F00), byte((n-0x7F00)>>8))
+	}
+	b = append(b, 0) // Predefined tables for all three codes
+	return append(b, e.encodeSequences()...)
+}
+
+// encodeSequences returns the bitstream of the sequences, written in the
+// reverse of the order they are read in.
+func (e *zstdEncoder) encodeSequences() []byte {
+	type coded struct {
+		ll, ml, of                uint8
+		llExtra, mlExtra, ofExtra uint32
+	}
+	codes := make([]coded, len(e.seqs))
+	for i, seq := range e.seqs {
+		// Offsets are never repeated, so their values are shifted past the
+		// three repeat codes
+		value := seq.offset + 3
+		c := coded{
+			ll: zstdCode(zstdLLBase[:], seq.litLen),
+			ml: zstdCode(zstdMLBase[:], seq.matchLen),
+			of: uint8(bits.Len32(value) - 1),
+		}
+		c.llExtra = seq.litLen - zstdLLBase[c.ll]
+		c.mlExtra = seq.matchLen - zstdMLBase[c.ml]
+		c.ofExtra = value - 1<<c.of
+		codes[i] = c
+	}
+
+	var w bitWriter
+	extras := func(c coded) {
+		w.add(uint64(c.llExtra), zstdLLBits[c.ll])
+		w.add(uint64(c.mlExtra), zstdMLBits[c.ml])
+		w.add(uint64(c.ofExtra), c.of)
+	}
+
+	last := codes[len(codes)-1]
+	llState := zstdLLTable.cover[last.ll][0]
+	mlState := zstdMLTable.cover[last.ml][0]
+	ofState := zstdOFTable.cover[last.of][0]
+	extras(last)
+	for i := len(codes) - 2; i >= 0; i-- {
+		c := codes[i]
+		llNext, mlNext, ofNext := llState, mlState, ofState
+		llState = zstdLLTable.cover[c.ll][llNext]
+		mlState = zstdMLTable.cover[c.ml][mlNext]
+		ofState = zstdOFTable.cover[c.of][ofNext]
+
+		cell := zstdOFTable.cells[ofState]
+		w.add(uint64(ofNext-cell.base), cell.bits)
+		cell = zstdMLTable.cells[mlState]
+		w.add(uint64(mlNext-cell.base), cell.bits)
+		cell = zstdLLTable.cells[llState]
+		w.add(uint64(llNext-cell.base), cell.bits)
+		extras(c)
+	}
+	w.add(uint64(mlState), zstdMLTable.log)
+	w.add(uint64(ofState), zstdOFTable.log)
+	w.add(uint64(llState), zstdLLTable.log)
+	return w.close()
+}
+
+// readZstdFrame reads the next frame of r: the content of a Zstandard frame,
+// or the payload and magic number of a skippable frame. It returns io.EOF if
+// r ends before the frame starts.
+//
+// Frames are decoded as far as ZstdWriter writes them: blocks of literals
+// that are raw or run-length encoded, and sequences of predefined or
+// run-length encoded tables. Frames with other literals or tables, or with a
+// dictionary, fail with errZstd, as do frames whose content doesn't match
+// their checksum.
+func readZstdFrame(r io.Reader) ([]byte, uint32, error) {
+	var head [4]byte
+	if _, err := io.ReadFull(r, head[:]); err != nil {
+		if errors.Is(err, io.ErrUnexpectedEOF) {
+			err = fmt.Errorf("%w: truncated magic number", errZstd)
+		}
+		return nil, 0, err
+	}
+
+	magic := binary.LittleEndian.Uint32(head[:])
+	if magic&zstdSkippableMask == zstdSkippableMagic {
+		if _, err := io.ReadFull(r, head[:]); err != nil {
+			return nil, 0, truncatedZstd(err)
+		}
+		payload, err := readZstdBytes(r, uint64(binary.LittleEndian.Uint32(head[:])))
+		return payload, magic, err
+	}
+	if magic != zstdMagic {
+		return nil, 0, fmt.Errorf("%w: unknown magic number %#x", errZstd, magic)
+	}
+
+	content, err := readZstdContent(r)
+	return content, magic, err
+}
+
+// readZstdContent decodes the header and blocks of a Zstandard frame.
+func readZstdContent(r io.Reader) ([]byte, error) {
+	var b [8]byte
+	if _, err := io.ReadFull(r, b[:1]); err != nil {
+		return nil, truncatedZstd(err)
+	}
+	descriptor := b[0]
+	singleSegment := descriptor&0x20 != 0
+	checksum := descriptor&0x04 != 0
+	if descriptor&0x03 != 0 {
+		return nil, fmt.Errorf("%w: dictionaries are not supported", errZstd)
+	}
+
+	skip := 0 // Bytes of the window descriptor
+	if !singleSegment {
+		skip = 1
+	}
+	sizeBytes := [4]int{0, 2, 4, 8}[descriptor>>6]
+	if sizeBytes == 0 && singleSegment {
+		sizeBytes = 1
+	}
+	if _, err := io.ReadFull(r, b[:skip]); err != nil {
+		return nil, truncatedZstd(err)
+	}
+	if _, err := io.ReadFull(r, b[:sizeBytes]); err != nil {
+		return nil, truncatedZstd(err)
+	}
+	var size uint64
+	for i := sizeBytes - 1; i >= 0; i-- {
+		size = size<<8 | uint64(b[i])
+	}
+	if sizeBytes == 2 {
+		size += 256
+	}
+	if size > zstdMaxFrame {
+		return nil, fmt.Errorf("%w: frame of %d bytes", errZstd, size)
+	}
+
+	// The size is only trusted so far before the blocks confirm it
+	d := zstdDecoder{out: make([]byte, 0, min(size, 8*zstdBlockMax)), reps: [3]uint32{1, 4, 8}}
+	for {
+		if _, err := io.ReadFull(r, b[:3]); err != nil {
+			return nil, truncatedZstd(err)
+		}
+		h := uint32(b[0]) | uint32(b[1])<<8 | uint32(b[2])<<16
+		last, kind, blockSize := h&1 != 0, int(h>>1&3), int(h>>3)
+		if blockSize > zstdBlockMax {
+			return nil, fmt.Errorf("%w: block of %d bytes", errZstd, blockSize)
+		}
+
+		switch kind {
+		case zstdBlockRaw:
+			block, err := readZstdBytes(r, uint64(blockSize))
+			if err != nil {
+				return nil, err
+			}
+			d.out = append(d.out, block...)
+		case zstdBlockRLE:
+			if _, err := io.ReadFull(r, b[:1]); err != nil {
+				return nil, truncatedZstd(err)
+			}
+			for range blockSize {
+				d.out = append(d.out, b[0])
+			}
+		case zstdBlockCompressed:
+			block, err := readZstdBytes(r, uint64(blockSize))
+			if err != nil {
+				return nil, err
+			}
+			if err := d.decodeBlock(block); err != nil {
+				return nil, err
+			}
+		default:
+			return nil, fmt.Errorf("%w: reserved block type", errZstd)
+		}
+		if len(d.out) > zstdMaxFrame {
+			return nil, fmt.Errorf("%w: frame over %d bytes", errZstd, zstdMaxFrame)
+		}
+		if last {
+			break
+		}
+	}
+
+	if checksum {
+		if _, err := io.ReadFull(r, b[:4]); err != nil {
+			return nil, truncatedZstd(err)
+		}
+		if binary.LittleEndian.Uint32(b[:4]) != zstdChecksum(d.out) {
+			return nil, fmt.Errorf("%w: checksum mismatch", errZstd)
+		}
+	}
+	return d.out, nil
+}
+
+// Primes of XXH64, as variables so that arithmetic on them wraps.
+var (
+	xxhPrime1 uint64 = 11400714785074694791
+	xxhPrime2 uint64 = 14029467366897019727
+	xxhPrime3 uint64 = 1609587929392839161
+	xxhPrime4 uint64 = 9650029242287828579
+	xxhPrime5 uint64 = 2870177450012600261
+)
+
+// zstdChecksum returns the checksum of the content of a frame: the low 32
+// bits of its XXH64 hash with a seed of 0.
+func zstdChecksum(b []byte) uint32 {
+	return uint32(xxh64(b))
+}
+
+// xxh64 returns the XXH64 hash of b with a seed of 0.
+func xxh64(b []byte) uint64 {
+	n := len(b)
+	var h uint64
+	if n >= 32 {
+		v1, v2, v3, v4 := xxhPrime1+xxhPrime2, xxhPrime2, uint64(0), -xxhPrime1
+		for ; len(b) >= 32; b = b[32:] {
+			v1 = xxhRound(v1, binary.LittleEndian.Uint64(b))
+			v2 = xxhRound(v2, binary.LittleEndian.Uint64(b[8:]))
+			v3 = xxhRound(v3, binary.LittleEndian.Uint64(b[16:]))
+			v4 = xxhRound(v4, binary.LittleEndian.Uint64(b[24:]))
+		}
+		h = bits.RotateLeft64(v1, 1) + bits.RotateLeft64(v2, 7) + bits.RotateLeft64(v3, 12) + bits.RotateLeft64(v4, 18)
+		for _, v := range [4]uint64{v1, v2, v3, v4} {
+			h ^= xxhRound(0, v)
+			h = h*xxhPrime1 + xxhPrime4
+		}
+	} else {
+		h = xxhPrime5
+	}
+	h += uint64(n)
+
+	for ; len(b) >= 8; b = b[8:] {
+		h ^= xxhRound(0, binary.LittleEndian.Uint64(b))
+		h = bits.RotateLeft64(h, 27)*xxhPrime1 + xxhPrime4
+	}
+	if len(b) >= 4 {
+		h ^= uint64(binary.LittleEndian.Uint32(b)) * xxhPrime1
+		h = bits.RotateLeft64(h, 23)*xxhPrime2 + xxhPrime3
+		b = b[4:]
+	}
+	for _, c := range b {
+		h ^= uint64(c) * xxhPrime5
+		h = bits.RotateLeft64(h, 11) * xxhPrime1
+	}
+
+	h ^= h >> 33
+	h *= xxhPrime2
+	h ^= h >> 29
+	h *= xxhPrime3
+	h ^= h >> 32
+	return h
+}
+
+// xxhRound mixes the 8 bytes v into the accumulator of XXH64.
+func xxhRound(acc, v uint64) uint64 {
+	acc += v * xxhPrime2
+	return bits.RotateLeft64(acc, 31) * xxhPrime1
+}
+
+// readZstdBytes reads n bytes of a frame.
+func readZstdBytes(r io.Reader, n uint64) ([]byte, error) {
+	if n > zstdMaxFrame {
+		return nil, fmt.Errorf("%w: %d bytes", errZstd, n)
+	}
+	b := make([]byte, n)
+	if _, err := io.ReadFull(r, b); err != nil {
+		return nil, truncatedZstd(err)
+	}
+	return b, nil
+}
+
+// truncatedZstd reports a frame that ends early as such.
+func truncatedZstd(err error) error {
+	if errors.Is(err, io.EOF) || errors.Is(err, io.ErrUnexpectedEOF) {
+		return fmt.Errorf("%w: %w", errZstd, io.ErrUnexpectedEOF)
+	}
+	return err
+}
+
+// zstdDecoder decodes the blocks of a frame.
+type zstdDecoder struct {
+	out  []byte
+	reps [3]uint32 // Repeated offsets, most recent first
+}
+
+// decodeBlock decodes a compressed block.
+func (d *zstdDecoder) decodeBlock(block []byte) error {
+	lits, rest, err := zstdLiterals(block)
+	if err != nil {
+		return err
+	}
+
+	if len(rest) == 0 {
+		return fmt.Errorf("%w: missing sequences", errZstd)
+	}
+	n := int(rest[0])
+	switch {
+	case n == 0:
+		d.out = append(d.out, lits...)
+		return nil
+	case n < 128:
+		rest = rest[1:]
+	case n < 255:
+		if len(rest) < 2 {
+			return fmt.Errorf("%w: truncated sequences", errZstd)
+		}
+		n, rest = (n-128)<<8+int(rest[1]), rest[2:]
+	default:
+		if len(rest) < 3 {
+			return fmt.Errorf("%w: truncated sequences", errZstd)
+		}
+		n, rest = int(rest[1])+int(rest[2])<<8+0x7F00, rest[3:]
+	}
+
+	if len(rest) == 0 {
+		return fmt.Errorf("%w: truncated sequences", errZstd)
+	}
+	modes := rest[0]
+	rest = rest[1:]
+	tables := [3]*fseTable{zstdLLTable, zstdOFTable, zstdMLTable}
+	for i, shift := range []uint{6, 4, 2} {
+		switch modes >> shift & 3 {
+		case 0:
+		case 1:
+			if len(rest) == 0 {
+				return fmt.Errorf("%w: truncated sequences", errZstd)
+			}
+			tables[i] = &fseTable{cells: []fseCell{{symbol: rest[0]}}}
+			rest = rest[1:]
+		default:
+			return fmt.Errorf("%w: FSE compressed tables are not supported", errZstd)
+		}
+	}
+	return d.execute(lits, rest, n, tables)
+}
+
+// zstdLiterals returns the raw or run-length encoded literals of a block and
+// what follows them.
+func zstdLiterals(block []byte) ([]byte, []byte, error) {
+	if len(block) == 0 {
+		return nil, nil, fmt.Errorf("%w: empty block", errZstd)
+	}
+	kind := block[0] & 3
+	if kind > 1 {
+		return nil, nil, fmt.Errorf("%w: Huffman coded literals are not supported", errZstd)
+	}
+
+	var size, header int
+	switch block[0] >> 2 & 3 {
+	case 0, 2:
+		size, header = int(block[0]>>3), 1
+	case 1:
+		if len(block) < 2 {
+			return nil, nil, fmt.Errorf("%w: truncated literals", errZstd)
+		}
+		size, header = int(block[0]>>4)+int(block[1])<<4, 2
+	case 3:
+		if len(block) < 3 {
+			return nil, nil, fmt.Errorf("%w: truncated literals", errZstd)
+		}
+		size, header = int(block[0]>>4)+int(block[1])<<4+int(block[2])<<12, 3
+	}
+
+	if kind == zstdBlockRLE {
+		if len(block) < header+1 {
+			return nil, nil, fmt.Errorf("%w: truncated literals", errZstd)
+		}
+		lits := make([]byte, size)
+		for i := range lits {
+			lits[i] = block[header]
+		}
+		return lits, block[header+1:], nil
+	}
+	if len(block) < header+size {
+		return nil, nil, fmt.Errorf("%w: truncated literals", errZstd)
+	}
+	return block[header : header+size], block[header+size:], nil
+}
+
+// execute decodes n sequences from their bitstream, with the tables of
+// literal lengths, offsets, and match lengths, and copies their literals and
+// matches to the output.
+func (d *zstdDecoder) execute(lits, stream []byte, n int, tables [3]*fseTable) error {
+	r, err := newBitReader(stream)
+	if err != nil {
+		return err
+	}
+
+	var states [3]uint64
+	for i, t := range tables {
+		if states[i], err = r.read(t.log); err != nil {
+			return err
+		}
+	}
+
+	for seq := range n {
+		ll := tables[0].cells[states[0]].symbol
+		of := tables[1].cells[states[1]].symbol
+		ml := tables[2].cells[states[2]].symbol
+		if int(ll) >= len(zstdLLBase) || int(ml) >= len(zstdMLBase) || of > 31 {
+			return fmt.Errorf("%w: invalid sequence code", errZstd)
+		}
+
+		ofExtra, err := r.read(of)
+		if err != nil {
+			return err
+		}
+		mlExtra, err := r.read(zstdMLBits[ml])
+		if err != nil {
+			return err
+		}
+		llExtra, err := r.read(zstdLLBits[ll])
+		if err != nil {
+			return err
+		}
+		litLen := zstdLLBase[ll] + uint32(llExtra)
+		matchLen := zstdMLBase[ml] + uint32(mlExtra)
+		offset := d.offset(1<<of+uint32(ofExtra), litLen)
+
+		if seq < n-1 {
+			for _, i := range []int{0, 2, 1} {
+				cell := tables[i].cells[states[i]]
+				v, err := r.read(cell.bits)
+				if err != nil {
+					return err
+				}
+				states[i] = uint64(cell.base) + v
+			}
+		}
+
+		if int(litLen) > len(lits) {
+			return fmt.Errorf("%w: literals overflow", errZstd)
+		}
+		d.out = append(d.out, lits[:litLen]...)
+		lits = lits[litLen:]
+		if offset == 0 || int(offset) > len(d.out) {
+			return fmt.Errorf("%w: offset %d out of range", errZstd, offset)
+		}
+		from := len(d.out) - int(offset)
+		for i := range int(matchLen) {
+			d.out = append(d.out, d.out[from+i])
+		}
+		if len(d.out) > zstdMaxFrame {
+			return fmt.Errorf("%w: frame over %d bytes", errZstd, zstdMaxFrame)
+		}
+	}
+	d.out = append(d.out, lits...)
+	return nil
+}
+
+// offset returns the offset of an offset value, resolving and updating the
+// repeated offsets.
+func (d *zstdDecoder) offset(value, litLen uint32) uint32 {
+	if value > 3 {
+		d.reps = [3]uint32{value - 3, d.reps[0], d.reps[1]}
+		return d.reps[0]
+	}
+
+	// Without literals, the repeat codes shift by one
+	index := value - 1
+	if litLen == 0 {
+		index++
+	}
+	var offset uint32
+	switch index {
+	case 0:
+		return d.reps[0]
+	case 1, 2:
+		offset = d.reps[index]
+	default:
+		offset = d.reps[0] - 1
+	}
+	if index == 1 {
+		d.reps[1] = d.reps[0]
+	} else {
+		d.reps[2], d.reps[1] = d.reps[1], d.reps[0]
+	}
+	d.reps[0] = offset
+	return offset
+}
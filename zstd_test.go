@@ -0,0 +1,134 @@
+package chunkx
+
+import (
+	"bytes"
+	"errors"
+	"fmt"
+	"io"
+	"math/rand/v2"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"testing"
+)
+
+func TestZstdFrame_RoundTrip(t *testing.T) {
+	r := rand.New(rand.NewPCG(1, 1))
+	random := make([]byte, 5000)
+	for i := range random {
+		random[i] = byte(r.IntN(256))
+	}
+
+	inputs := map[string][]byte{
+		"empty":  nil,
+		"short":  []byte("abc"),
+		"random": random,
+		"prose":  []byte(randomProse(2, 1500)), // Spans several blocks
+		"runs":   bytes.Repeat([]byte("a"), 200<<10),
+	}
+	for name, src := range inputs {
+		t.Run(name, func(t *testing.T) {
+			var e zstdEncoder
+			frame := e.appendFrame(nil, src)
+			content, magic, err := readZstdFrame(bytes.NewReader(frame))
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if magic != zstdMagic || !bytes.Equal(content, src) {
+				t.Errorf("expected the frame to decode to its %d bytes, got %d", len(src), len(content))
+			}
+			if name == "prose" && len(frame) > len(src)/2 {
+				t.Errorf("expected compression, got %d bytes of %d", len(frame), len(src))
+			}
+		})
+	}
+}
+
+func TestReadZstdFrame(t *testing.T) {
+	var e zstdEncoder
+	frame := e.appendFrame(nil, []byte(strings.Repeat("chunk ", 100)))
+	skippable := []byte{0x5A, 0x2A, 0x4D, 0x18, 3, 0, 0, 0, 'a', 'b', 'c'}
+
+	r := bytes.NewReader(slices.Concat(skippable, frame))
+	if payload, magic, err := readZstdFrame(r); err != nil || magic != 0x184D2A5A || string(payload) != "abc" {
+		t.Errorf("expected the skippable frame, got %q, %#x, %v", payload, magic, err)
+	}
+	if _, _, err := readZstdFrame(r); err != nil {
+		t.Errorf("unexpected error: %v", err)
+	}
+	if _, _, err := readZstdFrame(r); !errors.Is(err, io.EOF) {
+		t.Errorf("expected io.EOF at the end, got %v", err)
+	}
+
+	_, _, err := readZstdFrame(bytes.NewReader(frame[:len(frame)-1]))
+	if !errors.Is(err, errZstd) || !errors.Is(err, io.ErrUnexpectedEOF) {
+		t.Errorf("expected a truncated frame to fail, got %v", err)
+	}
+	_, _, err = readZstdFrame(bytes.NewReader([]byte{1, 2, 3, 4, 5}))
+	if !errors.Is(err, errZstd) {
+		t.Errorf("expected an unknown magic number to fail, got %v", err)
+	}
+}
+
+func TestReadZstdFrame_Reference(t *testing.T) {
+	// Written by the zstd tool at its default level, with a checksum
+	frame, err := os.ReadFile(filepath.Join("testdata", "zstd", "reference.txt.zst"))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	want, err := os.ReadFile(filepath.Join("testdata", "zstd", "reference.txt"))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+
+	content, _, err := readZstdFrame(bytes.NewReader(frame))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	if !bytes.Equal(content, want) {
+		t.Errorf("got %q, want %q", content, want)
+	}
+
+	corrupt := slices.Clone(frame)
+	corrupt[len(corrupt)-1] ^= 1
+	if _, _, err := readZstdFrame(bytes.NewReader(corrupt)); !errors.Is(err, errZstd) {
+		t.Errorf("expected a checksum mismatch to fail, got %v", err)
+	}
+}
+
+func TestZstdFrame_Golden(t *testing.T) {
+	var src strings.Builder
+	for i := range 1400 {
+		fmt.Fprintf(&src, `{"id":"%04d","path":"src/file%d.go","start_line":%d,"content":"func f%d() int { return %d }"}`+"\n",
+			i, i%17, i*3, i, i*i%1000)
+	}
+
+	// The golden frame decompresses with zstd -d, so output matching it stays
+	// readable by the zstd tool
+	want, err := os.ReadFile(filepath.Join("testdata", "zstd", "writer.zst"))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	var e zstdEncoder
+	if frame := e.appendFrame(nil, []byte(src.String())); !bytes.Equal(frame, want) {
+		t.Errorf("frame of %d bytes differs from the golden frame of %d bytes", len(frame), len(want))
+	}
+}
+
+func TestXXH64(t *testing.T) {
+	tests := []struct {
+		input string
+		want  uint64
+	}{
+		{"", 0xef46db3751d8e999},
+		{"a", 0xd24ec4f1a98c6e5b},
+		{"abc", 0x44bc2cf5ad770999},
+		{"Nobody inspects the spammish repetition", 0xfbcea83c8a378bf1}, // Past the 32-byte stripes
+	}
+	for _, tt := range tests {
+		if got := xxh64([]byte(tt.input)); got != tt.want {
+			t.Errorf("xxh64(%q) = %#x, want %#x", tt.input, got, tt.want)
+		}
+	}
+}
@@ -0,0 +1,462 @@
+package chunkx
+
+import (
+	"bufio"
+	"bytes"
+	"encoding/binary"
+	"encoding/hex"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+// RecordFormat is the format of the records of a ZstdWriter.
+type RecordFormat int
+
+const (
+	RecordsJSONL       RecordFormat = iota // One JSON object per line, as the command-line jsonl output
+	RecordsMessagePack                     // MessagePack records, as a RecordEncoder writes them
+	RecordsCBOR                            // CBOR records, as a RecordEncoder writes them
+)
+
+// zstdStreamFrameBytes is the amount of records at which a ZstdWriter
+// compresses a frame. Frames are the unit of random access, so they are kept
+// small enough for a lookup to decompress quickly.
+const zstdStreamFrameBytes = 1 << 20
+
+// Skippable frames of a chunk stream, which zstd tools pass over.
+const (
+	zstdHeaderMagic = zstdSkippableMagic | 0x0C
+	zstdIndexMagic  = zstdSkippableMagic | 0x0D
+
+	zstdStreamVersion = 1
+	zstdFooterSize    = 12 // Offset of the index frame and the footer magic
+	zstdFooterMagic   = "CXIX"
+)
+
+// zstdRecord is a record of RecordsJSONL, with the same fields as the
+// command-line JSON output.
+type zstdRecord struct {
+	ID        string            `json:"id"`
+	Path      string            `json:"path"`
+	Language  string            `json:"language"`
+	StartLine int               `json:"start_line"`
+	EndLine   int               `json:"end_line"`
+	StartByte int               `json:"start_byte"`
+	EndByte   int               `json:"end_byte"`
+	NodeTypes []string          `json:"node_types"`
+	Metadata  map[string]string `json:"metadata,omitempty"`
+	Content   string            `json:"content"`
+}
+
+// ZstdWriter writes chunks as a Zstandard compressed stream of records, for
+// storing large chunk corpora. The output is a valid .zst file that the zstd
+// tool decompresses to the plain records, and ZstdReader reads back.
+//
+// Records are compressed in independent frames of about 1 MiB, followed by a
+// skippable frame with an index of the chunks by Chunk.ID, so OpenZstdIndex
+// can fetch a chunk by decompressing only its frame. The compression is
+// simpler than the zstd tool's, trading ratio for no dependencies: it makes
+// the most of the repetition between records, such as paths and keys, and
+// of repeated content. Every frame carries a checksum of its records, so
+// ZstdReader and OpenZstdIndex detect corruption. The output is written
+// sequentially, so w need not support seeking.
+type ZstdWriter struct {
+	w       io.Writer
+	format  RecordFormat
+	enc     zstdEncoder
+	json    *json.Encoder
+	records *RecordEncoder
+	frame   bytes.Buffer // Records of the frame being filled
+	count   int          // Records of the frame being filled
+	offset  int64        // Bytes written to w
+	first   int64        // Offset of the first frame of records
+	frames  []byte       // Compressed size and records of each frame, as uvarints
+	ids     []byte       // Binary ID, frame, and record of each chunk
+	nFrames int
+	buf     []byte
+	err     error
+}
+
+// NewZstdWriter creates a writer that writes records of the format to w.
+// Close must be called to write the last frame and the index.
+func NewZstdWriter(w io.Writer, format RecordFormat) *ZstdWriter {
+	zw := &ZstdWriter{w: w, format: format}
+	switch format {
+	case RecordsMessagePack:
+		zw.records = NewMessagePackEncoder(&zw.frame)
+	case RecordsCBOR:
+		zw.records = NewCBOREncoder(&zw.frame)
+	default:
+		zw.json = json.NewEncoder(&zw.frame)
+	}
+
+	header := append([]byte("chunkx"), zstdStreamVersion, byte(format))
+	zw.writeSkippable(zstdHeaderMagic, header)
+	zw.first = zw.offset
+	return zw
+}
+
+// Write adds a record for each chunk of the file at path.
+func (zw *ZstdWriter) Write(path string, chunks ...Chunk) error {
+	if zw.err != nil {
+		return zw.err
+	}
+
+	for _, chunk := range chunks {
+		id := chunk.ID()
+		if zw.json != nil {
+			zw.err = zw.json.Encode(zstdRecord{
+				ID:        id,
+				Path:      path,
+				Language:  chunk.Language.String(),
+				StartLine: chunk.StartLine,
+				EndLine:   chunk.EndLine,
+				StartByte: chunk.StartByte,
+				EndByte:   chunk.EndByte,
+				NodeTypes: chunk.NodeTypes,
+				Metadata:  chunk.Metadata,
+				Content:   chunk.Content,
+			})
+		} else {
+			zw.err = zw.records.Encode(path, chunk)
+		}
+		if zw.err != nil {
+			return zw.err
+		}
+
+		raw, _ := hex.DecodeString(id)
+		zw.ids = append(zw.ids, raw...)
+		zw.ids = binary.AppendUvarint(zw.ids, uint64(zw.nFrames))
+		zw.ids = binary.AppendUvarint(zw.ids, uint64(zw.count))
+		zw.count++
+
+		if zw.frame.Len() >= zstdStreamFrameBytes {
+			zw.flush()
+		}
+	}
+	return zw.err
+}
+
+// Close writes the frame of the last records and the index. It does not
+// close the underlying writer.
+func (zw *ZstdWriter) Close() error {
+	if zw.err != nil {
+		return zw.err
+	}
+	zw.flush()
+
+	// The index ends with the footer, so it can be found from the end
+	index := binary.AppendUvarint(nil, uint64(zw.first))
+	index = binary.AppendUvarint(index, uint64(zw.nFrames))
+	index = append(index, zw.frames...)
+	index = append(index, zw.ids...)
+	index = binary.LittleEndian.AppendUint64(index, uint64(zw.offset))
+	index = append(index, zstdFooterMagic...)
+	zw.writeSkippable(zstdIndexMagic, index)
+	if zw.err != nil {
+		return zw.err
+	}
+
+	// Reject writes after the index
+	zw.err = errWriterClosed
+	return nil
+}
+
+// flush compresses the buffered records as a frame.
+func (zw *ZstdWriter) flush() {
+	if zw.count == 0 {
+		return
+	}
+	zw.buf = zw.enc.appendFrame(zw.buf[:0], zw.frame.Bytes())
+	zw.frames = binary.AppendUvarint(zw.frames, uint64(len(zw.buf)))
+	zw.frames = binary.AppendUvarint(zw.frames, uint64(zw.count))
+	zw.nFrames++
+	zw.frame.Reset()
+	zw.count = 0
+	zw.write(zw.buf)
+}
+
+// writeSkippable writes a skippable frame with the payload.
+func (zw *ZstdWriter) writeSkippable(magic uint32, payload []byte) {
+	var head [8]byte
+	binary.LittleEndian.PutUint32(head[:], magic)
+	binary.LittleEndian.PutUint32(head[4:], uint32(len(payload)))
+	zw.write(head[:])
+	zw.write(payload)
+}
+
+func (zw *ZstdWriter) write(b []byte) {
+	if zw.err != nil {
+		return
+	}
+	n, err := zw.w.Write(b)
+	zw.offset += int64(n)
+	zw.err = err
+}
+
+// recordReader reads the records of a frame.
+type recordReader interface {
+	Decode() (string, Chunk, error)
+}
+
+// newRecordReader creates a reader of the records of the format in content.
+func newRecordReader(content []byte, format RecordFormat) recordReader {
+	switch format {
+	case RecordsMessagePack:
+		return NewMessagePackDecoder(bytes.NewReader(content))
+	case RecordsCBOR:
+		return NewCBORDecoder(bytes.NewReader(content))
+	default:
+		return &jsonRecordReader{dec: json.NewDecoder(bytes.NewReader(content))}
+	}
+}
+
+// jsonRecordReader reads records of RecordsJSONL.
+type jsonRecordReader struct {
+	dec *json.Decoder
+}
+
+func (r *jsonRecordReader) Decode() (string, Chunk, error) {
+	var record zstdRecord
+	if err := r.dec.Decode(&record); err != nil {
+		if errors.Is(err, io.EOF) {
+			return "", Chunk{}, err
+		}
+		return "", Chunk{}, fmt.Errorf("%w: %w", ErrInvalidRecord, err)
+	}
+	return record.Path, Chunk{
+		Content:   record.Content,
+		StartLine: record.StartLine,
+		EndLine:   record.EndLine,
+		StartByte: record.StartByte,
+		EndByte:   record.EndByte,
+		NodeTypes: record.NodeTypes,
+		Language:  languages.LanguageName(record.Language),
+		Metadata:  record.Metadata,
+	}, nil
+}
+
+// ZstdReader reads the chunks of a stream written by a ZstdWriter, one frame
+// at a time.
+type ZstdReader struct {
+	r       *bufio.Reader
+	format  RecordFormat
+	records recordReader // Records of the current frame
+}
+
+// NewZstdReader creates a reader of the chunk stream in r.
+func NewZstdReader(r io.Reader) *ZstdReader {
+	return &ZstdReader{r: bufio.NewReader(r)}
+}
+
+// Decode reads the next chunk and returns the path of its file. It returns
+// io.EOF at the end of the stream. As with RecordDecoder, Chunk.ID is
+// recomputed from the content rather than trusted.
+func (zr *ZstdReader) Decode() (string, Chunk, error) {
+	for {
+		if zr.records != nil {
+			path, chunk, err := zr.records.Decode()
+			if !errors.Is(err, io.EOF) {
+				return path, chunk, err
+			}
+			zr.records = nil
+		}
+
+		content, magic, err := readZstdFrame(zr.r)
+		if err != nil {
+			if errors.Is(err, io.EOF) {
+				return "", Chunk{}, err
+			}
+			return "", Chunk{}, invalidRecord(err)
+		}
+		switch magic {
+		case zstdMagic:
+			zr.records = newRecordReader(content, zr.format)
+		case zstdHeaderMagic:
+			if zr.format, err = zstdHeaderFormat(content); err != nil {
+				return "", Chunk{}, err
+			}
+		}
+	}
+}
+
+// zstdHeaderFormat returns the record format of the header frame of a stream.
+func zstdHeaderFormat(header []byte) (RecordFormat, error) {
+	if len(header) != 8 || string(header[:6]) != "chunkx" {
+		return 0, fmt.Errorf("%w: invalid stream header", ErrInvalidRecord)
+	}
+	if header[6] != zstdStreamVersion {
+		return 0, fmt.Errorf("%w: unsupported stream version %d", ErrInvalidRecord, header[6])
+	}
+	if format := RecordFormat(header[7]); format <= RecordsCBOR {
+		return format, nil
+	}
+	return 0, fmt.Errorf("%w: unknown record format %d", ErrInvalidRecord, header[7])
+}
+
+// zstdLocation is where a chunk is stored in a stream.
+type zstdLocation struct {
+	frame, record int
+}
+
+// ZstdIndex looks up the chunks of a stream written by a ZstdWriter by their
+// Chunk.ID, decompressing only the frame that holds each chunk. The index of
+// every chunk is held in memory, at about 20 bytes a chunk.
+type ZstdIndex struct {
+	r       io.ReaderAt
+	format  RecordFormat
+	offsets []int64 // Offset of each frame, and the end of the last one
+	chunks  map[[16]byte]zstdLocation
+
+	// The last frame decompressed, as lookups tend to cluster
+	cached  int
+	content []byte
+}
+
+// OpenZstdIndex reads the index of the chunk stream of size bytes in r.
+func OpenZstdIndex(r io.ReaderAt, size int64) (*ZstdIndex, error) {
+	if size < zstdFooterSize {
+		return nil, fmt.Errorf("%w: stream too short for an index", ErrInvalidRecord)
+	}
+	var footer [zstdFooterSize]byte
+	if _, err := r.ReadAt(footer[:], size-zstdFooterSize); err != nil {
+		return nil, invalidRecord(err)
+	}
+	if string(footer[8:]) != zstdFooterMagic {
+		return nil, fmt.Errorf("%w: stream has no index", ErrInvalidRecord)
+	}
+	at := binary.LittleEndian.Uint64(footer[:])
+	if at >= uint64(size) {
+		return nil, fmt.Errorf("%w: index offset %d out of range", ErrInvalidRecord, at)
+	}
+
+	index, magic, err := readZstdFrame(io.NewSectionReader(r, int64(at), size-int64(at)))
+	if err != nil {
+		return nil, invalidRecord(err)
+	}
+	if magic != zstdIndexMagic || len(index) < zstdFooterSize {
+		return nil, fmt.Errorf("%w: no index frame at offset %d", ErrInvalidRecord, at)
+	}
+
+	header := make([]byte, 8+8)
+	if _, err := r.ReadAt(header, 0); err != nil {
+		return nil, invalidRecord(err)
+	}
+	if binary.LittleEndian.Uint32(header) != zstdHeaderMagic {
+		return nil, fmt.Errorf("%w: invalid stream header", ErrInvalidRecord)
+	}
+	format, err := zstdHeaderFormat(header[8:])
+	if err != nil {
+		return nil, err
+	}
+
+	zi := &ZstdIndex{r: r, format: format, chunks: make(map[[16]byte]zstdLocation), cached: -1}
+	if err := zi.parse(index[:len(index)-zstdFooterSize], int64(at)); err != nil {
+		return nil, err
+	}
+	return zi, nil
+}
+
+// parse reads the frames and chunks of the index, whose frames must end by
+// the index frame at end.
+func (zi *ZstdIndex) parse(index []byte, end int64) error {
+	invalid := fmt.Errorf("%w: invalid index", ErrInvalidRecord)
+	uvarint := func() (uint64, bool) {
+		n, size := binary.Uvarint(index)
+		if size <= 0 {
+			return 0, false
+		}
+		index = index[size:]
+		return n, true
+	}
+
+	first, ok := uvarint()
+	frames, ok2 := uvarint()
+	if !ok || !ok2 || frames > uint64(len(index)) {
+		return invalid
+	}
+	offset := int64(first)
+	zi.offsets = append(make([]int64, 0, frames+1), offset)
+	records := make([]uint64, frames)
+	for i := range records {
+		size, ok := uvarint()
+		if !ok || size > uint64(end-offset) {
+			return invalid
+		}
+		if records[i], ok = uvarint(); !ok {
+			return invalid
+		}
+		offset += int64(size)
+		zi.offsets = append(zi.offsets, offset)
+	}
+
+	for len(index) > 0 {
+		if len(index) < 16 {
+			return invalid
+		}
+		id := [16]byte(index[:16])
+		index = index[16:]
+		frame, ok := uvarint()
+		record, ok2 := uvarint()
+		if !ok || !ok2 || frame >= frames || record >= records[frame] {
+			return invalid
+		}
+		// Repeated content keeps the first chunk with its ID
+		if _, ok := zi.chunks[id]; !ok {
+			zi.chunks[id] = zstdLocation{frame: int(frame), record: int(record)}
+		}
+	}
+	return nil
+}
+
+// Len returns the number of distinct chunk IDs in the index.
+func (zi *ZstdIndex) Len() int {
+	return len(zi.chunks)
+}
+
+// Get returns the chunk with the ID and the path of its file. If several
+// chunks share the ID, the first one written is returned. It returns
+// ErrChunkNotFound if the stream holds no chunk with the ID.
+func (zi *ZstdIndex) Get(id string) (string, Chunk, error) {
+	var key [16]byte
+	if len(id) != hex.EncodedLen(len(key)) {
+		return "", Chunk{}, fmt.Errorf("%w: %q", ErrChunkNotFound, id)
+	}
+	if _, err := hex.Decode(key[:], []byte(id)); err != nil {
+		return "", Chunk{}, fmt.Errorf("%w: %q", ErrChunkNotFound, id)
+	}
+	loc, ok := zi.chunks[key]
+	if !ok {
+		return "", Chunk{}, fmt.Errorf("%w: %q", ErrChunkNotFound, id)
+	}
+
+	if zi.cached != loc.frame {
+		start, end := zi.offsets[loc.frame], zi.offsets[loc.frame+1]
+		content, magic, err := readZstdFrame(io.NewSectionReader(zi.r, start, end-start))
+		if err != nil {
+			return "", Chunk{}, invalidRecord(err)
+		}
+		if magic != zstdMagic {
+			return "", Chunk{}, fmt.Errorf("%w: no frame at offset %d", ErrInvalidRecord, start)
+		}
+		zi.cached, zi.content = loc.frame, content
+	}
+
+	records := newRecordReader(zi.content, zi.format)
+	for i := 0; ; i++ {
+		path, chunk, err := records.Decode()
+		if errors.Is(err, io.EOF) {
+			return "", Chunk{}, fmt.Errorf("%w: record %d missing from its frame", ErrInvalidRecord, loc.record)
+		}
+		if err != nil {
+			return "", Chunk{}, err
+		}
+		if i == loc.record {
+			return path, chunk, nil
+		}
+	}
+}
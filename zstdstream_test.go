@@ -0,0 +1,91 @@
+package chunkx
+
+import (
+	"bytes"
+	"errors"
+	"fmt"
+	"io"
+	"maps"
+	"reflect"
+	"slices"
+	"testing"
+
+	"github.com/gomantics/chunkx/languages"
+)
+
+func TestZstdWriter_RoundTrip(t *testing.T) {
+	code := randomProse(3, 4000)
+	chunks, err := NewChunker().Chunk(code, WithLanguage(languages.Markdown), WithMaxSize(200))
+	if err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	chunks[0].Metadata = map[string]string{MetadataArchive: "docs.zip"}
+
+	for _, format := range []RecordFormat{RecordsJSONL, RecordsMessagePack, RecordsCBOR} {
+		t.Run(fmt.Sprint(format), func(t *testing.T) {
+			var buf bytes.Buffer
+			zw := NewZstdWriter(&buf, format)
+			for i := 0; i < len(chunks); i += 10 {
+				if err := zw.Write(fmt.Sprintf("doc%d.md", i/10), chunks[i:min(i+10, len(chunks))]...); err != nil {
+					t.Fatalf("unexpected error: %v", err)
+				}
+			}
+			if err := zw.Close(); err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			if err := zw.Write("late.md", chunks[0]); err == nil {
+				t.Error("expected an error writing after Close")
+			}
+			if buf.Len() > len(code) {
+				t.Errorf("expected the stream to be compressed, got %d bytes of %d", buf.Len(), len(code))
+			}
+
+			zr := NewZstdReader(bytes.NewReader(buf.Bytes()))
+			for i, want := range chunks {
+				path, got, err := zr.Decode()
+				if err != nil {
+					t.Fatalf("chunk %d: unexpected error: %v", i, err)
+				}
+				// Empty node types and metadata needn't keep their nil-ness
+				same := slices.Equal(got.NodeTypes, want.NodeTypes) && maps.Equal(got.Metadata, want.Metadata)
+				got.NodeTypes, got.Metadata = want.NodeTypes, want.Metadata
+				if path != fmt.Sprintf("doc%d.md", i/10) || !same || !reflect.DeepEqual(got, want) {
+					t.Fatalf("chunk %d: got %q %+v, want %+v", i, path, got, want)
+				}
+			}
+			if _, _, err := zr.Decode(); !errors.Is(err, io.EOF) {
+				t.Errorf("expected io.EOF after the last chunk, got %v", err)
+			}
+
+			index, err := OpenZstdIndex(bytes.NewReader(buf.Bytes()), int64(buf.Len()))
+			if err != nil {
+				t.Fatalf("unexpected error: %v", err)
+			}
+			for _, i := range []int{len(chunks) - 1, 0, len(chunks) / 2} {
+				path, got, err := index.Get(chunks[i].ID())
+				if err != nil {
+					t.Fatalf("chunk %d: unexpected error: %v", i, err)
+				}
+				if path != fmt.Sprintf("doc%d.md", i/10) || got.Content != chunks[i].Content {
+					t.Errorf("chunk %d: got %q %q", i, path, got.Content)
+				}
+			}
+			if _, _, err := index.Get(Chunk{Content: "missing"}.ID()); !errors.Is(err, ErrChunkNotFound) {
+				t.Errorf("expected ErrChunkNotFound, got %v", err)
+			}
+		})
+	}
+}
+
+func TestOpenZstdIndex_Invalid(t *testing.T) {
+	var buf bytes.Buffer
+	zw := NewZstdWriter(&buf, RecordsJSONL)
+	if err := zw.Write("a.txt", Chunk{Content: "a"}); err != nil {
+		t.Fatalf("unexpected error: %v", err)
+	}
+	stream := buf.Bytes() // Without the index of Close
+
+	if _, err := OpenZstdIndex(bytes.NewReader(stream), int64(len(stream))); !errors.Is(err, ErrInvalidRecord) {
+		t.Errorf("expected ErrInvalidRecord without an index, got %v", err)
+	}
+}